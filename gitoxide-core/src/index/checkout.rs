@@ -184,27 +184,80 @@ where
     fn try_find<'a>(&self, id: &gix::oid, buf: &'a mut Vec<u8>) -> Result<Option<gix::objs::Data<'a>>, Error> {
         if self.empty_files {
             // We always want to query the ODB here…
-            let Some(kind) = self.db.try_find(id, buf)?.map(|d| d.kind) else {
+            let Some((kind, object_hash)) = self.db.try_find(id, buf)?.map(|d| (d.kind, d.object_hash)) else {
                 return Ok(None);
             };
             buf.clear();
             // …but write nothing
-            Ok(Some(gix::objs::Data { kind, data: buf }))
+            Ok(Some(gix::objs::Data {
+                kind,
+                object_hash,
+                data: buf,
+            }))
         } else {
             self.db.try_find(id, buf)
         }
     }
 }
 
+impl<Find> gix::objs::FindHeader for EmptyOrDb<Find>
+where
+    Find: gix::objs::FindHeader,
+{
+    fn try_header(&self, id: &gix::oid) -> Result<Option<gix::objs::Header>, Error> {
+        let header = self.db.try_header(id)?;
+        Ok(if self.empty_files {
+            header.map(|h| gix::objs::Header { kind: h.kind, size: 0 })
+        } else {
+            header
+        })
+    }
+}
+
+impl<Find> gix::objs::FindStream for EmptyOrDb<Find>
+where
+    Find: gix::objs::FindStream + gix::objs::FindHeader,
+{
+    fn try_find_stream(&self, id: &gix::oid) -> Result<Option<gix::objs::find::Stream>, Error> {
+        if self.empty_files {
+            Ok(self
+                .db
+                .try_header(id)?
+                .map(|h| gix::objs::find::Stream::from_data(h.kind, Vec::new())))
+        } else {
+            self.db.try_find_stream(id)
+        }
+    }
+}
+
 #[derive(Clone)]
 struct Empty;
 
 impl gix::objs::Find for Empty {
-    fn try_find<'a>(&self, _id: &gix::oid, buffer: &'a mut Vec<u8>) -> Result<Option<gix::objs::Data<'a>>, Error> {
+    fn try_find<'a>(&self, id: &gix::oid, buffer: &'a mut Vec<u8>) -> Result<Option<gix::objs::Data<'a>>, Error> {
         buffer.clear();
         Ok(Some(gix::objs::Data {
             kind: gix::object::Kind::Blob,
+            object_hash: id.kind(),
             data: buffer,
         }))
     }
 }
+
+impl gix::objs::FindHeader for Empty {
+    fn try_header(&self, _id: &gix::oid) -> Result<Option<gix::objs::Header>, Error> {
+        Ok(Some(gix::objs::Header {
+            kind: gix::object::Kind::Blob,
+            size: 0,
+        }))
+    }
+}
+
+impl gix::objs::FindStream for Empty {
+    fn try_find_stream(&self, _id: &gix::oid) -> Result<Option<gix::objs::find::Stream>, Error> {
+        Ok(Some(gix::objs::find::Stream::from_data(
+            gix::object::Kind::Blob,
+            Vec::new(),
+        )))
+    }
+}
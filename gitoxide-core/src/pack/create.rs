@@ -16,11 +16,12 @@ pub enum ObjectExpansion {
     None,
     TreeTraversal,
     TreeDiff,
+    AllReachable,
 }
 
 impl ObjectExpansion {
     pub fn variants() -> &'static [&'static str] {
-        &["none", "tree-traversal", "tree-diff"]
+        &["none", "tree-traversal", "tree-diff", "all-reachable"]
     }
 }
 
@@ -34,6 +35,7 @@ impl FromStr for ObjectExpansion {
             "none" => None,
             "tree-traversal" => TreeTraversal,
             "tree-diff" => TreeDiff,
+            "all-reachable" => AllReachable,
             _ => return Err("invalid value".into()),
         })
     }
@@ -46,6 +48,7 @@ impl From<ObjectExpansion> for pack::data::output::count::objects::ObjectExpansi
             ObjectExpansion::None => AsIs,
             ObjectExpansion::TreeTraversal => TreeContents,
             ObjectExpansion::TreeDiff => TreeAdditionsComparedToAncestor,
+            ObjectExpansion::AllReachable => AllReachable,
         }
     }
 }
@@ -80,6 +83,11 @@ pub struct Context<W> {
     /// This is a total, shared among all threads if `thread_limit` permits.
     /// Only used when known to be effective, namely when `expansion == ObjectExpansion::TreeDiff`.
     pub object_cache_size_in_bytes: usize,
+    /// If set, search this amount of objects for a delta base for each object, to produce smaller packs at the cost of time.
+    /// Otherwise, only deltas of existing packs are copied, and `thin` packs may be created.
+    pub delta_window: Option<usize>,
+    /// The maximum length of delta chains if `delta_window` is set.
+    pub delta_depth: usize,
     /// The output stream for use of additional information
     pub out: W,
 }
@@ -98,6 +106,8 @@ pub fn create<W, P>(
         statistics,
         pack_cache_size_in_bytes,
         object_cache_size_in_bytes,
+        delta_window,
+        delta_depth,
         mut out,
     }: Context<W>,
 ) -> anyhow::Result<()>
@@ -108,7 +118,19 @@ where
 {
     type ObjectIdIter = dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync>>> + Send;
 
-    let repo = gix::discover(repository_path)?.into_sync();
+    let repo = gix::discover(repository_path)?;
+    let big_file_threshold = repo
+        .config_snapshot()
+        .integer("core.bigFileThreshold")
+        .map(|value| gix::config::tree::Core::BIG_FILE_THRESHOLD.try_into_u64(Ok(value)))
+        .transpose()?
+        .unwrap_or_else(|| pack::data::output::entry::iter_from_counts::Options::default().big_file_threshold);
+    let bitmaps = if matches!(expansion, ObjectExpansion::AllReachable) {
+        pack::bitmap::Bundle::at(repo.objects.store_ref().path().join("pack"), repo.object_hash())?
+    } else {
+        None
+    };
+    let repo = repo.into_sync();
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
     let make_cancellation_err = || anyhow!("Cancelled by user");
@@ -129,11 +151,16 @@ where
                 })
                 .collect::<Result<Vec<_>, _>>()?;
             let handle = repo.objects.into_shared_arc().to_cache_arc();
-            let iter = Box::new(
-                traverse::commit::Simple::new(tips, handle.clone())
-                    .map(|res| res.map_err(|err| Box::new(err) as Box<_>).map(|c| c.id))
-                    .inspect(move |_| progress.inc()),
-            );
+            let iter: Box<ObjectIdIter> = if matches!(expansion, ObjectExpansion::AllReachable) {
+                // All objects are found from the tips, which don't need a traversal of their own.
+                Box::new(tips.into_iter().map(Ok).inspect(move |_| progress.inc()))
+            } else {
+                Box::new(
+                    traverse::commit::Simple::new(tips, handle.clone())
+                        .map(|res| res.map_err(|err| Box::new(err) as Box<_>).map(|c| c.id))
+                        .inspect(move |_| progress.inc()),
+                )
+            };
             (handle, iter)
         }
         Some(input) => {
@@ -193,7 +220,7 @@ where
         let input_object_expansion = expansion.into();
         handle.prevent_pack_unload();
         handle.ignore_replacements = true;
-        let (mut counts, count_stats) = if may_use_multiple_threads {
+        let (mut counts, count_stats) = if may_use_multiple_threads || bitmaps.is_some() {
             pack::data::output::count::objects(
                 handle.clone(),
                 input,
@@ -203,6 +230,8 @@ where
                     thread_limit,
                     chunk_size,
                     input_object_expansion,
+                    bitmaps: bitmaps.as_ref(),
+                    haves: &[],
                 },
             )?
         } else {
@@ -229,10 +258,18 @@ where
             Box::new(progress),
             pack::data::output::entry::iter_from_counts::Options {
                 thread_limit,
-                mode: pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                mode: if delta_window.is_some() {
+                    pack::data::output::entry::iter_from_counts::Mode::DeltaCompression
+                } else {
+                    pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects
+                },
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
+                window: delta_window.unwrap_or_default(),
+                depth: delta_depth,
+                islands: None,
+                big_file_threshold,
             },
         ))
     };
@@ -319,6 +356,7 @@ fn human_output(
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             },
     }: Statistics,
     mut out: impl std::io::Write,
@@ -339,10 +377,11 @@ fn human_output(
     #[rustfmt::skip]
     writeln!(
         out,
-        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
+        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
         "decoded and recompressed", decoded_and_recompressed_objects,
         "pack-to-pack copies", objects_copied_from_pack,
         "ref-delta-objects", ref_delta_objects,
+        "delta-compressed objects", delta_compressed_objects,
         "missing objects", missing_objects,
         width = width
     )?;
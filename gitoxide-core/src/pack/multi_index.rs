@@ -33,7 +33,10 @@ pub fn create(
         &mut out,
         &mut progress,
         should_interrupt,
-        gix::odb::pack::multi_index::write::Options { object_hash },
+        gix::odb::pack::multi_index::write::Options {
+            object_hash,
+            reverse_index: false,
+        },
     )?;
     out.into_inner()?.commit()?;
     Ok(())
@@ -0,0 +1,49 @@
+use gix::bstr::{BStr, ByteSlice};
+use std::ffi::OsStr;
+
+pub fn blame_file(
+    mut repo: gix::Repository,
+    file: &OsStr,
+    rev: Option<&BStr>,
+    out: impl std::io::Write,
+    err: Option<&mut dyn std::io::Write>,
+) -> anyhow::Result<()> {
+    repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
+
+    let suspect = repo
+        .rev_parse_single(rev.unwrap_or("HEAD".into()))?
+        .object()?
+        .peel_to_kind(gix::object::Kind::Commit)?;
+    let file = gix::path::to_unix_separators_on_windows(gix::path::os_str_into_bstr(file)?);
+    let outcome = repo.blame_file(file.as_ref(), suspect.id, None)?;
+    let statistics = outcome.statistics;
+    write_blame_entries(out, outcome)?;
+
+    if let Some(err) = err {
+        writeln!(err, "{statistics:#?}")?;
+    }
+    Ok(())
+}
+
+fn write_blame_entries(mut out: impl std::io::Write, outcome: gix::blame::Outcome) -> Result<(), std::io::Error> {
+    for (entry, lines_in_hunk) in outcome.entries_with_lines() {
+        for ((actual_lno, source_lno), line) in entry
+            .range_in_blamed_file()
+            .zip(entry.range_in_source_file())
+            .zip(lines_in_hunk)
+        {
+            write!(
+                out,
+                "{short_id} {source_file_name} {line_no} {src_line_no} {line}",
+                line_no = actual_lno + 1,
+                src_line_no = source_lno + 1,
+                short_id = entry.commit_id.to_hex_with_len(8),
+                source_file_name = entry.source_file_name,
+            )?;
+            if !line.ends_with_str("\n") {
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
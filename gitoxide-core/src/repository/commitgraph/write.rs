@@ -0,0 +1,57 @@
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If set, add a layer to a split commit-graph, merging layers as configured.
+    pub split: Option<gix::commitgraph::write::Split>,
+    /// Write changed-path Bloom filters for all commits.
+    pub changed_paths: bool,
+}
+
+pub(crate) mod function {
+    use std::sync::atomic::AtomicBool;
+
+    use anyhow::bail;
+    use gix::maintenance::CommitGraph;
+
+    use crate::{repository::commitgraph::write::Options, OutputFormat};
+
+    pub fn write(
+        repo: gix::Repository,
+        mut out: impl std::io::Write,
+        mut progress: impl gix::NestedProgress + 'static,
+        should_interrupt: &AtomicBool,
+        Options {
+            format,
+            split,
+            changed_paths,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        if format != OutputFormat::Human {
+            bail!("JSON output isn't implemented yet");
+        }
+        let existing_settings = repo.commit_graph().ok().and_then(|graph| graph.bloom_filter_settings());
+        let outcome = repo.write_commit_graph(
+            &CommitGraph {
+                split,
+                generation_data: true,
+                changed_paths: changed_paths.then(|| existing_settings.unwrap_or_default()),
+            },
+            &mut progress,
+            should_interrupt,
+        )?;
+        match outcome.path {
+            Some(path) => writeln!(
+                out,
+                "wrote {} commits to '{}', the commit-graph now consists of {} file(s)",
+                outcome.num_commits,
+                path.display(),
+                outcome.num_files
+            )?,
+            None => writeln!(out, "the commit-graph is up to date")?,
+        }
+        Ok(())
+    }
+}
@@ -9,6 +9,8 @@ pub struct Options {
     /// If non-empty, override all ref-specs otherwise configured in the remote
     pub ref_specs: Vec<BString>,
     pub shallow: gix::remote::fetch::Shallow,
+    /// If set, omit objects matching this filter and mark the received pack as promisor pack.
+    pub filter: Option<gix::remote::fetch::ObjectFilter>,
     pub handshake_info: bool,
     pub negotiation_info: bool,
     pub open_negotiation_graph: Option<std::path::PathBuf>,
@@ -45,6 +47,7 @@ pub(crate) mod function {
             negotiation_info,
             open_negotiation_graph,
             shallow,
+            filter,
             ref_specs,
         }: Options,
     ) -> anyhow::Result<()>
@@ -61,12 +64,15 @@ pub(crate) mod function {
             remote.replace_refspecs(ref_specs.iter(), gix::remote::Direction::Fetch)?;
             remote = remote.with_fetch_tags(gix::remote::fetch::Tags::None);
         }
-        let res: gix::remote::fetch::Outcome = remote
+        let mut prepare = remote
             .connect(gix::remote::Direction::Fetch)?
             .prepare_fetch(&mut progress, Default::default())?
             .with_dry_run(dry_run)
-            .with_shallow(shallow)
-            .receive(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
+            .with_shallow(shallow);
+        if let Some(filter) = filter {
+            prepare = prepare.with_filter(filter);
+        }
+        let res: gix::remote::fetch::Outcome = prepare.receive(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
 
         if handshake_info {
             writeln!(out, "Handshake Information")?;
@@ -0,0 +1,74 @@
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// Only pack loose objects instead of repacking everything into a single pack.
+    pub incremental: bool,
+    /// If `false`, don't delete unreachable loose objects even if `gc.pruneExpire` says they expired.
+    pub prune: bool,
+    /// Write a multi-pack-index for all packs after repacking.
+    pub multi_pack_index: bool,
+}
+
+pub(crate) mod function {
+    use std::sync::atomic::AtomicBool;
+
+    use anyhow::bail;
+    use gix::maintenance::Repack;
+
+    use crate::{repository::maintenance::Options, OutputFormat};
+
+    pub fn maintenance(
+        repo: gix::Repository,
+        mut out: impl std::io::Write,
+        mut progress: impl gix::NestedProgress + 'static,
+        should_interrupt: &AtomicBool,
+        Options {
+            format,
+            incremental,
+            prune,
+            multi_pack_index,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        if format != OutputFormat::Human {
+            bail!("JSON output isn't implemented yet");
+        }
+        let mut options = repo.maintenance_options()?;
+        if incremental {
+            options.repack = Some(Repack::Incremental);
+        }
+        if !prune {
+            options.prune_expire = None;
+        }
+        options.write_multi_pack_index = multi_pack_index;
+
+        let outcome = repo.maintenance(&options, &mut progress, should_interrupt)?;
+        writeln!(out, "packed references: {}", outcome.packed_refs)?;
+        writeln!(out, "expired reflog entries: {}", outcome.expired_reflog_entries)?;
+        if let Some(path) = outcome.pack_path {
+            writeln!(
+                out,
+                "wrote {} objects to pack at '{}'",
+                outcome.packed_objects,
+                path.display()
+            )?;
+        }
+        writeln!(out, "deleted packs: {}", outcome.deleted_packs)?;
+        writeln!(out, "unpacked unreachable objects: {}", outcome.unpacked_objects)?;
+        writeln!(
+            out,
+            "deleted packed loose objects: {}",
+            outcome.deleted_packed_loose_objects
+        )?;
+        writeln!(out, "pruned objects: {}", outcome.pruned_objects)?;
+        if let Some(path) = outcome.multi_pack_index_path {
+            writeln!(out, "wrote multi-pack-index at '{}'", path.display())?;
+        }
+        if let Some(path) = outcome.commit_graph_path {
+            writeln!(out, "wrote commit-graph at '{}'", path.display())?;
+        }
+        Ok(())
+    }
+}
@@ -19,6 +19,7 @@ pub enum PathsOrPatterns {
 
 #[cfg(feature = "archive")]
 pub mod archive;
+pub mod blame;
 pub mod cat;
 pub use cat::function::cat;
 pub mod commit;
@@ -47,6 +48,8 @@ mod fsck;
 pub use fsck::function as fsck;
 pub mod index;
 pub mod mailmap;
+pub mod maintenance;
+pub use maintenance::function::maintenance;
 mod merge_base;
 pub use merge_base::merge_base;
 pub mod merge;
@@ -1,10 +1,14 @@
 use std::{ffi::OsString, path::PathBuf};
 
+use gix::bstr::BString;
+
 use crate::OutputFormat;
 
 pub struct Context {
     pub limit: Option<usize>,
     pub spec: OsString,
+    /// If not empty, only list commits that changed paths matching these path-specs.
+    pub pathspec: Vec<BString>,
     pub format: OutputFormat,
     pub text: Format,
 }
@@ -32,6 +36,7 @@ pub(crate) mod function {
         mut out: impl std::io::Write,
         super::Context {
             spec,
+            pathspec,
             format,
             text,
             limit,
@@ -46,14 +51,27 @@ pub(crate) mod function {
         let id = repo
             .rev_parse_single(spec)
             .context("Only single revisions are currently supported")?;
-        let commits = id
+        let mut commits = id
             .object()?
             .peel_to_kind(gix::object::Kind::Commit)
             .context("Need committish as starting point")?
             .id()
             .ancestors()
-            .sorting(Sorting::ByCommitTime(Default::default()))
-            .all()?;
+            .sorting(Sorting::ByCommitTime(Default::default()));
+        if !pathspec.is_empty() {
+            let index = repo.index_or_empty()?;
+            let (search, _attributes) = repo
+                .pathspec(
+                    true,
+                    pathspec,
+                    true,
+                    &index,
+                    gix::worktree::stack::state::attributes::Source::IdMapping,
+                )?
+                .into_parts();
+            commits = commits.with_pathspec(search);
+        }
+        let commits = commits.all()?;
 
         let mut vg = match text {
             Format::Svg { path } => (
@@ -49,10 +49,129 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+mod encode {
+    use super::{Vec, RLW_LARGEST_LITERAL_COUNT, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
+
+    /// Creation
+    impl Vec {
+        /// Create a new compressed bitmap with the bits at the indices yielded by `set_bits` set, which must be strictly increasing.
+        ///
+        /// The amount of bits in the bitmap will be one more than the last set bit.
+        pub fn from_set_bits(set_bits: impl IntoIterator<Item = usize>) -> Self {
+            let mut words = std::vec::Vec::<u64>::new();
+            let mut num_bits = 0;
+            for index in set_bits {
+                assert!(index >= num_bits, "BUG: bits must be set in strictly increasing order");
+                let word_index = index / 64;
+                if word_index >= words.len() {
+                    words.resize(word_index + 1, 0);
+                }
+                words[word_index] |= 1 << (index % 64);
+                num_bits = index + 1;
+            }
+            Self::compress(&words, num_bits)
+        }
+
+        /// Create a new compressed bitmap from uncompressed `words`, with bit `n` being bit `n % 64` of `words[n / 64]`.
+        ///
+        /// Like git, trailing words without set bits are dropped, and the amount of bits is a multiple of 64, but at least 64.
+        pub fn from_words(words: &[u64]) -> Self {
+            let num_words = words.iter().rposition(|w| *w != 0).map_or(1, |pos| pos + 1);
+            let words = if words.is_empty() {
+                &[0][..]
+            } else {
+                &words[..num_words]
+            };
+            Self::compress(words, words.len() * 64)
+        }
+
+        fn compress(words: &[u64], num_bits: usize) -> Self {
+            let mut bits = std::vec::Vec::with_capacity(words.len() + 1);
+            let mut rlw;
+            let mut remaining = words;
+            loop {
+                let (run_bit, run_len) = match remaining.first() {
+                    Some(&word) if word == 0 || word == u64::MAX => {
+                        let run_len = remaining
+                            .iter()
+                            .take(RLW_LARGEST_RUNNING_COUNT as usize)
+                            .take_while(|w| **w == word)
+                            .count();
+                        (u64::from(word == u64::MAX), run_len)
+                    }
+                    _ => (0, 0),
+                };
+                remaining = &remaining[run_len..];
+                let num_literals = remaining
+                    .iter()
+                    .take(RLW_LARGEST_LITERAL_COUNT as usize)
+                    .take_while(|w| **w != 0 && **w != u64::MAX)
+                    .count();
+
+                rlw = bits.len();
+                bits.push(run_bit | (run_len as u64) << 1 | (num_literals as u64) << (1 + RLW_RUNNING_BITS));
+                bits.extend_from_slice(&remaining[..num_literals]);
+                remaining = &remaining[num_literals..];
+                if remaining.is_empty() {
+                    break;
+                }
+            }
+
+            Vec {
+                num_bits: num_bits.try_into().expect("less than 4 billion bits"),
+                bits,
+                rlw: rlw as u64,
+            }
+        }
+    }
+
+    /// Serialization
+    impl Vec {
+        /// Write this bitmap to `out` in the format understood by [`decode()`][super::decode()].
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(
+                &u32::try_from(self.bits.len())
+                    .expect("less than 4 billion words")
+                    .to_be_bytes(),
+            )?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(
+                &u32::try_from(self.rlw)
+                    .expect("rlw offset is within our words")
+                    .to_be_bytes(),
+            )
+        }
+    }
+}
+
 mod access {
-    use super::Vec;
+    use super::{Vec, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
 
     impl Vec {
+        /// Return all bits uncompressed, with bit `n` being bit `n % 64` of the word at `n / 64`.
+        ///
+        /// There are as many words as needed to hold [all bits](Self::num_bits()).
+        pub fn to_words(&self) -> std::vec::Vec<u64> {
+            let mut out = std::vec::Vec::with_capacity((self.num_bits() + 63) / 64);
+            let mut iter = self.bits.iter();
+            while let Some(word) = iter.next() {
+                let run_word = if rlw_runbit_is_set(word) { u64::MAX } else { 0 };
+                out.extend(std::iter::repeat(run_word).take(rlw_running_len(word) as usize));
+                for _ in 0..rlw_literal_words(word) {
+                    out.push(
+                        *iter
+                            .next()
+                            .expect("BUG: ran out of words while going through uncompressed portion"),
+                    );
+                }
+            }
+            out.resize((self.num_bits() + 63) / 64, 0);
+            out
+        }
+
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
         /// If `f` returns `None` the iteration will be stopped and `None` is returned.
         ///
@@ -90,6 +209,11 @@ mod access {
         pub fn num_bits(&self) -> usize {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
         }
+
+        /// The amount of compressed words we store, useful to compare the size of bitmaps.
+        pub fn num_words(&self) -> usize {
+            self.bits.len()
+        }
     }
 
     #[inline]
@@ -111,14 +235,15 @@ mod access {
     fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
-
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
 }
 
+const RLW_RUNNING_BITS: u64 = 4 * 8;
+const RLW_LITERAL_BITS: u64 = 64 - 1 - RLW_RUNNING_BITS;
+const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << RLW_LITERAL_BITS) - 1;
+
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Vec {
     num_bits: u32,
     bits: std::vec::Vec<u64>,
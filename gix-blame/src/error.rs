@@ -0,0 +1,28 @@
+use bstr::BString;
+
+/// The error returned by [file()](crate::file()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The file to blame at '{file_path}' wasn't found in the first commit at {commit_id}")]
+    FileMissing {
+        /// The file-path to the object to blame.
+        file_path: BString,
+        /// The commit whose tree didn't contain `file_path`.
+        commit_id: gix_hash::ObjectId,
+    },
+    #[error("Couldn't find commit or tree in the object database")]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error("Couldn't find commit or tree in the object database")]
+    FindIterObject(#[from] gix_object::find::existing_iter::Error),
+    #[error("Could not decode the commit or tree that was just looked up")]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::topo::Error),
+    #[error(transparent)]
+    DiffTree(#[from] gix_diff::tree_with_rewrites::Error),
+    #[error(transparent)]
+    SetResource(#[from] gix_diff::blob::platform::set_resource::Error),
+    #[error(transparent)]
+    PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+}
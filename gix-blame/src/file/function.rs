@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_diff::blob::ResourceKind;
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+use gix_object::FindExt;
+
+use super::{blame_hunks, coalesce_blame_entries, split_hunk, unchanged_ranges};
+use crate::types::UnblamedHunk;
+use crate::{Error, Options, Outcome, Statistics};
+
+/// Produce a list of consecutive [`BlameEntry`](crate::BlameEntry) instances to indicate in which commits the ranges
+/// of the file at `file_path` in the `suspect` commit were introduced.
+///
+/// * `odb`
+///    - Access to the object database for looking up commits, trees and blobs.
+/// * `suspect`
+///    - The commit to start the blame at, which is the first commit that all lines are attributed to.
+/// * `cache`
+///    - An optional commit-graph to accelerate the traversal of the history.
+/// * `resource_cache`
+///    - A cache to convert blobs into a diffable state, and to perform the diffs. Configure it with
+///      [`Mode::ToGit`](gix_diff::blob::pipeline::Mode::ToGit) to see blobs just like `git blame` would.
+/// * `file_path`
+///    - A slash-separated path to the file to blame, relative to the root of the tree of `suspect`.
+/// * `options`
+///    - Options to control the blame, like rename tracking.
+///
+/// ## The algorithm
+///
+/// All lines of the *Blamed File* start out as being attributed to `suspect`. The history is then traversed in
+/// topological order so children are always seen before their parents. For each *Suspect* with lines attributed to it,
+/// the version of the file in each of its parents is diffed against the version in the *Suspect*, and all lines that
+/// are unchanged are passed on to the parent which becomes their new *Suspect*.
+/// The lines that couldn't be passed on to any parent were introduced by the *Suspect*, which is then blamed for them.
+///
+/// If a parent has the very same version of the file, all lines are passed on to it without diffing, which
+/// is also what `git` does, so it's the first parent with the same version that will receive all lines of a merge commit.
+/// If the file can't be found under its current name in a parent, it will be searched for as the source of a
+/// rename if [`Options::rewrites`] are configured.
+///
+/// The traversal ends once all lines are blamed.
+///
+/// Note that binary files are blamed as a whole on the first commit in which they changed, as their content can't be diffed.
+pub fn file(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    cache: Option<gix_commitgraph::Graph>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    file_path: &BStr,
+    options: Options,
+) -> Result<Outcome, Error> {
+    let _span = gix_trace::coarse!("gix_blame::file()", ?file_path, ?suspect);
+
+    let mut stats = Statistics::default();
+    let (mut buf, mut buf2) = (Vec::new(), Vec::new());
+    let root_tree = odb.find_commit(&suspect, &mut buf)?.tree();
+    let (blamed_blob_id, _mode) = find_path_entry_in_tree(&odb, root_tree, file_path, &mut buf, &mut stats)?
+        .ok_or_else(|| Error::FileMissing {
+            file_path: file_path.to_owned(),
+            commit_id: suspect,
+        })?;
+    let blamed_file_blob = odb.find_blob(&blamed_blob_id, &mut buf)?.data.to_vec();
+    let num_lines_in_blamed = gix_diff::blob::sources::byte_lines_with_terminator(&blamed_file_blob).count() as u32;
+
+    // An empty file has no lines that could be blamed.
+    if num_lines_in_blamed == 0 {
+        return Ok(Outcome {
+            entries: Vec::new(),
+            blob: blamed_file_blob,
+            statistics: stats,
+        });
+    }
+
+    let mut paths: Vec<BString> = vec![file_path.to_owned()];
+    let mut pending: HashMap<ObjectId, Vec<UnblamedHunk>> = HashMap::new();
+    pending.insert(
+        suspect,
+        vec![UnblamedHunk {
+            range_in_blamed_file: 0..num_lines_in_blamed,
+            start_in_suspect: 0,
+            path_index: 0,
+        }],
+    );
+
+    let traverse = gix_traverse::commit::topo::Builder::from_iters(&odb, [suspect], None::<Vec<ObjectId>>)
+        .with_commit_graph(cache)
+        .build()?;
+
+    let mut out = Vec::new();
+    let mut diff_state = gix_diff::tree::State::default();
+    let mut changes = Vec::new();
+    for item in traverse {
+        let item = item?;
+        stats.commits_traversed += 1;
+        let Some(mut hunks) = pending.remove(&item.id) else {
+            continue;
+        };
+
+        let suspect_tree = odb.find_commit(&item.id, &mut buf)?.tree();
+        let parent_trees = item
+            .parent_ids
+            .iter()
+            .map(|parent_id| Ok((*parent_id, odb.find_commit(parent_id, &mut buf)?.tree())))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        hunks.sort_by_key(|hunk| hunk.path_index);
+        let mut path_groups = Vec::new();
+        while let Some(path_index) = hunks.first().map(|hunk| hunk.path_index) {
+            let split_at = hunks.partition_point(|hunk| hunk.path_index == path_index);
+            path_groups.push((path_index, hunks.drain(..split_at).collect::<Vec<_>>()));
+        }
+
+        for (path_index, mut hunks) in path_groups {
+            let Some((suspect_blob_id, suspect_mode)) =
+                find_path_entry_in_tree(&odb, suspect_tree, paths[path_index].as_ref(), &mut buf, &mut stats)?
+            else {
+                // The path was seen by a child, so it must be present.
+                blame_hunks(hunks, item.id, &paths, &mut out);
+                continue;
+            };
+
+            let mut parent_entries = Vec::with_capacity(parent_trees.len());
+            for (parent_id, parent_tree) in &parent_trees {
+                let entry = match find_path_entry_in_tree(
+                    &odb,
+                    *parent_tree,
+                    paths[path_index].as_ref(),
+                    &mut buf,
+                    &mut stats,
+                )? {
+                    Some((id, mode)) => Some((id, mode, path_index)),
+                    None => match &options.rewrites {
+                        Some(rewrites) => find_rename_source(
+                            &odb,
+                            *parent_tree,
+                            suspect_tree,
+                            paths[path_index].as_ref(),
+                            rewrites,
+                            resource_cache,
+                            &mut diff_state,
+                            (&mut buf, &mut buf2),
+                            &mut stats,
+                        )?
+                        .map(|(id, mode, source_path)| {
+                            let index = paths.iter().position(|p| *p == source_path).unwrap_or_else(|| {
+                                paths.push(source_path);
+                                paths.len() - 1
+                            });
+                            (id, mode, index)
+                        }),
+                        None => None,
+                    },
+                };
+                parent_entries.push((*parent_id, entry));
+            }
+
+            if let Some((parent_id, parent_path_index)) =
+                parent_entries.iter().find_map(|(parent_id, entry)| match entry {
+                    Some((id, _mode, parent_path_index)) if *id == suspect_blob_id => {
+                        Some((*parent_id, *parent_path_index))
+                    }
+                    _ => None,
+                })
+            {
+                let to_parent = pending.entry(parent_id).or_default();
+                to_parent.extend(hunks.into_iter().map(|hunk| UnblamedHunk {
+                    path_index: parent_path_index,
+                    ..hunk
+                }));
+                continue;
+            }
+
+            for (parent_id, entry) in parent_entries {
+                let Some((parent_blob_id, parent_mode, parent_path_index)) = entry else {
+                    continue;
+                };
+                if hunks.is_empty() {
+                    break;
+                }
+                resource_cache.set_resource(
+                    parent_blob_id,
+                    parent_mode.kind(),
+                    paths[parent_path_index].as_ref(),
+                    ResourceKind::OldOrSource,
+                    &odb,
+                )?;
+                resource_cache.set_resource(
+                    suspect_blob_id,
+                    suspect_mode.kind(),
+                    paths[path_index].as_ref(),
+                    ResourceKind::NewOrDestination,
+                    &odb,
+                )?;
+                let outcome = resource_cache.prepare_diff()?;
+                let algorithm = match outcome.operation {
+                    gix_diff::blob::platform::prepare_diff::Operation::InternalDiff { algorithm } => algorithm,
+                    gix_diff::blob::platform::prepare_diff::Operation::ExternalCommand { .. }
+                    | gix_diff::blob::platform::prepare_diff::Operation::SourceOrDestinationIsBinary => continue,
+                };
+                let input = outcome.interned_input();
+                let num_lines_in_suspect = input.after.len() as u32;
+                changes.clear();
+                gix_diff::blob::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+                    changes.push((before, after));
+                });
+                stats.blobs_diffed += 1;
+
+                let unchanged = unchanged_ranges(&changes, num_lines_in_suspect);
+                let mut to_parent = Vec::new();
+                let mut remaining = Vec::with_capacity(hunks.len());
+                for hunk in hunks.drain(..) {
+                    split_hunk(hunk, &unchanged, parent_path_index, &mut to_parent, &mut remaining);
+                }
+                hunks = remaining;
+                if !to_parent.is_empty() {
+                    pending.entry(parent_id).or_default().extend(to_parent);
+                }
+            }
+            blame_hunks(hunks, item.id, &paths, &mut out);
+        }
+        resource_cache.clear_resource_cache_keep_allocation();
+
+        if pending.is_empty() {
+            break;
+        }
+    }
+
+    // Lines that are still pending belong to commits that weren't returned by the traversal,
+    // which happens if they are beyond a shallow boundary, and they are blamed on the commit they are pending for.
+    for (commit_id, hunks) in pending {
+        blame_hunks(hunks, commit_id, &paths, &mut out);
+    }
+
+    Ok(Outcome {
+        entries: coalesce_blame_entries(out),
+        blob: blamed_file_blob,
+        statistics: stats,
+    })
+}
+
+/// Find the entry at the slash-separated `path` starting at the tree with `tree_id`, and return its id and mode
+/// if it's a blob or a symlink.
+fn find_path_entry_in_tree(
+    odb: &impl gix_object::Find,
+    tree_id: ObjectId,
+    path: &BStr,
+    buf: &mut Vec<u8>,
+    stats: &mut Statistics,
+) -> Result<Option<(ObjectId, EntryMode)>, Error> {
+    let mut tree_id = tree_id;
+    let mut components = path.split_str("/").peekable();
+    while let Some(component) = components.next() {
+        let tree = odb.find_tree_iter(&tree_id, buf)?;
+        stats.trees_decoded += 1;
+        let Some(entry) = tree.filter_map(Result::ok).find(|entry| entry.filename == component) else {
+            return Ok(None);
+        };
+        if components.peek().is_none() {
+            return Ok(entry
+                .mode
+                .is_blob_or_symlink()
+                .then(|| (entry.oid.to_owned(), entry.mode)));
+        }
+        if !entry.mode.is_tree() {
+            return Ok(None);
+        }
+        tree_id = entry.oid.to_owned();
+    }
+    Ok(None)
+}
+
+/// Diff `parent_tree` against `suspect_tree` with rename tracking to find the source of `path` in `parent_tree`,
+/// returning its id, mode and location if it was renamed or copied.
+#[allow(clippy::too_many_arguments)]
+fn find_rename_source(
+    odb: &(impl gix_object::Find + gix_object::FindHeader),
+    parent_tree: ObjectId,
+    suspect_tree: ObjectId,
+    path: &BStr,
+    rewrites: &gix_diff::Rewrites,
+    resource_cache: &mut gix_diff::blob::Platform,
+    diff_state: &mut gix_diff::tree::State,
+    (parent_buf, suspect_buf): (&mut Vec<u8>, &mut Vec<u8>),
+    stats: &mut Statistics,
+) -> Result<Option<(ObjectId, EntryMode, BString)>, Error> {
+    let parent_tree_iter = odb.find_tree_iter(&parent_tree, parent_buf)?;
+    let suspect_tree_iter = odb.find_tree_iter(&suspect_tree, suspect_buf)?;
+    stats.trees_diffed += 1;
+
+    let mut source = None;
+    gix_diff::tree_with_rewrites(
+        parent_tree_iter,
+        suspect_tree_iter,
+        resource_cache,
+        diff_state,
+        odb,
+        |change| -> Result<_, std::convert::Infallible> {
+            if let gix_diff::tree_with_rewrites::ChangeRef::Rewrite {
+                source_location,
+                source_entry_mode,
+                source_id,
+                location,
+                ..
+            } = change
+            {
+                if location == path {
+                    source = Some((source_id, source_entry_mode, source_location.to_owned()));
+                }
+            }
+            Ok(gix_diff::tree_with_rewrites::Action::Continue)
+        },
+        gix_diff::tree_with_rewrites::Options {
+            location: Some(gix_diff::tree::recorder::Location::Path),
+            rewrites: Some(*rewrites),
+        },
+    )?;
+    Ok(source)
+}
@@ -0,0 +1,128 @@
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+use gix_hash::ObjectId;
+
+use crate::types::UnblamedHunk;
+use crate::BlameEntry;
+
+pub(super) mod function;
+
+/// A range of lines that is unchanged between the version of a file in a parent and the version in its child, the *Suspect*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Unchanged {
+    /// The range of lines in the *Suspect*.
+    pub range_in_suspect: Range<u32>,
+    /// The index of the first line of `range_in_suspect` in the parent.
+    pub start_in_parent: u32,
+}
+
+/// Turn the `changes` of a diff, pairs of `(before, after)` line ranges in the order produced by
+/// [`imara_diff`](gix_diff::blob::diff()), into the ranges of lines that didn't change, with `num_lines_in_suspect`
+/// being the amount of lines in the version of the file in the *Suspect*, i.e. *after*.
+pub(crate) fn unchanged_ranges(changes: &[(Range<u32>, Range<u32>)], num_lines_in_suspect: u32) -> Vec<Unchanged> {
+    let mut out = Vec::with_capacity(changes.len() + 1);
+    let (mut before_end, mut after_end) = (0, 0);
+    for (before, after) in changes {
+        if after.start > after_end {
+            out.push(Unchanged {
+                range_in_suspect: after_end..after.start,
+                start_in_parent: before_end,
+            });
+        }
+        before_end = before.end;
+        after_end = after.end;
+    }
+    if num_lines_in_suspect > after_end {
+        out.push(Unchanged {
+            range_in_suspect: after_end..num_lines_in_suspect,
+            start_in_parent: before_end,
+        });
+    }
+    out
+}
+
+/// Split `hunk` along the `unchanged` ranges of lines, which are assumed to be sorted and non-overlapping.
+///
+/// All portions of `hunk` that are unchanged are pushed to `to_parent` with their position adjusted to the parent,
+/// while everything else is pushed to `remaining` as it is still attributed to the *Suspect*.
+/// `parent_path_index` is the index of the path the file had in the parent.
+pub(crate) fn split_hunk(
+    hunk: UnblamedHunk,
+    unchanged: &[Unchanged],
+    parent_path_index: usize,
+    to_parent: &mut Vec<UnblamedHunk>,
+    remaining: &mut Vec<UnblamedHunk>,
+) {
+    let hunk_in_suspect = hunk.range_in_suspect();
+    let blamed_line_of =
+        |line_in_suspect: u32| hunk.range_in_blamed_file.start + (line_in_suspect - hunk_in_suspect.start);
+    let mut cursor = hunk_in_suspect.start;
+    let first_candidate = unchanged.partition_point(|u| u.range_in_suspect.end <= hunk_in_suspect.start);
+    for u in &unchanged[first_candidate..] {
+        if u.range_in_suspect.start >= hunk_in_suspect.end {
+            break;
+        }
+        let start = u.range_in_suspect.start.max(cursor);
+        let end = u.range_in_suspect.end.min(hunk_in_suspect.end);
+        if start > cursor {
+            remaining.push(UnblamedHunk {
+                range_in_blamed_file: blamed_line_of(cursor)..blamed_line_of(start),
+                start_in_suspect: cursor,
+                path_index: hunk.path_index,
+            });
+        }
+        to_parent.push(UnblamedHunk {
+            range_in_blamed_file: blamed_line_of(start)..blamed_line_of(end),
+            start_in_suspect: u.start_in_parent + (start - u.range_in_suspect.start),
+            path_index: parent_path_index,
+        });
+        cursor = end;
+    }
+    if cursor < hunk_in_suspect.end {
+        remaining.push(UnblamedHunk {
+            range_in_blamed_file: blamed_line_of(cursor)..hunk.range_in_blamed_file.end,
+            start_in_suspect: cursor,
+            path_index: hunk.path_index,
+        });
+    }
+}
+
+/// Attribute all `hunks` to `commit_id`, whose file was known under one of the `paths`, and push the result to `out`.
+pub(crate) fn blame_hunks(
+    hunks: impl IntoIterator<Item = UnblamedHunk>,
+    commit_id: ObjectId,
+    paths: &[bstr::BString],
+    out: &mut Vec<BlameEntry>,
+) {
+    out.extend(hunks.into_iter().filter_map(|hunk| {
+        Some(BlameEntry {
+            start_in_blamed_file: hunk.range_in_blamed_file.start,
+            start_in_source_file: hunk.start_in_suspect,
+            len: NonZeroU32::new(hunk.len())?,
+            commit_id,
+            source_file_name: paths[hunk.path_index].clone(),
+        })
+    }));
+}
+
+/// Sort `entries` by their position in the *Blamed File* and merge adjacent entries that originate from
+/// adjacent lines in the same *Source File* of the same commit.
+pub(crate) fn coalesce_blame_entries(mut entries: Vec<BlameEntry>) -> Vec<BlameEntry> {
+    entries.sort_by_key(|entry| entry.start_in_blamed_file);
+    let mut out: Vec<BlameEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match out.last_mut() {
+            Some(previous)
+                if previous.commit_id == entry.commit_id
+                    && previous.source_file_name == entry.source_file_name
+                    && previous.range_in_blamed_file().end == entry.start_in_blamed_file as usize
+                    && previous.range_in_source_file().end == entry.start_in_source_file as usize =>
+            {
+                previous.len = previous.len.saturating_add(entry.len.get());
+            }
+            _ => out.push(entry),
+        }
+    }
+    out
+}
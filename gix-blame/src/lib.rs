@@ -1,10 +1,23 @@
 //! A crate to implement an algorithm to annotate lines in tracked files with the commits that changed them.
-#![deny(rust_2018_idioms)]
+//!
+//! ### Terminology
+//!
+//! * **Blamed File**
+//!    - The file as it exists in the commit the blame starts from, whose lines are to be attributed to commits.
+//! * **Source File**
+//!    - The file as it exists in the commit a range of lines is attributed to. Its path may differ from the one
+//!      of the *Blamed File* if it was renamed since.
+//! * **Suspect**
+//!    - The commit that is currently considered to be responsible for a range of lines, until the lines are found to
+//!      exist unchanged in one of its parents, which then becomes the new suspect.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+#![allow(clippy::result_large_err)]
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    #[ignore]
-    fn it_works() {}
-}
+mod error;
+pub use error::Error;
+mod types;
+pub use types::{BlameEntry, Options, Outcome, Statistics};
+
+mod file;
+pub use file::function::file;
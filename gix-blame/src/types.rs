@@ -0,0 +1,115 @@
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// Options to be passed to [`file()`](crate::file()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `Some(_)`, rename tracking will be performed whenever the blamed file doesn't exist in a parent of the suspect
+    /// under its current name, allowing to follow the lines of a file across renames.
+    ///
+    /// If `None`, lines of a file that was renamed are attributed to the commit that introduced the new name.
+    pub rewrites: Option<gix_diff::Rewrites>,
+}
+
+/// The outcome of [`file()`](crate::file()).
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// One entry in sequential order, to associate a hunk in the blamed file with the source commit (and its lines)
+    /// that introduced it.
+    ///
+    /// Entries don't overlap and cover all lines of [`blob`](Self::blob) without gaps.
+    pub entries: Vec<BlameEntry>,
+    /// A buffer with the file content of the *Blamed File*, ready for tokenization.
+    pub blob: Vec<u8>,
+    /// Additional information about the amount of work performed to produce the blame.
+    pub statistics: Statistics,
+}
+
+impl Outcome {
+    /// Return an iterator over each entry in [`Self::entries`], along with its lines, line by line.
+    ///
+    /// Note that [`Self::blob`] must be tokenized in exactly the same way as the tokenizer that was used
+    /// to perform the diffs, which is what this method assures.
+    pub fn entries_with_lines(&self) -> impl Iterator<Item = (BlameEntry, Vec<BString>)> + '_ {
+        let lines: Vec<_> = gix_diff::blob::sources::byte_lines_with_terminator(&self.blob).collect();
+        self.entries.iter().map(move |entry| {
+            (
+                entry.clone(),
+                lines[entry.range_in_blamed_file()]
+                    .iter()
+                    .map(|line| BString::from(*line))
+                    .collect(),
+            )
+        })
+    }
+}
+
+/// Additional information about the performed operations.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Statistics {
+    /// The amount of commits it traversed until the blame was complete.
+    pub commits_traversed: usize,
+    /// The amount of trees that were decoded to find the entry of the file to blame.
+    pub trees_decoded: usize,
+    /// The amount of tree-diffs to see if the filepath was added, deleted or renamed.
+    pub trees_diffed: usize,
+    /// The amount of blobs there were compared to each other to learn what changed between commits.
+    /// Note that in order to diff a blob, one needs to load both versions from the database.
+    pub blobs_diffed: usize,
+}
+
+/// Describes the commit that a contiguous range of lines of the *Blamed File* is attributed to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BlameEntry {
+    /// The index of the first line of this hunk in the *Blamed File*, 0-based.
+    pub start_in_blamed_file: u32,
+    /// The index of the first line of this hunk in the *Source File* as it exists in [`commit_id`](Self::commit_id), 0-based.
+    pub start_in_source_file: u32,
+    /// The amount of lines the hunk is spanning.
+    pub len: NonZeroU32,
+    /// The commit that introduced the lines of this hunk.
+    pub commit_id: ObjectId,
+    /// The path of the *Source File* in [`commit_id`](Self::commit_id), which differs from the path of the
+    /// *Blamed File* if it was renamed in the meantime.
+    pub source_file_name: BString,
+}
+
+impl BlameEntry {
+    /// Return the range of lines this entry spans in the *Blamed File*.
+    pub fn range_in_blamed_file(&self) -> Range<usize> {
+        let start = self.start_in_blamed_file as usize;
+        start..start + self.len.get() as usize
+    }
+
+    /// Return the range of lines this entry spans in the *Source File*.
+    pub fn range_in_source_file(&self) -> Range<usize> {
+        let start = self.start_in_source_file as usize;
+        start..start + self.len.get() as usize
+    }
+}
+
+/// A range of lines in the *Blamed File* that still has to be attributed to a commit, along with the place
+/// where these lines can be found in the current *Suspect*.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct UnblamedHunk {
+    /// The lines in the *Blamed File* this hunk represents.
+    pub range_in_blamed_file: Range<u32>,
+    /// The index of the first line of this hunk in the version of the file in the *Suspect*.
+    pub start_in_suspect: u32,
+    /// The index into the table of paths the file had in the *Suspect*.
+    pub path_index: usize,
+}
+
+impl UnblamedHunk {
+    pub(crate) fn len(&self) -> u32 {
+        self.range_in_blamed_file.end - self.range_in_blamed_file.start
+    }
+
+    /// Return the range of lines in the *Suspect* this hunk covers.
+    pub(crate) fn range_in_suspect(&self) -> Range<u32> {
+        self.start_in_suspect..self.start_in_suspect + self.len()
+    }
+}
@@ -1,4 +1,197 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+
+use gix_blame::BlameEntry;
+use gix_hash::ObjectId;
+use gix_object::bstr::{BStr, BString, ByteSlice};
+use gix_ref::file::ReferenceExt;
+
+struct Baseline<'a> {
+    lines: bstr::Lines<'a>,
+    filenames: HashMap<ObjectId, BString>,
+}
+
+impl Baseline<'_> {
+    fn collect(baseline_path: impl AsRef<std::path::Path>) -> std::io::Result<Vec<BlameEntry>> {
+        let content = std::fs::read(baseline_path)?;
+        let entries = Baseline {
+            lines: content.lines(),
+            filenames: HashMap::new(),
+        }
+        .collect::<Vec<_>>();
+        Ok(coalesce(entries))
+    }
+}
+
+impl Iterator for Baseline<'_> {
+    type Item = BlameEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // A header looks like `<commit> <line-in-source> <line-in-blamed> [<lines-in-group>]`, followed by
+        // optional metadata lines, and the line itself which is prefixed with a tab.
+        let header = self.lines.next()?;
+        let mut fields = header.split_str(" ");
+        let commit_id = ObjectId::from_hex(fields.next()?).expect("valid hex");
+        let start_in_source_file: u32 = fields.next()?.to_str().ok()?.parse().ok()?;
+        let start_in_blamed_file: u32 = fields.next()?.to_str().ok()?.parse().ok()?;
+
+        for line in self.lines.by_ref() {
+            if line.starts_with(b"\t") {
+                break;
+            }
+            if let Some(filename) = line.strip_prefix(b"filename ") {
+                self.filenames.insert(commit_id, filename.into());
+            }
+        }
+
+        Some(BlameEntry {
+            start_in_blamed_file: start_in_blamed_file - 1,
+            start_in_source_file: start_in_source_file - 1,
+            len: NonZeroU32::new(1).expect("non-zero"),
+            commit_id,
+            source_file_name: self
+                .filenames
+                .get(&commit_id)
+                .cloned()
+                .expect("filename is always set once"),
+        })
+    }
+}
+
+/// Merge adjacent entries the same way `gix-blame` does.
+fn coalesce(entries: Vec<BlameEntry>) -> Vec<BlameEntry> {
+    let mut out: Vec<BlameEntry> = Vec::new();
+    for entry in entries {
+        match out.last_mut() {
+            Some(previous)
+                if previous.commit_id == entry.commit_id
+                    && previous.source_file_name == entry.source_file_name
+                    && previous.range_in_blamed_file().end == entry.start_in_blamed_file as usize
+                    && previous.range_in_source_file().end == entry.start_in_source_file as usize =>
+            {
+                previous.len = previous.len.checked_add(1).expect("no overflow");
+            }
+            _ => out.push(entry),
+        }
+    }
+    out
+}
+
+struct Fixture {
+    odb: gix_odb::Handle,
+    resource_cache: gix_diff::blob::Platform,
+    head_id: ObjectId,
+    baselines: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> gix_testtools::Result<Fixture> {
+        let worktree = gix_testtools::scripted_fixture_read_only("make_blame_repo.sh")?;
+        let git_dir = worktree.join(".git");
+        let odb = gix_odb::at(git_dir.join("objects"))?;
+        let store = gix_ref::file::Store::at(
+            git_dir.clone(),
+            gix_ref::store::init::Options {
+                write_reflog: gix_ref::store::WriteReflog::Disable,
+                ..Default::default()
+            },
+        );
+        let mut reference = store.find("HEAD")?;
+        let head_id = reference.peel_to_id_in_place(&store, &odb)?;
+
+        let resource_cache = gix_diff::blob::Platform::new(
+            Default::default(),
+            gix_diff::blob::Pipeline::new(Default::default(), Default::default(), Vec::new(), Default::default()),
+            gix_diff::blob::pipeline::Mode::ToGit,
+            gix_worktree::Stack::new(
+                &worktree,
+                gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::default()),
+                gix_worktree::glob::pattern::Case::Sensitive,
+                Vec::new(),
+                Vec::new(),
+            ),
+        );
+        Ok(Fixture {
+            odb,
+            resource_cache,
+            head_id,
+            baselines: git_dir.join("baselines"),
+        })
+    }
+
+    fn blame(&mut self, file_path: &str, options: gix_blame::Options) -> gix_testtools::Result<gix_blame::Outcome> {
+        Ok(gix_blame::file(
+            &self.odb,
+            self.head_id,
+            None,
+            &mut self.resource_cache,
+            BStr::new(file_path),
+            options,
+        )?)
+    }
+
+    fn baseline(&self, name: &str) -> std::io::Result<Vec<BlameEntry>> {
+        Baseline::collect(self.baselines.join(format!("{name}.baseline")))
+    }
+}
+
+fn with_rewrites() -> gix_blame::Options {
+    gix_blame::Options {
+        rewrites: Some(Default::default()),
+    }
+}
+
+#[test]
+fn files_match_baseline() -> gix_testtools::Result {
+    let mut fixture = Fixture::new()?;
+    for file in [
+        "simple.txt",
+        "multiline-hunks.txt",
+        "deleted-lines.txt",
+        "resolved-conflict.txt",
+        "added-on-branch.txt",
+    ] {
+        let outcome = fixture.blame(file, Default::default())?;
+        pretty_assertions::assert_eq!(outcome.entries, fixture.baseline(file)?, "{file}");
+        assert_eq!(
+            outcome
+                .entries_with_lines()
+                .map(|(_, lines)| lines.len())
+                .sum::<usize>(),
+            outcome.blob.lines().count(),
+            "all lines are covered"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn renames_are_followed_if_rewrites_are_configured() -> gix_testtools::Result {
+    let mut fixture = Fixture::new()?;
+    let outcome = fixture.blame("after-rename.txt", with_rewrites())?;
+    pretty_assertions::assert_eq!(outcome.entries, fixture.baseline("after-rename.txt")?);
+    assert!(outcome.statistics.trees_diffed > 0);
+
+    let outcome = fixture.blame("after-rename.txt", Default::default())?;
+    pretty_assertions::assert_eq!(outcome.entries, fixture.baseline("after-rename-no-follow.txt")?);
+    assert_eq!(outcome.statistics.trees_diffed, 0);
+    Ok(())
+}
+
+#[test]
+fn empty_files_have_no_entries() -> gix_testtools::Result {
+    let mut fixture = Fixture::new()?;
+    let outcome = fixture.blame("empty.txt", Default::default())?;
+    assert!(outcome.entries.is_empty());
+    assert!(outcome.blob.is_empty());
+    Ok(())
+}
+
 #[test]
-fn it_works() {
-    let _worktree = gix_testtools::scripted_fixture_read_only("make_blame_repo.sh").unwrap();
+fn missing_files_are_an_error() -> gix_testtools::Result {
+    let mut fixture = Fixture::new()?;
+    let err = fixture.blame("does-not-exist.txt", Default::default()).unwrap_err();
+    assert!(err.to_string().contains("wasn't found in the first commit"));
+    Ok(())
 }
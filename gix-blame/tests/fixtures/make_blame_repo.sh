@@ -21,3 +21,76 @@ git checkout -q main
 git commit -q --allow-empty -m c5
 git tag at-c5
 git merge branch1 -m m1b1
+
+echo "line 1" >> simple.txt
+git add simple.txt
+git commit -q -m c6
+
+echo "line 2" >> simple.txt
+git add simple.txt
+git commit -q -m c7
+
+echo -e "line 1\nline 2\nline 3" >> multiline-hunks.txt
+git add multiline-hunks.txt
+git commit -q -m c8
+
+echo -e "line 4\nline 5\nline 6" >> multiline-hunks.txt
+git add multiline-hunks.txt
+git commit -q -m c9
+
+echo -e "line 1\nline changed\nline 3\nline 4\nline 5\nline 6" > multiline-hunks.txt
+git add multiline-hunks.txt
+git commit -q -m c10
+
+printf "line 1\nline in middle\nline 2\n" > simple.txt
+git add simple.txt
+git commit -q -m c11
+
+echo -e "a\nb\nc\nd\ne\nf\ng\nh\ni\nj" > deleted-lines.txt
+git add deleted-lines.txt
+git commit -q -m c12
+
+echo -e "a\nc\nd\ne\nh\ni\nj" > deleted-lines.txt
+git add deleted-lines.txt
+git commit -q -m c13
+
+mkdir -p sub
+echo -e "one\ntwo\nthree\nfour\nfive" > sub/before-rename.txt
+git add sub/before-rename.txt
+git commit -q -m c14
+
+git mv sub/before-rename.txt after-rename.txt
+echo -e "six" >> after-rename.txt
+git add after-rename.txt
+git commit -q -m c15
+
+echo -e "line 1\nline 2\nline 3\nline 4" > resolved-conflict.txt
+git add resolved-conflict.txt
+git commit -q -m c16
+
+git checkout -q -b branch2
+echo -e "line 1\nline 2 on branch\nline 3\nline 4" > resolved-conflict.txt
+echo -e "x\ny\nz" > added-on-branch.txt
+git add resolved-conflict.txt added-on-branch.txt
+git commit -q -m b2c1
+
+git checkout -q main
+echo -e "line 1\nline 2\nline 3\nline 4 on main" > resolved-conflict.txt
+git add resolved-conflict.txt
+git commit -q -m c17
+
+git merge branch2 -q -m m2b2 || true
+echo -e "line 1\nline 2 on branch\nline 3\nline 4 on main\nline 5 in merge" > resolved-conflict.txt
+git add resolved-conflict.txt
+git commit -q -m m2b2
+
+: > empty.txt
+git add empty.txt
+git commit -q -m c18
+
+mkdir .git/baselines
+for file in simple.txt multiline-hunks.txt deleted-lines.txt resolved-conflict.txt added-on-branch.txt; do
+  git blame --porcelain "$file" > ".git/baselines/$file.baseline"
+done
+git blame --porcelain after-rename.txt > .git/baselines/after-rename.txt.baseline
+git blame --no-follow --porcelain after-rename.txt > .git/baselines/after-rename-no-follow.txt.baseline
@@ -41,6 +41,31 @@ impl Graph {
         Some(self.lookup_by_id(id.as_ref())?.graph_pos)
     }
 
+    /// Returns the settings of the changed-path Bloom filters of the most recent file that has them, or `None` if
+    /// there are no Bloom filters.
+    pub fn bloom_filter_settings(&self) -> Option<crate::bloom::Settings> {
+        self.files.iter().rev().find_map(File::bloom_filter_settings)
+    }
+
+    /// Returns `true` if all files contain corrected commit dates, which is when [`generation()`](Self::generation()) returns them.
+    pub fn has_generation_data(&self) -> bool {
+        self.files.iter().all(File::has_generation_data)
+    }
+
+    /// Returns the generation of `commit` for comparison with the generations of other commits in this graph.
+    ///
+    /// This is the [corrected commit date](Commit::corrected_commit_date()) if [all files have one](Self::has_generation_data()),
+    /// or the [topological level](Commit::generation()) otherwise, as mixing both would break their ordering.
+    /// Commits that aren't in the graph should be assigned [`GENERATION_INFINITY`](crate::GENERATION_INFINITY).
+    pub fn generation(&self, commit: &Commit<'_>) -> u64 {
+        if self.has_generation_data() {
+            if let Some(date) = commit.corrected_commit_date() {
+                return date;
+            }
+        }
+        commit.generation().into()
+    }
+
     /// Returns the number of commits stored in this file.
     pub fn num_commits(&self) -> u32 {
         self.files.iter().map(File::num_commits).sum()
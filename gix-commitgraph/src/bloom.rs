@@ -0,0 +1,196 @@
+//! Changed-path Bloom filters as stored in the `BIDX` and `BDAT` chunks of commit-graph files.
+//!
+//! Each commit may have a filter containing all paths that changed compared to its first parent, along with all of their
+//! leading directories, which allows to quickly rule out that a commit touched a given path.
+use bstr::BStr;
+
+/// The seed of the first of the two murmur3 hashes from which all hashes of a [`Key`] are derived.
+const SEED0: u32 = 0x293a_e76f;
+/// The seed of the second of the two murmur3 hashes from which all hashes of a [`Key`] are derived.
+const SEED1: u32 = 0x7e64_6e2c;
+/// The amount of bits in each byte of the filter data.
+const BITS_PER_WORD: u64 = 8;
+
+/// The data of a filter for commits that changed more than [`Settings::max_changed_paths`], which matches every path.
+pub const TOO_LARGE_FILTER: &[u8] = &[0xff];
+
+/// The parameters used to compute and query Bloom filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the murmur3 hash used to hash paths.
+    ///
+    /// Version `1` incorrectly sign-extends bytes with the high bit set, version `2` is correct for all paths.
+    pub hash_version: u32,
+    /// The amount of hashes and thus bits set per path.
+    pub num_hashes: u32,
+    /// The amount of bits reserved for each path in the filter.
+    pub bits_per_entry: u32,
+    /// If a commit changed more paths than this, including their leading directories, a filter is written that
+    /// matches everything.
+    pub max_changed_paths: u32,
+}
+
+impl Default for Settings {
+    /// The settings git uses, with version 1 hashes for compatibility with older git versions.
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+            max_changed_paths: 512,
+        }
+    }
+}
+
+/// The hashes of a single path, ready to be added to a filter or to be looked up in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key for `path`, which is relative to the repository root and doesn't end in a slash, with `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let hash0 = murmur3(settings.hash_version, SEED0, path);
+        let hash1 = murmur3(settings.hash_version, SEED1, path);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+
+    /// Return the hashes of this key.
+    pub fn hashes(&self) -> &[u32] {
+        &self.hashes
+    }
+
+    /// Return the bit positions in a filter of `filter_len` bytes that are set for this key, as byte offset and bit mask.
+    pub(crate) fn bit_positions(&self, filter_len: usize) -> impl Iterator<Item = (usize, u8)> + '_ {
+        let num_bits = filter_len as u64 * BITS_PER_WORD;
+        self.hashes.iter().map(move |hash| {
+            let bit = u64::from(*hash) % num_bits;
+            ((bit / BITS_PER_WORD) as usize, 1 << (bit % BITS_PER_WORD))
+        })
+    }
+}
+
+/// The changed-path Bloom filter of a single commit, as obtained with [`Commit::changed_paths_filter()`](crate::file::Commit::changed_paths_filter()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter<'a> {
+    settings: Settings,
+    data: &'a [u8],
+}
+
+impl<'a> Filter<'a> {
+    /// Create a new instance from the filter `data` which was computed with `settings`.
+    ///
+    /// An empty `data` is treated as unknown filter which may contain every path.
+    pub fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { settings, data }
+    }
+
+    /// Return the settings this filter was computed with, and which must be used to create [keys](Key) for lookups.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Return the raw data of this filter.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return `false` if the path of `key` definitely didn't change in the commit of this filter, or `true` if it may have changed.
+    ///
+    /// `key` must have been created with the [settings](Self::settings()) of this filter.
+    pub fn contains(&self, key: &Key) -> bool {
+        self.data.is_empty()
+            || key
+                .bit_positions(self.data.len())
+                .all(|(byte, mask)| self.data[byte] & mask != 0)
+    }
+
+    /// Like [`contains()`](Self::contains()), but computes the key for `path` with the settings of this filter.
+    pub fn contains_path(&self, path: &BStr) -> bool {
+        self.contains(&Key::new(path, &self.settings))
+    }
+}
+
+/// Compute the data of the filter for a commit that changed the files at `changed_paths`, with `settings`.
+///
+/// The leading directories of all paths are added automatically, and paths may be repeated.
+/// If there are more than [`Settings::max_changed_paths`] paths, the [filter matching everything](TOO_LARGE_FILTER) is returned.
+pub fn filter_data<'a>(changed_paths: impl IntoIterator<Item = &'a BStr>, settings: &Settings) -> Vec<u8> {
+    let mut paths = Vec::new();
+    for path in changed_paths {
+        let mut path = path;
+        loop {
+            paths.push(path);
+            match path.iter().rposition(|b| *b == b'/') {
+                Some(pos) => path = path[..pos].into(),
+                None => break,
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    if paths.len() > settings.max_changed_paths as usize {
+        return TOO_LARGE_FILTER.into();
+    }
+
+    let len = ((paths.len() as u64 * u64::from(settings.bits_per_entry) + BITS_PER_WORD - 1) / BITS_PER_WORD) as usize;
+    let mut data = vec![0; len.max(1)];
+    for path in paths {
+        let key = Key::new(path, settings);
+        for (byte, mask) in key.bit_positions(data.len()) {
+            data[byte] |= mask;
+        }
+    }
+    data
+}
+
+/// The murmur3 hash of `data` with `seed`, in the `version` of the git implementation.
+///
+/// Version `1` sign-extends bytes before using them, as git did unintentionally, which only makes a difference for
+/// non-ASCII paths.
+fn murmur3(version: u32, mut seed: u32, data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+
+    let byte = |b: u8| -> u32 {
+        if version == 1 {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        seed ^= mix(k);
+        seed = seed.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (shift * 8);
+        }
+        seed ^= mix(k);
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
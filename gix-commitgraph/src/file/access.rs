@@ -4,7 +4,8 @@ use std::{
 };
 
 use crate::{
-    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+    bloom,
+    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, GENERATION_DATA_OVERFLOW_MASK},
     File,
 };
 
@@ -15,6 +16,16 @@ impl File {
         self.base_graph_count
     }
 
+    /// Returns `true` if this file contains corrected commit dates, also known as generation numbers v2.
+    pub fn has_generation_data(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
+    /// Returns the settings of the changed-path Bloom filters stored in this file, or `None` if there are none.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filter_data.as_ref().map(|(settings, _)| *settings)
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
@@ -127,6 +138,37 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the offset between the corrected commit date and the commit date of the commit at `pos` from the
+    /// Generation Data (GDA2) chunk, or `None` if there is no such chunk or if the overflow entry is missing.
+    pub(crate) fn corrected_commit_date_offset(&self, pos: file::Position) -> Option<u64> {
+        let start = self.generation_data_offset? + pos.0 as usize * 4;
+        let offset = u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes"));
+        if offset & GENERATION_DATA_OVERFLOW_MASK == 0 {
+            return Some(offset.into());
+        }
+        let overflow = self.generation_data_overflow_range.clone()?;
+        let start = overflow.start + (offset & !GENERATION_DATA_OVERFLOW_MASK) as usize * 8;
+        let bytes = self.data.get(start..overflow.end)?.get(..8)?;
+        Some(u64::from_be_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    /// Returns the data of the changed-path Bloom filter of the commit at `pos` along with the settings it was computed with,
+    /// or `None` if there are no Bloom filters or if they are corrupt.
+    ///
+    /// Note that the data is empty if no filter was computed for the commit.
+    pub(crate) fn bloom_filter_at(&self, pos: file::Position) -> Option<(bloom::Settings, &[u8])> {
+        let index_offset = self.bloom_filter_index_offset?;
+        let (settings, range) = self.bloom_filter_data.as_ref()?;
+        let read_end = |pos: u32| {
+            let start = index_offset + pos as usize * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes")) as usize
+        };
+        let start = pos.0.checked_sub(1).map_or(0, read_end);
+        let end = read_end(pos.0);
+        let filter = self.data.get(range.clone())?.get(start..end)?;
+        Some((*settings, filter))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
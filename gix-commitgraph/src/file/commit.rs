@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
     File, Position,
 };
@@ -50,9 +51,6 @@ impl<'a> Commit<'a> {
             root_tree_id: gix_hash::oid::from_bytes_unchecked(&bytes[..file.hash_len]),
             parent1: ParentEdge::from_raw(read_u32(&bytes[file.hash_len..][..4])),
             parent2: ParentEdge::from_raw(read_u32(&bytes[file.hash_len + 4..][..4])),
-            // TODO: Add support for corrected commit date offset overflow.
-            //      See https://github.com/git/git/commit/e8b63005c48696a26f976f5f9b0ccaf1983e439d and
-            //          https://github.com/git/git/commit/f90fca638e99a031dce8e3aca72427b2f9b4bb38 for more details and hints at a test.
             generation: read_u32(&bytes[file.hash_len + 8..][..4]) >> 2,
             commit_timestamp: u64::from_be_bytes(bytes[file.hash_len + 8..][..8].try_into().unwrap())
                 & 0x0003_ffff_ffff,
@@ -74,6 +72,26 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, if the owning file has
+    /// generation data.
+    ///
+    /// It is the committer timestamp of this commit, or one more than the highest corrected commit date of its parents
+    /// if that is larger. Note that it is only valid if all files of the owning [Graph][crate::Graph] have generation data.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        self.file
+            .corrected_commit_date_offset(self.pos)
+            .map(|offset| self.commit_timestamp.saturating_add(offset))
+    }
+
+    /// Returns the changed-path Bloom filter of this commit, which tells which paths definitely didn't change compared to
+    /// its first parent, or `None` if the owning file has no filter for it.
+    pub fn changed_paths_filter(&self) -> Option<bloom::Filter<'a>> {
+        self.file
+            .bloom_filter_at(self.pos)
+            .filter(|(_, data)| !data.is_empty())
+            .map(|(settings, data)| bloom::Filter::new(data, settings))
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
@@ -3,8 +3,10 @@ use std::path::PathBuf;
 
 use crate::{
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
 };
@@ -217,13 +219,54 @@ impl File {
                 chunk2_commits: commit_data_count,
             });
         }
+
+        // Like git, optional chunks of the wrong size are ignored instead of rendering the whole file unusable.
+        let num_commits = fan[255] as usize;
+        let generation_data_offset = chunks
+            .usize_offset_by_id(GENERATION_DATA_CHUNK_ID)
+            .ok()
+            .filter(|range| range.len() == num_commits * 4)
+            .map(|range| range.start);
+        let generation_data_overflow_range = chunks
+            .usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID)
+            .ok()
+            .filter(|range| range.len() % 8 == 0);
+        let bloom_filter_index_offset = chunks
+            .usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID)
+            .ok()
+            .filter(|range| range.len() == num_commits * 4)
+            .map(|range| range.start);
+        let bloom_filter_data = chunks
+            .usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID)
+            .ok()
+            .filter(|range| range.len() >= BLOOM_FILTER_DATA_HEADER_LEN)
+            .map(|range| {
+                let header = &data[range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().expect("4 bytes"));
+                let settings = crate::bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                    ..Default::default()
+                };
+                (settings, range.start + BLOOM_FILTER_DATA_HEADER_LEN..range.end)
+            });
+        let (bloom_filter_index_offset, bloom_filter_data) = match (bloom_filter_index_offset, bloom_filter_data) {
+            (Some(index), Some(data)) => (Some(index), Some(data)),
+            _ => (None, None),
+        };
+
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data,
             commit_data_offset,
             data,
             extra_edges_list_range,
             fan,
+            generation_data_offset,
+            generation_data_overflow_range,
             oid_lookup_offset,
             path,
             hash_len: object_hash.len_in_bytes(),
@@ -138,7 +138,8 @@ impl File {
     /// Assure the [`checksum`][File::checksum()] matches the actual checksum over all content of this file, excluding the trailing
     /// checksum itself.
     ///
-    /// Return the actual checksum on success or `(actual checksum, expected checksum)` if there is a mismatch.
+    /// Return the actual checksum on success or `(actual checksum, expected checksum)` if there is a mismatch,
+    /// which is also the case if the content is part of a known SHA1 collision attack.
     pub fn verify_checksum(&self) -> Result<gix_hash::ObjectId, (gix_hash::ObjectId, gix_hash::ObjectId)> {
         // Even though we could use gix_features::hash::bytes_of_file(…), this would require using our own
         // Error type to support io::Error and Mismatch. As we only gain progress, there probably isn't much value
@@ -147,9 +148,11 @@ impl File {
         let data_len_without_trailer = self.data.len() - self.hash_len;
         let mut hasher = gix_features::hash::hasher(self.object_hash());
         hasher.update(&self.data[..data_len_without_trailer]);
-        let actual = gix_hash::ObjectId::from_bytes_or_panic(hasher.digest().as_ref());
-
         let expected = self.checksum();
+        let actual = match hasher.try_finalize() {
+            Ok(actual) => actual,
+            Err(gix_features::hash::Error::CollisionAttack { digest }) => return Err((digest, expected.into())),
+        };
         if actual == expected {
             Ok(actual)
         } else {
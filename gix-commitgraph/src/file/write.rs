@@ -0,0 +1,319 @@
+use std::io::Write;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN, BLOOM_FILTER_INDEX_CHUNK_ID,
+        COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN,
+        GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN,
+        LAST_EXTENDED_EDGE_MASK, NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    write::{ChangedPaths, Commit, Error, Options},
+    File, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// The largest corrected commit date offset that can be stored without using the overflow list.
+const GENERATION_DATA_OFFSET_MAX: u64 = 0x7fff_ffff;
+
+/// The generation data of a single commit.
+#[derive(Clone, Copy)]
+struct Generation {
+    topo_level: u32,
+    corrected_commit_date: u64,
+}
+
+/// The location of a parent, either in the file to write or in the base graph.
+enum Parent {
+    New(usize),
+    Base {
+        position: crate::Position,
+        generation: Generation,
+    },
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file with `commits` to `out` and return its checksum, with `base` being the layers of a
+    /// split commit-graph this file is supposed to be added to, oldest first.
+    ///
+    /// `commits` that are already contained in `base` are ignored, and all parents must be part of `commits` or `base`.
+    /// The Bloom filter settings and the [split options](crate::write::Options::split) of `options` are ignored.
+    pub fn write_to(
+        mut commits: Vec<Commit>,
+        base: &[File],
+        out: &mut dyn std::io::Write,
+        options: &Options,
+    ) -> Result<ObjectId, Error> {
+        let base_lookup = |id: &gix_hash::oid| {
+            let mut start = 0;
+            for file in base {
+                if let Some(pos) = file.lookup(id) {
+                    return Some((file.commit_at(pos), crate::Position(start + pos.0)));
+                }
+                start += file.num_commits();
+            }
+            None
+        };
+        commits.sort_by_key(|commit| commit.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        commits.retain(|commit| base_lookup(&commit.id).is_none());
+
+        let num_base_commits: u64 = base.iter().map(|file| u64::from(file.num_commits())).sum();
+        let num_commits = num_base_commits + commits.len() as u64;
+        if num_commits > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(num_commits));
+        }
+
+        let parents = commits
+            .iter()
+            .map(|commit| {
+                commit
+                    .parents
+                    .iter()
+                    .map(|parent| match commits.binary_search_by(|c| c.id.cmp(parent)) {
+                        Ok(idx) => Ok(Parent::New(idx)),
+                        Err(_) => base_lookup(parent)
+                            .map(|(commit, position)| Parent::Base {
+                                position,
+                                generation: Generation {
+                                    topo_level: commit.generation(),
+                                    corrected_commit_date: commit.corrected_commit_date().unwrap_or(0),
+                                },
+                            })
+                            .ok_or(Error::MissingParent {
+                                id: commit.id,
+                                parent: *parent,
+                            }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let parent_position = |parent: &Parent| match parent {
+            Parent::New(idx) => num_base_commits as u32 + *idx as u32,
+            Parent::Base { position, .. } => position.0,
+        };
+
+        let write_generation_data = options.generation_data && base.iter().all(File::has_generation_data);
+        let generations = compute_generations(&commits, &parents);
+        let num_generation_data_overflows = if write_generation_data {
+            commits
+                .iter()
+                .zip(&generations)
+                .filter(|(commit, generation)| {
+                    generation.corrected_commit_date - commit.committer_timestamp > GENERATION_DATA_OFFSET_MAX
+                })
+                .count()
+        } else {
+            0
+        };
+        let num_extra_edges: usize = parents
+            .iter()
+            .filter(|parents| parents.len() > 2)
+            .map(|parents| parents.len() - 1)
+            .sum();
+        let bloom_filters = options.changed_paths.map(|settings| {
+            commits
+                .iter_mut()
+                .map(|commit| match commit.changed_paths.take() {
+                    Some(ChangedPaths::Paths(paths)) => {
+                        bloom::filter_data(paths.iter().map(AsRef::as_ref), &settings)
+                    }
+                    Some(ChangedPaths::Filter(data)) => data,
+                    None => Vec::new(),
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let object_hash = options.object_hash;
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if write_generation_data {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+            if num_generation_data_overflows != 0 {
+                cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, (num_generation_data_overflows * 8) as u64);
+            }
+        }
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if let Some(filters) = &bloom_filters {
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + filters.iter().map(Vec::len).sum::<usize>()) as u64,
+            );
+        }
+        if !base.is_empty() {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base.len() * hash_len) as u64);
+        }
+
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base.len().try_into().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::Other, "a commit-graph can have at most 255 base graphs")
+            })?,
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut count = 0;
+                    let mut ids = commits.iter().peekable();
+                    for first_byte in 0..FAN_LEN {
+                        while ids.next_if(|commit| usize::from(commit.id.first_byte()) == first_byte).is_some() {
+                            count += 1;
+                        }
+                        chunk_write.write_all(&(count as u32).to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for commit in &commits {
+                        chunk_write.write_all(commit.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut extra_edge_index = 0;
+                    for ((commit, parents), generation) in commits.iter().zip(&parents).zip(&generations) {
+                        chunk_write.write_all(commit.tree.as_slice())?;
+                        let parent1 = parents.first().map_or(NO_PARENT, parent_position);
+                        let parent2 = match parents.len() {
+                            0 | 1 => NO_PARENT,
+                            2 => parent_position(&parents[1]),
+                            num_parents => {
+                                let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                                extra_edge_index += num_parents as u32 - 1;
+                                edge
+                            }
+                        };
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let date_high = (commit.committer_timestamp >> 32) as u32 & 0x3;
+                        chunk_write.write_all(&(generation.topo_level << 2 | date_high).to_be_bytes())?;
+                        chunk_write.write_all(&(commit.committer_timestamp as u32).to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_CHUNK_ID => {
+                    let mut num_overflows = 0;
+                    for (commit, generation) in commits.iter().zip(&generations) {
+                        let offset = generation.corrected_commit_date - commit.committer_timestamp;
+                        let offset = if offset > GENERATION_DATA_OFFSET_MAX {
+                            num_overflows += 1;
+                            GENERATION_DATA_OVERFLOW_MASK | (num_overflows - 1)
+                        } else {
+                            offset as u32
+                        };
+                        chunk_write.write_all(&offset.to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                    for (commit, generation) in commits.iter().zip(&generations) {
+                        let offset = generation.corrected_commit_date - commit.committer_timestamp;
+                        if offset > GENERATION_DATA_OFFSET_MAX {
+                            chunk_write.write_all(&offset.to_be_bytes())?;
+                        }
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for parents in parents.iter().filter(|parents| parents.len() > 2) {
+                        let (last, edges) = parents[1..].split_last().expect("at least two");
+                        for parent in edges {
+                            chunk_write.write_all(&parent_position(parent).to_be_bytes())?;
+                        }
+                        chunk_write.write_all(&(LAST_EXTENDED_EDGE_MASK | parent_position(last)).to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let mut end = 0;
+                    for filter in bloom_filters.as_deref().expect("available if planned") {
+                        end += filter.len() as u32;
+                        chunk_write.write_all(&end.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let settings = options.changed_paths.expect("available if planned");
+                    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                        chunk_write.write_all(&value.to_be_bytes())?;
+                    }
+                    for filter in bloom_filters.as_deref().expect("available if planned") {
+                        chunk_write.write_all(filter)?;
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for file in base {
+                        chunk_write.write_all(file.checksum().as_bytes())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum = out.hash.try_finalize()?;
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
+
+/// Compute the topological level and corrected commit date of all `commits` with their `parents`, whose generations
+/// are either computed as well or are known from the base graph.
+fn compute_generations(commits: &[Commit], parents: &[Vec<Parent>]) -> Vec<Generation> {
+    let mut out: Vec<Option<Generation>> = vec![None; commits.len()];
+    let mut stack = Vec::new();
+    for start in 0..commits.len() {
+        if out[start].is_some() {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&idx) = stack.last() {
+            let mut max = None::<Generation>;
+            let mut is_complete = true;
+            for parent in &parents[idx] {
+                let generation = match parent {
+                    Parent::New(parent_idx) => match out[*parent_idx] {
+                        Some(generation) => generation,
+                        None => {
+                            is_complete = false;
+                            stack.push(*parent_idx);
+                            continue;
+                        }
+                    },
+                    Parent::Base { generation, .. } => *generation,
+                };
+                max = Some(match max {
+                    Some(max) => Generation {
+                        topo_level: max.topo_level.max(generation.topo_level),
+                        corrected_commit_date: max.corrected_commit_date.max(generation.corrected_commit_date),
+                    },
+                    None => generation,
+                });
+            }
+            if !is_complete {
+                continue;
+            }
+            stack.pop();
+            let timestamp = commits[idx].committer_timestamp;
+            out[idx] = Some(match max {
+                Some(max) => Generation {
+                    topo_level: max.topo_level.min(GENERATION_NUMBER_MAX - 1) + 1,
+                    corrected_commit_date: timestamp.max(max.corrected_commit_date + 1),
+                },
+                None => Generation {
+                    topo_level: 1,
+                    corrected_commit_date: timestamp,
+                },
+            });
+        }
+    }
+    out.into_iter().map(|generation| generation.expect("all computed")).collect()
+}
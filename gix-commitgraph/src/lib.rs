@@ -24,10 +24,14 @@ use std::path::Path;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    bloom_filter_index_offset: Option<usize>,
+    bloom_filter_data: Option<(bloom::Settings, std::ops::Range<usize>)>,
     commit_data_offset: usize,
     data: memmap2::Mmap,
     extra_edges_list_range: Option<std::ops::Range<usize>>,
     fan: [u32; file::FAN_LEN],
+    generation_data_offset: Option<usize>,
+    generation_data_overflow_range: Option<std::ops::Range<usize>>,
     oid_lookup_offset: usize,
     path: std::path::PathBuf,
     hash_len: usize,
@@ -49,13 +53,18 @@ pub fn at(path: impl AsRef<Path>) -> Result<Graph, init::Error> {
 }
 
 mod access;
+pub mod bloom;
 pub mod file;
 ///
 pub mod init;
 pub mod verify;
+pub mod write;
 
 /// The number of generations that are considered 'infinite' commit history.
 pub const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
+/// The generation to assume for commits that aren't in the commit graph when comparing them to the
+/// [generations of a graph](Graph::generation()), as it is larger than all topological levels and corrected commit dates.
+pub const GENERATION_INFINITY: u64 = u64::MAX;
 /// The largest valid generation number.
 ///
 /// If a commit's real generation number is larger than this, the commit graph will cap the value to
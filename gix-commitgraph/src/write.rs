@@ -0,0 +1,353 @@
+//! Write commit-graph files and chains of split commit-graph files.
+use std::path::{Path, PathBuf};
+
+use bstr::BString;
+
+use crate::{bloom, File, Graph};
+
+/// The error returned by [`File::write_to()`] and [`to_info_dir()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not {action} '{}'", .path.display())]
+    IoPath {
+        source: std::io::Error,
+        action: &'static str,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
+    #[error("The parent {parent} of commit {id} is neither part of the commits to write nor of the base graph")]
+    MissingParent {
+        id: gix_hash::ObjectId,
+        parent: gix_hash::ObjectId,
+    },
+    #[error("Commit-graph files can contain at most {} commits, but {0} commits were to be written", crate::MAX_COMMITS)]
+    TooManyCommits(u64),
+    #[error(transparent)]
+    Init(#[from] crate::init::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::file::commit::Error),
+    #[error(transparent)]
+    LockAcquire(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    LockCommit(#[from] gix_lock::commit::Error<gix_lock::File>),
+}
+
+/// The changes of a commit, used to compute its changed-path Bloom filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangedPaths {
+    /// The paths of all files that changed compared to the first parent, or of all files if the commit has no parent.
+    ///
+    /// Leading directories are added automatically, and it's fine to stop collecting paths once there are more than
+    /// [`bloom::Settings::max_changed_paths`].
+    Paths(Vec<BString>),
+    /// The data of a filter that was computed with the [`Options::changed_paths`] settings before.
+    Filter(Vec<u8>),
+}
+
+/// A commit to write into a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: gix_hash::ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree: gix_hash::ObjectId,
+    /// The ids of all parents in order, each of which must either be written along with this commit or be part of the base graph.
+    pub parents: Vec<gix_hash::ObjectId>,
+    /// The committer time in seconds since the unix epoch.
+    pub committer_timestamp: u64,
+    /// The paths changed by this commit, or `None` if they are unknown in which case the commit won't have a Bloom filter.
+    pub changed_paths: Option<ChangedPaths>,
+}
+
+/// Determine how the layers of a split commit-graph are merged when adding a new layer.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SplitStrategy {
+    /// Merge the topmost layers into the new one as long as they are not much larger than it, as configured by
+    /// [`Split::size_multiple`] and [`Split::max_commits`].
+    #[default]
+    Merge,
+    /// Never merge existing layers, and always add a new one.
+    NoMerge,
+    /// Replace all existing layers with a single new one.
+    Replace,
+}
+
+/// Options for writing split commit-graphs in [`to_info_dir()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Split {
+    /// The way existing layers are merged.
+    pub strategy: SplitStrategy,
+    /// When merging, a layer is merged into the new one if it contains at most this many times the commits of the new layer.
+    pub size_multiple: u32,
+    /// When merging, layers are merged into the new one while it contains more than this amount of commits.
+    pub max_commits: Option<u32>,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Split {
+            strategy: SplitStrategy::default(),
+            size_multiple: 2,
+            max_commits: None,
+        }
+    }
+}
+
+/// Options for use in [`File::write_to()`] and [`to_info_dir()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The kind of hash used by all commits.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, write corrected commit dates, also known as generation numbers v2, unless a base graph doesn't have them.
+    pub generation_data: bool,
+    /// If set, write changed-path Bloom filters with the given settings.
+    pub changed_paths: Option<bloom::Settings>,
+    /// If set, write a new layer of a split commit-graph instead of a single file with all commits in [`to_info_dir()`].
+    pub split: Option<Split>,
+}
+
+/// The result of [`to_info_dir()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the file that was written, or `None` if there was nothing to write.
+    pub path: Option<PathBuf>,
+    /// The amount of commits in the written file.
+    pub num_commits: u32,
+    /// The amount of files the commit-graph consists of now.
+    pub num_files: usize,
+}
+
+/// Write `commits` into the commit-graph of the `info_dir`, typically `.git/objects/info`, with `options`.
+///
+/// Without [`Options::split`], a single `commit-graph` file is written that contains all `commits`, which thus have to
+/// contain the parents of all of them. Existing split commit-graph files are removed.
+///
+/// Commits without [changed paths](Commit::changed_paths) that are part of the existing commit-graph reuse its Bloom filters
+/// if these were computed with the same settings.
+///
+/// Otherwise, a new layer with all `commits` that aren't yet in the commit-graph is added to the chain of split commit-graph
+/// files, merged with existing layers according to the [split options](Split). Commits of merged layers that aren't part of `commits`
+/// are carried over along with their Bloom filters if these were computed with the same settings.
+/// If there are no new commits, nothing is written unless [`SplitStrategy::Replace`] is used.
+/// Unused layers and a single `commit-graph` file are removed.
+pub fn to_info_dir(info_dir: &Path, mut commits: Vec<Commit>, options: &Options) -> Result<Outcome, Error> {
+    let graphs_dir = info_dir.join("commit-graphs");
+    let single_file_path = info_dir.join("commit-graph");
+    let chain_path = graphs_dir.join("commit-graph-chain");
+
+    commits.sort_by_key(|commit| commit.id);
+    commits.dedup_by(|a, b| a.id == b.id);
+
+    let mut files = if single_file_path.is_file() || chain_path.is_file() {
+        Graph::from_info_dir(info_dir)?.files
+    } else {
+        Vec::new()
+    };
+    for commit in commits.iter_mut().filter(|commit| commit.changed_paths.is_none()) {
+        commit.changed_paths = files.iter().find_map(|file| {
+            file.lookup(commit.id)
+                .and_then(|pos| existing_changed_paths(file, pos, options))
+        });
+    }
+
+    let Some(split) = options.split else {
+        drop(files);
+        let num_commits = commits.len() as u32;
+        let mut out = std::io::BufWriter::new(gix_lock::File::acquire_to_update_resource(
+            &single_file_path,
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?);
+        File::write_to(commits, &[], &mut out, options)?;
+        out.into_inner()
+            .map_err(|err| Error::IoPath {
+                source: err.into_error(),
+                action: "write",
+                path: single_file_path.clone(),
+            })?
+            .commit()?;
+        remove_file(&chain_path)?;
+        remove_unused_layers(&graphs_dir, &[])?;
+        return Ok(Outcome {
+            path: Some(single_file_path),
+            num_commits,
+            num_files: 1,
+        });
+    };
+
+    let is_known = |files: &[File], id: &gix_hash::oid| files.iter().any(|file| file.lookup(id).is_some());
+
+    let mut num_retained = files.len();
+    let mut num_commits = commits.iter().filter(|commit| !is_known(&files, &commit.id)).count() as u64;
+    match split.strategy {
+        SplitStrategy::Replace => num_retained = 0,
+        SplitStrategy::Merge | SplitStrategy::NoMerge if num_commits == 0 => {
+            return Ok(Outcome {
+                path: None,
+                num_commits: 0,
+                num_files: files.len(),
+            })
+        }
+        SplitStrategy::NoMerge => {}
+        SplitStrategy::Merge => {
+            while let Some(top) = num_retained.checked_sub(1).map(|idx| &files[idx]) {
+                let top_commits = u64::from(top.num_commits());
+                if top_commits > u64::from(split.size_multiple) * num_commits
+                    && split.max_commits.map_or(true, |max| num_commits <= u64::from(max))
+                {
+                    break;
+                }
+                num_commits += top_commits;
+                num_retained -= 1;
+            }
+        }
+    }
+
+    {
+        let graph = Graph { files };
+        let mut carried_over = Vec::new();
+        for file in &graph.files[num_retained..] {
+            for commit in file.iter_commits() {
+                if commits.binary_search_by(|c| c.id.as_ref().cmp(commit.id())).is_ok() {
+                    continue;
+                }
+                let parents = commit
+                    .iter_parents()
+                    .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                    .collect::<Result<_, _>>()?;
+                carried_over.push(Commit {
+                    id: commit.id().to_owned(),
+                    tree: commit.root_tree_id().to_owned(),
+                    parents,
+                    committer_timestamp: commit.committer_timestamp(),
+                    changed_paths: existing_changed_paths(file, commit.position(), options),
+                });
+            }
+        }
+        commits.extend(carried_over);
+        files = graph.files;
+    }
+    files.truncate(num_retained);
+    commits.retain(|commit| !is_known(&files, &commit.id));
+    let num_commits = commits.len() as u32;
+
+    std::fs::create_dir_all(&graphs_dir).map_err(|source| Error::IoPath {
+        source,
+        action: "create directory",
+        path: graphs_dir.clone(),
+    })?;
+    let mut layer = std::io::BufWriter::new(gix_lock::tempfile::new(
+        &graphs_dir,
+        gix_lock::tempfile::ContainingDirectory::Exists,
+        gix_lock::tempfile::AutoRemove::Tempfile,
+    )?);
+    let checksum = File::write_to(commits, &files, &mut layer, options)?;
+    let layer_path = graphs_dir.join(layer_file_name(&checksum));
+    layer
+        .into_inner()
+        .map_err(|err| Error::IoPath {
+            source: err.into_error(),
+            action: "write",
+            path: layer_path.clone(),
+        })?
+        .persist(&layer_path)
+        .map_err(|err| Error::IoPath {
+            source: err.error,
+            action: "move commit-graph file into place at",
+            path: layer_path.clone(),
+        })?;
+
+    let mut chain: Vec<_> = files.iter().map(|file| file.checksum().to_owned()).collect();
+    chain.push(checksum);
+    let base_to_move = files
+        .first()
+        .filter(|file| file.path() == single_file_path)
+        .map(|file| graphs_dir.join(layer_file_name(file.checksum())));
+    drop(files);
+    if let Some(new_path) = base_to_move {
+        std::fs::rename(&single_file_path, &new_path).map_err(|source| Error::IoPath {
+            source,
+            action: "move base commit-graph file to",
+            path: new_path,
+        })?;
+    }
+
+    let mut lock = gix_lock::File::acquire_to_update_resource(&chain_path, gix_lock::acquire::Fail::Immediately, None)?;
+    for id in &chain {
+        use std::io::Write;
+        writeln!(lock, "{id}")?;
+    }
+    lock.commit()?;
+    remove_file(&single_file_path)?;
+    remove_unused_layers(&graphs_dir, &chain)?;
+
+    Ok(Outcome {
+        path: Some(layer_path),
+        num_commits,
+        num_files: chain.len(),
+    })
+}
+
+/// Return the Bloom filter of the commit at `pos` in `file` if it was computed with the settings of `options`.
+fn existing_changed_paths(file: &File, pos: crate::file::Position, options: &Options) -> Option<ChangedPaths> {
+    let wanted = options.changed_paths?;
+    let (settings, data) = file.bloom_filter_at(pos)?;
+    ((wanted.hash_version, wanted.num_hashes, wanted.bits_per_entry)
+        == (settings.hash_version, settings.num_hashes, settings.bits_per_entry)
+        && !data.is_empty())
+    .then(|| ChangedPaths::Filter(data.to_owned()))
+}
+
+fn layer_file_name(checksum: &gix_hash::oid) -> String {
+    format!("graph-{checksum}.graph")
+}
+
+/// Remove all split commit-graph files in `graphs_dir` that are not mentioned in `chain`.
+fn remove_unused_layers(graphs_dir: &Path, chain: &[gix_hash::ObjectId]) -> Result<(), Error> {
+    let entries = match std::fs::read_dir(graphs_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => {
+            return Err(Error::IoPath {
+                source,
+                action: "list",
+                path: graphs_dir.to_owned(),
+            })
+        }
+    };
+    let used: Vec<_> = chain.iter().map(|id| layer_file_name(id)).collect();
+    for entry in entries {
+        let path = entry
+            .map_err(|source| Error::IoPath {
+                source,
+                action: "list",
+                path: graphs_dir.to_owned(),
+            })?
+            .path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let is_layer = name.starts_with("graph-") && path.extension().map_or(false, |ext| ext == "graph");
+        if is_layer && !used.iter().any(|used| used == name) {
+            remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the file at `path` if it exists.
+fn remove_file(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(Error::IoPath {
+            source,
+            action: "delete",
+            path: path.to_owned(),
+        }),
+    }
+}
@@ -24,6 +24,12 @@ fn single_commit_huge_dates_generation_v2_also_do_not_allow_huge_dates() {
         "this is the value we would want to see, but it's not possible in V2 either, as that is just about generations"
     );
     assert_eq!(actual.generation(), 1, "generations are fine though");
+    assert!(cg.has_generation_data());
+    assert_eq!(
+        cg.generation(&actual),
+        actual.corrected_commit_date().expect("present in v2"),
+        "corrected commit dates are preferred"
+    );
 }
 
 #[test]
@@ -37,6 +43,12 @@ fn single_commit_huge_dates_overflow_v1() {
         "this is the value we would want to see, but it's not possible in V1"
     );
     assert_eq!(actual.generation(), 1, "generations are fine though");
+    assert!(!cg.has_generation_data());
+    assert_eq!(
+        cg.generation(&actual),
+        1,
+        "topological levels are used without generation data"
+    );
 }
 
 #[test]
@@ -56,17 +68,26 @@ fn single_commit_future_64bit_dates_work() {
 fn generation_numbers_overflow_is_handled_in_chained_graph() {
     let names = ["extra", "old-2", "future-2", "old-1", "future-1"];
     let (cg, mut refs) = graph_and_expected("generation_number_overflow.sh", &names);
+    let mut generations = Vec::new();
     for (r, expected) in names
         .iter()
         .map(|n| refs.remove(n.to_owned()).expect("present"))
         .zip((1..=5).rev())
     {
+        let commit = cg.commit_by_id(r.id).expect("present");
         assert_eq!(
-            cg.commit_by_id(r.id).expect("present").generation(),
+            commit.generation(),
             expected,
             "actually, this test seems to have valid generation numbers from the get-go. How to repro the actual issue?"
         );
+        generations.push(cg.generation(&commit));
     }
+
+    assert!(cg.has_generation_data(), "all layers have corrected commit dates");
+    assert!(
+        generations.windows(2).all(|w| w[0] > w[1]),
+        "corrected commit dates are strictly larger than those of parents, even with overflowing offsets: {generations:?}"
+    );
 }
 
 #[test]
@@ -111,3 +132,54 @@ fn two_parents() {
     assert_eq!(cg.commit_at(refs["parent2"].pos()).generation(), 1);
     assert_eq!(cg.commit_at(refs["child"].pos()).generation(), 2);
 }
+
+#[test]
+fn changed_paths_filters() -> gix_testtools::Result {
+    let repo_dir = gix_testtools::scripted_fixture_read_only("write.sh")?;
+    let cg = gix_commitgraph::at(repo_dir.join(".git/objects/info"))?;
+    assert_eq!(cg.bloom_filter_settings(), Some(Default::default()));
+
+    let rev_parse = |spec: &str| {
+        let out = std::process::Command::new("git")
+            .args(["rev-parse", spec])
+            .current_dir(&repo_dir)
+            .output()
+            .expect("git can be executed");
+        gix_hash::ObjectId::from_hex(&out.stdout[..40]).expect("valid hex")
+    };
+    let filter = |spec: &str| {
+        cg.commit_by_id(rev_parse(spec))
+            .expect("present")
+            .changed_paths_filter()
+            .expect("git wrote filters for all commits")
+    };
+
+    let c2 = filter("layer1~");
+    for path in ["dir/b", "dir"] {
+        assert!(
+            c2.contains_path(path.into()),
+            "{path} changed, along with its directory"
+        );
+    }
+    for path in ["a", "dir/sub/c", "dir/sub", "ü.txt"] {
+        assert!(!c2.contains_path(path.into()), "{path} didn't change");
+    }
+
+    let root = filter("layer1~2");
+    for path in ["a", "dir", "dir/b", "dir/sub", "dir/sub/c", "ü.txt"] {
+        assert!(root.contains_path(path.into()), "{path} was added");
+    }
+    assert!(!root.contains_path("b".into()));
+
+    let empty = filter("layer1");
+    assert_eq!(empty.data(), &[0], "empty commits have an empty filter");
+    assert!(!empty.contains_path("a".into()));
+
+    let too_large = filter("main~");
+    assert_eq!(too_large.data(), gix_commitgraph::bloom::TOO_LARGE_FILTER);
+    assert!(
+        too_large.contains_path("anything".into()),
+        "too many changed paths can't be ruled out"
+    );
+    Ok(())
+}
@@ -0,0 +1,45 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/sub
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+echo u > "ü.txt"
+git add .
+git commit -q -m c1
+
+echo b2 > dir/b
+git commit -q -am c2
+git commit -q --allow-empty -m empty
+git tag layer1
+
+for branch in o1 o2 o3; do
+  git checkout -q -b $branch layer1
+  mkdir -p $branch
+  echo $branch > $branch/file
+  git add $branch
+  git commit -q -m $branch
+done
+git checkout -q main
+git merge -q -m octopus --no-ff o1 o2 o3 >/dev/null
+git tag layer2
+
+mkdir many
+for i in $(seq 600); do
+  echo $i > many/$i
+done
+git add many
+git commit -q -m "too many changes"
+
+git checkout -q --orphan other
+git rm -rqf .
+echo other > other
+git add other
+git commit -q -m "other root"
+git checkout -q main
+git merge -q -m "merge other" --allow-unrelated-histories other >/dev/null
+
+git commit-graph write --no-progress --reachable --changed-paths
@@ -0,0 +1,281 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use bstr::{BString, ByteSlice};
+use gix_commitgraph::{
+    bloom,
+    write::{to_info_dir, ChangedPaths, Commit, Options, Split, SplitStrategy},
+    File, Graph,
+};
+use gix_testtools::{scripted_fixture_read_only, scripted_fixture_writable};
+
+fn git(repo_dir: &Path, args: &[&str]) -> Vec<u8> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(args)
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("git can be executed");
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        output.stderr.as_bstr()
+    );
+    output.stdout
+}
+
+/// Return all commits reachable from `rev` along with their changed paths as computed by git.
+fn commits(repo_dir: &Path, rev: &str) -> Vec<Commit> {
+    git(repo_dir, &["log", "--format=%H %T %ct %P", rev])
+        .lines()
+        .map(|line| {
+            let mut tokens = line.to_str().expect("ascii").split(' ');
+            let mut id = || gix_hash::ObjectId::from_hex(tokens.next().expect("present").as_bytes()).expect("valid");
+            let (id, tree) = (id(), id());
+            let committer_timestamp = tokens.next().expect("present").parse().expect("number");
+            let parents: Vec<_> = tokens
+                .filter(|id| !id.is_empty())
+                .map(|id| gix_hash::ObjectId::from_hex(id.as_bytes()).expect("valid"))
+                .collect();
+            let id_hex = id.to_string();
+            let diff = match parents.first() {
+                Some(parent) => git(
+                    repo_dir,
+                    &["diff-tree", "-r", "-z", "--name-only", &parent.to_string(), &id_hex],
+                ),
+                None => git(repo_dir, &["diff-tree", "-r", "-z", "--name-only", "--root", &id_hex]),
+            };
+            let mut paths: Vec<BString> = diff.split_str("\0").map(Into::into).collect();
+            if id_hex.as_bytes() == paths[0] {
+                paths.remove(0);
+            }
+            paths.retain(|path| !path.is_empty());
+            Commit {
+                id,
+                tree,
+                parents,
+                committer_timestamp,
+                changed_paths: Some(ChangedPaths::Paths(paths)),
+            }
+        })
+        .collect()
+}
+
+fn options(split: Option<Split>) -> Options {
+    Options {
+        object_hash: gix_hash::Kind::Sha1,
+        generation_data: true,
+        changed_paths: Some(bloom::Settings::default()),
+        split,
+    }
+}
+
+fn split(strategy: SplitStrategy) -> Options {
+    options(Some(Split {
+        strategy,
+        ..Default::default()
+    }))
+}
+
+fn layer_paths(info_dir: &Path) -> Vec<PathBuf> {
+    let graphs_dir = info_dir.join("commit-graphs");
+    std::fs::read_to_string(graphs_dir.join("commit-graph-chain"))
+        .expect("chain present")
+        .lines()
+        .map(|checksum| graphs_dir.join(format!("graph-{checksum}.graph")))
+        .collect()
+}
+
+#[test]
+fn single_file_with_bloom_filters_is_identical_to_the_one_written_by_git() -> gix_testtools::Result {
+    let repo_dir = scripted_fixture_read_only("write.sh")?;
+    let expected = std::fs::read(repo_dir.join(".git/objects/info/commit-graph"))?;
+
+    let mut actual = Vec::new();
+    let checksum = File::write_to(commits(&repo_dir, "main"), &[], &mut actual, &options(None))?;
+    assert_eq!(actual.as_bstr(), expected.as_bstr());
+    assert_eq!(checksum.as_slice(), &expected[expected.len() - 20..]);
+    Ok(())
+}
+
+#[test]
+fn rewritten_files_and_layers_are_identical_to_the_ones_written_by_git() -> gix_testtools::Result {
+    for (script, is_chain) in [
+        ("octopus_merges.sh", false),
+        ("two_parents.sh", false),
+        ("split_chain.sh", true),
+        ("generation_number_overflow.sh", true),
+    ] {
+        let info_dir = scripted_fixture_read_only(script)?.join(".git/objects/info");
+        let graph = Graph::from_info_dir(&info_dir)?;
+        let files = if is_chain {
+            layer_paths(&info_dir).iter().map(File::at).collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![File::at(info_dir.join("commit-graph"))?]
+        };
+
+        for (layer, file) in files.iter().enumerate() {
+            let commits = file
+                .iter_commits()
+                .map(|commit| {
+                    Ok(Commit {
+                        id: commit.id().to_owned(),
+                        tree: commit.root_tree_id().to_owned(),
+                        parents: commit
+                            .iter_parents()
+                            .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                            .collect::<Result<_, _>>()?,
+                        committer_timestamp: commit.committer_timestamp(),
+                        changed_paths: None,
+                    })
+                })
+                .collect::<Result<Vec<_>, gix_commitgraph::file::commit::Error>>()?;
+            let mut actual = Vec::new();
+            File::write_to(
+                commits,
+                &files[..layer],
+                &mut actual,
+                &Options {
+                    generation_data: file.has_generation_data(),
+                    changed_paths: None,
+                    ..options(None)
+                },
+            )?;
+            assert_eq!(
+                actual.as_bstr(),
+                std::fs::read(file.path())?.as_bstr(),
+                "{script}: layer {layer}"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn split_chains_are_merged_and_accepted_by_git() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("write.sh")?;
+    let repo_dir = tmp.path();
+    let info_dir = repo_dir.join(".git/objects/info");
+    let single_file_by_git = std::fs::read(info_dir.join("commit-graph"))?;
+
+    let out = to_info_dir(&info_dir, commits(repo_dir, "layer1"), &options(None))?;
+    assert_eq!((out.num_commits, out.num_files), (3, 1));
+
+    let out = to_info_dir(&info_dir, commits(repo_dir, "layer2"), &split(SplitStrategy::NoMerge))?;
+    assert_eq!(
+        (out.num_commits, out.num_files),
+        (4, 2),
+        "only new commits are written"
+    );
+    assert!(
+        !info_dir.join("commit-graph").exists(),
+        "the single file became the base of the chain"
+    );
+    git(repo_dir, &["commit-graph", "verify"]);
+
+    let out = to_info_dir(
+        &info_dir,
+        commits(repo_dir, "main"),
+        &options(Some(Split {
+            size_multiple: 1,
+            ..Default::default()
+        })),
+    )?;
+    assert_eq!(
+        (out.num_commits, out.num_files),
+        (3, 3),
+        "the top layer has more commits than the new one, so it isn't merged"
+    );
+    assert_eq!(layer_paths(&info_dir).last(), out.path.as_ref());
+    git(repo_dir, &["commit-graph", "verify"]);
+    let graph = Graph::from_info_dir(&info_dir)?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    assert_eq!(graph.num_commits(), 10);
+    assert!(graph.iter_commits().all(|commit| commit.corrected_commit_date().is_some()));
+
+    let out = to_info_dir(&info_dir, commits(repo_dir, "main"), &split(SplitStrategy::Merge))?;
+    assert_eq!(out.path, None, "there is nothing new to write");
+    assert_eq!(out.num_files, 3);
+
+    let out = to_info_dir(&info_dir, commits(repo_dir, "layer1"), &split(SplitStrategy::Merge))?;
+    assert_eq!(out.path, None, "all commits are known already");
+
+    let out = to_info_dir(&info_dir, Vec::new(), &split(SplitStrategy::Replace))?;
+    assert_eq!(
+        (out.num_commits, out.num_files),
+        (10, 1),
+        "all commits are carried over from the replaced layers"
+    );
+    assert_eq!(
+        std::fs::read(out.path.expect("written"))?.as_bstr(),
+        single_file_by_git.as_bstr(),
+        "a single layer is like a single file, and Bloom filters were carried over"
+    );
+    assert_eq!(
+        std::fs::read_dir(info_dir.join("commit-graphs"))?.count(),
+        2,
+        "unused layers are removed, leaving only the chain and its layer"
+    );
+    git(repo_dir, &["commit-graph", "verify"]);
+
+    let out = to_info_dir(&info_dir, commits(repo_dir, "main"), &options(None))?;
+    assert_eq!((out.num_commits, out.num_files), (10, 1));
+    assert_eq!(out.path, Some(info_dir.join("commit-graph")));
+    assert_eq!(
+        std::fs::read_dir(info_dir.join("commit-graphs"))?.count(),
+        0,
+        "the chain was removed"
+    );
+    assert_eq!(std::fs::read(info_dir.join("commit-graph"))?, single_file_by_git);
+
+    let mut commits = commits(repo_dir, "main");
+    for commit in &mut commits {
+        commit.changed_paths = None;
+    }
+    to_info_dir(&info_dir, commits, &options(None))?;
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        single_file_by_git,
+        "Bloom filters of commits without changed paths are reused"
+    );
+    Ok(())
+}
+
+#[test]
+fn layers_are_merged_according_to_their_size() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("write.sh")?;
+    let repo_dir = tmp.path();
+    let info_dir = repo_dir.join(".git/objects/info");
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+
+    to_info_dir(&info_dir, commits(repo_dir, "layer1"), &split(SplitStrategy::Merge))?;
+    to_info_dir(&info_dir, commits(repo_dir, "layer2"), &split(SplitStrategy::NoMerge))?;
+    let out = to_info_dir(&info_dir, commits(repo_dir, "main"), &split(SplitStrategy::Merge))?;
+    assert_eq!(
+        (out.num_commits, out.num_files),
+        (10, 1),
+        "the top layer with 4 commits is merged into the new one with 3, and the resulting 7 commits absorb the 3 of the base"
+    );
+    git(repo_dir, &["commit-graph", "verify"]);
+
+    std::fs::remove_dir_all(info_dir.join("commit-graphs"))?;
+    to_info_dir(&info_dir, commits(repo_dir, "layer2"), &split(SplitStrategy::Merge))?;
+    let out = to_info_dir(
+        &info_dir,
+        commits(repo_dir, "main"),
+        &options(Some(Split {
+            size_multiple: 1,
+            max_commits: Some(2),
+            ..Default::default()
+        })),
+    )?;
+    assert_eq!(
+        (out.num_commits, out.num_files),
+        (10, 1),
+        "the new layer has more than `max_commits` commits, so it is merged"
+    );
+    Ok(())
+}
@@ -40,6 +40,7 @@ mod util {
                     buffer.extend_from_slice(data);
                     Ok(Some(gix_object::Data {
                         kind: gix_object::Kind::Blob,
+                        object_hash: id.kind(),
                         data: buffer.as_slice(),
                     }))
                 }
@@ -51,7 +52,8 @@ mod util {
     impl ObjectDb {
         /// Insert `data` and return its hash. That can be used to find it again.
         pub fn insert(&mut self, data: &str) -> gix_hash::ObjectId {
-            let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, data.as_bytes());
+            let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, data.as_bytes())
+                .expect("no collision");
             self.data_by_id.insert(id, data.into());
             id
         }
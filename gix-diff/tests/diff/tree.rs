@@ -822,5 +822,49 @@ mod changes {
             );
             Ok(())
         }
+
+        #[test]
+        fn mode_only_change() -> crate::Result {
+            let oid = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+            let tree_with_mode = |mode: EntryKind| -> crate::Result<Vec<u8>> {
+                let mut buf = Vec::new();
+                gix_object::WriteTo::write_to(
+                    &gix_object::Tree {
+                        entries: vec![gix_object::tree::Entry {
+                            mode: mode.into(),
+                            filename: "f".into(),
+                            oid,
+                        }],
+                    },
+                    &mut buf,
+                )?;
+                Ok(buf)
+            };
+            let (lhs, rhs) = (
+                tree_with_mode(EntryKind::Blob)?,
+                tree_with_mode(EntryKind::BlobExecutable)?,
+            );
+
+            let mut recorder = gix_diff::tree::Recorder::default();
+            gix_diff::tree(
+                TreeRefIter::from_bytes(&lhs, gix_hash::Kind::Sha1),
+                TreeRefIter::from_bytes(&rhs, gix_hash::Kind::Sha1),
+                gix_diff::tree::State::default(),
+                gix_object::find::Never,
+                &mut recorder,
+            )?;
+            assert_eq!(
+                recorder.records,
+                vec![Modification {
+                    previous_entry_mode: EntryKind::Blob.into(),
+                    previous_oid: oid,
+                    entry_mode: EntryKind::BlobExecutable.into(),
+                    oid,
+                    path: "f".into()
+                }],
+                ":100644 100755 e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 M	f"
+            );
+            Ok(())
+        }
     }
 }
@@ -1,54 +1,133 @@
 //! Hash functions and hash utilities
 //!
-//! With the `fast-sha1` feature, the `Sha1` hash type will use a more elaborate implementation utilizing hardware support
-//! in case it is available. Otherwise the `rustsha1` feature should be set. `fast-sha1` will take precedence.
-//! Otherwise, a minimal yet performant implementation is used instead for a decent trade-off between compile times and run-time performance.
-#[cfg(all(feature = "rustsha1", not(feature = "fast-sha1")))]
+//! With the `rustsha1` feature (or `fast-sha1`, which implies it), the `Sha1` hash type uses an implementation with
+//! collision detection, which refuses to produce digests for input that is part of a known collision attack, just like
+//! `git` does when built with `sha1dc`.
+//!
+//! Either of these features also provides the `Sha256` hash type, so the [`Hasher`] can produce digests of any [`gix_hash::Kind`].
+#[cfg(feature = "rustsha1")]
 mod _impl {
-    use super::Digest;
+    use super::{Digest, Error};
 
-    /// A implementation of the Sha1 hash, which can be used once.
-    #[derive(Default, Clone)]
-    pub struct Sha1(sha1_smol::Sha1);
+    /// A implementation of the Sha1 hash with collision detection, which can be used once.
+    #[derive(Clone)]
+    pub struct Sha1(sha1_checked::Sha1);
+
+    impl Default for Sha1 {
+        fn default() -> Self {
+            Sha1(
+                sha1_checked::Builder::default()
+                    // Like `git`, we rather fail than producing a different hash for colliding input.
+                    .safe_hash(false)
+                    .build(),
+            )
+        }
+    }
 
     impl Sha1 {
         /// Digest the given `bytes`.
         pub fn update(&mut self, bytes: &[u8]) {
+            use sha1_checked::Digest;
             self.0.update(bytes);
         }
-        /// Finalize the hash and produce a digest.
-        pub fn digest(self) -> Digest {
-            self.0.digest().bytes()
+        /// Finalize the hash and produce a digest, or fail if the hashed bytes are part of a known collision attack.
+        pub fn try_digest(self) -> Result<Digest, Error> {
+            match self.0.try_finalize() {
+                sha1_checked::CollisionResult::Ok(digest) => Ok(digest.into()),
+                sha1_checked::CollisionResult::Mitigated(digest) | sha1_checked::CollisionResult::Collision(digest) => {
+                    Err(Error::CollisionAttack {
+                        digest: gix_hash::ObjectId::Sha1(digest.into()),
+                    })
+                }
+            }
         }
     }
 }
 
-/// A hash-digest produced by a [`Hasher`] hash implementation.
-#[cfg(any(feature = "fast-sha1", feature = "rustsha1"))]
+/// A hash-digest produced by the [`Sha1`] hash implementation.
+#[cfg(feature = "rustsha1")]
 pub type Digest = [u8; 20];
 
-#[cfg(feature = "fast-sha1")]
-mod _impl {
-    use sha1::Digest;
+#[cfg(feature = "rustsha1")]
+pub use _impl::Sha1;
+
+#[cfg(feature = "rustsha1")]
+mod _sha256 {
+    use sha2::Digest;
 
-    /// A implementation of the Sha1 hash, which can be used once.
+    /// A implementation of the Sha256 hash, which can be used once.
     #[derive(Default, Clone)]
-    pub struct Sha1(sha1::Sha1);
+    pub struct Sha256(sha2::Sha256);
 
-    impl Sha1 {
+    impl Sha256 {
         /// Digest the given `bytes`.
         pub fn update(&mut self, bytes: &[u8]) {
             self.0.update(bytes);
         }
         /// Finalize the hash and produce a digest.
-        pub fn digest(self) -> super::Digest {
+        pub fn digest(self) -> [u8; 32] {
             self.0.finalize().into()
         }
     }
 }
+#[cfg(feature = "rustsha1")]
+pub use _sha256::Sha256;
+
+/// The error returned when finalizing a [`Hasher`] or [`Sha1`].
+#[cfg(feature = "rustsha1")]
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Detected SHA-1 collision attack with digest {digest}")]
+    CollisionAttack { digest: gix_hash::ObjectId },
+}
+
+#[cfg(feature = "rustsha1")]
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
 
-#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
-pub use _impl::Sha1 as Hasher;
+/// A hash implementation for any of the supported [kinds of hashes](gix_hash::Kind), which can be used once.
+///
+/// Use [`hasher()`] to obtain one for a given hash kind.
+#[cfg(feature = "rustsha1")]
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)] // Sha1 is by far the most common, and it's not worth an allocation for each hash.
+pub enum Hasher {
+    /// The Sha1 hash.
+    Sha1(Sha1),
+    /// The Sha256 hash.
+    Sha256(Sha256),
+}
+
+#[cfg(feature = "rustsha1")]
+impl Hasher {
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(bytes),
+            Hasher::Sha256(h) => h.update(bytes),
+        }
+    }
+
+    /// Finalize the hash and produce the id of the hashed bytes, or fail if they are part of a known collision attack.
+    pub fn try_finalize(self) -> Result<gix_hash::ObjectId, Error> {
+        Ok(match self {
+            Hasher::Sha1(h) => gix_hash::ObjectId::Sha1(h.try_digest()?),
+            Hasher::Sha256(h) => gix_hash::ObjectId::Sha256(h.digest()),
+        })
+    }
+
+    /// Return the kind of hash this instance produces.
+    pub fn kind(&self) -> gix_hash::Kind {
+        match self {
+            Hasher::Sha1(_) => gix_hash::Kind::Sha1,
+            Hasher::Sha256(_) => gix_hash::Kind::Sha256,
+        }
+    }
+}
 
 /// Compute a CRC32 hash from the given `bytes`, returning the CRC32 hash.
 ///
@@ -73,10 +152,11 @@ pub fn crc32(bytes: &[u8]) -> u32 {
 }
 
 /// Produce a hasher suitable for the given kind of hash.
-#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[cfg(feature = "rustsha1")]
 pub fn hasher(kind: gix_hash::Kind) -> Hasher {
     match kind {
-        gix_hash::Kind::Sha1 => Hasher::default(),
+        gix_hash::Kind::Sha1 => Hasher::Sha1(Sha1::default()),
+        gix_hash::Kind::Sha256 => Hasher::Sha256(Sha256::default()),
     }
 }
 
@@ -91,7 +171,9 @@ pub fn hasher(kind: gix_hash::Kind) -> Hasher {
 /// * Only available with the `gix-object` feature enabled due to usage of the [`gix_hash::Kind`] enum and the
 ///   [`gix_hash::ObjectId`] return value.
 /// * [Interrupts][crate::interrupt] are supported.
-#[cfg(all(feature = "progress", any(feature = "rustsha1", feature = "fast-sha1")))]
+/// * A detected collision attack is returned as IO error of kind [`InvalidData`](std::io::ErrorKind::InvalidData),
+///   with [`Error`] as its source.
+#[cfg(all(feature = "progress", feature = "rustsha1"))]
 pub fn bytes_of_file(
     path: &std::path::Path,
     num_bytes_from_start: u64,
@@ -109,7 +191,7 @@ pub fn bytes_of_file(
 }
 
 /// Similar to [`bytes_of_file`], but operates on a stream of bytes.
-#[cfg(all(feature = "progress", any(feature = "rustsha1", feature = "fast-sha1")))]
+#[cfg(all(feature = "progress", feature = "rustsha1"))]
 pub fn bytes(
     read: &mut dyn std::io::Read,
     num_bytes_from_start: u64,
@@ -121,7 +203,7 @@ pub fn bytes(
 }
 
 /// Similar to [`bytes()`], but takes a `hasher` instead of a hash kind.
-#[cfg(all(feature = "progress", any(feature = "rustsha1", feature = "fast-sha1")))]
+#[cfg(all(feature = "progress", feature = "rustsha1"))]
 pub fn bytes_with_hasher(
     read: &mut dyn std::io::Read,
     num_bytes_from_start: u64,
@@ -151,14 +233,14 @@ pub fn bytes_with_hasher(
         }
     }
 
-    let id = gix_hash::ObjectId::from(hasher.digest());
+    let id = hasher.try_finalize()?;
     progress.show_throughput(start);
     Ok(id)
 }
 
-#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[cfg(feature = "rustsha1")]
 mod write {
-    use crate::hash::Hasher;
+    use crate::hash::{hasher, Hasher};
 
     /// A utility to automatically generate a hash while writing into an inner writer.
     pub struct Write<T> {
@@ -189,14 +271,12 @@ mod write {
     {
         /// Create a new hash writer which hashes all bytes written to `inner` with a hash of `kind`.
         pub fn new(inner: T, object_hash: gix_hash::Kind) -> Self {
-            match object_hash {
-                gix_hash::Kind::Sha1 => Write {
-                    inner,
-                    hash: Hasher::default(),
-                },
+            Write {
+                inner,
+                hash: hasher(object_hash),
             }
         }
     }
 }
-#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[cfg(feature = "rustsha1")]
 pub use write::Write;
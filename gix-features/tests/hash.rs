@@ -1,16 +1,48 @@
-use gix_features::hash::Hasher;
+use gix_features::hash::Sha1;
 
-#[cfg(not(feature = "fast-sha1"))]
 #[test]
 fn size_of_sha1() {
-    assert_eq!(std::mem::size_of::<Hasher>(), 96);
+    assert_eq!(std::mem::size_of::<Sha1>(), 824);
 }
 
-#[cfg(feature = "fast-sha1")]
 #[test]
-fn size_of_sha1() {
+fn sha256_digest() {
+    let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha256);
+    hasher.update(b"blob 0\0");
+    assert_eq!(
+        hasher.try_finalize().expect("no collision"),
+        gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha256),
+        "the known id of the empty blob is produced"
+    );
+}
+
+#[test]
+fn sha1_collision_attack_is_detected() {
+    // The first 320 bytes of the two 'shattered' PDFs, which are enough to produce the same SHA1.
+    for input in [
+        &include_bytes!("fixtures/shattered-1.prefix.bin")[..],
+        &include_bytes!("fixtures/shattered-2.prefix.bin")[..],
+    ] {
+        let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha1);
+        hasher.update(input);
+        let err = hasher.try_finalize().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix_features::hash::Error::CollisionAttack { digest }
+                    if digest == gix_hash::ObjectId::from_hex(b"f92d74e3874587aaf443d1db961d4e26dde13e9c").unwrap()
+            ),
+            "the colliding hash is reported"
+        );
+    }
+}
+
+#[test]
+fn sha1_digest_without_collision() {
+    let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha1);
+    hasher.update(b"blob 0\0");
     assert_eq!(
-        std::mem::size_of::<Hasher>(),
-        if cfg!(target_arch = "x86") { 96 } else { 104 }
+        hasher.try_finalize().expect("no collision"),
+        gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1)
     );
 }
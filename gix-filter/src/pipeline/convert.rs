@@ -52,6 +52,8 @@ pub mod to_worktree {
         #[error(transparent)]
         Worktree(#[from] crate::worktree::encode_to_worktree::Error),
         #[error(transparent)]
+        Ident(#[from] crate::ident::apply::Error),
+        #[error(transparent)]
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
         Configuration(#[from] super::configuration::Error),
@@ -228,6 +230,35 @@ impl Pipeline {
             None => ToWorktreeOutcome::Buffer(bufs.src),
         })
     }
+
+    /// Return `true` if blobs located at `rela_path` (in the index) may be changed when [converting them to the worktree](Self::convert_to_worktree()),
+    /// asking for `attributes` with `rela_path` as first argument.
+    ///
+    /// If `false` is returned, blobs at `rela_path` can be written to the worktree as they are, which allows them to be streamed
+    /// without holding them in memory.
+    pub fn may_convert_to_worktree(
+        &mut self,
+        rela_path: &BStr,
+        attributes: &mut dyn FnMut(&BStr, &mut gix_attributes::search::Outcome),
+    ) -> Result<bool, configuration::Error> {
+        let Configuration {
+            driver,
+            digest,
+            _attr_digest: _,
+            encoding,
+            apply_ident_filter,
+        } = Configuration::at_path(
+            rela_path,
+            &self.options.drivers,
+            &mut self.attrs,
+            attributes,
+            self.options.eol_config,
+        )?;
+        Ok(driver.is_some()
+            || encoding.is_some()
+            || apply_ident_filter
+            || digest.to_eol(self.options.eol_config) == Some(eol::Mode::CrLf))
+    }
 }
 
 /// The result of a conversion with zero or more filters to be stored in git.
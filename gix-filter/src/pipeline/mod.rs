@@ -29,6 +29,12 @@ pub struct Options {
     pub encodings_with_roundtrip_check: Vec<&'static encoding_rs::Encoding>,
     /// The object hash to use when applying the `ident` filter.
     pub object_hash: gix_hash::Kind,
+    /// Blobs larger than this amount of bytes should be streamed into the worktree if no filter
+    /// [may change them](Pipeline::may_convert_to_worktree()), to avoid holding them in memory.
+    /// If `0`, blobs are never streamed.
+    ///
+    /// This corresponds to `core.bigFileThreshold`.
+    pub large_file_threshold_bytes: u64,
 }
 
 /// Context that typically doesn't change throughout the lifetime of a pipeline, for use with `process` filters.
@@ -98,6 +104,11 @@ impl Pipeline {
         &mut self.context
     }
 
+    /// Return the options used to configure this instance.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
     /// Return a set of options for configuration after instantiation.
     pub fn options_mut(&mut self) -> &mut Options {
         &mut self.options
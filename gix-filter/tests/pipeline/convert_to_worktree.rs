@@ -98,3 +98,46 @@ fn no_filter() -> gix_testtools::Result {
     assert_eq!(actual.as_ptr(), input.as_ptr(), "…which means it's exactly the same");
     Ok(())
 }
+
+#[test]
+fn may_convert() -> gix_testtools::Result {
+    for (name, drivers, expected) in [
+        ("all-filters", Vec::new(), true),
+        ("driver-only", vec![driver_with_process()], true),
+        ("driver-only", Vec::new(), false),
+        ("no-filters", Vec::new(), false),
+    ] {
+        let (mut cache, mut pipe) = pipeline(name, || {
+            (drivers, Vec::new(), CrlfRoundTripCheck::Skip, Default::default())
+        })?;
+        let actual = pipe.may_convert_to_worktree("any.txt".into(), &mut |path, attrs| {
+            cache
+                .at_entry(path, None, &gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        })?;
+        assert_eq!(actual, expected, "{name}");
+    }
+
+    let (mut cache, mut pipe) = pipeline("no-filters", || {
+        (
+            Vec::new(),
+            Vec::new(),
+            CrlfRoundTripCheck::Skip,
+            gix_filter::eol::Configuration {
+                auto_crlf: gix_filter::eol::AutoCrlf::Enabled,
+                ..Default::default()
+            },
+        )
+    })?;
+    assert!(
+        pipe.may_convert_to_worktree("any.txt".into(), &mut |path, attrs| {
+            cache
+                .at_entry(path, None, &gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        })?,
+        "line endings may be converted according to the configuration"
+    );
+    Ok(())
+}
@@ -65,6 +65,7 @@ fn pipeline(
             encodings_with_roundtrip_check,
             crlf_roundtrip_check,
             object_hash: gix_hash::Kind::Sha1,
+            large_file_threshold_bytes: 0,
         },
     );
     Ok((cache, pipe))
@@ -3,10 +3,102 @@
 
 use std::collections::VecDeque;
 
+use bstr::BString;
 use gix_hash::ObjectId;
 use gix_hashtable::HashSet;
 use gix_object::{tree::EntryKind, Exists, FindExt, Kind};
 
+///
+pub mod msg;
+///
+pub mod object;
+///
+pub mod objects;
+///
+pub mod refs;
+
+/// What a [`Finding`] is about.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subject {
+    /// An object in the object database.
+    Object {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+    },
+    /// A reference, identified by its full name or the path of the file it is stored in.
+    Reference(BString),
+    /// The reflog of the reference with the given full name.
+    Reflog(BString),
+}
+
+/// A problem or an observation made while checking the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Finding {
+    /// `subject` has a problem identified by `id`, whose `severity` is configurable.
+    Message {
+        /// What the problem was found in.
+        subject: Subject,
+        /// The kind of problem.
+        id: msg::Id,
+        /// How severe the problem is.
+        severity: msg::Severity,
+        /// A description of the problem.
+        message: &'static str,
+    },
+    /// The object `id` is referred to by `referenced_by`, but it doesn't exist.
+    Missing {
+        /// The id of the missing object.
+        id: ObjectId,
+        /// The kind the object is expected to have, if known.
+        kind: Option<Kind>,
+        /// Where the object is referred to.
+        referenced_by: Subject,
+    },
+    /// The object `id` is referred to as `expected` kind by `referenced_by`, but it is of the `actual` kind.
+    ///
+    /// For tags, `expected` is the kind of their target according to their `type` line.
+    WrongKind {
+        /// The id of the object with the unexpected kind.
+        id: ObjectId,
+        /// The kind it should have.
+        expected: Kind,
+        /// The kind it has.
+        actual: Kind,
+        /// The object that refers to it.
+        referenced_by: Subject,
+    },
+    /// The object `id` can't be reached from any root.
+    Unreachable {
+        /// The id of the unreachable object.
+        id: ObjectId,
+        /// Its kind.
+        kind: Kind,
+    },
+    /// The object `id` can't be reached from any root, and no other object refers to it.
+    Dangling {
+        /// The id of the dangling object.
+        id: ObjectId,
+        /// Its kind.
+        kind: Kind,
+    },
+}
+
+impl Finding {
+    /// Return `true` if this finding means that the repository is corrupt, which is the case for missing objects,
+    /// objects of the wrong kind and for problems with [error severity](msg::Severity::Error).
+    ///
+    /// Unreachable and dangling objects are normal in any repository.
+    pub fn is_error(&self) -> bool {
+        match self {
+            Finding::Message { severity, .. } => *severity == msg::Severity::Error,
+            Finding::Missing { .. } | Finding::WrongKind { .. } => true,
+            Finding::Unreachable { .. } | Finding::Dangling { .. } => false,
+        }
+    }
+}
+
 /// Perform a connectivity check.
 pub struct Connectivity<T, F>
 where
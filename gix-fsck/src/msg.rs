@@ -0,0 +1,242 @@
+//! Identifiers for the problems we find and how severe they are, as configured with `fsck.<msg-id>`.
+use std::str::FromStr;
+
+macro_rules! ids {
+    ($($(#[$doc:meta])* $name:ident = $camel:literal, $level:ident;)*) => {
+        /// Identifies a kind of problem, with the same names that git uses in its configuration.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Id {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl Id {
+            /// All known ids.
+            pub const ALL: &'static [Id] = &[$(Id::$name,)*];
+
+            /// The name of the id as used in git configuration, like `badDate`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Id::$name => $camel,)*
+                }
+            }
+
+            fn level(&self) -> Level {
+                match self {
+                    $(Id::$name => Level::$level,)*
+                }
+            }
+        }
+    };
+}
+
+ids! {
+    /// An object header contains a NUL byte.
+    NulInHeader = "nulInHeader", Fatal;
+    /// An object header isn't terminated by a newline.
+    UnterminatedHeader = "unterminatedHeader", Fatal;
+
+    /// The date of an identity isn't a number.
+    BadDate = "badDate", Error;
+    /// The date of an identity doesn't fit into a timestamp.
+    BadDateOverflow = "badDateOverflow", Error;
+    /// The email of an identity isn't terminated by `>`.
+    BadEmail = "badEmail", Error;
+    /// The symbolic target of `HEAD` is no branch.
+    BadHeadTarget = "badHeadTarget", Error;
+    /// The name of an identity contains `>`.
+    BadName = "badName", Error;
+    /// The `object` line of a tag doesn't contain a valid hash.
+    BadObjectSha1 = "badObjectSha1", Error;
+    /// An entry of the `packed-refs` file can't be parsed.
+    BadPackedRefEntry = "badPackedRefEntry", Error;
+    /// A `parent` line of a commit doesn't contain a valid hash.
+    BadParentSha1 = "badParentSha1", Error;
+    /// A loose reference can't be parsed.
+    BadRefContent = "badRefContent", Error;
+    /// A reference has an invalid name.
+    BadRefName = "badRefName", Error;
+    /// The target of a symbolic reference has an invalid name.
+    BadReferentName = "badReferentName", Error;
+    /// The time zone of an identity isn't of the form `+hhmm` or `-hhmm`.
+    BadTimezone = "badTimezone", Error;
+    /// A tree can't be parsed.
+    BadTree = "badTree", Error;
+    /// The `tree` line of a commit doesn't contain a valid hash.
+    BadTreeSha1 = "badTreeSha1", Error;
+    /// The `type` line of a tag doesn't name an object type.
+    BadType = "badType", Error;
+    /// A tree contains the same name more than once.
+    DuplicateEntries = "duplicateEntries", Error;
+    /// A commit has no `author` line.
+    MissingAuthor = "missingAuthor", Error;
+    /// A commit has no `committer` line.
+    MissingCommitter = "missingCommitter", Error;
+    /// An identity has no email.
+    MissingEmail = "missingEmail", Error;
+    /// An identity starts with its email.
+    MissingNameBeforeEmail = "missingNameBeforeEmail", Error;
+    /// A tag has no `object` line.
+    MissingObject = "missingObject", Error;
+    /// There is no space between the email and the date of an identity.
+    MissingSpaceBeforeDate = "missingSpaceBeforeDate", Error;
+    /// There is no space between the name and the email of an identity.
+    MissingSpaceBeforeEmail = "missingSpaceBeforeEmail", Error;
+    /// A tag ends after its `tag` line.
+    MissingTag = "missingTag", Error;
+    /// A tag has no `tag` line.
+    MissingTagEntry = "missingTagEntry", Error;
+    /// A commit has no `tree` line.
+    MissingTree = "missingTree", Error;
+    /// A tag ends after its `type` line.
+    MissingType = "missingType", Error;
+    /// A tag has no `type` line.
+    MissingTypeEntry = "missingTypeEntry", Error;
+    /// A commit has more than one `author` line.
+    MultipleAuthors = "multipleAuthors", Error;
+    /// The entries of a tree aren't sorted.
+    TreeNotSorted = "treeNotSorted", Error;
+    /// The date of an identity has leading zeroes.
+    ZeroPaddedDate = "zeroPaddedDate", Error;
+
+    /// A tree entry has an empty name.
+    EmptyName = "emptyName", Warn;
+    /// A tree entry name contains a `/`.
+    FullPathname = "fullPathname", Warn;
+    /// A tree entry is named `.`.
+    HasDot = "hasDot", Warn;
+    /// A tree entry is named `..`.
+    HasDotdot = "hasDotdot", Warn;
+    /// A tree entry is named `.git`, or something that some filesystems treat as such.
+    HasDotgit = "hasDotgit", Warn;
+    /// A commit contains a NUL byte in its message.
+    NulInCommit = "nulInCommit", Warn;
+    /// A tree entry points to the null hash.
+    NullSha1 = "nullSha1", Warn;
+    /// A tree entry has a mode with leading zeroes.
+    ZeroPaddedFilemode = "zeroPaddedFilemode", Warn;
+
+    /// A tree entry has a mode that git doesn't write.
+    BadFilemode = "badFilemode", Info;
+    /// The name of a tag isn't a valid reference name.
+    BadTagName = "badTagName", Info;
+    /// A tag has no `tagger` line, which is common for very old tags.
+    MissingTaggerEntry = "missingTaggerEntry", Info;
+}
+
+/// The level git assigns to each id by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    /// An error that can't be demoted.
+    Fatal,
+    Error,
+    Warn,
+    /// Reported as warning, but not affected by strict mode.
+    Info,
+}
+
+impl Id {
+    /// Return `true` if problems of this kind are always errors.
+    pub fn is_fatal(&self) -> bool {
+        self.level() == Level::Fatal
+    }
+
+    /// Return the severity git assigns to this id if it isn't configured, where `strict` turns warnings into errors.
+    pub fn default_severity(&self, strict: bool) -> Severity {
+        match self.level() {
+            Level::Fatal | Level::Error => Severity::Error,
+            Level::Warn if strict => Severity::Error,
+            Level::Warn | Level::Info => Severity::Warn,
+        }
+    }
+}
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Id {
+    type Err = Error;
+
+    /// Parse an id case-insensitively, as configuration keys are case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Id::ALL
+            .iter()
+            .find(|id| id.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::UnknownId { name: s.into() })
+    }
+}
+
+/// How severe a problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The problem makes the object or reference invalid.
+    Error,
+    /// The problem is reported, but doesn't make the object or reference invalid.
+    Warn,
+    /// The problem isn't reported.
+    Ignore,
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "error" => Severity::Error,
+            "warn" => Severity::Warn,
+            "ignore" => Severity::Ignore,
+            _ => return Err(Error::UnknownSeverity { value: s.into() }),
+        })
+    }
+}
+
+/// The error returned when configuring [`Severities`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Unknown fsck message id: '{name}'")]
+    UnknownId { name: String },
+    #[error("Unknown fsck severity '{value}', expected one of 'error', 'warn' or 'ignore'")]
+    UnknownSeverity { value: String },
+    #[error("Cannot demote {id} to {severity:?}")]
+    CannotDemote { id: Id, severity: Severity },
+}
+
+/// The severity of each [`Id`], as configured with `fsck.<msg-id>` and `fsck.strict` (or their `receive.fsck.*`
+/// and `fetch.fsck.*` equivalents), which falls back to git's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct Severities {
+    /// If `true`, problems that would be warnings by default are errors.
+    ///
+    /// Explicitly configured severities aren't affected.
+    pub strict: bool,
+    configured: Vec<(Id, Severity)>,
+}
+
+impl Severities {
+    /// Return the severity of `id`.
+    pub fn get(&self, id: Id) -> Severity {
+        self.configured
+            .iter()
+            .find_map(|(configured, severity)| (*configured == id).then_some(*severity))
+            .unwrap_or_else(|| id.default_severity(self.strict))
+    }
+
+    /// Set the severity of `id` to `severity`, which fails if a fatal `id` would be demoted.
+    pub fn set(&mut self, id: Id, severity: Severity) -> Result<&mut Self, Error> {
+        if id.is_fatal() && severity != Severity::Error {
+            return Err(Error::CannotDemote { id, severity });
+        }
+        self.configured.retain(|(configured, _)| *configured != id);
+        self.configured.push((id, severity));
+        Ok(self)
+    }
+
+    /// Apply the configuration `fsck.<name> = <value>`, like `fsck.badDate = warn`.
+    pub fn set_from_config(&mut self, name: &str, value: &str) -> Result<&mut Self, Error> {
+        self.set(name.parse()?, value.parse()?)
+    }
+}
@@ -0,0 +1,419 @@
+//! Syntax checks for individual objects, which follow those of `git fsck` closely.
+use bstr::{BStr, ByteSlice};
+use gix_object::Kind;
+
+use crate::msg::{Id, Severities, Severity};
+
+/// Check the object of `kind` with `data` for problems, and call `report` with each problem that isn't ignored
+/// according to `severities`, along with a message that describes it.
+///
+/// Like git, commits and tags are checked until the first problem that is an error, while trees are checked in full
+/// and each kind of problem is reported only once. Blobs are never checked.
+pub fn check(
+    kind: Kind,
+    data: &[u8],
+    object_hash: gix_hash::Kind,
+    severities: &Severities,
+    report: &mut dyn FnMut(Id, Severity, &'static str),
+) {
+    let mut out = Report { severities, report };
+    let _stopped_at_error = match kind {
+        Kind::Commit => commit(data, object_hash, &mut out),
+        Kind::Tag => tag(data, object_hash, &mut out),
+        Kind::Tree => {
+            tree(data, object_hash, &mut out);
+            Ok(())
+        }
+        Kind::Blob => Ok(()),
+    };
+}
+
+/// `Err(())` indicates that an error was reported, which stops the check.
+type Stop = Result<(), ()>;
+
+struct Report<'a> {
+    severities: &'a Severities,
+    report: &'a mut dyn FnMut(Id, Severity, &'static str),
+}
+
+impl Report<'_> {
+    fn report(&mut self, id: Id, message: &'static str) -> Stop {
+        match self.severities.get(id) {
+            Severity::Ignore => Ok(()),
+            severity => {
+                (self.report)(id, severity, message);
+                if severity == Severity::Error {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Return the byte at `pos`, or `0` past the end, which mimics reading a NUL-terminated buffer.
+fn at(data: &[u8], pos: usize) -> u8 {
+    data.get(pos).copied().unwrap_or(0)
+}
+
+/// Return the position of the first byte at or after `pos` that is one of `bytes`, or the end of `data`.
+fn find_any(data: &[u8], pos: usize, bytes: &[u8]) -> usize {
+    data.get(pos..)
+        .and_then(|rest| rest.find_byteset(bytes))
+        .map_or(data.len(), |offset| pos + offset)
+}
+
+/// Return the position after `prefix` if `data` has it at `pos`.
+fn skip_prefix(data: &[u8], pos: usize, prefix: &[u8]) -> Option<usize> {
+    data.get(pos..)
+        .filter(|rest| rest.starts_with(prefix))
+        .map(|_| pos + prefix.len())
+}
+
+/// Return the position after the line starting at `pos`.
+fn next_line(data: &[u8], pos: usize) -> usize {
+    data.get(pos..)
+        .and_then(|rest| rest.find_byte(b'\n'))
+        .map_or(data.len(), |offset| pos + offset + 1)
+}
+
+/// Return `true` if `data` contains a hash followed by a newline at `pos`.
+fn is_hash_line(data: &[u8], pos: usize, object_hash: gix_hash::Kind) -> bool {
+    let hex_len = object_hash.len_in_hex();
+    data.get(pos..pos + hex_len)
+        .map_or(false, |hex| gix_hash::ObjectId::from_hex(hex).is_ok())
+        && at(data, pos + hex_len) == b'\n'
+}
+
+fn verify_headers(data: &[u8], out: &mut Report<'_>) -> Stop {
+    for (pos, byte) in data.iter().enumerate() {
+        match byte {
+            0 => return out.report(Id::NulInHeader, "unterminated header: NUL in header"),
+            b'\n' if at(data, pos + 1) == b'\n' => return Ok(()),
+            _ => {}
+        }
+    }
+    if data.last() == Some(&b'\n') {
+        return Ok(());
+    }
+    out.report(Id::UnterminatedHeader, "unterminated header")
+}
+
+fn commit(data: &[u8], object_hash: gix_hash::Kind, out: &mut Report<'_>) -> Stop {
+    verify_headers(data, out)?;
+    let Some(mut pos) = skip_prefix(data, 0, b"tree ") else {
+        return out.report(Id::MissingTree, "invalid format - expected 'tree' line");
+    };
+    if !is_hash_line(data, pos, object_hash) {
+        out.report(Id::BadTreeSha1, "invalid 'tree' line format - bad sha1")?;
+    }
+    pos = next_line(data, pos);
+    while let Some(parent) = skip_prefix(data, pos, b"parent ") {
+        if !is_hash_line(data, parent, object_hash) {
+            out.report(Id::BadParentSha1, "invalid 'parent' line format - bad sha1")?;
+        }
+        pos = next_line(data, parent);
+    }
+    let mut num_authors = 0;
+    while let Some(author) = skip_prefix(data, pos, b"author ") {
+        num_authors += 1;
+        ident(data, author, out)?;
+        pos = next_line(data, author);
+    }
+    match num_authors {
+        0 => out.report(Id::MissingAuthor, "invalid format - expected 'author' line")?,
+        1 => {}
+        _ => out.report(Id::MultipleAuthors, "invalid format - multiple 'author' lines")?,
+    }
+    let Some(committer) = skip_prefix(data, pos, b"committer ") else {
+        return out.report(Id::MissingCommitter, "invalid format - expected 'committer' line");
+    };
+    ident(data, committer, out)?;
+    if data.find_byte(0).is_some() {
+        out.report(Id::NulInCommit, "NUL byte in the commit object body")?;
+    }
+    Ok(())
+}
+
+fn tag(data: &[u8], object_hash: gix_hash::Kind, out: &mut Report<'_>) -> Stop {
+    verify_headers(data, out)?;
+    let Some(object) = skip_prefix(data, 0, b"object ") else {
+        return out.report(Id::MissingObject, "invalid format - expected 'object' line");
+    };
+    if !is_hash_line(data, object, object_hash) {
+        out.report(Id::BadObjectSha1, "invalid 'object' line format - bad sha1")?;
+    }
+    let Some(kind) = skip_prefix(data, next_line(data, object), b"type ") else {
+        return out.report(Id::MissingTypeEntry, "invalid format - expected 'type' line");
+    };
+    let Some(kind_end) = data[kind..].find_byte(b'\n').map(|end| kind + end) else {
+        return out.report(Id::MissingType, "invalid format - unexpected end after 'type' line");
+    };
+    if Kind::from_bytes(&data[kind..kind_end]).is_err() {
+        out.report(Id::BadType, "invalid 'type' value")?;
+    }
+    let Some(name) = skip_prefix(data, kind_end + 1, b"tag ") else {
+        return out.report(Id::MissingTagEntry, "invalid format - expected 'tag' line");
+    };
+    let Some(name_end) = data[name..].find_byte(b'\n').map(|end| name + end) else {
+        return out.report(Id::MissingTag, "invalid format - unexpected end after 'type' line");
+    };
+    let mut ref_name = bstr::BString::from("refs/tags/");
+    ref_name.extend_from_slice(&data[name..name_end]);
+    if gix_validate::reference::name(ref_name.as_ref()).is_err() {
+        out.report(Id::BadTagName, "invalid 'tag' name")?;
+    }
+    match skip_prefix(data, name_end + 1, b"tagger ") {
+        Some(tagger) => ident(data, tagger, out),
+        None => out.report(Id::MissingTaggerEntry, "invalid format - expected 'tagger' line"),
+    }
+}
+
+/// Check the identity of the form `name <email> seconds +hhmm` starting at `pos`.
+fn ident(data: &[u8], mut pos: usize, out: &mut Report<'_>) -> Stop {
+    if at(data, pos) == b'<' {
+        return out.report(
+            Id::MissingNameBeforeEmail,
+            "invalid author/committer line - missing name before email",
+        );
+    }
+    pos = find_any(data, pos, b"<>\n");
+    if at(data, pos) == b'>' {
+        return out.report(Id::BadName, "invalid author/committer line - bad name");
+    }
+    if at(data, pos) != b'<' {
+        return out.report(Id::MissingEmail, "invalid author/committer line - missing email");
+    }
+    if at(data, pos - 1) != b' ' {
+        return out.report(
+            Id::MissingSpaceBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        );
+    }
+    pos = find_any(data, pos + 1, b"<>\n");
+    if at(data, pos) != b'>' {
+        return out.report(Id::BadEmail, "invalid author/committer line - bad email");
+    }
+    pos += 1;
+    if at(data, pos) != b' ' {
+        return out.report(
+            Id::MissingSpaceBeforeDate,
+            "invalid author/committer line - missing space before date",
+        );
+    }
+    pos += 1;
+    if at(data, pos) == b'0' && at(data, pos + 1) != b' ' {
+        return out.report(Id::ZeroPaddedDate, "invalid author/committer line - zero-padded date");
+    }
+    let date_end = data[pos..]
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .map_or(data.len(), |end| pos + end);
+    let date = &data[pos..date_end];
+    if !date.is_empty()
+        && date
+            .to_str()
+            .ok()
+            .and_then(|date| date.parse::<gix_object::date::SecondsSinceUnixEpoch>().ok())
+            .is_none()
+    {
+        return out.report(
+            Id::BadDateOverflow,
+            "invalid author/committer line - date causes integer overflow",
+        );
+    }
+    if date.is_empty() || at(data, date_end) != b' ' {
+        return out.report(Id::BadDate, "invalid author/committer line - bad date");
+    }
+    pos = date_end + 1;
+    if !matches!(at(data, pos), b'+' | b'-')
+        || !(1..5).all(|offset| at(data, pos + offset).is_ascii_digit())
+        || at(data, pos + 5) != b'\n'
+    {
+        return out.report(Id::BadTimezone, "invalid author/committer line - bad time zone");
+    }
+    Ok(())
+}
+
+/// Check all entries of a tree, and report each kind of problem once.
+fn tree(data: &[u8], object_hash: gix_hash::Kind, out: &mut Report<'_>) {
+    #[derive(Default)]
+    struct Found {
+        null_sha1: bool,
+        full_path: bool,
+        dot: bool,
+        dotdot: bool,
+        dotgit: bool,
+        zero_padded_mode: bool,
+        bad_mode: bool,
+        duplicates: bool,
+        not_sorted: bool,
+    }
+    let mut found = Found::default();
+    let mut previous = None::<(u32, &BStr)>;
+    let mut candidates = Vec::new();
+    let mut entries = data;
+    while !entries.is_empty() {
+        let Some(entry) = TreeEntry::parse(entries, object_hash) else {
+            let _ = out.report(Id::BadTree, "cannot be parsed as a tree");
+            break;
+        };
+        entries = &entries[entry.len..];
+
+        found.null_sha1 |= entry.oid.iter().all(|b| *b == 0);
+        found.full_path |= entry.name.contains(&b'/');
+        found.dot |= entry.name == ".";
+        found.dotdot |= entry.name == "..";
+        found.dotgit |= is_dotgit(entry.name);
+        found.zero_padded_mode |= entry.mode_is_zero_padded;
+        found.bad_mode |= !matches!(entry.mode, 0o100755 | 0o100644 | 0o120000 | 0o040000 | 0o160000)
+            && (entry.mode != 0o100664 || out.severities.strict);
+
+        if let Some((previous_mode, previous_name)) = previous {
+            match verify_ordered(previous_mode, previous_name, entry.mode, entry.name, &mut candidates) {
+                Order::Ordered => {}
+                Order::Unordered => found.not_sorted = true,
+                Order::Duplicate => found.duplicates = true,
+            }
+        }
+        previous = Some((entry.mode, entry.name));
+    }
+
+    for (is_found, id, message) in [
+        (found.null_sha1, Id::NullSha1, "contains entries pointing to null sha1"),
+        (found.full_path, Id::FullPathname, "contains full pathnames"),
+        (found.dot, Id::HasDot, "contains '.'"),
+        (found.dotdot, Id::HasDotdot, "contains '..'"),
+        (found.dotgit, Id::HasDotgit, "contains '.git'"),
+        (
+            found.zero_padded_mode,
+            Id::ZeroPaddedFilemode,
+            "contains zero-padded file modes",
+        ),
+        (found.bad_mode, Id::BadFilemode, "contains bad file modes"),
+        (
+            found.duplicates,
+            Id::DuplicateEntries,
+            "contains duplicate file entries",
+        ),
+        (found.not_sorted, Id::TreeNotSorted, "not properly sorted"),
+    ] {
+        if is_found {
+            let _ = out.report(id, message);
+        }
+    }
+}
+
+fn is_dotgit(name: &BStr) -> bool {
+    matches!(
+        gix_validate::path::component(
+            name,
+            None,
+            gix_validate::path::component::Options {
+                protect_windows: false,
+                protect_hfs: true,
+                protect_ntfs: true,
+            },
+        ),
+        Err(gix_validate::path::component::Error::DotGitDir)
+    )
+}
+
+/// A tree entry with its mode as written, which we parse ourselves to see what `gix-object` would normalize.
+struct TreeEntry<'a> {
+    mode: u32,
+    mode_is_zero_padded: bool,
+    name: &'a BStr,
+    oid: &'a [u8],
+    /// The amount of bytes the entry takes.
+    len: usize,
+}
+
+impl<'a> TreeEntry<'a> {
+    fn parse(data: &'a [u8], object_hash: gix_hash::Kind) -> Option<Self> {
+        let mode_end = data.find_byte(b' ')?;
+        let mode_bytes = &data[..mode_end];
+        if mode_bytes.is_empty() || !mode_bytes.iter().all(|b| (b'0'..=b'7').contains(b)) {
+            return None;
+        }
+        let mode = mode_bytes
+            .iter()
+            .try_fold(0u32, |mode, b| mode.checked_mul(8)?.checked_add(u32::from(b - b'0')))?;
+        let name_start = mode_end + 1;
+        let name_end = name_start + data[name_start..].find_byte(0)?;
+        if name_end == name_start {
+            return None;
+        }
+        let oid = data.get(name_end + 1..name_end + 1 + object_hash.len_in_bytes())?;
+        Some(TreeEntry {
+            mode,
+            mode_is_zero_padded: mode_bytes[0] == b'0',
+            name: data[name_start..name_end].as_bstr(),
+            oid,
+            len: name_end + 1 + oid.len(),
+        })
+    }
+}
+
+enum Order {
+    Ordered,
+    Unordered,
+    Duplicate,
+}
+
+fn is_tree(mode: u32) -> bool {
+    mode & 0o170000 == 0o040000
+}
+
+/// `a < b < /` for all characters except NUL.
+fn is_less_than_slash(b: u8) -> bool {
+    b != 0 && b < b'/'
+}
+
+/// Check that `name2` sorts after `name1` in the way git sorts tree entries, where trees sort as if their name
+/// had a trailing slash.
+///
+/// `candidates` keeps names of non-trees which might clash with trees that follow later, like the file `foo` in
+/// `foo`, `foo.bar`, `foo/`.
+fn verify_ordered<'a>(mode1: u32, name1: &'a BStr, mode2: u32, name2: &BStr, candidates: &mut Vec<&'a BStr>) -> Order {
+    let len = name1.len().min(name2.len());
+    match name1[..len].cmp(&name2[..len]) {
+        std::cmp::Ordering::Less => return Order::Ordered,
+        std::cmp::Ordering::Greater => return Order::Unordered,
+        std::cmp::Ordering::Equal => {}
+    }
+    let mut c1 = at(name1, len);
+    let mut c2 = at(name2, len);
+    if c1 == 0 && c2 == 0 {
+        return Order::Duplicate;
+    }
+    if c1 == 0 && is_tree(mode1) {
+        c1 = b'/';
+    }
+    if c2 == 0 && is_tree(mode2) {
+        c2 = b'/';
+    }
+
+    if c1 == 0 && is_less_than_slash(c2) {
+        candidates.push(name1);
+    } else if c2 == b'/' && is_less_than_slash(c1) {
+        while let Some(file_name) = candidates.last() {
+            let Some(rest) = name2.strip_prefix(file_name.as_bytes()) else {
+                break;
+            };
+            match rest.first() {
+                None => return Order::Duplicate,
+                Some(b) if is_less_than_slash(*b) => break,
+                Some(_) => {
+                    candidates.pop();
+                }
+            }
+        }
+    }
+    if c1 < c2 {
+        Order::Ordered
+    } else {
+        Order::Unordered
+    }
+}
@@ -0,0 +1,226 @@
+//! Check all objects of a repository for syntax and connectivity, and find those that are unreachable.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::{CommitRefIter, Kind, TagRefIter, TreeRefIter};
+
+use crate::{msg, object, Finding, Subject};
+
+/// The error returned by [`check()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read object {id}")]
+    Find {
+        id: ObjectId,
+        source: gix_object::find::Error,
+    },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`check()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The severities of the problems found in each object.
+    pub severities: msg::Severities,
+    /// If `true`, report each object that can't be reached from the roots as unreachable, instead of reporting
+    /// only dangling ones.
+    ///
+    /// This is like `git fsck --unreachable`.
+    pub unreachable: bool,
+    /// If `true`, report unreachable objects that no other object refers to, unless all unreachable objects
+    /// are reported.
+    ///
+    /// This is like `git fsck --dangling`, which is the default.
+    pub dangling: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            severities: Default::default(),
+            unreachable: false,
+            dangling: true,
+        }
+    }
+}
+
+/// The result of [`check()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects that were checked.
+    pub objects: usize,
+    /// The amount of checked objects that are reachable from the roots.
+    pub reachable: usize,
+}
+
+/// An object we have seen, along with the objects it refers to and the kind they should have.
+struct Node {
+    kind: Kind,
+    edges: Vec<(ObjectId, Kind)>,
+}
+
+/// Check the objects with `ids` in `db`, which should be all objects of the repository, and call `report` with each
+/// [`Finding`], with objects that aren't reachable from any of the `roots` reported last.
+///
+/// Each object is [checked](object::check()) for syntax, and each object it refers to must exist with the expected kind,
+/// which includes the targets of tags. `roots` are typically obtained from [`refs::check()`](crate::refs::check()),
+/// and those that don't exist are ignored as they are reported there.
+///
+/// Note that the objects referred to by all objects are kept in memory.
+pub fn check(
+    db: &dyn gix_object::Find,
+    ids: impl IntoIterator<Item = ObjectId>,
+    roots: impl IntoIterator<Item = ObjectId>,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+    report: &mut dyn FnMut(Finding),
+) -> Result<Outcome, Error> {
+    let mut nodes = HashMap::<ObjectId, Node>::default();
+    let mut buf = Vec::new();
+    for id in ids {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        if nodes.contains_key(&id) {
+            continue;
+        }
+        let Some(obj) = db
+            .try_find(&id, &mut buf)
+            .map_err(|source| Error::Find { id, source })?
+        else {
+            continue;
+        };
+        object::check(
+            obj.kind,
+            obj.data,
+            obj.object_hash,
+            &options.severities,
+            &mut |msg_id, severity, message| {
+                report(Finding::Message {
+                    subject: Subject::Object { id, kind: obj.kind },
+                    id: msg_id,
+                    severity,
+                    message,
+                });
+            },
+        );
+        nodes.insert(
+            id,
+            Node {
+                kind: obj.kind,
+                edges: edges(obj),
+            },
+        );
+    }
+
+    let mut missing = HashMap::<ObjectId, (Kind, Subject)>::default();
+    let mut referenced = HashSet::default();
+    let mut sorted_ids: Vec<_> = nodes.keys().copied().collect();
+    sorted_ids.sort();
+    for id in &sorted_ids {
+        let node = &nodes[id];
+        for (target, expected) in &node.edges {
+            referenced.insert(*target);
+            let referenced_by = || Subject::Object {
+                id: *id,
+                kind: node.kind,
+            };
+            match nodes.get(target) {
+                None => {
+                    missing.entry(*target).or_insert_with(|| (*expected, referenced_by()));
+                }
+                Some(target_node) if target_node.kind != *expected => report(Finding::WrongKind {
+                    id: *target,
+                    expected: *expected,
+                    actual: target_node.kind,
+                    referenced_by: referenced_by(),
+                }),
+                Some(_) => {}
+            }
+        }
+    }
+    let mut missing: Vec<_> = missing.into_iter().collect();
+    missing.sort_by_key(|(id, _)| *id);
+    for (id, (kind, referenced_by)) in missing {
+        report(Finding::Missing {
+            id,
+            kind: Some(kind),
+            referenced_by,
+        });
+    }
+
+    let mut reachable = HashSet::default();
+    let mut queue: Vec<_> = roots.into_iter().filter(|id| nodes.contains_key(id)).collect();
+    while let Some(id) = queue.pop() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        queue.extend(
+            nodes[&id]
+                .edges
+                .iter()
+                .map(|(target, _)| *target)
+                .filter(|target| nodes.contains_key(target) && !reachable.contains(target)),
+        );
+    }
+
+    if options.unreachable || options.dangling {
+        for id in sorted_ids.iter().filter(|id| !reachable.contains(*id)) {
+            let kind = nodes[id].kind;
+            if options.unreachable {
+                report(Finding::Unreachable { id: *id, kind });
+            } else if !referenced.contains(id) {
+                report(Finding::Dangling { id: *id, kind });
+            }
+        }
+    }
+
+    Ok(Outcome {
+        objects: nodes.len(),
+        reachable: reachable.len(),
+    })
+}
+
+/// Return the objects `obj` refers to along with the kind they should have, or nothing if it can't be decoded.
+fn edges(obj: gix_object::Data<'_>) -> Vec<(ObjectId, Kind)> {
+    let mut out = Vec::new();
+    match obj.kind {
+        Kind::Commit => {
+            let mut iter = CommitRefIter::from_bytes(obj.data);
+            let Ok(tree) = iter.tree_id() else {
+                return out;
+            };
+            out.push((tree, Kind::Tree));
+            for token in iter {
+                match token {
+                    Ok(gix_object::commit::ref_iter::Token::Parent { id }) => out.push((id, Kind::Commit)),
+                    _ => break,
+                }
+            }
+        }
+        Kind::Tag => {
+            use gix_object::tag::ref_iter::Token;
+            let mut iter = TagRefIter::from_bytes(obj.data);
+            if let (Some(Ok(Token::Target { id })), Some(Ok(Token::TargetKind(kind)))) = (iter.next(), iter.next()) {
+                out.push((id, kind));
+            }
+        }
+        Kind::Tree => {
+            for entry in TreeRefIter::from_bytes(obj.data, obj.object_hash) {
+                let Ok(entry) = entry else {
+                    break;
+                };
+                if entry.mode.is_commit() {
+                    continue;
+                }
+                let kind = if entry.mode.is_tree() { Kind::Tree } else { Kind::Blob };
+                out.push((entry.oid.to_owned(), kind));
+            }
+        }
+        Kind::Blob => {}
+    }
+    out
+}
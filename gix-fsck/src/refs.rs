@@ -0,0 +1,209 @@
+//! Check references and their reflogs.
+use std::path::Path;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+use gix_ref::file::iter::loose_then_packed;
+
+use crate::{
+    msg::{Id, Severities, Severity},
+    Finding, Subject,
+};
+
+/// The error returned by [`check()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not list the references directory at '{path}'")]
+    ReadDir {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error(transparent)]
+    PackedOpen(#[from] gix_ref::packed::buffer::open::Error),
+    #[error("Could not iterate references")]
+    Iter(#[source] std::io::Error),
+    #[error(transparent)]
+    Traverse(#[from] loose_then_packed::Error),
+    #[error(transparent)]
+    Find(#[from] gix_ref::file::find::Error),
+    #[error(transparent)]
+    Reflog(#[from] gix_ref::file::log::Error),
+}
+
+/// Check all references in `store` and their reflogs, and call `report` with each [`Finding`].
+///
+/// References must have valid names and content, `HEAD` must point to a branch if it is symbolic, and all objects
+/// that references and reflog entries point to must exist in `db`.
+///
+/// Return the ids of all existing objects that references and reflog entries point to, which are the roots for
+/// finding unreachable objects with [`objects::check()`](crate::objects::check()).
+pub fn check(
+    store: &gix_ref::file::Store,
+    db: &dyn gix_object::Exists,
+    severities: &Severities,
+    report: &mut dyn FnMut(Finding),
+) -> Result<Vec<ObjectId>, Error> {
+    let mut out = Report { severities, report };
+    let mut roots = Vec::new();
+
+    let common_dir = store.common_dir_resolved();
+    check_names(common_dir, &common_dir.join("refs"), &mut out)?;
+
+    let mut names = Vec::new();
+    if let Some(head) = store.try_find("HEAD")? {
+        match &head.target {
+            gix_ref::Target::Symbolic(target) if !target.as_bstr().starts_with(b"refs/heads/") => {
+                out.message(
+                    Subject::Reference(head.name.as_bstr().to_owned()),
+                    Id::BadHeadTarget,
+                    "HEAD points to something strange",
+                );
+            }
+            _ => {}
+        }
+        check_target(&head, db, &mut roots, &mut out);
+        names.push(head.name);
+    }
+
+    for reference in store.iter()?.all().map_err(Error::Iter)? {
+        let reference = match reference {
+            Ok(reference) => reference,
+            Err(loose_then_packed::Error::ReferenceCreation { source, relative_path }) => {
+                let (id, message) = match source {
+                    gix_ref::file::loose::reference::decode::Error::Parse { .. } => {
+                        (Id::BadRefContent, "invalid reference content")
+                    }
+                    gix_ref::file::loose::reference::decode::Error::RefnameValidation { .. } => {
+                        (Id::BadReferentName, "points to a reference with an invalid name")
+                    }
+                };
+                out.message(Subject::Reference(path_to_name(&relative_path)), id, message);
+                continue;
+            }
+            Err(loose_then_packed::Error::PackedReference { .. }) => {
+                out.message(
+                    Subject::Reference("packed-refs".into()),
+                    Id::BadPackedRefEntry,
+                    "contains an invalid entry",
+                );
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        check_target(&reference, db, &mut roots, &mut out);
+        names.push(reference.name);
+    }
+
+    let mut buf = Vec::new();
+    for name in &names {
+        let Some(log) = store.reflog_iter(name.as_ref(), &mut buf)? else {
+            continue;
+        };
+        let subject = || Subject::Reflog(name.as_bstr().to_owned());
+        for line in log {
+            let Ok(line) = line else {
+                out.message(subject(), Id::BadRefContent, "contains an invalid reflog entry");
+                continue;
+            };
+            for id in [line.previous_oid(), line.new_oid()] {
+                if id.is_null() {
+                    continue;
+                }
+                if db.exists(&id) {
+                    roots.push(id);
+                } else {
+                    (out.report)(Finding::Missing {
+                        id,
+                        kind: None,
+                        referenced_by: subject(),
+                    });
+                }
+            }
+        }
+    }
+
+    roots.sort();
+    roots.dedup();
+    Ok(roots)
+}
+
+struct Report<'a> {
+    severities: &'a Severities,
+    report: &'a mut dyn FnMut(Finding),
+}
+
+impl Report<'_> {
+    fn message(&mut self, subject: Subject, id: Id, message: &'static str) {
+        let severity = self.severities.get(id);
+        if severity != Severity::Ignore {
+            (self.report)(Finding::Message {
+                subject,
+                id,
+                severity,
+                message,
+            });
+        }
+    }
+}
+
+fn check_target(
+    reference: &gix_ref::Reference,
+    db: &dyn gix_object::Exists,
+    roots: &mut Vec<ObjectId>,
+    out: &mut Report<'_>,
+) {
+    let gix_ref::Target::Object(id) = reference.target else {
+        return;
+    };
+    if db.exists(&id) {
+        roots.push(id);
+    } else {
+        (out.report)(Finding::Missing {
+            id,
+            kind: None,
+            referenced_by: Subject::Reference(reference.name.as_bstr().to_owned()),
+        });
+    }
+}
+
+/// Report all files in `dir` whose path relative to `base` isn't a valid reference name.
+fn check_names(base: &Path, dir: &Path, out: &mut Report<'_>) -> Result<(), Error> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => {
+            return Err(Error::ReadDir {
+                source,
+                path: dir.to_owned(),
+            })
+        }
+    };
+    for entry in entries {
+        let entry = entry.map_err(|source| Error::ReadDir {
+            source,
+            path: dir.to_owned(),
+        })?;
+        let path = entry.path();
+        let file_type = entry.file_type().map_err(|source| Error::ReadDir {
+            source,
+            path: path.clone(),
+        })?;
+        if file_type.is_dir() {
+            check_names(base, &path, out)?;
+            continue;
+        }
+        if path.extension().map_or(false, |ext| ext == "lock") {
+            continue;
+        }
+        let name = path_to_name(path.strip_prefix(base).expect("dir is within base"));
+        if gix_validate::reference::name(name.as_ref()).is_err() {
+            out.message(Subject::Reference(name), Id::BadRefName, "invalid reference name");
+        }
+    }
+    Ok(())
+}
+
+fn path_to_name(path: &Path) -> BString {
+    gix_path::to_unix_separators_on_windows(gix_path::into_bstr(path)).into_owned()
+}
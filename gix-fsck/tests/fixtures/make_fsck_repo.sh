@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+git add a && git commit -qm "first"
+echo b > b
+git add b && git commit -qm "second"
+git tag -m "annotated" v1
+
+# A blob that nothing refers to.
+echo "dangling" | git hash-object -w --stdin > dangling-blob
+
+# A commit that nothing refers to once its branch and all reflogs are gone, along with its tree and blob.
+git checkout -q -b gone
+echo c > c
+git add c && git commit -qm "third"
+git rev-parse gone > unreachable-commit
+git rev-parse gone^{tree} > unreachable-tree
+git rev-parse gone:c > unreachable-blob
+git checkout -q main
+git branch -qD gone
+git reflog expire --expire=now --all
+
+# A commit whose author date is zero-padded.
+printf 'tree %s\nparent %s\nauthor A U Thor <author@example.com> 0123 +0000\ncommitter C O Mitter <committer@example.com> 1112911993 +0000\n\nzero-padded date\n' \
+  "$(git rev-parse HEAD^{tree})" "$(git rev-parse HEAD)" \
+  | git hash-object -t commit --literally -w --stdin > zero-padded-date-commit
+git update-ref refs/heads/zero-padded-date "$(cat zero-padded-date-commit)"
+
+# A reference file that doesn't have a valid name.
+git rev-parse HEAD > .git/refs/heads/bad..name
@@ -5,3 +5,6 @@ pub fn hex_to_id(hex: &str) -> ObjectId {
 }
 
 mod connectivity;
+mod msg;
+mod object;
+mod repository;
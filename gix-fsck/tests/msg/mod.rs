@@ -0,0 +1,50 @@
+use gix_fsck::msg::{Error, Id, Severities, Severity};
+
+#[test]
+fn defaults_follow_git() {
+    let severities = Severities::default();
+    assert_eq!(severities.get(Id::BadDate), Severity::Error);
+    assert_eq!(severities.get(Id::ZeroPaddedFilemode), Severity::Warn);
+    assert_eq!(severities.get(Id::MissingTaggerEntry), Severity::Warn);
+
+    let mut strict = Severities::default();
+    strict.strict = true;
+    assert_eq!(strict.get(Id::ZeroPaddedFilemode), Severity::Error);
+    assert_eq!(
+        strict.get(Id::MissingTaggerEntry),
+        Severity::Warn,
+        "informational messages stay warnings"
+    );
+}
+
+#[test]
+fn configuration_is_case_insensitive_and_fatal_ids_cannot_be_demoted() -> Result<(), Error> {
+    let mut severities = Severities::default();
+    severities
+        .set_from_config("baddate", "ignore")?
+        .set_from_config("zeroPaddedFilemode", "error")?;
+    assert_eq!(severities.get(Id::BadDate), Severity::Ignore);
+    assert_eq!(severities.get(Id::ZeroPaddedFilemode), Severity::Error);
+
+    assert!(matches!(
+        severities.set_from_config("nulInHeader", "warn"),
+        Err(Error::CannotDemote {
+            id: Id::NulInHeader,
+            severity: Severity::Warn
+        })
+    ));
+    severities.set(Id::NulInHeader, Severity::Error)?;
+    assert!(matches!(
+        severities.set_from_config("noSuchId", "warn"),
+        Err(Error::UnknownId { .. })
+    ));
+    assert!(matches!(
+        severities.set_from_config("badDate", "fatal"),
+        Err(Error::UnknownSeverity { .. })
+    ));
+
+    for id in Id::ALL {
+        assert_eq!(id.as_str().parse::<Id>()?, *id, "names round-trip");
+    }
+    Ok(())
+}
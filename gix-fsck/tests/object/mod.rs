@@ -0,0 +1,341 @@
+use gix_fsck::msg::{Id, Severities, Severity};
+use gix_object::Kind;
+
+fn check_with(kind: Kind, data: &[u8], severities: &Severities) -> Vec<(Id, Severity)> {
+    let mut out = Vec::new();
+    gix_fsck::object::check(
+        kind,
+        data,
+        gix_hash::Kind::Sha1,
+        severities,
+        &mut |id, severity, _message| out.push((id, severity)),
+    );
+    out
+}
+
+fn check(kind: Kind, data: &[u8]) -> Vec<(Id, Severity)> {
+    check_with(kind, data, &Severities::default())
+}
+
+const TREE: &str = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n";
+const AUTHOR: &str = "author A U Thor <author@example.com> 1112911993 +0700\n";
+const COMMITTER: &str = "committer C O Mitter <committer@example.com> 1112911993 -0700\n";
+
+fn commit(headers: &[&str]) -> Vec<u8> {
+    let mut out = headers.concat().into_bytes();
+    out.extend_from_slice(b"\nmessage\n");
+    out
+}
+
+mod commit {
+    use gix_fsck::msg::{Id, Severities, Severity};
+    use gix_object::Kind;
+
+    use super::{check, check_with, commit, AUTHOR, COMMITTER, TREE};
+
+    #[test]
+    fn valid() {
+        assert_eq!(check(Kind::Commit, &commit(&[TREE, AUTHOR, COMMITTER])), vec![]);
+        let parent = "parent 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n";
+        assert_eq!(
+            check(Kind::Commit, &commit(&[TREE, parent, parent, AUTHOR, COMMITTER])),
+            vec![]
+        );
+        assert_eq!(check(Kind::Blob, b"\0anything"), vec![], "blobs are never checked");
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(
+            check(Kind::Commit, &commit(&[AUTHOR, COMMITTER])),
+            vec![(Id::MissingTree, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, &commit(&["tree 4b825d\n", AUTHOR, COMMITTER])),
+            vec![(Id::BadTreeSha1, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, &commit(&[TREE, "parent x\n", AUTHOR, COMMITTER])),
+            vec![(Id::BadParentSha1, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, &commit(&[TREE, COMMITTER])),
+            vec![(Id::MissingAuthor, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, &commit(&[TREE, AUTHOR, AUTHOR, COMMITTER])),
+            vec![(Id::MultipleAuthors, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, &commit(&[TREE, AUTHOR])),
+            vec![(Id::MissingCommitter, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, format!("{TREE}{AUTHOR}{COMMITTER}").trim_end().as_bytes()),
+            vec![(Id::UnterminatedHeader, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Commit, format!("{TREE}\0{AUTHOR}{COMMITTER}").as_bytes()),
+            vec![(Id::NulInHeader, Severity::Error)]
+        );
+        let mut nul_in_message = commit(&[TREE, AUTHOR, COMMITTER]);
+        nul_in_message.push(0);
+        assert_eq!(
+            check(Kind::Commit, &nul_in_message),
+            vec![(Id::NulInCommit, Severity::Warn)]
+        );
+    }
+
+    #[test]
+    fn identities() {
+        for (author, expected) in [
+            ("author <author@example.com> 1 +0000\n", Id::MissingNameBeforeEmail),
+            ("author A U Thor> 1 +0000\n", Id::BadName),
+            ("author A U Thor 1 +0000\n", Id::MissingEmail),
+            (
+                "author A U Thor<author@example.com> 1 +0000\n",
+                Id::MissingSpaceBeforeEmail,
+            ),
+            ("author A U Thor <author@example.com 1 +0000\n", Id::BadEmail),
+            (
+                "author A U Thor <author@example.com>1 +0000\n",
+                Id::MissingSpaceBeforeDate,
+            ),
+            ("author A U Thor <author@example.com> 0123 +0000\n", Id::ZeroPaddedDate),
+            (
+                "author A U Thor <author@example.com> 99999999999999999999 +0000\n",
+                Id::BadDateOverflow,
+            ),
+            ("author A U Thor <author@example.com> yesterday +0000\n", Id::BadDate),
+            ("author A U Thor <author@example.com> 1 0000\n", Id::BadTimezone),
+            ("author A U Thor <author@example.com> 1 +00\n", Id::BadTimezone),
+        ] {
+            assert_eq!(
+                check(Kind::Commit, &commit(&[TREE, author, COMMITTER])),
+                vec![(expected, Severity::Error)],
+                "{author:?}"
+            );
+        }
+        assert_eq!(
+            check(
+                Kind::Commit,
+                &commit(&[TREE, AUTHOR, "committer C O Mitter <committer@example.com> 0 +0000\n"])
+            ),
+            vec![],
+            "a zero date isn't padded"
+        );
+    }
+
+    #[test]
+    fn checks_continue_after_problems_that_are_no_errors() -> Result<(), gix_fsck::msg::Error> {
+        let data = commit(&[
+            TREE,
+            "author A U Thor <author@example.com> 0123 +0000\n",
+            "committer C O Mitter <committer@example.com> 1 +00\n",
+        ]);
+        assert_eq!(
+            check(Kind::Commit, &data),
+            vec![(Id::ZeroPaddedDate, Severity::Error)],
+            "errors stop the check"
+        );
+
+        let mut severities = Severities::default();
+        severities.set(Id::ZeroPaddedDate, Severity::Warn)?;
+        assert_eq!(
+            check_with(Kind::Commit, &data, &severities),
+            vec![(Id::ZeroPaddedDate, Severity::Warn), (Id::BadTimezone, Severity::Error)]
+        );
+
+        severities.set(Id::ZeroPaddedDate, Severity::Ignore)?;
+        assert_eq!(
+            check_with(Kind::Commit, &data, &severities),
+            vec![(Id::BadTimezone, Severity::Error)]
+        );
+        Ok(())
+    }
+}
+
+mod tag {
+    use gix_fsck::msg::{Id, Severity};
+    use gix_object::Kind;
+
+    use super::check;
+
+    const OBJECT: &str = "object 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n";
+    const TAGGER: &str = "tagger C O Mitter <committer@example.com> 1112911993 +0000\n";
+
+    fn tag(headers: &[&str]) -> Vec<u8> {
+        let mut out = headers.concat().into_bytes();
+        out.extend_from_slice(b"\nmessage\n");
+        out
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            check(Kind::Tag, &tag(&[OBJECT, "type tree\n", "tag v1\n", TAGGER])),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn headers() {
+        for (headers, expected) in [
+            (
+                &["type tree\n", "tag v1\n", TAGGER][..],
+                (Id::MissingObject, Severity::Error),
+            ),
+            (
+                &["object 123\n", "type tree\n", "tag v1\n", TAGGER],
+                (Id::BadObjectSha1, Severity::Error),
+            ),
+            (&[OBJECT, "tag v1\n", TAGGER], (Id::MissingTypeEntry, Severity::Error)),
+            (
+                &[OBJECT, "type pear\n", "tag v1\n", TAGGER],
+                (Id::BadType, Severity::Error),
+            ),
+            (&[OBJECT, "type tree\n", TAGGER], (Id::MissingTagEntry, Severity::Error)),
+            (
+                &[OBJECT, "type tree\n", "tag bad..name\n", TAGGER],
+                (Id::BadTagName, Severity::Warn),
+            ),
+            (
+                &[OBJECT, "type tree\n", "tag v1\n"],
+                (Id::MissingTaggerEntry, Severity::Warn),
+            ),
+            (
+                &[OBJECT, "type tree\n", "tag v1\n", "tagger C O Mitter 1 +0000\n"],
+                (Id::MissingEmail, Severity::Error),
+            ),
+        ] {
+            assert_eq!(check(Kind::Tag, &tag(headers)), vec![expected], "{headers:?}");
+        }
+        assert_eq!(
+            check(Kind::Tag, format!("{OBJECT}type tree").as_bytes()),
+            vec![(Id::UnterminatedHeader, Severity::Error)]
+        );
+    }
+}
+
+mod tree {
+    use gix_fsck::msg::{Id, Severities, Severity};
+    use gix_object::Kind;
+
+    use super::{check, check_with};
+
+    fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (mode, name) in entries {
+            out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+            out.extend_from_slice(&[1; 20]);
+        }
+        out
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            check(
+                Kind::Tree,
+                &tree(&[
+                    ("100644", "a"),
+                    ("40000", "a-dir"),
+                    ("100755", "b"),
+                    ("120000", "c"),
+                    ("160000", "d"),
+                    ("100644", "foo.bar"),
+                    ("40000", "foo"),
+                    ("100644", "foo0"),
+                ])
+            ),
+            vec![],
+            "trees sort as if they had a trailing slash"
+        );
+        assert_eq!(check(Kind::Tree, b""), vec![], "the empty tree");
+    }
+
+    #[test]
+    fn names() {
+        for (name, expected) in [
+            (".", Id::HasDot),
+            ("..", Id::HasDotdot),
+            (".git", Id::HasDotgit),
+            (".GIT", Id::HasDotgit),
+            ("git~1", Id::HasDotgit),
+            ("a/b", Id::FullPathname),
+        ] {
+            assert_eq!(
+                check(Kind::Tree, &tree(&[("100644", name)])),
+                vec![(expected, Severity::Warn)],
+                "{name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("040000", "a"), ("040000", "b")])),
+            vec![(Id::ZeroPaddedFilemode, Severity::Warn)],
+            "each kind of problem is reported once"
+        );
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("100600", "a")])),
+            vec![(Id::BadFilemode, Severity::Warn)]
+        );
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("100664", "a")])),
+            vec![],
+            "old versions of git wrote group-writable files"
+        );
+        let mut strict = Severities::default();
+        strict.strict = true;
+        assert_eq!(
+            check_with(Kind::Tree, &tree(&[("100664", "a"), ("040000", "b")]), &strict),
+            vec![
+                (Id::ZeroPaddedFilemode, Severity::Error),
+                (Id::BadFilemode, Severity::Warn)
+            ]
+        );
+    }
+
+    #[test]
+    fn order_and_duplicates() {
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("100644", "b"), ("100644", "a")])),
+            vec![(Id::TreeNotSorted, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("100644", "a"), ("40000", "a")])),
+            vec![(Id::DuplicateEntries, Severity::Error)]
+        );
+        assert_eq!(
+            check(
+                Kind::Tree,
+                &tree(&[("100644", "foo"), ("100644", "foo.bar"), ("40000", "foo")])
+            ),
+            vec![(Id::DuplicateEntries, Severity::Error)],
+            "duplicates may not be adjacent"
+        );
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("40000", "foo"), ("100644", "foo.bar")])),
+            vec![(Id::TreeNotSorted, Severity::Error)],
+            "the tree 'foo' sorts as 'foo/'"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let mut data = tree(&[("100644", "a")]);
+        data.pop();
+        assert_eq!(check(Kind::Tree, &data), vec![(Id::BadTree, Severity::Error)]);
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("10064x", "a")])),
+            vec![(Id::BadTree, Severity::Error)]
+        );
+        assert_eq!(
+            check(Kind::Tree, &tree(&[("100644", "")])),
+            vec![(Id::BadTree, Severity::Error)]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_fsck::{
+    msg::{Id, Severity},
+    objects, refs, Finding, Subject,
+};
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::hex_to_id;
+
+fn id_in(dir: &Path, name: &str) -> ObjectId {
+    let hex = std::fs::read_to_string(dir.join(name)).expect("id file exists");
+    hex_to_id(hex.trim())
+}
+
+fn fsck(options: &objects::Options) -> gix_testtools::Result<(std::path::PathBuf, Vec<Finding>, objects::Outcome)> {
+    let dir = gix_testtools::scripted_fixture_read_only("make_fsck_repo.sh")?;
+    let git_dir = dir.join(".git");
+    let db = gix_odb::at(git_dir.join("objects"))?;
+    let store = gix_ref::file::Store::at(git_dir, Default::default());
+
+    let mut findings = Vec::new();
+    let roots = refs::check(&store, &db, &options.severities, &mut |finding| findings.push(finding))?;
+    let ids = db.iter()?.collect::<Result<Vec<_>, _>>()?;
+    let outcome = objects::check(&db, ids, roots, options, &AtomicBool::default(), &mut |finding| {
+        findings.push(finding);
+    })?;
+    Ok((dir, findings, outcome))
+}
+
+#[test]
+fn refs_and_objects() -> gix_testtools::Result {
+    let (dir, findings, outcome) = fsck(&Default::default())?;
+    let zero_padded_date_commit = id_in(&dir, "zero-padded-date-commit");
+    assert_eq!(
+        findings,
+        vec![
+            Finding::Message {
+                subject: Subject::Reference("refs/heads/bad..name".into()),
+                id: Id::BadRefName,
+                severity: Severity::Error,
+                message: "invalid reference name",
+            },
+            Finding::Message {
+                subject: Subject::Object {
+                    id: zero_padded_date_commit,
+                    kind: Kind::Commit
+                },
+                id: Id::ZeroPaddedDate,
+                severity: Severity::Error,
+                message: "invalid author/committer line - zero-padded date",
+            },
+        ]
+        .into_iter()
+        .chain({
+            let mut dangling = vec![
+                Finding::Dangling {
+                    id: id_in(&dir, "dangling-blob"),
+                    kind: Kind::Blob,
+                },
+                Finding::Dangling {
+                    id: id_in(&dir, "unreachable-commit"),
+                    kind: Kind::Commit,
+                },
+            ];
+            dangling.sort_by_key(|finding| match finding {
+                Finding::Dangling { id, .. } => *id,
+                _ => unreachable!("only dangling objects"),
+            });
+            dangling
+        })
+        .collect::<Vec<_>>()
+    );
+    assert!(findings.iter().any(Finding::is_error));
+    assert_eq!(outcome.objects, 12, "4 commits, a tag, 3 trees and 4 blobs");
+    assert_eq!(outcome.reachable, outcome.objects - 4);
+    Ok(())
+}
+
+#[test]
+fn unreachable_objects_are_never_dangling() -> gix_testtools::Result {
+    let mut options = objects::Options {
+        unreachable: true,
+        ..Default::default()
+    };
+    options.severities.set(Id::ZeroPaddedDate, Severity::Ignore)?;
+    let (dir, findings, _outcome) = fsck(&options)?;
+
+    let mut expected: Vec<_> = [
+        ("dangling-blob", Kind::Blob),
+        ("unreachable-commit", Kind::Commit),
+        ("unreachable-tree", Kind::Tree),
+        ("unreachable-blob", Kind::Blob),
+    ]
+    .into_iter()
+    .map(|(name, kind)| (id_in(&dir, name), kind))
+    .collect();
+    expected.sort();
+    let actual: Vec<_> = findings
+        .iter()
+        .filter_map(|finding| match finding {
+            Finding::Unreachable { id, kind } => Some((*id, *kind)),
+            Finding::Dangling { .. } => unreachable!("dangling objects are reported as unreachable"),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(actual, expected);
+    assert!(
+        !findings.iter().any(|finding| matches!(
+            finding,
+            Finding::Message {
+                id: Id::ZeroPaddedDate,
+                ..
+            }
+        )),
+        "ignored messages aren't reported"
+    );
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::hash;
 
-use crate::{Kind, ObjectId, SIZE_OF_SHA1_DIGEST};
+use crate::{Kind, ObjectId, SIZE_OF_SHA1_DIGEST, SIZE_OF_SHA256_DIGEST};
 
 /// A borrowed reference to a hash identifying objects.
 ///
@@ -57,6 +57,7 @@ impl std::fmt::Debug for oid {
             "{}({})",
             match self.kind() {
                 Kind::Sha1 => "Sha1",
+                Kind::Sha256 => "Sha256",
             },
             self.to_hex(),
         )
@@ -77,7 +78,7 @@ impl oid {
     #[inline]
     pub fn try_from_bytes(digest: &[u8]) -> Result<&Self, Error> {
         match digest.len() {
-            20 => Ok(
+            20 | 32 => Ok(
                 #[allow(unsafe_code)]
                 unsafe {
                     &*(digest as *const [u8] as *const oid)
@@ -146,11 +147,12 @@ impl oid {
     pub fn is_null(&self) -> bool {
         match self.kind() {
             Kind::Sha1 => &self.bytes == oid::null_sha1().as_bytes(),
+            Kind::Sha256 => &self.bytes == oid::null_sha256().as_bytes(),
         }
     }
 }
 
-/// Sha1 specific methods
+/// Hex formatting
 impl oid {
     /// Write ourselves to the `out` in hexadecimal notation, returning the amount of written bytes.
     ///
@@ -176,6 +178,12 @@ impl oid {
     pub(crate) fn null_sha1() -> &'static Self {
         oid::from_bytes([0u8; SIZE_OF_SHA1_DIGEST].as_ref())
     }
+
+    /// Returns a Sha256 digest with all bytes being initialized to zero.
+    #[inline]
+    pub(crate) fn null_sha256() -> &'static Self {
+        oid::from_bytes([0u8; SIZE_OF_SHA256_DIGEST].as_ref())
+    }
 }
 
 impl AsRef<oid> for &oid {
@@ -198,6 +206,7 @@ impl ToOwned for oid {
     fn to_owned(&self) -> Self::Owned {
         match self.kind() {
             Kind::Sha1 => ObjectId::Sha1(self.bytes.try_into().expect("no bug in hash detection")),
+            Kind::Sha256 => ObjectId::Sha256(self.bytes.try_into().expect("no bug in hash detection")),
         }
     }
 }
@@ -208,6 +217,12 @@ impl<'a> From<&'a [u8; SIZE_OF_SHA1_DIGEST]> for &'a oid {
     }
 }
 
+impl<'a> From<&'a [u8; SIZE_OF_SHA256_DIGEST]> for &'a oid {
+    fn from(v: &'a [u8; SIZE_OF_SHA256_DIGEST]) -> Self {
+        oid::from_bytes(v.as_ref())
+    }
+}
+
 impl std::fmt::Display for &oid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for b in self.as_bytes() {
@@ -12,6 +12,17 @@ mod from_hex {
         fn twenty_hex_chars_uppercase() {
             assert!(ObjectId::from_hex(b"1234567890ABCDEFAAAAAAAAAAAAAAAAAAAAAAAA").is_ok());
         }
+
+        #[test]
+        fn sixty_four_hex_chars() {
+            let id = ObjectId::from_hex(b"1234567890abcdefaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+            assert_eq!(id.kind(), gix_hash::Kind::Sha256);
+            assert_eq!(
+                id.to_string(),
+                "1234567890abcdefaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "round-trips"
+            );
+        }
     }
 
     mod invalid {
@@ -46,19 +57,23 @@ mod empty {
     use gix_features::hash::hasher;
     use gix_hash::{Kind, ObjectId};
 
-    fn hash_contents(s: &[u8]) -> ObjectId {
-        let mut hasher = hasher(Kind::Sha1);
+    fn hash_contents(s: &[u8], kind: Kind) -> ObjectId {
+        let mut hasher = hasher(kind);
         hasher.update(s);
-        ObjectId::Sha1(hasher.digest())
+        hasher.try_finalize().expect("no collision")
     }
 
     #[test]
     fn blob() {
-        assert_eq!(ObjectId::empty_blob(Kind::Sha1), hash_contents(b"blob 0\0"));
+        for kind in [Kind::Sha1, Kind::Sha256] {
+            assert_eq!(ObjectId::empty_blob(kind), hash_contents(b"blob 0\0", kind));
+        }
     }
 
     #[test]
     fn tree() {
-        assert_eq!(ObjectId::empty_tree(Kind::Sha1), hash_contents(b"tree 0\0"));
+        for kind in [Kind::Sha1, Kind::Sha256] {
+            assert_eq!(ObjectId::empty_tree(kind), hash_contents(b"tree 0\0", kind));
+        }
     }
 }
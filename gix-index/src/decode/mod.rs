@@ -240,6 +240,10 @@ impl State {
             offset_table,
         } = ext;
         is_sparse |= is_sparse_from_ext;
+        let fs_monitor = fs_monitor.map(|mut fs_monitor| {
+            fs_monitor.set_entries(&entries, &path_backing);
+            fs_monitor
+        });
 
         Ok((
             State {
@@ -310,11 +314,11 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     let (size, data) = read_u32(data)?;
     Some((
         entry::Stat {
-            mtime: entry::stat::Time {
+            ctime: entry::stat::Time {
                 secs: ctime_secs,
                 nsecs: ctime_nsecs,
             },
-            ctime: entry::stat::Time {
+            mtime: entry::stat::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
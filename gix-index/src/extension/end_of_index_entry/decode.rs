@@ -29,11 +29,11 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<usize> {
 
     let (offset, checksum) = ext_data.split_at(4);
     let offset = from_be_u32(offset) as usize;
-    if offset < header::SIZE || offset > start_of_eoie || checksum.len() != gix_hash::Kind::Sha1.len_in_bytes() {
+    if offset < header::SIZE || offset > start_of_eoie || checksum.len() != hash_len {
         return None;
     }
 
-    let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha1);
+    let mut hasher = gix_features::hash::hasher(object_hash);
     let mut last_chunk = None;
     for (signature, chunk) in extension::Iter::new(&data[offset..data.len() - MIN_SIZE_WITH_HEADER - hash_len]) {
         hasher.update(&signature);
@@ -41,7 +41,7 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<usize> {
         last_chunk = Some(chunk);
     }
 
-    if hasher.digest() != checksum {
+    if hasher.try_finalize().ok()?.as_slice() != checksum {
         return None;
     }
     // The last-to-this chunk ends where ours starts
@@ -1,12 +1,13 @@
-use bstr::BString;
+use bstr::{BString, ByteSlice};
 
 use crate::{
+    entry,
     extension::{FsMonitor, Signature},
     util::{read_u32, read_u64, split_at_byte_exclusive},
+    Entry, PathStorageRef,
 };
 
 #[derive(Clone)]
-#[allow(dead_code)]
 pub enum Token {
     V1 { nanos_since_1970: u64 },
     V2 { token: BString },
@@ -35,5 +36,184 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
         return None;
     }
 
-    FsMonitor { token, entry_dirty }.into()
+    FsMonitor {
+        token,
+        entry_dirty,
+        clean: Vec::new(),
+    }
+    .into()
+}
+
+impl FsMonitor {
+    /// Associate our bitmap with `entries` and their `path_backing`, which are the ones it was read with.
+    ///
+    /// Remember all entries that aren't marked dirty so they can be recognized when writing.
+    pub(crate) fn set_entries(&mut self, entries: &[Entry], path_backing: &PathStorageRef) {
+        let mut dirty = Vec::new();
+        self.entry_dirty.for_each_set_bit(|idx| {
+            dirty.push(idx);
+            Some(())
+        });
+        let mut dirty = dirty.into_iter().peekable();
+        self.clean = entries
+            .iter()
+            .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+            .enumerate()
+            .filter_map(|(idx, entry)| {
+                while dirty.next_if(|dirty_idx| *dirty_idx < idx).is_some() {}
+                dirty
+                    .next_if_eq(&idx)
+                    .is_none()
+                    .then(|| CleanEntry::new(entry, path_backing))
+            })
+            .collect();
+    }
+
+    /// Return a copy of ourselves whose bitmap is valid for `entries` with `path_backing`, while the token is retained.
+    ///
+    /// Entries are matched by path and stage with the ones the bitmap was read with, and only those that didn't change
+    /// at all keep their clean state. All others, including new entries, are marked dirty to let git check them.
+    pub(crate) fn for_entries(&self, entries: &[Entry], path_backing: &PathStorageRef) -> FsMonitor {
+        let mut clean = self.clean.iter().peekable();
+        let entry_dirty = gix_bitmap::ewah::Vec::from_set_bits(
+            entries
+                .iter()
+                .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                .enumerate()
+                .filter_map(|(idx, entry)| {
+                    let key = (entry.path_in(path_backing), entry.stage_raw());
+                    // Both are sorted by path and stage, so we can skip everything that sorts before the current entry.
+                    while clean.next_if(|c| (c.path.as_bstr(), c.stage) < key).is_some() {}
+                    clean
+                        .next_if(|c| (c.path.as_bstr(), c.stage) == key && c.is_unchanged(entry))
+                        .is_none()
+                        .then_some(idx)
+                }),
+        );
+        FsMonitor {
+            token: self.token.clone(),
+            entry_dirty,
+            clean: self.clean.clone(),
+        }
+    }
+
+    /// Serialize this instance to `out`.
+    pub(crate) fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut bitmap = Vec::new();
+        self.entry_dirty.write_to(&mut bitmap)?;
+        data.extend_from_slice(&(u32::try_from(bitmap.len()).expect("less than 4GB bitmap")).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+/// An entry that was not dirty according to the fs monitor, to find it among the entries to write.
+#[derive(Clone)]
+pub(crate) struct CleanEntry {
+    path: BString,
+    stage: entry::StageRaw,
+    id: gix_hash::ObjectId,
+    stat: entry::Stat,
+    mode: entry::Mode,
+}
+
+impl CleanEntry {
+    fn new(entry: &Entry, path_backing: &PathStorageRef) -> Self {
+        CleanEntry {
+            path: entry.path_in(path_backing).to_owned(),
+            stage: entry.stage_raw(),
+            id: entry.id,
+            stat: entry.stat,
+            mode: entry.mode,
+        }
+    }
+
+    fn is_unchanged(&self, entry: &Entry) -> bool {
+        self.id == entry.id && self.stat == entry.stat && self.mode == entry.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::{Flags, Mode, Stat};
+
+    fn entries(paths: &[(&str, u8)]) -> (Vec<Entry>, Vec<u8>) {
+        let mut path_backing = Vec::new();
+        let entries = paths
+            .iter()
+            .map(|(path, id)| {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(path.as_bytes());
+                Entry {
+                    stat: Stat::default(),
+                    id: gix_hash::ObjectId::from_bytes_or_panic(&[*id; 20]),
+                    flags: Flags::empty(),
+                    mode: Mode::FILE,
+                    path: start..path_backing.len(),
+                }
+            })
+            .collect();
+        (entries, path_backing)
+    }
+
+    fn dirty(fs_monitor: &FsMonitor) -> Vec<usize> {
+        let mut out = Vec::new();
+        fs_monitor.entry_dirty.for_each_set_bit(|idx| {
+            out.push(idx);
+            Some(())
+        });
+        out
+    }
+
+    #[test]
+    fn bits_are_mapped_by_path_and_only_unchanged_entries_stay_clean() {
+        let (read_entries, read_backing) = entries(&[("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+        let mut fs_monitor = FsMonitor {
+            token: Token::V1 { nanos_since_1970: 42 },
+            entry_dirty: gix_bitmap::ewah::Vec::from_set_bits([1]),
+            clean: Vec::new(),
+        };
+        fs_monitor.set_entries(&read_entries, &read_backing);
+        assert_eq!(
+            dirty(&fs_monitor.for_entries(&read_entries, &read_backing)),
+            [1],
+            "unchanged entries keep their bits"
+        );
+
+        let (entries, backing) = entries(&[("a", 1), ("a-new", 5), ("b", 2), ("c", 6), ("d", 4)]);
+        assert_eq!(
+            dirty(&fs_monitor.for_entries(&entries, &backing)),
+            [1, 2, 3],
+            "new and changed entries are dirty, and bits of unchanged ones move along with their entry"
+        );
+
+        let (entries, backing) = entries_without_first(&read_entries, &read_backing);
+        assert_eq!(
+            dirty(&fs_monitor.for_entries(&entries, &backing)),
+            [0],
+            "removed entries shift all bits after them"
+        );
+    }
+
+    fn entries_without_first(entries: &[Entry], path_backing: &[u8]) -> (Vec<Entry>, Vec<u8>) {
+        let mut entries = entries.to_vec();
+        entries[0].flags.insert(Flags::REMOVE);
+        (entries, path_backing.to_vec())
+    }
 }
@@ -41,3 +41,17 @@ pub fn find(extensions: &[u8], object_hash: gix_hash::Kind) -> Option<Vec<Offset
         .find_map(|(sig, ext_data)| (sig == SIGNATURE).then_some(ext_data))
         .and_then(decode)
 }
+
+/// Write `offsets` as index-entry-offset-table extension to `out`.
+pub fn write_to(mut out: impl std::io::Write, offsets: &[Offset]) -> Result<(), std::io::Error> {
+    out.write_all(&SIGNATURE)?;
+    let extension_size = 4 + offsets.len() as u32 * (4 + 4);
+    out.write_all(&extension_size.to_be_bytes())?;
+
+    out.write_all(&1_u32.to_be_bytes())?;
+    for offset in offsets {
+        out.write_all(&offset.from_beginning_of_file.to_be_bytes())?;
+        out.write_all(&offset.num_entries.to_be_bytes())?;
+    }
+    Ok(())
+}
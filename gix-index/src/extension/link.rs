@@ -1,6 +1,10 @@
+use std::cmp::Ordering;
+
 use crate::{
+    entry,
     extension::{Link, Signature},
     util::split_at_pos,
+    Entry, PathStorage, State,
 };
 
 /// The signature of the link extension.
@@ -90,10 +94,11 @@ impl Link {
             },
         )?;
 
-        if let Some(bitmaps) = self.bitmaps {
-            let mut split_entry_index = 0;
+        split_index.shared_index = Some(Box::new(shared_index.clone()));
 
-            let mut err = None;
+        let mut split_entry_index = 0;
+        let mut err = None;
+        if let Some(bitmaps) = &self.bitmaps {
             bitmaps.replace.for_each_set_bit(|replace_index| {
                 let shared_entry = match shared_index.entries.get_mut(replace_index) {
                     Some(e) => e,
@@ -134,20 +139,22 @@ impl Link {
             if let Some(err) = err {
                 return Err(err.into());
             }
+        }
 
-            let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
-            for mut split_entry in split_index.entries.drain(split_entry_index..) {
-                let start = shared_index.path_backing.len();
-                let split_index_path = split_entry.path.clone();
+        let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
+        for mut split_entry in split_index.entries.drain(split_entry_index..) {
+            let start = shared_index.path_backing.len();
+            let split_index_path = split_entry.path.clone();
 
-                split_entry.path = start..start + split_entry.path.len();
-                shared_index.entries.push(split_entry);
+            split_entry.path = start..start + split_entry.path.len();
+            shared_index.entries.push(split_entry);
 
-                shared_index
-                    .path_backing
-                    .extend_from_slice(&split_index_path_backing[split_index_path]);
-            }
+            shared_index
+                .path_backing
+                .extend_from_slice(&split_index_path_backing[split_index_path]);
+        }
 
+        if let Some(bitmaps) = &self.bitmaps {
             bitmaps.delete.for_each_set_bit(|delete_index| {
                 let shared_entry = match shared_index.entries.get_mut(delete_index) {
                     Some(e) => e,
@@ -162,18 +169,117 @@ impl Link {
             if let Some(err) = err {
                 return Err(err.into());
             }
+        }
 
-            shared_index
-                .entries
-                .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
+        shared_index
+            .entries
+            .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
 
-            let mut shared_entries = std::mem::take(&mut shared_index.entries);
-            shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
+        let mut shared_entries = std::mem::take(&mut shared_index.entries);
+        shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
 
-            split_index.entries = shared_entries;
-            split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
-        }
+        split_index.entries = shared_entries;
+        split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
 
         Ok(())
     }
 }
+
+impl Link {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        let mut data = Vec::with_capacity(self.shared_index_checksum.as_slice().len());
+        data.extend_from_slice(self.shared_index_checksum.as_slice());
+        if let Some(bitmaps) = &self.bitmaps {
+            bitmaps.delete.write_to(&mut data)?;
+            bitmaps.replace.write_to(&mut data)?;
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB link extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+/// The entries of an index which aren't stored in its shared index, along with the bitmaps to apply to the shared index.
+pub(crate) struct Split {
+    /// Replacements of shared entries with empty paths in the order of the replace bitmap, followed by all new entries.
+    pub entries: Vec<Entry>,
+    pub path_backing: PathStorage,
+    pub bitmaps: Bitmaps,
+}
+
+/// Compute how the entries of `state` differ from the entries of `shared`, the shared index it is supposed to be split from.
+///
+/// Entries marked for removal are ignored and thus deleted if they exist in `shared`.
+pub(crate) fn split(state: &State, shared: &State) -> Split {
+    let mut replacements = Vec::new();
+    let mut additions = Vec::new();
+    let mut path_backing = Vec::new();
+    let mut replaced_indices = Vec::new();
+    let mut deleted_indices = Vec::new();
+
+    let mut entries = state
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .peekable();
+    let mut shared_entries = shared.entries().iter().enumerate().peekable();
+    loop {
+        let ordering = match (entries.peek(), shared_entries.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(entry), Some((_, shared_entry))) => {
+                Entry::cmp_filepaths(entry.path(state), shared_entry.path(shared))
+                    .then_with(|| entry.stage().cmp(&shared_entry.stage()))
+            }
+        };
+        match ordering {
+            Ordering::Less => {
+                let entry = entries.next().expect("peeked");
+                let path = entry.path(state);
+                let start = path_backing.len();
+                path_backing.extend_from_slice(path);
+                additions.push(Entry {
+                    path: start..path_backing.len(),
+                    ..entry.clone()
+                });
+            }
+            Ordering::Greater => {
+                let (shared_index, _) = shared_entries.next().expect("peeked");
+                deleted_indices.push(shared_index);
+            }
+            Ordering::Equal => {
+                let entry = entries.next().expect("peeked");
+                let (shared_index, shared_entry) = shared_entries.next().expect("peeked");
+                if !is_same_at_rest(entry, shared_entry) {
+                    replaced_indices.push(shared_index);
+                    replacements.push(Entry {
+                        path: 0..0,
+                        ..entry.clone()
+                    });
+                }
+            }
+        }
+    }
+
+    replacements.extend(additions);
+    Split {
+        entries: replacements,
+        path_backing,
+        bitmaps: Bitmaps {
+            delete: gix_bitmap::ewah::Vec::from_set_bits(deleted_indices),
+            replace: gix_bitmap::ewah::Vec::from_set_bits(replaced_indices),
+        },
+    }
+}
+
+/// Return `true` if `a` and `b` would be written the same, disregarding their paths.
+fn is_same_at_rest(a: &Entry, b: &Entry) -> bool {
+    a.stat == b.stat
+        && a.id == b.id
+        && a.mode == b.mode
+        && a.flags.to_storage().bits() == b.flags.to_storage().bits()
+        && entry::at_rest::FlagsExtended::from_flags(a.flags) == entry::at_rest::FlagsExtended::from_flags(b.flags)
+}
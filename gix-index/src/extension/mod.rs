@@ -42,7 +42,6 @@ pub struct Link {
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
@@ -61,12 +60,13 @@ pub struct UntrackedCache {
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct FsMonitor {
     token: fs_monitor::Token,
     /// if a bit is true, the respective entry is NOT valid as per the fs monitor.
     entry_dirty: gix_bitmap::ewah::Vec,
+    /// The entries (without removed ones) that weren't dirty at the time `entry_dirty` was read, as the bitmap is only valid for these.
+    clean: Vec<fs_monitor::CleanEntry>,
 }
 
 mod iter;
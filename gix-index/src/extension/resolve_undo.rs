@@ -1,3 +1,5 @@
+use std::io::Write;
+
 use bstr::BString;
 use gix_hash::ObjectId;
 
@@ -8,7 +10,6 @@ use crate::{
 
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
@@ -18,7 +19,6 @@ pub struct ResolvePath {
     stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
 #[derive(Clone, Copy)]
 pub struct Stage {
     mode: u32,
@@ -62,3 +62,22 @@ pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     }
     out.into()
 }
+
+/// Serialize `paths` as resolve-undo extension to `out`.
+pub fn write_to(mut out: impl std::io::Write, paths: &Paths) -> Result<(), std::io::Error> {
+    let mut data = Vec::new();
+    for path in paths {
+        data.extend_from_slice(&path.name);
+        data.push(0);
+        for stage in &path.stages {
+            write!(data, "{:o}\0", stage.map_or(0, |stage| stage.mode))?;
+        }
+        for stage in path.stages.iter().flatten() {
+            data.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(data.len()).expect("less than 4GB resolve-undo extension")).to_be_bytes())?;
+    out.write_all(&data)
+}
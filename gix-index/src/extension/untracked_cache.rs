@@ -4,7 +4,7 @@ use gix_hash::ObjectId;
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
-    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int, write_var_int},
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
@@ -16,6 +16,13 @@ pub struct OidStat {
     pub id: ObjectId,
 }
 
+impl OidStat {
+    /// Git writes both null stat and null id if the file isn't known, but may keep the stat of an empty file.
+    fn is_unset(&self) -> bool {
+        self.id.is_null() && self.stat == entry::Stat::default()
+    }
+}
+
 /// A directory with information about its untracked files, and its sub-directories
 #[derive(Clone)]
 pub struct Directory {
@@ -56,8 +63,8 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
 
     let mut res = UntrackedCache {
         identifier: identifier.into(),
-        info_exclude: (!info_exclude.id.is_null()).then_some(info_exclude),
-        excludes_file: (!excludes_file.id.is_null()).then_some(excludes_file),
+        info_exclude: (!info_exclude.is_unset()).then_some(info_exclude),
+        excludes_file: (!excludes_file.is_unset()).then_some(excludes_file),
         exclude_filename_per_dir: exclude_filename_per_dir.into(),
         dir_flags,
         directories: Vec::new(),
@@ -152,3 +159,108 @@ fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
         data,
     ))
 }
+
+impl UntrackedCache {
+    /// Serialize this instance to `out`, assuming object hashes are of type `object_hash`.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: gix_hash::Kind) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        write_var_int(&mut data, self.identifier.len() as u64)?;
+        data.extend_from_slice(&self.identifier);
+        for oid_stat in [&self.info_exclude, &self.excludes_file] {
+            match oid_stat {
+                Some(OidStat { stat, id }) => {
+                    write_stat(&mut data, stat)?;
+                    data.extend_from_slice(id.as_bytes());
+                }
+                None => {
+                    write_stat(&mut data, &entry::Stat::default())?;
+                    data.extend_from_slice(object_hash.null().as_bytes());
+                }
+            }
+        }
+        data.extend_from_slice(&self.dir_flags.to_be_bytes());
+        data.extend_from_slice(&self.exclude_filename_per_dir);
+        data.push(0);
+
+        write_var_int(&mut data, self.directories.len() as u64)?;
+        if let Some(root) = self.directories.first() {
+            let mut blocks = Blocks::default();
+            write_directory_block(&mut data, root, &self.directories, &mut blocks)?;
+
+            for bitmap in [blocks.valid, blocks.check_only, blocks.hash_valid] {
+                gix_bitmap::ewah::Vec::from_set_bits(bitmap).write_to(&mut data)?;
+            }
+            data.extend_from_slice(&blocks.stats);
+            data.extend_from_slice(&blocks.hashes);
+            data.push(0);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB untracked cache")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+}
+
+/// Information about all directory blocks that is written after them.
+#[derive(Default)]
+struct Blocks {
+    /// The amount of blocks written so far.
+    count: usize,
+    valid: Vec<usize>,
+    check_only: Vec<usize>,
+    hash_valid: Vec<usize>,
+    stats: Vec<u8>,
+    hashes: Vec<u8>,
+}
+
+fn write_directory_block(
+    out: &mut Vec<u8>,
+    dir: &Directory,
+    directories: &[Directory],
+    blocks: &mut Blocks,
+) -> Result<(), std::io::Error> {
+    let index = blocks.count;
+    blocks.count += 1;
+    if dir.check_only {
+        blocks.check_only.push(index);
+    }
+    if let Some(stat) = &dir.stat {
+        blocks.valid.push(index);
+        write_stat(&mut blocks.stats, stat)?;
+    }
+    if let Some(id) = &dir.exclude_file_oid {
+        blocks.hash_valid.push(index);
+        blocks.hashes.extend_from_slice(id.as_bytes());
+    }
+
+    write_var_int(&mut *out, dir.untracked_entries.len() as u64)?;
+    write_var_int(&mut *out, dir.sub_directories.len() as u64)?;
+    out.extend_from_slice(&dir.name);
+    out.push(0);
+    for name in &dir.untracked_entries {
+        out.extend_from_slice(name);
+        out.push(0);
+    }
+    for subdir_index in &dir.sub_directories {
+        write_directory_block(out, &directories[*subdir_index], directories, blocks)?;
+    }
+    Ok(())
+}
+
+/// Write `stat` in the format understood by [`crate::decode::stat()`].
+fn write_stat(mut out: impl std::io::Write, stat: &entry::Stat) -> Result<(), std::io::Error> {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.write_all(&field.to_be_bytes())?;
+    }
+    Ok(())
+}
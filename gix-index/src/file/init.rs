@@ -96,9 +96,17 @@ impl File {
         };
 
         let (state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
-        let mut file = File { state, path, checksum };
+        let mut file = File {
+            state,
+            path,
+            checksum,
+            shared_index: None,
+        };
         if let Some(mut link) = file.link.take() {
             link.dissolve_into(&mut file, object_hash, skip_hash, options)?;
+            if let Some(fs_monitor) = file.state.fs_monitor.as_mut() {
+                fs_monitor.set_entries(&file.state.entries, &file.state.path_backing);
+            }
         }
 
         Ok(file)
@@ -112,6 +120,7 @@ impl File {
             state,
             path: path.into(),
             checksum: None,
+            shared_index: None,
         }
     }
 }
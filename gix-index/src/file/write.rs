@@ -1,6 +1,8 @@
+use std::path::PathBuf;
+
 use gix_features::hash;
 
-use crate::{write, File, Version};
+use crate::{entry, extension, write, File, State, Version};
 
 /// The error produced by [`File::write()`].
 #[derive(Debug, thiserror::Error)]
@@ -17,28 +19,23 @@ pub enum Error {
 impl File {
     /// Write the index to `out` with `options`, to be readable by [`File::at()`], returning the version that was actually written
     /// to retain all information of this index.
+    ///
+    /// Note that this always writes all entries, even if this index was read from a split index.
     pub fn write_to(
         &self,
-        mut out: impl std::io::Write,
+        out: impl std::io::Write,
         options: write::Options,
     ) -> std::io::Result<(Version, gix_hash::ObjectId)> {
         let _span = gix_features::trace::detail!("gix_index::File::write_to()", skip_hash = options.skip_hash);
-        let (version, hash) = if options.skip_hash {
-            let out: &mut dyn std::io::Write = &mut out;
-            let version = self.state.write_to(out, options)?;
-            (version, self.state.object_hash.null())
-        } else {
-            let mut hasher = hash::Write::new(&mut out, self.state.object_hash);
-            let out: &mut dyn std::io::Write = &mut hasher;
-            let version = self.state.write_to(out, options)?;
-            (version, gix_hash::ObjectId::from(hasher.hash.digest()))
-        };
-        out.write_all(hash.as_slice())?;
-        Ok((version, hash))
+        write_state_to(&self.state, out, options)
     }
 
     /// Write ourselves to the path we were read from after acquiring a lock, using `options`.
     ///
+    /// If [a split index](write::SplitIndex) is to be written, only entries that differ from the shared index are written
+    /// to our path, along with a `link` extension to refer to the shared index. The shared index is written next to us if
+    /// there is none yet, or if too many entries changed compared to it.
+    ///
     /// Note that the hash produced will be stored which is why we need to be mutable.
     pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write()", path = ?self.path);
@@ -46,7 +43,13 @@ impl File {
             64 * 1024,
             gix_lock::File::acquire_to_update_resource(&self.path, gix_lock::acquire::Fail::Immediately, None)?,
         );
-        let (version, digest) = self.write_to(&mut lock, options)?;
+        let write_split_index = options.split_index.enabled.unwrap_or(self.shared_index.is_some()) && !self.is_sparse();
+        let (version, digest) = if write_split_index {
+            self.write_split_index_to(&mut lock, options)?
+        } else {
+            self.shared_index = None;
+            self.write_to(&mut lock, options)?
+        };
         match lock.into_inner() {
             Ok(lock) => lock.commit()?,
             Err(err) => return Err(err.into_error().into()),
@@ -56,3 +59,149 @@ impl File {
         Ok(())
     }
 }
+
+/// Split index
+impl File {
+    /// Write the entries that aren't in our shared index to `out`, after writing a new shared index if needed.
+    fn write_split_index_to(
+        &mut self,
+        out: impl std::io::Write,
+        options: write::Options,
+    ) -> Result<(Version, gix_hash::ObjectId), Error> {
+        let split = self
+            .shared_index
+            .as_deref()
+            .filter(|shared| self.freshen_shared_index(shared))
+            .map(|shared| extension::link::split(&self.state, shared))
+            .filter(|split| !self.has_too_many_unshared_entries(split, options.split_index.max_percent_change));
+        let split = match split {
+            Some(split) => split,
+            None => {
+                let shared = self.write_shared_index(options.thread_limit)?;
+                let split = extension::link::split(&self.state, &shared);
+                self.shared_index = Some(Box::new(shared));
+                split
+            }
+        };
+
+        let shared = self.shared_index.as_deref().expect("set or written");
+        let state = State {
+            object_hash: self.object_hash,
+            timestamp: self.timestamp,
+            version: self.version,
+            entries: split.entries,
+            path_backing: split.path_backing,
+            is_sparse: false,
+            end_of_index_at_decode_time: false,
+            offset_table_at_decode_time: false,
+            tree: self.tree.clone(),
+            link: Some(extension::Link {
+                shared_index_checksum: shared.checksum.expect("shared indices are always hashed"),
+                bitmaps: Some(split.bitmaps),
+            }),
+            resolve_undo: self.resolve_undo.clone(),
+            untracked: self.untracked.clone(),
+            // The bitmap refers to all entries, not only to the ones in the split index.
+            fs_monitor: self
+                .fs_monitor
+                .as_ref()
+                .map(|fs_monitor| fs_monitor.for_entries(&self.entries, &self.path_backing)),
+        };
+        let (_split_version, digest) = write_state_to(&state, out, options)?;
+        Ok((self.detect_required_version(), digest))
+    }
+
+    /// Write all of our entries into a new shared index next to us using up to `thread_limit` threads, and return it.
+    fn write_shared_index(&self, thread_limit: Option<usize>) -> Result<File, Error> {
+        let state = State {
+            object_hash: self.object_hash,
+            timestamp: self.timestamp,
+            version: self.version,
+            entries: self
+                .entries
+                .iter()
+                .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                .cloned()
+                .collect(),
+            path_backing: self.path_backing.clone(),
+            is_sparse: false,
+            end_of_index_at_decode_time: false,
+            offset_table_at_decode_time: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        };
+        // The checksum is needed to name the file, so it can't be written directly.
+        let mut buf = Vec::new();
+        let (version, checksum) = write_state_to(
+            &state,
+            &mut buf,
+            write::Options {
+                extensions: write::Extensions::None,
+                skip_hash: false,
+                split_index: Default::default(),
+                thread_limit,
+            },
+        )?;
+
+        let path = self.shared_index_path(checksum);
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        std::io::Write::write_all(&mut lock, &buf)?;
+        lock.commit()?;
+
+        let mut shared = File::from_state(state, path);
+        shared.version = version;
+        shared.checksum = Some(checksum);
+        Ok(shared)
+    }
+
+    /// Return `true` if `shared` exists next to us, and mark it as recently used like git does to prevent it from expiring.
+    fn freshen_shared_index(&self, shared: &File) -> bool {
+        shared.checksum.map_or(false, |checksum| {
+            filetime::set_file_mtime(self.shared_index_path(checksum), filetime::FileTime::now()).is_ok()
+        })
+    }
+
+    fn has_too_many_unshared_entries(&self, split: &extension::link::Split, max_percent_change: u8) -> bool {
+        match max_percent_change {
+            0 => true,
+            100.. => false,
+            max_percent_change => {
+                let num_entries = self
+                    .entries
+                    .iter()
+                    .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                    .count();
+                num_entries * usize::from(max_percent_change) < split.entries.len() * 100
+            }
+        }
+    }
+
+    fn shared_index_path(&self, checksum: gix_hash::ObjectId) -> PathBuf {
+        self.path
+            .parent()
+            .expect("split index file in .git folder")
+            .join(format!("sharedindex.{checksum}"))
+    }
+}
+
+fn write_state_to(
+    state: &State,
+    mut out: impl std::io::Write,
+    options: write::Options,
+) -> std::io::Result<(Version, gix_hash::ObjectId)> {
+    let (version, hash) = if options.skip_hash {
+        let out: &mut dyn std::io::Write = &mut out;
+        let version = state.write_to(out, options)?;
+        (version, state.object_hash.null())
+    } else {
+        let mut hasher = hash::Write::new(&mut out, state.object_hash);
+        let out: &mut dyn std::io::Write = &mut hasher;
+        let version = state.write_to(out, options)?;
+        (version, hasher.hash.try_finalize()?)
+    };
+    out.write_all(hash.as_slice())?;
+    Ok((version, hash))
+}
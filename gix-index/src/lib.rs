@@ -77,6 +77,8 @@ pub struct File {
     pub(crate) path: PathBuf,
     /// The checksum of all bytes prior to the checksum itself.
     pub(crate) checksum: Option<gix_hash::ObjectId>,
+    /// The shared index we were split from when reading or writing, or `None` if this isn't a split index.
+    pub(crate) shared_index: Option<Box<File>>,
 }
 
 /// The type to use and store paths to all entries.
@@ -197,6 +199,23 @@ pub(crate) mod util {
         (num, data).into()
     }
 
+    /// Write `n` to `out` in the variable-length encoding understood by [`var_int()`].
+    pub fn write_var_int(mut out: impl std::io::Write, mut n: u64) -> std::io::Result<()> {
+        let mut buf = [0u8; 10];
+        let mut pos = buf.len() - 1;
+        buf[pos] = (n & 0x7f) as u8;
+        loop {
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+            n -= 1;
+            pos -= 1;
+            buf[pos] = 0x80 | (n & 0x7f) as u8;
+        }
+        out.write_all(&buf[pos..])
+    }
+
     #[inline]
     pub fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
         split_at_pos(data, 4).map(|(num, data)| (u32::from_be_bytes(num.try_into().unwrap()), data))
@@ -240,7 +259,7 @@ pub(crate) mod util {
 
 #[test]
 fn size_of_entry() {
-    assert_eq!(std::mem::size_of::<crate::Entry>(), 80);
+    assert_eq!(std::mem::size_of::<crate::Entry>(), 96);
 
     // the reason we have our own time is half the size.
     assert_eq!(std::mem::size_of::<crate::entry::stat::Time>(), 8);
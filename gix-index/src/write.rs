@@ -1,6 +1,8 @@
 use std::io::Write;
 
-use crate::{entry, extension, write::util::CountBytes, State, Version};
+use crate::{
+    entry, extension, extension::index_entry_offset_table::Offset, write::util::CountBytes, Entry, State, Version,
+};
 
 /// A way to specify which of the optional extensions to write.
 #[derive(Default, Debug, Copy, Clone)]
@@ -17,8 +19,16 @@ pub enum Extensions {
     Given {
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
+        /// Write the index-entry-offset-table extension, if entries were written in more than one block.
+        index_entry_offset_table: bool,
     },
     /// Write no optional extension at all for what should be the smallest possible index
     None,
@@ -32,10 +42,18 @@ impl Extensions {
             Extensions::All => Some(signature),
             Extensions::Given {
                 tree_cache,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
+                index_entry_offset_table,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
+                extension::index_entry_offset_table::SIGNATURE => index_entry_offset_table,
                 _ => &false,
             }
             .then(|| signature),
@@ -56,6 +74,43 @@ pub struct Options {
     /// via [`File::write()`](crate::File::write()) and [`File::write_to()`](crate::File::write_to()).
     /// Note that
     pub skip_hash: bool,
+    /// Configure if and how a split index is written.
+    ///
+    /// Only respected by [`File::write()`](crate::File::write()).
+    pub split_index: SplitIndex,
+    /// If `Some(N)`, write entries in `N` blocks using up to `N` threads, with `Some(1)` writing them in one block on the current thread.
+    /// If `Some(0)|None`, use as many threads as there are logical cores, but only if there are enough entries for it to be worth it.
+    ///
+    /// Entries written in more than one block can be read in parallel as well if the `IEOT` extension is written.
+    ///
+    /// This value is typically controlled by `index.threads`.
+    pub thread_limit: Option<usize>,
+}
+
+/// The options to control writing a split index, which keeps most entries in a shared index file which changes rarely.
+///
+/// Note that split indices are never written for sparse indices.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitIndex {
+    /// If `Some(true)`, always write a split index, and if `Some(false)`, always write a regular index, possibly
+    /// turning a split index into a regular one. If `None`, write a split index only if the index was read as one.
+    ///
+    /// This value is typically controlled by `core.splitIndex`.
+    pub enabled: Option<bool>,
+    /// The percentage of all entries which may be stored in the split index before a new shared index is written,
+    /// with `0` writing a new shared index each time, and `100` never doing so unless there is none yet.
+    ///
+    /// This value is typically controlled by `splitIndex.maxPercentChange`.
+    pub max_percent_change: u8,
+}
+
+impl Default for SplitIndex {
+    fn default() -> Self {
+        SplitIndex {
+            enabled: None,
+            max_percent_change: 20,
+        }
+    }
 }
 
 impl State {
@@ -66,6 +121,8 @@ impl State {
         Options {
             extensions,
             skip_hash: _,
+            split_index: _,
+            thread_limit,
         }: Options,
     ) -> std::io::Result<Version> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
@@ -86,8 +143,8 @@ impl State {
             .expect("definitely not too many entries");
 
         let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
-        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
+        let (offset_to_extensions, entry_offsets) = entries(&mut write, self, offset_to_entries, thread_limit)?;
+        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, entry_offsets, extensions)?;
 
         if num_entries > 0
             && extensions
@@ -105,6 +162,7 @@ impl State {
         &self,
         mut write: CountBytes<T>,
         offset_to_extensions: u32,
+        entry_offsets: Option<Vec<Offset>>,
         extensions: Extensions,
     ) -> std::io::Result<(Vec<(extension::Signature, u32)>, T)>
     where
@@ -112,11 +170,57 @@ impl State {
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                extensions
+                    .should_write(extension::index_entry_offset_table::SIGNATURE)
+                    .and_then(|signature| {
+                        entry_offsets.as_deref().map(|offsets| {
+                            extension::index_entry_offset_table::write_to(write, offsets).map(|_| signature)
+                        })
+                    })
+            },
+            &|write| {
+                self.link
+                    .as_ref()
+                    .map(|link| link.write_to(write).map(|_| extension::link::SIGNATURE))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(write, paths).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|cache| cache.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor().map(|fs_monitor| {
+                            // A split index can't know all entries, so its bitmap was prepared when splitting.
+                            match self.link {
+                                Some(_) => fs_monitor.write_to(write),
+                                None => fs_monitor
+                                    .for_entries(&self.entries, &self.path_backing)
+                                    .write_to(write),
+                            }
+                            .map(|_| signature)
+                        })
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -124,7 +228,7 @@ impl State {
         ];
 
         let mut offset_to_previous_ext = offset_to_extensions;
-        let mut out = Vec::with_capacity(5);
+        let mut out = Vec::with_capacity(7);
         for write_ext in extensions {
             if let Some(signature) = write_ext(&mut write).transpose()? {
                 let offset_past_ext = write.count;
@@ -138,7 +242,7 @@ impl State {
 }
 
 impl State {
-    fn detect_required_version(&self) -> Version {
+    pub(crate) fn detect_required_version(&self) -> Version {
         self.entries
             .iter()
             .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then_some(Version::V3))
@@ -164,25 +268,75 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
-    for entry in state.entries() {
-        if entry.flags.contains(entry::Flags::REMOVE) {
-            continue;
+/// The minimum amount of entries to write per thread, as spawning threads and allocating buffers isn't free.
+const MIN_ENTRIES_PER_THREAD: usize = 10_000;
+
+/// Write all entries of `state` to `out`, in multiple blocks if `thread_limit` permits, and return the offset past the entries
+/// along with the offsets of each block if there was more than one.
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    header_size: u32,
+    thread_limit: Option<usize>,
+) -> Result<(u32, Option<Vec<Offset>>), std::io::Error> {
+    let num_entries = state.entries.len();
+    let num_blocks = match thread_limit {
+        Some(num_threads) if num_threads > 0 => num_threads.min(num_entries),
+        _ => gix_features::parallel::num_threads(thread_limit).min(num_entries / MIN_ENTRIES_PER_THREAD),
+    };
+    if num_blocks <= 1 {
+        for entry in state.entries() {
+            write_entry(&mut *out, entry, state, header_size)?;
         }
-        entry.write_to(&mut *out, state)?;
-        match (out.count - header_size) % 8 {
-            0 => {}
-            n => {
-                let eight_null_bytes = [0u8; 8];
-                out.write_all(&eight_null_bytes[n as usize..])?;
-            }
-        };
+        return Ok((out.count, None));
     }
 
-    Ok(out.count)
+    let block_size = (num_entries + num_blocks - 1) / num_blocks;
+    let offsets = gix_features::parallel::in_parallel_if(
+        || true,
+        state.entries.chunks(block_size).enumerate(),
+        thread_limit,
+        |_| (),
+        |(block_id, entries), _| {
+            let mut block = CountBytes::new(Vec::new());
+            let mut num_entries = 0;
+            for entry in entries {
+                num_entries += u32::from(write_entry(&mut block, entry, state, 0)?);
+            }
+            Ok((block_id, block.inner, num_entries))
+        },
+        util::InOrderBlocks::new(out),
+    )?;
+    Ok((out.count, Some(offsets)))
+}
+
+/// Write `entry` to `out` unless it is marked for removal, and return `true` if it was written.
+/// `header_size` is the offset at which the first entry was written to `out`, for alignment.
+fn write_entry<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    entry: &Entry,
+    state: &State,
+    header_size: u32,
+) -> Result<bool, std::io::Error> {
+    if entry.flags.contains(entry::Flags::REMOVE) {
+        return Ok(false);
+    }
+    entry.write_to(&mut *out, state)?;
+    match (out.count - header_size) % 8 {
+        0 => {}
+        n => {
+            let eight_null_bytes = [0u8; 8];
+            out.write_all(&eight_null_bytes[n as usize..])?;
+        }
+    };
+    Ok(true)
 }
 
 mod util {
+    use std::collections::BTreeMap;
+
+    use crate::extension::index_entry_offset_table::Offset;
+
     pub struct CountBytes<T> {
         pub count: u32,
         pub inner: T,
@@ -219,4 +373,55 @@ mod util {
             self.inner.flush()
         }
     }
+
+    /// Write blocks of serialized entries to `out` in the order of their block id, no matter in which order they are produced.
+    pub struct InOrderBlocks<'a, T> {
+        out: &'a mut CountBytes<T>,
+        next_block: usize,
+        pending: BTreeMap<usize, (Vec<u8>, u32)>,
+        offsets: Vec<Offset>,
+    }
+
+    impl<'a, T> InOrderBlocks<'a, T> {
+        pub fn new(out: &'a mut CountBytes<T>) -> Self {
+            InOrderBlocks {
+                out,
+                next_block: 0,
+                pending: Default::default(),
+                offsets: Vec::new(),
+            }
+        }
+    }
+
+    impl<T> gix_features::parallel::Reduce for InOrderBlocks<'_, T>
+    where
+        T: std::io::Write,
+    {
+        type Input = std::io::Result<(usize, Vec<u8>, u32)>;
+        type FeedProduce = ();
+        type Output = Vec<Offset>;
+        type Error = std::io::Error;
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            let (block_id, data, num_entries) = item?;
+            self.pending.insert(block_id, (data, num_entries));
+            while let Some((data, num_entries)) = self.pending.remove(&self.next_block) {
+                self.next_block += 1;
+                if num_entries == 0 {
+                    continue;
+                }
+                self.offsets.push(Offset {
+                    from_beginning_of_file: self.out.count,
+                    num_entries,
+                });
+                std::io::Write::write_all(self.out, &data)?;
+            }
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            debug_assert!(self.pending.is_empty(), "all blocks are written in order");
+            Ok(self.offsets)
+        }
+    }
 }
@@ -14,9 +14,17 @@ fn roundtrips() -> crate::Result {
             Generated("v2"),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: true,
+                index_entry_offset_table: true,
             }),
         ),
+        (Loose("REUC"), all_ext_but_eoie()),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
@@ -48,6 +56,8 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: false,
+        split_index: Default::default(),
+        thread_limit: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -65,6 +75,8 @@ fn skip_hash() -> crate::Result {
     expected.write(Options {
         extensions: Default::default(),
         skip_hash: true,
+        split_index: Default::default(),
+        thread_limit: None,
     })?;
 
     let actual = gix_index::File::at(
@@ -148,11 +160,19 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: false,
+                index_entry_offset_table: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
+                resolve_undo: true,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: true,
+                index_entry_offset_table: true,
             }),
         ] {
             let expected = fixture.open();
@@ -210,6 +230,182 @@ fn remove_flag_is_respected() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn entries_written_in_blocks_are_recorded_in_offset_table() -> crate::Result {
+    let mut index = Generated("v4_more_files_IEOT").open();
+    index.entries_mut()[4].flags.insert(entry::Flags::REMOVE);
+    let expected_paths = paths_of(&index)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, path)| (idx != 4).then_some(path))
+        .collect::<Vec<_>>();
+
+    let mut entries_only = Vec::new();
+    index.write_to(&mut entries_only, options_with(write::Extensions::None))?;
+    let entries_only = &entries_only[..entries_only.len() - gix_hash::Kind::Sha1.len_in_bytes()];
+
+    for thread_limit in [2, 3, 10] {
+        let mut buf = Vec::new();
+        index.write_to(
+            &mut buf,
+            Options {
+                thread_limit: Some(thread_limit),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            &buf[..entries_only.len()],
+            entries_only,
+            "entries are the same no matter how many blocks they are written in"
+        );
+
+        for decode_thread_limit in [1, 3] {
+            let (state, _checksum) = State::from_bytes(
+                &buf,
+                FileTime::now(),
+                gix_hash::Kind::Sha1,
+                gix_index::decode::Options {
+                    thread_limit: Some(decode_thread_limit),
+                    ..Default::default()
+                },
+            )?;
+            assert!(state.had_offset_table(), "the blocks are recorded");
+            assert!(state.had_end_of_index_marker(), "needed to find the offset table");
+            assert_eq!(paths_of(&state), expected_paths);
+            state.verify_entries()?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn split_index_records_changes_to_shared_index_in_bitmaps() -> crate::Result {
+    let tmp = split_vs_regular_fixture()?;
+    let path = tmp.path().join("split/.git/index");
+    let mut index = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(paths_of(&index), ["b", "d", "e", "y", "z"]);
+
+    let entries = index.entries_mut();
+    entries[3].flags.insert(entry::Flags::REMOVE);
+    entries[4].id = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    index.write(split_index_options(None, 100))?;
+    assert_eq!(shared_indices(&path).len(), 1, "the shared index is reused");
+
+    let raw = raw_state(&path)?;
+    let bitmaps = raw
+        .link()
+        .expect("still a split index")
+        .bitmaps
+        .as_ref()
+        .expect("always written");
+    assert_eq!(
+        set_bits(&bitmaps.replace),
+        [1, 5],
+        "'b' and 'z' are changed compared to the shared index"
+    );
+    assert_eq!(
+        set_bits(&bitmaps.delete),
+        [0, 2, 3, 4],
+        "'a', 'c', 'x' and 'y' were removed"
+    );
+    assert_eq!(
+        paths_of(&raw),
+        ["", "", "d", "e"],
+        "replacements come first, followed by new entries"
+    );
+
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(paths_of(&actual), ["b", "d", "e", "z"]);
+    assert_eq!(actual.entries()[3].id, gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1));
+    Ok(())
+}
+
+#[test]
+fn split_index_can_be_enabled_and_disabled() -> crate::Result {
+    let tmp = split_vs_regular_fixture()?;
+    let path = tmp.path().join("regular/.git/index");
+    let mut index = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    let expected = paths_of(&index);
+
+    index.write(Default::default())?;
+    assert!(raw_state(&path)?.link().is_none(), "nothing changes by default");
+
+    index.write(split_index_options(Some(true), 20))?;
+    assert_eq!(shared_indices(&path).len(), 1, "a shared index was created");
+    let raw = raw_state(&path)?;
+    assert_eq!(raw.entries().len(), 0, "all entries are in the shared index");
+    assert!(raw.tree().is_some(), "extensions are kept in the split index");
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(paths_of(&actual), expected);
+
+    index.write(Default::default())?;
+    assert!(raw_state(&path)?.link().is_some(), "split indices stay split by default");
+
+    index.write(split_index_options(Some(false), 20))?;
+    let raw = raw_state(&path)?;
+    assert!(raw.link().is_none(), "the index isn't split anymore");
+    assert_eq!(paths_of(&raw), expected);
+    Ok(())
+}
+
+#[test]
+fn split_index_writes_new_shared_index_if_too_many_entries_changed() -> crate::Result {
+    let tmp = split_vs_regular_fixture()?;
+    let path = tmp.path().join("split/.git/index");
+    let mut index = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+
+    index.write(split_index_options(None, 60))?;
+    assert_eq!(shared_indices(&path).len(), 1, "3 of 5 entries aren't shared, which is still fine");
+    assert_eq!(raw_state(&path)?.entries().len(), 3);
+
+    index.write(split_index_options(None, 59))?;
+    assert_eq!(shared_indices(&path).len(), 2, "a new shared index was written");
+    assert_eq!(raw_state(&path)?.entries().len(), 0);
+
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(paths_of(&actual), ["b", "d", "e", "y", "z"]);
+    Ok(())
+}
+
+fn split_vs_regular_fixture() -> gix_testtools::Result<gix_testtools::tempfile::TempDir> {
+    gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")
+}
+
+fn split_index_options(enabled: Option<bool>, max_percent_change: u8) -> Options {
+    Options {
+        split_index: write::SplitIndex {
+            enabled,
+            max_percent_change,
+        },
+        ..Default::default()
+    }
+}
+
+fn raw_state(path: &std::path::Path) -> crate::Result<State> {
+    Ok(State::from_bytes(&std::fs::read(path)?, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?.0)
+}
+
+fn shared_indices(index_path: &std::path::Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(index_path.parent().expect("in .git dir"))
+        .expect("readable")
+        .map(|e| e.expect("valid entry").path())
+        .filter(|p| p.file_name().map_or(false, |n| n.to_string_lossy().starts_with("sharedindex.")))
+        .collect()
+}
+
+fn paths_of(state: &State) -> Vec<String> {
+    state.entries().iter().map(|e| e.path(state).to_string()).collect()
+}
+
+fn set_bits(bitmap: &gix_bitmap::ewah::Vec) -> Vec<usize> {
+    let mut out = Vec::new();
+    bitmap.for_each_set_bit(|idx| {
+        out.push(idx);
+        Some(())
+    });
+    out
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -243,6 +439,30 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
             .and_then(|_| expected.tree()),
         "tree extension mismatch, actual vs option in {fixture:?}"
     );
+    for (signature, actual_has_ext, expected_has_ext) in [
+        (
+            *b"REUC",
+            actual.resolve_undo().is_some(),
+            expected.resolve_undo().is_some(),
+        ),
+        (
+            *b"UNTR",
+            actual.untracked().is_some(),
+            expected.untracked().is_some(),
+        ),
+        (
+            *b"FSMN",
+            actual.fs_monitor().is_some(),
+            expected.fs_monitor().is_some(),
+        ),
+    ] {
+        assert_eq!(
+            actual_has_ext,
+            options.extensions.should_write(signature).is_some() && expected_has_ext,
+            "{} extension mismatch, actual vs option in {fixture:?}",
+            std::str::from_utf8(&signature).expect("ascii")
+        );
+    }
 
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing against baseline
@@ -292,15 +512,34 @@ fn only_tree_ext() -> Options {
     Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
+            index_entry_offset_table: false,
             tree_cache: true,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
         },
         skip_hash: false,
+        split_index: Default::default(),
+        thread_limit: None,
     }
 }
 
+fn all_ext_but_eoie() -> Options {
+    options_with(write::Extensions::Given {
+        tree_cache: true,
+        resolve_undo: true,
+        untracked_cache: true,
+        fs_monitor: true,
+        end_of_index_entry: false,
+        index_entry_offset_table: false,
+    })
+}
+
 fn options_with(extensions: write::Extensions) -> Options {
     Options {
         extensions,
         skip_hash: false,
+        split_index: Default::default(),
+        thread_limit: None,
     }
 }
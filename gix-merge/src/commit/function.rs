@@ -0,0 +1,184 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_revision::merge_base::Flags;
+
+use crate::blob::builtin_driver::text::{Conflict, Labels};
+use crate::commit::{Error, Options, Outcome};
+
+/// The graph type needed to find merge-bases.
+type Graph<'find, 'cache> = gix_revision::Graph<'find, 'cache, gix_revision::graph::Commit<Flags>>;
+
+/// Like [`tree()`](crate::tree()), but it takes only two commits, `our_commit` and `their_commit`, to automatically
+/// compute the merge-base between them, and to use it as the common ancestor to merge their trees.
+///
+/// * `labels` are used for conflict markers, with `ancestor` being ignored as it's determined automatically.
+/// * `graph` is used to find the merge-bases, and can be re-used across calls for speed.
+/// * `objects` provides access to commits, trees and blobs.
+/// * `write_object(object) -> Result<ObjectId, E>` writes blobs and trees to the object database, which is needed
+///   for merged blobs and virtual merge-bases.
+/// * `diff_resource_cache`, `blob_merge` and `options` are used just like in [`tree()`](crate::tree()).
+///
+/// ### Multiple merge-bases
+///
+/// If there are multiple merge-bases, which happens with criss-cross merges, all of them are merged into a
+/// virtual merge-base one after another, starting with the oldest, just like the `ort` strategy of `git` does.
+/// The merge-bases of each of these merges are determined by finding the merge-bases between the next merge-base and
+/// all merge-bases merged so far, and if there are multiple, these are recursively merged as well.
+/// Conflicts during these merges are kept in the virtual merge-base, with conflict markers being two characters longer
+/// for each level of recursion.
+///
+/// Use [`Options::use_first_merge_base`] to use the best merge-base instead.
+#[allow(clippy::too_many_arguments)]
+pub fn commit<'objects, E>(
+    our_commit: ObjectId,
+    their_commit: ObjectId,
+    labels: Labels<'_>,
+    graph: &mut Graph<'_, '_>,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut crate::blob::Platform,
+    objects: &'objects impl gix_object::FindObjectOrHeader,
+    mut write_object: impl FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, E>,
+    options: Options,
+) -> Result<Outcome<'objects>, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let _span = gix_trace::coarse!("gix_merge::commit()", ?our_commit, ?their_commit);
+
+    let mut buf = Vec::new();
+    let merge_bases = gix_revision::merge_base(our_commit, &[their_commit], graph)?;
+    let mut virtual_merge_bases = Vec::new();
+    let merge_base_tree_id = match merge_bases.as_deref() {
+        None if options.allow_missing_merge_base => write_empty_tree(&mut write_object)?,
+        None => {
+            return Err(Error::NoMergeBase {
+                our_commit_id: our_commit,
+                their_commit_id: their_commit,
+            })
+        }
+        Some([base]) => tree_id(objects, base, &mut buf)?,
+        Some([base, ..]) if options.use_first_merge_base => tree_id(objects, base, &mut buf)?,
+        Some(bases) => {
+            let mut state = VirtualMergeBase {
+                graph,
+                diff_resource_cache,
+                blob_merge,
+                objects,
+                write_object: &mut write_object,
+                options: &options,
+                trees: &mut virtual_merge_bases,
+            };
+            state.merge(bases, 1)?
+        }
+    };
+
+    let our_tree_id = tree_id(objects, &our_commit, &mut buf)?;
+    let their_tree_id = tree_id(objects, &their_commit, &mut buf)?;
+    let labels = Labels {
+        ancestor: (!virtual_merge_bases.is_empty())
+            .then(|| "merged common ancestors".into())
+            .or(labels.ancestor),
+        ..labels
+    };
+    let tree_merge = crate::tree(
+        &merge_base_tree_id,
+        &our_tree_id,
+        &their_tree_id,
+        labels,
+        objects,
+        |buf| write_object(&gix_object::BlobRef { data: buf }),
+        &mut Default::default(),
+        diff_resource_cache,
+        blob_merge,
+        options.tree_merge,
+    )?;
+
+    Ok(Outcome {
+        tree_merge,
+        merge_base_tree_id,
+        merge_bases,
+        virtual_merge_bases,
+    })
+}
+
+/// The state needed to merge multiple merge-bases into one.
+struct VirtualMergeBase<'a, 'find, 'cache, Find, WriteObject> {
+    graph: &'a mut Graph<'find, 'cache>,
+    diff_resource_cache: &'a mut gix_diff::blob::Platform,
+    blob_merge: &'a mut crate::blob::Platform,
+    objects: &'a Find,
+    write_object: &'a mut WriteObject,
+    options: &'a Options,
+    /// All trees produced while merging merge-bases.
+    trees: &'a mut Vec<ObjectId>,
+}
+
+impl<Find, WriteObject, E> VirtualMergeBase<'_, '_, '_, Find, WriteObject>
+where
+    Find: gix_object::FindObjectOrHeader,
+    WriteObject: FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, E>,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    /// Merge all `merge_bases`, sorted from best to worst, at the given recursion `depth`, and return the id
+    /// of the merged tree.
+    fn merge(&mut self, merge_bases: &[ObjectId], depth: usize) -> Result<ObjectId, Error> {
+        let mut buf = Vec::new();
+        let mut bases = merge_bases.iter().rev();
+        let first = *bases.next().expect("at least two merge-bases");
+        let mut merged_commits = vec![first];
+        let mut merged_tree_id = tree_id(self.objects, &first, &mut buf)?;
+
+        for next in bases {
+            // The merge-bases of a virtual commit with all merged commits as parents and the next merge-base.
+            let ancestor_tree_id = match gix_revision::merge_base(*next, &merged_commits, self.graph)?.as_deref() {
+                None => write_empty_tree(self.write_object)?,
+                Some([base]) => tree_id(self.objects, base, &mut buf)?,
+                Some(bases) => self.merge(bases, depth + 1)?,
+            };
+
+            let mut options = self.options.tree_merge.clone();
+            options.blob_merge.is_virtual_ancestor = true;
+            if let Conflict::Keep { marker_size, .. } = &mut options.blob_merge.text.conflict {
+                *marker_size += depth * 2;
+            }
+            let next_tree_id = tree_id(self.objects, next, &mut buf)?;
+            let write_object = &mut *self.write_object;
+            let mut outcome = crate::tree(
+                &ancestor_tree_id,
+                &merged_tree_id,
+                &next_tree_id,
+                Labels {
+                    ancestor: None,
+                    current: Some("Temporary merge branch 1".into()),
+                    other: Some("Temporary merge branch 2".into()),
+                },
+                self.objects,
+                |buf| write_object(&gix_object::BlobRef { data: buf }),
+                &mut Default::default(),
+                self.diff_resource_cache,
+                self.blob_merge,
+                options,
+            )?;
+            merged_tree_id = outcome
+                .tree
+                .write(|tree| write_object(tree))
+                .map_err(|err| Error::WriteObject(err.into()))?;
+            self.trees.push(merged_tree_id);
+            merged_commits.push(*next);
+        }
+        Ok(merged_tree_id)
+    }
+}
+
+fn tree_id(objects: &impl gix_object::Find, commit_id: &ObjectId, buf: &mut Vec<u8>) -> Result<ObjectId, Error> {
+    Ok(objects.find_commit(commit_id, buf)?.tree())
+}
+
+fn write_empty_tree<E>(
+    write_object: &mut impl FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, E>,
+) -> Result<ObjectId, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    write_object(&gix_object::Tree::empty()).map_err(|err| Error::WriteObject(err.into()))
+}
@@ -0,0 +1,56 @@
+use gix_hash::ObjectId;
+
+/// The error returned by [`commit()`](crate::commit()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to obtain the merge base between the commits to merge")]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error("No common ancestor between {our_commit_id} and {their_commit_id}")]
+    NoMergeBase {
+        our_commit_id: ObjectId,
+        their_commit_id: ObjectId,
+    },
+    #[error("Could not find commit to obtain its tree")]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error("Failed to merge the trees")]
+    MergeTree(#[from] crate::tree::Error),
+    #[error("Failed to write a tree or blob while creating a virtual merge base")]
+    WriteObject(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Options to be passed to [`commit()`](crate::commit()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, merging unrelated commits is allowed, with the merge-base being assumed as empty tree.
+    pub allow_missing_merge_base: bool,
+    /// Options to define how trees should be merged.
+    ///
+    /// Note that the blob-merge options are adjusted when merging merge-bases into a virtual merge-base.
+    pub tree_merge: crate::tree::Options,
+    /// If `true`, only the first merge-base is used, which is the best one, instead of merging all merge-bases into
+    /// a virtual merge-base like `git` does by default.
+    pub use_first_merge_base: bool,
+}
+
+/// The result of [`commit()`](crate::commit()).
+#[derive(Clone)]
+pub struct Outcome<'a> {
+    /// The outcome of the actual tree-merge.
+    pub tree_merge: crate::tree::Outcome<'a>,
+    /// The tree id of the base commit we used. This is either…
+    /// * the single merge-base we found
+    /// * the first of multiple merge-bases if [`use_first_merge_base`](Options::use_first_merge_base) was `true`.
+    /// * the merged tree of all merge-bases, which then isn't linked to an actual commit.
+    /// * an empty tree, if [`allow_missing_merge_base`](Options::allow_missing_merge_base) is enabled.
+    pub merge_base_tree_id: ObjectId,
+    /// The object ids of all the commits which were found to be merge-bases, best first, or `None` if there was no merge-base.
+    pub merge_bases: Option<Vec<ObjectId>>,
+    /// A list of trees that were created by merging the merge-bases one by one, with the last one being
+    /// [`merge_base_tree_id`](Self::merge_base_tree_id).
+    ///
+    /// It's empty if no virtual merge-base had to be created.
+    pub virtual_merge_bases: Vec<ObjectId>,
+}
+
+pub(super) mod function;
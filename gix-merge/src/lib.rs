@@ -4,3 +4,13 @@
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+///
+#[cfg(feature = "tree")]
+pub mod tree;
+#[cfg(feature = "tree")]
+pub use tree::function::tree;
+///
+#[cfg(feature = "commit")]
+pub mod commit;
+#[cfg(feature = "commit")]
+pub use commit::function::commit;
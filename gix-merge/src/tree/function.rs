@@ -0,0 +1,663 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_diff::tree_with_rewrites::{Action, ChangeRef};
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+use gix_object::FindExt;
+
+use crate::blob::builtin_driver::text::Labels;
+use crate::blob::{Resolution, ResourceKind};
+use crate::tree::{Conflict, Error, Options, Outcome, Side};
+
+/// A change of one side relative to the common ancestor, for a single non-tree entry.
+#[derive(Debug, Clone)]
+enum Change {
+    Addition {
+        mode: EntryMode,
+        id: ObjectId,
+    },
+    Deletion,
+    Modification {
+        previous_mode: EntryMode,
+        previous_id: ObjectId,
+        mode: EntryMode,
+        id: ObjectId,
+    },
+    /// The entry was renamed to `location`, where it now has `mode` and `id`.
+    RenamedTo {
+        location: BString,
+        mode: EntryMode,
+        id: ObjectId,
+    },
+    /// The entry was renamed or copied from `source_location`.
+    RenamedFrom {
+        source_location: BString,
+        source_mode: EntryMode,
+        source_id: ObjectId,
+        mode: EntryMode,
+        id: ObjectId,
+        copy: bool,
+    },
+}
+
+impl Change {
+    /// Return the mode and id this change leaves at its location, if it leaves anything.
+    fn entry(&self) -> Option<(EntryMode, ObjectId)> {
+        match self {
+            Change::Addition { mode, id }
+            | Change::Modification { mode, id, .. }
+            | Change::RenamedFrom { mode, id, .. } => Some((*mode, *id)),
+            Change::Deletion | Change::RenamedTo { .. } => None,
+        }
+    }
+}
+
+/// All changes of one side, by location.
+type Changes = BTreeMap<BString, Change>;
+
+/// Perform a three-way merge of `our_tree` and `their_tree`, using `ancestor_tree` as their common ancestor,
+/// and return an editor with the merged tree along with all conflicts that were encountered.
+///
+/// * `labels` are used to annotate conflict markers in blobs, and `current` and `other` are also used to produce
+///   unique names for entries that have to be moved out of the way.
+/// * `objects` provides access to trees and blobs, and will be used by the returned editor to look up trees.
+/// * `write_blob_to_odb(content) -> Result<ObjectId, E>` writes merged blobs to the object database, returning their id.
+/// * `diff_state` and `diff_resource_cache` are used to diff both sides against the common ancestor, which includes
+///   rename tracking if [`Options::rewrites`] is set.
+/// * `blob_merge` is used to merge the content of blobs that were changed by both sides, following `.gitattributes`.
+/// * `options` control the merge.
+///
+/// Write the returned [`Outcome::tree`] to obtain the id of the merged tree.
+///
+/// ## The algorithm
+///
+/// Both sides are diffed against the common ancestor, and the changes of their side are applied to our tree.
+/// Whenever both sides changed the same entry, the changes are merged, which merges the content of blobs with the
+/// `blob_merge` platform. Changes to renamed entries are merged into the location they were renamed to.
+///
+/// Conflicts are resolved in a way that keeps all information, for instance by keeping modified entries that were
+/// deleted on the other side, keeping both destinations of an entry renamed differently by each side, or by moving
+/// entries that clash with directories to `<location>~<side-label>`.
+#[allow(clippy::too_many_arguments)]
+pub fn tree<'objects, E>(
+    ancestor_tree: &gix_hash::oid,
+    our_tree: &gix_hash::oid,
+    their_tree: &gix_hash::oid,
+    labels: Labels<'_>,
+    objects: &'objects impl gix_object::FindObjectOrHeader,
+    mut write_blob_to_odb: impl FnMut(&[u8]) -> Result<ObjectId, E>,
+    diff_state: &mut gix_diff::tree::State,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut crate::blob::Platform,
+    options: Options,
+) -> Result<Outcome<'objects>, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let _span = gix_trace::coarse!("gix_merge::tree()", ?ancestor_tree, ?our_tree, ?their_tree);
+
+    let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
+    let mut changes_of = |side: &gix_hash::oid| {
+        changes_against_ancestor(
+            objects,
+            ancestor_tree,
+            side,
+            diff_state,
+            diff_resource_cache,
+            options.rewrites,
+            (&mut buf1, &mut buf2),
+        )
+    };
+    let ours = changes_of(our_tree)?;
+    let theirs = changes_of(their_tree)?;
+    diff_resource_cache.clear_resource_cache_keep_allocation();
+
+    let root = objects.find_tree(our_tree, &mut buf1)?.into();
+    let mut state = State {
+        editor: gix_object::tree::Editor::new(root, objects, our_tree.kind()),
+        conflicts: Vec::new(),
+        ours: &ours,
+        trees: [our_tree.to_owned(), their_tree.to_owned()],
+        objects,
+        blob_merge,
+        labels,
+        options: &options,
+        write_blob_to_odb: &mut write_blob_to_odb,
+        moved_locations: BTreeSet::new(),
+        buf: Vec::new(),
+    };
+
+    for (location, their_change) in &theirs {
+        let location = location.as_bstr();
+        match their_change {
+            Change::Addition { mode, id } => {
+                state.place_theirs(location, None, None, (*mode, *id))?;
+            }
+            Change::Modification {
+                previous_mode,
+                previous_id,
+                mode,
+                id,
+            } => {
+                let ancestor = Some((*previous_mode, *previous_id));
+                match ours.get(location) {
+                    None => {
+                        state.place_theirs(location, ancestor, None, (*mode, *id))?;
+                    }
+                    Some(Change::Deletion) => {
+                        state.conflicts.push(Conflict::ModifyDelete {
+                            location: location.to_owned(),
+                            modified_by: Side::Theirs,
+                        });
+                        state.place_theirs(location, ancestor, None, (*mode, *id))?;
+                    }
+                    Some(Change::RenamedTo {
+                        location: our_location,
+                        mode: our_mode,
+                        id: our_id,
+                    }) => {
+                        state.merge_entries(our_location.as_ref(), ancestor, (*our_mode, *our_id), (*mode, *id))?;
+                    }
+                    Some(
+                        Change::Modification {
+                            mode: our_mode,
+                            id: our_id,
+                            ..
+                        }
+                        | Change::Addition {
+                            mode: our_mode,
+                            id: our_id,
+                        }
+                        | Change::RenamedFrom {
+                            mode: our_mode,
+                            id: our_id,
+                            ..
+                        },
+                    ) => {
+                        state.merge_entries(location, ancestor, (*our_mode, *our_id), (*mode, *id))?;
+                    }
+                }
+            }
+            Change::Deletion => match ours.get(location) {
+                None => {
+                    state.editor.remove(to_components(location))?;
+                }
+                Some(Change::Deletion) => {}
+                Some(Change::Modification { .. }) => state.conflicts.push(Conflict::ModifyDelete {
+                    location: location.to_owned(),
+                    modified_by: Side::Ours,
+                }),
+                Some(Change::RenamedTo {
+                    location: our_location, ..
+                }) => state.conflicts.push(Conflict::RenameDelete {
+                    source_location: location.to_owned(),
+                    location: our_location.clone(),
+                    renamed_by: Side::Ours,
+                }),
+                // Our entry at this location is new, so it's not affected by their deletion.
+                Some(Change::Addition { .. } | Change::RenamedFrom { .. }) => {}
+            },
+            // Handled along with the destination of the rename.
+            Change::RenamedTo { .. } => {}
+            Change::RenamedFrom {
+                source_location,
+                source_mode,
+                source_id,
+                mode,
+                id,
+                copy,
+            } => {
+                let ancestor = Some((*source_mode, *source_id));
+                let theirs = (*mode, *id);
+                if *copy {
+                    state.place_theirs(location, None, None, theirs)?;
+                    continue;
+                }
+                match ours.get(source_location) {
+                    None => {
+                        state.editor.remove(to_components(source_location.as_ref()))?;
+                        state.place_theirs(location, ancestor, None, theirs)?;
+                    }
+                    Some(Change::Modification {
+                        mode: our_mode,
+                        id: our_id,
+                        ..
+                    }) => {
+                        state.editor.remove(to_components(source_location.as_ref()))?;
+                        state.place_theirs(location, ancestor, Some((*our_mode, *our_id)), theirs)?;
+                    }
+                    Some(Change::Deletion) => {
+                        state.conflicts.push(Conflict::RenameDelete {
+                            source_location: source_location.clone(),
+                            location: location.to_owned(),
+                            renamed_by: Side::Theirs,
+                        });
+                        state.place_theirs(location, ancestor, None, theirs)?;
+                    }
+                    Some(Change::RenamedTo {
+                        location: our_location,
+                        mode: our_mode,
+                        id: our_id,
+                    }) => {
+                        if our_location.as_bstr() == location {
+                            state.merge_entries(location, ancestor, (*our_mode, *our_id), theirs)?;
+                        } else {
+                            state.conflicts.push(Conflict::RenameRename {
+                                source_location: source_location.clone(),
+                                our_location: our_location.clone(),
+                                their_location: location.to_owned(),
+                            });
+                            state.place_theirs(location, ancestor, None, theirs)?;
+                        }
+                    }
+                    // Our entry at the source location is new, so we keep it and only add theirs.
+                    Some(Change::Addition { .. } | Change::RenamedFrom { .. }) => {
+                        state.place_theirs(location, ancestor, None, theirs)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Outcome {
+        tree: state.editor,
+        conflicts: state.conflicts,
+    })
+}
+
+/// Everything needed to apply their changes to our tree.
+struct State<'a, 'objects, Find, WriteBlob> {
+    editor: gix_object::tree::Editor<'objects>,
+    conflicts: Vec<Conflict>,
+    /// Our changes, relative to the common ancestor.
+    ours: &'a Changes,
+    /// Our tree and their tree, to know which locations are taken.
+    trees: [ObjectId; 2],
+    objects: &'objects Find,
+    blob_merge: &'a mut crate::blob::Platform,
+    labels: Labels<'a>,
+    options: &'a Options,
+    write_blob_to_odb: &'a mut WriteBlob,
+    /// Locations of our entries that were moved to make room for their directories, and all locations
+    /// that entries were moved to.
+    moved_locations: BTreeSet<BString>,
+    buf: Vec<u8>,
+}
+
+impl<Find, WriteBlob, E> State<'_, '_, Find, WriteBlob>
+where
+    Find: gix_object::FindObjectOrHeader,
+    WriteBlob: FnMut(&[u8]) -> Result<ObjectId, E>,
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    /// Place their entry at `location` while handling clashes with whatever we placed at a parent location or below it.
+    /// `ancestor` is the version of the entry in the common ancestor, if there is one.
+    /// If `our_version` is set, it will be merged with `theirs`, otherwise whatever we placed at `location` will be used.
+    fn place_theirs(
+        &mut self,
+        location: &BStr,
+        ancestor: Option<(EntryMode, ObjectId)>,
+        our_version: Option<(EntryMode, ObjectId)>,
+        theirs: (EntryMode, ObjectId),
+    ) -> Result<(), Error> {
+        // We placed a non-tree entry where one of their parent directories is.
+        let mut parent = location;
+        while let Some(pos) = parent.rfind_byte(b'/') {
+            parent = parent[..pos].as_bstr();
+            if self.moved_locations.contains(parent) {
+                continue;
+            }
+            if let Some((mode, id)) = self.ours.get(parent).and_then(Change::entry) {
+                let new_location = self.unique_location(parent, Side::Ours)?;
+                self.editor.remove(to_components(parent))?;
+                self.editor
+                    .upsert(to_components(new_location.as_ref()), mode.kind(), id)?;
+                self.moved_locations.insert(parent.to_owned());
+                self.conflicts.push(Conflict::DirectoryFile {
+                    location: parent.to_owned(),
+                    new_location,
+                    file_side: Side::Ours,
+                });
+            }
+        }
+
+        // We placed entries below the location of their non-tree entry, so it's a directory for us.
+        if placed_below(self.ours, location) {
+            let new_location = self.unique_location(location, Side::Theirs)?;
+            self.editor
+                .upsert(to_components(new_location.as_ref()), theirs.0.kind(), theirs.1)?;
+            self.conflicts.push(Conflict::DirectoryFile {
+                location: location.to_owned(),
+                new_location,
+                file_side: Side::Theirs,
+            });
+            return Ok(());
+        }
+
+        match our_version.or_else(|| self.ours.get(location).and_then(Change::entry)) {
+            Some(ours) => self.merge_entries(location, ancestor, ours, theirs),
+            None => {
+                self.editor.upsert(to_components(location), theirs.0.kind(), theirs.1)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Merge our and their version of an entry at `location`, with `ancestor` being the version in the common ancestor,
+    /// or `None` if both sides added it, and place the result at `location`.
+    fn merge_entries(
+        &mut self,
+        location: &BStr,
+        ancestor: Option<(EntryMode, ObjectId)>,
+        ours: (EntryMode, ObjectId),
+        theirs: (EntryMode, ObjectId),
+    ) -> Result<(), Error> {
+        if ours.0.is_blob() != theirs.0.is_blob() || (!ours.0.is_blob() && ours.0 != theirs.0) {
+            let their_location = self.unique_location(location, Side::Theirs)?;
+            self.editor.upsert(to_components(location), ours.0.kind(), ours.1)?;
+            self.editor
+                .upsert(to_components(their_location.as_ref()), theirs.0.kind(), theirs.1)?;
+            self.conflicts.push(Conflict::DistinctTypes {
+                location: location.to_owned(),
+                their_location,
+            });
+            return Ok(());
+        }
+
+        let mode = match merge_modes(ancestor.map(|(mode, _)| mode), ours.0, theirs.0) {
+            Some(mode) => mode,
+            None => {
+                self.conflicts.push(Conflict::Mode {
+                    location: location.to_owned(),
+                    ours: ours.0,
+                    theirs: theirs.0,
+                });
+                ours.0
+            }
+        };
+
+        let ancestor_id = ancestor.map(|(_, id)| id);
+        let id = if ours.1 == theirs.1 || ancestor_id == Some(theirs.1) {
+            ours.1
+        } else if ancestor_id == Some(ours.1) {
+            theirs.1
+        } else if !(ours.0.is_blob() && theirs.0.is_blob() && ancestor.map_or(true, |(mode, _)| mode.is_blob())) {
+            self.conflicts.push(Conflict::Unmergeable {
+                location: location.to_owned(),
+                ours,
+                theirs,
+            });
+            self.editor.upsert(to_components(location), ours.0.kind(), ours.1)?;
+            return Ok(());
+        } else {
+            self.merge_blobs(location, ancestor, ours, theirs)?
+        };
+        self.editor.upsert(to_components(location), mode.kind(), id)?;
+        Ok(())
+    }
+
+    /// Merge the content of the blobs `ours` and `theirs` with `ancestor` as common ancestor, if there is one,
+    /// and return the id of the merged blob.
+    fn merge_blobs(
+        &mut self,
+        location: &BStr,
+        ancestor: Option<(EntryMode, ObjectId)>,
+        ours: (EntryMode, ObjectId),
+        theirs: (EntryMode, ObjectId),
+    ) -> Result<ObjectId, Error> {
+        use crate::blob::platform::builtin_merge::Pick;
+
+        let (ancestor_mode, ancestor_id) = ancestor.unwrap_or((ours.0, ours.1.kind().null()));
+        self.blob_merge.set_resource(
+            ancestor_id,
+            ancestor_mode.kind(),
+            location,
+            ResourceKind::CommonAncestorOrBase,
+            self.objects,
+        )?;
+        self.blob_merge.set_resource(
+            ours.1,
+            ours.0.kind(),
+            location,
+            ResourceKind::CurrentOrOurs,
+            self.objects,
+        )?;
+        self.blob_merge.set_resource(
+            theirs.1,
+            theirs.0.kind(),
+            location,
+            ResourceKind::OtherOrTheirs,
+            self.objects,
+        )?;
+        let platform = self.blob_merge.prepare_merge(self.objects, self.options.blob_merge)?;
+        let (pick, resolution) =
+            platform.merge(&mut self.buf, self.labels, self.options.blob_merge_command_ctx.clone())?;
+
+        let id = match pick {
+            Pick::Ours => ours.1,
+            Pick::Theirs => theirs.1,
+            Pick::Ancestor if !ancestor_id.is_null() => ancestor_id,
+            Pick::Ancestor | Pick::Buffer => {
+                let data = match pick {
+                    Pick::Buffer => self.buf.as_slice(),
+                    _ => platform.buffer_by_pick(pick).ok_or_else(|| Error::PickedBlobTooLarge {
+                        location: location.to_owned(),
+                    })?,
+                };
+                (self.write_blob_to_odb)(data).map_err(|err| Error::WriteBlobToOdb {
+                    location: location.to_owned(),
+                    source: err.into(),
+                })?
+            }
+        };
+        if resolution == Resolution::Conflict {
+            self.conflicts.push(Conflict::Content {
+                location: location.to_owned(),
+                ancestor: ancestor.map(|(_, id)| id),
+                ours: ours.1,
+                theirs: theirs.1,
+                merged: id,
+            });
+        }
+        Ok(id)
+    }
+
+    /// Produce a location based on `location` for the entry of `side` that isn't used by our tree, their tree,
+    /// or any previously moved entry.
+    fn unique_location(&mut self, location: &BStr, side: Side) -> Result<BString, Error> {
+        let label: BString = match side {
+            Side::Ours => self
+                .labels
+                .current
+                .map_or_else(|| "ours".into(), |label| label.replace("/", "_").into()),
+            Side::Theirs => self
+                .labels
+                .other
+                .map_or_else(|| "theirs".into(), |label| label.replace("/", "_").into()),
+        };
+
+        let mut candidate = location.to_owned();
+        candidate.push_byte(b'~');
+        candidate.push_str(&label);
+        let base_len = candidate.len();
+        let mut suffix = 0;
+        loop {
+            let is_taken = self.moved_locations.contains(&candidate) || {
+                let mut is_taken = false;
+                for tree in self.trees {
+                    is_taken |= entry_exists(self.objects, tree, candidate.as_ref(), &mut self.buf)?;
+                }
+                is_taken
+            };
+            if !is_taken {
+                break;
+            }
+            suffix += 1;
+            candidate.truncate(base_len);
+            candidate.push_str(format!("_{suffix}"));
+        }
+        self.moved_locations.insert(candidate.clone());
+        Ok(candidate)
+    }
+}
+
+/// Return `true` if there is an entry at the slash-separated `location` in the tree with `tree_id`.
+fn entry_exists(
+    objects: &impl gix_object::Find,
+    mut tree_id: ObjectId,
+    location: &BStr,
+    buf: &mut Vec<u8>,
+) -> Result<bool, Error> {
+    let mut components = to_components(location).peekable();
+    while let Some(component) = components.next() {
+        let Some(entry) = objects
+            .find_tree_iter(&tree_id, buf)?
+            .filter_map(Result::ok)
+            .find(|entry| entry.filename == component)
+        else {
+            return Ok(false);
+        };
+        if components.peek().is_none() {
+            return Ok(true);
+        }
+        if !entry.mode.is_tree() {
+            return Ok(false);
+        }
+        tree_id = entry.oid.to_owned();
+    }
+    Ok(false)
+}
+
+/// Return `true` if `ours` placed any entry below `location`, turning it into a directory.
+fn placed_below(ours: &Changes, location: &BStr) -> bool {
+    let mut prefix = location.to_owned();
+    prefix.push_byte(b'/');
+    ours.range::<BString, _>(&prefix..)
+        .take_while(|(path, _)| path.starts_with(&prefix))
+        .any(|(_, change)| change.entry().is_some())
+}
+
+/// Return the mode to use for an entry whose mode was `ancestor` and which has `ours` and `theirs` now,
+/// or `None` if both sides changed the mode differently.
+fn merge_modes(ancestor: Option<EntryMode>, ours: EntryMode, theirs: EntryMode) -> Option<EntryMode> {
+    if ours == theirs {
+        Some(ours)
+    } else if ancestor == Some(ours) {
+        Some(theirs)
+    } else if ancestor == Some(theirs) {
+        Some(ours)
+    } else {
+        None
+    }
+}
+
+fn to_components(location: &BStr) -> impl Iterator<Item = &BStr> {
+    location.split(|b| *b == b'/').map(ByteSlice::as_bstr)
+}
+
+/// Diff `side` against `ancestor` and collect all changes to non-tree entries by location.
+fn changes_against_ancestor(
+    objects: &impl gix_object::FindObjectOrHeader,
+    ancestor: &gix_hash::oid,
+    side: &gix_hash::oid,
+    diff_state: &mut gix_diff::tree::State,
+    resource_cache: &mut gix_diff::blob::Platform,
+    rewrites: Option<gix_diff::Rewrites>,
+    (ancestor_buf, side_buf): (&mut Vec<u8>, &mut Vec<u8>),
+) -> Result<Changes, Error> {
+    let mut out = Changes::new();
+    gix_diff::tree_with_rewrites(
+        objects.find_tree_iter(ancestor, ancestor_buf)?,
+        objects.find_tree_iter(side, side_buf)?,
+        resource_cache,
+        diff_state,
+        objects,
+        |change| -> Result<_, std::convert::Infallible> {
+            match change {
+                ChangeRef::Addition {
+                    location,
+                    entry_mode,
+                    id,
+                    ..
+                } => {
+                    if !entry_mode.is_tree() {
+                        out.insert(location.to_owned(), Change::Addition { mode: entry_mode, id });
+                    }
+                }
+                ChangeRef::Deletion {
+                    location, entry_mode, ..
+                } => {
+                    if !entry_mode.is_tree() {
+                        out.insert(location.to_owned(), Change::Deletion);
+                    }
+                }
+                ChangeRef::Modification {
+                    location,
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } => match (previous_entry_mode.is_tree(), entry_mode.is_tree()) {
+                    (false, false) => {
+                        out.insert(
+                            location.to_owned(),
+                            Change::Modification {
+                                previous_mode: previous_entry_mode,
+                                previous_id,
+                                mode: entry_mode,
+                                id,
+                            },
+                        );
+                    }
+                    (false, true) => {
+                        out.insert(location.to_owned(), Change::Deletion);
+                    }
+                    (true, false) => {
+                        out.insert(location.to_owned(), Change::Addition { mode: entry_mode, id });
+                    }
+                    (true, true) => {}
+                },
+                ChangeRef::Rewrite {
+                    source_location,
+                    source_entry_mode,
+                    source_id,
+                    entry_mode,
+                    id,
+                    location,
+                    copy,
+                    ..
+                } => {
+                    if !entry_mode.is_tree() {
+                        if !copy {
+                            // Prefer additions at the source location, which happen if another entry took its place.
+                            out.entry(source_location.to_owned())
+                                .or_insert_with(|| Change::RenamedTo {
+                                    location: location.to_owned(),
+                                    mode: entry_mode,
+                                    id,
+                                });
+                        }
+                        out.insert(
+                            location.to_owned(),
+                            Change::RenamedFrom {
+                                source_location: source_location.to_owned(),
+                                source_mode: source_entry_mode,
+                                source_id,
+                                mode: entry_mode,
+                                id,
+                                copy,
+                            },
+                        );
+                    }
+                }
+            }
+            Ok(Action::Continue)
+        },
+        gix_diff::tree_with_rewrites::Options {
+            location: Some(gix_diff::tree::recorder::Location::Path),
+            rewrites,
+        },
+    )?;
+    Ok(out)
+}
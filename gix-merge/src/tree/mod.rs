@@ -0,0 +1,177 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+/// The error returned by [`tree()`](crate::tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not find ancestor, our or their tree to merge")]
+    FindTree(#[from] gix_object::find::existing_object::Error),
+    #[error("Could not find ancestor, our or their tree iterator to diff")]
+    FindTreeIter(#[from] gix_object::find::existing_iter::Error),
+    #[error("Failed to diff our side or their side against the common ancestor")]
+    DiffTree(#[from] gix_diff::tree_with_rewrites::Error),
+    #[error("Failed to edit the tree that is the result of the merge")]
+    TreeEdit(#[from] gix_object::tree::editor::Error),
+    #[error("Failed to set a blob resource to merge")]
+    SetResource(#[from] crate::blob::platform::set_resource::Error),
+    #[error("Failed to prepare the blob merge")]
+    PrepareMerge(#[from] crate::blob::platform::prepare_merge::Error),
+    #[error("Failed to merge the content of a blob")]
+    MergeBlob(#[from] crate::blob::platform::merge::Error),
+    #[error("The blob at '{location}' picked as merge result was too large to be processed")]
+    PickedBlobTooLarge { location: BString },
+    #[error("Failed to write the merged blob at '{location}'")]
+    WriteBlobToOdb {
+        location: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+}
+
+/// The side of a merge, used to indicate where a change is originating from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Side {
+    /// The current version, *ours*.
+    Ours,
+    /// The other version, *theirs*.
+    Theirs,
+}
+
+/// A conflict that was encountered while merging trees.
+///
+/// All locations are slash-separated paths relative to the root of the merged tree.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Conflict {
+    /// Both sides changed the content of the blob at `location`, and the merge of their content left conflict markers.
+    ///
+    /// The blob with the conflict markers is `merged`, and it is stored in the merged tree.
+    Content {
+        /// The location of the blob in the merged tree.
+        location: BString,
+        /// The version of the blob in the common ancestor, or `None` if both sides added it.
+        ancestor: Option<ObjectId>,
+        /// Our version of the blob.
+        ours: ObjectId,
+        /// Their version of the blob.
+        theirs: ObjectId,
+        /// The merged blob with conflict markers.
+        merged: ObjectId,
+    },
+    /// Both sides changed the mode of the entry at `location` in different ways. The merged tree uses `ours`.
+    Mode {
+        /// The location of the entry in the merged tree.
+        location: BString,
+        /// Our mode of the entry.
+        ours: EntryMode,
+        /// Their mode of the entry.
+        theirs: EntryMode,
+    },
+    /// Both sides placed entries of different types at `location`, like a blob and a symlink, which can't be merged.
+    /// Their entry was moved to `their_location` so both are kept in the merged tree.
+    DistinctTypes {
+        /// The location of our entry in the merged tree.
+        location: BString,
+        /// The location of their entry in the merged tree.
+        their_location: BString,
+    },
+    /// The entries at `location` can't be merged as at least one of them isn't a blob, like symlinks or submodules.
+    /// The merged tree uses our version.
+    Unmergeable {
+        /// The location of the entry in the merged tree.
+        location: BString,
+        /// Our mode and id of the entry.
+        ours: (EntryMode, ObjectId),
+        /// Their mode and id of the entry.
+        theirs: (EntryMode, ObjectId),
+    },
+    /// One side modified the entry at `location` while the other side deleted it. The merged tree keeps the modified version.
+    ModifyDelete {
+        /// The location of the modified entry.
+        location: BString,
+        /// The side that modified the entry, the other side deleted it.
+        modified_by: Side,
+    },
+    /// One side renamed the entry at `source_location` to `location`, while the other side deleted it.
+    /// The merged tree keeps the renamed version.
+    RenameDelete {
+        /// The location of the entry in the common ancestor.
+        source_location: BString,
+        /// The location the entry was renamed to.
+        location: BString,
+        /// The side that renamed the entry, the other side deleted it.
+        renamed_by: Side,
+    },
+    /// Both sides renamed the entry at `source_location`, but to different locations.
+    /// The merged tree keeps the entry at both locations.
+    RenameRename {
+        /// The location of the entry in the common ancestor.
+        source_location: BString,
+        /// The location we renamed the entry to.
+        our_location: BString,
+        /// The location they renamed the entry to.
+        their_location: BString,
+    },
+    /// One side placed a non-tree entry at `location`, while the other side placed a directory there or below.
+    /// The non-tree entry was moved to `new_location` in the merged tree to make room for the directory.
+    DirectoryFile {
+        /// The location where the non-tree entry clashed with the directory.
+        location: BString,
+        /// The location the non-tree entry was moved to.
+        new_location: BString,
+        /// The side whose non-tree entry was moved.
+        file_side: Side,
+    },
+}
+
+impl Conflict {
+    /// Return the location in the merged tree the conflict is most closely related to.
+    pub fn location(&self) -> &bstr::BStr {
+        match self {
+            Conflict::Content { location, .. }
+            | Conflict::Mode { location, .. }
+            | Conflict::DistinctTypes { location, .. }
+            | Conflict::Unmergeable { location, .. }
+            | Conflict::ModifyDelete { location, .. }
+            | Conflict::RenameDelete { location, .. }
+            | Conflict::DirectoryFile { location, .. } => location.as_ref(),
+            Conflict::RenameRename { source_location, .. } => source_location.as_ref(),
+        }
+    }
+}
+
+/// The outcome of [`tree()`](crate::tree()).
+#[derive(Clone)]
+pub struct Outcome<'a> {
+    /// The editor holding the merged tree, ready to be [written](gix_object::tree::Editor::write())
+    /// to obtain the id of the merged tree.
+    pub tree: gix_object::tree::Editor<'a>,
+    /// All conflicts that were encountered during the merge, in the order in which they were encountered.
+    ///
+    /// Note that the merge is only clean if there are no conflicts, even though [`tree`](Self::tree) always
+    /// contains a tree that can be written.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Outcome<'_> {
+    /// Return `true` if there was at least one conflict that requires the attention of the user.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Options to be passed to [`tree()`](crate::tree()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `Some(_)`, rename tracking will be performed when diffing our side and their side against the common ancestor,
+    /// so that changes to a renamed file can be merged into its new location.
+    ///
+    /// If `None`, renames are seen as deletions and additions.
+    pub rewrites: Option<gix_diff::Rewrites>,
+    /// Options to use when merging the content of blobs.
+    pub blob_merge: crate::blob::platform::merge::Options,
+    /// The context to use when invoking external merge drivers.
+    pub blob_merge_command_ctx: gix_command::Context,
+}
+
+pub(super) mod function;
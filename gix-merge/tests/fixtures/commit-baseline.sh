@@ -0,0 +1,92 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function seq_lines() {
+  local from=$1 to=$2
+  for i in $(seq "$from" "$to"); do echo "line $i"; done
+}
+
+# Write the baseline for merging `A` (ours) into `B` (theirs) of the repository at `$1`, passing all other arguments
+# to `git merge-tree`.
+function baseline() {
+  local name=$1; shift
+  local tree_id status=0
+  tree_id=$(git -C "$name" merge-tree --write-tree --no-messages "$@" A B | head -n 1) || status=$?
+  echo "$name $(git -C "$name" rev-parse A) $(git -C "$name" rev-parse B) $( (git -C "$name" merge-base --all A B || true) | wc -l | tr -d ' ') $tree_id $status" >> baseline.cases
+}
+
+git init -q criss-cross
+(cd criss-cross
+  seq_lines 1 10 > a
+  seq_lines 11 20 > b
+  git add . && git commit -q -m "base"
+  git branch A && git branch B
+
+  git checkout -q A
+  sed -i.bak 's/line 1$/line 1 A/' a && rm a.bak
+  git commit -q -am "A1"
+  git tag A1
+
+  git checkout -q B
+  sed -i.bak 's/line 10$/line 10 B/' a && rm a.bak
+  git commit -q -am "B1"
+
+  git checkout -q A
+  git merge -q --no-edit B
+  echo x > x && git add x && git commit -q --amend --no-edit
+
+  git checkout -q B
+  git merge -q --no-edit A1
+  echo y > y && git add y && git commit -q --amend --no-edit
+
+  git checkout -q A
+  sed -i.bak 's/line 5$/line 5 A/' a && rm a.bak
+  git commit -q -am "A3"
+
+  git checkout -q B
+  sed -i.bak 's/line 15$/line 15 B/' b && rm b.bak
+  git commit -q -am "B3"
+)
+baseline criss-cross
+
+git init -q criss-cross-conflicting-bases
+(cd criss-cross-conflicting-bases
+  seq_lines 1 10 > a
+  git add . && git commit -q -m "base"
+  git branch A && git branch B
+
+  git checkout -q A
+  sed -i.bak 's/line 1$/line 1 A/' a && rm a.bak
+  git commit -q -am "A1"
+  git tag A1
+
+  git checkout -q B
+  sed -i.bak 's/line 1$/line 1 B/' a && rm a.bak
+  git commit -q -am "B1"
+  git tag B1
+
+  git checkout -q A
+  git merge -q --no-edit B1 -s ours
+
+  git checkout -q B
+  git merge -q --no-edit A1 -s ours
+
+  git checkout -q A
+  sed -i.bak 's/line 10$/line 10 A/' a && rm a.bak
+  git commit -q -am "A3"
+
+  git checkout -q B
+  echo new > new && git add new
+  git commit -q -m "B3"
+)
+baseline criss-cross-conflicting-bases
+
+git init -q unrelated
+(cd unrelated
+  echo a > a && git add . && git commit -q -m "A"
+  git branch -m A
+  git checkout -q --orphan B
+  git rm -q --cached a && rm a
+  echo b > b && git add . && git commit -q -m "B"
+)
+baseline unrelated --allow-unrelated-histories
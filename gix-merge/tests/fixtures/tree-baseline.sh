@@ -0,0 +1,186 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function seq_lines() {
+  local from=$1 to=$2
+  for i in $(seq "$from" "$to"); do echo "line $i"; done
+}
+
+# Create a repository in `$1` with an initial commit tagged `base`, and the branches `A` (ours) and `B` (theirs) based on it.
+# `setup`, `ours` and `theirs` are functions to run to produce the respective commits.
+function scenario() {
+  local name=$1
+  git init -q "$name"
+  (cd "$name"
+    setup
+    git add -A && git commit -q -m "base"
+    git tag base && git branch A && git branch B
+
+    git checkout -q A
+    ours
+    git add -A && git commit -q -m "ours"
+
+    git checkout -q B
+    theirs
+    git add -A && git commit -q -m "theirs"
+  )
+  local tree_id status=0
+  tree_id=$(git -C "$name" merge-tree --write-tree --no-messages A B | head -n 1) || status=$?
+  echo "$name $(git -C "$name" rev-parse A^{tree}) $(git -C "$name" rev-parse B^{tree}) $(git -C "$name" rev-parse base^{tree}) $tree_id $status" >> baseline.cases
+}
+
+function setup() {
+  seq_lines 1 10 > a
+  seq_lines 11 20 > b
+  echo d > d
+  mkdir dir && echo e > dir/e
+}
+function ours() {
+  sed -i.bak 's/line 1$/line 1 ours/' a && rm a.bak
+  rm d
+}
+function theirs() {
+  sed -i.bak 's/line 11$/line 11 theirs/' b && rm b.bak
+  echo c > c
+  echo f > dir/f
+}
+scenario non-overlapping
+
+function setup() {
+  seq_lines 1 10 > a
+}
+function ours() {
+  sed -i.bak 's/line 1$/line 1 ours/' a && rm a.bak
+}
+function theirs() {
+  sed -i.bak 's/line 10$/line 10 theirs/' a && rm a.bak
+}
+scenario content-merge
+
+function theirs() {
+  sed -i.bak 's/line 1$/line 1 theirs/' a && rm a.bak
+}
+scenario content-conflict
+
+function setup() {
+  echo base > base
+}
+function ours() {
+  seq_lines 1 10 > new
+}
+function theirs() {
+  seq_lines 1 10 > new
+}
+scenario add-add-identical
+
+function theirs() {
+  seq_lines 1 10 > new
+  sed -i.bak 's/line 5$/line 5 theirs/' new && rm new.bak
+}
+function ours() {
+  seq_lines 1 10 > new
+  sed -i.bak 's/line 5$/line 5 ours/' new && rm new.bak
+}
+scenario add-add-conflict
+
+function setup() {
+  seq_lines 1 10 > a
+}
+function ours() {
+  rm a
+}
+function theirs() {
+  sed -i.bak 's/line 1$/line 1 theirs/' a && rm a.bak
+}
+scenario modify-delete
+
+function setup() {
+  seq_lines 1 20 > a
+}
+function ours() {
+  git mv a renamed
+}
+function theirs() {
+  sed -i.bak 's/line 20$/line 20 theirs/' a && rm a.bak
+}
+scenario rename-modify
+
+function ours() {
+  git mv a renamed
+  sed -i.bak 's/line 1$/line 1 ours/' renamed && rm renamed.bak
+}
+scenario rename-modify-both
+
+function ours() {
+  git mv a x
+}
+function theirs() {
+  git mv a y
+}
+scenario rename-rename
+
+function theirs() {
+  git mv a x
+  sed -i.bak 's/line 20$/line 20 theirs/' x && rm x.bak
+}
+scenario rename-same
+
+function theirs() {
+  rm a
+}
+scenario rename-delete
+
+function setup() {
+  echo base > base
+}
+function ours() {
+  echo ours > d
+}
+function theirs() {
+  mkdir d && echo theirs > d/file
+}
+scenario directory-file
+
+function setup() {
+  mkdir d && echo base > d/file
+}
+function ours() {
+  echo change >> d/file
+}
+function theirs() {
+  rm -Rf d && echo theirs > d
+}
+scenario modify-in-directory-replaced-by-file
+
+function ours() {
+  echo other > other
+}
+scenario directory-replaced-by-file
+
+function setup() {
+  seq_lines 1 10 > a
+}
+function ours() {
+  sed -i.bak 's/line 1$/line 1 ours/' a && rm a.bak
+}
+function theirs() {
+  chmod +x a
+}
+scenario mode-change
+
+function ours() {
+  chmod +x a
+}
+scenario mode-change-both
+
+function setup() {
+  seq_lines 1 10 > a
+}
+function ours() {
+  rm a
+  ln -s target a
+}
+function theirs() {
+  sed -i.bak 's/line 1$/line 1 theirs/' a && rm a.bak
+}
+scenario symlink-vs-modification
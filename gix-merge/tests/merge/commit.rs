@@ -0,0 +1,110 @@
+use crate::tree::{labels, new_blob_merge_platform, new_diff_resource_cache};
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_odb::Write;
+
+#[test]
+fn run_baseline() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("commit-baseline.sh")?;
+    let cases = std::fs::read_to_string(root.join("baseline.cases"))?;
+    for line in cases.lines() {
+        let mut tokens = line.split(' ');
+        let (Some(name), Some(ours), Some(theirs), Some(num_merge_bases), Some(merged_tree), Some(status), None) = (
+            tokens.next(),
+            tokens.next(),
+            tokens.next(),
+            tokens.next(),
+            tokens.next(),
+            tokens.next(),
+            tokens.next(),
+        ) else {
+            unreachable!("Need 6 fields per line, got {line:?}")
+        };
+        let repo_root = root.join(name);
+        let odb = gix_odb::memory::Proxy::new(gix_odb::at(repo_root.join(".git/objects"))?, gix_hash::Kind::Sha1);
+        let mut graph = gix_revision::Graph::new(&odb, None);
+        let num_merge_bases: usize = num_merge_bases.parse()?;
+        let (ours, theirs): (ObjectId, ObjectId) = (ours.parse()?, theirs.parse()?);
+
+        if num_merge_bases == 0 {
+            let err = gix_merge::commit(
+                ours,
+                theirs,
+                labels(),
+                &mut graph,
+                &mut new_diff_resource_cache(&repo_root),
+                &mut new_blob_merge_platform(&repo_root),
+                &odb,
+                |obj| odb.write(obj),
+                Default::default(),
+            )
+            .err()
+            .expect("unrelated histories must be allowed explicitly");
+            assert!(
+                matches!(err, gix_merge::commit::Error::NoMergeBase { our_commit_id, their_commit_id } if our_commit_id == ours && their_commit_id == theirs),
+                "{name}"
+            );
+        }
+
+        let outcome = gix_merge::commit(
+            ours,
+            theirs,
+            labels(),
+            &mut graph,
+            &mut new_diff_resource_cache(&repo_root),
+            &mut new_blob_merge_platform(&repo_root),
+            &odb,
+            |obj| odb.write(obj),
+            gix_merge::commit::Options {
+                allow_missing_merge_base: num_merge_bases == 0,
+                tree_merge: gix_merge::tree::Options {
+                    rewrites: Some(Default::default()),
+                    ..Default::default()
+                },
+                use_first_merge_base: false,
+            },
+        )?;
+        let gix_merge::commit::Outcome {
+            mut tree_merge,
+            merge_bases,
+            virtual_merge_bases,
+            ..
+        } = outcome;
+        let actual_id = tree_merge.tree.write(|tree| odb.write(tree))?;
+        if status == "0" {
+            // Conflicting hunks aren't simplified like `git` does, so only clean merges are comparable.
+            assert_eq!(
+                actual_id,
+                merged_tree.parse::<ObjectId>()?,
+                "{name}: merged tree mismatch"
+            );
+        }
+        assert_eq!(
+            tree_merge.has_conflicts(),
+            status == "1",
+            "{name}: {:?}",
+            tree_merge.conflicts
+        );
+        assert_eq!(merge_bases.map_or(0, |bases| bases.len()), num_merge_bases, "{name}");
+        assert_eq!(
+            virtual_merge_bases.is_empty(),
+            num_merge_bases < 2,
+            "{name}: virtual merge-bases are created only if there is more than one merge-base"
+        );
+        if name == "criss-cross-conflicting-bases" {
+            let mut buf = Vec::new();
+            let virtual_merge_base = odb.find_tree(virtual_merge_bases.last().expect("present"), &mut buf)?;
+            let blob_id = virtual_merge_base
+                .bisect_entry("a".into(), false)
+                .expect("present")
+                .oid
+                .to_owned();
+            let blob = odb.find_blob(&blob_id, &mut buf)?;
+            assert!(
+                blob.data.starts_with(b"<<<<<<<<< Temporary merge branch 1\n"),
+                "conflicts are kept in virtual merge-bases, with longer markers"
+            );
+        }
+    }
+    Ok(())
+}
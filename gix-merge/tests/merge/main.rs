@@ -2,5 +2,9 @@ extern crate core;
 
 #[cfg(feature = "blob")]
 mod blob;
+#[cfg(feature = "commit")]
+mod commit;
+#[cfg(feature = "tree")]
+mod tree;
 
 pub use gix_testtools::Result;
@@ -0,0 +1,191 @@
+use gix_merge::tree::{Conflict, Side};
+use gix_odb::Write;
+
+#[test]
+fn run_baseline() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("tree-baseline.sh")?;
+    let cases = std::fs::read_to_string(root.join("baseline.cases"))?;
+    for case in baseline::Expectations::new(&root, &cases) {
+        let mut diff_resource_cache = new_diff_resource_cache(&case.root);
+        let mut blob_merge = new_blob_merge_platform(&case.root);
+        let mut outcome = gix_merge::tree(
+            &case.ancestor,
+            &case.ours,
+            &case.theirs,
+            labels(),
+            &case.odb,
+            |buf| case.odb.write_buf(gix_object::Kind::Blob, buf),
+            &mut Default::default(),
+            &mut diff_resource_cache,
+            &mut blob_merge,
+            gix_merge::tree::Options {
+                rewrites: Some(Default::default()),
+                ..Default::default()
+            },
+        )?;
+        let actual_id = outcome.tree.write(|tree| case.odb.write(tree))?;
+        assert_eq!(
+            actual_id, case.merge_info.merged_tree,
+            "{}: merged tree mismatch",
+            case.name
+        );
+        assert_eq!(
+            outcome.has_conflicts(),
+            case.merge_info.conflicting,
+            "{}: {:?}",
+            case.name,
+            outcome.conflicts
+        );
+        if let Some(expected) = expected_conflicts(case.name) {
+            assert_eq!(outcome.conflicts, expected, "{}", case.name);
+        }
+    }
+    Ok(())
+}
+
+fn expected_conflicts(case: &str) -> Option<Vec<Conflict>> {
+    Some(match case {
+        "modify-delete" => vec![Conflict::ModifyDelete {
+            location: "a".into(),
+            modified_by: Side::Theirs,
+        }],
+        "rename-rename" => vec![Conflict::RenameRename {
+            source_location: "a".into(),
+            our_location: "x".into(),
+            their_location: "y".into(),
+        }],
+        "rename-delete" => vec![Conflict::RenameDelete {
+            source_location: "a".into(),
+            location: "x".into(),
+            renamed_by: Side::Ours,
+        }],
+        "directory-file" => vec![Conflict::DirectoryFile {
+            location: "d".into(),
+            new_location: "d~A".into(),
+            file_side: Side::Ours,
+        }],
+        "modify-in-directory-replaced-by-file" => vec![
+            Conflict::DirectoryFile {
+                location: "d".into(),
+                new_location: "d~B".into(),
+                file_side: Side::Theirs,
+            },
+            Conflict::ModifyDelete {
+                location: "d/file".into(),
+                modified_by: Side::Ours,
+            },
+        ],
+        "symlink-vs-modification" => vec![Conflict::DistinctTypes {
+            location: "a".into(),
+            their_location: "a~B".into(),
+        }],
+        _ => return None,
+    })
+}
+
+pub(crate) fn labels() -> gix_merge::blob::builtin_driver::text::Labels<'static> {
+    gix_merge::blob::builtin_driver::text::Labels {
+        ancestor: None,
+        current: Some("A".into()),
+        other: Some("B".into()),
+    }
+}
+
+mod baseline {
+    use gix_hash::ObjectId;
+    use std::path::{Path, PathBuf};
+
+    pub struct MergeInfo {
+        pub merged_tree: ObjectId,
+        pub conflicting: bool,
+    }
+
+    pub struct Expectation<'a> {
+        pub root: PathBuf,
+        pub odb: gix_odb::memory::Proxy<gix_odb::Handle>,
+        pub name: &'a str,
+        pub ours: ObjectId,
+        pub theirs: ObjectId,
+        pub ancestor: ObjectId,
+        pub merge_info: MergeInfo,
+    }
+
+    pub struct Expectations<'a> {
+        root: &'a Path,
+        lines: std::str::Lines<'a>,
+    }
+
+    impl<'a> Expectations<'a> {
+        pub fn new(root: &'a Path, cases: &'a str) -> Self {
+            Expectations {
+                root,
+                lines: cases.lines(),
+            }
+        }
+    }
+
+    impl<'a> Iterator for Expectations<'a> {
+        type Item = Expectation<'a>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let line = self.lines.next()?;
+            let mut tokens = line.split(' ');
+            let (Some(name), Some(ours), Some(theirs), Some(ancestor), Some(merged_tree), Some(status), None) = (
+                tokens.next(),
+                tokens.next(),
+                tokens.next(),
+                tokens.next(),
+                tokens.next(),
+                tokens.next(),
+                tokens.next(),
+            ) else {
+                unreachable!("Need 6 fields per line, got {line:?}")
+            };
+            let root = self.root.join(name);
+            let objects = gix_odb::at(root.join(".git/objects")).expect("object database exists");
+            Some(Expectation {
+                root,
+                odb: gix_odb::memory::Proxy::new(objects, gix_hash::Kind::Sha1),
+                name,
+                ours: ours.parse().expect("valid hash"),
+                theirs: theirs.parse().expect("valid hash"),
+                ancestor: ancestor.parse().expect("valid hash"),
+                merge_info: MergeInfo {
+                    merged_tree: merged_tree.parse().expect("valid hash"),
+                    conflicting: status == "1",
+                },
+            })
+        }
+    }
+}
+
+pub(crate) fn new_diff_resource_cache(root: &std::path::Path) -> gix_diff::blob::Platform {
+    gix_diff::blob::Platform::new(
+        Default::default(),
+        gix_diff::blob::Pipeline::new(Default::default(), Default::default(), Vec::new(), Default::default()),
+        gix_diff::blob::pipeline::Mode::ToGit,
+        gix_worktree::Stack::new(
+            root,
+            gix_worktree::stack::State::AttributesStack(Default::default()),
+            gix_worktree::glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Vec::new(),
+        ),
+    )
+}
+
+pub(crate) fn new_blob_merge_platform(root: &std::path::Path) -> gix_merge::blob::Platform {
+    gix_merge::blob::Platform::new(
+        gix_merge::blob::Pipeline::new(Default::default(), gix_filter::Pipeline::default(), Default::default()),
+        gix_merge::blob::pipeline::Mode::ToGit,
+        gix_worktree::Stack::new(
+            root,
+            gix_worktree::stack::State::AttributesStack(Default::default()),
+            gix_worktree::glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Vec::new(),
+        ),
+        Vec::new(),
+        Default::default(),
+    )
+}
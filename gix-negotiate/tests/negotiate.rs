@@ -40,7 +40,7 @@ mod baseline;
 fn size_of_entry() {
     assert_eq!(
         std::mem::size_of::<gix_revwalk::graph::Commit<gix_negotiate::Metadata>>(),
-        56,
+        80,
         "we may keep a lot of these, so let's not let them grow unnoticed"
     );
 }
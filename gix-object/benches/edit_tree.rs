@@ -148,7 +148,7 @@ fn new_inmemory_writes() -> (TreeStore, impl FnMut(&Tree) -> Result<ObjectId, st
             let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha1);
             hasher.update(&header);
             hasher.update(&buf);
-            let id = hasher.digest().into();
+            let id = hasher.try_finalize()?;
             let mut borrowed = store.borrow_mut();
             match borrowed.entry(id) {
                 Entry::Occupied(_) => {}
@@ -178,6 +178,7 @@ impl gix_object::Find for StorageOdb {
                 tree.write_to(buffer).expect("valid trees can always be serialized");
                 Ok(Some(gix_object::Data {
                     kind: gix_object::Kind::Tree,
+                    object_hash: id.kind(),
                     data: &*buffer,
                 }))
             }
@@ -3,9 +3,13 @@
 use crate::{BlobRef, CommitRef, CommitRefIter, Data, Kind, ObjectRef, TagRef, TagRefIter, TreeRef, TreeRefIter};
 
 impl<'a> Data<'a> {
-    /// Constructs a new data object from `kind` and `data`.
-    pub fn new(kind: Kind, data: &'a [u8]) -> Data<'a> {
-        Data { kind, data }
+    /// Constructs a new data object from `kind` and `data`, with `object_hash` being the kind of hash used in its object database.
+    pub fn new(kind: Kind, data: &'a [u8], object_hash: gix_hash::Kind) -> Data<'a> {
+        Data {
+            kind,
+            object_hash,
+            data,
+        }
     }
     /// Decodes the data in the backing slice into a [`ObjectRef`], allowing to access all of its data
     /// conveniently. The cost of parsing an object is negligible.
@@ -14,7 +18,7 @@ impl<'a> Data<'a> {
     /// using [`crate::ObjectRef::into_owned()`].
     pub fn decode(&self) -> Result<ObjectRef<'a>, crate::decode::Error> {
         Ok(match self.kind {
-            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes(self.data)?),
+            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes(self.data, self.object_hash)?),
             Kind::Blob => ObjectRef::Blob(BlobRef { data: self.data }),
             Kind::Commit => ObjectRef::Commit(CommitRef::from_bytes(self.data)?),
             Kind::Tag => ObjectRef::Tag(TagRef::from_bytes(self.data)?),
@@ -25,7 +29,7 @@ impl<'a> Data<'a> {
     /// `None` if this is not a tree object.
     pub fn try_into_tree_iter(self) -> Option<TreeRefIter<'a>> {
         match self.kind {
-            Kind::Tree => Some(TreeRefIter::from_bytes(self.data)),
+            Kind::Tree => Some(TreeRefIter::from_bytes(self.data, self.object_hash)),
             _ => None,
         }
     }
@@ -61,6 +65,8 @@ pub mod verify {
             desired: gix_hash::ObjectId,
             actual: gix_hash::ObjectId,
         },
+        #[error(transparent)]
+        Hasher(#[from] gix_features::hash::Error),
     }
 
     impl crate::Data<'_> {
@@ -68,7 +74,7 @@ pub mod verify {
         /// If the hashes do not match, an [`Error`] is returned, containing the actual
         /// hash of `self`.
         pub fn verify_checksum(&self, desired: &gix_hash::oid) -> Result<(), Error> {
-            let actual_id = crate::compute_hash(desired.kind(), self.kind, self.data);
+            let actual_id = crate::compute_hash(desired.kind(), self.kind, self.data)?;
             if desired != actual_id {
                 return Err(Error::ChecksumMismatch {
                     desired: desired.into(),
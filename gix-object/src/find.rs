@@ -62,6 +62,74 @@ pub mod existing_iter {
     }
 }
 
+/// A stream of an object's decompressed data, which allows to read objects of any size without holding them in memory.
+///
+/// It's obtained with [`FindStream::try_find_stream()`](crate::FindStream::try_find_stream()).
+pub struct Stream {
+    /// The kind of the object.
+    pub kind: crate::Kind,
+    /// The size of the object's data in bytes, which is also the amount of bytes this stream will yield.
+    pub size: u64,
+    /// The amount of bytes that are yet to be read.
+    remaining: u64,
+    inner: Box<dyn std::io::Read + Send + 'static>,
+}
+
+mod stream {
+    use std::io;
+
+    use super::Stream;
+
+    impl Stream {
+        /// Create a new instance to yield `size` bytes of an object of `kind` from `inner`.
+        pub fn new(kind: crate::Kind, size: u64, inner: impl io::Read + Send + 'static) -> Self {
+            Stream {
+                kind,
+                size,
+                remaining: size,
+                inner: Box::new(inner),
+            }
+        }
+
+        /// Create a new instance from the fully decoded object `data` of `kind`.
+        pub fn from_data(kind: crate::Kind, data: Vec<u8>) -> Self {
+            Stream::new(kind, data.len() as u64, io::Cursor::new(data))
+        }
+    }
+
+    impl io::Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.remaining == 0 || buf.is_empty() {
+                return Ok(0);
+            }
+            let max = buf.len().min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+            let num_read = self.inner.read(&mut buf[..max])?;
+            if num_read == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "object stream ended after {} of {} bytes",
+                        self.size - self.remaining,
+                        self.size
+                    ),
+                ));
+            }
+            self.remaining -= num_read as u64;
+            Ok(num_read)
+        }
+    }
+
+    impl std::fmt::Debug for Stream {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Stream")
+                .field("kind", &self.kind)
+                .field("size", &self.size)
+                .field("remaining", &self.remaining)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
 /// An implementation of all traits that never fails, but also never finds anything.
 #[derive(Debug, Copy, Clone)]
 pub struct Never;
@@ -83,3 +151,9 @@ impl super::Exists for Never {
         false
     }
 }
+
+impl super::FindStream for Never {
+    fn try_find_stream(&self, _id: &gix_hash::oid) -> Result<Option<Stream>, Error> {
+        Ok(None)
+    }
+}
@@ -34,7 +34,9 @@ pub mod data;
 pub mod find;
 
 mod traits;
-pub use traits::{Exists, Find, FindExt, FindObjectOrHeader, Header as FindHeader, HeaderExt, WriteTo};
+pub use traits::{
+    Exists, Find, FindExt, FindObjectOrHeader, FindStream, FindStreamExt, Header as FindHeader, HeaderExt, WriteTo,
+};
 
 pub mod encode;
 pub(crate) mod parse;
@@ -229,6 +231,8 @@ pub struct TreeRef<'a> {
 pub struct TreeRefIter<'a> {
     /// The directories and files contained in this tree.
     data: &'a [u8],
+    /// The kind of hash used to refer to the entries.
+    object_hash: gix_hash::Kind,
 }
 
 /// A mutable Tree, containing other trees, blobs or commits.
@@ -253,6 +257,8 @@ impl Tree {
 pub struct Data<'a> {
     /// kind of object
     pub kind: Kind,
+    /// The kind of hash used by the object database this object originates from, needed to decode trees.
+    pub object_hash: gix_hash::Kind,
     /// decoded, decompressed data, owned by a backing store.
     pub data: &'a [u8],
 }
@@ -393,15 +399,21 @@ pub mod decode {
 }
 
 /// A function to compute a hash of kind `hash_kind` for an object of `object_kind` and its `data`.
+///
+/// It fails if the object is part of a known SHA1 collision attack.
 #[doc(alias = "hash_object", alias = "git2")]
-pub fn compute_hash(hash_kind: gix_hash::Kind, object_kind: Kind, data: &[u8]) -> gix_hash::ObjectId {
+pub fn compute_hash(
+    hash_kind: gix_hash::Kind,
+    object_kind: Kind,
+    data: &[u8],
+) -> Result<gix_hash::ObjectId, gix_features::hash::Error> {
     let header = encode::loose_header(object_kind, data.len() as u64);
 
     let mut hasher = gix_features::hash::hasher(hash_kind);
     hasher.update(&header);
     hasher.update(data);
 
-    hasher.digest().into()
+    hasher.try_finalize()
 }
 
 /// A function to compute a hash of kind `hash_kind` for an object of `object_kind` and its data read from `stream`
@@ -79,6 +79,15 @@ mod find {
         fn try_header(&self, id: &gix_hash::oid) -> Result<Option<crate::Header>, find::Error>;
     }
 
+    /// Find an object in the object store and read its data as a stream, without loading it into memory in full.
+    pub trait FindStream {
+        /// Find the object matching `id` in the database and return a stream over its decompressed data.
+        ///
+        /// Returns `Some` stream if the object was present in the database, or the error that occurred during lookup.
+        /// Note that implementations may decode objects that can't be streamed directly, like deltified ones, into memory first.
+        fn try_find_stream(&self, id: &gix_hash::oid) -> Result<Option<find::Stream>, find::Error>;
+    }
+
     /// A combination of [`Find`] and [`Header`] traits to help with `dyn` trait objects.
     pub trait FindObjectOrHeader: Find + Header {}
 
@@ -118,6 +127,15 @@ mod find {
             }
         }
 
+        impl<T> crate::FindStream for &T
+        where
+            T: crate::FindStream,
+        {
+            fn try_find_stream(&self, id: &oid) -> Result<Option<crate::find::Stream>, crate::find::Error> {
+                (*self).try_find_stream(id)
+            }
+        }
+
         impl<T> crate::Exists for Box<T>
         where
             T: crate::Exists,
@@ -154,6 +172,15 @@ mod find {
             }
         }
 
+        impl<T> crate::FindStream for Rc<T>
+        where
+            T: crate::FindStream,
+        {
+            fn try_find_stream(&self, id: &oid) -> Result<Option<crate::find::Stream>, crate::find::Error> {
+                self.deref().try_find_stream(id)
+            }
+        }
+
         impl<T> crate::Find for Box<T>
         where
             T: crate::Find,
@@ -172,6 +199,15 @@ mod find {
             }
         }
 
+        impl<T> crate::FindStream for Box<T>
+        where
+            T: crate::FindStream,
+        {
+            fn try_find_stream(&self, id: &oid) -> Result<Option<crate::find::Stream>, crate::find::Error> {
+                self.deref().try_find_stream(id)
+            }
+        }
+
         impl<T> crate::Exists for Arc<T>
         where
             T: crate::Exists,
@@ -198,6 +234,15 @@ mod find {
                 self.deref().try_header(id)
             }
         }
+
+        impl<T> crate::FindStream for Arc<T>
+        where
+            T: crate::FindStream,
+        {
+            fn try_find_stream(&self, id: &oid) -> Result<Option<crate::find::Stream>, crate::find::Error> {
+                self.deref().try_find_stream(id)
+            }
+        }
     }
 
     mod ext {
@@ -274,6 +319,18 @@ mod find {
             }
         }
 
+        /// An extension trait with convenience functions.
+        pub trait FindStreamExt: super::FindStream {
+            /// Like [`try_find_stream(…)`](super::FindStream::try_find_stream()), but flattens the `Result<Option<_>>` into a single `Result` making a non-existing object an error.
+            fn find_stream(&self, id: &gix_hash::oid) -> Result<find::Stream, find::existing::Error> {
+                self.try_find_stream(id)
+                    .map_err(find::existing::Error::Find)?
+                    .ok_or_else(|| find::existing::Error::NotFound { oid: id.to_owned() })
+            }
+        }
+
+        impl<T: super::FindStream + ?Sized> FindStreamExt for T {}
+
         /// An extension trait with convenience functions.
         pub trait FindExt: super::Find {
             /// Like [`try_find(…)`](super::Find::try_find()), but flattens the `Result<Option<_>>` into a single `Result` making a non-existing object an error.
@@ -298,6 +355,6 @@ mod find {
 
         impl<T: super::Find + ?Sized> FindExt for T {}
     }
-    pub use ext::{FindExt, HeaderExt};
+    pub use ext::{FindExt, FindStreamExt, HeaderExt};
 }
 pub use find::*;
@@ -1,20 +1,20 @@
 use bstr::BStr;
-use winnow::{error::ParserError, prelude::*};
+use winnow::error::ParserError;
 
 use crate::{tree, tree::EntryRef, TreeRef, TreeRefIter};
 
 impl<'a> TreeRefIter<'a> {
-    /// Instantiate an iterator from the given tree data.
-    pub fn from_bytes(data: &'a [u8]) -> TreeRefIter<'a> {
-        TreeRefIter { data }
+    /// Instantiate an iterator from the given tree `data`, whose entries refer to objects using hashes of kind `object_hash`.
+    pub fn from_bytes(data: &'a [u8], object_hash: gix_hash::Kind) -> TreeRefIter<'a> {
+        TreeRefIter { data, object_hash }
     }
 }
 
 impl<'a> TreeRef<'a> {
-    /// Deserialize a Tree from `data`.
-    pub fn from_bytes(mut data: &'a [u8]) -> Result<TreeRef<'a>, crate::decode::Error> {
+    /// Deserialize a Tree from `data`, whose entries refer to objects using hashes of kind `object_hash`.
+    pub fn from_bytes(mut data: &'a [u8], object_hash: gix_hash::Kind) -> Result<TreeRef<'a>, crate::decode::Error> {
         let input = &mut data;
-        match decode::tree.parse_next(input) {
+        match decode::tree(input, object_hash.len_in_bytes()) {
             Ok(tag) => Ok(tag),
             Err(err) => Err(crate::decode::Error::with_err(err, input)),
         }
@@ -64,7 +64,7 @@ impl<'a> Iterator for TreeRefIter<'a> {
         if self.data.is_empty() {
             return None;
         }
-        match decode::fast_entry(self.data) {
+        match decode::fast_entry(self.data, self.object_hash.len_in_bytes()) {
             Some((data_left, entry)) => {
                 self.data = data_left;
                 Some(Ok(entry))
@@ -131,31 +131,30 @@ mod decode {
         TreeRef,
     };
 
-    pub fn fast_entry(i: &[u8]) -> Option<(&[u8], EntryRef<'_>)> {
+    pub fn fast_entry(i: &[u8], hash_len: usize) -> Option<(&[u8], EntryRef<'_>)> {
         let (mode, i) = mode_from_decimal(i)?;
         let mode = tree::EntryMode::try_from(mode).ok()?;
         let (filename, i) = i.split_at(i.find_byte(0)?);
         let i = &i[1..];
-        const HASH_LEN_FIXME: usize = 20; // TODO(SHA256): know actual/desired length or we may overshoot
         let (oid, i) = match i.len() {
-            len if len < HASH_LEN_FIXME => return None,
-            _ => i.split_at(20),
+            len if len < hash_len => return None,
+            _ => i.split_at(hash_len),
         };
         Some((
             i,
             EntryRef {
                 mode,
                 filename: filename.as_bstr(),
-                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly 20 bytes"),
+                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly as many bytes as the hash has"),
             },
         ))
     }
 
-    pub fn tree<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> PResult<TreeRef<'a>, E> {
+    pub fn tree<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8], hash_len: usize) -> PResult<TreeRef<'a>, E> {
         let mut out = Vec::new();
         let mut i = &**i;
         while !i.is_empty() {
-            let Some((rest, entry)) = fast_entry(i) else {
+            let Some((rest, entry)) = fast_entry(i, hash_len) else {
                 #[allow(clippy::unit_arg)]
                 return Err(winnow::error::ErrMode::from_error_kind(
                     &i,
@@ -12,11 +12,11 @@ mod tree;
 fn compute_hash() {
     let hk = gix_hash::Kind::Sha1;
     assert_eq!(
-        gix_object::compute_hash(hk, gix_object::Kind::Blob, &[]),
+        gix_object::compute_hash(hk, gix_object::Kind::Blob, &[]).expect("no collision"),
         gix_hash::ObjectId::empty_blob(hk)
     );
     assert_eq!(
-        gix_object::compute_hash(hk, gix_object::Kind::Tree, &[]),
+        gix_object::compute_hash(hk, gix_object::Kind::Tree, &[]).expect("no collision"),
         gix_hash::ObjectId::empty_tree(hk)
     );
 }
@@ -81,8 +81,8 @@ fn fixture_name(kind: &str, path: &str) -> Vec<u8> {
 fn size_in_memory() {
     let actual = std::mem::size_of::<gix_object::Object>();
     assert!(
-        actual <= 264,
-        "{actual} <= 264: Prevent unexpected growth of what should be lightweight objects"
+        actual <= 288,
+        "{actual} <= 288: Prevent unexpected growth of what should be lightweight objects"
     );
 }
 
@@ -732,7 +732,7 @@ mod utils {
                 let mut hasher = gix_features::hash::hasher(gix_hash::Kind::Sha1);
                 hasher.update(&header);
                 hasher.update(&buf);
-                let id = hasher.digest().into();
+                let id = hasher.try_finalize()?;
                 store.borrow_mut().insert(id, tree.clone());
                 let old = num_writes.get();
                 num_writes.set(old + 1);
@@ -780,6 +780,7 @@ mod utils {
                     tree.write_to(buffer).expect("valid trees can always be serialized");
                     Ok(Some(gix_object::Data {
                         kind: gix_object::Kind::Tree,
+                        object_hash: id.kind(),
                         data: &*buffer,
                     }))
                 }
@@ -4,13 +4,17 @@ use crate::{fixture_name, hex_to_id};
 
 #[test]
 fn empty() {
-    assert_eq!(TreeRefIter::from_bytes(&[]).count(), 0, "empty trees are definitely ok");
+    assert_eq!(
+        TreeRefIter::from_bytes(&[], gix_hash::Kind::Sha1).count(),
+        0,
+        "empty trees are definitely ok"
+    );
 }
 
 #[test]
 fn error_handling() {
     let data = fixture_name("tree", "everything.tree");
-    let iter = TreeRefIter::from_bytes(&data[..data.len() / 2]);
+    let iter = TreeRefIter::from_bytes(&data[..data.len() / 2], gix_hash::Kind::Sha1);
     let entries = iter.collect::<Vec<_>>();
     assert!(
         entries.last().expect("at least one token").is_err(),
@@ -21,7 +25,8 @@ fn error_handling() {
 #[test]
 fn everything() -> crate::Result {
     assert_eq!(
-        TreeRefIter::from_bytes(&fixture_name("tree", "everything.tree")).collect::<Result<Vec<_>, _>>()?,
+        TreeRefIter::from_bytes(&fixture_name("tree", "everything.tree"), gix_hash::Kind::Sha1)
+            .collect::<Result<Vec<_>, _>>()?,
         vec![
             EntryRef {
                 mode: tree::EntryKind::BlobExecutable.into(),
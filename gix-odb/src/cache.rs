@@ -139,7 +139,11 @@ mod impls {
     use gix_object::{Data, Kind};
     use gix_pack::cache::Object;
 
-    use crate::{find::Header, pack::data::entry::Location, Cache};
+    use crate::{
+        find::{Header, Stream},
+        pack::data::entry::Location,
+        Cache,
+    };
 
     impl<S> crate::Write for Cache<S>
     where
@@ -177,6 +181,15 @@ mod impls {
         }
     }
 
+    impl<S> crate::FindStream for Cache<S>
+    where
+        S: crate::FindStream,
+    {
+        fn try_find_stream(&self, id: &oid) -> Result<Option<Stream>, gix_object::find::Error> {
+            self.inner.try_find_stream(id)
+        }
+    }
+
     impl<S> gix_object::FindHeader for Cache<S>
     where
         S: gix_object::FindHeader,
@@ -213,7 +226,7 @@ mod impls {
         ) -> Result<Option<(Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
             if let Some(mut obj_cache) = self.object_cache.as_ref().map(RefCell::borrow_mut) {
                 if let Some(kind) = obj_cache.get(&id.as_ref().to_owned(), buffer) {
-                    return Ok(Some((Data::new(kind, buffer), None)));
+                    return Ok(Some((Data::new(kind, buffer, id.kind()), None)));
                 }
             }
             let possibly_obj = self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?;
@@ -60,3 +60,5 @@ mod header {
         }
     }
 }
+
+pub use gix_object::find::Stream;
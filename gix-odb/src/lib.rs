@@ -77,6 +77,7 @@ pub mod find;
 /// An object database equivalent to `/dev/null`, dropping all objects stored into it.
 mod traits;
 
+pub use gix_object::{FindStream, FindStreamExt};
 pub use traits::{Header, HeaderExt, Write};
 
 ///
@@ -1,4 +1,4 @@
-use crate::find::Header;
+use crate::find::{Header, Stream};
 use crate::Cache;
 use gix_object::Data;
 use std::cell::RefCell;
@@ -149,6 +149,7 @@ where
                 buffer.extend_from_slice(data);
                 return Ok(Some(Data {
                     kind: *kind,
+                    object_hash: self.object_hash,
                     data: &*buffer,
                 }));
             }
@@ -184,6 +185,21 @@ where
     }
 }
 
+impl<T> crate::FindStream for Proxy<T>
+where
+    T: crate::FindStream,
+{
+    fn try_find_stream(&self, id: &gix_hash::oid) -> Result<Option<Stream>, gix_object::find::Error> {
+        if let Some(map) = self.memory.as_ref() {
+            let map = map.borrow();
+            if let Some((kind, data)) = map.get(id) {
+                return Ok(Some(Stream::from_data(*kind, data.clone())));
+            }
+        }
+        self.inner.try_find_stream(id)
+    }
+}
+
 impl<T> gix_object::FindHeader for Proxy<T>
 where
     T: gix_object::FindHeader,
@@ -219,7 +235,7 @@ where
         let mut buf = Vec::new();
         from.read_to_end(&mut buf)?;
 
-        let id = gix_object::compute_hash(self.object_hash, kind, &buf);
+        let id = gix_object::compute_hash(self.object_hash, kind, &buf)?;
         map.borrow_mut().insert(id, (kind, buf));
         Ok(id)
     }
@@ -53,6 +53,6 @@ impl crate::traits::Write for Sink {
             c.reset();
         }
 
-        Ok(hasher.digest().into())
+        Ok(hasher.try_finalize()?)
     }
 }
@@ -44,6 +44,20 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("Could not obtain missing object {id} from the promisor")]
+        Promisor {
+            #[source]
+            source: Box<dyn std::error::Error + Send + Sync + 'static>,
+            /// The object that was missing
+            id: gix_hash::ObjectId,
+        },
+        #[error("Could not obtain {num_objects} missing objects from the promisor")]
+        PromisorPrefetch {
+            #[source]
+            source: Box<dyn std::error::Error + Send + Sync + 'static>,
+            /// The amount of objects that were missing
+            num_objects: usize,
+        },
     }
 
     #[derive(Copy, Clone)]
@@ -111,6 +125,7 @@ where
             }
         }
 
+        let mut used_promisor = false;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -164,6 +179,7 @@ where
                             Ok(r) => Ok((
                                 gix_object::Data {
                                     kind: r.kind,
+                                    object_hash: id.kind(),
                                     data: buffer.as_slice(),
                                 },
                                 Some(gix_pack::data::entry::Location {
@@ -265,6 +281,7 @@ where
                                     (
                                         gix_object::Data {
                                             kind: r.kind,
+                                            object_hash: id.kind(),
                                             data: buffer.as_slice(),
                                         },
                                         Some(gix_pack::data::entry::Location {
@@ -301,22 +318,73 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    if recursion.is_none() && !used_promisor && self.fetch_from_promisor(id, snapshot)? {
+                        used_promisor = true;
+                        continue 'outer;
+                    }
+                    return Ok(None);
+                }
             }
         }
     }
 
-    pub(crate) fn clear_cache(&self) {
-        self.packed_object_count.borrow_mut().take();
+    /// Obtain the missing object `id` from our promisor, if there is one, and refresh `snapshot` to learn about it.
+    /// Return `true` if the promisor was asked for the object.
+    pub(crate) fn fetch_from_promisor(
+        &self,
+        id: &gix_hash::oid,
+        snapshot: &mut load_index::Snapshot,
+    ) -> Result<bool, Error> {
+        let Some(promisor) = self.promisor.as_deref() else {
+            return Ok(false);
+        };
+        promisor.fetch(&[id.to_owned()]).map_err(|source| Error::Promisor {
+            source,
+            id: id.to_owned(),
+        })?;
+        self.refresh_after_promisor_fetch(snapshot)?;
+        Ok(true)
     }
-}
 
-impl<S> gix_pack::Find for super::Handle<S>
-where
-    S: Deref<Target = super::Store> + Clone,
-{
-    // TODO: probably make this method fallible, but that would mean its own error type.
-    fn contains(&self, id: &gix_hash::oid) -> bool {
+    /// Obtain all objects with `ids` that are missing locally from our promisor with a single request, if there is one,
+    /// instead of fetching each of them individually once they are accessed.
+    /// Return the amount of objects that were requested from the promisor.
+    ///
+    /// This is useful before accessing many objects of a partial clone, like when checking out a tree.
+    pub fn prefetch(&self, ids: impl IntoIterator<Item = gix_hash::ObjectId>) -> Result<usize, Error> {
+        let Some(promisor) = self.promisor.as_deref() else {
+            return Ok(0);
+        };
+        let mut missing: Vec<_> = ids.into_iter().collect();
+        missing.sort_unstable();
+        missing.dedup();
+        // Avoid checking the disk for new packs for each missing object, the promisor is what provides them.
+        missing.retain(|id| !self.contains_inner(id, super::RefreshMode::Never));
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        promisor.fetch(&missing).map_err(|source| Error::PromisorPrefetch {
+            source,
+            num_objects: missing.len(),
+        })?;
+        self.refresh_after_promisor_fetch(&mut self.snapshot.borrow_mut())?;
+        Ok(missing.len())
+    }
+
+    fn refresh_after_promisor_fetch(&self, snapshot: &mut load_index::Snapshot) -> Result<(), Error> {
+        // The objects were written to disk, so we have to look there even if refreshes are turned off otherwise.
+        if let Some(new_snapshot) = self
+            .store
+            .load_one_index(super::RefreshMode::AfterAllIndicesLoaded, snapshot.marker)?
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+        Ok(())
+    }
+
+    fn contains_inner(&self, id: &gix_hash::oid, refresh: super::RefreshMode) -> bool {
         let mut snapshot = self.snapshot.borrow_mut();
         loop {
             for (idx, index) in snapshot.indices.iter().enumerate() {
@@ -334,7 +402,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker) {
+            match self.store.load_one_index(refresh, snapshot.marker) {
                 Ok(Some(new_snapshot)) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
@@ -345,6 +413,20 @@ where
         }
     }
 
+    pub(crate) fn clear_cache(&self) {
+        self.packed_object_count.borrow_mut().take();
+    }
+}
+
+impl<S> gix_pack::Find for super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    // TODO: probably make this method fallible, but that would mean its own error type.
+    fn contains(&self, id: &gix_hash::oid) -> bool {
+        self.contains_inner(id, self.refresh)
+    }
+
     fn try_find_cached<'a>(
         &self,
         id: &gix_hash::oid,
@@ -255,6 +255,9 @@ impl super::Store {
             store: self.clone(),
             refresh: RefreshMode::default(),
             ignore_replacements: false,
+            promisor: None,
+            #[cfg(feature = "big-files")]
+            big_file_threshold: None,
             token: Some(token),
             inflate: RefCell::new(Default::default()),
             snapshot: RefCell::new(self.collect_snapshot()),
@@ -272,6 +275,9 @@ impl super::Store {
             store: self.clone(),
             refresh: Default::default(),
             ignore_replacements: false,
+            promisor: None,
+            #[cfg(feature = "big-files")]
+            big_file_threshold: None,
             token: Some(token),
             inflate: RefCell::new(Default::default()),
             snapshot: RefCell::new(self.collect_snapshot()),
@@ -364,6 +370,11 @@ impl super::Handle<Rc<super::Store>> {
         let mut cache = store.to_handle_arc();
         cache.refresh = self.refresh;
         cache.max_recursion_depth = self.max_recursion_depth;
+        cache.promisor.clone_from(&self.promisor);
+        #[cfg(feature = "big-files")]
+        {
+            cache.big_file_threshold = self.big_file_threshold;
+        }
         Ok(cache)
     }
 }
@@ -384,6 +395,9 @@ where
             store: self.store.clone(),
             refresh: self.refresh,
             ignore_replacements: self.ignore_replacements,
+            promisor: self.promisor.clone(),
+            #[cfg(feature = "big-files")]
+            big_file_threshold: self.big_file_threshold,
             token: {
                 let token = self.store.register_handle();
                 match self.token.as_ref().expect("token is always set here ") {
@@ -1,5 +1,5 @@
 //! The standard object store which should fit all needs.
-use std::{cell::RefCell, ops::Deref};
+use std::{cell::RefCell, ops::Deref, sync::Arc};
 
 use gix_features::zlib;
 
@@ -24,6 +24,17 @@ where
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
 
+    /// If set, objects that can't be found will be obtained from it once before giving up, making them available in
+    /// the object database. This is the way partial clones obtain objects from their promisor remote.
+    ///
+    /// Note that [`contains()`](gix_pack::Find::contains()) never calls it.
+    pub promisor: Option<Arc<dyn Promisor>>,
+
+    /// If set, blobs larger than this amount of bytes are written into a pack of their own along with an index, instead of
+    /// being written as loose object, similar to `core.bigFileThreshold` in `git`.
+    #[cfg(feature = "big-files")]
+    pub big_file_threshold: Option<u64>,
+
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     inflate: RefCell<zlib::Inflate>,
@@ -43,6 +54,24 @@ pub enum RefreshMode {
     Never,
 }
 
+/// A way to obtain objects that are missing locally on demand, typically by fetching them from the *promisor remote*
+/// of a partial clone.
+pub trait Promisor: Send + Sync {
+    /// Make the objects with the given `ids` available in the object database, or fail.
+    ///
+    /// Objects that are still missing after a successful call are considered to not exist.
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+impl<F> Promisor for F
+where
+    F: Fn(&[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> + Send + Sync,
+{
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self(ids)
+    }
+}
+
 impl RefreshMode {
     /// Set this refresh mode to never refresh.
     pub fn never(&mut self) {
@@ -58,6 +87,8 @@ pub mod prefix;
 
 mod header;
 
+mod stream;
+
 ///
 pub mod iter;
 
@@ -0,0 +1,147 @@
+use std::{io, ops::Deref, sync::Arc};
+
+use gix_features::zlib;
+use gix_hash::oid;
+
+use crate::{
+    find::Stream,
+    store::{handle, load_index},
+};
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Find the undeltified pack entry for `id` in all currently loaded indices, returning the pack and the entry.
+    ///
+    /// Packs that aren't loaded yet will be loaded, but other than that no attempt is made to refresh the `snapshot`.
+    fn try_find_base_entry_in_packs(
+        &self,
+        id: &oid,
+        snapshot: &mut load_index::Snapshot,
+    ) -> Result<Option<(Arc<gix_pack::data::File>, gix_pack::data::Entry)>, super::find::Error> {
+        let marker = snapshot.marker;
+        for index in snapshot.indices.iter_mut() {
+            if let Some(handle::index_lookup::Outcome {
+                object_index: handle::IndexForObjectInPack { pack_id, pack_offset },
+                index_file: _,
+                pack: possibly_pack,
+            }) = index.lookup(id)
+            {
+                let pack = match possibly_pack {
+                    Some(pack) => pack.clone(),
+                    None => match self.store.load_pack(pack_id, marker)? {
+                        Some(pack) => {
+                            *possibly_pack = Some(pack.clone());
+                            pack
+                        }
+                        None => return Ok(None),
+                    },
+                };
+                let entry = pack.entry(pack_offset)?;
+                return Ok((!entry.header.is_delta()).then_some((pack, entry)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<S> crate::FindStream for super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    fn try_find_stream(&self, id: &oid) -> Result<Option<Stream>, gix_object::find::Error> {
+        let resolved_id = match self.ignore_replacements {
+            true => id,
+            false => self
+                .store
+                .replacements
+                .binary_search_by(|(map_this, _)| map_this.as_ref().cmp(id))
+                .map_or(id, |pos| self.store.replacements[pos].1.as_ref()),
+        };
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if let Some((pack, entry)) = self
+                .try_find_base_entry_in_packs(resolved_id, &mut snapshot)
+                .map_err(|err| Box::new(err) as gix_object::find::Error)?
+            {
+                let kind = entry.header.as_kind().expect("non-delta entries have a kind");
+                return Ok(Some(Stream::new(
+                    kind,
+                    entry.decompressed_size,
+                    PackEntry {
+                        input: PackData {
+                            pack,
+                            pos: entry.data_offset,
+                        },
+                        state: zlib::Decompress::new(true),
+                    },
+                )));
+            }
+
+            for lodb in snapshot.loose_dbs.iter() {
+                if let Some(stream) = lodb
+                    .try_find_stream(resolved_id)
+                    .map_err(|err| Box::new(err) as gix_object::find::Error)?
+                {
+                    return Ok(Some(stream));
+                }
+            }
+        }
+
+        // Deltified objects need to be resolved in memory, as do objects that only appear after refreshing the
+        // snapshot or fetching from a promisor remote, which is all handled when finding the object in full.
+        let mut buf = Vec::new();
+        let kind = match gix_object::Find::try_find(self, id, &mut buf)? {
+            Some(data) => data.kind,
+            None => return Ok(None),
+        };
+        Ok(Some(Stream::from_data(kind, buf)))
+    }
+}
+
+/// Decompress the data of an undeltified pack entry while reading.
+struct PackEntry {
+    input: PackData,
+    state: zlib::Decompress,
+}
+
+/// The compressed data of a pack entry, starting at `pos`.
+struct PackData {
+    pack: Arc<gix_pack::data::File>,
+    pos: gix_pack::data::Offset,
+}
+
+impl io::BufRead for PackData {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let pack_end = self.pack.pack_end() as gix_pack::data::Offset;
+        self.pack.entry_slice(self.pos..pack_end).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Pack entry data at offset {} is out of bounds of the pack data ending at {pack_end}",
+                    self.pos
+                ),
+            )
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as gix_pack::data::Offset;
+    }
+}
+
+impl io::Read for PackData {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_read = io::BufRead::fill_buf(self)?.read(buf)?;
+        io::BufRead::consume(self, num_read);
+        Ok(num_read)
+    }
+}
+
+impl io::Read for PackEntry {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        zlib::stream::inflate::read(&mut self.input, &mut self.state, buf)
+    }
+}
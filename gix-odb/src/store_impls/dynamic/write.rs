@@ -18,6 +18,9 @@ mod error {
         LooseWrite(#[from] loose::write::Error),
         #[error(transparent)]
         Io(#[from] std::io::Error),
+        #[cfg(feature = "big-files")]
+        #[error("Could not write a big blob into a pack of its own")]
+        BigBlob(#[from] gix_pack::bundle::write::Error),
     }
 }
 pub use error::Error;
@@ -29,6 +32,20 @@ where
     S: Deref<Target = dynamic::Store> + Clone,
 {
     fn write_stream(&self, kind: Kind, size: u64, from: &mut dyn Read) -> Result<ObjectId, crate::write::Error> {
+        #[cfg(feature = "big-files")]
+        if kind == Kind::Blob && self.big_file_threshold.map_or(false, |threshold| size > threshold) {
+            let pack_dir = self.store.path().join("pack");
+            std::fs::create_dir_all(&pack_dir).map_err(Error::from)?;
+            let (id, _bundle) = gix_pack::Bundle::write_blob_to_directory(
+                size,
+                from,
+                &pack_dir,
+                gix_pack::index::Version::default(),
+                self.store.object_hash(),
+            )
+            .map_err(Error::from)?;
+            return Ok(id);
+        }
         let mut snapshot = self.snapshot.borrow_mut();
         Ok(match snapshot.loose_dbs.first() {
             Some(ldb) => ldb.write_stream(kind, size, from)?,
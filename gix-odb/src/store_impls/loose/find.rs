@@ -179,6 +179,40 @@ impl Store {
         Ok(Some((size, kind)))
     }
 
+    /// Return a stream over the data of the object identified by `id`, decompressing it incrementally while reading,
+    /// or `None` if it doesn't exist in the database.
+    pub fn try_find_stream(&self, id: &gix_hash::oid) -> Result<Option<crate::find::Stream>, Error> {
+        debug_assert_eq!(self.object_hash, id.kind());
+        let path = hash_path(id, self.path.clone());
+        let file = match fs::File::open(&path) {
+            Ok(f) => f,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Io {
+                    source: err,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+
+        let mut stream = stream::Inflate {
+            file: std::io::BufReader::new(file),
+            state: zlib::Decompress::new(true),
+            header_buf: [0; HEADER_MAX_SIZE],
+            body_in_header_buf: 0..0,
+        };
+        let num_read = zlib::stream::inflate::read(&mut stream.file, &mut stream.state, &mut stream.header_buf)
+            .map_err(|e| Error::Io {
+                source: e,
+                action: "deflate",
+                path: path.to_owned(),
+            })?;
+        let (kind, size, header_size) = gix_object::decode::loose_header(&stream.header_buf[..num_read])?;
+        stream.body_in_header_buf = header_size..num_read;
+        Ok(Some(crate::find::Stream::new(kind, size, stream)))
+    }
+
     fn find_inner<'a>(&self, id: &gix_hash::oid, buf: &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
@@ -261,6 +295,39 @@ impl Store {
                 .expect("BUG: here the size is already confirmed to fit into memory"),
             0,
         );
-        Ok(gix_object::Data { kind, data: buf })
+        Ok(gix_object::Data {
+            kind,
+            object_hash: self.object_hash,
+            data: buf,
+        })
+    }
+}
+
+mod stream {
+    use std::{fs, io, ops::Range};
+
+    use gix_features::zlib;
+
+    use crate::store_impls::loose::HEADER_MAX_SIZE;
+
+    /// Decompress a loose object file while reading, starting with the part of the body that was decompressed along with the header.
+    pub(super) struct Inflate {
+        pub file: io::BufReader<fs::File>,
+        pub state: zlib::Decompress,
+        pub header_buf: [u8; HEADER_MAX_SIZE],
+        pub body_in_header_buf: Range<usize>,
+    }
+
+    impl io::Read for Inflate {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if !self.body_in_header_buf.is_empty() {
+                let body = &self.header_buf[self.body_in_header_buf.clone()];
+                let num_copied = body.len().min(buf.len());
+                buf[..num_copied].copy_from_slice(&body[..num_copied]);
+                self.body_in_header_buf.start += num_copied;
+                return Ok(num_copied);
+            }
+            zlib::stream::inflate::read(&mut self.file, &mut self.state, buf)
+        }
     }
 }
@@ -24,6 +24,8 @@ pub enum Error {
         source: tempfile::PersistError,
         target: PathBuf,
     },
+    #[error(transparent)]
+    Hasher(#[from] hash::Error),
 }
 
 impl crate::traits::Write for Store {
@@ -129,7 +131,7 @@ impl Store {
         &self,
         hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
     ) -> Result<gix_hash::ObjectId, Error> {
-        let id = gix_hash::ObjectId::from(hash.digest());
+        let id = hash.try_finalize()?;
         let object_path = loose::hash_path(&id, self.path.clone());
         let object_dir = object_path
             .parent()
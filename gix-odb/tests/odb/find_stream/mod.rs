@@ -0,0 +1,61 @@
+use std::io::Read;
+
+use gix_odb::FindStreamExt;
+
+use crate::{hex_to_id, odb::db};
+
+fn assert_stream_matches_object(db: &gix_odb::Handle, id: &gix_hash::oid) {
+    let mut buf = Vec::new();
+    let expected = gix_object::Find::try_find(db, id, &mut buf)
+        .expect("no read error")
+        .expect("object exists");
+
+    let mut stream = db.find_stream(id).expect("object exists");
+    assert_eq!(stream.kind, expected.kind, "{id}");
+    assert_eq!(stream.size, expected.data.len() as u64, "{id}");
+    let mut actual = Vec::new();
+    stream.read_to_end(&mut actual).expect("stream can be read in full");
+    assert_eq!(
+        actual, expected.data,
+        "{id}: streamed data is the same as the decoded object"
+    );
+}
+
+#[test]
+fn loose_object() {
+    assert_stream_matches_object(&db(), &hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"));
+}
+
+#[test]
+fn pack_object() {
+    let db = db();
+    for hex in [
+        "501b297447a8255d3533c6858bb692575cdefaa0", // pack 11fd
+        "4dac9989f96bc5b5b1263b582c08f0c5f0b58542", // pack a2bf
+        "dd25c539efbb0ab018caa4cda2d133285634e9b5", // pack c043
+    ] {
+        assert_stream_matches_object(&db, &hex_to_id(hex));
+    }
+}
+
+#[test]
+fn all_objects_including_deltified_ones() -> crate::Result {
+    let db = db();
+    for id in db.iter()? {
+        assert_stream_matches_object(&db, &id?);
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_object() {
+    let db = db();
+    let id = hex_to_id("0000000000000000000000000000000000000001");
+    assert!(gix_odb::FindStream::try_find_stream(&db, &id)
+        .expect("no read error")
+        .is_none());
+    assert!(matches!(
+        db.find_stream(&id),
+        Err(gix_object::find::existing::Error::NotFound { .. })
+    ));
+}
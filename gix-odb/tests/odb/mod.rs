@@ -17,6 +17,7 @@ fn db_small_packs() -> gix_odb::Handle {
 
 pub mod alternate;
 pub mod find;
+pub mod find_stream;
 pub mod header;
 pub mod memory;
 pub mod regression;
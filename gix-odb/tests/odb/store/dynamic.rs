@@ -2,7 +2,7 @@ use std::process::Command;
 
 use gix_hash::ObjectId;
 use gix_object::{Exists, FindExt};
-use gix_odb::{store, store::iter::Ordering, Header, Write};
+use gix_odb::{store, store::iter::Ordering, Header, HeaderExt, Write};
 use gix_testtools::fixture_path_standalone;
 
 use crate::{hex_to_id, odb::db};
@@ -33,6 +33,7 @@ fn db_with_all_object_sources() -> crate::Result<(gix_odb::Handle, gix_testtools
         &std::sync::atomic::AtomicBool::default(),
         gix_odb::pack::multi_index::write::Options {
             object_hash: gix_hash::Kind::Sha1,
+            reverse_index: false,
         },
     )?;
     Ok((gix_odb::at(objects_dir.path())?, objects_dir))
@@ -221,6 +222,42 @@ fn write() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn write_big_blobs_into_packs_of_their_own() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let mut handle = gix_odb::at(dir.path())?;
+    handle.big_file_threshold = Some(b"hello world".len() as u64 - 1);
+
+    let small_id = handle.write_buf(gix_object::Kind::Blob, b"hi")?;
+    let tree_id = handle.write_buf(gix_object::Kind::Tree, b"")?;
+    let big_id = handle.write_buf(gix_object::Kind::Blob, b"hello world")?;
+    assert_eq!(big_id, hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f"));
+
+    let loose = gix_odb::loose::Store::at(dir.path(), gix_hash::Kind::Sha1);
+    assert!(loose.contains(&small_id), "small blobs are written as loose objects…");
+    assert!(loose.contains(&tree_id), "…as are all objects that aren't blobs…");
+    assert!(!loose.contains(&big_id), "…while big blobs are not");
+
+    let mut pack_files = std::fs::read_dir(dir.path().join("pack"))?
+        .map(|entry| entry.map(|e| e.path().extension().map(ToOwned::to_owned)))
+        .collect::<Result<Vec<_>, _>>()?;
+    pack_files.sort();
+    assert_eq!(
+        pack_files,
+        [Some("idx".into()), Some("pack".into()), Some("rev".into())],
+        "there is a pack with its index and reverse index, but no keep file"
+    );
+
+    let mut buf = Vec::new();
+    assert_eq!(handle.find_blob(&big_id, &mut buf)?.data, b"hello world");
+    assert!(
+        matches!(handle.header(big_id)?, gix_odb::find::Header::Packed(_)),
+        "the blob is found in the pack"
+    );
+    assert_eq!(handle.packed_object_count()?, 1);
+    Ok(())
+}
+
 #[test]
 fn alternate_dbs_query() -> crate::Result {
     let dir = gix_testtools::scripted_fixture_read_only_standalone("make_alternates_odb.sh")?;
@@ -1003,3 +1040,137 @@ mod verify {
         );
     }
 }
+
+#[test]
+fn promisor_provides_missing_objects_on_lookup() -> crate::Result {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    let dir = gix_testtools::tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("pack"))?;
+    let mut handle = gix_odb::at(dir.path())?;
+    handle.refresh_never();
+
+    let pack_name = "pack-a2bf8e71d8c18879e499335762dd95119d93d9f1";
+    let index = gix_odb::pack::index::File::at(
+        fixture_path_standalone(format!("objects/pack/{pack_name}.idx")),
+        gix_hash::Kind::Sha1,
+    )?;
+    let id = index.oid_at_index(0).to_owned();
+    let calls = Arc::new(AtomicUsize::default());
+    handle.promisor = Some(Arc::new({
+        let calls = calls.clone();
+        let pack_dir = dir.path().join("pack");
+        move |ids: &[ObjectId]| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            calls.fetch_add(ids.len(), Ordering::SeqCst);
+            for ext in ["idx", "pack"] {
+                let name = format!("{pack_name}.{ext}");
+                std::fs::copy(
+                    fixture_path_standalone(format!("objects/pack/{name}")),
+                    pack_dir.join(name),
+                )?;
+            }
+            Ok(())
+        }
+    }));
+
+    assert!(
+        !handle.exists(&id),
+        "checking for existence never triggers the promisor…"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let mut buf = Vec::new();
+    let obj = handle.find(&id, &mut buf)?;
+    assert_eq!(obj.kind, gix_object::Kind::Tree, "…but finding objects does");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(handle.try_header(&id)?.is_some(), "the object is now available locally");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert!(handle.try_header(&missing)?.is_none());
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        2,
+        "the promisor is asked only once per lookup even if it can't provide the object"
+    );
+
+    handle.promisor = Some(Arc::new(
+        |_ids: &[ObjectId]| -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Err("unreachable".into()) },
+    ));
+    let err = gix_object::Find::try_find(&handle, &missing, &mut buf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Could not obtain missing object aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa from the promisor"
+    );
+    Ok(())
+}
+
+#[test]
+fn promisor_provides_missing_objects_in_batches_with_prefetch() -> crate::Result {
+    use std::sync::{Arc, Mutex};
+    let dir = gix_testtools::tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("pack"))?;
+    let mut handle = gix_odb::at(dir.path())?;
+
+    let pack_name = "pack-a2bf8e71d8c18879e499335762dd95119d93d9f1";
+    let index = gix_odb::pack::index::File::at(
+        fixture_path_standalone(format!("objects/pack/{pack_name}.idx")),
+        gix_hash::Kind::Sha1,
+    )?;
+    assert_eq!(
+        handle.prefetch(index.iter().map(|e| e.oid))?,
+        0,
+        "without promisor, there is nothing to do"
+    );
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    handle.promisor = Some(Arc::new({
+        let requests = requests.clone();
+        let pack_dir = dir.path().join("pack");
+        move |ids: &[ObjectId]| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            requests.lock().expect("not poisoned").push(ids.to_owned());
+            for ext in ["idx", "pack"] {
+                let name = format!("{pack_name}.{ext}");
+                std::fs::copy(
+                    fixture_path_standalone(format!("objects/pack/{name}")),
+                    pack_dir.join(name),
+                )?;
+            }
+            Ok(())
+        }
+    }));
+
+    let ids: Vec<_> = index.iter().map(|e| e.oid).collect();
+    let num_objects = ids.len();
+    assert_eq!(
+        handle.prefetch(ids.iter().chain(ids.iter()).copied())?,
+        num_objects,
+        "duplicates are removed"
+    );
+    assert_eq!(
+        requests.lock().expect("not poisoned").as_slice(),
+        std::slice::from_ref(&ids),
+        "all missing objects are requested at once, in order"
+    );
+
+    let mut buf = Vec::new();
+    for id in &ids {
+        handle.find(id, &mut buf)?;
+    }
+    assert_eq!(handle.prefetch(ids)?, 0, "nothing is missing anymore");
+    assert_eq!(
+        requests.lock().expect("not poisoned").len(),
+        1,
+        "the prefetched objects are available without further requests"
+    );
+
+    handle.promisor = Some(Arc::new(
+        |_ids: &[ObjectId]| -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Err("unreachable".into()) },
+    ));
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    let err = handle.prefetch(Some(missing)).unwrap_err();
+    assert_eq!(err.to_string(), "Could not obtain 1 missing objects from the promisor");
+    Ok(())
+}
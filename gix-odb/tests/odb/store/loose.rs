@@ -74,6 +74,41 @@ mod write {
         Ok(())
     }
 
+    #[test]
+    fn read_and_write_sha256() -> crate::Result {
+        let hk = gix_hash::Kind::Sha256;
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), hk);
+
+        let empty_blob = db.write_buf(gix_object::Kind::Blob, &[])?;
+        assert_eq!(empty_blob, gix_hash::ObjectId::empty_blob(hk));
+        let blob = db.write_buf(gix_object::Kind::Blob, b"content")?;
+        let mut tree = gix_object::Tree::empty();
+        tree.entries.push(gix_object::tree::Entry {
+            mode: gix_object::tree::EntryKind::Blob.into(),
+            filename: "file".into(),
+            oid: blob,
+        });
+        let tree_id = db.write(&tree)?;
+        assert_eq!(tree_id.kind(), hk);
+
+        let mut buf = Vec::new();
+        let obj = db.try_find(&tree_id, &mut buf)?.expect("id present");
+        assert_eq!(obj.object_hash, hk);
+        assert_eq!(
+            obj.decode()?.into_tree().expect("a tree").entries[0].oid,
+            blob.as_ref(),
+            "trees with Sha256 entries can be decoded"
+        );
+
+        let mut ids = db.iter().collect::<Result<Vec<_>, _>>()?;
+        ids.sort();
+        let mut expected = vec![empty_blob, blob, tree_id];
+        expected.sort();
+        assert_eq!(ids, expected, "objects can be listed");
+        Ok(())
+    }
+
     #[test]
     #[cfg(unix)]
     fn it_writes_objects_with_similar_permissions() -> crate::Result {
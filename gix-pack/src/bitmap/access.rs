@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use crate::bitmap::{Bits, Entry, File, Index, PackOrder, Version};
+
+/// Basic file information
+impl File {
+    /// The version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The kind of hash we assume.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// The checksum of the pack or multi-pack index whose objects we refer to.
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        self.checksum
+    }
+
+    /// All entries in the order they are stored in.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Return `true` if the name-hash of each object is stored.
+    pub fn has_name_hash_cache(&self) -> bool {
+        self.name_hashes.is_some()
+    }
+
+    /// Return `true` if a lookup table will be written, or was present when reading the file.
+    pub fn has_lookup_table(&self) -> bool {
+        self.has_lookup_table
+    }
+}
+
+/// Bitmaps
+impl File {
+    /// Return the name-hash of the object at `index_pos` in the index, if we have a name-hash cache.
+    ///
+    /// Note that a hash of `0` means the name is unknown.
+    pub fn name_hash(&self, index_pos: u32) -> Option<u32> {
+        self.name_hashes.as_ref()?.get(index_pos as usize).copied()
+    }
+
+    /// Return the bits of all objects of the given `kind`.
+    pub fn type_bits(&self, kind: gix_object::Kind) -> Bits {
+        use gix_object::Kind::*;
+        Bits::from_ewah(match kind {
+            Commit => &self.commits,
+            Tree => &self.trees,
+            Blob => &self.blobs,
+            Tag => &self.tags,
+        })
+    }
+
+    /// Return the index into [`entries()`](Self::entries()) of the entry for the commit at `index_pos` in the index.
+    pub fn entry_index_by_index_position(&self, index_pos: u32) -> Option<usize> {
+        self.entries_by_index_position
+            .binary_search_by_key(&index_pos, |(pos, _)| *pos)
+            .ok()
+            .map(|idx| self.entries_by_index_position[idx].1)
+    }
+
+    /// Return the bits of all objects reachable from the commit of the entry at `entry_idx`, which must be valid.
+    pub fn bits(&self, entry_idx: usize) -> Bits {
+        let mut chain = vec![entry_idx];
+        let mut idx = entry_idx;
+        while self.entries[idx].xor_offset != 0 {
+            idx -= usize::from(self.entries[idx].xor_offset);
+            chain.push(idx);
+        }
+        let mut bits = Bits::default();
+        for idx in chain.into_iter().rev() {
+            bits.symmetric_difference_with(&Bits::from_ewah(&self.entries[idx].bitmap));
+        }
+        bits
+    }
+}
+
+/// Access
+impl Index<'_> {
+    /// The amount of objects in the index.
+    pub fn num_objects(&self) -> u32 {
+        match self {
+            Index::Pack(index) => index.num_objects(),
+            Index::Multi { index, .. } => index.num_objects(),
+        }
+    }
+
+    /// The kind of hash used by the index.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        match self {
+            Index::Pack(index) => index.object_hash(),
+            Index::Multi { index, .. } => index.object_hash(),
+        }
+    }
+
+    /// The checksum a bitmap file for this index refers to, which is the checksum of the pack for pack indices.
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        match self {
+            Index::Pack(index) => index.pack_checksum(),
+            Index::Multi { index, .. } => index.checksum(),
+        }
+    }
+
+    /// Return the position of `id` in the index, if present.
+    pub fn lookup(&self, id: &gix_hash::oid) -> Option<u32> {
+        match self {
+            Index::Pack(index) => index.lookup(id),
+            Index::Multi { index, .. } => index.lookup(id),
+        }
+    }
+
+    /// Return the object id at `index_pos`, which must be valid.
+    pub fn oid_at_index(&self, index_pos: u32) -> &gix_hash::oid {
+        match self {
+            Index::Pack(index) => index.oid_at_index(index_pos),
+            Index::Multi { index, .. } => index.oid_at_index(index_pos),
+        }
+    }
+
+    /// Return the order of objects used by the bits of bitmaps for this index, or `None` if it can't be determined
+    /// for a multi-pack index.
+    ///
+    /// For packs, the reverse index next to the pack index is used if it belongs to it, which avoids sorting all offsets.
+    pub fn order(&self) -> Option<PackOrder> {
+        match self {
+            Index::Pack(index) => Some(
+                crate::index::reverse::File::at_index(index)
+                    .and_then(|rev| PackOrder::from_reverse_index(&rev))
+                    .unwrap_or_else(|| PackOrder::from_index(index)),
+            ),
+            Index::Multi { index, preferred_pack } => PackOrder::from_multi_index(index, *preferred_pack),
+        }
+    }
+
+    /// The path at which git expects the bitmap file for this index.
+    pub fn bitmap_path(&self) -> PathBuf {
+        match self {
+            Index::Pack(index) => index.path().with_extension("bitmap"),
+            Index::Multi { index, .. } => index
+                .path()
+                .with_file_name(format!("multi-pack-index-{}.bitmap", index.checksum())),
+        }
+    }
+}
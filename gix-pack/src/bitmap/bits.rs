@@ -0,0 +1,115 @@
+/// An uncompressed bitmap, with one bit for each object in [pack order](super::PackOrder), suitable for fast set operations.
+#[derive(Default, Debug, Clone)]
+pub struct Bits {
+    words: Vec<u64>,
+}
+
+impl PartialEq for Bits {
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        longer[..shorter.len()] == shorter[..] && longer[shorter.len()..].iter().all(|w| *w == 0)
+    }
+}
+
+impl Eq for Bits {}
+
+/// Conversion
+impl Bits {
+    /// Decompress `bitmap`.
+    pub fn from_ewah(bitmap: &gix_bitmap::ewah::Vec) -> Self {
+        Bits {
+            words: bitmap.to_words(),
+        }
+    }
+
+    /// Compress ourselves into an EWAH bitmap like git would.
+    pub fn to_ewah(&self) -> gix_bitmap::ewah::Vec {
+        gix_bitmap::ewah::Vec::from_words(&self.words)
+    }
+}
+
+/// Access
+impl Bits {
+    /// Return `true` if the bit at `pos` is set.
+    pub fn contains(&self, pos: usize) -> bool {
+        self.words.get(pos / 64).map_or(false, |w| w & (1 << (pos % 64)) != 0)
+    }
+
+    /// Return `true` if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Return the amount of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Return an iterator over the positions of all set bits, in ascending order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    word_idx * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// Mutation
+impl Bits {
+    /// Set the bit at `pos`, and return `true` if it wasn't set before.
+    pub fn insert(&mut self, pos: usize) -> bool {
+        let word_idx = pos / 64;
+        if word_idx >= self.words.len() {
+            self.words.resize(word_idx + 1, 0);
+        }
+        let mask = 1 << (pos % 64);
+        let was_unset = self.words[word_idx] & mask == 0;
+        self.words[word_idx] |= mask;
+        was_unset
+    }
+
+    /// Set all bits that are set in `other`.
+    pub fn union_with(&mut self, other: &Bits) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Unset all bits that are set in `other`.
+    pub fn difference_with(&mut self, other: &Bits) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= !other;
+        }
+    }
+
+    /// Unset all bits that are not set in `other`.
+    pub fn intersect_with(&mut self, other: &Bits) {
+        self.words.truncate(other.words.len());
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    /// Toggle all bits that are set in `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Bits) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+}
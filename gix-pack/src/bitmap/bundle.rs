@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use crate::{
+    bitmap::{self, Bits, Bundle, Index, PackOrder},
+    index, multi_index,
+};
+
+/// The error returned by [`Bundle::at()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not list the pack directory at '{path}'")]
+    ReadDir {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error(transparent)]
+    Index(#[from] index::init::Error),
+    #[error(transparent)]
+    MultiIndex(#[from] multi_index::init::Error),
+    #[error(transparent)]
+    Bitmap(#[from] bitmap::init::Error),
+}
+
+pub(crate) enum OwnedIndex {
+    Pack(index::File),
+    Multi(multi_index::File),
+}
+
+/// Initialization
+impl Bundle {
+    /// Open the bitmap file in `pack_dir` that git would use, which is the one of the multi-pack index if present,
+    /// or else the first one that belongs to a pack, or return `None` if there is no usable bitmap file.
+    ///
+    /// Like git, bitmap files that don't match the checksum of their (multi-pack) index are ignored.
+    pub fn at(pack_dir: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<Option<Bundle>, Error> {
+        let pack_dir = pack_dir.as_ref();
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        if multi_index_path.is_file() {
+            let index = multi_index::File::at(&multi_index_path)?;
+            let bitmap_path = Index::Multi {
+                index: &index,
+                preferred_pack: None,
+            }
+            .bitmap_path();
+            if bitmap_path.is_file() {
+                let bitmap = bitmap::File::at(bitmap_path, object_hash)?;
+                let order = PackOrder::from_multi_index(&index, None);
+                if let Some(order) = order.filter(|_| bitmap.checksum() == index.checksum()) {
+                    return Ok(Some(Bundle {
+                        bitmap,
+                        index: OwnedIndex::Multi(index),
+                        order,
+                    }));
+                }
+            }
+        }
+
+        let mut bitmap_paths: Vec<_> = std::fs::read_dir(pack_dir)
+            .map_err(|source| Error::ReadDir {
+                source,
+                path: pack_dir.to_owned(),
+            })?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().map_or(false, |ext| ext == "bitmap")
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .map_or(false, |name| name.starts_with("pack-"))
+            })
+            .collect();
+        bitmap_paths.sort();
+        for bitmap_path in bitmap_paths {
+            let index_path = bitmap_path.with_extension("idx");
+            if !index_path.is_file() {
+                continue;
+            }
+            let index = index::File::at(&index_path, object_hash)?;
+            let bitmap = bitmap::File::at(&bitmap_path, object_hash)?;
+            if bitmap.checksum() != index.pack_checksum() {
+                continue;
+            }
+            let order = Index::Pack(&index).order().expect("always available for packs");
+            return Ok(Some(Bundle {
+                bitmap,
+                index: OwnedIndex::Pack(index),
+                order,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Access
+impl Bundle {
+    /// The bitmap file itself.
+    pub fn bitmap(&self) -> &bitmap::File {
+        &self.bitmap
+    }
+
+    /// The index whose objects the bits refer to.
+    pub fn index(&self) -> Index<'_> {
+        match &self.index {
+            OwnedIndex::Pack(index) => Index::Pack(index),
+            OwnedIndex::Multi(index) => Index::Multi {
+                index,
+                preferred_pack: None,
+            },
+        }
+    }
+
+    /// The order of objects in our bitmaps.
+    pub fn order(&self) -> &PackOrder {
+        &self.order
+    }
+
+    /// Return the bit position of `id`, or `None` if it's not contained in our index.
+    pub fn bit_position(&self, id: &gix_hash::oid) -> Option<usize> {
+        self.index()
+            .lookup(id)
+            .and_then(|index_pos| self.order.bit_position(index_pos))
+    }
+
+    /// Return the id of the object at `bit_pos`, which must be valid.
+    pub fn oid_at_bit_position(&self, bit_pos: usize) -> &gix_hash::oid {
+        let index_pos = self.order.index_position(bit_pos).expect("valid bit position");
+        match &self.index {
+            OwnedIndex::Pack(index) => index.oid_at_index(index_pos),
+            OwnedIndex::Multi(index) => index.oid_at_index(index_pos),
+        }
+    }
+
+    /// Return the bits of all objects reachable from the commit `id`, or `None` if it has no bitmap.
+    pub fn reachable_from(&self, id: &gix_hash::oid) -> Option<Bits> {
+        let index_pos = self.index().lookup(id)?;
+        let entry_idx = self.bitmap.entry_index_by_index_position(index_pos)?;
+        Some(self.bitmap.bits(entry_idx))
+    }
+}
+
+impl std::fmt::Debug for Bundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let index_path = match &self.index {
+            OwnedIndex::Pack(index) => index.path(),
+            OwnedIndex::Multi(index) => index.path(),
+        };
+        f.debug_struct("Bundle")
+            .field("index", &index_path)
+            .field("entries", &self.bitmap.entries().len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use crate::bitmap::{self, flags, Entry, File, Version};
+
+/// Returned by [`bitmap::File::at()`] and [`bitmap::File::from_bytes()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open bitmap file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{message}")]
+    Corrupt { message: String },
+    #[error("Unsupported bitmap version: {version}")]
+    UnsupportedVersion { version: u16 },
+    #[error("Bitmap files with pseudo-merges aren't supported")]
+    UnsupportedPseudoMerges,
+    #[error("Could not decode {what} bitmap")]
+    Ewah {
+        what: &'static str,
+        source: gix_bitmap::ewah::decode::Error,
+    },
+}
+
+const HEADER_LEN: usize = 4 /* signature */ + 2 /* version */ + 2 /* flags */ + 4 /* entry count */;
+const ENTRY_HEADER_LEN: usize = 4 /* index position */ + 1 /* xor offset */ + 1 /* flags */;
+const LOOKUP_TABLE_ROW_LEN: usize = 4 /* commit position */ + 8 /* offset */ + 4 /* xor row */;
+
+/// Instantiation
+impl File {
+    /// Open the bitmap file at the given `path`.
+    ///
+    /// The `object_hash` is a way to read the same file format with different hashes, as the hash kind
+    /// isn't stored within the file format itself.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<File, Error> {
+        let path = path.as_ref();
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Self::from_bytes(&data, object_hash)
+    }
+
+    /// Parse a bitmap file from `data`, with object ids being of kind `object_hash`.
+    ///
+    /// The trailing checksum isn't verified.
+    pub fn from_bytes(data: &[u8], object_hash: gix_hash::Kind) -> Result<File, Error> {
+        let hash_len = object_hash.len_in_bytes();
+        if data.len() < HEADER_LEN + hash_len * 2 {
+            return Err(corrupt(format!(
+                "Bitmap file of size {} is too small for even an empty bitmap",
+                data.len()
+            )));
+        }
+        let (data, _trailer) = data.split_at(data.len() - hash_len);
+        let (signature, d) = data.split_at(4);
+        if signature != bitmap::SIGNATURE {
+            return Err(corrupt("Invalid signature".into()));
+        }
+        let version = u16::from_be_bytes(d[..2].try_into().unwrap());
+        if version != Version::V1 as u16 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let header_flags = u16::from_be_bytes(d[2..4].try_into().unwrap());
+        if header_flags & flags::PSEUDO_MERGES != 0 {
+            return Err(Error::UnsupportedPseudoMerges);
+        }
+        if header_flags & flags::FULL_DAG == 0 {
+            return Err(corrupt("Bitmaps must be closed under reachability".into()));
+        }
+        let num_entries = crate::read_u32(&d[4..8]) as usize;
+        let (checksum, mut d) = d[8..].split_at(hash_len);
+        let checksum = gix_hash::ObjectId::from_bytes_or_panic(checksum);
+
+        let mut decode = |what| {
+            gix_bitmap::ewah::decode(d)
+                .map(|(bitmap, rest)| {
+                    d = rest;
+                    bitmap
+                })
+                .map_err(|source| Error::Ewah { what, source })
+        };
+        let commits = decode("commit")?;
+        let trees = decode("tree")?;
+        let blobs = decode("blob")?;
+        let tags = decode("tag")?;
+
+        let mut entries = Vec::with_capacity(num_entries.min(d.len() / ENTRY_HEADER_LEN));
+        for entry_idx in 0..num_entries {
+            if d.len() < ENTRY_HEADER_LEN {
+                return Err(corrupt(format!(
+                    "Unexpected end of file while reading entry {entry_idx}"
+                )));
+            }
+            let index_position = crate::read_u32(&d[..4]);
+            let (xor_offset, entry_flags) = (d[4], d[5]);
+            if usize::from(xor_offset) > entry_idx || xor_offset > bitmap::MAX_XOR_OFFSET {
+                return Err(corrupt(format!(
+                    "Entry {entry_idx} refers to invalid previous entry at distance {xor_offset}"
+                )));
+            }
+            let (bitmap, rest) = gix_bitmap::ewah::decode(&d[ENTRY_HEADER_LEN..]).map_err(|source| Error::Ewah {
+                what: "commit entry",
+                source,
+            })?;
+            d = rest;
+            entries.push(Entry {
+                index_position,
+                xor_offset,
+                flags: entry_flags,
+                bitmap,
+            });
+        }
+
+        let has_lookup_table = header_flags & flags::LOOKUP_TABLE != 0;
+        if has_lookup_table {
+            // The lookup table only speeds up reading individual entries, which we read all of.
+            d = d
+                .get(num_entries * LOOKUP_TABLE_ROW_LEN..)
+                .ok_or_else(|| corrupt("Unexpected end of file while reading lookup table".into()))?;
+        }
+
+        let name_hashes = if header_flags & flags::HASH_CACHE != 0 {
+            if d.len() % 4 != 0 {
+                return Err(corrupt(format!(
+                    "Name-hash cache of {} bytes isn't a multiple of 4",
+                    d.len()
+                )));
+            }
+            let name_hashes = d.chunks_exact(4).map(crate::read_u32).collect();
+            d = &[];
+            Some(name_hashes)
+        } else {
+            None
+        };
+        if !d.is_empty() {
+            return Err(corrupt(format!("{} unexpected trailing bytes", d.len())));
+        }
+
+        Ok(File::from_parts(
+            Version::V1,
+            object_hash,
+            checksum,
+            [commits, trees, blobs, tags],
+            entries,
+            name_hashes,
+            has_lookup_table,
+        ))
+    }
+
+    pub(crate) fn from_parts(
+        version: Version,
+        object_hash: gix_hash::Kind,
+        checksum: gix_hash::ObjectId,
+        [commits, trees, blobs, tags]: [gix_bitmap::ewah::Vec; 4],
+        entries: Vec<Entry>,
+        name_hashes: Option<Vec<u32>>,
+        has_lookup_table: bool,
+    ) -> File {
+        let mut entries_by_index_position: Vec<_> = entries
+            .iter()
+            .enumerate()
+            .map(|(entry_idx, entry)| (entry.index_position, entry_idx))
+            .collect();
+        entries_by_index_position.sort_unstable();
+        File {
+            version,
+            object_hash,
+            checksum,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+            entries_by_index_position,
+            name_hashes,
+            has_lookup_table,
+        }
+    }
+}
+
+fn corrupt(message: String) -> Error {
+    Error::Corrupt { message }
+}
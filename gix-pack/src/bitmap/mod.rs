@@ -0,0 +1,118 @@
+//! Reachability bitmaps, which store for selected commits all objects reachable from them as bits in a bitmap.
+//!
+//! Bits refer to objects in the order in which they appear in a pack, or in the pseudo-pack of a multi-pack index,
+//! and allow to learn which objects to send without traversing trees.
+use crate::{index, multi_index};
+
+mod bits;
+pub use bits::Bits;
+
+mod order;
+pub use order::PackOrder;
+
+mod access;
+
+///
+pub mod bundle;
+
+///
+pub mod init;
+
+///
+pub mod write;
+
+/// The signature at the beginning of each bitmap file.
+pub const SIGNATURE: &[u8] = b"BITM";
+
+/// The largest distance to a previous entry whose bitmap an entry may be XORed with.
+pub const MAX_XOR_OFFSET: u8 = 160;
+
+/// Flags stored in the header of a bitmap file, controlling which optional parts are present.
+pub(crate) mod flags {
+    /// All bitmaps are closed under reachability, which is the only mode we support.
+    pub const FULL_DAG: u16 = 0x1;
+    /// A cache with the name-hash of each object is present.
+    pub const HASH_CACHE: u16 = 0x4;
+    /// A table to look up bitmaps by commit without reading all of them is present.
+    pub const LOOKUP_TABLE: u16 = 0x10;
+    /// Pseudo-merge bitmaps are present.
+    pub const PSEUDO_MERGES: u16 = 0x20;
+}
+
+/// The version of a bitmap file.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// The index whose objects are referred to by the bits in a bitmap.
+#[derive(Clone, Copy)]
+pub enum Index<'a> {
+    /// The index of a single pack, whose objects are ordered by their offset in the pack.
+    Pack(&'a index::File),
+    /// A multi-pack index, whose objects are ordered by pack and offset, with the preferred pack sorting first.
+    Multi {
+        /// The multi-pack index itself.
+        index: &'a multi_index::File,
+        /// The pack to sort first if the multi-pack index doesn't have a reverse index.
+        preferred_pack: Option<multi_index::PackIndex>,
+    },
+}
+
+/// A bitmap file, with reachability bitmaps for a selection of commits, along with bitmaps for each object type.
+///
+/// Its bits refer to objects in [pack order](PackOrder), whereas entries refer to commits by their position in the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    version: Version,
+    object_hash: gix_hash::Kind,
+    /// The checksum of the pack or multi-pack index we refer to.
+    checksum: gix_hash::ObjectId,
+    commits: gix_bitmap::ewah::Vec,
+    trees: gix_bitmap::ewah::Vec,
+    blobs: gix_bitmap::ewah::Vec,
+    tags: gix_bitmap::ewah::Vec,
+    entries: Vec<Entry>,
+    /// Sorted index positions of our entries, along with the index into `entries`.
+    entries_by_index_position: Vec<(u32, usize)>,
+    /// The name-hash of each object in index order.
+    name_hashes: Option<Vec<u32>>,
+    has_lookup_table: bool,
+}
+
+/// A bitmap file along with the index whose objects its bits refer to, which is needed to learn which objects are reachable.
+pub struct Bundle {
+    bitmap: File,
+    index: bundle::OwnedIndex,
+    order: PackOrder,
+}
+
+/// A commit with its reachability bitmap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The position of the commit in the pack index or multi-pack index.
+    pub index_position: u32,
+    /// If not `0`, the distance to a previous entry whose bitmap was XORed with ours before storing it in `bitmap`.
+    pub xor_offset: u8,
+    /// Flags like [`Entry::REUSE`].
+    pub flags: u8,
+    /// The bitmap as stored, which may have to be XORed with the bitmap of a previous entry to obtain all reachable objects.
+    pub bitmap: gix_bitmap::ewah::Vec,
+}
+
+impl Entry {
+    /// The flag indicating that the objects of this commit may be reused when delta-compressing.
+    pub const REUSE: u8 = 0x1;
+}
+
+/// Return the hash of `path` as used by git to find objects that are likely to be good deltas of each other.
+///
+/// The hash mostly depends on the last characters of the path, ignoring spaces, tabs, newlines and carriage returns like `git`.
+pub fn name_hash(path: &[u8]) -> u32 {
+    path.iter()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .fold(0, |hash, b| (hash >> 2).wrapping_add(u32::from(*b) << 24))
+}
@@ -0,0 +1,92 @@
+use crate::{index, multi_index};
+
+/// The order of objects used by the bits of a bitmap, which is the order in which they appear in a pack,
+/// or in the pseudo-pack of a multi-pack index.
+///
+/// It maps bit positions to positions in the (multi-pack) index, which are ordered by object id, and back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackOrder {
+    /// The index position for each bit position.
+    index_positions: Vec<u32>,
+    /// The bit position for each index position.
+    bit_positions: Vec<u32>,
+}
+
+/// Initialization
+impl PackOrder {
+    /// Order all objects in `index` by their offset in the pack.
+    pub fn from_index(index: &index::File) -> Self {
+        let mut offsets: Vec<_> = index
+            .iter()
+            .enumerate()
+            .map(|(index_pos, entry)| (entry.pack_offset, index_pos as u32))
+            .collect();
+        offsets.sort_unstable();
+        Self::from_index_positions(offsets.into_iter().map(|(_, index_pos)| index_pos).collect())
+    }
+
+    /// Use the order stored in the reverse index `file`, or return `None` if it refers to positions beyond its
+    /// amount of objects.
+    pub fn from_reverse_index(file: &index::reverse::File) -> Option<Self> {
+        let index_positions: Vec<_> = file.iter().collect();
+        index_positions
+            .iter()
+            .all(|index_pos| *index_pos < file.num_objects())
+            .then(|| Self::from_index_positions(index_positions))
+    }
+
+    /// Use the pseudo-pack order stored in `index`, or if there is none, order all objects by pack and offset
+    /// with `preferred_pack` sorting first.
+    ///
+    /// Return `None` if there is neither a stored order nor a `preferred_pack`.
+    pub fn from_multi_index(index: &multi_index::File, preferred_pack: Option<multi_index::PackIndex>) -> Option<Self> {
+        if let Some(order) = index.iter_pseudo_pack_order() {
+            return Some(Self::from_index_positions(order.collect()));
+        }
+        let preferred_pack = preferred_pack?;
+        let mut offsets: Vec<_> = (0..index.num_objects())
+            .map(|index_pos| {
+                let (pack, offset) = index.pack_id_and_pack_offset_at_index(index_pos);
+                (pack != preferred_pack, pack, offset, index_pos)
+            })
+            .collect();
+        offsets.sort_unstable();
+        Some(Self::from_index_positions(
+            offsets.into_iter().map(|(_, _, _, index_pos)| index_pos).collect(),
+        ))
+    }
+
+    fn from_index_positions(index_positions: Vec<u32>) -> Self {
+        let mut bit_positions = vec![0; index_positions.len()];
+        for (bit_pos, index_pos) in index_positions.iter().enumerate() {
+            bit_positions[*index_pos as usize] = bit_pos as u32;
+        }
+        PackOrder {
+            index_positions,
+            bit_positions,
+        }
+    }
+}
+
+/// Access
+impl PackOrder {
+    /// The amount of objects we order.
+    pub fn len(&self) -> usize {
+        self.index_positions.len()
+    }
+
+    /// Return `true` if there are no objects.
+    pub fn is_empty(&self) -> bool {
+        self.index_positions.is_empty()
+    }
+
+    /// Return the position in the index of the object at `bit_pos`.
+    pub fn index_position(&self, bit_pos: usize) -> Option<u32> {
+        self.index_positions.get(bit_pos).copied()
+    }
+
+    /// Return the bit position of the object at `index_pos` in the index.
+    pub fn bit_position(&self, index_pos: u32) -> Option<usize> {
+        self.bit_positions.get(index_pos as usize).map(|pos| *pos as usize)
+    }
+}
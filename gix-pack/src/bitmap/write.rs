@@ -0,0 +1,389 @@
+use std::{
+    collections::HashMap,
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::Count;
+use gix_object::{bstr::BString, FindExt};
+
+use crate::bitmap::{self, flags, name_hash, Bits, Entry, File, Index, Version};
+
+/// Configuration for [`bitmap::File::compute()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, store the name-hash of each object to help finding good delta bases when reusing the pack.
+    pub name_hash_cache: bool,
+    /// If `true`, write a table to look up the bitmap of individual commits without reading all of them.
+    pub lookup_table: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            name_hash_cache: true,
+            lookup_table: false,
+        }
+    }
+}
+
+/// The error returned by [`bitmap::File::compute()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    Header(#[from] gix_object::find::Error),
+    #[error("Object {id} is reachable but not contained in the pack, which is required for bitmaps")]
+    MissingObject { id: gix_hash::ObjectId },
+    #[error(
+        "The order of objects in the multi-pack index is unknown as it has no reverse index and no preferred pack"
+    )]
+    NoPackOrder,
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// The amount of previous entries to try XORing our bitmap with, like git.
+const XOR_OFFSET_SEARCH_WINDOW: usize = 10;
+
+/// Write
+impl File {
+    /// Write ourselves to `out` in the format understood by [`bitmap::File::at()`], and return the trailing checksum.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<gix_hash::ObjectId> {
+        let mut buf = Vec::new();
+        let mut header_flags = flags::FULL_DAG;
+        if self.name_hashes.is_some() {
+            header_flags |= flags::HASH_CACHE;
+        }
+        if self.has_lookup_table {
+            header_flags |= flags::LOOKUP_TABLE;
+        }
+        buf.extend_from_slice(bitmap::SIGNATURE);
+        buf.extend_from_slice(&(self.version as u16).to_be_bytes());
+        buf.extend_from_slice(&header_flags.to_be_bytes());
+        buf.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
+        buf.extend_from_slice(self.checksum.as_slice());
+        for bitmap in [&self.commits, &self.trees, &self.blobs, &self.tags] {
+            bitmap.write_to(&mut buf)?;
+        }
+
+        let mut offsets = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            offsets.push(buf.len() as u64);
+            buf.extend_from_slice(&entry.index_position.to_be_bytes());
+            buf.extend_from_slice(&[entry.xor_offset, entry.flags]);
+            entry.bitmap.write_to(&mut buf)?;
+        }
+
+        if self.has_lookup_table {
+            let mut rows: Vec<_> = (0..self.entries.len()).collect();
+            rows.sort_by_key(|entry_idx| self.entries[*entry_idx].index_position);
+            let mut row_by_entry = vec![0; rows.len()];
+            for (row, entry_idx) in rows.iter().enumerate() {
+                row_by_entry[*entry_idx] = row as u32;
+            }
+            for entry_idx in rows {
+                let entry = &self.entries[entry_idx];
+                let xor_row = match entry.xor_offset {
+                    0 => u32::MAX,
+                    xor_offset => row_by_entry[entry_idx - usize::from(xor_offset)],
+                };
+                buf.extend_from_slice(&entry.index_position.to_be_bytes());
+                buf.extend_from_slice(&offsets[entry_idx].to_be_bytes());
+                buf.extend_from_slice(&xor_row.to_be_bytes());
+            }
+        }
+
+        if let Some(name_hashes) = &self.name_hashes {
+            for hash in name_hashes {
+                buf.extend_from_slice(&hash.to_be_bytes());
+            }
+        }
+
+        let mut hasher = gix_features::hash::hasher(self.object_hash);
+        hasher.update(&buf);
+        let checksum = hasher.try_finalize()?;
+        out.write_all(&buf)?;
+        out.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
+
+/// Creation
+impl File {
+    /// Compute bitmaps for the objects in `index`, with bitmaps for a selection of commits reachable from `tips`,
+    /// which are peeled to commits, and with `objects` providing access to all objects in `index`.
+    ///
+    /// Like git, all commits get a bitmap if there are less than 100 of them, and otherwise the most recent ones
+    /// are selected more densely than older ones, preferring `tips` and merge commits.
+    /// `progress` is incremented for each object we learn the type of, and each bitmap we compute.
+    ///
+    /// All objects reachable from `tips` must be contained in `index`.
+    pub fn compute(
+        index: Index<'_>,
+        tips: impl IntoIterator<Item = gix_hash::ObjectId>,
+        objects: &(impl gix_object::Find + gix_object::FindHeader),
+        progress: &dyn Count,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<File, Error> {
+        let order = index.order().ok_or(Error::NoPackOrder)?;
+        let bit_position = |id: &gix_hash::oid| {
+            index
+                .lookup(id)
+                .and_then(|index_pos| order.bit_position(index_pos))
+                .ok_or_else(|| Error::MissingObject { id: id.to_owned() })
+        };
+
+        let mut type_bits: [Bits; 4] = Default::default();
+        for bit_pos in 0..order.len() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = index.oid_at_index(order.index_position(bit_pos).expect("in bounds"));
+            let kind = objects
+                .try_header(id)?
+                .ok_or_else(|| Error::MissingObject { id: id.to_owned() })?
+                .kind;
+            type_bits[kind_index(kind)].insert(bit_pos);
+            progress.inc();
+        }
+
+        let mut buf = Vec::new();
+        let mut tip_commits = Vec::new();
+        for mut id in tips {
+            let kind = loop {
+                let kind = objects.try_header(&id)?.ok_or(Error::MissingObject { id })?.kind;
+                if kind != gix_object::Kind::Tag {
+                    break kind;
+                }
+                id = objects.find_tag(&id, &mut buf)?.target();
+            };
+            if kind == gix_object::Kind::Commit {
+                tip_commits.push(bit_position(&id)?);
+            }
+        }
+
+        let mut commits = HashMap::new();
+        let mut queue = tip_commits.clone();
+        while let Some(bit_pos) = queue.pop() {
+            if commits.contains_key(&bit_pos) {
+                continue;
+            }
+            let id = index.oid_at_index(order.index_position(bit_pos).expect("in bounds"));
+            let commit = objects.find_commit(id, &mut buf)?;
+            let info = CommitInfo {
+                tree: bit_position(&commit.tree())?,
+                parents: commit.parents().map(|id| bit_position(&id)).collect::<Result<_, _>>()?,
+                time: commit.committer().time.seconds,
+            };
+            queue.extend(info.parents.iter().copied());
+            commits.insert(bit_pos, info);
+        }
+
+        let selected = select_commits(&commits, &tip_commits);
+        let mut is_selected = Bits::default();
+        for bit_pos in &selected {
+            is_selected.insert(*bit_pos);
+        }
+
+        let mut name_hashes = options.name_hash_cache.then(|| vec![0; order.len()]);
+        let mut named = Bits::default();
+        let mut bitmaps = HashMap::<usize, Bits>::new();
+        for commit in topo_order(&commits, &selected) {
+            if !is_selected.contains(commit) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let mut bits = Bits::default();
+            let mut trees = Vec::new();
+            let mut queue = vec![commit];
+            while let Some(bit_pos) = queue.pop() {
+                if bit_pos != commit {
+                    if let Some(ancestor_bits) = bitmaps.get(&bit_pos) {
+                        bits.union_with(ancestor_bits);
+                        continue;
+                    }
+                }
+                if !bits.insert(bit_pos) {
+                    continue;
+                }
+                let info = &commits[&bit_pos];
+                trees.push((info.tree, BString::default()));
+                queue.extend(info.parents.iter().copied());
+            }
+
+            while let Some((bit_pos, path)) = trees.pop() {
+                if !bits.insert(bit_pos) {
+                    continue;
+                }
+                let id = index.oid_at_index(order.index_position(bit_pos).expect("in bounds"));
+                let tree = objects.find_tree(id, &mut buf)?;
+                for entry in tree.entries.iter().filter(|entry| !entry.mode.is_commit()) {
+                    let entry_bit_pos = bit_position(entry.oid)?;
+                    let mut entry_path = path.clone();
+                    if !entry_path.is_empty() {
+                        entry_path.push(b'/');
+                    }
+                    entry_path.extend_from_slice(entry.filename);
+                    if let Some(name_hashes) = name_hashes.as_mut() {
+                        if named.insert(entry_bit_pos) {
+                            let index_pos = order.index_position(entry_bit_pos).expect("in bounds");
+                            name_hashes[index_pos as usize] = name_hash(&entry_path);
+                        }
+                    }
+                    if entry.mode.is_tree() {
+                        trees.push((entry_bit_pos, entry_path));
+                    } else {
+                        bits.insert(entry_bit_pos);
+                    }
+                }
+            }
+            bitmaps.insert(commit, bits);
+            progress.inc();
+        }
+
+        let bitmaps: Vec<_> = selected
+            .iter()
+            .map(|commit| bitmaps.remove(commit).expect("computed for each selected commit"))
+            .collect();
+        let entries = selected
+            .iter()
+            .enumerate()
+            .map(|(entry_idx, commit)| {
+                let (xor_offset, bitmap) = xor_compress(&bitmaps, entry_idx);
+                Entry {
+                    index_position: order.index_position(*commit).expect("in bounds"),
+                    xor_offset,
+                    flags: 0,
+                    bitmap,
+                }
+            })
+            .collect();
+
+        let [commits, trees, blobs, tags] = type_bits;
+        Ok(File::from_parts(
+            Version::V1,
+            index.object_hash(),
+            index.checksum(),
+            [commits.to_ewah(), trees.to_ewah(), blobs.to_ewah(), tags.to_ewah()],
+            entries,
+            name_hashes,
+            options.lookup_table,
+        ))
+    }
+}
+
+struct CommitInfo {
+    tree: usize,
+    parents: Vec<usize>,
+    time: gix_object::date::SecondsSinceUnixEpoch,
+}
+
+fn kind_index(kind: gix_object::Kind) -> usize {
+    use gix_object::Kind::*;
+    match kind {
+        Commit => 0,
+        Tree => 1,
+        Blob => 2,
+        Tag => 3,
+    }
+}
+
+/// Select the commits to compute bitmaps for like git, from newest to oldest.
+fn select_commits(commits: &HashMap<usize, CommitInfo>, tips: &[usize]) -> Vec<usize> {
+    let mut by_date: Vec<_> = commits.keys().copied().collect();
+    by_date.sort_by_key(|bit_pos| (std::cmp::Reverse(commits[bit_pos].time), *bit_pos));
+    if by_date.len() < 100 {
+        return by_date;
+    }
+
+    let mut selected = Vec::new();
+    let mut idx = 0;
+    loop {
+        let next = next_commit_index(idx);
+        if idx + next >= by_date.len() {
+            break;
+        }
+        let chosen = if next == 0 {
+            by_date[idx]
+        } else {
+            let mut chosen = by_date[idx + next];
+            for &candidate in &by_date[idx..=idx + next] {
+                if tips.contains(&candidate) {
+                    chosen = candidate;
+                    break;
+                }
+                if commits[&candidate].parents.len() > 1 {
+                    chosen = candidate;
+                }
+            }
+            chosen
+        };
+        selected.push(chosen);
+        idx += next + 1;
+    }
+    selected
+}
+
+/// Return how many commits to skip after the one at `idx` in date order, with more distance the older the commit is.
+fn next_commit_index(idx: usize) -> usize {
+    const MIN_COMMITS: usize = 100;
+    const MAX_COMMITS: usize = 5000;
+    const MUST_REGION: usize = 100;
+    const MIN_REGION: usize = 20000;
+
+    if idx <= MUST_REGION {
+        0
+    } else if idx <= MIN_REGION {
+        (idx - MUST_REGION).min(MIN_COMMITS)
+    } else {
+        (idx - MIN_REGION).clamp(MIN_COMMITS, MAX_COMMITS)
+    }
+}
+
+/// Return all commits reachable from `selected` such that parents come before their children.
+fn topo_order(commits: &HashMap<usize, CommitInfo>, selected: &[usize]) -> Vec<usize> {
+    let mut out = Vec::with_capacity(commits.len());
+    let mut seen = Bits::default();
+    for &start in selected {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut stack = vec![(start, 0)];
+        while let Some((commit, parent_idx)) = stack.last_mut() {
+            match commits[commit].parents.get(*parent_idx) {
+                Some(&parent) => {
+                    *parent_idx += 1;
+                    if seen.insert(parent) {
+                        stack.push((parent, 0));
+                    }
+                }
+                None => {
+                    out.push(*commit);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Compress the bitmap at `entry_idx`, possibly XORed with one of the previous bitmaps if that makes it smaller.
+fn xor_compress(bitmaps: &[Bits], entry_idx: usize) -> (u8, gix_bitmap::ewah::Vec) {
+    let bits = &bitmaps[entry_idx];
+    let mut best = (0, bits.to_ewah());
+    for xor_offset in 1..=XOR_OFFSET_SEARCH_WINDOW.min(entry_idx) {
+        let mut xored = bits.clone();
+        xored.symmetric_difference_with(&bitmaps[entry_idx - xor_offset]);
+        let xored = xored.to_ewah();
+        if xored.num_words() < best.1.num_words() {
+            best = (xor_offset as u8, xored);
+        }
+    }
+    best
+}
@@ -0,0 +1,174 @@
+use std::{io, io::Write, path::Path};
+
+use gix_features::{hash, zlib::stream::deflate};
+use gix_tempfile::{AutoRemove, ContainingDirectory};
+
+use super::{Error, Outcome};
+use crate::{
+    cache::delta::Item,
+    data,
+    index::write::{compat, TreeEntry},
+};
+
+impl crate::Bundle {
+    /// Write a single blob of `size` bytes read from `from` into a new pack with its index in `directory`,
+    /// returning the id of the blob along with information about the newly written bundle.
+    ///
+    /// Unlike with [`write_to_directory()`](Self::write_to_directory()), the blob is compressed and hashed while it is
+    /// streamed into the pack so memory usage is bounded, which makes this suitable for storing large blobs that shouldn't
+    /// be stored as loose objects, similar to what `git` does for blobs larger than `core.bigFileThreshold`.
+    ///
+    /// * `index_version` is the version of the index to write, with [`crate::index::Version::default()`] being the most compatible one.
+    /// * `object_hash` is the kind of hash to use for the pack, the index and the name of the blob.
+    ///
+    /// # Note
+    ///
+    /// * No `.keep` file is written as the pack isn't received from a remote.
+    /// * If a pack with the same name already exists, nothing is written.
+    pub fn write_blob_to_directory(
+        size: u64,
+        from: &mut dyn io::Read,
+        directory: &Path,
+        index_version: crate::index::Version,
+        object_hash: gix_hash::Kind,
+    ) -> Result<(gix_hash::ObjectId, Outcome), Error> {
+        let _span = gix_features::trace::coarse!("gix_pack::Bundle::write_blob_to_directory()", size);
+        let pack_version = data::Version::V2;
+        let mut pack = hash::Write::new(
+            io::BufWriter::with_capacity(
+                64 * 1024,
+                gix_tempfile::new(directory, ContainingDirectory::Exists, AutoRemove::Tempfile)?,
+            ),
+            object_hash,
+        );
+        let pack_header = data::header::encode(pack_version, 1);
+        pack.write_all(&pack_header)?;
+        let pack_offset = pack_header.len() as data::Offset;
+
+        let kind = gix_object::Kind::Blob;
+        let loose_header = gix_object::encode::loose_header(kind, size);
+        let mut object = hash::hasher(object_hash);
+        object.update(&loose_header);
+        let compat_hash = (index_version == crate::index::Version::V3).then(|| compat::other_kind(object_hash));
+        let mut compat_object = compat_hash.map(|compat_hash| {
+            let mut hasher = hash::hasher(compat_hash);
+            hasher.update(&loose_header);
+            hasher
+        });
+
+        let mut entry = Crc32Write {
+            inner: &mut pack,
+            crc32: 0,
+            bytes: 0,
+        };
+        data::entry::Header::Blob.write_to(size, &mut entry)?;
+        {
+            let mut compressed = deflate::Write::new(&mut entry);
+            let mut buf = vec![0; 64 * 1024];
+            let mut bytes_read = 0;
+            loop {
+                let num_read = match from.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err.into()),
+                };
+                let chunk = &buf[..num_read];
+                object.update(chunk);
+                if let Some(compat_object) = compat_object.as_mut() {
+                    compat_object.update(chunk);
+                }
+                compressed.write_all(chunk)?;
+                bytes_read += num_read as u64;
+            }
+            if bytes_read != size {
+                return Err(Error::BlobSizeMismatch {
+                    expected: size,
+                    actual: bytes_read,
+                });
+            }
+            compressed.flush()?;
+        }
+        let (crc32, entry_size) = (entry.crc32, entry.bytes);
+
+        let hash::Write {
+            hash,
+            inner: mut data_file,
+        } = pack;
+        let pack_hash = hash.try_finalize().map_err(crate::index::write::Error::from)?;
+        data_file.write_all(pack_hash.as_slice())?;
+        let data_file = data_file.into_inner().map_err(io::IntoInnerError::into_error)?;
+
+        let id = object.try_finalize().map_err(crate::index::write::Error::from)?;
+        let compat_id = compat_object
+            .map(hash::Hasher::try_finalize)
+            .transpose()
+            .map_err(crate::index::write::Error::from)?;
+        let mut index_file = gix_tempfile::new(directory, ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+        let index_hash = crate::index::encode::write_to(
+            &mut index_file,
+            vec![Item::without_children(
+                pack_offset,
+                pack_offset + entry_size,
+                TreeEntry {
+                    id,
+                    crc32,
+                    compat: None,
+                },
+            )],
+            compat_hash
+                .zip(compat_id.as_ref())
+                .map(|(kind, id)| (kind, std::slice::from_ref(id))),
+            &pack_hash,
+            index_version,
+            object_hash,
+            &mut gix_features::progress::Discard,
+        )?;
+
+        let data_path = directory.join(format!("pack-{}.pack", pack_hash.to_hex()));
+        let index_path = data_path.with_extension("idx");
+        if !data_path.is_file() {
+            data_file.persist(&data_path)?;
+        }
+        if !index_path.is_file() {
+            index_file.persist(&index_path)?;
+        }
+        super::write_reverse_index(directory, &index_path, object_hash)?;
+        Ok((
+            id,
+            Outcome {
+                index: crate::index::write::Outcome {
+                    index_version,
+                    index_hash,
+                    data_hash: pack_hash,
+                    num_objects: 1,
+                },
+                pack_version,
+                object_hash,
+                index_path: Some(index_path),
+                data_path: Some(data_path),
+                keep_path: None,
+            },
+        ))
+    }
+}
+
+/// Compute the CRC32 of all bytes written to `inner` and count them, as needed for the index entry of a pack entry.
+struct Crc32Write<W> {
+    inner: W,
+    crc32: u32,
+    bytes: u64,
+}
+
+impl<W: io::Write> io::Write for Crc32Write<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.crc32 = hash::crc32_update(self.crc32, &buf[..written]);
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
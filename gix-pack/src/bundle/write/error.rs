@@ -14,4 +14,8 @@ pub enum Error {
     Persist(#[from] gix_tempfile::handle::persist::Error<Writable>),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
+    #[error("Could not open the written index to write its reverse index")]
+    IndexOpen(#[from] crate::index::init::Error),
+    #[error("The blob was expected to have {expected} bytes, but {actual} bytes were read")]
+    BlobSizeMismatch { expected: u64, actual: u64 },
 }
@@ -11,6 +11,7 @@ use gix_tempfile::{AutoRemove, ContainingDirectory};
 
 use crate::data;
 
+mod blob;
 mod error;
 pub use error::Error;
 use gix_features::progress::prodash::DynNestedProgress;
@@ -59,6 +60,7 @@ impl crate::Bundle {
     /// * the resulting pack may be empty, that is, contains zero objects in some situations. This is a valid reply by a server and should
     ///   be accounted for.
     ///   - Empty packs always have the same name and not handling this case will result in at most one superfluous pack.
+    /// * like `git`, a [reverse index](crate::index::reverse::File) is written next to the pack index.
     pub fn write_to_directory(
         pack: &mut dyn io::BufRead,
         directory: Option<&Path>,
@@ -109,7 +111,7 @@ impl crate::Bundle {
                         writer: data_file.clone(),
                     },
                     pack_version,
-                    gix_hash::Kind::Sha1, // Thin packs imply a pack being transported, and there we only ever know SHA1 at the moment.
+                    object_hash,
                 );
                 (Box::new(pack_entries_iter), pack_version)
             }
@@ -334,6 +336,7 @@ impl crate::Bundle {
                                 err
                             })?;
                     }
+                    write_reverse_index(directory, &index_path, object_hash)?;
                     WriteOutcome {
                         outcome,
                         data_path: Some(data_path),
@@ -362,6 +365,24 @@ impl crate::Bundle {
     }
 }
 
+/// Write the reverse index of the pack index at `index_path` next to it, unless it exists already,
+/// to allow mapping pack offsets to entries without sorting all offsets of the index.
+pub(crate) fn write_reverse_index(
+    directory: &Path,
+    index_path: &Path,
+    object_hash: gix_hash::Kind,
+) -> Result<(), Error> {
+    let reverse_index_path = index_path.with_extension("rev");
+    if reverse_index_path.is_file() {
+        return Ok(());
+    }
+    let index = crate::index::File::at(index_path, object_hash)?;
+    let mut reverse_index_file = gix_tempfile::new(directory, ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+    crate::index::reverse::File::write_from_index(&index, &mut reverse_index_file)?;
+    reverse_index_file.persist(&reverse_index_path)?;
+    Ok(())
+}
+
 fn resolve_entry(range: data::EntryRange, mapped_file: &memmap2::Mmap) -> Option<&[u8]> {
     mapped_file.get(range.start as usize..range.end as usize)
 }
@@ -10,7 +10,9 @@ pub struct Options {
     pub thread_limit: Option<usize>,
     /// Determine how much processing to spend on protecting against corruption or recovering from errors.
     pub iteration_mode: crate::data::input::Mode,
-    /// The version of pack index to write, should be [`crate::index::Version::default()`]
+    /// The version of pack index to write, should be [`crate::index::Version::default()`] unless compatibility with
+    /// old tooling ([`V1`][crate::index::Version::V1]) or the names of all objects with a second kind of hash
+    /// ([`V3`][crate::index::Version::V3]) are required.
     pub index_version: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: gix_hash::Kind,
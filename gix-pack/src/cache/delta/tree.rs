@@ -22,6 +22,18 @@ pub struct Item<T> {
 }
 
 impl<T> Item<T> {
+    /// Create an item for the pack entry between `offset` and `next_offset` that no other entry depends on.
+    #[cfg(all(not(feature = "wasm"), feature = "streaming-input"))]
+    pub(crate) fn without_children(offset: crate::data::Offset, next_offset: crate::data::Offset, data: T) -> Self {
+        Item {
+            offset,
+            next_offset,
+            data,
+            // SAFETY INVARIANT upheld: there are no children
+            children: Default::default(),
+        }
+    }
+
     /// Get the children
     // (we don't want to expose mutable access)
     pub fn children(&self) -> &[u32] {
@@ -245,6 +257,6 @@ mod tests {
             _level: u16,
         }
 
-        assert_eq!(std::mem::size_of::<[Item<EntryWithDefault>; 7_500_000]>(), 840_000_000);
+        assert_eq!(std::mem::size_of::<[Item<EntryWithDefault>; 7_500_000]>(), 960_000_000);
     }
 }
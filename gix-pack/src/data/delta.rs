@@ -68,3 +68,247 @@ pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     assert_eq!(i, data.len());
     assert_eq!(target.len(), 0);
 }
+
+/// Create deltas that turn a base object into a target object, in the same format that [`apply()`] reads.
+#[cfg(feature = "generate")]
+pub(crate) mod encode {
+    /// The size of the blocks in the base object that are indexed, and thus the smallest match we can find.
+    const BLOCK_SIZE: usize = 16;
+    /// The maximum amount of base offsets to check for each hash, to bound the time spent on repetitive data.
+    const MAX_CHAIN_LEN: usize = 64;
+    /// The largest amount of bytes to copy with a single instruction, which is what git supports as well.
+    const MAX_COPY_SIZE: usize = 0x10000;
+    /// The largest amount of bytes to insert with a single instruction.
+    const MAX_INSERT_SIZE: usize = 0x7f;
+    /// A multiplier for the rolling hash.
+    const PRIME: u32 = 0x0100_0193;
+    /// The factor of the oldest byte in the rolling hash of a block.
+    const OUTGOING_FACTOR: u32 = PRIME.wrapping_pow(BLOCK_SIZE as u32 - 1);
+
+    /// An index over the blocks of a base object to quickly find the parts that a target object has in common with it.
+    pub(crate) struct Index {
+        base: Vec<u8>,
+        shift: u32,
+        /// The block number + 1 of the most recent block with a given hash, or 0 if there is none.
+        heads: Vec<u32>,
+        /// The block number + 1 of the previous block with the same hash, or 0 if there is none.
+        next: Vec<u32>,
+    }
+
+    impl Index {
+        /// Index `base`, which must be smaller than 4GB.
+        pub(crate) fn new(base: Vec<u8>) -> Self {
+            let num_blocks = base.len() / BLOCK_SIZE;
+            let bits = (num_blocks.max(1).next_power_of_two().trailing_zeros() + 1).min(31);
+            let mut heads = vec![0; 1 << bits];
+            let mut next = vec![0; num_blocks];
+            let shift = 32 - bits;
+            for (block, chunk) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+                let bucket = bucket(block_hash(chunk), shift);
+                next[block] = heads[bucket];
+                heads[bucket] = block as u32 + 1;
+            }
+            Index {
+                base,
+                shift,
+                heads,
+                next,
+            }
+        }
+
+        /// The object this index was created from.
+        pub(crate) fn base(&self) -> &[u8] {
+            &self.base
+        }
+
+        /// Return the offset into the base and the length of the longest match of at least a block in size
+        /// for `target[pos..]`, whose first block hashes to `hash`.
+        fn longest_match(&self, target: &[u8], pos: usize, hash: u32) -> Option<(usize, usize)> {
+            let mut best: Option<(usize, usize)> = None;
+            let mut block = self.heads[bucket(hash, self.shift)];
+            let mut checked = 0;
+            while block != 0 && checked < MAX_CHAIN_LEN {
+                let offset = (block as usize - 1) * BLOCK_SIZE;
+                let len = self.base[offset..]
+                    .iter()
+                    .zip(&target[pos..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if len >= BLOCK_SIZE && best.map_or(true, |(_, best_len)| len > best_len) {
+                    best = Some((offset, len));
+                }
+                block = self.next[block as usize - 1];
+                checked += 1;
+            }
+            best
+        }
+    }
+
+    /// Write a delta to `out` which turns the base object of `index` into `target`, and return `true` on success.
+    ///
+    /// If the delta would be larger than `max_size` bytes, `false` is returned and `out` has unspecified content.
+    pub(crate) fn delta(index: &Index, target: &[u8], max_size: usize, out: &mut Vec<u8>) -> bool {
+        out.clear();
+        encode_size(index.base.len(), out);
+        encode_size(target.len(), out);
+
+        let mut insert_start = 0;
+        let mut pos = 0;
+        let mut hash = target.get(..BLOCK_SIZE).map_or(0, block_hash);
+        while pos + BLOCK_SIZE <= target.len() {
+            match index.longest_match(target, pos, hash) {
+                Some((mut offset, mut len)) => {
+                    while pos > insert_start && offset > 0 && index.base[offset - 1] == target[pos - 1] {
+                        offset -= 1;
+                        pos -= 1;
+                        len += 1;
+                    }
+                    insert(&target[insert_start..pos], out);
+                    copy(offset, len, out);
+                    pos += len;
+                    insert_start = pos;
+                    if let Some(block) = target.get(pos..pos + BLOCK_SIZE) {
+                        hash = block_hash(block);
+                    }
+                }
+                None => {
+                    if let Some(&incoming) = target.get(pos + BLOCK_SIZE) {
+                        hash = roll(hash, target[pos], incoming);
+                    }
+                    pos += 1;
+                }
+            }
+            if out.len() + (pos - insert_start) > max_size {
+                return false;
+            }
+        }
+        insert(&target[insert_start..], out);
+        out.len() <= max_size
+    }
+
+    fn encode_size(mut size: usize, out: &mut Vec<u8>) {
+        loop {
+            let byte = (size & 0x7f) as u8;
+            size >>= 7;
+            if size == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn insert(mut data: &[u8], out: &mut Vec<u8>) {
+        while !data.is_empty() {
+            let len = data.len().min(MAX_INSERT_SIZE);
+            out.push(len as u8);
+            out.extend_from_slice(&data[..len]);
+            data = &data[len..];
+        }
+    }
+
+    fn copy(mut offset: usize, mut len: usize, out: &mut Vec<u8>) {
+        while len != 0 {
+            let size = len.min(MAX_COPY_SIZE);
+            let cmd_pos = out.len();
+            let mut cmd = 0b1000_0000;
+            out.push(0);
+            for (bit, byte) in (offset as u32).to_le_bytes().into_iter().enumerate() {
+                if byte != 0 {
+                    cmd |= 1 << bit;
+                    out.push(byte);
+                }
+            }
+            for (bit, byte) in (size as u32).to_le_bytes().into_iter().take(3).enumerate() {
+                if byte != 0 {
+                    cmd |= 1 << (bit + 4);
+                    out.push(byte);
+                }
+            }
+            out[cmd_pos] = cmd;
+            offset += size;
+            len -= size;
+        }
+    }
+
+    fn block_hash(block: &[u8]) -> u32 {
+        block.iter().fold(0u32, |hash, byte| {
+            hash.wrapping_mul(PRIME).wrapping_add(u32::from(*byte))
+        })
+    }
+
+    /// Remove `outgoing` from a hash of `BLOCK_SIZE` bytes and add `incoming` to it.
+    fn roll(hash: u32, outgoing: u8, incoming: u8) -> u32 {
+        hash.wrapping_sub(u32::from(outgoing).wrapping_mul(OUTGOING_FACTOR))
+            .wrapping_mul(PRIME)
+            .wrapping_add(u32::from(incoming))
+    }
+
+    fn bucket(hash: u32, shift: u32) -> usize {
+        (hash.wrapping_mul(0x9e37_79b1) >> shift) as usize
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{delta, Index};
+
+        fn roundtrip(base: &[u8], target: &[u8]) -> usize {
+            let mut out = Vec::new();
+            assert!(delta(&Index::new(base.to_vec()), target, usize::MAX, &mut out));
+            let (base_size, consumed) = super::super::decode_header_size(&out);
+            assert_eq!(base_size as usize, base.len());
+            let (target_size, consumed_target) = super::super::decode_header_size(&out[consumed..]);
+            assert_eq!(target_size as usize, target.len());
+            let mut actual = vec![0; target.len()];
+            super::super::apply(base, &mut actual, &out[consumed + consumed_target..]);
+            assert_eq!(actual, target, "applying the delta restores the target");
+            out.len()
+        }
+
+        fn pseudo_random(len: usize, mut seed: u32) -> Vec<u8> {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (seed >> 16) as u8
+                })
+                .collect()
+        }
+
+        #[test]
+        fn similar_objects_have_small_deltas() {
+            let base = pseudo_random(200_000, 1);
+            let mut target = base.clone();
+            target[1000..1010].copy_from_slice(b"0123456789");
+            target.splice(50_000..50_000, b"inserted".iter().copied());
+            target.drain(150_000..150_500);
+            let size = roundtrip(&base, &target);
+            assert!(
+                size < 100,
+                "only the changes and copy instructions are stored, got {size}"
+            );
+        }
+
+        #[test]
+        fn edge_cases_roundtrip() {
+            let data = pseudo_random(70_000, 2);
+            roundtrip(b"", b"");
+            roundtrip(b"", b"hello");
+            roundtrip(b"hello", b"");
+            roundtrip(&data, &data);
+            roundtrip(&data[..15], &data[..16]);
+            roundtrip(&data, &pseudo_random(300, 3));
+            roundtrip(&[0; 1000], &[0; 5000]);
+            let mut repeated = data[..100].repeat(10);
+            repeated.extend_from_slice(&data[5..17]);
+            roundtrip(&data[..100], &repeated);
+        }
+
+        #[test]
+        fn deltas_larger_than_max_size_are_rejected() {
+            let base = pseudo_random(1000, 4);
+            let target = pseudo_random(1000, 5);
+            assert!(!delta(&Index::new(base.clone()), &target, 500, &mut Vec::new()));
+            assert!(delta(&Index::new(base.clone()), &base, 500, &mut Vec::new()));
+        }
+    }
+}
@@ -114,6 +114,26 @@ impl File {
         self.data.get(entry_start..entry_end)
     }
 
+    /// Return the amount of bytes the entry at `pack_offset` occupies in the pack, including its header,
+    /// or `None` if there is no entry at `pack_offset`.
+    ///
+    /// `index` is the index of this pack, and `reverse_index` is its [reverse index](crate::index::reverse::File)
+    /// to learn where the entry following it starts.
+    pub fn entry_size_on_disk(
+        &self,
+        pack_offset: Offset,
+        index: &crate::index::File,
+        reverse_index: &crate::index::reverse::File,
+    ) -> Option<u64> {
+        let pack_pos = reverse_index.pack_position(index, pack_offset)?;
+        let next_offset = reverse_index
+            .index_position(pack_pos + 1)
+            .map_or(self.pack_end() as Offset, |index_pos| {
+                index.pack_offset_at_index(index_pos)
+            });
+        Some(next_offset - pack_offset)
+    }
+
     /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the mapped data.
     ///
     /// _Note:_ finding the right size is only possible by decompressing
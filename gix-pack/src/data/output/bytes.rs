@@ -15,6 +15,8 @@ where
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Input(E),
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
 }
 
 /// An implementation of [`Iterator`] to write [encoded entries][output::Entry] to an inner implementation each time
@@ -120,12 +122,12 @@ where
                 }
             }
             None => {
-                let digest = self.output.hash.clone().digest();
-                self.output.inner.write_all(&digest[..])?;
-                self.written += digest.len() as u64;
+                let digest = self.output.hash.clone().try_finalize()?;
+                self.output.inner.write_all(digest.as_slice())?;
+                self.written += digest.as_slice().len() as u64;
                 self.output.inner.flush()?;
                 self.is_done = true;
-                self.trailer = Some(gix_hash::ObjectId::from(digest));
+                self.trailer = Some(digest);
             }
         };
         Ok(self.written - previous_written)
@@ -35,8 +35,26 @@ impl Count {
         Count {
             id: oid.into(),
             entry_pack_location: PackLocation::LookedUp(location),
+            name_hash: 0,
         }
     }
+
+    /// Set the [`name_hash`](Count::name_hash) of this instance to the hash of `name`, the path at which it was found.
+    pub fn with_name(mut self, name: &[u8]) -> Self {
+        self.name_hash = name_hash(name);
+        self
+    }
+}
+
+/// Hash the path or file `name` of an object so that objects with the same name, or with names that share the same ending,
+/// have hashes that are equal or close to each other, which makes them good candidates for delta-compression.
+///
+/// This is the same as the one used by `git` for the same purpose, and as it only considers the last 16 non-whitespace
+/// characters, file names are enough to hash most paths.
+pub fn name_hash(name: &[u8]) -> u32 {
+    name.iter()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .fold(0u32, |hash, b| (hash >> 2).wrapping_add(u32::from(*b) << 24))
 }
 
 #[path = "objects/mod.rs"]
@@ -11,6 +11,7 @@ mod util;
 mod types;
 pub use types::{Error, ObjectExpansion, Options, Outcome};
 
+mod reachable;
 mod tree;
 
 /// Generate [`Count`][output::Count]s from input `objects` with object expansion based on [`options`][Options]
@@ -29,20 +30,36 @@ mod tree;
 ///  * A flag that is set to true if the operation should stop
 /// * `options`
 ///   * more configuration
+///
+/// Note that [`ObjectExpansion::AllReachable`] is performed on the current thread only.
 pub fn objects<Find>(
     db: Find,
-    objects_ids: Box<dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>> + Send>,
+    mut objects_ids: Box<
+        dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>> + Send,
+    >,
     objects: &dyn gix_features::progress::Count,
     should_interrupt: &AtomicBool,
     Options {
         thread_limit,
         input_object_expansion,
         chunk_size,
-    }: Options,
+        bitmaps,
+        haves,
+    }: Options<'_>,
 ) -> Result<(Vec<output::Count>, Outcome), Error>
 where
     Find: crate::Find + Send + Clone,
 {
+    if input_object_expansion == ObjectExpansion::AllReachable {
+        return reachable::objects(
+            &db,
+            &mut objects_ids,
+            bitmaps,
+            haves,
+            &objects.counter(),
+            should_interrupt,
+        );
+    }
     let lower_bound = objects_ids.size_hint().0;
     let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(
         chunk_size,
@@ -90,6 +107,8 @@ where
 }
 
 /// Like [`objects()`] but using a single thread only to mostly save on the otherwise required overhead.
+///
+/// [`ObjectExpansion::AllReachable`] is performed without bitmaps or objects to exclude.
 pub fn objects_unthreaded(
     db: &dyn crate::Find,
     object_ids: &mut dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>>,
@@ -97,6 +116,9 @@ pub fn objects_unthreaded(
     should_interrupt: &AtomicBool,
     input_object_expansion: ObjectExpansion,
 ) -> Result<(Vec<output::Count>, Outcome), Error> {
+    if input_object_expansion == ObjectExpansion::AllReachable {
+        return reachable::objects(db, object_ids, None, &[], &objects.counter(), should_interrupt);
+    }
     let seen_objs = RefCell::new(gix_hashtable::HashSet::default());
 
     let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
@@ -204,7 +226,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, objects, stats, true,
                                     );
-                                    gix_object::TreeRefIter::from_bytes(obj.data)
+                                    gix_object::TreeRefIter::from_bytes(obj.data, obj.object_hash)
                                 };
 
                                 let objects_ref = if parent_commit_ids.is_empty() {
@@ -242,7 +264,10 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            gix_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            gix_object::TreeRefIter::from_bytes(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.object_hash,
+                                            )
                                         };
 
                                         changes_delegate.clear();
@@ -259,8 +284,16 @@ mod expand {
                                     }
                                     &changes_delegate.objects
                                 };
-                                for id in objects_ref.iter() {
-                                    out.push(id_to_count(db, buf2, id, objects, stats, allow_pack_lookups));
+                                for (id, name_hash) in objects_ref.iter() {
+                                    out.push(id_to_count(
+                                        db,
+                                        buf2,
+                                        id,
+                                        *name_hash,
+                                        objects,
+                                        stats,
+                                        allow_pack_lookups,
+                                    ));
                                 }
                                 break;
                             }
@@ -279,7 +312,7 @@ mod expand {
                                 {
                                     let objects = ExpandedCountingObjects::new(db, out, objects);
                                     gix_traverse::tree::breadthfirst(
-                                        gix_object::TreeRefIter::from_bytes(obj.0.data),
+                                        gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
                                         &mut tree_traversal_state,
                                         &objects,
                                         &mut traverse_delegate,
@@ -287,8 +320,16 @@ mod expand {
                                     .map_err(Error::TreeTraverse)?;
                                     out = objects.dissolve(stats);
                                 }
-                                for id in &traverse_delegate.non_trees {
-                                    out.push(id_to_count(db, buf1, id, objects, stats, allow_pack_lookups));
+                                for (id, name_hash) in &traverse_delegate.non_trees {
+                                    out.push(id_to_count(
+                                        db,
+                                        buf1,
+                                        id,
+                                        *name_hash,
+                                        objects,
+                                        stats,
+                                        allow_pack_lookups,
+                                    ));
                                 }
                                 break;
                             }
@@ -313,6 +354,7 @@ mod expand {
                     }
                 }
                 AsIs => push_obj_count_unique(&mut out, seen_objs, &id, location, objects, stats, false),
+                AllReachable => unreachable!("handled by the caller as it needs all input objects at once"),
             }
         }
         outcome.total_objects = out.len();
@@ -345,6 +387,7 @@ mod expand {
         db: &dyn crate::Find,
         buf: &mut Vec<u8>,
         id: &oid,
+        name_hash: u32,
         objects: &gix_features::progress::AtomicStep,
         statistics: &mut Outcome,
         allow_pack_lookups: bool,
@@ -358,6 +401,7 @@ mod expand {
             } else {
                 PackLocation::NotLookedUp
             },
+            name_hash,
         }
     }
 
@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::{oid, ObjectId};
+use gix_object::{CommitRefIter, TagRefIter, TreeRefIter};
+
+use super::types::{Error, Outcome};
+use crate::{
+    bitmap,
+    data::{output, output::count::PackLocation},
+};
+
+/// Count all objects reachable from `wants` that aren't reachable from `haves`, using `bitmaps` if available.
+pub fn objects(
+    db: &dyn crate::Find,
+    wants: &mut dyn Iterator<Item = Result<ObjectId, Box<dyn std::error::Error + Send + Sync + 'static>>>,
+    bitmaps: Option<&bitmap::Bundle>,
+    haves: &[ObjectId],
+    objects: &gix_features::progress::AtomicStep,
+    should_interrupt: &AtomicBool,
+) -> Result<(Vec<output::Count>, Outcome), Error> {
+    let mut outcome = Outcome::default();
+    let mut buf = Vec::new();
+    let wants = wants
+        .map(|id| id.map_err(Error::InputIteration))
+        .collect::<Result<Vec<_>, _>>()?;
+    outcome.input_objects = wants.len();
+
+    let mut walk = Walk {
+        db,
+        bitmaps,
+        buf: &mut buf,
+        decoded_objects: 0,
+        should_interrupt,
+    };
+    let mut names = gix_hashtable::HashMap::default();
+    let haves = walk.reachable_from(haves, true, &mut names)?;
+    let mut wants = walk.reachable_from(&wants, false, &mut names)?;
+    outcome.decoded_objects = walk.decoded_objects;
+
+    wants.bits.difference_with(&haves.bits);
+    let mut uncovered: Vec<_> = wants
+        .uncovered
+        .into_iter()
+        .filter(|id| !haves.uncovered.contains(id))
+        .collect();
+    uncovered.sort();
+
+    let mut out = Vec::new();
+    let covered = bitmaps.into_iter().flat_map(|bitmaps| {
+        wants
+            .bits
+            .iter_ones()
+            .map(move |bit_pos| bitmaps.oid_at_bit_position(bit_pos))
+    });
+    for id in covered.chain(uncovered.iter().map(AsRef::as_ref)) {
+        objects.fetch_add(1, Ordering::Relaxed);
+        out.push(output::Count {
+            id: id.to_owned(),
+            entry_pack_location: PackLocation::LookedUp(db.location_by_oid(id, &mut buf)),
+            name_hash: names.get(id).copied().unwrap_or_default(),
+        });
+    }
+    outcome.total_objects = out.len();
+    outcome.expanded_objects = out.len().saturating_sub(outcome.input_objects);
+    Ok((out, outcome))
+}
+
+/// Objects that were found to be reachable.
+#[derive(Default)]
+struct Reachable {
+    /// Objects contained in the bitmap index.
+    bits: bitmap::Bits,
+    /// Objects that aren't contained in the bitmap index, or all objects if there are no bitmaps.
+    uncovered: gix_hashtable::HashSet,
+}
+
+impl Reachable {
+    fn insert(&mut self, id: &oid, bitmaps: Option<&bitmap::Bundle>) -> bool {
+        match bitmaps.and_then(|bitmaps| bitmaps.bit_position(id)) {
+            Some(bit_pos) => self.bits.insert(bit_pos),
+            None => self.uncovered.insert(id.to_owned()),
+        }
+    }
+
+    fn contains(&self, id: &oid, bitmaps: Option<&bitmap::Bundle>) -> bool {
+        match bitmaps.and_then(|bitmaps| bitmaps.bit_position(id)) {
+            Some(bit_pos) => self.bits.contains(bit_pos),
+            None => self.uncovered.contains(id),
+        }
+    }
+}
+
+struct Walk<'a> {
+    db: &'a dyn crate::Find,
+    bitmaps: Option<&'a bitmap::Bundle>,
+    buf: &'a mut Vec<u8>,
+    decoded_objects: usize,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl Walk<'_> {
+    /// Find all objects reachable from `tips`, and skip those that don't exist if `ignore_missing` is set.
+    ///
+    /// As bitmaps are closed under reachability, so are the bits we set, which is why we can skip everything
+    /// whose bit is already set.
+    /// The [name hashes](output::count::name_hash()) of traversed tree entries are added to `names`,
+    /// by the name they were first seen with.
+    fn reachable_from(
+        &mut self,
+        tips: &[ObjectId],
+        ignore_missing: bool,
+        names: &mut gix_hashtable::HashMap<ObjectId, u32>,
+    ) -> Result<Reachable, Error> {
+        let bitmaps = self.bitmaps;
+        let mut out = Reachable::default();
+        let mut commits = Vec::new();
+        let mut trees = Vec::new();
+        for tip in tips {
+            let mut id = *tip;
+            loop {
+                let Some(obj) = self.find(&id, ignore_missing)? else {
+                    break;
+                };
+                match obj.kind {
+                    gix_object::Kind::Commit => commits.push(id),
+                    gix_object::Kind::Tree => trees.push(id),
+                    gix_object::Kind::Blob => {
+                        out.insert(&id, bitmaps);
+                    }
+                    gix_object::Kind::Tag => {
+                        let target = TagRefIter::from_bytes(obj.data)
+                            .target_id()
+                            .expect("every tag has a target");
+                        out.insert(&id, bitmaps);
+                        id = target;
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+
+        loop {
+            while let Some(id) = trees.pop() {
+                if !out.insert(&id, bitmaps) {
+                    continue;
+                }
+                let Some(obj) = self.find(&id, ignore_missing)? else {
+                    continue;
+                };
+                for entry in TreeRefIter::from_bytes(obj.data, obj.object_hash) {
+                    let entry = entry.map_err(Error::TreeDecode)?;
+                    if entry.mode.is_commit() {
+                        continue;
+                    }
+                    names
+                        .entry(entry.oid.to_owned())
+                        .or_insert_with(|| output::count::name_hash(entry.filename));
+                    if entry.mode.is_tree() {
+                        trees.push(entry.oid.to_owned());
+                    } else {
+                        out.insert(entry.oid, bitmaps);
+                    }
+                }
+            }
+
+            let Some(id) = commits.pop() else {
+                break;
+            };
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if out.contains(&id, bitmaps) {
+                continue;
+            }
+            if let Some(bits) = bitmaps.and_then(|bitmaps| bitmaps.reachable_from(&id)) {
+                out.bits.union_with(&bits);
+                continue;
+            }
+            let Some(obj) = self.find(&id, ignore_missing)? else {
+                continue;
+            };
+            out.insert(&id, bitmaps);
+            let mut iter = CommitRefIter::from_bytes(obj.data);
+            trees.push(iter.tree_id().map_err(Error::CommitDecode)?);
+            for token in iter {
+                match token {
+                    Ok(gix_object::commit::ref_iter::Token::Parent { id }) => commits.push(id),
+                    Ok(_) => break,
+                    Err(err) => return Err(Error::CommitDecode(err)),
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn find(&mut self, id: &oid, ignore_missing: bool) -> Result<Option<gix_object::Data<'_>>, Error> {
+        let obj = match self.db.try_find(id, self.buf) {
+            Ok(Some((obj, _location))) => obj,
+            Ok(None) if ignore_missing => return Ok(None),
+            Ok(None) => {
+                return Err(Error::FindExisting(gix_object::find::existing::Error::NotFound {
+                    oid: id.to_owned(),
+                }))
+            }
+            Err(err) => return Err(Error::FindExisting(gix_object::find::existing::Error::Find(err))),
+        };
+        self.decoded_objects += 1;
+        Ok(Some(obj))
+    }
+}
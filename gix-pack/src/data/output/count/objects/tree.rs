@@ -9,8 +9,11 @@ pub mod changes {
     use crate::data::output::count::objects_impl::util::InsertImmutable;
 
     pub struct AllNew<'a, H> {
-        pub objects: Vec<ObjectId>,
+        /// New objects along with the name hash of the path they were found at.
+        pub objects: Vec<(ObjectId, u32)>,
         all_seen: &'a H,
+        /// The name hash of the most recently pushed path component, which is the name of the entry being visited.
+        name_hash: u32,
     }
 
     impl<'a, H> AllNew<'a, H>
@@ -21,6 +24,7 @@ pub mod changes {
             AllNew {
                 objects: Default::default(),
                 all_seen,
+                name_hash: 0,
             }
         }
         pub fn clear(&mut self) {
@@ -36,7 +40,9 @@ pub mod changes {
 
         fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            self.name_hash = crate::data::output::count::name_hash(component);
+        }
 
         fn pop_path_component(&mut self) {}
 
@@ -53,7 +59,7 @@ pub mod changes {
                     }
                     let inserted = self.all_seen.insert(oid);
                     if inserted {
-                        self.objects.push(oid);
+                        self.objects.push((oid, self.name_hash));
                     }
                 }
                 Change::Deletion { .. } => {}
@@ -71,7 +77,8 @@ pub mod traverse {
     use crate::data::output::count::objects_impl::util::InsertImmutable;
 
     pub struct AllUnseen<'a, H> {
-        pub non_trees: Vec<ObjectId>,
+        /// Objects that aren't trees along with the name hash of their file name.
+        pub non_trees: Vec<(ObjectId, u32)>,
         all_seen: &'a H,
     }
 
@@ -117,7 +124,10 @@ pub mod traverse {
             }
             let inserted = self.all_seen.insert(entry.oid.to_owned());
             if inserted {
-                self.non_trees.push(entry.oid.to_owned());
+                self.non_trees.push((
+                    entry.oid.to_owned(),
+                    crate::data::output::count::name_hash(entry.filename),
+                ));
             }
             Action::Continue
         }
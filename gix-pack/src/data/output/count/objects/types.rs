@@ -51,12 +51,22 @@ pub enum ObjectExpansion {
     ///
     /// This mode is useful to build a pack containing only new objects compared to a previous state.
     TreeAdditionsComparedToAncestor,
+    /// Turn the input objects and all objects reachable from them into pack entries, which includes all ancestors of commits
+    /// along with their trees, except for those that are reachable from [`Options::haves`].
+    /// Annotated tags are peeled and sent along with their targets.
+    ///
+    /// If [`Options::bitmaps`] are set, objects reachable from commits with a bitmap are learned without traversal.
+    ///
+    /// This mode is useful to build a pack for a clone or fetch.
+    AllReachable,
 }
 
 /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
-#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+///
+/// Note that [`bitmaps`](Options::bitmaps) are ignored when comparing or hashing options.
+#[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Options {
+pub struct Options<'a> {
     /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
     /// If more than one thread is used, the order of returned [counts][crate::data::output::Count] is not deterministic anymore
     /// especially when tree traversal is involved. Thus deterministic ordering requires `Some(1)` to be set.
@@ -65,18 +75,67 @@ pub struct Options {
     pub chunk_size: usize,
     /// The way input objects are handled
     pub input_object_expansion: ObjectExpansion,
+    /// Reachability bitmaps to avoid traversing commits and trees when using [`ObjectExpansion::AllReachable`].
+    ///
+    /// Objects that aren't covered by bitmaps are still found by traversal.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub bitmaps: Option<&'a crate::bitmap::Bundle>,
+    /// Objects the receiving side already has, which won't be counted along with all objects reachable from them
+    /// when using [`ObjectExpansion::AllReachable`]. Those that don't exist are ignored.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub haves: &'a [gix_hash::ObjectId],
 }
 
-impl Default for Options {
+impl Default for Options<'_> {
     fn default() -> Self {
         Options {
             thread_limit: None,
             chunk_size: 10,
             input_object_expansion: Default::default(),
+            bitmaps: None,
+            haves: &[],
         }
     }
 }
 
+impl Options<'_> {
+    /// All fields that are relevant for comparisons, which excludes the `bitmaps`.
+    fn comparable(&self) -> (Option<usize>, usize, ObjectExpansion, &[gix_hash::ObjectId]) {
+        (
+            self.thread_limit,
+            self.chunk_size,
+            self.input_object_expansion,
+            self.haves,
+        )
+    }
+}
+
+impl PartialEq for Options<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparable() == other.comparable()
+    }
+}
+
+impl Eq for Options<'_> {}
+
+impl PartialOrd for Options<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Options<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.comparable().cmp(&other.comparable())
+    }
+}
+
+impl std::hash::Hash for Options<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.comparable().hash(state);
+    }
+}
+
 /// The error returned by the pack generation iterator [`bytes::FromEntriesIter`][crate::data::output::bytes::FromEntriesIter].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -84,6 +143,8 @@ pub enum Error {
     #[error(transparent)]
     CommitDecode(gix_object::decode::Error),
     #[error(transparent)]
+    TreeDecode(gix_object::decode::Error),
+    #[error(transparent)]
     FindExisting(#[from] gix_object::find::existing::Error),
     #[error(transparent)]
     InputIteration(Box<dyn std::error::Error + Send + Sync + 'static>),
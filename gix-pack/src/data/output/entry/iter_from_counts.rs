@@ -10,7 +10,7 @@ pub(crate) mod function {
         },
     };
 
-    use super::{reduce, util, Error, Mode, Options, Outcome, ProgressId};
+    use super::{delta_search, reduce, util, Error, Mode, Options, Outcome, ProgressId};
     use crate::data::output;
 
     /// Given a known list of object `counts`, calculate entries ready to be put into a data pack.
@@ -52,6 +52,10 @@ pub(crate) mod function {
             allow_thin_pack,
             thread_limit,
             chunk_size,
+            window,
+            depth,
+            islands,
+            big_file_threshold,
         }: Options,
     ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error>>
            + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error>>
@@ -97,6 +101,7 @@ pub(crate) mod function {
             .expect("infallible - we ignore none-existing objects");
             progress.lock().show_throughput(start);
         }
+        let mut deltas = None;
         let counts_range_by_pack_id = match mode {
             Mode::PackCopyAndBaseObjects => {
                 let mut progress = progress.add_child_with_id("sorting".into(), ProgressId::SortEntries.into());
@@ -133,6 +138,22 @@ pub(crate) mod function {
 
                 index
             }
+            Mode::DeltaCompression => {
+                let (sorted_counts, plan) = delta_search::plan(
+                    counts,
+                    &db,
+                    window,
+                    depth,
+                    islands.as_deref(),
+                    big_file_threshold,
+                    thread_limit,
+                    version,
+                    &mut *progress,
+                );
+                counts = sorted_counts;
+                deltas = Some(Arc::new(plan));
+                Vec::new()
+            }
         };
 
         let counts = Arc::new(counts);
@@ -157,11 +178,27 @@ pub(crate) mod function {
                 let counts = Arc::clone(&counts);
                 move |(chunk_id, chunk_range): (SequenceId, std::ops::Range<usize>), (buf, progress)| {
                     let mut out = Vec::new();
-                    let chunk = &counts[chunk_range];
+                    let chunk = &counts[chunk_range.clone()];
                     let mut stats = Outcome::default();
                     let mut pack_offsets_to_id = None;
                     progress.init(Some(chunk.len()), gix_features::progress::count("objects"));
 
+                    if let Some(deltas) = &deltas {
+                        for index in chunk_range {
+                            out.push(delta_search::entry(
+                                &counts,
+                                index,
+                                deltas[index].as_ref(),
+                                &db,
+                                buf,
+                                version,
+                                &mut stats,
+                            )?);
+                            progress.inc();
+                        }
+                        return Ok((chunk_id, out, stats));
+                    }
+
                     for count in chunk.iter() {
                         out.push(match count
                             .entry_pack_location
@@ -244,6 +281,8 @@ pub(crate) mod function {
     }
 }
 
+mod delta_search;
+
 mod util {
     #[derive(Clone)]
     pub struct ChunkRanges {
@@ -335,6 +374,9 @@ mod types {
         pub objects_copied_from_pack: usize,
         /// The amount of objects that ref to their base as ref-delta, an indication for a thin back being created.
         pub ref_delta_objects: usize,
+        /// The amount of objects that were newly delta-compressed against another object of the pack,
+        /// which only happens with [`Mode::DeltaCompression`].
+        pub delta_compressed_objects: usize,
     }
 
     impl Outcome {
@@ -345,12 +387,14 @@ mod types {
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             }: Self,
         ) {
             self.decoded_and_recompressed_objects += decoded_objects;
             self.missing_objects += missing_objects;
             self.objects_copied_from_pack += objects_copied_from_pack;
             self.ref_delta_objects += ref_delta_objects;
+            self.delta_compressed_objects += delta_compressed_objects;
         }
     }
 
@@ -363,10 +407,41 @@ mod types {
         /// from existing pack compression and spending the smallest possible time on compressing unpacked objects at
         /// the cost of bandwidth.
         PackCopyAndBaseObjects,
+        /// Search for similar objects within a sliding window of [`Options::window`] objects and delta-compress objects
+        /// against them, for packs that are about as small as the ones produced by `git pack-objects`.
+        ///
+        /// Objects are sorted by kind, a hash of the name they were encountered with and size beforehand, so that
+        /// similar objects end up close to each other. Deltas in existing packs are copied if their base is part of
+        /// the pack as well, as long as their chains don't get longer than [`Options::depth`].
+        /// Objects larger than 512MB aren't delta-compressed, similar to `core.bigFileThreshold` in `git`.
+        ///
+        /// Note that [`Options::allow_thin_pack`] has no effect in this mode, and the resulting pack is always self-contained.
+        DeltaCompression,
+    }
+
+    /// Delta islands, which restrict the objects that may serve as delta base for other objects with [`Mode::DeltaCompression`].
+    ///
+    /// Objects are marked with the islands they belong to, typically all objects reachable from a set of references, and are only
+    /// delta-compressed against objects that belong to at least the same islands. That way, packs created only from objects of an island
+    /// can reuse all of their deltas, which is the equivalent of `git pack-objects --delta-islands`.
+    #[derive(Default, PartialEq, Eq, Debug, Clone)]
+    pub struct Islands {
+        /// The islands each object belongs to as a bitmap with one bit per island, allowing for up to 64 islands.
+        /// Objects that aren't listed belong to no island.
+        pub marks: gix_hashtable::HashMap<gix_hash::ObjectId, u64>,
+    }
+
+    impl Islands {
+        /// Return `true` if the object `target` may be delta-compressed against the object `base`.
+        pub fn allows_delta(&self, target: &gix_hash::oid, base: &gix_hash::oid) -> bool {
+            let target = self.marks.get(target).copied().unwrap_or_default();
+            let base = self.marks.get(base).copied().unwrap_or_default();
+            target & !base == 0
+        }
     }
 
     /// Configuration options for the pack generation functions provided in [`iter_from_counts()`][crate::data::output::entry::iter_from_counts()].
-    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    #[derive(PartialEq, Eq, Debug, Clone)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Options {
         /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
@@ -385,6 +460,21 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// The amount of objects to consider as delta base for each object with [`Mode::DeltaCompression`], similar to `pack.window`.
+        ///
+        /// Larger windows lead to smaller packs at the cost of time. If 0, no deltas are created.
+        pub window: usize,
+        /// The maximum length of delta chains with [`Mode::DeltaCompression`], similar to `pack.depth`.
+        ///
+        /// Longer chains allow for smaller packs but make objects more expensive to decode. If 0, no deltas are created
+        /// and packed deltas aren't reused.
+        pub depth: usize,
+        /// If set, restrict the bases of deltas to objects of the same islands with [`Mode::DeltaCompression`].
+        #[cfg_attr(feature = "serde", serde(skip))]
+        pub islands: Option<std::sync::Arc<Islands>>,
+        /// Objects larger than this amount of bytes are never delta-compressed with [`Mode::DeltaCompression`],
+        /// similar to `core.bigFileThreshold`.
+        pub big_file_threshold: u64,
     }
 
     impl Default for Options {
@@ -395,6 +485,10 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                window: 10,
+                depth: 50,
+                islands: None,
+                big_file_threshold: 512 * 1024 * 1024,
             }
         }
     }
@@ -418,6 +512,8 @@ mod types {
         ResolveCounts,
         /// Layout pack entries for placement into a pack (by pack-id and by offset).
         SortEntries,
+        /// The amount of objects for which a delta base was searched.
+        FindDeltas,
     }
 
     impl From<ProgressId> for gix_features::progress::Id {
@@ -425,8 +521,9 @@ mod types {
             match v {
                 ProgressId::ResolveCounts => *b"ECRC",
                 ProgressId::SortEntries => *b"ECSE",
+                ProgressId::FindDeltas => *b"ECFD",
             }
         }
     }
 }
-pub use types::{Error, Mode, Options, Outcome, ProgressId};
+pub use types::{Error, Islands, Mode, Options, Outcome, ProgressId};
@@ -0,0 +1,485 @@
+use std::{collections::VecDeque, sync::atomic::Ordering};
+
+use gix_features::{
+    parallel,
+    progress::{prodash::Count, DynNestedProgress, Progress},
+};
+use gix_hash::ObjectId;
+
+use super::{Error, Islands, Outcome, ProgressId};
+use crate::data::{self, delta::encode, output};
+
+/// The smallest amount of objects to search for deltas in one go on a thread, as objects at the boundaries can't find
+/// delta bases in the preceding segment. It's independent of the amount of threads to produce the same pack with any of them.
+const MIN_SEGMENT_LEN: usize = 4096;
+/// The amount of objects for which to obtain header information on a thread in one go.
+const HEADER_CHUNK_LEN: usize = 1000;
+
+/// The way an object is written as delta.
+pub(super) enum Delta {
+    /// The delta in the pack the object is stored in is copied as its base is part of the pack as well, at the given index.
+    Reuse { base_index: usize },
+    /// A newly created delta against the object at the given index.
+    New { base_index: usize, data: Vec<u8> },
+}
+
+/// Information about a counted object, as needed to find deltas for it.
+#[derive(Default, Clone, Copy)]
+struct Info {
+    /// The kind of the object, if it's known.
+    kind: Option<gix_object::Kind>,
+    /// The size of the decoded object in bytes.
+    size: u64,
+    /// The pack offset of the base object if the object is stored as offset-delta in a pack.
+    packed_base_offset: Option<data::Offset>,
+    /// The index of the base object if the packed delta of this object will be copied.
+    reused_base: Option<usize>,
+}
+
+/// An object to find a delta base for.
+struct Candidate {
+    /// The index into the counts.
+    index: usize,
+    kind: gix_object::Kind,
+    size: u64,
+    name_hash: u32,
+    /// The maximum depth of the delta chain this object may be placed in, which is lower than the configured one if
+    /// copied deltas are based on it.
+    max_depth: usize,
+    /// The delta found for this object, along with the index of its base.
+    delta: Option<(usize, Vec<u8>)>,
+}
+
+/// An object in the window of possible delta bases.
+struct Base {
+    /// The index into the counts.
+    index: usize,
+    id: ObjectId,
+    kind: gix_object::Kind,
+    /// The length of the delta chain this object is placed in.
+    depth: usize,
+    data: encode::Index,
+}
+
+/// Decide which objects of `counts` to write as deltas and return them in an order that places bases before their deltas,
+/// along with the delta to write for each object.
+///
+/// Objects that can't be read are ignored here, so that the error or their absence can be noticed when writing them.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn plan<Find>(
+    counts: Vec<output::Count>,
+    db: &Find,
+    window: usize,
+    max_depth: usize,
+    islands: Option<&Islands>,
+    big_file_threshold: u64,
+    thread_limit: Option<usize>,
+    version: data::Version,
+    progress: &mut dyn DynNestedProgress,
+) -> (Vec<output::Count>, Vec<Option<Delta>>)
+where
+    Find: crate::Find + Send + Clone,
+{
+    let mut infos = vec![Info::default(); counts.len()];
+    for_each_info(&mut infos, &counts, db, thread_limit, |info, count, db, _buf| {
+        *info = packed_info(count, db, version).unwrap_or_default();
+    });
+
+    let count_by_pack_location: std::collections::HashMap<_, _> = counts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, count)| {
+            count
+                .entry_pack_location
+                .as_ref()
+                .map(|location| ((location.pack_id, location.pack_offset), index))
+        })
+        .collect();
+    for (info, count) in infos.iter_mut().zip(&counts) {
+        if let (Some(base_offset), Some(location)) = (info.packed_base_offset, count.entry_pack_location.as_ref()) {
+            info.reused_base = count_by_pack_location.get(&(location.pack_id, base_offset)).copied();
+        }
+    }
+    drop(count_by_pack_location);
+    limit_reused_delta_chains(&mut infos, max_depth);
+
+    for_each_info(&mut infos, &counts, db, thread_limit, |info, count, db, buf| {
+        if info.kind.is_none() && info.reused_base.is_none() {
+            if let Ok(Some((obj, _location))) = db.try_find(&count.id, buf) {
+                info.kind = Some(obj.kind);
+                info.size = obj.data.len() as u64;
+            }
+        }
+    });
+
+    let mut children_depth = vec![0; infos.len()];
+    for index in 0..infos.len() {
+        let mut depth = 0;
+        let mut cursor = index;
+        while let Some(base) = infos[cursor].reused_base {
+            depth += 1;
+            cursor = base;
+        }
+        children_depth[cursor] = children_depth[cursor].max(depth);
+    }
+
+    let mut candidates: Vec<_> = infos
+        .iter()
+        .enumerate()
+        .filter_map(|(index, info)| {
+            let kind = info.kind?;
+            (info.reused_base.is_none() && info.size <= big_file_threshold).then(|| Candidate {
+                index,
+                kind,
+                size: info.size,
+                name_hash: counts[index].name_hash,
+                max_depth: max_depth.saturating_sub(children_depth[index]),
+                delta: None,
+            })
+        })
+        .collect();
+    drop(children_depth);
+
+    if window != 0 && max_depth != 0 {
+        find_deltas(&mut candidates, &counts, db, window, islands, thread_limit, progress);
+    }
+
+    let mut deltas: Vec<_> = infos
+        .iter()
+        .map(|info| info.reused_base.map(|base_index| Delta::Reuse { base_index }))
+        .collect();
+    for candidate in candidates {
+        if let Some((base_index, data)) = candidate.delta {
+            deltas[candidate.index] = Some(Delta::New { base_index, data });
+        }
+    }
+    bases_first(counts, deltas)
+}
+
+/// Call `f` on each info and its count, on multiple threads if there are enough of them.
+fn for_each_info<Find>(
+    infos: &mut [Info],
+    counts: &[output::Count],
+    db: &Find,
+    thread_limit: Option<usize>,
+    f: impl Fn(&mut Info, &output::Count, &Find, &mut Vec<u8>) + Send + Clone,
+) where
+    Find: crate::Find + Send + Clone,
+{
+    parallel::in_parallel_if(
+        || counts.len() > 4_000,
+        infos.chunks_mut(HEADER_CHUNK_LEN).zip(counts.chunks(HEADER_CHUNK_LEN)),
+        thread_limit,
+        |_n| Vec::<u8>::new(),
+        {
+            let db = db.clone();
+            move |(infos, counts): (&mut [Info], &[output::Count]), buf| {
+                for (info, count) in infos.iter_mut().zip(counts) {
+                    f(info, count, &db, buf);
+                }
+                Ok::<_, ()>(())
+            }
+        },
+        parallel::reduce::IdentityWithResult::<(), ()>::default(),
+    )
+    .expect("infallible - objects that can't be read are ignored");
+}
+
+/// Obtain information about `count` from its pack entry, if it is in a pack of `version`.
+fn packed_info(count: &output::Count, db: &impl crate::Find, version: data::Version) -> Option<Info> {
+    let location = count.entry_pack_location.as_ref()?;
+    let entry = db.entry_by_location(location)?;
+    if entry.version != version {
+        return None;
+    }
+    let pack_offset_must_be_zero = 0;
+    let header = data::Entry::from_bytes(&entry.data, pack_offset_must_be_zero, count.id.as_slice().len()).ok()?;
+    match header.header {
+        data::entry::Header::OfsDelta { base_distance } => Some(Info {
+            packed_base_offset: data::entry::Header::verified_base_pack_offset(location.pack_offset, base_distance),
+            ..Default::default()
+        }),
+        data::entry::Header::RefDelta { .. } => None,
+        base => Some(Info {
+            kind: base.as_kind(),
+            size: header.decompressed_size,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Turn copied deltas into base objects where their chains would get longer than `max_depth`.
+fn limit_reused_delta_chains(infos: &mut [Info], max_depth: usize) {
+    let mut depths = vec![None; infos.len()];
+    let mut chain = Vec::new();
+    for index in 0..infos.len() {
+        let mut cursor = index;
+        let mut depth = loop {
+            if let Some(depth) = depths[cursor] {
+                break depth;
+            }
+            match infos[cursor].reused_base {
+                Some(base) => {
+                    chain.push(cursor);
+                    cursor = base;
+                }
+                None => {
+                    depths[cursor] = Some(0);
+                    break 0;
+                }
+            }
+        };
+        while let Some(index) = chain.pop() {
+            depth += 1;
+            if depth > max_depth {
+                infos[index].reused_base = None;
+                depth = 0;
+            }
+            depths[index] = Some(depth);
+        }
+    }
+}
+
+/// Sort `candidates` so that similar objects are close to each other and find the best delta base for each within
+/// the `window` of objects preceding it.
+fn find_deltas<Find>(
+    candidates: &mut [Candidate],
+    counts: &[output::Count],
+    db: &Find,
+    window: usize,
+    islands: Option<&Islands>,
+    thread_limit: Option<usize>,
+    progress: &mut dyn DynNestedProgress,
+) where
+    Find: crate::Find + Send + Clone,
+{
+    // Like `git`, place larger objects first so they become the bases of smaller ones, as deltas that remove data are smaller.
+    candidates.sort_by(|a, b| {
+        type_id(b.kind)
+            .cmp(&type_id(a.kind))
+            .then(b.name_hash.cmp(&a.name_hash))
+            .then(b.size.cmp(&a.size))
+            .then(a.index.cmp(&b.index))
+    });
+
+    let mut progress = progress.add_child_with_id("finding deltas".into(), ProgressId::FindDeltas.into());
+    progress.init(Some(candidates.len()), gix_features::progress::count("objects"));
+    let start = std::time::Instant::now();
+    let objects_searched = progress.counter();
+
+    let segment_len = MIN_SEGMENT_LEN.max(window * 64);
+    let mut segments = Vec::new();
+    let mut rest = candidates;
+    while !rest.is_empty() {
+        let mut end = segment_len.min(rest.len());
+        // Keep objects with the same name in one segment as they are most likely to be similar.
+        while end < rest.len() && rest[end].name_hash != 0 && rest[end].name_hash == rest[end - 1].name_hash {
+            end += 1;
+        }
+        let (segment, tail) = rest.split_at_mut(end);
+        segments.push(segment);
+        rest = tail;
+    }
+
+    let has_multiple_segments = segments.len() > 1;
+    parallel::in_parallel_if(
+        || has_multiple_segments,
+        segments.into_iter(),
+        thread_limit,
+        |_n| Vec::<u8>::new(),
+        {
+            let db = db.clone();
+            let objects_searched = objects_searched.clone();
+            move |segment: &mut [Candidate], buf| {
+                let mut bases = VecDeque::<Base>::with_capacity(window);
+                for candidate in segment {
+                    objects_searched.fetch_add(1, Ordering::Relaxed);
+                    let count = &counts[candidate.index];
+                    let target = match db.try_find(&count.id, buf) {
+                        Ok(Some((obj, _location))) => obj.data.to_vec(),
+                        _ => continue,
+                    };
+
+                    let hash_len = count.id.as_slice().len();
+                    let mut best: Option<(usize, usize, Vec<u8>)> = None;
+                    let mut out = Vec::new();
+                    for base in bases.iter().rev() {
+                        if base.kind != candidate.kind
+                            || base.depth >= candidate.max_depth
+                            || islands.map_or(false, |islands| !islands.allows_delta(&count.id, &base.id))
+                        {
+                            continue;
+                        }
+                        // These heuristics are the ones of `git`, favoring shallow deltas and skipping bases that differ too
+                        // much in size to produce a small enough delta.
+                        let (max_size, ref_depth) = match &best {
+                            Some((_, depth, delta)) => (delta.len(), *depth),
+                            None => ((target.len() / 2).saturating_sub(hash_len), 1),
+                        };
+                        let max_size = (max_size as u64 * (candidate.max_depth - base.depth) as u64
+                            / (candidate.max_depth + 1 - ref_depth) as u64)
+                            as usize;
+                        let base_size = base.data.base().len();
+                        if max_size == 0
+                            || target.len().saturating_sub(base_size) >= max_size
+                            || target.len() < base_size / 32
+                        {
+                            continue;
+                        }
+                        if !encode::delta(&base.data, &target, max_size, &mut out) {
+                            continue;
+                        }
+                        let depth = base.depth + 1;
+                        let is_better = best.as_ref().map_or(true, |(_, best_depth, best_delta)| {
+                            out.len() < best_delta.len() || (out.len() == best_delta.len() && depth < *best_depth)
+                        });
+                        if is_better {
+                            best = Some((base.index, depth, std::mem::take(&mut out)));
+                        }
+                    }
+
+                    let depth = best.as_ref().map_or(0, |(_, depth, _)| *depth);
+                    candidate.delta = best.map(|(base_index, _, delta)| (base_index, delta));
+                    if bases.len() == window {
+                        bases.pop_front();
+                    }
+                    bases.push_back(Base {
+                        index: candidate.index,
+                        id: count.id,
+                        kind: candidate.kind,
+                        depth,
+                        data: encode::Index::new(target),
+                    });
+                }
+                Ok::<_, ()>(())
+            }
+        },
+        parallel::reduce::IdentityWithResult::<(), ()>::default(),
+    )
+    .expect("infallible - objects that can't be read are ignored");
+
+    progress.show_throughput(start);
+}
+
+/// The type id of objects in packs, which is the order in which `git` sorts them for delta compression.
+fn type_id(kind: gix_object::Kind) -> u8 {
+    match kind {
+        gix_object::Kind::Commit => 1,
+        gix_object::Kind::Tree => 2,
+        gix_object::Kind::Blob => 3,
+        gix_object::Kind::Tag => 4,
+    }
+}
+
+/// Reorder `counts` and their `deltas` so that each base comes before the deltas using it, keeping the order of `counts` otherwise.
+fn bases_first(counts: Vec<output::Count>, mut deltas: Vec<Option<Delta>>) -> (Vec<output::Count>, Vec<Option<Delta>>) {
+    let base_of = |delta: &Option<Delta>| match delta {
+        Some(Delta::Reuse { base_index } | Delta::New { base_index, .. }) => Some(*base_index),
+        None => None,
+    };
+    let mut order = Vec::with_capacity(counts.len());
+    let mut is_placed = vec![false; counts.len()];
+    let mut chain = Vec::new();
+    for index in 0..counts.len() {
+        let mut cursor = index;
+        while !is_placed[cursor] {
+            is_placed[cursor] = true;
+            chain.push(cursor);
+            match base_of(&deltas[cursor]) {
+                Some(base) => cursor = base,
+                None => break,
+            }
+        }
+        order.extend(chain.drain(..).rev());
+    }
+
+    let mut new_index = vec![0; counts.len()];
+    for (new, old) in order.iter().enumerate() {
+        new_index[*old] = new;
+    }
+    let mut counts: Vec<_> = counts.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .map(|old| {
+            let count = counts[old].take().expect("each count is placed once");
+            let delta = deltas[old].take().map(|delta| match delta {
+                Delta::Reuse { base_index } => Delta::Reuse {
+                    base_index: new_index[base_index],
+                },
+                Delta::New { base_index, data } => Delta::New {
+                    base_index: new_index[base_index],
+                    data,
+                },
+            });
+            (count, delta)
+        })
+        .unzip()
+}
+
+/// Produce the entry for the count at `index` of `counts`, which is written as `delta` if set.
+pub(super) fn entry<Find>(
+    counts: &[output::Count],
+    index: usize,
+    delta: Option<&Delta>,
+    db: &Find,
+    buf: &mut Vec<u8>,
+    version: data::Version,
+    stats: &mut Outcome,
+) -> Result<output::Entry, Error>
+where
+    Find: crate::Find,
+{
+    let count = &counts[index];
+    match delta {
+        Some(Delta::New { base_index, data }) => {
+            stats.delta_compressed_objects += 1;
+            return Ok(output::Entry::from_delta(count, *base_index, data)?);
+        }
+        Some(Delta::Reuse { base_index }) => {
+            if let Some(entry) = copy_from_pack(
+                count,
+                std::slice::from_ref(&counts[*base_index]),
+                *base_index,
+                db,
+                version,
+            ) {
+                stats.objects_copied_from_pack += 1;
+                return Ok(entry?);
+            }
+        }
+        None => {
+            if let Some(entry) = copy_from_pack(count, &[], 0, db, version) {
+                stats.objects_copied_from_pack += 1;
+                return Ok(entry?);
+            }
+        }
+    }
+    match db.try_find(&count.id, buf).map_err(Error::Find)? {
+        Some((obj, _location)) => {
+            stats.decoded_and_recompressed_objects += 1;
+            Ok(output::Entry::from_data(count, &obj)?)
+        }
+        None => {
+            stats.missing_objects += 1;
+            Ok(output::Entry::invalid())
+        }
+    }
+}
+
+fn copy_from_pack(
+    count: &output::Count,
+    potential_bases: &[output::Count],
+    bases_index_offset: usize,
+    db: &impl crate::Find,
+    version: data::Version,
+) -> Option<Result<output::Entry, output::entry::Error>> {
+    let pack_entry = db.entry_by_location(count.entry_pack_location.as_ref()?)?;
+    output::Entry::from_pack_entry(
+        pack_entry,
+        count,
+        potential_bases,
+        bases_index_offset,
+        None::<fn(u32, u64) -> Option<ObjectId>>,
+        version,
+    )
+}
@@ -31,7 +31,7 @@ pub enum Kind {
     },
 }
 
-/// The error returned by [`output::Entry::from_data()`].
+/// The error returned by [`output::Entry::from_data()`] and [`output::Entry::from_delta()`].
 #[allow(missing_docs)]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -137,17 +137,20 @@ impl output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
-            compressed_data: {
-                let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
-                if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
-                    match err.kind() {
-                        std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
-                        err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
-                    }
-                };
-                out.flush()?;
-                out.into_inner()
+            compressed_data: deflate(obj.data)?,
+        })
+    }
+
+    /// Create a new instance from the given `oid` whose object can be produced by applying `delta` to the object
+    /// written at `base_index`.
+    pub fn from_delta(count: &output::Count, base_index: usize, delta: &[u8]) -> Result<Self, Error> {
+        Ok(output::Entry {
+            id: count.id.to_owned(),
+            kind: Kind::DeltaRef {
+                object_index: base_index,
             },
+            decompressed_size: delta.len(),
+            compressed_data: deflate(delta)?,
         })
     }
 
@@ -184,3 +187,15 @@ impl output::Entry {
         }
     }
 }
+
+fn deflate(mut data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    if let Err(err) = std::io::copy(&mut data, &mut out) {
+        match err.kind() {
+            std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
+            err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
+        }
+    };
+    out.flush()?;
+    Ok(out.into_inner())
+}
@@ -14,6 +14,10 @@ pub struct Count {
     pub id: ObjectId,
     /// A way to locate a pack entry in the object database, only available if the object is in a pack.
     pub entry_pack_location: count::PackLocation,
+    /// A [hash of the path](count::name_hash()) at which the object was first seen, or 0 if it is unknown.
+    ///
+    /// It's used to find objects that are likely similar when delta-compressing them.
+    pub name_hash: u32,
 }
 
 /// An entry to be written to a file.
@@ -68,6 +68,14 @@ impl index::File {
         }
     }
 
+    fn iter_v3(&self) -> impl Iterator<Item = Entry> + '_ {
+        (0..self.num_objects).map(move |index| Entry {
+            oid: self.oid_at_index(index).to_owned(),
+            pack_offset: self.pack_offset_at_index(index),
+            crc32: self.crc32_at_index(index),
+        })
+    }
+
     /// Returns the object hash at the given index in our list of (sorted) sha1 hashes.
     /// The index ranges from 0 to `self.num_objects()`
     ///
@@ -79,10 +87,26 @@ impl index::File {
         let start = match self.version {
             index::Version::V2 => V2_HEADER_SIZE + index * self.hash_len,
             index::Version::V1 => V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len) + N32_SIZE,
+            index::Version::V3 => self.v3_tables().names_in_pack_order + self.pack_order_v3(index) * self.hash_len,
         };
         gix_hash::oid::from_bytes_unchecked(&self.data[start..][..self.hash_len])
     }
 
+    /// Returns the name of the object at the given `index` using the second object format of a version 3 index,
+    /// which is the counterpart of [`oid_at_index()`][index::File::oid_at_index()] using a different kind of hash.
+    ///
+    /// It's `None` if this isn't an index of version 3, or if it doesn't contain a second object format.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn compat_oid_at_index(&self, index: EntryIndex) -> Option<&gix_hash::oid> {
+        let (kind, names_in_pack_order) = self.v3?.compat?;
+        let hash_len = kind.len_in_bytes();
+        let start = names_in_pack_order + self.pack_order_v3(index as usize) * hash_len;
+        Some(gix_hash::oid::from_bytes_unchecked(&self.data[start..][..hash_len]))
+    }
+
     /// Returns the offset into our pack data file at which to start reading the object at `index`.
     ///
     /// # Panics
@@ -99,6 +123,10 @@ impl index::File {
                 let start = V1_HEADER_SIZE + index * (N32_SIZE + self.hash_len);
                 u64::from(crate::read_u32(&self.data[start..][..N32_SIZE]))
             }
+            index::Version::V3 => {
+                let start = self.v3_tables().pack_offsets + index * N32_SIZE;
+                self.pack_offset_from_offset_v2(&self.data[start..][..N32_SIZE], self.offset_pack_offset64_v3())
+            }
         }
     }
 
@@ -116,6 +144,10 @@ impl index::File {
                 Some(crate::read_u32(&self.data[start..start + N32_SIZE]))
             }
             index::Version::V1 => None,
+            index::Version::V3 => {
+                let start = self.v3_tables().crc32 + self.pack_order_v3(index) * N32_SIZE;
+                Some(crate::read_u32(&self.data[start..start + N32_SIZE]))
+            }
         }
     }
 
@@ -156,15 +188,23 @@ impl index::File {
         match self.version {
             index::Version::V2 => Box::new(self.iter_v2()),
             index::Version::V1 => Box::new(self.iter_v1()),
+            index::Version::V3 => Box::new(self.iter_v3()),
         }
     }
 
     /// Return a vector of ascending offsets into our respective pack data file.
     ///
     /// Useful to control an iteration over all pack entries in a cache-friendly way.
+    /// If there is a [reverse index](index::reverse::File) for this index, it's used to avoid sorting.
     pub fn sorted_offsets(&self) -> Vec<data::Offset> {
+        if let Some(rev) = index::reverse::File::at_index(self) {
+            return rev
+                .iter()
+                .map(|index_pos| self.pack_offset_at_index(index_pos))
+                .collect();
+        }
         let mut ofs: Vec<_> = match self.version {
-            index::Version::V1 => self.iter().map(|e| e.pack_offset).collect(),
+            index::Version::V1 | index::Version::V3 => self.iter().map(|e| e.pack_offset).collect(),
             index::Version::V2 => {
                 let offset32_start = &self.data[self.offset_pack_offset_v2()..];
                 let offsets32 = offset32_start.chunks_exact(N32_SIZE).take(self.num_objects as usize);
@@ -194,9 +234,26 @@ impl index::File {
         self.offset_pack_offset_v2() + self.num_objects as usize * N32_SIZE
     }
 
+    #[inline]
+    fn v3_tables(&self) -> index::V3Tables {
+        self.v3.expect("only called for indices of version 3")
+    }
+
+    /// Return the position in pack order of the object at the sorted `index`.
+    #[inline]
+    fn pack_order_v3(&self, index: usize) -> usize {
+        let start = self.v3_tables().pack_order + index * N32_SIZE;
+        crate::read_u32(&self.data[start..][..N32_SIZE]) as usize
+    }
+
+    #[inline]
+    fn offset_pack_offset64_v3(&self) -> usize {
+        self.v3_tables().pack_offsets + self.num_objects as usize * N32_SIZE
+    }
+
     #[inline]
     fn pack_offset_from_offset_v2(&self, offset: &[u8], pack64_offset: usize) -> data::Offset {
-        debug_assert_eq!(self.version, index::Version::V2);
+        debug_assert_ne!(self.version, index::Version::V1);
         let ofs32 = crate::read_u32(offset);
         if (ofs32 & N32_HIGH_BIT) == N32_HIGH_BIT {
             let from = pack64_offset + (ofs32 ^ N32_HIGH_BIT) as usize * N64_SIZE;
@@ -70,88 +70,244 @@ mod function {
         }
     }
 
+    /// Write an index of the given `kind` for `entries_sorted_by_oid` to `out`, hashing it with `object_hash`.
+    ///
+    /// `compat` is only used for indices of version 3 and contains the kind of the second hash along with the names of
+    /// each entry using it, in the same order as `entries_sorted_by_oid`.
     pub(crate) fn write_to(
         out: &mut dyn io::Write,
         entries_sorted_by_oid: Vec<crate::cache::delta::Item<crate::index::write::TreeEntry>>,
+        compat: Option<(gix_hash::Kind, &[gix_hash::ObjectId])>,
         pack_hash: &gix_hash::ObjectId,
         kind: crate::index::Version,
+        object_hash: gix_hash::Kind,
         progress: &mut dyn DynNestedProgress,
     ) -> io::Result<gix_hash::ObjectId> {
         use io::Write;
-        assert_eq!(kind, crate::index::Version::V2, "Can only write V2 packs right now");
         assert!(
             entries_sorted_by_oid.len() <= u32::MAX as usize,
             "a pack cannot have more than u32::MAX objects"
         );
 
-        // Write header
         let mut out = Count::new(std::io::BufWriter::with_capacity(
             8 * 4096,
-            hash::Write::new(out, kind.hash()),
+            hash::Write::new(out, object_hash),
         ));
-        out.write_all(V2_SIGNATURE)?;
-        out.write_all(&(kind as u32).to_be_bytes())?;
-
-        progress.init(Some(4), progress::steps());
         let start = std::time::Instant::now();
+        match kind {
+            crate::index::Version::V1 => write_v1(&mut out, &entries_sorted_by_oid, progress)?,
+            crate::index::Version::V2 => write_v2(&mut out, &entries_sorted_by_oid, progress)?,
+            crate::index::Version::V3 => {
+                let (compat_hash, compat_ids) = compat.expect("compatibility names are computed for version 3");
+                write_v3(
+                    &mut out,
+                    &entries_sorted_by_oid,
+                    object_hash,
+                    compat_hash,
+                    compat_ids,
+                    progress,
+                )?;
+            }
+        }
+
+        out.write_all(pack_hash.as_slice())?;
+
+        let bytes_written_without_trailer = out.bytes;
+        let out = out.inner.into_inner()?;
+        let index_hash = out.hash.try_finalize()?;
+        out.inner.write_all(index_hash.as_slice())?;
+        out.inner.flush()?;
+
+        progress.inc();
+        progress.show_throughput_with(
+            start,
+            (bytes_written_without_trailer + index_hash.as_slice().len() as u64) as usize,
+            progress::bytes().expect("unit always set"),
+            progress::MessageLevel::Success,
+        );
+
+        Ok(index_hash)
+    }
+
+    type Entries = [crate::cache::delta::Item<crate::index::write::TreeEntry>];
+
+    fn write_v1(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &Entries,
+        progress: &mut dyn DynNestedProgress,
+    ) -> io::Result<()> {
+        progress.init(Some(3), progress::steps());
         let _info = progress.add_child_with_id("writing fan-out table".into(), gix_features::progress::UNKNOWN);
-        let fan_out = fanout(&mut entries_sorted_by_oid.iter().map(|e| e.data.id.first_byte()));
+        write_fanout(out, entries_sorted_by_oid)?;
 
-        for value in fan_out.iter() {
-            out.write_all(&value.to_be_bytes())?;
+        progress.inc();
+        let _info = progress.add_child_with_id("writing offsets and ids".into(), gix_features::progress::UNKNOWN);
+        for entry in entries_sorted_by_oid {
+            debug_assert!(entry.offset <= u64::from(u32::MAX), "caller assures offsets fit");
+            out.write_all(&(entry.offset as u32).to_be_bytes())?;
+            out.write_all(entry.data.id.as_slice())?;
         }
+        progress.inc();
+        Ok(())
+    }
+
+    fn write_v2(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &Entries,
+        progress: &mut dyn DynNestedProgress,
+    ) -> io::Result<()> {
+        out.write_all(V2_SIGNATURE)?;
+        out.write_all(&(crate::index::Version::V2 as u32).to_be_bytes())?;
+
+        progress.init(Some(4), progress::steps());
+        let _info = progress.add_child_with_id("writing fan-out table".into(), gix_features::progress::UNKNOWN);
+        write_fanout(out, entries_sorted_by_oid)?;
 
         progress.inc();
         let _info = progress.add_child_with_id("writing ids".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
+        for entry in entries_sorted_by_oid {
             out.write_all(entry.data.id.as_slice())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing crc32".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
+        for entry in entries_sorted_by_oid {
             out.write_all(&entry.data.crc32.to_be_bytes())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing offsets".into(), gix_features::progress::UNKNOWN);
-        {
-            let mut offsets64 = Vec::<u64>::new();
-            for entry in &entries_sorted_by_oid {
-                let offset: u32 = if entry.offset > LARGE_OFFSET_THRESHOLD {
-                    assert!(
-                        offsets64.len() < LARGE_OFFSET_THRESHOLD as usize,
-                        "Encoding breakdown - way too many 64bit offsets"
-                    );
-                    offsets64.push(entry.offset);
-                    ((offsets64.len() - 1) as u32) | HIGH_BIT
-                } else {
-                    entry.offset as u32
-                };
-                out.write_all(&offset.to_be_bytes())?;
-            }
-            for value in offsets64 {
-                out.write_all(&value.to_be_bytes())?;
-            }
+        write_offsets(out, entries_sorted_by_oid.iter().map(|e| e.offset))?;
+        Ok(())
+    }
+
+    fn write_v3(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &Entries,
+        object_hash: gix_hash::Kind,
+        compat_hash: gix_hash::Kind,
+        compat_ids: &[gix_hash::ObjectId],
+        progress: &mut dyn DynNestedProgress,
+    ) -> io::Result<()> {
+        const NUM_FORMATS: usize = 2;
+        const HEADER_LEN: usize = 4 * 6 + NUM_FORMATS * 4 * 3;
+        let num_objects = entries_sorted_by_oid.len();
+
+        progress.init(Some(3), progress::steps());
+        let _info = progress.add_child_with_id("computing pack order".into(), gix_features::progress::UNKNOWN);
+        let mut in_pack_order: Vec<usize> = (0..num_objects).collect();
+        in_pack_order.sort_by_key(|idx| entries_sorted_by_oid[*idx].offset);
+        let mut pack_position = vec![0u32; num_objects];
+        for (position, idx) in in_pack_order.iter().enumerate() {
+            pack_position[*idx] = position as u32;
         }
+        let mut compat_sorted: Vec<usize> = (0..num_objects).collect();
+        compat_sorted.sort_by_key(|idx| compat_ids[*idx]);
 
-        out.write_all(pack_hash.as_slice())?;
+        let short_len = shortest_unique_prefix_len(entries_sorted_by_oid.iter().map(|e| e.data.id.as_slice()));
+        let compat_short_len = shortest_unique_prefix_len(compat_sorted.iter().map(|idx| compat_ids[*idx].as_slice()));
+        let num_large_offsets = entries_sorted_by_oid
+            .iter()
+            .filter(|e| e.offset > LARGE_OFFSET_THRESHOLD)
+            .count();
 
-        let bytes_written_without_trailer = out.bytes;
-        let out = out.inner.into_inner()?;
-        let index_hash: gix_hash::ObjectId = out.hash.digest().into();
-        out.inner.write_all(index_hash.as_slice())?;
-        out.inner.flush()?;
+        let tables_len = num_objects * (short_len + object_hash.len_in_bytes() + 4 * 3) + num_large_offsets * 8;
+        let compat_tables_len = num_objects * (compat_short_len + compat_hash.len_in_bytes() + 4);
+        let table_offset = |ofs: usize| {
+            u32::try_from(ofs)
+                .map_err(|_| io::Error::new(io::ErrorKind::Other, "Pack index is too large for version 3"))
+        };
+        let native_tables = HEADER_LEN;
+        let compat_tables = native_tables + tables_len;
+        let trailer = compat_tables + compat_tables_len;
 
         progress.inc();
-        progress.show_throughput_with(
-            start,
-            (bytes_written_without_trailer + 20) as usize,
-            progress::bytes().expect("unit always set"),
-            progress::MessageLevel::Success,
-        );
+        let _info = progress.add_child_with_id("writing header".into(), gix_features::progress::UNKNOWN);
+        out.write_all(V2_SIGNATURE)?;
+        out.write_all(&(crate::index::Version::V3 as u32).to_be_bytes())?;
+        out.write_all(&(HEADER_LEN as u32).to_be_bytes())?;
+        out.write_all(&(num_objects as u32).to_be_bytes())?;
+        out.write_all(&(NUM_FORMATS as u32).to_be_bytes())?;
+        for (kind, short_len, offset) in [
+            (object_hash, short_len, native_tables),
+            (compat_hash, compat_short_len, compat_tables),
+        ] {
+            out.write_all(&crate::index::format_id(kind).to_be_bytes())?;
+            out.write_all(&(short_len as u32).to_be_bytes())?;
+            out.write_all(&table_offset(offset)?.to_be_bytes())?;
+        }
+        out.write_all(&table_offset(trailer)?.to_be_bytes())?;
 
-        Ok(index_hash)
+        progress.inc();
+        let _info = progress.add_child_with_id("writing tables".into(), gix_features::progress::UNKNOWN);
+        for entry in entries_sorted_by_oid {
+            out.write_all(&entry.data.id.as_slice()[..short_len])?;
+        }
+        for idx in &in_pack_order {
+            out.write_all(entries_sorted_by_oid[*idx].data.id.as_slice())?;
+        }
+        for position in &pack_position {
+            out.write_all(&position.to_be_bytes())?;
+        }
+        for idx in &in_pack_order {
+            out.write_all(&entries_sorted_by_oid[*idx].data.crc32.to_be_bytes())?;
+        }
+        write_offsets(out, entries_sorted_by_oid.iter().map(|e| e.offset))?;
+
+        for idx in &compat_sorted {
+            out.write_all(&compat_ids[*idx].as_slice()[..compat_short_len])?;
+        }
+        for idx in &in_pack_order {
+            out.write_all(compat_ids[*idx].as_slice())?;
+        }
+        for idx in &compat_sorted {
+            out.write_all(&pack_position[*idx].to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn write_fanout(out: &mut dyn io::Write, entries_sorted_by_oid: &Entries) -> io::Result<()> {
+        let fan_out = fanout(&mut entries_sorted_by_oid.iter().map(|e| e.data.id.first_byte()));
+        for value in fan_out.iter() {
+            out.write_all(&value.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Write 32 bit `offsets`, followed by a table of 64 bit offsets for those that are too large to be stored directly.
+    fn write_offsets(out: &mut dyn io::Write, offsets: impl Iterator<Item = u64>) -> io::Result<()> {
+        let mut offsets64 = Vec::<u64>::new();
+        for offset in offsets {
+            let offset: u32 = if offset > LARGE_OFFSET_THRESHOLD {
+                assert!(
+                    offsets64.len() < LARGE_OFFSET_THRESHOLD as usize,
+                    "Encoding breakdown - way too many 64bit offsets"
+                );
+                offsets64.push(offset);
+                ((offsets64.len() - 1) as u32) | HIGH_BIT
+            } else {
+                offset as u32
+            };
+            out.write_all(&offset.to_be_bytes())?;
+        }
+        for value in offsets64 {
+            out.write_all(&value.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Return the amount of bytes needed to tell all sorted `ids` apart, which is at least one.
+    fn shortest_unique_prefix_len<'a>(ids: impl Iterator<Item = &'a [u8]>) -> usize {
+        let mut len = 1;
+        let mut prev: Option<&[u8]> = None;
+        for id in ids {
+            if let Some(prev) = prev {
+                let common = prev.iter().zip(id).take_while(|(a, b)| a == b).count();
+                len = len.max((common + 1).min(id.len()));
+            }
+            prev = Some(id);
+        }
+        len
     }
 }
 #[cfg(feature = "streaming-input")]
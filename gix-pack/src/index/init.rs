@@ -1,6 +1,6 @@
 use std::{mem::size_of, path::Path};
 
-use crate::index::{self, Version, FAN_LEN, V2_SIGNATURE};
+use crate::index::{self, V3Tables, Version, FAN_LEN, V2_SIGNATURE};
 
 /// Returned by [`index::File::at()`].
 #[derive(thiserror::Error, Debug)]
@@ -38,50 +38,142 @@ impl index::File {
         let hash_len = object_hash.len_in_bytes();
 
         let footer_size = hash_len * 2;
+        let version = if data.starts_with(V2_SIGNATURE) && idx_len >= V2_SIGNATURE.len() + N32_SIZE {
+            let version = crate::read_u32(&data[V2_SIGNATURE.len()..][..N32_SIZE]);
+            match version {
+                2 => Version::V2,
+                3 => Version::V3,
+                _ => return Err(Error::UnsupportedVersion { version }),
+            }
+        } else {
+            Version::V1
+        };
+        if version == Version::V3 {
+            let (num_objects, (sorted_names, short_len), tables) = read_v3_header(&data, object_hash)?;
+            let mut fan = [0u32; FAN_LEN];
+            for idx in 0..num_objects as usize {
+                fan[data[sorted_names + idx * short_len] as usize] += 1;
+            }
+            for idx in 1..FAN_LEN {
+                fan[idx] += fan[idx - 1];
+            }
+            return Ok(index::File {
+                data,
+                path: path.to_owned(),
+                version,
+                num_objects,
+                fan,
+                hash_len,
+                object_hash,
+                v3: Some(tables),
+            });
+        }
+
         if idx_len < FAN_LEN * N32_SIZE + footer_size {
             return Err(Error::Corrupt {
                 message: format!("Pack index of size {idx_len} is too small for even an empty index"),
             });
         }
-        let (kind, fan, num_objects) = {
-            let (kind, d) = {
-                let (sig, d) = data.split_at(V2_SIGNATURE.len());
-                if sig == V2_SIGNATURE {
-                    (Version::V2, d)
-                } else {
-                    (Version::V1, &data[..])
-                }
-            };
-            let d = {
-                if let Version::V2 = kind {
-                    let (vd, dr) = d.split_at(N32_SIZE);
-                    let version = crate::read_u32(vd);
-                    if version != Version::V2 as u32 {
-                        return Err(Error::UnsupportedVersion { version });
-                    }
-                    dr
-                } else {
-                    d
-                }
+        let (fan, num_objects) = {
+            let d = match version {
+                Version::V2 => &data[V2_SIGNATURE.len() + N32_SIZE..],
+                _ => &data[..],
             };
-            let (fan, bytes_read) = read_fan(d);
-            let (_, _d) = d.split_at(bytes_read);
+            let (fan, _bytes_read) = read_fan(d);
             let num_objects = fan[FAN_LEN - 1];
 
-            (kind, fan, num_objects)
+            (fan, num_objects)
         };
         Ok(index::File {
             data,
             path: path.to_owned(),
-            version: kind,
+            version,
             num_objects,
             fan,
             hash_len,
             object_hash,
+            v3: None,
         })
     }
 }
 
+/// Parse the header of a version 3 index in `data` and return the amount of objects, the offset and length of the
+/// sorted (and possibly shortened) object names of `object_hash`, along with the location of all other tables.
+fn read_v3_header(data: &[u8], object_hash: gix_hash::Kind) -> Result<(u32, (usize, usize), V3Tables), Error> {
+    let corrupt = |message: &str| Error::Corrupt {
+        message: message.into(),
+    };
+    let hash_len = object_hash.len_in_bytes();
+    let read_u32_at = |ofs: usize| {
+        data.get(ofs..ofs + N32_SIZE)
+            .map(crate::read_u32)
+            .ok_or_else(|| corrupt("Pack index header is truncated"))
+    };
+    let header_len = read_u32_at(8)? as usize;
+    let num_objects = read_u32_at(12)?;
+    let num_formats = read_u32_at(16)? as usize;
+    let trailer_offset = read_u32_at(20 + num_formats * 3 * N32_SIZE)? as usize;
+    if header_len > data.len() || trailer_offset + hash_len * 2 != data.len() {
+        return Err(corrupt(
+            "Pack index header points to a trailer beyond the end of the file",
+        ));
+    }
+
+    let mut formats = (0..num_formats).map(|idx| -> Result<_, Error> {
+        let ofs = 20 + idx * 3 * N32_SIZE;
+        Ok((
+            read_u32_at(ofs)?,
+            read_u32_at(ofs + N32_SIZE)? as usize,
+            read_u32_at(ofs + N32_SIZE * 2)? as usize,
+        ))
+    });
+    let (format, short_len, names) = formats
+        .next()
+        .ok_or_else(|| corrupt("Pack index does not contain any object format"))??;
+    if index::kind_from_format_id(format) != Some(object_hash) {
+        return Err(Error::Corrupt {
+            message: format!(
+                "Pack index of version 3 must list {object_hash} as its first object format to be readable with it"
+            ),
+        });
+    }
+    if short_len == 0 || short_len > hash_len {
+        return Err(corrupt("Pack index has an invalid length for shortened object names"));
+    }
+    let num_objects_usize = num_objects as usize;
+    let names_in_pack_order = names + num_objects_usize * short_len;
+    let pack_order = names_in_pack_order + num_objects_usize * hash_len;
+    let crc32 = pack_order + num_objects_usize * N32_SIZE;
+    let pack_offsets = crc32 + num_objects_usize * N32_SIZE;
+    if pack_offsets + num_objects_usize * N32_SIZE > trailer_offset {
+        return Err(corrupt("Pack index tables exceed the size of the file"));
+    }
+
+    let compat = match formats.next().transpose()? {
+        Some((format, short_len, names)) => index::kind_from_format_id(format)
+            .map(|kind| {
+                let names_in_pack_order = names + num_objects_usize * short_len;
+                if names_in_pack_order + num_objects_usize * kind.len_in_bytes() > trailer_offset {
+                    return Err(corrupt("Pack index tables exceed the size of the file"));
+                }
+                Ok((kind, names_in_pack_order))
+            })
+            .transpose()?,
+        None => None,
+    };
+    Ok((
+        num_objects,
+        (names, short_len),
+        V3Tables {
+            names_in_pack_order,
+            pack_order,
+            crc32,
+            pack_offsets,
+            compat,
+        },
+    ))
+}
+
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     assert!(d.len() >= FAN_LEN * N32_SIZE);
 
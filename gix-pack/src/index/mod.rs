@@ -83,17 +83,52 @@ pub enum Version {
     V1 = 1,
     #[default]
     V2 = 2,
+    /// An index with tables for the object names of all known hash kinds, along with the mapping between them.
+    V3 = 3,
 }
 
 impl Version {
     /// The kind of hash to produce to be compatible to this kind of index
     pub fn hash(&self) -> gix_hash::Kind {
         match self {
-            Version::V1 | Version::V2 => gix_hash::Kind::Sha1,
+            Version::V1 | Version::V2 | Version::V3 => gix_hash::Kind::Sha1,
         }
     }
 }
 
+/// The location of the tables of a version 3 index, as offsets from the beginning of the file.
+#[derive(Debug, Clone, Copy)]
+struct V3Tables {
+    /// The full object names in pack order.
+    names_in_pack_order: usize,
+    /// For each sorted object name, its position in pack order.
+    pack_order: usize,
+    /// The crc32 of each entry, in pack order.
+    crc32: usize,
+    /// The 32 bit pack offsets for each sorted object name, followed by the 64 bit offsets.
+    pack_offsets: usize,
+    /// The kind of hash of the second object format, along with its full object names in pack order.
+    compat: Option<(gix_hash::Kind, usize)>,
+}
+
+/// Return the identifier of `kind` as used in the header of a version 3 index.
+#[cfg(feature = "streaming-input")]
+pub(crate) fn format_id(kind: gix_hash::Kind) -> u32 {
+    u32::from_be_bytes(match kind {
+        gix_hash::Kind::Sha1 => *b"sha1",
+        gix_hash::Kind::Sha256 => *b"s256",
+    })
+}
+
+/// Return the kind of hash identified by `id` in the header of a version 3 index, or `None` if it is unknown.
+pub(crate) fn kind_from_format_id(id: u32) -> Option<gix_hash::Kind> {
+    Some(match &id.to_be_bytes() {
+        b"sha1" => gix_hash::Kind::Sha1,
+        b"s256" => gix_hash::Kind::Sha256,
+        _ => return None,
+    })
+}
+
 /// A way to indicate if a lookup, despite successful, was ambiguous or yielded exactly
 /// one result in the particular index.
 pub type PrefixLookupResult = Result<EntryIndex, ()>;
@@ -112,6 +147,7 @@ pub struct File {
     fan: [u32; FAN_LEN],
     hash_len: usize,
     object_hash: gix_hash::Kind,
+    v3: Option<V3Tables>,
 }
 
 /// Basic file information
@@ -143,6 +179,8 @@ pub use access::Entry;
 
 pub(crate) mod encode;
 ///
+pub mod reverse;
+///
 pub mod traverse;
 mod util;
 ///
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use crate::index::reverse::{File, Version, HEADER_LEN, SIGNATURE};
+
+/// Returned by [`File::at()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open pack reverse index file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{message}")]
+    Corrupt { message: String },
+    #[error("Unsupported reverse index version: {version}")]
+    UnsupportedVersion { version: u32 },
+    #[error("The reverse index uses hash id {actual}, but {expected:?} was expected")]
+    ObjectHashMismatch { actual: u32, expected: gix_hash::Kind },
+}
+
+/// Instantiation
+impl File {
+    /// Open the reverse index file at the given `path`, expecting hashes of kind `object_hash`.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<File, Error> {
+        Self::at_inner(path.as_ref(), object_hash)
+    }
+
+    /// Open the reverse index next to the pack `index`, or return `None` if there is none or if it doesn't belong to `index`.
+    pub fn at_index(index: &crate::index::File) -> Option<File> {
+        File::at(index.path().with_extension("rev"), index.object_hash())
+            .ok()
+            .filter(|rev| rev.pack_checksum() == index.pack_checksum() && rev.num_objects() == index.num_objects())
+    }
+
+    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<File, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let footer_size = object_hash.len_in_bytes() * 2;
+        if data.len() < HEADER_LEN + footer_size {
+            return Err(Error::Corrupt {
+                message: format!(
+                    "Reverse index of size {} is too small for even an empty index",
+                    data.len()
+                ),
+            });
+        }
+        if &data[..SIGNATURE.len()] != SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Reverse index does not start with the expected signature".into(),
+            });
+        }
+        let version = match crate::read_u32(&data[4..8]) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let hash_id = crate::read_u32(&data[8..12]);
+        if hash_id != object_hash as u32 {
+            return Err(Error::ObjectHashMismatch {
+                actual: hash_id,
+                expected: object_hash,
+            });
+        }
+        let table_len = data.len() - HEADER_LEN - footer_size;
+        if table_len % 4 != 0 {
+            return Err(Error::Corrupt {
+                message: format!("Reverse index table of {table_len} bytes is not a multiple of 4"),
+            });
+        }
+        Ok(File {
+            num_objects: (table_len / 4) as u32,
+            data,
+            path: path.to_owned(),
+            version,
+            object_hash,
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use memmap2::Mmap;
+
+use crate::{data, index::EntryIndex};
+
+/// The signature of reverse index files, which is also used for the reverse index chunk in multi-pack indices.
+pub const SIGNATURE: &[u8] = b"RIDX";
+const HEADER_LEN: usize = 4 /*signature*/ + 4 /*version*/ + 4 /*hash id*/;
+
+/// The version of a reverse index file.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// A representation of a pack reverse index file (`pack-*.rev`), which lists the entries of a pack index
+/// in the order in which they appear in the pack.
+///
+/// This allows to map pack offsets to entries without sorting all offsets of the index first,
+/// and to learn the size of an entry in the pack by looking at the offset of the entry following it.
+pub struct File {
+    data: Mmap,
+    path: std::path::PathBuf,
+    version: Version,
+    num_objects: u32,
+    object_hash: gix_hash::Kind,
+}
+
+/// Basic file information
+impl File {
+    /// The version of the reverse index.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// The path of the opened reverse index file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The amount of objects in the pack whose entries we order.
+    pub fn num_objects(&self) -> u32 {
+        self.num_objects
+    }
+    /// The kind of hash used in the pack and in this file.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+    /// The checksum of the pack whose entries we order, which matches the one in its index file.
+    pub fn pack_checksum(&self) -> gix_hash::ObjectId {
+        let hash_len = self.object_hash.len_in_bytes();
+        gix_hash::ObjectId::from_bytes_or_panic(&self.data[self.data.len() - hash_len * 2..][..hash_len])
+    }
+    /// The trailing hash over all bytes of this file.
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        let hash_len = self.object_hash.len_in_bytes();
+        gix_hash::ObjectId::from_bytes_or_panic(&self.data[self.data.len() - hash_len..])
+    }
+}
+
+/// Access
+impl File {
+    /// Return the position in the pack index of the entry at `pack_pos`, the position of the entry in the pack
+    /// when ordered by offset, or `None` if `pack_pos` is out of bounds.
+    pub fn index_position(&self, pack_pos: u32) -> Option<EntryIndex> {
+        (pack_pos < self.num_objects).then(|| crate::read_u32(&self.data[HEADER_LEN + pack_pos as usize * 4..][..4]))
+    }
+
+    /// Return an iterator over all positions in the pack index in the order in which their entries appear in the pack.
+    pub fn iter(&self) -> impl Iterator<Item = EntryIndex> + '_ {
+        self.data[HEADER_LEN..][..self.num_objects as usize * 4]
+            .chunks_exact(4)
+            .map(crate::read_u32)
+    }
+
+    /// Return the position in the pack of the entry at `pack_offset`, or `None` if there is no entry at this offset.
+    ///
+    /// `index` is the pack index this file belongs to.
+    pub fn pack_position(&self, index: &crate::index::File, pack_offset: data::Offset) -> Option<u32> {
+        let offset_at = |pack_pos: u32| {
+            index.pack_offset_at_index(self.index_position(pack_pos).expect("pack position is in bounds"))
+        };
+        let (mut lo, mut hi) = (0, self.num_objects);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match offset_at(mid).cmp(&pack_offset) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    /// Return the position in `index` of the entry at `pack_offset`, or `None` if there is no entry at this offset.
+    pub fn lookup_by_pack_offset(&self, index: &crate::index::File, pack_offset: data::Offset) -> Option<EntryIndex> {
+        self.pack_position(index, pack_offset)
+            .and_then(|pack_pos| self.index_position(pack_pos))
+    }
+
+    /// Return the offset of the entry that follows the one at `pack_offset` in the pack, or `None` if it is the last entry
+    /// or if there is no entry at `pack_offset`.
+    ///
+    /// Use [`data::File::entry_size_on_disk()`] to learn how many bytes an entry occupies without decoding it.
+    pub fn next_offset(&self, index: &crate::index::File, pack_offset: data::Offset) -> Option<data::Offset> {
+        let pack_pos = self.pack_position(index, pack_offset)?;
+        self.index_position(pack_pos + 1)
+            .map(|index_pos| index.pack_offset_at_index(index_pos))
+    }
+}
+
+///
+pub mod init;
+///
+pub mod write;
@@ -0,0 +1,33 @@
+use std::io::Write;
+
+use crate::index::{
+    self,
+    reverse::{File, Version, SIGNATURE},
+};
+
+impl File {
+    /// Write a reverse index for the pack `index` into `out` and return the checksum of everything written,
+    /// which is also its trailer.
+    pub fn write_from_index(index: &index::File, out: &mut dyn std::io::Write) -> std::io::Result<gix_hash::ObjectId> {
+        let object_hash = index.object_hash();
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&(Version::V1 as u32).to_be_bytes())?;
+        out.write_all(&(object_hash as u32).to_be_bytes())?;
+
+        let mut offsets: Vec<_> = index
+            .iter()
+            .enumerate()
+            .map(|(index_pos, entry)| (entry.pack_offset, index_pos as u32))
+            .collect();
+        offsets.sort_unstable();
+        for (_, index_pos) in offsets {
+            out.write_all(&index_pos.to_be_bytes())?;
+        }
+        out.write_all(index.pack_checksum().as_slice())?;
+
+        let checksum = out.hash.try_finalize()?;
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(checksum)
+    }
+}
@@ -43,4 +43,6 @@ pub enum Error<E: std::error::Error + Send + Sync + 'static> {
     },
     #[error("Interrupted")]
     Interrupted,
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
 }
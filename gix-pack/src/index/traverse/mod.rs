@@ -210,7 +210,7 @@ where
     E: std::error::Error + Send + Sync + 'static,
 {
     if check.object_checksum() {
-        let actual_oid = gix_object::compute_hash(index_entry.oid.kind(), object_kind, decompressed);
+        let actual_oid = gix_object::compute_hash(index_entry.oid.kind(), object_kind, decompressed)?;
         if actual_oid != index_entry.oid {
             return Err(Error::PackObjectMismatch {
                 actual: actual_oid,
@@ -10,11 +10,25 @@ pub(crate) fn index_entries_sorted_by_offset_ascending(
     let start = Instant::now();
 
     let mut v = Vec::with_capacity(idx.num_objects as usize);
-    for entry in idx.iter() {
-        v.push(entry);
-        progress.inc();
+    match crate::index::reverse::File::at_index(idx) {
+        Some(rev) => {
+            for index_pos in rev.iter() {
+                v.push(crate::index::Entry {
+                    oid: idx.oid_at_index(index_pos).to_owned(),
+                    pack_offset: idx.pack_offset_at_index(index_pos),
+                    crc32: idx.crc32_at_index(index_pos),
+                });
+                progress.inc();
+            }
+        }
+        None => {
+            for entry in idx.iter() {
+                v.push(entry);
+                progress.inc();
+            }
+            v.sort_by_key(|e| e.pack_offset);
+        }
     }
-    v.sort_by_key(|e| e.pack_offset);
 
     progress.show_throughput(start);
     v
@@ -0,0 +1,176 @@
+use gix_hash::{oid, ObjectId};
+
+use crate::{cache::delta::Item, index::write::TreeEntry};
+
+/// The name of an object using the second kind of hash of an index of version 3.
+pub(crate) enum Entry {
+    /// The name is known.
+    Id(ObjectId),
+    /// The object refers to other objects by name, so its own name can only be computed once theirs are known.
+    Pending { kind: gix_object::Kind, data: Vec<u8> },
+}
+
+impl Entry {
+    /// Create a new instance for an object of `kind` with `data`, computing its name with `compat_hash` right away if
+    /// it doesn't refer to other objects.
+    pub(crate) fn new(
+        kind: gix_object::Kind,
+        data: &[u8],
+        compat_hash: gix_hash::Kind,
+    ) -> Result<Self, gix_features::hash::Error> {
+        Ok(match kind {
+            gix_object::Kind::Blob => Entry::Id(gix_object::compute_hash(compat_hash, kind, data)?),
+            gix_object::Kind::Tree | gix_object::Kind::Commit | gix_object::Kind::Tag => Entry::Pending {
+                kind,
+                data: data.to_owned(),
+            },
+        })
+    }
+}
+
+/// Return the kind of hash that isn't `kind`, to be used as second object format in indices of version 3.
+pub(crate) fn other_kind(kind: gix_hash::Kind) -> gix_hash::Kind {
+    match kind {
+        gix_hash::Kind::Sha1 => gix_hash::Kind::Sha256,
+        gix_hash::Kind::Sha256 => gix_hash::Kind::Sha1,
+    }
+}
+
+/// Compute the names of all `entries` with `compat_hash` by rewriting the object names they refer to, and return them
+/// in the same order as `entries`, which must be sorted by their name.
+///
+/// All objects referred to must be contained in `entries` as well.
+pub(crate) fn resolve(
+    entries: &mut [Item<TreeEntry>],
+    object_hash: gix_hash::Kind,
+    compat_hash: gix_hash::Kind,
+) -> Result<Vec<ObjectId>, super::Error> {
+    let mut ids: Vec<Option<ObjectId>> = entries
+        .iter()
+        .map(|item| match item.data.compat.as_deref() {
+            Some(Entry::Id(id)) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let index_of = |id: &oid| entries.binary_search_by(|item| item.data.id.as_ref().cmp(id)).ok();
+
+    let mut buf = Vec::new();
+    let mut stack = Vec::new();
+    for idx in 0..entries.len() {
+        stack.push(idx);
+        while let Some(&current) = stack.last() {
+            if ids[current].is_some() {
+                stack.pop();
+                continue;
+            }
+            let Some(Entry::Pending { kind, data }) = entries[current].data.compat.as_deref() else {
+                unreachable!("names of blobs are always known")
+            };
+            let num_pending = stack.len();
+            buf.clear();
+            rewrite(*kind, data, object_hash, &mut buf, &mut |id| {
+                let idx = index_of(id).ok_or(super::Error::CompatObjectMissing {
+                    id: id.to_owned(),
+                    kind: compat_hash,
+                })?;
+                Ok(ids[idx].or_else(|| {
+                    stack.push(idx);
+                    None
+                }))
+            })
+            .map_err(|err| match err {
+                RewriteError::Decode => super::Error::CompatObjectDecode {
+                    id: entries[current].data.id,
+                },
+                RewriteError::Lookup(err) => err,
+            })?;
+            if stack.len() == num_pending {
+                ids[current] = Some(gix_object::compute_hash(compat_hash, *kind, &buf)?);
+                stack.pop();
+            }
+        }
+    }
+
+    for item in entries.iter_mut() {
+        item.data.compat = None;
+    }
+    Ok(ids.into_iter().map(|id| id.expect("all names were computed")).collect())
+}
+
+enum RewriteError {
+    Decode,
+    Lookup(super::Error),
+}
+
+impl From<super::Error> for RewriteError {
+    fn from(err: super::Error) -> Self {
+        RewriteError::Lookup(err)
+    }
+}
+
+/// Write `data` of an object of `kind` into `out`, with all object names of `object_hash` it refers to replaced by the ones
+/// returned by `map`. If `map` returns `None`, the name isn't known yet and `out` will be incomplete.
+///
+/// Only the headers of commits and tags are rewritten, while signatures and embedded tags are copied as is.
+fn rewrite(
+    kind: gix_object::Kind,
+    data: &[u8],
+    object_hash: gix_hash::Kind,
+    out: &mut Vec<u8>,
+    map: &mut dyn FnMut(&oid) -> Result<Option<ObjectId>, super::Error>,
+) -> Result<(), RewriteError> {
+    let mut map_to_out = |id: &oid, out: &mut Vec<u8>, hex: bool| -> Result<(), RewriteError> {
+        if let Some(id) = map(id)? {
+            if hex {
+                out.extend_from_slice(id.to_hex().to_string().as_bytes());
+            } else {
+                out.extend_from_slice(id.as_slice());
+            }
+        }
+        Ok(())
+    };
+    match kind {
+        gix_object::Kind::Blob => out.extend_from_slice(data),
+        gix_object::Kind::Tree => {
+            let mut data = data;
+            while !data.is_empty() {
+                let nul = data.iter().position(|b| *b == 0).ok_or(RewriteError::Decode)?;
+                let (mode_and_name, rest) = data.split_at(nul + 1);
+                if rest.len() < object_hash.len_in_bytes() {
+                    return Err(RewriteError::Decode);
+                }
+                out.extend_from_slice(mode_and_name);
+                let (id, rest) = rest.split_at(object_hash.len_in_bytes());
+                map_to_out(oid::from_bytes_unchecked(id), out, false)?;
+                data = rest;
+            }
+        }
+        gix_object::Kind::Commit | gix_object::Kind::Tag => {
+            let fields: &[&[u8]] = if kind == gix_object::Kind::Commit {
+                &[b"tree ", b"parent "]
+            } else {
+                &[b"object "]
+            };
+            let mut lines = data.split_inclusive(|b| *b == b'\n');
+            for line in lines.by_ref() {
+                match fields.iter().find(|field| line.starts_with(field)) {
+                    Some(field) => {
+                        let hex = line[field.len()..].strip_suffix(b"\n").ok_or(RewriteError::Decode)?;
+                        let id = ObjectId::from_hex(hex).map_err(|_| RewriteError::Decode)?;
+                        out.extend_from_slice(field);
+                        map_to_out(&id, out, true)?;
+                        out.push(b'\n');
+                    }
+                    None => out.extend_from_slice(line),
+                }
+                if line == b"\n" {
+                    break;
+                }
+            }
+            for line in lines {
+                out.extend_from_slice(line);
+            }
+        }
+    }
+    Ok(())
+}
@@ -8,18 +8,27 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("A pack entry could not be extracted")]
     PackEntryDecode(#[from] crate::data::input::Error),
-    #[error("Indices of type {} cannot be written, only {} are supported", *.0 as usize, crate::index::Version::default() as usize)]
-    Unsupported(crate::index::Version),
     #[error("Ref delta objects are not supported as there is no way to look them up. Resolve them beforehand.")]
     IteratorInvariantNoRefDelta,
     #[error("The iterator failed to set a trailing hash over all prior pack entries in the last provided entry")]
     IteratorInvariantTrailer,
     #[error("Only u32::MAX objects can be stored in a pack, found {0}")]
     IteratorInvariantTooManyObjects(usize),
+    #[error("The pack offset {pack_offset} can't be stored in an index of version 1, which is limited to packs smaller than 4GB")]
+    IteratorInvariantOffsetV1 { pack_offset: u64 },
+    #[error("Object {id} is referred to but not contained in the pack, so its {kind} name can't be computed for the version 3 index")]
+    CompatObjectMissing {
+        id: gix_hash::ObjectId,
+        kind: gix_hash::Kind,
+    },
+    #[error("Object {id} could not be parsed to compute its name with a second kind of hash")]
+    CompatObjectDecode { id: gix_hash::ObjectId },
     #[error("{pack_offset} is not a valid offset for pack offset {distance}")]
     IteratorInvariantBaseOffset { pack_offset: u64, distance: u64 },
     #[error(transparent)]
     Tree(#[from] crate::cache::delta::Error),
     #[error(transparent)]
     TreeTraversal(#[from] crate::cache::delta::traverse::Error),
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
 }
@@ -5,11 +5,14 @@ use gix_features::progress::{self, prodash::DynNestedProgress, Count, Progress};
 
 use crate::cache::delta::{traverse, Tree};
 
+pub(crate) mod compat;
 mod error;
 
 pub(crate) struct TreeEntry {
     pub id: gix_hash::ObjectId,
     pub crc32: u32,
+    /// The name of the object with a second kind of hash, only used when writing indices of version 3.
+    pub compat: Option<Box<compat::Entry>>,
 }
 
 /// Information gathered while executing [`write_data_iter_to_stream()`][crate::index::File::write_data_iter_to_stream]
@@ -67,6 +70,8 @@ impl crate::index::File {
     /// `entries` iterator.
     ///
     /// * `kind` is the version of pack index to produce, use [`crate::index::Version::default()`] if in doubt.
+    ///   Version 1 is limited to packs smaller than 4GB, while version 3 also contains the names of all objects
+    ///   using the kind of hash that isn't `object_hash`.
     /// * `tread_limit` is used for a parallel tree traversal for obtaining object hashes with optimal performance.
     /// * `root_progress` is the top-level progress to stay informed about the progress of this potentially long-running
     ///    computation.
@@ -77,6 +82,8 @@ impl crate::index::File {
     /// # Remarks
     ///
     /// * neither in-pack nor out-of-pack Ref Deltas are supported here, these must have been resolved beforehand.
+    /// * for version 3, all objects referred to by trees, commits and tags must be contained in the pack as their names
+    ///   can otherwise not be translated into the second kind of hash.
     /// * `make_resolver()` will only be called after the iterator stopped returning elements and produces a function that
     ///   provides all bytes belonging to a pack entry writing them to the given mutable output `Vec`.
     ///   It should return `None` if the entry cannot be resolved from the pack that produced the `entries` iterator, causing
@@ -98,9 +105,7 @@ impl crate::index::File {
         R: Send + Sync,
         F2: for<'r> Fn(crate::data::EntryRange, &'r R) -> Option<&'r [u8]> + Send + Clone,
     {
-        if version != crate::index::Version::default() {
-            return Err(Error::Unsupported(version));
-        }
+        let compat_hash = (version == crate::index::Version::V3).then(|| compat::other_kind(object_hash));
         let mut num_objects: usize = 0;
         let mut last_seen_trailer = None;
         let (anticipated_num_objects, upper_bound) = entries.size_hint();
@@ -132,6 +137,9 @@ impl crate::index::File {
 
             let entry_len = u64::from(header_size) + compressed_size;
             pack_entries_end = pack_offset + entry_len;
+            if version == crate::index::Version::V1 && pack_offset > u64::from(u32::MAX) {
+                return Err(Error::IteratorInvariantOffsetV1 { pack_offset });
+            }
 
             let crc32 = crc32.expect("crc32 to be computed by the iterator. Caller assures correct configuration.");
 
@@ -143,6 +151,7 @@ impl crate::index::File {
                         TreeEntry {
                             id: object_hash.null(),
                             crc32,
+                            compat: None,
                         },
                     )?;
                 }
@@ -161,6 +170,7 @@ impl crate::index::File {
                         TreeEntry {
                             id: object_hash.null(),
                             crc32,
+                            compat: None,
                         },
                     )?;
                 }
@@ -181,32 +191,45 @@ impl crate::index::File {
         root_progress.inc();
 
         let (resolver, pack) = make_resolver()?;
-        let sorted_pack_offsets_by_oid = {
-            let traverse::Outcome { roots, children } = tree.traverse(
-                resolver,
-                &pack,
-                pack_entries_end,
-                |data,
-                 _progress,
-                 traverse::Context {
-                     entry,
-                     decompressed: bytes,
-                     ..
-                 }| {
-                    modify_base(data, entry, bytes, version.hash());
-                    Ok::<_, Error>(())
-                },
-                traverse::Options {
-                    object_progress: Box::new(
-                        root_progress.add_child_with_id("Resolving".into(), ProgressId::ResolveObjects.into()),
-                    ),
-                    size_progress: &mut root_progress
-                        .add_child_with_id("Decoding".into(), ProgressId::DecodedBytes.into()),
-                    thread_limit,
-                    should_interrupt,
-                    object_hash,
-                },
-            )?;
+        let mut sorted_pack_offsets_by_oid = {
+            let traverse::Outcome { roots, children } = tree
+                .traverse(
+                    resolver,
+                    &pack,
+                    pack_entries_end,
+                    |data,
+                     _progress,
+                     traverse::Context {
+                         entry,
+                         decompressed: bytes,
+                         ..
+                     }| {
+                        modify_base(data, entry, bytes, object_hash)?;
+                        if let Some(compat_hash) = compat_hash {
+                            let kind = entry.header.as_kind().expect("base object as source of iteration");
+                            data.compat = Some(Box::new(compat::Entry::new(kind, bytes, compat_hash)?));
+                        }
+                        Ok::<_, Error>(())
+                    },
+                    traverse::Options {
+                        object_progress: Box::new(
+                            root_progress.add_child_with_id("Resolving".into(), ProgressId::ResolveObjects.into()),
+                        ),
+                        size_progress: &mut root_progress
+                            .add_child_with_id("Decoding".into(), ProgressId::DecodedBytes.into()),
+                        thread_limit,
+                        should_interrupt,
+                        object_hash,
+                    },
+                )
+                .map_err(|err| match err {
+                    // Unwrap our own errors, like collision attacks detected while hashing objects, to keep them typed.
+                    traverse::Error::Inspect(err) => match err.downcast::<Error>() {
+                        Ok(err) => *err,
+                        Err(err) => Error::TreeTraversal(traverse::Error::Inspect(err)),
+                    },
+                    err => Error::TreeTraversal(err),
+                })?;
             root_progress.inc();
 
             let mut items = roots;
@@ -220,6 +243,15 @@ impl crate::index::File {
             root_progress.inc();
             items
         };
+        let compat_ids = compat_hash
+            .map(|compat_hash| {
+                let _progress = root_progress.add_child_with_id(
+                    format!("computing {compat_hash} object names"),
+                    gix_features::progress::UNKNOWN,
+                );
+                compat::resolve(&mut sorted_pack_offsets_by_oid, object_hash, compat_hash).map(|ids| (compat_hash, ids))
+            })
+            .transpose()?;
 
         let pack_hash = match last_seen_trailer {
             Some(ph) => ph,
@@ -227,15 +259,17 @@ impl crate::index::File {
                 let header = crate::data::header::encode(pack_version, 0);
                 let mut hasher = gix_features::hash::hasher(object_hash);
                 hasher.update(&header);
-                gix_hash::ObjectId::from(hasher.digest())
+                hasher.try_finalize()?
             }
             None => return Err(Error::IteratorInvariantTrailer),
         };
         let index_hash = crate::index::encode::write_to(
             out,
             sorted_pack_offsets_by_oid,
+            compat_ids.as_ref().map(|(kind, ids)| (*kind, ids.as_slice())),
             &pack_hash,
             version,
+            object_hash,
             &mut root_progress.add_child_with_id("writing index file".into(), ProgressId::IndexBytesWritten.into()),
         )?;
         root_progress.show_throughput_with(
@@ -253,8 +287,14 @@ impl crate::index::File {
     }
 }
 
-fn modify_base(entry: &mut TreeEntry, pack_entry: &crate::data::Entry, decompressed: &[u8], hash: gix_hash::Kind) {
+fn modify_base(
+    entry: &mut TreeEntry,
+    pack_entry: &crate::data::Entry,
+    decompressed: &[u8],
+    hash: gix_hash::Kind,
+) -> Result<(), gix_features::hash::Error> {
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
-    let id = gix_object::compute_hash(hash, object_kind, decompressed);
+    let id = gix_object::compute_hash(hash, object_kind, decompressed)?;
     entry.id = id;
+    Ok(())
 }
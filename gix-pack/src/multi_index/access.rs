@@ -129,6 +129,62 @@ impl File {
         (pack_index, pack_offset)
     }
 
+    /// Return an iterator over all entry indices in pseudo-pack order, that is ordered by pack and offset, with the
+    /// preferred pack sorting first, or `None` if there is no reverse index in this file.
+    ///
+    /// This order is used by multi-pack bitmaps.
+    pub fn iter_pseudo_pack_order(&self) -> Option<impl Iterator<Item = EntryIndex> + '_> {
+        let start = self.reverse_index_ofs?;
+        Some(
+            self.data[start..][..self.num_objects as usize * 4]
+                .chunks_exact(4)
+                .map(crate::read_u32),
+        )
+    }
+
+    /// Return the entry index at `pseudo_pack_pos` in pseudo-pack order, or `None` if it is out of bounds or if there is
+    /// no reverse index in this file.
+    pub fn entry_index_at_pseudo_pack_position(&self, pseudo_pack_pos: u32) -> Option<EntryIndex> {
+        let start = self.reverse_index_ofs?;
+        (pseudo_pack_pos < self.num_objects)
+            .then(|| crate::read_u32(&self.data[start + pseudo_pack_pos as usize * 4..][..4]))
+    }
+
+    /// Return the pack that sorts first in pseudo-pack order, or `None` if there is no reverse index or no object.
+    ///
+    /// This is the pack from which duplicate objects are taken.
+    pub fn preferred_pack(&self) -> Option<PackIndex> {
+        self.entry_index_at_pseudo_pack_position(0)
+            .map(|entry_index| self.pack_id_and_pack_offset_at_index(entry_index).0)
+    }
+
+    /// Return the position in pseudo-pack order of the entry stored in `pack_index` at `pack_offset`, or `None` if there
+    /// is no such entry or no reverse index in this file.
+    pub fn pseudo_pack_position(&self, pack_index: PackIndex, pack_offset: data::Offset) -> Option<u32> {
+        let preferred_pack = self.preferred_pack()?;
+        let key = |pack: PackIndex, offset: data::Offset| (pack != preferred_pack, pack, offset);
+        let needle = key(pack_index, pack_offset);
+        let (mut lo, mut hi) = (0, self.num_objects);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let entry_index = self.entry_index_at_pseudo_pack_position(mid).expect("in bounds");
+            let (pack, offset) = self.pack_id_and_pack_offset_at_index(entry_index);
+            match key(pack, offset).cmp(&needle) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    /// Return the entry index of the object stored in `pack_index` at `pack_offset`, or `None` if there is no such entry
+    /// or no reverse index in this file.
+    pub fn lookup_by_pack_offset(&self, pack_index: PackIndex, pack_offset: data::Offset) -> Option<EntryIndex> {
+        self.pseudo_pack_position(pack_index, pack_offset)
+            .and_then(|pos| self.entry_index_at_pseudo_pack_position(pos))
+    }
+
     /// Return an iterator over all entries within this file.
     pub fn iter(&self) -> impl Iterator<Item = Entry> + '_ {
         (0..self.num_objects).map(move |idx| {
@@ -272,3 +272,30 @@ pub mod large_offsets {
         8 * large_offsets as u64
     }
 }
+
+/// Information about the reverse index, which orders objects as they appear in the packs.
+pub mod reverse_index {
+    use std::ops::Range;
+
+    use crate::multi_index::EntryIndex;
+
+    /// The id uniquely identifying the reverse index, listing entry indices in pseudo-pack order.
+    pub const ID: gix_chunk::Id = *b"RIDX";
+
+    /// Returns true if the `offset` range seems to match the size required for `num_objects`.
+    pub fn is_valid(offset: &Range<usize>, num_objects: u32) -> bool {
+        offset.end - offset.start == num_objects as usize * 4
+    }
+
+    /// Return the amount of bytes needed to store the pseudo-pack order of `entries`.
+    pub fn storage_size(entries: usize) -> u64 {
+        (entries * 4) as u64
+    }
+
+    pub(crate) fn write(pseudo_pack_order: &[EntryIndex], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for entry_index in pseudo_pack_order {
+            out.write_all(&entry_index.to_be_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -308,6 +308,7 @@ impl File {
                                 kind,
                             },
                             Interrupted => Interrupted,
+                            Hasher(err) => Hasher(err),
                         }
                     })?;
                 pack_traverse_statistics.push(pack_traverse_outcome);
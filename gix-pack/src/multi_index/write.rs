@@ -19,6 +19,8 @@ mod error {
         Interrupted,
         #[error(transparent)]
         OpenIndex(#[from] crate::index::init::Error),
+        #[error(transparent)]
+        Hasher(#[from] gix_features::hash::Error),
     }
 }
 pub use error::Error;
@@ -36,6 +38,12 @@ pub(crate) struct Entry {
 pub struct Options {
     /// The kind of hash to use for objects and to expect in the input files.
     pub object_hash: gix_hash::Kind,
+    /// If `true`, write the reverse index chunk which lists all objects in pseudo-pack order, as needed for
+    /// multi-pack bitmaps.
+    ///
+    /// Like git, the pack whose index is oldest is chosen as preferred pack, which sorts first and from which
+    /// duplicate objects are taken.
+    pub reverse_index: bool,
 }
 
 /// The result of [`multi_index::File::write_from_index_paths()`].
@@ -81,7 +89,10 @@ impl multi_index::File {
         out: &mut dyn std::io::Write,
         progress: &mut dyn DynNestedProgress,
         should_interrupt: &AtomicBool,
-        Options { object_hash }: Options,
+        Options {
+            object_hash,
+            reverse_index,
+        }: Options,
     ) -> Result<Outcome, Error> {
         let out = gix_features::hash::Write::new(out, object_hash);
         let (index_paths_sorted, index_filenames_sorted) = {
@@ -93,6 +104,7 @@ impl multi_index::File {
             (index_paths, file_names)
         };
 
+        let mut preferred_pack = None::<(SystemTime, u32)>;
         let entries = {
             let mut entries = Vec::new();
             let start = Instant::now();
@@ -109,6 +121,12 @@ impl multi_index::File {
                     .and_then(|m| m.modified())
                     .unwrap_or(SystemTime::UNIX_EPOCH);
                 let index = crate::index::File::at(index, object_hash)?;
+                if reverse_index
+                    && index.num_objects() != 0
+                    && preferred_pack.map_or(true, |(oldest_mtime, _)| mtime < oldest_mtime)
+                {
+                    preferred_pack = Some((mtime, index_id as u32));
+                }
 
                 entries.reserve(index.num_objects() as usize);
                 entries.extend(index.iter().map(|e| Entry {
@@ -127,8 +145,10 @@ impl multi_index::File {
             let start = Instant::now();
             progress.set_name("Deduplicate".into());
             progress.init(Some(entries.len()), gix_features::progress::count("entries"));
+            let preferred_pack = preferred_pack.map(|(_, pack_index)| pack_index);
             entries.sort_by(|l, r| {
                 l.id.cmp(&r.id)
+                    .then_with(|| (Some(l.pack_index) != preferred_pack).cmp(&(Some(r.pack_index) != preferred_pack)))
                     .then_with(|| l.index_mtime.cmp(&r.index_mtime).reverse())
                     .then_with(|| l.pack_index.cmp(&r.pack_index))
             });
@@ -164,6 +184,21 @@ impl multi_index::File {
             );
         }
 
+        let pseudo_pack_order = preferred_pack.map(|(_, preferred_pack)| {
+            let mut order: Vec<_> = (0..entries.len() as u32).collect();
+            order.sort_by_key(|idx| {
+                let entry = &entries[*idx as usize];
+                (entry.pack_index != preferred_pack, entry.pack_index, entry.pack_offset)
+            });
+            order
+        });
+        if let Some(order) = &pseudo_pack_order {
+            cf.plan_chunk(
+                multi_index::chunk::reverse_index::ID,
+                multi_index::chunk::reverse_index::storage_size(order.len()),
+            );
+        }
+
         let mut write_progress =
             progress.add_child_with_id("Writing multi-index".into(), ProgressId::BytesWritten.into());
         let write_start = Instant::now();
@@ -203,6 +238,10 @@ impl multi_index::File {
                         num_large_offsets.expect("available if planned"),
                         &mut chunk_write,
                     )?,
+                    multi_index::chunk::reverse_index::ID => multi_index::chunk::reverse_index::write(
+                        pseudo_pack_order.as_deref().expect("available if planned"),
+                        &mut chunk_write,
+                    )?,
                     unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
                 }
                 progress.inc();
@@ -213,7 +252,7 @@ impl multi_index::File {
         }
 
         // write trailing checksum
-        let multi_index_checksum: gix_hash::ObjectId = out.inner.hash.digest().into();
+        let multi_index_checksum = out.inner.hash.try_finalize()?;
         out.inner.inner.write_all(multi_index_checksum.as_slice())?;
         out.progress.show_throughput(write_start);
 
@@ -15,6 +15,8 @@ pub mod checksum {
             expected: gix_hash::ObjectId,
             actual: gix_hash::ObjectId,
         },
+        #[error(transparent)]
+        Hasher(#[from] gix_features::hash::Error),
     }
 }
 
@@ -52,7 +54,7 @@ pub fn checksum_on_disk_or_mmap(
             hasher.update(&data[..data_len_without_trailer]);
             progress.inc_by(data_len_without_trailer);
             progress.show_throughput(start);
-            gix_hash::ObjectId::from(hasher.digest())
+            hasher.try_finalize()?
         }
     };
 
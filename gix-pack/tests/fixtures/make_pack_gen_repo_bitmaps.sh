@@ -0,0 +1,71 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_files() {
+  local base_dir=${1:?directory to write them into}
+  local num_files=${2:?amount of files to write}
+  local nonce=${3:?something to make files more unique}
+
+  mkdir -p "$base_dir"
+  for file_id in $(seq -w "$num_files"); do
+    seq "$file_id" > "$base_dir/$file_id"
+    echo "$nonce" >> "$base_dir/$file_id"
+  done
+}
+
+function make_history() {
+  local from_round=${1:?first round}
+  local to_round=${2:?last round}
+  local dirs=(. a b c a/a a/b a/c a/a/a)
+
+  for round in $(seq "$from_round" "$to_round"); do
+    dir_index=$(( round % ${#dirs[@]} ))
+    num_files=$(( (round + 1) * 6 ))
+    write_files "${dirs[$dir_index]}" $num_files "$round"
+    git add .
+    git commit -qm "$round $num_files"
+  done
+}
+
+git init -q single-pack
+(cd single-pack
+  git checkout -q -b main
+  make_history 1 10
+  git tag -m "a tag object" v1
+  git checkout -q -b other HEAD~3
+  make_history 11 13
+  git checkout -q main
+  git merge -q --no-edit other
+  git -c pack.writeBitmapHashCache=true -c pack.writeBitmapLookupTable=true repack -adbq
+)
+
+git clone -q --no-local single-pack single-pack-no-extensions
+(cd single-pack-no-extensions
+  git -c pack.writeBitmapHashCache=false -c pack.writeBitmapLookupTable=false repack -adbq
+)
+
+git clone -q --no-local single-pack multi-pack
+(cd multi-pack
+  git repack -adq
+  make_history 14 16
+  git repack -dq
+  git -c pack.writeBitmapHashCache=true multi-pack-index write --bitmap
+)
+
+git clone -q --no-local single-pack with-loose-objects
+(cd with-loose-objects
+  git -c pack.writeBitmapHashCache=true repack -adbq
+  make_history 17 18
+)
+
+git init -q names-with-whitespace
+(cd names-with-whitespace
+  for name in plain "with space" $'with\ttab' $'with\nnewline' $'with\vvertical-tab' $'with\fform-feed' $'with\rcarriage-return'; do
+    echo "$name" > "$name"
+  done
+  mkdir $'dir\vwith\fwhitespace'
+  echo nested > $'dir\vwith\fwhitespace/file name'
+  git add .
+  git commit -qm "files with whitespace in their names"
+  git -c pack.writeBitmapHashCache=true repack -adbq
+)
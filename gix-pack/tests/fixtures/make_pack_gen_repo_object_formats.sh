@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function make_history() {
+  local dirs=(. a b a/a)
+
+  for round in $(seq 1 6); do
+    dir=${dirs[$(( round % ${#dirs[@]} ))]}
+    mkdir -p "$dir"
+    for file_id in $(seq -w $(( round * 3 ))); do
+      { seq "$file_id"; echo "$round"; } > "$dir/$file_id"
+    done
+    git add .
+    git commit -qm "$round"
+  done
+  git tag -am "annotated" v1
+}
+
+# The same history in repositories of both object formats, so the names of all objects correspond to each other.
+for object_format in sha1 sha256; do
+  git init -q --object-format="$object_format" "$object_format"
+  (cd "$object_format"
+    git checkout -q -b main
+    make_history
+    git repack -adq
+  )
+done
+
+# An index of version 1 of the same pack as written by git, for comparison.
+git index-pack --index-version=1 -o sha1-v1.idx sha1/.git/objects/pack/pack-*.pack
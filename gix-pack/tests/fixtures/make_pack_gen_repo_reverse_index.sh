@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function make_history() {
+  local from_round=${1:?first round}
+  local to_round=${2:?last round}
+  local dirs=(. a b a/a)
+
+  for round in $(seq "$from_round" "$to_round"); do
+    dir=${dirs[$(( round % ${#dirs[@]} ))]}
+    mkdir -p "$dir"
+    for file_id in $(seq -w $(( round * 4 ))); do
+      { seq "$file_id"; echo "$round"; } > "$dir/$file_id"
+    done
+    git add .
+    git commit -qm "$round"
+  done
+}
+
+git init -q single-pack
+(cd single-pack
+  git checkout -q -b main
+  make_history 1 8
+  git -c pack.writeReverseIndex=true repack -adq
+)
+
+git clone -q --no-local single-pack multi-pack
+(cd multi-pack
+  git repack -adq
+  # git prefers the oldest pack for the pseudo-pack order when writing bitmaps, so make that deterministic.
+  touch -d '2000-01-01' .git/objects/pack/pack-*
+  make_history 9 12
+  git repack -dq
+  git multi-pack-index write --bitmap
+)
@@ -0,0 +1,255 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_features::progress;
+use gix_object::FindHeader;
+use gix_pack::bitmap;
+
+fn repo(name: &str) -> PathBuf {
+    crate::scripted_fixture_read_only("make_pack_gen_repo_bitmaps.sh")
+        .expect("test fixture exists")
+        .join(name)
+        .join(".git/objects")
+}
+
+fn pack_dir_file(objects_dir: &std::path::Path, extension: &str) -> PathBuf {
+    let mut paths: Vec<_> = std::fs::read_dir(objects_dir.join("pack"))
+        .expect("pack directory exists")
+        .map(|entry| entry.expect("readable").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == extension))
+        .collect();
+    assert_eq!(paths.len(), 1, "expecting a single file with extension '{extension}'");
+    paths.pop().expect("one")
+}
+
+fn pack_index(objects_dir: &std::path::Path) -> gix_pack::index::File {
+    gix_pack::index::File::at(pack_dir_file(objects_dir, "idx"), gix_hash::Kind::Sha1).expect("valid index")
+}
+
+fn multi_index(objects_dir: &std::path::Path) -> gix_pack::multi_index::File {
+    gix_pack::multi_index::File::at(objects_dir.join("pack/multi-pack-index")).expect("valid multi-pack index")
+}
+
+/// Use all commits and tags as tips, which gives every commit a bitmap as there are so few of them.
+fn tips(index: bitmap::Index<'_>, odb: &impl FindHeader) -> Vec<gix_hash::ObjectId> {
+    (0..index.num_objects())
+        .map(|index_pos| index.oid_at_index(index_pos).to_owned())
+        .filter(|id| {
+            let kind = odb.try_header(id).expect("no error").expect("present").kind;
+            matches!(kind, gix_object::Kind::Commit | gix_object::Kind::Tag)
+        })
+        .collect()
+}
+
+fn assert_computed_bitmaps_match(
+    index: bitmap::Index<'_>,
+    odb: &(impl gix_object::Find + FindHeader),
+) -> crate::Result {
+    let expected = bitmap::File::at(index.bitmap_path(), gix_hash::Kind::Sha1)?;
+    let actual = bitmap::File::compute(
+        index,
+        tips(index, odb),
+        odb,
+        &progress::Discard,
+        &AtomicBool::new(false),
+        Default::default(),
+    )?;
+
+    assert_eq!(actual.checksum(), expected.checksum());
+    for kind in [
+        gix_object::Kind::Commit,
+        gix_object::Kind::Tree,
+        gix_object::Kind::Blob,
+        gix_object::Kind::Tag,
+    ] {
+        assert_eq!(actual.type_bits(kind), expected.type_bits(kind), "{kind}");
+    }
+    assert_eq!(
+        actual.entries().len(),
+        actual.type_bits(gix_object::Kind::Commit).count_ones(),
+        "all commits are selected in small repositories"
+    );
+    for (expected_entry_idx, entry) in expected.entries().iter().enumerate() {
+        let actual_entry_idx = actual
+            .entry_index_by_index_position(entry.index_position)
+            .expect("every commit has a bitmap");
+        assert_eq!(
+            actual.bits(actual_entry_idx),
+            expected.bits(expected_entry_idx),
+            "the same objects are reachable from {}",
+            index.oid_at_index(entry.index_position)
+        );
+    }
+    assert!(actual.has_name_hash_cache());
+
+    let mut buf = Vec::new();
+    actual.write_to(&mut buf)?;
+    assert_eq!(
+        bitmap::File::from_bytes(&buf, gix_hash::Kind::Sha1)?,
+        actual,
+        "computed bitmaps can be read back"
+    );
+    Ok(())
+}
+
+mod read_and_write {
+    use crate::pack::bitmap::{multi_index, pack_index, repo};
+    use gix_pack::bitmap;
+
+    #[test]
+    fn round_trips_byte_for_byte() -> crate::Result {
+        for (name, multi) in [
+            ("single-pack", false),
+            ("single-pack-no-extensions", false),
+            ("multi-pack", true),
+        ] {
+            let objects_dir = repo(name);
+            let (pack_index, multi_index);
+            let index = if multi {
+                multi_index = self::multi_index(&objects_dir);
+                bitmap::Index::Multi {
+                    index: &multi_index,
+                    preferred_pack: None,
+                }
+            } else {
+                pack_index = self::pack_index(&objects_dir);
+                bitmap::Index::Pack(&pack_index)
+            };
+            let path = index.bitmap_path();
+            let expected = std::fs::read(&path)?;
+            let file = bitmap::File::at(&path, gix_hash::Kind::Sha1)?;
+            assert_eq!(
+                file.checksum(),
+                index.checksum(),
+                "{name}: the bitmap belongs to its index"
+            );
+
+            let mut actual = Vec::new();
+            let checksum = file.write_to(&mut actual)?;
+            assert_eq!(actual, expected, "{name}: we write exactly what git wrote");
+            assert_eq!(checksum.as_slice(), &expected[expected.len() - 20..]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extensions_are_detected() -> crate::Result {
+        let objects_dir = repo("single-pack");
+        let index = pack_index(&objects_dir);
+        let file = bitmap::File::at(bitmap::Index::Pack(&index).bitmap_path(), gix_hash::Kind::Sha1)?;
+        assert!(file.has_name_hash_cache());
+        assert!(file.has_lookup_table());
+        assert_eq!(file.version(), bitmap::Version::V1);
+        assert!(
+            (0..index.num_objects()).any(|index_pos| file.name_hash(index_pos).map_or(false, |hash| hash != 0)),
+            "there are named trees and blobs"
+        );
+
+        let objects_dir = repo("single-pack-no-extensions");
+        let index = pack_index(&objects_dir);
+        let file = bitmap::File::at(bitmap::Index::Pack(&index).bitmap_path(), gix_hash::Kind::Sha1)?;
+        assert!(!file.has_name_hash_cache());
+        assert!(!file.has_lookup_table());
+        assert_eq!(file.name_hash(0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn bits_are_closed_under_reachability() -> crate::Result {
+        let objects_dir = repo("single-pack");
+        let index = pack_index(&objects_dir);
+        let file = bitmap::File::at(bitmap::Index::Pack(&index).bitmap_path(), gix_hash::Kind::Sha1)?;
+        let commits = file.type_bits(gix_object::Kind::Commit);
+        assert_eq!(file.entries().len(), 14, "all 13 commits and the merge have a bitmap");
+        let max_commits = (0..file.entries().len())
+            .map(|entry_idx| {
+                let mut bits = file.bits(entry_idx);
+                bits.intersect_with(&commits);
+                bits.count_ones()
+            })
+            .max()
+            .expect("there are entries");
+        assert_eq!(max_commits, 14, "the merge commit reaches all commits");
+        assert_eq!(file.type_bits(gix_object::Kind::Tag).count_ones(), 1);
+        Ok(())
+    }
+}
+
+mod compute {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_features::progress;
+    use gix_pack::bitmap;
+
+    use crate::pack::bitmap::{assert_computed_bitmaps_match, multi_index, pack_index, repo, tips};
+
+    #[test]
+    fn single_pack_matches_git() -> crate::Result {
+        let objects_dir = repo("single-pack");
+        let odb = gix_odb::at(&objects_dir)?;
+        let index = pack_index(&objects_dir);
+        assert_computed_bitmaps_match(bitmap::Index::Pack(&index), &odb)
+    }
+
+    #[test]
+    fn multi_pack_matches_git() -> crate::Result {
+        let objects_dir = repo("multi-pack");
+        let odb = gix_odb::at(&objects_dir)?;
+        let index = multi_index(&objects_dir);
+        assert!(
+            index.iter_pseudo_pack_order().is_some(),
+            "git writes a reverse index along with bitmaps"
+        );
+        assert_computed_bitmaps_match(
+            bitmap::Index::Multi {
+                index: &index,
+                preferred_pack: None,
+            },
+            &odb,
+        )
+    }
+
+    #[test]
+    fn name_hashes_match_git_for_paths_with_whitespace() -> crate::Result {
+        let objects_dir = repo("names-with-whitespace");
+        let odb = gix_odb::at(&objects_dir)?;
+        let index = pack_index(&objects_dir);
+        let index = bitmap::Index::Pack(&index);
+        let expected = bitmap::File::at(index.bitmap_path(), gix_hash::Kind::Sha1)?;
+        let actual = bitmap::File::compute(
+            index,
+            tips(index, &odb),
+            &odb,
+            &progress::Discard,
+            &AtomicBool::new(false),
+            Default::default(),
+        )?;
+
+        assert!(expected.has_name_hash_cache());
+        for index_pos in 0..index.num_objects() {
+            assert_eq!(
+                actual.name_hash(index_pos),
+                expected.name_hash(index_pos),
+                "{}: only space, tab, newline and carriage return are ignored",
+                index.oid_at_index(index_pos)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pack_order_of_multi_pack_index_without_reverse_index_needs_preferred_pack() {
+        let index = gix_pack::multi_index::File::at(
+            crate::scripted_fixture_read_only("make_pack_gen_repo_multi_index.sh")
+                .expect("test fixture exists")
+                .join(".git/objects/pack/multi-pack-index"),
+        )
+        .expect("valid");
+        assert!(bitmap::PackOrder::from_multi_index(&index, None).is_none());
+        let order = bitmap::PackOrder::from_multi_index(&index, Some(0)).expect("preferred pack is set");
+        assert_eq!(order.len(), index.num_objects() as usize);
+        for bit_pos in 0..order.len() {
+            let index_pos = order.index_position(bit_pos).expect("in bounds");
+            assert_eq!(order.bit_position(index_pos), Some(bit_pos));
+        }
+    }
+}
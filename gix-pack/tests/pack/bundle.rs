@@ -97,9 +97,10 @@ mod write_to_directory {
     use crate::{
         fixture_path,
         pack::{SMALL_PACK, SMALL_PACK_INDEX},
+        scripted_fixture_read_only,
     };
 
-    fn expected_outcome() -> Result<pack::bundle::write::Outcome, Box<dyn std::error::Error>> {
+    fn expected_outcome() -> crate::Result<pack::bundle::write::Outcome> {
         Ok(pack::bundle::write::Outcome {
             index: pack::index::write::Outcome {
                 index_version: pack::index::Version::V2,
@@ -116,8 +117,8 @@ mod write_to_directory {
     }
 
     #[test]
-    fn without_providing_one() -> Result<(), Box<dyn std::error::Error>> {
-        let res = write_pack(None::<&Path>, SMALL_PACK)?;
+    fn without_providing_one() -> crate::Result {
+        let res = write_pack(None::<&Path>, &fixture_path(SMALL_PACK), pack::index::Version::V2)?;
         assert_eq!(res, expected_outcome()?);
         assert_eq!(
             res.index.index_hash,
@@ -128,17 +129,17 @@ mod write_to_directory {
     }
 
     #[test]
-    fn given_a_directory() -> Result<(), Box<dyn std::error::Error>> {
+    fn given_a_directory() -> crate::Result {
         let dir = TempDir::new()?;
-        let mut res = write_pack(Some(&dir), SMALL_PACK)?;
+        let mut res = write_pack(Some(&dir), &fixture_path(SMALL_PACK), pack::index::Version::V2)?;
         let (index_path, data_path, keep_path) = (res.index_path.take(), res.data_path.take(), res.keep_path.take());
         assert_eq!(res, expected_outcome()?);
         let mut sorted_entries = fs::read_dir(&dir)?.filter_map(Result::ok).collect::<Vec<_>>();
         sorted_entries.sort_by_key(fs::DirEntry::file_name);
         assert_eq!(
             sorted_entries.len(),
-            3,
-            "we want a pack and the corresponding index and the keep file"
+            4,
+            "we want a pack and the corresponding index and reverse index, along with the keep file"
         );
 
         let pack_hash = res.index.data_hash.to_hex();
@@ -148,21 +149,134 @@ mod write_to_directory {
         assert_eq!(Some(sorted_entries[1].path()), keep_path);
         assert_eq!(file_name(&sorted_entries[2]), format!("pack-{pack_hash}.pack"));
         assert_eq!(Some(sorted_entries[2].path()), data_path);
+        assert_eq!(file_name(&sorted_entries[3]), format!("pack-{pack_hash}.rev"));
 
         res.index_path = index_path;
-        assert!(res.to_bundle().transpose()?.is_some());
+        let bundle = res.to_bundle().transpose()?.expect("index and pack were written");
+        let mut expected_rev = Vec::new();
+        pack::index::reverse::File::write_from_index(&bundle.index, &mut expected_rev)?;
+        assert_eq!(
+            fs::read(sorted_entries[3].path())?,
+            expected_rev,
+            "the reverse index is the one we would write for the index"
+        );
+        assert_eq!(
+            pack::index::reverse::File::at_index(&bundle.index).map(|rev| rev.num_objects()),
+            Some(bundle.index.num_objects()),
+            "it is found for the index it belongs to"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn index_version_1() -> crate::Result {
+        let root = scripted_fixture_read_only("make_pack_gen_repo_object_formats.sh")?;
+        let git_index = git_written_index(&root.join("sha1"), gix_hash::Kind::Sha1)?;
+        let dir = TempDir::new()?;
+        let res = write_pack(
+            Some(&dir),
+            &git_index.path().with_extension("pack"),
+            pack::index::Version::V1,
+        )?;
+        assert_eq!(res.index.index_version, pack::index::Version::V1);
+        assert_eq!(
+            fs::read(res.index_path.as_ref().expect("written to directory"))?,
+            fs::read(root.join("sha1-v1.idx"))?,
+            "we write a bit-exact version of the index as written by git"
+        );
+        let bundle = res.to_bundle().expect("written to directory")?;
+        assert_eq!(bundle.index.version(), pack::index::Version::V1);
+        assert_eq!(
+            bundle.index.iter().map(|e| (e.oid, e.pack_offset)).collect::<Vec<_>>(),
+            git_index.iter().map(|e| (e.oid, e.pack_offset)).collect::<Vec<_>>()
+        );
         Ok(())
     }
 
+    #[test]
+    fn index_version_3_contains_object_names_of_both_hashes() -> crate::Result {
+        static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
+        let root = scripted_fixture_read_only("make_pack_gen_repo_object_formats.sh")?;
+        for ((object_hash, repo), (compat_hash, compat_repo)) in [
+            ((gix_hash::Kind::Sha1, "sha1"), (gix_hash::Kind::Sha256, "sha256")),
+            ((gix_hash::Kind::Sha256, "sha256"), (gix_hash::Kind::Sha1, "sha1")),
+        ] {
+            let git_index = git_written_index(&root.join(repo), object_hash)?;
+            let git_compat_index = git_written_index(&root.join(compat_repo), compat_hash)?;
+
+            let dir = TempDir::new()?;
+            let res = pack::Bundle::write_to_directory_eagerly(
+                Box::new(fs::File::open(git_index.path().with_extension("pack"))?),
+                None,
+                Some(&dir),
+                &mut progress::Discard,
+                &SHOULD_INTERRUPT,
+                None::<gix_object::find::Never>,
+                pack::bundle::write::Options {
+                    index_version: pack::index::Version::V3,
+                    object_hash,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(res.index.index_version, pack::index::Version::V3);
+            assert_eq!(res.index.num_objects, git_index.num_objects());
+
+            let index = pack::index::File::at(res.index_path.expect("written to directory"), object_hash)?;
+            assert_eq!(index.version(), pack::index::Version::V3);
+            assert_eq!(index.index_checksum(), res.index.index_hash);
+            assert_eq!(
+                index.verify_checksum(&mut progress::Discard, &SHOULD_INTERRUPT)?,
+                res.index.index_hash
+            );
+            assert_eq!(
+                index.iter().collect::<Vec<_>>(),
+                git_index.iter().collect::<Vec<_>>(),
+                "all entries are the same as in the index written by git"
+            );
+            for entry in index.iter() {
+                assert_eq!(
+                    index.lookup(entry.oid).map(|idx| index.oid_at_index(idx)),
+                    Some(entry.oid.as_ref())
+                );
+            }
+
+            let mut compat_ids: Vec<_> = (0..index.num_objects())
+                .map(|idx| {
+                    index
+                        .compat_oid_at_index(idx)
+                        .expect("second object format present")
+                        .to_owned()
+                })
+                .collect();
+            compat_ids.sort();
+            assert_eq!(
+                compat_ids,
+                git_compat_index.iter().map(|e| e.oid).collect::<Vec<_>>(),
+                "the translated names match the ones of the same history in a repository using {compat_hash}"
+            );
+        }
+        Ok(())
+    }
+
+    fn git_written_index(repo: &Path, object_hash: gix_hash::Kind) -> crate::Result<pack::index::File> {
+        let index_path = fs::read_dir(repo.join(".git/objects/pack"))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+            .expect("git wrote a single pack");
+        Ok(pack::index::File::at(index_path, object_hash)?)
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
 
     fn write_pack(
         directory: Option<impl AsRef<Path>>,
-        pack_file: &str,
-    ) -> Result<pack::bundle::write::Outcome, Box<dyn std::error::Error>> {
-        let pack_file = fs::File::open(fixture_path(pack_file))?;
+        pack_file: &Path,
+        index_version: pack::index::Version,
+    ) -> crate::Result<pack::bundle::write::Outcome> {
+        let pack_file = fs::File::open(pack_file)?;
         static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
         pack::Bundle::write_to_directory_eagerly(
             Box::new(pack_file),
@@ -174,10 +288,115 @@ mod write_to_directory {
             pack::bundle::write::Options {
                 thread_limit: None,
                 iteration_mode: pack::data::input::Mode::Verify,
-                index_version: pack::index::Version::V2,
+                index_version,
                 object_hash: gix_hash::Kind::Sha1,
             },
         )
         .map_err(Into::into)
     }
 }
+
+mod write_blob_to_directory {
+    use std::{fs, sync::atomic::AtomicBool};
+
+    use gix_features::progress;
+    use gix_odb::pack;
+    use gix_testtools::tempfile::TempDir;
+
+    fn blob() -> Vec<u8> {
+        (0..100_000u32).flat_map(u32::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn index_is_the_same_as_when_indexing_the_pack() -> crate::Result {
+        static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
+        let data = blob();
+        for index_version in [
+            pack::index::Version::V1,
+            pack::index::Version::V2,
+            pack::index::Version::V3,
+        ] {
+            let dir = TempDir::new()?;
+            let (id, res) = pack::Bundle::write_blob_to_directory(
+                data.len() as u64,
+                &mut data.as_slice(),
+                dir.path(),
+                index_version,
+                gix_hash::Kind::Sha1,
+            )?;
+            assert_eq!(
+                id,
+                gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, &data)?
+            );
+            assert_eq!(res.index.num_objects, 1);
+            assert_eq!(res.keep_path, None, "there is no need to keep packs we create locally");
+            let data_path = res.data_path.clone().expect("written to directory");
+            assert_eq!(
+                data_path.file_name().and_then(|name| name.to_str()),
+                Some(format!("pack-{}.pack", res.index.data_hash.to_hex()).as_str())
+            );
+
+            let bundle = res.to_bundle().expect("written to directory")?;
+            assert_eq!(bundle.index.version(), index_version);
+            assert_eq!(
+                bundle.pack.verify_checksum(&mut progress::Discard, &SHOULD_INTERRUPT)?,
+                res.index.data_hash
+            );
+            let mut buf = Vec::new();
+            let (obj, _location) = bundle
+                .find(&id, &mut buf, &mut Default::default(), &mut pack::cache::Never)?
+                .expect("blob is present");
+            assert_eq!(obj.kind, gix_object::Kind::Blob);
+            assert_eq!(obj.data, data.as_slice());
+            if index_version == pack::index::Version::V3 {
+                assert_eq!(
+                    bundle.index.compat_oid_at_index(0),
+                    Some(gix_object::compute_hash(gix_hash::Kind::Sha256, gix_object::Kind::Blob, &data)?.as_ref()),
+                    "the name of the blob using the second kind of hash is present as well"
+                );
+            }
+
+            let indexed_dir = TempDir::new()?;
+            let indexed = pack::Bundle::write_to_directory_eagerly(
+                Box::new(fs::File::open(&data_path)?),
+                None,
+                Some(&indexed_dir),
+                &mut progress::Discard,
+                &SHOULD_INTERRUPT,
+                None::<gix_object::find::Never>,
+                pack::bundle::write::Options {
+                    index_version,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(indexed.index, res.index, "the outcome is the same…");
+            assert_eq!(
+                fs::read(indexed.index_path.expect("written to directory"))?,
+                fs::read(res.index_path.expect("written to directory"))?,
+                "…and so is the index"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() -> crate::Result {
+        let dir = TempDir::new()?;
+        let data = blob();
+        let err = pack::Bundle::write_blob_to_directory(
+            data.len() as u64 + 1,
+            &mut data.as_slice(),
+            dir.path(),
+            pack::index::Version::default(),
+            gix_hash::Kind::Sha1,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            pack::bundle::write::Error::BlobSizeMismatch { expected, actual }
+                if expected == data.len() as u64 + 1 && actual == data.len() as u64
+        ));
+        assert_eq!(fs::read_dir(&dir)?.count(), 0, "temporary files are removed on error");
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_features::progress;
+use gix_hash::ObjectId;
+use gix_pack::{
+    bitmap,
+    data::output::{count, count::objects::ObjectExpansion},
+};
+
+fn repo(name: &str) -> std::path::PathBuf {
+    crate::scripted_fixture_read_only("make_pack_gen_repo_bitmaps.sh")
+        .expect("test fixture exists")
+        .join(name)
+        .join(".git")
+}
+
+fn rev_parse(git_dir: &Path, ref_name: &str) -> ObjectId {
+    let loose = git_dir.join(ref_name);
+    if loose.is_file() {
+        let hex = std::fs::read_to_string(loose).expect("readable");
+        return crate::hex_to_id(hex.trim());
+    }
+    let packed = std::fs::read_to_string(git_dir.join("packed-refs")).expect("ref is packed");
+    packed
+        .lines()
+        .find_map(|line| {
+            let (hex, name) = line.split_once(' ')?;
+            (name == ref_name).then(|| crate::hex_to_id(hex))
+        })
+        .expect("ref exists")
+}
+
+fn count(
+    git_dir: &Path,
+    wants: Vec<ObjectId>,
+    haves: &[ObjectId],
+    bitmaps: Option<&bitmap::Bundle>,
+) -> crate::Result<(Vec<ObjectId>, count::objects::Outcome)> {
+    let mut db = gix_odb::at(git_dir.join("objects"))?.into_arc()?;
+    db.prevent_pack_unload();
+    let (counts, outcome) = count::objects(
+        db,
+        Box::new(wants.into_iter().map(Ok)),
+        &progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: ObjectExpansion::AllReachable,
+            bitmaps,
+            haves,
+            ..Default::default()
+        },
+    )?;
+    let mut ids: Vec<_> = counts.into_iter().map(|count| count.id).collect();
+    ids.sort();
+    assert_eq!(
+        ids.windows(2).filter(|ids| ids[0] == ids[1]).count(),
+        0,
+        "no object is counted twice"
+    );
+    Ok((ids, outcome))
+}
+
+fn bundle(git_dir: &Path) -> crate::Result<bitmap::Bundle> {
+    Ok(bitmap::Bundle::at(git_dir.join("objects/pack"), gix_hash::Kind::Sha1)?.expect("bitmap present"))
+}
+
+#[test]
+fn bitmaps_yield_the_same_objects_as_traversal_with_fewer_decoded_objects() -> crate::Result {
+    for name in ["single-pack", "multi-pack", "with-loose-objects"] {
+        let git_dir = repo(name);
+        let bitmaps = bundle(&git_dir)?;
+        let main = rev_parse(&git_dir, "refs/heads/main");
+
+        let (expected, expected_outcome) = count(&git_dir, vec![main], &[], None)?;
+        let (actual, actual_outcome) = count(&git_dir, vec![main], &[], Some(&bitmaps))?;
+        assert_eq!(actual, expected, "{name}");
+        assert_eq!(actual_outcome.total_objects, expected_outcome.total_objects);
+        assert_eq!(actual_outcome.input_objects, 1);
+        assert!(
+            actual_outcome.decoded_objects < expected_outcome.decoded_objects,
+            "{name}: bitmaps avoid decoding commits and trees"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn objects_reachable_from_haves_are_excluded() -> crate::Result {
+    for name in ["single-pack", "with-loose-objects"] {
+        let git_dir = repo(name);
+        let bitmaps = bundle(&git_dir)?;
+        let main = rev_parse(&git_dir, "refs/heads/main");
+        let other = rev_parse(
+            &git_dir,
+            if name == "single-pack" {
+                "refs/heads/other"
+            } else {
+                "refs/remotes/origin/other"
+            },
+        );
+
+        let (all, _) = count(&git_dir, vec![main], &[], None)?;
+        let (expected, _) = count(&git_dir, vec![main], &[other], None)?;
+        let (actual, _) = count(&git_dir, vec![main], &[other], Some(&bitmaps))?;
+        assert_eq!(actual, expected, "{name}");
+        assert!(
+            actual.len() < all.len(),
+            "{name}: some objects are known to the other side"
+        );
+        assert!(actual.binary_search(&main).is_ok());
+        assert!(actual.binary_search(&other).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn tags_are_peeled_and_missing_haves_are_ignored() -> crate::Result {
+    let git_dir = repo("single-pack");
+    let bitmaps = bundle(&git_dir)?;
+    assert!(
+        matches!(bitmaps.index(), bitmap::Index::Pack(_)),
+        "the bitmap of the only pack is used"
+    );
+    let tag = rev_parse(&git_dir, "refs/tags/v1");
+    let missing = gix_hash::Kind::Sha1.null();
+
+    let (expected, _) = count(&git_dir, vec![tag], &[missing], None)?;
+    let (actual, outcome) = count(&git_dir, vec![tag], &[missing], Some(&bitmaps))?;
+    assert_eq!(actual, expected);
+    assert!(actual.binary_search(&tag).is_ok(), "the tag object itself is included");
+    assert_eq!(outcome.expanded_objects, actual.len() - 1);
+    Ok(())
+}
+
+#[test]
+fn multi_pack_index_bitmaps_are_preferred() -> crate::Result {
+    let bitmaps = bundle(&repo("multi-pack"))?;
+    assert!(matches!(bitmaps.index(), bitmap::Index::Multi { .. }));
+    assert!(bitmap::Bundle::at(
+        crate::scripted_fixture_read_only("make_pack_gen_repo.sh")?.join(".git/objects/pack"),
+        gix_hash::Kind::Sha1
+    )?
+    .is_none());
+    Ok(())
+}
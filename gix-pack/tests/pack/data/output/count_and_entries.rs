@@ -121,6 +121,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 16,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3"),
                 None,
@@ -154,6 +155,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 103,
                     ref_delta_objects: 74,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("25114bd8820b393c402cd53ad8ec7f6a84bb0633"),
                 Some(hex_to_id("29ab9797aff1ca826afb699680356695d19c5acb")),
@@ -187,6 +189,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 29,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("d83d42128e40957c5174920189a0390b5a70f446"),
                 None,
@@ -208,6 +211,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -229,6 +233,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -320,6 +325,222 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+mod delta_compression {
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    use gix_features::{
+        parallel::{reduce::Finalize, InOrderIter},
+        progress,
+    };
+    use gix_pack::data::output::{self, count, entry::iter_from_counts};
+
+    use super::write_and_verify;
+    use crate::pack::{
+        data::output::{db, DbKind},
+        hex_to_id,
+    };
+
+    fn entries_and_outcome(
+        db: gix_odb::HandleArc,
+        tips: Vec<gix_hash::ObjectId>,
+        options: iter_from_counts::Options,
+    ) -> crate::Result<(Vec<output::Entry>, iter_from_counts::Outcome)> {
+        let (counts, _stats) = output::count::objects(
+            db.clone(),
+            Box::new(tips.into_iter().map(Ok)),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+                ..Default::default()
+            },
+        )?;
+        let mut entries_iter = output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress::Discard),
+            iter_from_counts::Options {
+                mode: iter_from_counts::Mode::DeltaCompression,
+                ..options
+            },
+        );
+        let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok((entries, entries_iter.finalize()?))
+    }
+
+    fn all_commits(db: &gix_odb::HandleArc, head: gix_hash::ObjectId) -> Vec<gix_hash::ObjectId> {
+        gix_traverse::commit::Simple::new(Some(head), db.clone())
+            .map(|c| c.expect("valid commit").id)
+            .collect()
+    }
+
+    fn num_deltas(entries: &[output::Entry]) -> usize {
+        entries
+            .iter()
+            .filter(|e| matches!(e.kind, output::entry::Kind::DeltaRef { .. }))
+            .count()
+    }
+
+    fn loose_db() -> crate::Result<(gix_odb::HandleArc, gix_hash::ObjectId, u64)> {
+        let dir = crate::scripted_fixture_read_only("make_pack_gen_repo_loose.sh")?;
+        let git_pack_size = std::fs::read_to_string(dir.join("git-pack-size"))?.trim().parse()?;
+        let git_dir = dir.join(".git");
+        let head = gix_hash::ObjectId::from_hex(
+            std::fs::read_to_string(git_dir.join("refs/heads/main"))?
+                .trim()
+                .as_bytes(),
+        )?;
+        let mut db = gix_odb::at(git_dir.join("objects"))?.into_arc()?;
+        db.prevent_pack_unload();
+        Ok((db, head, git_pack_size))
+    }
+
+    #[test]
+    fn loose_objects_are_compressed_about_as_well_as_by_git() -> crate::Result {
+        let (db, head, git_pack_size) = loose_db()?;
+        let (entries, outcome) = entries_and_outcome(db.clone(), all_commits(&db, head), Default::default())?;
+        assert_eq!(outcome.missing_objects, 0);
+        assert_eq!(outcome.objects_copied_from_pack, 0, "there are no packs");
+        assert_eq!(
+            outcome.delta_compressed_objects,
+            num_deltas(&entries),
+            "all deltas are new"
+        );
+        assert!(
+            outcome.delta_compressed_objects > entries.len() / 2,
+            "most objects are similar to others: {outcome:?}"
+        );
+
+        let pack_size = write_and_verify(
+            db.clone(),
+            entries,
+            hex_to_id("0000000000000000000000000000000000000000"),
+            None,
+        )?;
+        assert!(
+            pack_size * 10 <= git_pack_size * 12,
+            "our pack of {pack_size} bytes is about as small as the one of git with {git_pack_size} bytes"
+        );
+
+        let (entries, outcome) = entries_and_outcome(
+            db.clone(),
+            all_commits(&db, head),
+            iter_from_counts::Options {
+                window: 0,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(num_deltas(&entries), 0, "without window there is no delta search");
+        assert_eq!(outcome.delta_compressed_objects, 0);
+
+        let (entries, outcome) = entries_and_outcome(
+            db.clone(),
+            all_commits(&db, head),
+            iter_from_counts::Options {
+                big_file_threshold: 0,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            num_deltas(&entries),
+            0,
+            "objects larger than the big file threshold aren't delta-compressed"
+        );
+        assert_eq!(outcome.delta_compressed_objects, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn delta_chains_are_limited_in_depth() -> crate::Result {
+        let (db, head, _) = loose_db()?;
+        for depth in [1, 3] {
+            let (entries, _outcome) = entries_and_outcome(
+                db.clone(),
+                all_commits(&db, head),
+                iter_from_counts::Options {
+                    depth,
+                    ..Default::default()
+                },
+            )?;
+            let mut depths = vec![0; entries.len()];
+            for (index, entry) in entries.iter().enumerate() {
+                if let output::entry::Kind::DeltaRef { object_index } = entry.kind {
+                    assert!(object_index < index, "bases are written before their deltas");
+                    depths[index] = depths[object_index] + 1;
+                }
+            }
+            assert_eq!(depths.iter().max().copied(), Some(depth));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn islands_restrict_delta_bases() -> crate::Result {
+        let (db, head, _) = loose_db()?;
+        let (counts, _stats) = output::count::objects(
+            db.clone(),
+            Box::new(all_commits(&db, head).into_iter().map(Ok)),
+            &progress::Discard,
+            &AtomicBool::new(false),
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+                ..Default::default()
+            },
+        )?;
+        let islands = iter_from_counts::Islands {
+            marks: counts
+                .iter()
+                .enumerate()
+                .map(|(index, count)| (count.id, 1 << (index % 2)))
+                .collect(),
+        };
+        let (entries, outcome) = entries_and_outcome(
+            db.clone(),
+            all_commits(&db, head),
+            iter_from_counts::Options {
+                islands: Some(Arc::new(islands.clone())),
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.delta_compressed_objects > 0);
+        for entry in &entries {
+            if let output::entry::Kind::DeltaRef { object_index } = entry.kind {
+                assert!(
+                    islands.allows_delta(&entry.id, &entries[object_index].id),
+                    "bases are always in the same islands"
+                );
+            }
+        }
+        write_and_verify(db, entries, hex_to_id("0000000000000000000000000000000000000000"), None)?;
+        Ok(())
+    }
+
+    #[test]
+    fn packed_deltas_are_reused() -> crate::Result {
+        let db = db(DbKind::DeterministicGeneratedContent)?;
+        let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+        let tag = hex_to_id("e3fb53cbb4c346d48732a24f09cf445e49bc63d6");
+        let (entries, outcome) = entries_and_outcome(
+            db.clone(),
+            all_commits(&db, head).into_iter().chain(Some(tag)).collect(),
+            Default::default(),
+        )?;
+        assert_eq!(outcome.missing_objects, 0);
+        assert_eq!(
+            outcome.objects_copied_from_pack + outcome.delta_compressed_objects,
+            entries.len(),
+            "everything is copied, unless a better delta was found"
+        );
+        assert!(num_deltas(&entries) > outcome.delta_compressed_objects);
+        write_and_verify(db, entries, hex_to_id("0000000000000000000000000000000000000000"), None)?;
+        Ok(())
+    }
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     assert_eq!(
@@ -341,7 +562,7 @@ fn write_and_verify(
     entries: Vec<output::Entry>,
     _expected_pack_hash: gix_hash::ObjectId,
     _expected_thin_pack_hash: Option<gix_hash::ObjectId>,
-) -> crate::Result {
+) -> crate::Result<u64> {
     let tmp_dir = gix_testtools::tempfile::TempDir::new()?;
     let pack_file_path = tmp_dir.path().join("new.pack");
     let mut pack_file = std::fs::OpenOptions::new()
@@ -420,5 +641,5 @@ fn write_and_verify(
         },
     )?;
 
-    Ok(())
+    Ok(num_written_bytes)
 }
@@ -6,7 +6,7 @@ use gix_pack::data::output;
 fn size_of_entry() {
     assert_eq!(
         std::mem::size_of::<output::Entry>(),
-        80,
+        112,
         "The size of the structure shouldn't change unexpectedly"
     );
 }
@@ -15,7 +15,7 @@ fn size_of_entry() {
 fn size_of_count() {
     assert_eq!(
         std::mem::size_of::<output::Count>(),
-        56,
+        72,
         "The size of the structure shouldn't change unexpectedly"
     );
 }
@@ -41,4 +41,5 @@ fn db(kind: DbKind) -> crate::Result<gix_odb::HandleArc> {
         })
 }
 
+mod count_all_reachable;
 mod count_and_entries;
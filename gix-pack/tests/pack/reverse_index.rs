@@ -0,0 +1,157 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use gix_features::progress;
+use gix_pack::{bitmap, index};
+
+fn repo(name: &str) -> PathBuf {
+    crate::scripted_fixture_read_only("make_pack_gen_repo_reverse_index.sh")
+        .expect("test fixture exists")
+        .join(name)
+        .join(".git/objects/pack")
+}
+
+fn pack_index(pack_dir: &std::path::Path) -> index::File {
+    let mut paths: Vec<_> = std::fs::read_dir(pack_dir)
+        .expect("pack directory exists")
+        .map(|entry| entry.expect("readable").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .collect();
+    assert_eq!(paths.len(), 1, "expecting a single pack");
+    index::File::at(paths.pop().expect("one"), gix_hash::Kind::Sha1).expect("valid index")
+}
+
+#[test]
+fn read_and_write_byte_for_byte() -> crate::Result {
+    let index = pack_index(&repo("single-pack"));
+    let path = index.path().with_extension("rev");
+    let file = index::reverse::File::at(&path, gix_hash::Kind::Sha1)?;
+    assert_eq!(file.version(), index::reverse::Version::V1);
+    assert_eq!(file.num_objects(), index.num_objects());
+    assert_eq!(file.pack_checksum(), index.pack_checksum(), "it belongs to its pack");
+
+    let mut actual = Vec::new();
+    let checksum = index::reverse::File::write_from_index(&index, &mut actual)?;
+    assert_eq!(actual, std::fs::read(&path)?, "we write exactly what git wrote");
+    assert_eq!(checksum, file.checksum());
+
+    let order = bitmap::Index::Pack(&index).order().expect("always present for packs");
+    assert_eq!(
+        Some(order),
+        bitmap::PackOrder::from_reverse_index(&file),
+        "bitmaps use the reverse index"
+    );
+    assert_eq!(
+        bitmap::PackOrder::from_reverse_index(&file),
+        Some(bitmap::PackOrder::from_index(&index)),
+        "it's the same as ordering by offset"
+    );
+    Ok(())
+}
+
+#[test]
+fn offsets_map_to_entries_and_sizes() -> crate::Result {
+    let pack_dir = repo("single-pack");
+    let index = pack_index(&pack_dir);
+    let file = index::reverse::File::at(index.path().with_extension("rev"), gix_hash::Kind::Sha1)?;
+    let pack = gix_pack::data::File::at(index.path().with_extension("pack"), gix_hash::Kind::Sha1)?;
+
+    let mut total_size = 0;
+    for (index_pos, entry) in index.iter().enumerate() {
+        assert_eq!(
+            file.lookup_by_pack_offset(&index, entry.pack_offset),
+            Some(index_pos as u32)
+        );
+        let size = pack
+            .entry_size_on_disk(entry.pack_offset, &index, &file)
+            .expect("an entry starts here");
+        let entry_data = pack
+            .entry_slice(entry.pack_offset..entry.pack_offset + size)
+            .expect("in bounds");
+        assert_eq!(
+            gix_features::hash::crc32(entry_data),
+            entry.crc32.expect("present in V2"),
+            "sizes are exact"
+        );
+        total_size += size;
+    }
+    assert_eq!(
+        total_size,
+        pack.pack_end() as u64 - 12,
+        "all entries are covered, except for the pack header"
+    );
+
+    assert_eq!(file.lookup_by_pack_offset(&index, 1), None, "not the start of an entry");
+    assert_eq!(pack.entry_size_on_disk(1, &index, &file), None);
+    assert_eq!(file.index_position(file.num_objects()), None);
+
+    let mut offsets: Vec<_> = index.iter().map(|entry| entry.pack_offset).collect();
+    offsets.sort_unstable();
+    assert_eq!(
+        index.sorted_offsets(),
+        offsets,
+        "the reverse index yields offsets in pack order"
+    );
+
+    let num_traversed = AtomicUsize::new(0);
+    index.traverse_with_index(
+        &pack,
+        |_, _, _, _| {
+            num_traversed.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, std::io::Error>(())
+        },
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        index::traverse::with_index::Options::default(),
+    )?;
+    assert_eq!(
+        num_traversed.load(Ordering::SeqCst),
+        index.num_objects() as usize,
+        "the delta tree is built from the reverse index"
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_index_reverse_index_is_written_like_git() -> crate::Result {
+    let pack_dir = repo("multi-pack");
+    let expected = gix_pack::multi_index::File::at(pack_dir.join("multi-pack-index"))?;
+    let expected_order: Vec<_> = expected
+        .iter_pseudo_pack_order()
+        .expect("git writes it along with bitmaps")
+        .collect();
+
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let output_path = dir.path().join("multi-pack-index");
+    let mut out = std::fs::File::create(&output_path)?;
+    gix_pack::multi_index::File::write_from_index_paths(
+        expected.index_names().iter().map(|name| pack_dir.join(name)).collect(),
+        &mut out,
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        gix_pack::multi_index::write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            reverse_index: true,
+        },
+    )?;
+    let actual = gix_pack::multi_index::File::at(output_path)?;
+    assert_eq!(actual.preferred_pack(), expected.preferred_pack());
+    assert_eq!(
+        actual.iter_pseudo_pack_order().expect("written").collect::<Vec<_>>(),
+        expected_order
+    );
+    assert_eq!(actual.iter().collect::<Vec<_>>(), expected.iter().collect::<Vec<_>>());
+
+    for (pseudo_pack_pos, entry_index) in expected_order.into_iter().enumerate() {
+        let (pack_index, pack_offset) = actual.pack_id_and_pack_offset_at_index(entry_index);
+        assert_eq!(
+            actual.pseudo_pack_position(pack_index, pack_offset),
+            Some(pseudo_pack_pos as u32)
+        );
+        assert_eq!(actual.lookup_by_pack_offset(pack_index, pack_offset), Some(entry_index));
+    }
+    assert_eq!(actual.lookup_by_pack_offset(0, 1), None);
+    Ok(())
+}
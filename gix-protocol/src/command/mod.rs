@@ -12,6 +12,7 @@ impl Command {
         match self {
             Command::LsRefs => "ls-refs",
             Command::Fetch => "fetch",
+            Command::Push => "push",
         }
     }
 }
@@ -53,6 +54,7 @@ mod with_io {
                     // wait-for-done feature
                     "wait-for-done",
                 ],
+                Command::Push => &[],
             }
         }
 
@@ -87,6 +89,18 @@ mod with_io {
                         "wait-for-done",
                     ],
                 },
+                Command::Push => match version {
+                    gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => &[
+                        "report-status",
+                        "report-status-v2",
+                        "side-band-64k",
+                        "quiet",
+                        "atomic",
+                        "push-options",
+                        "object-format",
+                    ],
+                    gix_transport::Protocol::V2 => &[],
+                },
             }
         }
 
@@ -108,6 +122,7 @@ mod with_io {
                     )
                     .collect(),
                 Command::LsRefs => vec![b"symrefs".as_bstr().to_owned(), b"peel".as_bstr().to_owned()],
+                Command::Push => Vec::new(),
             }
         }
 
@@ -154,6 +169,26 @@ mod with_io {
                             .collect()
                     }
                 },
+                Command::Push => match version {
+                    gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => {
+                        let has_report_status_v2 = server_capabilities.contains("report-status-v2");
+                        let object_format = server_capabilities
+                            .capability("object-format")
+                            .and_then(|c| c.value().map(|v| v.to_str_lossy().into_owned()));
+                        self.all_features(version)
+                            .iter()
+                            .copied()
+                            .filter(|feature| match *feature {
+                                "report-status" if has_report_status_v2 => false,
+                                "quiet" | "atomic" | "push-options" | "object-format" => false,
+                                feature => server_capabilities.contains(feature),
+                            })
+                            .map(|s| (s, None))
+                            .chain(object_format.map(|format| ("object-format", Some(format.into()))))
+                            .collect()
+                    }
+                    gix_transport::Protocol::V2 => vec![],
+                },
                 Command::LsRefs => vec![],
             }
         }
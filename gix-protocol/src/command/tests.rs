@@ -48,6 +48,41 @@ mod v1 {
             }
         }
     }
+
+    mod push {
+        mod default_features {
+            use crate::{command::tests::v1::capabilities, Command};
+
+            #[test]
+            fn it_prefers_report_status_v2_and_echoes_the_object_format() {
+                assert_eq!(
+                    Command::Push.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities(
+                            "report-status report-status-v2 delete-refs side-band-64k quiet atomic ofs-delta push-options object-format=sha1 agent=git/2.45.2"
+                        )
+                    ),
+                    &[
+                        ("report-status-v2", None),
+                        ("side-band-64k", None),
+                        ("object-format", Some("sha1".into()))
+                    ],
+                    "atomic, quiet and push-options are opt-in"
+                );
+            }
+
+            #[test]
+            fn it_falls_back_to_report_status() {
+                assert_eq!(
+                    Command::Push.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities("report-status delete-refs ofs-delta")
+                    ),
+                    &[("report-status", None)]
+                );
+            }
+        }
+    }
 }
 
 mod v2 {
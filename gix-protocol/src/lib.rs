@@ -1,7 +1,9 @@
-//! An abstraction over [fetching][fetch()] a pack from the server.
+//! An abstraction over [fetching][fetch()] a pack from the server, and [pushing][push()] one to it.
 //!
 //! This implementation hides the transport layer, statefulness and the protocol version to the [fetch delegate][fetch::Delegate],
 //! the actual client implementation.
+//!
+//! With the `blocking-server` feature, the [`server`] module provides the server side of the protocol, like `upload-pack`.
 //! ## Feature Flags
 #![cfg_attr(
     all(doc, feature = "document-features"),
@@ -17,6 +19,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// Push a pack along with reference updates, only available in V0 and V1 via `git-receive-pack`.
+    Push,
 }
 pub mod command;
 
@@ -46,6 +50,12 @@ pub use remote_progress::RemoteProgress;
 #[cfg(all(feature = "blocking-client", feature = "async-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
 
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod push;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use push::function::push;
+
 ///
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub mod handshake;
@@ -58,6 +68,10 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+#[cfg(feature = "blocking-server")]
+pub mod server;
+
 mod util;
 pub use util::agent;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -0,0 +1,185 @@
+use bstr::{BString, ByteSlice};
+#[cfg(feature = "async-client")]
+use futures_lite::io::AsyncWriteExt;
+use gix_features::progress::NestedProgress;
+use gix_transport::{
+    client::{Capabilities, MessageKind, Transport, WriteMode},
+    packetline::PacketLineRef,
+};
+use maybe_async::maybe_async;
+#[cfg(feature = "blocking-client")]
+use std::io::Write;
+
+use super::{Error, Options, Report, Update};
+use crate::{indicate_end_of_interaction, Command};
+
+/// Send the reference `updates` to the server behind `transport` and stream the `pack` to it, which is expected to contain all objects
+/// the server needs to perform the updates. The server `capabilities` were obtained with a prior [handshake](crate::push::handshake()).
+///
+/// * `pack` must be `Some` if at least one of the `updates` isn't a deletion, and must be a complete pack, including its trailer.
+///   It's ignored if all `updates` are deletions.
+/// * `options` configure atomicity, push-options and more, each of which may require server capabilities.
+/// * `progress` is used to emit progress messages, including the ones sent by the remote.
+/// * If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+///
+/// Return the report of the server, or `None` if the server doesn't support reporting the status of updates, or if there were
+/// no `updates` to begin with, in which case the server is informed that we are done.
+///
+/// Note that the server will only reject individual updates as a whole if `options.atomic` is `true`, so the returned report
+/// has to be checked for the status of each update.
+#[allow(clippy::result_large_err)]
+#[maybe_async]
+pub async fn push<P>(
+    mut transport: impl Transport,
+    capabilities: &Capabilities,
+    updates: &[Update],
+    pack: Option<&mut dyn std::io::Read>,
+    options: &Options,
+    progress: &mut P,
+    trace: bool,
+) -> Result<Option<Report>, Error>
+where
+    P: NestedProgress,
+    P::SubProgress: 'static,
+{
+    let _span = gix_features::trace::detail!("gix_protocol::push()", updates = updates.len());
+    if updates.is_empty() {
+        indicate_end_of_interaction(&mut transport, trace).await?;
+        return Ok(None);
+    }
+
+    let push = Command::Push;
+    let mut features = push.default_features(gix_transport::Protocol::V1, capabilities);
+    if let Err(err) = add_requested_features(capabilities, updates, options, &mut features) {
+        indicate_end_of_interaction(&mut transport, trace).await?;
+        return Err(err);
+    }
+    push.validate_argument_prefixes_or_panic(gix_transport::Protocol::V1, capabilities, &[], &features);
+    let has_report = features
+        .iter()
+        .any(|(name, _)| matches!(*name, "report-status" | "report-status-v2"));
+    let has_sideband = features.iter().any(|(name, _)| *name == "side-band-64k");
+    if let Some(agent) = &options.agent {
+        features.push(("agent", Some(crate::agent(agent.clone()).into())));
+    }
+
+    progress.set_name("send updates".into());
+    let mut request = transport.request(WriteMode::Binary, MessageKind::Flush, trace)?;
+    for (idx, update) in updates.iter().enumerate() {
+        let mut line = update.to_line();
+        if idx == 0 {
+            line.push(0);
+            line.extend_from_slice(encode_features(&features).as_bytes());
+        }
+        request.write_all(&line).await?;
+    }
+    request.write_message(MessageKind::Flush).await?;
+    if !options.push_options.is_empty() {
+        for option in &options.push_options {
+            request.write_all(option).await?;
+        }
+        request.write_message(MessageKind::Flush).await?;
+    }
+
+    let (mut writer, mut reader) = request.into_parts();
+    if let Some(pack) = pack.filter(|_| updates.iter().any(|update| !update.is_delete())) {
+        progress.set_name("send pack".into());
+        let mut buf = vec![0; u16::MAX as usize];
+        loop {
+            let bytes_read = pack.read(&mut buf)?;
+            if bytes_read == 0 {
+                break;
+            }
+            writer.write_all(&buf[..bytes_read]).await?;
+        }
+    }
+    writer.flush().await?;
+    drop(writer);
+
+    if !has_report {
+        return Ok(None);
+    }
+
+    progress.set_name("read report".into());
+    let mut lines = Vec::<BString>::new();
+    if has_sideband {
+        reader.set_progress_handler(Some(Box::new({
+            let mut remote_progress = progress.add_child("remote");
+            move |is_err: bool, data: &[u8]| {
+                crate::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress);
+                gix_transport::packetline::read::ProgressAction::Continue
+            }
+        }) as gix_transport::client::HandleProgress<'_>));
+        // The report itself is encoded as packetlines, sent through the data channel of the side-band.
+        let mut report =
+            gix_transport::packetline::StreamingPeekableIter::new(&mut reader, &[PacketLineRef::Flush], trace);
+        while let Some(line) = report.read_line().await {
+            if let Some(data) = line??.as_slice() {
+                lines.push(data.into());
+            }
+        }
+    } else {
+        while let Some(line) = reader.readline().await {
+            if let Some(data) = line??.as_slice() {
+                lines.push(data.into());
+            }
+        }
+    }
+    Ok(Some(Report::from_lines(lines.iter().map(|line| line.as_slice()))?))
+}
+
+/// Add features to `features` which are needed to carry out `updates` according to `options`, or fail if the server
+/// doesn't advertise them via `capabilities`.
+fn add_requested_features(
+    capabilities: &Capabilities,
+    updates: &[Update],
+    options: &Options,
+    features: &mut Vec<crate::command::Feature>,
+) -> Result<(), Error> {
+    if updates.iter().any(Update::is_delete) && !capabilities.contains("delete-refs") {
+        return Err(Error::MissingServerCapability {
+            feature: "delete-refs",
+            reason: "delete references",
+        });
+    }
+    if options.atomic {
+        if !capabilities.contains("atomic") {
+            return Err(Error::MissingServerCapability {
+                feature: "atomic",
+                reason: "perform an atomic push",
+            });
+        }
+        features.push(("atomic", None));
+    }
+    if !options.push_options.is_empty() {
+        if !capabilities.contains("push-options") {
+            return Err(Error::MissingServerCapability {
+                feature: "push-options",
+                reason: "send push-options",
+            });
+        }
+        if let Some(option) = options
+            .push_options
+            .iter()
+            .find(|option| option.find_byteset(b"\n\0").is_some())
+        {
+            return Err(Error::InvalidPushOption { option: option.clone() });
+        }
+        features.push(("push-options", None));
+    }
+    if options.quiet && capabilities.contains("quiet") {
+        features.push(("quiet", None));
+    }
+    Ok(())
+}
+
+fn encode_features(features: &[crate::command::Feature]) -> String {
+    features
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!("{name}={value}"),
+            None => name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
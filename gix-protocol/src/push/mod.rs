@@ -0,0 +1,104 @@
+use bstr::{BString, ByteVec};
+
+///
+pub mod response;
+pub use response::Report;
+
+mod handshake;
+pub use handshake::receive_pack as handshake;
+
+pub(crate) mod function;
+
+mod error {
+    /// The error returned by [`push()`][crate::push()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        PacketlineDecode(#[from] gix_transport::packetline::decode::Error),
+        #[error("Pushing is only possible with protocol V0 or V1, but the server responded with {version:?}")]
+        UnsupportedProtocolVersion { version: gix_transport::Protocol },
+        #[error("The server does not support the '{feature}' capability, which is required to {reason}")]
+        MissingServerCapability {
+            feature: &'static str,
+            reason: &'static str,
+        },
+        #[error("Push-options must not contain newlines or NUL bytes: {option:?}")]
+        InvalidPushOption { option: BString },
+        #[error(transparent)]
+        Response(#[from] super::response::Error),
+    }
+
+    use bstr::BString;
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+pub use error::Error;
+
+/// A single reference update as it is sent to the server, which uses it to atomically change `name` from `old` to `new`.
+///
+/// The server rejects the update if `name` doesn't currently point to `old`, which makes it possible to safely push
+/// against a known state of the remote.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+    /// The object we expect the reference to point to on the remote, or the null-id if it is expected to not exist.
+    pub old: gix_hash::ObjectId,
+    /// The object the reference should point to after the update, or the null-id if it should be deleted.
+    pub new: gix_hash::ObjectId,
+    /// The full name of the reference to update on the remote, like `refs/heads/main`.
+    pub name: BString,
+}
+
+impl Update {
+    /// Return `true` if this update deletes the reference on the remote.
+    pub fn is_delete(&self) -> bool {
+        self.new.is_null()
+    }
+
+    /// Return `true` if this update creates the reference on the remote.
+    pub fn is_create(&self) -> bool {
+        self.old.is_null()
+    }
+
+    /// Encode this update as line for use in a packetline, without trailing newline.
+    pub(crate) fn to_line(&self) -> BString {
+        let mut out = BString::from(format!("{} {} ", self.old, self.new));
+        out.push_str(&self.name);
+        out
+    }
+}
+
+/// Options to configure a [`push()`][crate::push()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, the server will either apply all reference updates or none of them.
+    ///
+    /// This requires the `atomic` server capability.
+    pub atomic: bool,
+    /// Strings to pass to the server-side hooks, typically in the form of `key=value`.
+    ///
+    /// They must not contain newlines or NUL bytes, and require the `push-options` server capability.
+    pub push_options: Vec<BString>,
+    /// If `true`, ask the server not to send progress information of its own, like the progress of unpacking objects.
+    ///
+    /// This is only effective if the server supports the `quiet` capability, and is ignored otherwise.
+    pub quiet: bool,
+    /// The name of the client to present as `agent`, like `"my-app (v2.0)"`.
+    pub agent: Option<String>,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,171 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned when parsing the server's [`Report`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The server did not send any report, even though it was asked to")]
+    MissingUnpackStatus,
+    #[error("Expected the report to start with the 'unpack' status, got {line:?}")]
+    ExpectedUnpackStatus { line: BString },
+    #[error("Encountered an unknown line in the report: {line:?}")]
+    UnknownLine { line: BString },
+    #[error("An 'option' line was received before the status of any reference: {line:?}")]
+    OrphanedOption { line: BString },
+    #[error("Could not parse object id in line {line:?}")]
+    InvalidObjectId {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+}
+
+/// The result of unpacking the pack we sent on the server side.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unpack {
+    /// The pack was received and stored successfully, or no pack had to be sent.
+    Ok,
+    /// The server could not unpack the objects we sent, and thus couldn't update any reference.
+    Failed {
+        /// The reason for the failure as provided by the server.
+        message: BString,
+    },
+}
+
+/// Additional information about a successfully updated reference, as provided by `report-status-v2`.
+///
+/// It's sent when the server-side hooks altered the update that was originally requested.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rewrite {
+    /// The name of the reference that was actually updated, if it differs from the one that was requested.
+    pub name: Option<BString>,
+    /// The value the reference had before it was updated, if it differs from the one that was requested.
+    pub old: Option<gix_hash::ObjectId>,
+    /// The value the reference has after it was updated, if it differs from the one that was requested.
+    pub new: Option<gix_hash::ObjectId>,
+    /// If `true`, the reference was updated in a non-fast-forward fashion.
+    pub forced_update: bool,
+}
+
+/// The status of a reference update as reported by the server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Status {
+    /// The reference was updated.
+    Ok {
+        /// Additional information about how the update was carried out, only available with `report-status-v2`.
+        ///
+        /// There may be more than one as hooks may turn a single update into multiple ones, and it's empty
+        /// if the update was performed as requested.
+        rewrites: Vec<Rewrite>,
+    },
+    /// The reference was not updated.
+    Rejected {
+        /// The reason for the rejection as provided by the server, like `non-fast-forward`.
+        reason: BString,
+    },
+}
+
+/// A reference and the status of its update as reported by the server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefStatus {
+    /// The full name of the reference the update was requested for, like `refs/heads/main`.
+    pub name: BString,
+    /// What happened to the reference.
+    pub status: Status,
+}
+
+/// The report sent by the server after receiving reference updates and possibly a pack, with `report-status` or
+/// `report-status-v2` enabled.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// The result of unpacking the objects we sent.
+    pub unpack: Unpack,
+    /// The status of each reference update, in the order they were reported by the server.
+    pub refs: Vec<RefStatus>,
+}
+
+impl Report {
+    /// Return the status of the reference named `name`, if the server reported it.
+    pub fn status_by_name(&self, name: &BStr) -> Option<&Status> {
+        self.refs.iter().find(|r| r.name == name).map(|r| &r.status)
+    }
+
+    /// Return `true` if the server unpacked all objects and updated all references.
+    pub fn is_success(&self) -> bool {
+        self.unpack == Unpack::Ok && self.refs.iter().all(|r| matches!(r.status, Status::Ok { .. }))
+    }
+
+    /// Parse a report from the given `lines`, each of which is the content of a single packetline,
+    /// with or without trailing newline.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a [u8]>) -> Result<Self, Error> {
+        let mut lines = lines
+            .into_iter()
+            .map(|line| line.trim_end_with(|c| c == '\n').as_bstr());
+        let unpack = match lines.next() {
+            None => return Err(Error::MissingUnpackStatus),
+            Some(line) => match line.strip_prefix(b"unpack ") {
+                Some(b"ok") => Unpack::Ok,
+                Some(message) => Unpack::Failed {
+                    message: message.into(),
+                },
+                None => return Err(Error::ExpectedUnpackStatus { line: line.into() }),
+            },
+        };
+
+        let mut refs = Vec::<RefStatus>::new();
+        let mut start_rewrite = false;
+        for line in lines {
+            if let Some(name) = line.strip_prefix(b"ok ") {
+                // With `report-status-v2`, each rewrite of the same reference is introduced by its own `ok` line.
+                start_rewrite = true;
+                let is_repeated = refs.last().map_or(false, |last| {
+                    last.name == name && matches!(&last.status, Status::Ok { rewrites } if !rewrites.is_empty())
+                });
+                if !is_repeated {
+                    refs.push(RefStatus {
+                        name: name.into(),
+                        status: Status::Ok { rewrites: Vec::new() },
+                    });
+                }
+            } else if let Some(rest) = line.strip_prefix(b"ng ") {
+                let (name, reason) = rest.split_once_str(b" ").unwrap_or((rest, &[]));
+                start_rewrite = false;
+                refs.push(RefStatus {
+                    name: name.into(),
+                    status: Status::Rejected { reason: reason.into() },
+                });
+            } else if let Some(option) = line.strip_prefix(b"option ") {
+                let rewrites = match refs.last_mut().map(|r| &mut r.status) {
+                    Some(Status::Ok { rewrites }) => rewrites,
+                    _ => return Err(Error::OrphanedOption { line: line.into() }),
+                };
+                let (key, value) = option.split_once_str(b" ").unwrap_or((option, &[]));
+                let parse_id = |value: &[u8]| {
+                    gix_hash::ObjectId::from_hex(value).map_err(|source| Error::InvalidObjectId {
+                        line: line.into(),
+                        source,
+                    })
+                };
+                if start_rewrite {
+                    rewrites.push(Rewrite::default());
+                    start_rewrite = false;
+                }
+                let rewrite = rewrites.last_mut().expect("just pushed");
+                match key {
+                    b"refname" => rewrite.name = Some(value.into()),
+                    b"old-oid" => rewrite.old = Some(parse_id(value)?),
+                    b"new-oid" => rewrite.new = Some(parse_id(value)?),
+                    b"forced-update" => rewrite.forced_update = true,
+                    _ => return Err(Error::UnknownLine { line: line.into() }),
+                }
+            } else {
+                return Err(Error::UnknownLine { line: line.into() });
+            }
+        }
+        Ok(Report { unpack, refs })
+    }
+}
@@ -0,0 +1,152 @@
+mod update {
+    use crate::push::Update;
+
+    #[test]
+    fn to_line() {
+        let update = Update {
+            old: gix_hash::Kind::Sha1.null(),
+            new: gix_hash::ObjectId::from_hex(b"7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf").unwrap(),
+            name: "refs/heads/main".into(),
+        };
+        assert!(update.is_create());
+        assert!(!update.is_delete());
+        assert_eq!(
+            update.to_line(),
+            "0000000000000000000000000000000000000000 7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf refs/heads/main"
+        );
+    }
+}
+
+mod report {
+    use crate::push::{
+        response::{Error, RefStatus, Rewrite, Status, Unpack},
+        Report,
+    };
+
+    fn report(lines: &[&str]) -> Result<Report, Error> {
+        Report::from_lines(lines.iter().map(|line| line.as_bytes()))
+    }
+
+    fn oid(hex: &str) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+    }
+
+    #[test]
+    fn v1_with_mixed_results() -> Result<(), Error> {
+        let report = report(&[
+            "unpack ok\n",
+            "ok refs/heads/main\n",
+            "ng refs/heads/feature non-fast-forward\n",
+            "ng refs/heads/hooked pre-receive hook declined",
+        ])?;
+        assert_eq!(report.unpack, Unpack::Ok);
+        assert_eq!(
+            report.refs,
+            vec![
+                RefStatus {
+                    name: "refs/heads/main".into(),
+                    status: Status::Ok { rewrites: Vec::new() }
+                },
+                RefStatus {
+                    name: "refs/heads/feature".into(),
+                    status: Status::Rejected {
+                        reason: "non-fast-forward".into()
+                    }
+                },
+                RefStatus {
+                    name: "refs/heads/hooked".into(),
+                    status: Status::Rejected {
+                        reason: "pre-receive hook declined".into()
+                    }
+                },
+            ]
+        );
+        assert!(!report.is_success());
+        assert_eq!(
+            report.status_by_name("refs/heads/main".into()),
+            Some(&Status::Ok { rewrites: Vec::new() })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_failure() -> Result<(), Error> {
+        let report = report(&["unpack index-pack abnormal exit", "ng refs/heads/main unpacker error"])?;
+        assert_eq!(
+            report.unpack,
+            Unpack::Failed {
+                message: "index-pack abnormal exit".into()
+            }
+        );
+        assert!(!report.is_success());
+        Ok(())
+    }
+
+    #[test]
+    fn v2_with_rewrites() -> Result<(), Error> {
+        let old = "7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf";
+        let new = "ae0bd9e4de5e8e3f9ef1ef5ea6aa5f55c4bb0f00";
+        let report = report(&[
+            "unpack ok",
+            "ok refs/for/main",
+            "option refname refs/changes/1/1",
+            &format!("option old-oid {old}"),
+            &format!("option new-oid {new}"),
+            "ok refs/for/main",
+            "option refname refs/changes/1/2",
+            "option forced-update",
+            "ok refs/heads/main",
+        ])?;
+        assert!(report.is_success());
+        assert_eq!(
+            report.refs,
+            vec![
+                RefStatus {
+                    name: "refs/for/main".into(),
+                    status: Status::Ok {
+                        rewrites: vec![
+                            Rewrite {
+                                name: Some("refs/changes/1/1".into()),
+                                old: Some(oid(old)),
+                                new: Some(oid(new)),
+                                forced_update: false,
+                            },
+                            Rewrite {
+                                name: Some("refs/changes/1/2".into()),
+                                forced_update: true,
+                                ..Default::default()
+                            }
+                        ]
+                    }
+                },
+                RefStatus {
+                    name: "refs/heads/main".into(),
+                    status: Status::Ok { rewrites: Vec::new() }
+                }
+            ],
+            "repeated 'ok' lines for the same reference introduce another rewrite"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(report(&[]), Err(Error::MissingUnpackStatus)));
+        assert!(matches!(
+            report(&["ok refs/heads/main"]),
+            Err(Error::ExpectedUnpackStatus { .. })
+        ));
+        assert!(matches!(
+            report(&["unpack ok", "option forced-update"]),
+            Err(Error::OrphanedOption { .. })
+        ));
+        assert!(matches!(
+            report(&["unpack ok", "ok refs/heads/main", "option old-oid foo"]),
+            Err(Error::InvalidObjectId { .. })
+        ));
+        assert!(matches!(
+            report(&["unpack ok", "unknown refs/heads/main"]),
+            Err(Error::UnknownLine { .. })
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_transport::{Protocol, Service};
+
+///
+pub mod receive_pack;
+pub use receive_pack::function::receive_pack;
+
+///
+pub mod upload_pack;
+pub use upload_pack::function::upload_pack;
+
+mod util;
+mod write;
+
+///
+pub mod connect_request {
+    use bstr::BString;
+
+    /// The error returned by [`ConnectRequest::from_bytes()`](super::ConnectRequest::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The connect request {line:?} didn't contain a service and a repository path")]
+        Malformed { line: BString },
+        #[error("The service {service:?} is unknown")]
+        UnknownService { service: BString },
+        #[error("The requested protocol version {version:?} is unsupported")]
+        UnsupportedVersion { version: BString },
+        #[error("The virtual host {host:?} could not be parsed")]
+        InvalidHost { host: BString },
+    }
+}
+
+/// The request sent by a client as the very first line of a `git://` connection to instruct the daemon about the service to run.
+///
+/// It's the counterpart of the message sent by the `git` transport of clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectRequest {
+    /// The service the client wants to talk to.
+    pub service: Service,
+    /// The path to the repository as sent by the client, which may be relative to the base directory of the daemon.
+    pub repository_path: BString,
+    /// The host and port the client connected to, useful to serve multiple virtual hosts.
+    pub virtual_host: Option<(String, Option<u16>)>,
+    /// The protocol version the client would like to use, which is [`Protocol::V0`] if it wasn't specified.
+    pub desired_version: Protocol,
+    /// Additional parameters in the form of `(key, optional value)`, excluding the protocol version.
+    pub extra_parameters: Vec<(String, Option<String>)>,
+}
+
+impl ConnectRequest {
+    /// Parse the data of the first packet line of a `git://` connection, like `git-upload-pack /project.git\0host=example.com\0`.
+    pub fn from_bytes(line: &[u8]) -> Result<Self, connect_request::Error> {
+        use connect_request::Error;
+
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let mut segments = line.split(|b| *b == 0);
+        let (service, repository_path) = segments
+            .next()
+            .and_then(|command| command.split_once_str(" "))
+            .filter(|(_, path)| !path.is_empty())
+            .ok_or_else(|| Error::Malformed { line: line.into() })?;
+        let service = match service {
+            b"git-upload-pack" => Service::UploadPack,
+            b"git-receive-pack" => Service::ReceivePack,
+            _ => {
+                return Err(Error::UnknownService {
+                    service: service.into(),
+                })
+            }
+        };
+
+        let mut virtual_host = None;
+        let mut desired_version = Protocol::V0;
+        let mut extra_parameters = Vec::new();
+        for (idx, segment) in segments.enumerate().filter(|(_, segment)| !segment.is_empty()) {
+            if let Some(host) = segment.strip_prefix(b"host=").filter(|_| idx == 0) {
+                virtual_host =
+                    Some(parse_host(host.as_bstr()).ok_or_else(|| Error::InvalidHost { host: host.into() })?);
+                continue;
+            }
+            match segment.split_once_str("=") {
+                Some((b"version", version)) => desired_version = parse_version(version.as_bstr())?,
+                Some((key, value)) => {
+                    extra_parameters.push((key.to_str_lossy().into_owned(), Some(value.to_str_lossy().into_owned())));
+                }
+                None => extra_parameters.push((segment.to_str_lossy().into_owned(), None)),
+            }
+        }
+        Ok(ConnectRequest {
+            service,
+            repository_path: repository_path.into(),
+            virtual_host,
+            desired_version,
+            extra_parameters,
+        })
+    }
+}
+
+/// Parse the value of the `GIT_PROTOCOL` environment variable, like `version=2`, as set by clients that spawn the server
+/// process directly or via `ssh`, to learn which protocol version they desire.
+///
+/// Return [`Protocol::V0`] if no valid version was specified, as clients then expect the reference advertisement without a version line.
+pub fn desired_version_from_git_protocol(value: &BStr) -> Protocol {
+    value
+        .split(|b| *b == b':')
+        .filter_map(|parameter| parameter.strip_prefix(b"version="))
+        .filter_map(|version| parse_version(version.as_bstr()).ok())
+        .next_back()
+        .unwrap_or(Protocol::V0)
+}
+
+fn parse_version(version: &BStr) -> Result<Protocol, connect_request::Error> {
+    Ok(match version.as_bytes() {
+        b"0" => Protocol::V0,
+        b"1" => Protocol::V1,
+        b"2" => Protocol::V2,
+        _ => {
+            return Err(connect_request::Error::UnsupportedVersion {
+                version: version.into(),
+            })
+        }
+    })
+}
+
+fn parse_host(host: &BStr) -> Option<(String, Option<u16>)> {
+    let host = host.to_str().ok()?;
+    Some(match host.rsplit_once(':') {
+        Some((name, port)) if !host.ends_with(']') => (name.to_owned(), Some(port.parse().ok()?)),
+        _ => (host.to_owned(), None),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,231 @@
+use std::io::{self, Write};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_transport::{
+    packetline::{encode, Channel, PacketLineRef, StreamingPeekableIter},
+    Protocol,
+};
+
+use super::{Delegate, Error, Options, UpdateOptions};
+use crate::{
+    handshake::Ref,
+    push::{
+        response::{RefStatus, Status, Unpack},
+        Report, Update,
+    },
+    server::{
+        util::{is_hangup, line_data, object_format},
+        write,
+    },
+};
+
+/// Serve a client connected through `input` and `out` as `git receive-pack` would, using the protocol `version`
+/// the client asked for, to let it update references and send the objects they need through `delegate`.
+///
+/// As there is no V2 for pushing, clients asking for it are served with V0, just like `git` does.
+///
+/// Return the report of the push, which is also sent to the client if it asked for it, or `None` if the client
+/// didn't send any update.
+/// Note that `input` should be buffered for performance.
+pub fn receive_pack(
+    input: impl io::Read,
+    mut out: impl io::Write,
+    version: Protocol,
+    delegate: &mut impl Delegate,
+    options: &Options,
+) -> Result<Option<Report>, Error> {
+    let _span = gix_features::trace::coarse!("gix_protocol::server::receive_pack()", version = ?version);
+    let res = serve(input, &mut out, version, delegate, options);
+    if let Err(err) = &res {
+        if matches!(err, Error::UnexpectedLine { .. } | Error::InvalidObjectId { .. }) {
+            encode::error_to_write(format!("receive-pack: {err}").as_bytes(), &mut out).ok();
+            out.flush().ok();
+        }
+    }
+    res
+}
+
+fn serve(
+    input: impl io::Read,
+    mut out: impl io::Write,
+    version: Protocol,
+    delegate: &mut impl Delegate,
+    options: &Options,
+) -> Result<Option<Report>, Error> {
+    let refs = delegate.refs().map_err(Error::Delegate)?;
+    if version == Protocol::V1 {
+        write::text("version 1", &mut out)?;
+    }
+    advertise_refs(&refs, options, &mut out)?;
+    write::flush(&mut out)?;
+
+    let mut lines = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], options.trace);
+    let mut updates = Vec::new();
+    let mut features = Vec::<BString>::new();
+    while let Some(line) = lines.read_line() {
+        let line = match line {
+            Err(err) if is_hangup(&err) && updates.is_empty() => return Ok(None),
+            line => line??,
+        };
+        let data = line_data(&line);
+        let (command, capabilities) = data.split_once_str(b"\0").unwrap_or((data, &[]));
+        if updates.is_empty() {
+            features = capabilities
+                .split(|b| *b == b' ')
+                .filter(|feature| !feature.is_empty())
+                .map(Into::into)
+                .collect();
+        }
+        updates.push(parse_update(command.as_bstr(), data)?);
+    }
+    if updates.is_empty() || lines.stopped_at().is_none() {
+        // The client doesn't want to update anything, or hung up.
+        return Ok(None);
+    }
+
+    let has_feature = |name: &str| features.iter().any(|feature| feature == name);
+    let mut update_options = UpdateOptions {
+        atomic: has_feature("atomic"),
+        push_options: Vec::new(),
+    };
+    if has_feature("push-options") {
+        lines.reset();
+        while let Some(line) = lines.read_line() {
+            update_options.push_options.push(line_data(&line??).into());
+        }
+    }
+
+    let unpack = if updates.iter().any(|update| !update.is_delete()) {
+        let mut pack = io::BufReader::new(lines.into_inner());
+        match delegate.receive_pack(&mut pack) {
+            Ok(()) => Unpack::Ok,
+            Err(err) => Unpack::Failed {
+                message: err.to_string().into(),
+            },
+        }
+    } else {
+        Unpack::Ok
+    };
+    let statuses = match unpack {
+        Unpack::Ok => {
+            let statuses = delegate
+                .update_refs(&updates, &update_options)
+                .map_err(Error::Delegate)?;
+            if statuses.len() != updates.len() {
+                return Err(Error::StatusCountMismatch {
+                    expected: updates.len(),
+                    actual: statuses.len(),
+                });
+            }
+            statuses
+        }
+        Unpack::Failed { .. } => vec![
+            Status::Rejected {
+                reason: "unpacker error".into()
+            };
+            updates.len()
+        ],
+    };
+    let report = Report {
+        unpack,
+        refs: updates
+            .into_iter()
+            .zip(statuses)
+            .map(|(update, status)| RefStatus {
+                name: update.name,
+                status,
+            })
+            .collect(),
+    };
+
+    if has_feature("report-status") {
+        send_report(&report, has_feature("side-band-64k"), out)?;
+    }
+    Ok(Some(report))
+}
+
+fn advertise_refs(refs: &[Ref], options: &Options, mut out: impl io::Write) -> io::Result<()> {
+    let mut capabilities: Vec<BString> = [
+        "report-status",
+        "delete-refs",
+        "side-band-64k",
+        "quiet",
+        "atomic",
+        "ofs-delta",
+        "push-options",
+    ]
+    .iter()
+    .map(|capability| BString::from(*capability))
+    .collect();
+    capabilities.push(format!("object-format={}", object_format(options.object_hash)).into());
+    if let Some(agent) = &options.agent {
+        capabilities.push(format!("agent={}", crate::agent(agent.clone())).into());
+    }
+
+    let mut lines: Vec<_> = refs
+        .iter()
+        .filter_map(|r| match r {
+            Ref::Direct { full_ref_name, object } => Some((*object, full_ref_name.clone())),
+            Ref::Peeled { full_ref_name, tag, .. } => Some((*tag, full_ref_name.clone())),
+            Ref::Symbolic { .. } | Ref::Unborn { .. } => None,
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push((options.object_hash.null(), "capabilities^{}".into()));
+    }
+
+    for (idx, (id, name)) in lines.into_iter().enumerate() {
+        let mut line = BString::from(format!("{id} {name}"));
+        if idx == 0 {
+            line.push(0);
+            line.extend_from_slice(&bstr::join(" ", &capabilities));
+        }
+        write::text(line, &mut out)?;
+    }
+    Ok(())
+}
+
+/// Send `report` to the client, possibly encoded in the data channel of a side-band.
+fn send_report(report: &Report, use_sideband: bool, mut out: impl io::Write) -> io::Result<()> {
+    let mut lines = Vec::new();
+    match &report.unpack {
+        Unpack::Ok => write::text("unpack ok", &mut lines)?,
+        Unpack::Failed { message } => write::text(format!("unpack {message}"), &mut lines)?,
+    }
+    for r in &report.refs {
+        match &r.status {
+            Status::Ok { .. } => write::text(format!("ok {}", r.name), &mut lines)?,
+            Status::Rejected { reason } => write::text(format!("ng {} {reason}", r.name), &mut lines)?,
+        }
+    }
+    encode::flush_to_write(&mut lines)?;
+
+    if use_sideband {
+        let mut band = write::Sideband::buffered(&mut out, Channel::Data);
+        band.write_all(&lines)?;
+        band.flush()?;
+        drop(band);
+        write::flush(out)
+    } else {
+        out.write_all(&lines)?;
+        out.flush()
+    }
+}
+
+fn parse_update(command: &BStr, line: &BStr) -> Result<Update, Error> {
+    let mut tokens = command.splitn(3, |b| *b == b' ');
+    let (Some(old), Some(new), Some(name)) = (tokens.next(), tokens.next(), tokens.next()) else {
+        return Err(Error::UnexpectedLine { line: line.into() });
+    };
+    let parse_id = |hex: &[u8]| {
+        gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::InvalidObjectId {
+            line: line.into(),
+            source,
+        })
+    };
+    Ok(Update {
+        old: parse_id(old)?,
+        new: parse_id(new)?,
+        name: name.into(),
+    })
+}
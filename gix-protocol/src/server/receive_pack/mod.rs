@@ -0,0 +1,91 @@
+use bstr::BString;
+
+use crate::{
+    handshake::Ref,
+    push::{response::Status, Update},
+};
+
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`receive_pack()`](crate::server::receive_pack()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to read from or write to the client")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        PacketlineDecode(#[from] gix_transport::packetline::decode::Error),
+        #[error("The delegate failed")]
+        Delegate(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Could not understand the line {line:?} sent by the client")]
+        UnexpectedLine { line: BString },
+        #[error("Could not parse object id in line {line:?}")]
+        InvalidObjectId {
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("The delegate returned {actual} statuses for {expected} updates")]
+        StatusCountMismatch { expected: usize, actual: usize },
+    }
+}
+pub use error::Error;
+
+/// The error type to be returned by [`Delegate`] implementations.
+pub type DelegateError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Provide access to the repository served by [`receive_pack()`](crate::server::receive_pack()).
+pub trait Delegate {
+    /// Return all references to advertise to the client, so it can learn which updates to send.
+    ///
+    /// Symbolic references like `HEAD` are not advertised, and annotated tags are advertised with the id of the tag object.
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError>;
+
+    /// Read the pack sent by the client from `pack` and make its objects available in the repository.
+    ///
+    /// It's only called if at least one of the updates isn't a deletion. The pack may be thin, i.e. it may refer to
+    /// objects in the repository which it doesn't contain itself.
+    /// If an error is returned, it's reported to the client and no reference is updated.
+    fn receive_pack(&mut self, pack: &mut dyn std::io::BufRead) -> Result<(), DelegateError>;
+
+    /// Apply the reference `updates` sent by the client, and return one status per update in the same order.
+    ///
+    /// This is where connectivity checks and hooks should decide if an update should be rejected, and where `options`
+    /// should be respected.
+    fn update_refs(&mut self, updates: &[Update], options: &UpdateOptions) -> Result<Vec<Status>, DelegateError>;
+}
+
+/// Information about the reference updates requested by the client.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UpdateOptions {
+    /// If `true`, all updates must be applied, or none of them.
+    pub atomic: bool,
+    /// Strings sent by the client to pass to server-side hooks, typically in the form of `key=value`.
+    pub push_options: Vec<BString>,
+}
+
+/// Options for use in [`receive_pack()`](crate::server::receive_pack()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash used in the repository, which is advertised to the client.
+    pub object_hash: gix_hash::Kind,
+    /// The name of the server to send to the client, which is prefixed with `git/` if it isn't already.
+    pub agent: Option<String>,
+    /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    pub trace: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            agent: None,
+            trace: false,
+        }
+    }
+}
+
+pub(crate) mod function;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,241 @@
+use gix_transport::Protocol;
+
+use crate::{
+    handshake::Ref,
+    push::{
+        response::{RefStatus, Status, Unpack},
+        Report, Update,
+    },
+    server::{
+        receive_pack::{Delegate, DelegateError, Error, Options, UpdateOptions},
+        tests::{input, lines},
+    },
+};
+
+const MAIN: &str = "7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf";
+const TAG: &str = "1111111111111111111111111111111111111111";
+const NEW: &str = "2222222222222222222222222222222222222222";
+const NULL: &str = "0000000000000000000000000000000000000000";
+
+fn oid(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+#[derive(Default)]
+struct MockDelegate {
+    fail_unpack: bool,
+    packs: Vec<Vec<u8>>,
+    updates: Vec<(Vec<Update>, UpdateOptions)>,
+}
+
+impl Delegate for MockDelegate {
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+        Ok(vec![
+            Ref::Symbolic {
+                full_ref_name: "HEAD".into(),
+                target: "refs/heads/main".into(),
+                tag: None,
+                object: oid(MAIN),
+            },
+            Ref::Direct {
+                full_ref_name: "refs/heads/main".into(),
+                object: oid(MAIN),
+            },
+            Ref::Peeled {
+                full_ref_name: "refs/tags/v1".into(),
+                tag: oid(TAG),
+                object: oid(MAIN),
+            },
+        ])
+    }
+
+    fn receive_pack(&mut self, pack: &mut dyn std::io::BufRead) -> Result<(), DelegateError> {
+        let mut buf = Vec::new();
+        pack.read_to_end(&mut buf)?;
+        self.packs.push(buf);
+        if self.fail_unpack {
+            return Err("index-pack abnormal exit".into());
+        }
+        Ok(())
+    }
+
+    fn update_refs(&mut self, updates: &[Update], options: &UpdateOptions) -> Result<Vec<Status>, DelegateError> {
+        self.updates.push((updates.to_owned(), options.clone()));
+        Ok(updates
+            .iter()
+            .map(|update| {
+                if update.name == "refs/heads/protected" {
+                    Status::Rejected {
+                        reason: "pre-receive hook declined".into(),
+                    }
+                } else {
+                    Status::Ok { rewrites: Vec::new() }
+                }
+            })
+            .collect())
+    }
+}
+
+fn serve(
+    mut delegate: MockDelegate,
+    version: Protocol,
+    request: &[&str],
+    pack: &[u8],
+) -> (Result<Option<Report>, Error>, Vec<String>, MockDelegate) {
+    let mut out = Vec::new();
+    let mut request = input(request);
+    request.extend_from_slice(pack);
+    let res = crate::server::receive_pack(
+        request.as_slice(),
+        &mut out,
+        version,
+        &mut delegate,
+        &Options {
+            agent: Some("server".into()),
+            ..Default::default()
+        },
+    );
+    (res, lines(&out), delegate)
+}
+
+#[test]
+fn advertisement_only() {
+    let (res, lines, delegate) = serve(MockDelegate::default(), Protocol::V1, &["0000"], &[]);
+    assert!(res.expect("no error").is_none(), "nothing was pushed");
+    assert_eq!(
+        lines,
+        [
+            "version 1".to_string(),
+            format!("{MAIN} refs/heads/main\0report-status delete-refs side-band-64k quiet atomic ofs-delta push-options object-format=sha1 agent=git/server"),
+            format!("{TAG} refs/tags/v1"),
+            "0000".into(),
+        ]
+    );
+    assert!(delegate.packs.is_empty() && delegate.updates.is_empty());
+}
+
+#[test]
+fn updates_with_pack_and_sideband_report() {
+    let (res, lines, delegate) = serve(
+        MockDelegate::default(),
+        Protocol::V0,
+        &[
+            &format!("{MAIN} {NEW} refs/heads/main\0report-status side-band-64k atomic push-options"),
+            &format!("{NULL} {NEW} refs/heads/protected"),
+            &format!("{TAG} {NULL} refs/tags/v1"),
+            "0000",
+            "ci.skip",
+            "0000",
+        ],
+        b"PACK",
+    );
+    let expected_report = Report {
+        unpack: Unpack::Ok,
+        refs: vec![
+            RefStatus {
+                name: "refs/heads/main".into(),
+                status: Status::Ok { rewrites: Vec::new() },
+            },
+            RefStatus {
+                name: "refs/heads/protected".into(),
+                status: Status::Rejected {
+                    reason: "pre-receive hook declined".into(),
+                },
+            },
+            RefStatus {
+                name: "refs/tags/v1".into(),
+                status: Status::Ok { rewrites: Vec::new() },
+            },
+        ],
+    };
+    assert_eq!(res.expect("no error"), Some(expected_report));
+    assert_eq!(
+        &lines[3..],
+        [
+            "\u{1}000eunpack ok\n0017ok refs/heads/main\n0036ng refs/heads/protected pre-receive hook declined\n0014ok refs/tags/v1\n0000",
+            "0000"
+        ],
+        "the report is sent in packetlines within the data channel"
+    );
+    assert_eq!(delegate.packs, [b"PACK".to_vec()]);
+    assert_eq!(
+        delegate.updates,
+        [(
+            vec![
+                Update {
+                    old: oid(MAIN),
+                    new: oid(NEW),
+                    name: "refs/heads/main".into()
+                },
+                Update {
+                    old: oid(NULL),
+                    new: oid(NEW),
+                    name: "refs/heads/protected".into()
+                },
+                Update {
+                    old: oid(TAG),
+                    new: oid(NULL),
+                    name: "refs/tags/v1".into()
+                },
+            ],
+            UpdateOptions {
+                atomic: true,
+                push_options: vec!["ci.skip".into()]
+            }
+        )]
+    );
+}
+
+#[test]
+fn deletions_only_need_no_pack() {
+    let (res, lines, delegate) = serve(
+        MockDelegate::default(),
+        Protocol::V0,
+        &[&format!("{MAIN} {NULL} refs/heads/main\0report-status"), "0000"],
+        &[],
+    );
+    assert!(res.expect("no error").expect("report").is_success());
+    assert_eq!(&lines[3..], ["unpack ok", "ok refs/heads/main", "0000"]);
+    assert!(delegate.packs.is_empty(), "the delegate isn't asked to receive a pack");
+}
+
+#[test]
+fn unpack_failures_reject_all_updates() {
+    let (res, lines, delegate) = serve(
+        MockDelegate {
+            fail_unpack: true,
+            ..Default::default()
+        },
+        Protocol::V0,
+        &[&format!("{MAIN} {NEW} refs/heads/main\0report-status"), "0000"],
+        b"PACK",
+    );
+    assert!(!res.expect("no error").expect("report").is_success());
+    assert_eq!(
+        &lines[3..],
+        [
+            "unpack index-pack abnormal exit",
+            "ng refs/heads/main unpacker error",
+            "0000"
+        ]
+    );
+    assert!(delegate.updates.is_empty(), "no reference is touched");
+}
+
+#[test]
+fn malformed_commands() {
+    let (res, lines, _) = serve(
+        MockDelegate::default(),
+        Protocol::V0,
+        &[&format!("{MAIN} refs/heads/main"), "0000"],
+        &[],
+    );
+    assert!(matches!(res, Err(Error::UnexpectedLine { .. })));
+    assert_eq!(
+        lines.last().map(String::as_str),
+        Some(
+            format!("ERR receive-pack: Could not understand the line \"{MAIN} refs/heads/main\" sent by the client")
+                .as_str()
+        )
+    );
+}
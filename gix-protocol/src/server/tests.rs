@@ -0,0 +1,123 @@
+use bstr::ByteSlice;
+use gix_transport::packetline::encode;
+
+/// Encode `lines` as packet lines, with `0000` and `0001` being a flush and a delimiter packet respectively.
+pub(crate) fn input(lines: &[&str]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for line in lines {
+        match *line {
+            "0000" => encode::flush_to_write(&mut out),
+            "0001" => encode::delim_to_write(&mut out),
+            line => encode::text_to_write(line.as_bytes(), &mut out),
+        }
+        .expect("in-memory writes don't fail");
+    }
+    out
+}
+
+/// Decode all packet lines in `out` into strings, with flush and delimiter packets represented as `0000` and `0001`.
+pub(crate) fn lines(mut out: &[u8]) -> Vec<String> {
+    let mut lines = Vec::new();
+    while !out.is_empty() {
+        let Some(len) = out[..4]
+            .to_str()
+            .ok()
+            .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+        else {
+            // Data that isn't a packet line, like a pack sent without side-band.
+            lines.push(format!("raw:{}", out.to_str_lossy()));
+            break;
+        };
+        match len {
+            0 | 1 => {
+                lines.push(format!("000{len}"));
+                out = &out[4..];
+            }
+            _ => {
+                let data = &out[4..len];
+                lines.push(data.strip_suffix(b"\n").unwrap_or(data).to_str_lossy().into_owned());
+                out = &out[len..];
+            }
+        }
+    }
+    lines
+}
+
+mod connect_request {
+    use gix_transport::{Protocol, Service};
+
+    use crate::server::{connect_request::Error, ConnectRequest};
+
+    #[test]
+    fn with_host_port_and_version() -> Result<(), Error> {
+        let request =
+            ConnectRequest::from_bytes(b"git-upload-pack /project.git\0host=example.com:9418\0\0version=2\0")?;
+        assert_eq!(
+            request,
+            ConnectRequest {
+                service: Service::UploadPack,
+                repository_path: "/project.git".into(),
+                virtual_host: Some(("example.com".into(), Some(9418))),
+                desired_version: Protocol::V2,
+                extra_parameters: Vec::new(),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_host_and_with_extra_parameters() -> Result<(), Error> {
+        let request = ConnectRequest::from_bytes(b"git-receive-pack ~user/repo\0\0key=value\0flag\0")?;
+        assert_eq!(request.service, Service::ReceivePack);
+        assert_eq!(request.repository_path, "~user/repo");
+        assert_eq!(request.virtual_host, None);
+        assert_eq!(request.desired_version, Protocol::V0, "V0 is the default");
+        assert_eq!(
+            request.extra_parameters,
+            vec![("key".into(), Some("value".into())), ("flag".into(), None)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn host_without_port() -> Result<(), Error> {
+        let request = ConnectRequest::from_bytes(b"git-upload-pack /repo\0host=localhost\0")?;
+        assert_eq!(request.virtual_host, Some(("localhost".into(), None)));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_requests() {
+        assert!(matches!(
+            ConnectRequest::from_bytes(b"git-upload-pack"),
+            Err(Error::Malformed { .. })
+        ));
+        assert!(matches!(
+            ConnectRequest::from_bytes(b"git-upload-archive /repo\0"),
+            Err(Error::UnknownService { .. })
+        ));
+        assert!(matches!(
+            ConnectRequest::from_bytes(b"git-upload-pack /repo\0\0version=3\0"),
+            Err(Error::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            ConnectRequest::from_bytes(b"git-upload-pack /repo\0host=example.com:port\0"),
+            Err(Error::InvalidHost { .. })
+        ));
+    }
+}
+
+mod desired_version_from_git_protocol {
+    use gix_transport::Protocol;
+
+    use crate::server::desired_version_from_git_protocol as desired_version;
+
+    #[test]
+    fn values() {
+        assert_eq!(desired_version("version=2".into()), Protocol::V2);
+        assert_eq!(desired_version("version=0".into()), Protocol::V0);
+        assert_eq!(desired_version("other:version=1:version=2".into()), Protocol::V2);
+        assert_eq!(desired_version("version=2:version=42".into()), Protocol::V2);
+        assert_eq!(desired_version("".into()), Protocol::V0, "the default");
+    }
+}
@@ -0,0 +1,442 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_transport::{
+    packetline::{encode, Channel, PacketLineRef, StreamingPeekableIter},
+    Protocol,
+};
+
+use super::{Delegate, Error, Options, PackOptions, Request};
+use crate::{
+    handshake::Ref,
+    server::{
+        util::{is_hangup, line_data, object_format},
+        write,
+    },
+};
+
+/// Serve a client connected through `input` and `out` as `git upload-pack` would, using the protocol `version`
+/// the client asked for, and obtain references and packs from `delegate`.
+///
+/// * In V0 and V1, references are advertised right away, followed by a single negotiation and the pack if the client wants one.
+/// * In V2, capabilities are advertised, and `ls-refs` and `fetch` commands are served until the client hangs up.
+///
+/// Note that `input` should be buffered for performance, and that this function returns once the client is done, or
+/// on the first error, after trying to inform the client about it.
+pub fn upload_pack(
+    input: impl io::Read,
+    mut out: impl io::Write,
+    version: Protocol,
+    delegate: &mut impl Delegate,
+    options: &Options,
+) -> Result<(), Error> {
+    let _span = gix_features::trace::coarse!("gix_protocol::server::upload_pack()", version = ?version);
+    let res = match version {
+        Protocol::V0 | Protocol::V1 => serve_v1(input, &mut out, version, delegate, options),
+        Protocol::V2 => serve_v2(input, &mut out, delegate, options),
+    };
+    if let Err(err) = &res {
+        if !matches!(err, Error::Io(_) | Error::Delegate(_)) {
+            encode::error_to_write(format!("upload-pack: {err}").as_bytes(), &mut out).ok();
+            out.flush().ok();
+        }
+    }
+    res
+}
+
+fn serve_v1(
+    input: impl io::Read,
+    mut out: impl io::Write,
+    version: Protocol,
+    delegate: &mut impl Delegate,
+    options: &Options,
+) -> Result<(), Error> {
+    let refs = delegate.refs().map_err(Error::Delegate)?;
+    if version == Protocol::V1 {
+        write::text("version 1", &mut out)?;
+    }
+    advertise_refs_v1(&refs, options, &mut out)?;
+    write::flush(&mut out)?;
+
+    let mut lines = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], options.trace);
+    let mut request = Request::default();
+    while let Some(line) = lines.read_line() {
+        let line = match line {
+            Err(err) if is_hangup(&err) && request.wants.is_empty() => return Ok(()),
+            line => line??,
+        };
+        let data = line_data(&line);
+        let Some(rest) = data.strip_prefix(b"want ") else {
+            return Err(Error::UnexpectedLine { line: data.into() });
+        };
+        let (id, features) = rest.split_once_str(" ").unwrap_or((rest, &[]));
+        if request.wants.is_empty() {
+            request.features = features
+                .split(|b| *b == b' ')
+                .filter(|feature| !feature.is_empty())
+                .map(Into::into)
+                .collect();
+        }
+        request.wants.push(parse_id(id, data)?);
+    }
+    if request.wants.is_empty() || lines.stopped_at().is_none() {
+        // The client doesn't want anything, or hung up.
+        return Ok(());
+    }
+    check_wants(&request.wants, &refs)?;
+
+    let multi_ack_detailed = request.has_feature("multi_ack_detailed");
+    let mut common = Vec::new();
+    let mut sent_ready = false;
+    lines.reset();
+    loop {
+        match lines.read_line() {
+            Some(line) => {
+                let data = line_data(&line??);
+                if let Some(id) = data.strip_prefix(b"have ") {
+                    let id = parse_id(id, data)?;
+                    if delegate.contains(&id) {
+                        if multi_ack_detailed {
+                            write::text(format!("ACK {id} common"), &mut out)?;
+                        } else if common.is_empty() {
+                            write::text(format!("ACK {id}"), &mut out)?;
+                        }
+                        common.push(id);
+                    }
+                } else if data == "done" {
+                    match common.last() {
+                        Some(id) if multi_ack_detailed => write::text(format!("ACK {id}"), &mut out)?,
+                        Some(_) => {}
+                        None => write::text("NAK", &mut out)?,
+                    }
+                    break;
+                } else {
+                    return Err(Error::UnexpectedLine { line: data.into() });
+                }
+            }
+            None => {
+                if lines.stopped_at().is_none() {
+                    return Ok(());
+                }
+                if multi_ack_detailed {
+                    if let Some(id) = common.last().filter(|_| !sent_ready) {
+                        // Without the means to compute a better cut, we are happy with any common commit.
+                        write::text(format!("ACK {id} ready"), &mut out)?;
+                        sent_ready = true;
+                    }
+                    write::text("NAK", &mut out)?;
+                } else if common.is_empty() {
+                    write::text("NAK", &mut out)?;
+                }
+                out.flush()?;
+                lines.reset();
+            }
+        }
+    }
+
+    let use_sideband = request.has_feature("side-band-64k");
+    send_pack(
+        delegate,
+        &request.wants,
+        &common,
+        request.pack_options(),
+        use_sideband,
+        out,
+    )
+}
+
+fn advertise_refs_v1(refs: &[Ref], options: &Options, mut out: impl io::Write) -> io::Result<()> {
+    let mut capabilities: Vec<BString> = [
+        "multi_ack_detailed",
+        "side-band-64k",
+        "ofs-delta",
+        "no-progress",
+        "include-tag",
+    ]
+    .iter()
+    .map(|capability| BString::from(*capability))
+    .collect();
+    let mut lines = Vec::<(gix_hash::ObjectId, BString)>::new();
+    for r in refs {
+        match r {
+            Ref::Direct { full_ref_name, object } => lines.push((*object, full_ref_name.clone())),
+            Ref::Peeled {
+                full_ref_name,
+                tag,
+                object,
+            } => {
+                lines.push((*tag, full_ref_name.clone()));
+                lines.push((*object, peeled_name(full_ref_name.as_ref())));
+            }
+            Ref::Symbolic {
+                full_ref_name,
+                target,
+                tag,
+                object,
+            } => {
+                capabilities.push(format!("symref={full_ref_name}:{target}").into());
+                lines.push((tag.unwrap_or(*object), full_ref_name.clone()));
+                if tag.is_some() {
+                    lines.push((*object, peeled_name(full_ref_name.as_ref())));
+                }
+            }
+            Ref::Unborn { full_ref_name, target } => {
+                capabilities.push(format!("symref={full_ref_name}:{target}").into());
+            }
+        }
+    }
+    capabilities.push(format!("object-format={}", object_format(options.object_hash)).into());
+    if let Some(agent) = &options.agent {
+        capabilities.push(format!("agent={}", crate::agent(agent.clone())).into());
+    }
+    if lines.is_empty() {
+        lines.push((options.object_hash.null(), "capabilities^{}".into()));
+    }
+
+    for (idx, (id, name)) in lines.into_iter().enumerate() {
+        let mut line = BString::from(format!("{id} {name}"));
+        if idx == 0 {
+            line.push(0);
+            line.extend_from_slice(&bstr::join(" ", &capabilities));
+        }
+        write::text(line, &mut out)?;
+    }
+    Ok(())
+}
+
+fn serve_v2(
+    input: impl io::Read,
+    mut out: impl io::Write,
+    delegate: &mut impl Delegate,
+    options: &Options,
+) -> Result<(), Error> {
+    write::text("version 2", &mut out)?;
+    if let Some(agent) = &options.agent {
+        write::text(format!("agent={}", crate::agent(agent.clone())), &mut out)?;
+    }
+    write::text("ls-refs=unborn", &mut out)?;
+    write::text("fetch", &mut out)?;
+    write::text(
+        format!("object-format={}", object_format(options.object_hash)),
+        &mut out,
+    )?;
+    write::flush(&mut out)?;
+
+    let mut lines = StreamingPeekableIter::new(input, &[PacketLineRef::Flush, PacketLineRef::Delimiter], options.trace);
+    loop {
+        let mut command = None;
+        let mut arguments = Vec::<BString>::new();
+        let mut in_arguments = false;
+        loop {
+            match lines.read_line() {
+                Some(line) => {
+                    let line = match line {
+                        Err(err) if is_hangup(&err) && command.is_none() && !in_arguments => return Ok(()),
+                        line => line??,
+                    };
+                    let data = line_data(&line);
+                    if in_arguments {
+                        arguments.push(data.into());
+                    } else if let Some(name) = data.strip_prefix(b"command=") {
+                        command = Some(BString::from(name));
+                    }
+                    // Other capabilities, like the agent of the client, are ignored.
+                }
+                None => match lines.stopped_at() {
+                    Some(PacketLineRef::Delimiter) => {
+                        in_arguments = true;
+                        lines.reset();
+                    }
+                    Some(_) => {
+                        lines.reset();
+                        break;
+                    }
+                    None => return Ok(()),
+                },
+            }
+        }
+
+        match command.as_ref().map(|command| command.as_bytes()) {
+            None => return Ok(()),
+            Some(b"ls-refs") => ls_refs(delegate, &arguments, &mut out)?,
+            Some(b"fetch") => fetch_v2(delegate, &arguments, &mut out)?,
+            Some(_) => {
+                return Err(Error::UnknownCommand {
+                    command: command.expect("present"),
+                })
+            }
+        }
+    }
+}
+
+fn ls_refs(delegate: &mut impl Delegate, arguments: &[BString], mut out: impl io::Write) -> Result<(), Error> {
+    let (mut symrefs, mut peel, mut unborn) = (false, false, false);
+    let mut prefixes = Vec::new();
+    for argument in arguments {
+        match argument.as_bytes() {
+            b"symrefs" => symrefs = true,
+            b"peel" => peel = true,
+            b"unborn" => unborn = true,
+            _ => match argument.strip_prefix(b"ref-prefix ") {
+                Some(prefix) => prefixes.push(prefix.as_bstr()),
+                None => return Err(Error::UnexpectedLine { line: argument.clone() }),
+            },
+        }
+    }
+
+    for r in delegate.refs().map_err(Error::Delegate)? {
+        let (name, _, _) = r.unpack();
+        if !prefixes.is_empty() && !prefixes.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+        let line = match &r {
+            Ref::Direct { full_ref_name, object } => format!("{object} {full_ref_name}"),
+            Ref::Peeled {
+                full_ref_name,
+                tag,
+                object,
+            } => {
+                let mut line = format!("{tag} {full_ref_name}");
+                if peel {
+                    write!(line, " peeled:{object}").expect("infallible");
+                }
+                line
+            }
+            Ref::Symbolic {
+                full_ref_name,
+                target,
+                tag,
+                object,
+            } => {
+                let mut line = format!("{} {full_ref_name}", tag.unwrap_or(*object));
+                if symrefs {
+                    write!(line, " symref-target:{target}").expect("infallible");
+                }
+                if peel && tag.is_some() {
+                    write!(line, " peeled:{object}").expect("infallible");
+                }
+                line
+            }
+            Ref::Unborn { full_ref_name, target } => {
+                if !unborn {
+                    continue;
+                }
+                let mut line = format!("unborn {full_ref_name}");
+                if symrefs {
+                    write!(line, " symref-target:{target}").expect("infallible");
+                }
+                line
+            }
+        };
+        write::text(line, &mut out)?;
+    }
+    write::flush(out)?;
+    Ok(())
+}
+
+fn fetch_v2(delegate: &mut impl Delegate, arguments: &[BString], mut out: impl io::Write) -> Result<(), Error> {
+    let mut request = Request::default();
+    for argument in arguments {
+        if let Some(id) = argument.strip_prefix(b"want ") {
+            request.wants.push(parse_id(id, argument.as_ref())?);
+        } else if let Some(id) = argument.strip_prefix(b"have ") {
+            request.haves.push(parse_id(id, argument.as_ref())?);
+        } else if argument == "done" {
+            request.done = true;
+        } else if matches!(
+            argument.as_bytes(),
+            b"thin-pack" | b"no-progress" | b"include-tag" | b"ofs-delta"
+        ) {
+            request.features.push(argument.clone());
+        } else {
+            return Err(Error::UnexpectedLine { line: argument.clone() });
+        }
+    }
+    if request.wants.is_empty() {
+        return Err(Error::NoWants);
+    }
+    check_wants(&request.wants, &delegate.refs().map_err(Error::Delegate)?)?;
+
+    let common: Vec<_> = request
+        .haves
+        .iter()
+        .filter(|id| delegate.contains(id))
+        .copied()
+        .collect();
+    if !request.done {
+        write::text("acknowledgments", &mut out)?;
+        if common.is_empty() {
+            write::text("NAK", &mut out)?;
+            write::flush(&mut out)?;
+            return Ok(());
+        }
+        for id in &common {
+            write::text(format!("ACK {id}"), &mut out)?;
+        }
+        // Without the means to compute a better cut, we are happy with any common commit.
+        write::text("ready", &mut out)?;
+        encode::delim_to_write(&mut out)?;
+    }
+    write::text("packfile", &mut out)?;
+    send_pack(delegate, &request.wants, &common, request.pack_options(), true, out)
+}
+
+/// Let `delegate` write the pack to `out`, possibly encoded in the data channel of a side-band.
+fn send_pack(
+    delegate: &mut impl Delegate,
+    wants: &[gix_hash::ObjectId],
+    common: &[gix_hash::ObjectId],
+    options: PackOptions,
+    use_sideband: bool,
+    mut out: impl io::Write,
+) -> Result<(), Error> {
+    if !use_sideband {
+        delegate
+            .write_pack(wants, common, options, &mut out)
+            .map_err(Error::Delegate)?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    let mut pack = write::Sideband::buffered(&mut out, Channel::Data);
+    let res = delegate.write_pack(wants, common, options, &mut pack);
+    pack.flush()?;
+    drop(pack);
+    if let Err(err) = res {
+        encode::band_to_write(Channel::Error, format!("upload-pack: {err}").as_bytes(), &mut out)?;
+        write::flush(&mut out)?;
+        return Err(Error::Delegate(err));
+    }
+    write::flush(out)?;
+    Ok(())
+}
+
+/// Assure the client only asks for objects we advertised.
+fn check_wants(wants: &[gix_hash::ObjectId], refs: &[Ref]) -> Result<(), Error> {
+    for want in wants {
+        let is_advertised = refs.iter().any(|r| {
+            let (_, target, peeled) = r.unpack();
+            target == Some(want.as_ref()) || peeled == Some(want.as_ref())
+        });
+        if !is_advertised {
+            return Err(Error::NotOurRef { id: *want });
+        }
+    }
+    Ok(())
+}
+
+fn parse_id(hex: &[u8], line: &BStr) -> Result<gix_hash::ObjectId, Error> {
+    gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::InvalidObjectId {
+        line: line.into(),
+        source,
+    })
+}
+
+fn peeled_name(name: &BStr) -> BString {
+    let mut name = name.to_owned();
+    name.extend_from_slice(b"^{}");
+    name
+}
@@ -0,0 +1,116 @@
+use bstr::BString;
+
+use crate::handshake::Ref;
+
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`upload_pack()`](crate::server::upload_pack()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to read from or write to the client")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        PacketlineDecode(#[from] gix_transport::packetline::decode::Error),
+        #[error("The delegate failed")]
+        Delegate(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Could not understand the line {line:?} sent by the client")]
+        UnexpectedLine { line: BString },
+        #[error("The command {command:?} is unknown or unsupported")]
+        UnknownCommand { command: BString },
+        #[error("Could not parse object id in line {line:?}")]
+        InvalidObjectId {
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("The client wants {id} which isn't the tip of an advertised reference")]
+        NotOurRef { id: gix_hash::ObjectId },
+        #[error("The client sent no wants in its fetch request")]
+        NoWants,
+    }
+}
+pub use error::Error;
+
+/// The error type to be returned by [`Delegate`] implementations.
+pub type DelegateError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Provide access to the repository served by [`upload_pack()`](crate::server::upload_pack()).
+pub trait Delegate {
+    /// Return all references to advertise to the client, which is called once per request for references.
+    ///
+    /// `HEAD` should be listed as well, as [`Ref::Symbolic`] if it points to a branch, or as [`Ref::Unborn`] if that branch doesn't exist yet.
+    /// Annotated tags should be listed as [`Ref::Peeled`].
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError>;
+
+    /// Return `true` if the object with `id` is present in the repository, which is used to determine if an object announced by
+    /// the client is one we have in common.
+    fn contains(&mut self, id: &gix_hash::oid) -> bool;
+
+    /// Write a pack to `out` which contains all objects reachable from `wants`, but none of the objects reachable from `common`,
+    /// all objects the client and we have in common.
+    ///
+    /// The pack must be self-contained as thin packs aren't advertised.
+    fn write_pack(
+        &mut self,
+        wants: &[gix_hash::ObjectId],
+        common: &[gix_hash::ObjectId],
+        options: PackOptions,
+        out: &mut dyn std::io::Write,
+    ) -> Result<(), DelegateError>;
+}
+
+/// Information about the pack requested by the client.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PackOptions {
+    /// If `true`, annotated tags pointing to objects in the pack should be sent as well.
+    pub include_tags: bool,
+}
+
+/// Options for use in [`upload_pack()`](crate::server::upload_pack()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash used in the repository, which is advertised to the client.
+    pub object_hash: gix_hash::Kind,
+    /// The name of the server to send to the client, which is prefixed with `git/` if it isn't already.
+    pub agent: Option<String>,
+    /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    pub trace: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            agent: None,
+            trace: false,
+        }
+    }
+}
+
+/// The arguments of a single fetch request as sent by the client.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Request {
+    wants: Vec<gix_hash::ObjectId>,
+    haves: Vec<gix_hash::ObjectId>,
+    /// The capabilities of the first `want` line in V1, or the arguments without value in V2.
+    features: Vec<BString>,
+    done: bool,
+}
+
+impl Request {
+    fn has_feature(&self, name: &str) -> bool {
+        self.features.iter().any(|feature| feature == name)
+    }
+
+    fn pack_options(&self) -> PackOptions {
+        PackOptions {
+            include_tags: self.has_feature("include-tag"),
+        }
+    }
+}
+
+pub(crate) mod function;
+
+#[cfg(test)]
+mod tests;
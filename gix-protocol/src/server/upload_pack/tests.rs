@@ -0,0 +1,269 @@
+use gix_transport::Protocol;
+
+use crate::{
+    handshake::Ref,
+    server::{
+        tests::{input, lines},
+        upload_pack::{Delegate, DelegateError, Error, Options, PackOptions},
+    },
+};
+
+const MAIN: &str = "7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf";
+const TAG: &str = "1111111111111111111111111111111111111111";
+const BASE: &str = "2222222222222222222222222222222222222222";
+
+fn oid(hex: &str) -> gix_hash::ObjectId {
+    gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+#[derive(Default)]
+struct MockDelegate {
+    packs: Vec<(Vec<gix_hash::ObjectId>, Vec<gix_hash::ObjectId>, PackOptions)>,
+}
+
+impl Delegate for MockDelegate {
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+        Ok(vec![
+            Ref::Symbolic {
+                full_ref_name: "HEAD".into(),
+                target: "refs/heads/main".into(),
+                tag: None,
+                object: oid(MAIN),
+            },
+            Ref::Direct {
+                full_ref_name: "refs/heads/main".into(),
+                object: oid(MAIN),
+            },
+            Ref::Peeled {
+                full_ref_name: "refs/tags/v1".into(),
+                tag: oid(TAG),
+                object: oid(MAIN),
+            },
+        ])
+    }
+
+    fn contains(&mut self, id: &gix_hash::oid) -> bool {
+        id == oid(BASE)
+    }
+
+    fn write_pack(
+        &mut self,
+        wants: &[gix_hash::ObjectId],
+        common: &[gix_hash::ObjectId],
+        options: PackOptions,
+        out: &mut dyn std::io::Write,
+    ) -> Result<(), DelegateError> {
+        self.packs.push((wants.to_owned(), common.to_owned(), options));
+        out.write_all(b"PACK")?;
+        Ok(())
+    }
+}
+
+fn serve(version: Protocol, request: &[&str]) -> (Result<(), Error>, Vec<String>, MockDelegate) {
+    let mut delegate = MockDelegate::default();
+    let mut out = Vec::new();
+    let res = crate::server::upload_pack(
+        input(request).as_slice(),
+        &mut out,
+        version,
+        &mut delegate,
+        &Options {
+            agent: Some("server".into()),
+            ..Default::default()
+        },
+    );
+    (res, lines(&out), delegate)
+}
+
+mod v1 {
+    use gix_transport::Protocol;
+
+    use super::{oid, serve, BASE, MAIN, TAG};
+    use crate::server::upload_pack::{Error, PackOptions};
+
+    #[test]
+    fn advertisement_only() {
+        let (res, lines, delegate) = serve(Protocol::V1, &["0000"]);
+        res.expect("a client may hang up after the advertisement");
+        assert_eq!(
+            lines,
+            [
+                "version 1".to_string(),
+                format!("{MAIN} HEAD\0multi_ack_detailed side-band-64k ofs-delta no-progress include-tag symref=HEAD:refs/heads/main object-format=sha1 agent=git/server"),
+                format!("{MAIN} refs/heads/main"),
+                format!("{TAG} refs/tags/v1"),
+                format!("{MAIN} refs/tags/v1^{{}}"),
+                "0000".into(),
+            ]
+        );
+        assert!(delegate.packs.is_empty());
+    }
+
+    #[test]
+    fn negotiation_with_multi_ack_detailed_and_sideband() {
+        let (res, lines, delegate) = serve(
+            Protocol::V0,
+            &[
+                &format!("want {MAIN} multi_ack_detailed side-band-64k include-tag"),
+                "0000",
+                &format!("have {TAG}"),
+                &format!("have {BASE}"),
+                "0000",
+                "done",
+            ],
+        );
+        res.expect("no error");
+        assert_eq!(
+            &lines[5..],
+            [
+                format!("ACK {BASE} common"),
+                format!("ACK {BASE} ready"),
+                "NAK".into(),
+                format!("ACK {BASE}"),
+                "\u{1}PACK".into(),
+                "0000".into(),
+            ]
+        );
+        assert_eq!(
+            delegate.packs,
+            [(vec![oid(MAIN)], vec![oid(BASE)], PackOptions { include_tags: true })]
+        );
+    }
+
+    #[test]
+    fn clone_without_sideband() {
+        let (res, lines, delegate) = serve(Protocol::V1, &[&format!("want {MAIN}"), "0000", "done"]);
+        res.expect("no error");
+        assert_eq!(&lines[6..], ["NAK", "raw:PACK"], "the pack is sent as is");
+        assert_eq!(
+            delegate.packs,
+            [(vec![oid(MAIN)], Vec::new(), PackOptions { include_tags: false })]
+        );
+    }
+
+    #[test]
+    fn wants_must_be_advertised() {
+        let (res, lines, delegate) = serve(Protocol::V1, &[&format!("want {BASE}"), "0000", "done"]);
+        assert!(matches!(res, Err(Error::NotOurRef { id }) if id == oid(BASE)));
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some(
+                format!("ERR upload-pack: The client wants {BASE} which isn't the tip of an advertised reference")
+                    .as_str()
+            )
+        );
+        assert!(delegate.packs.is_empty());
+    }
+}
+
+mod v2 {
+    use gix_transport::Protocol;
+
+    use super::{oid, serve, BASE, MAIN, TAG};
+    use crate::server::upload_pack::{Error, PackOptions};
+
+    #[test]
+    fn capabilities_and_ls_refs() {
+        let (res, lines, _) = serve(
+            Protocol::V2,
+            &[
+                "command=ls-refs",
+                "agent=git/client",
+                "0001",
+                "symrefs",
+                "peel",
+                "ref-prefix HEAD",
+                "ref-prefix refs/tags/",
+                "0000",
+                "0000",
+            ],
+        );
+        res.expect("no error");
+        assert_eq!(
+            lines,
+            [
+                "version 2".to_string(),
+                "agent=git/server".into(),
+                "ls-refs=unborn".into(),
+                "fetch".into(),
+                "object-format=sha1".into(),
+                "0000".into(),
+                format!("{MAIN} HEAD symref-target:refs/heads/main"),
+                format!("{TAG} refs/tags/v1 peeled:{MAIN}"),
+                "0000".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fetch_with_negotiation_round() {
+        let (res, lines, delegate) = serve(
+            Protocol::V2,
+            &[
+                "command=fetch",
+                "0001",
+                &format!("want {MAIN}"),
+                &format!("have {TAG}"),
+                "0000",
+                "command=fetch",
+                "0001",
+                "thin-pack",
+                "ofs-delta",
+                &format!("want {MAIN}"),
+                &format!("have {BASE}"),
+                "0000",
+            ],
+        );
+        res.expect("no error");
+        assert_eq!(
+            &lines[6..],
+            [
+                "acknowledgments".to_string(),
+                "NAK".into(),
+                "0000".into(),
+                "acknowledgments".into(),
+                format!("ACK {BASE}"),
+                "ready".into(),
+                "0001".into(),
+                "packfile".into(),
+                "\u{1}PACK".into(),
+                "0000".into(),
+            ]
+        );
+        assert_eq!(
+            delegate.packs,
+            [(vec![oid(MAIN)], vec![oid(BASE)], PackOptions { include_tags: false })]
+        );
+    }
+
+    #[test]
+    fn fetch_with_done() {
+        let (res, lines, delegate) = serve(
+            Protocol::V2,
+            &[
+                "command=fetch",
+                "0001",
+                "include-tag",
+                &format!("want {TAG}"),
+                "done",
+                "0000",
+            ],
+        );
+        res.expect("no error");
+        assert_eq!(&lines[6..], ["packfile", "\u{1}PACK", "0000"]);
+        assert_eq!(
+            delegate.packs,
+            [(vec![oid(TAG)], Vec::new(), PackOptions { include_tags: true })]
+        );
+    }
+
+    #[test]
+    fn unknown_command() {
+        let (res, lines, _) = serve(Protocol::V2, &["command=bundle-uri", "0000"]);
+        assert!(matches!(res, Err(Error::UnknownCommand { command }) if command == "bundle-uri"));
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("ERR upload-pack: The command \"bundle-uri\" is unknown or unsupported")
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use std::io;
+
+use bstr::{BStr, ByteSlice};
+use gix_transport::packetline::PacketLineRef;
+
+/// Return `true` if `err` indicates that the client closed the connection.
+pub(crate) fn is_hangup(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::UnexpectedEof
+}
+
+/// Return the data of `line` without its trailing newline, or an empty slice if it's not a data line.
+pub(crate) fn line_data<'a>(line: &PacketLineRef<'a>) -> &'a BStr {
+    let data = line.as_slice().unwrap_or_default();
+    data.strip_suffix(b"\n").unwrap_or(data).as_bstr()
+}
+
+/// Return the name of `kind` as used by the `object-format` capability.
+pub(crate) fn object_format(kind: gix_hash::Kind) -> String {
+    kind.to_string().to_ascii_lowercase()
+}
@@ -0,0 +1,51 @@
+use std::io;
+
+use gix_transport::packetline::{encode, Channel};
+
+/// The maximum amount of data bytes in a side-band packet line, after accounting for the band byte.
+const MAX_BAND_DATA_LEN: usize = 65515;
+
+/// A writer which encodes all data it receives as packet lines in the given side-band `channel`.
+///
+/// Wrap it into a buffered writer to avoid producing too many small packet lines.
+pub(crate) struct Sideband<W> {
+    pub out: W,
+    pub channel: Channel,
+}
+
+impl<W> Sideband<W>
+where
+    W: io::Write,
+{
+    /// Return a buffered writer that produces packets of the largest possible size.
+    pub fn buffered(out: W, channel: Channel) -> io::BufWriter<Self> {
+        io::BufWriter::with_capacity(MAX_BAND_DATA_LEN, Sideband { out, channel })
+    }
+}
+
+impl<W> io::Write for Sideband<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for chunk in buf.chunks(MAX_BAND_DATA_LEN) {
+            encode::band_to_write(self.channel, chunk, &mut self.out)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Write `text` as packet line to `out`, followed by a newline.
+pub(crate) fn text(text: impl AsRef<[u8]>, out: impl io::Write) -> io::Result<()> {
+    encode::text_to_write(text.as_ref(), out).map(|_| ())
+}
+
+/// Write a flush packet to `out` and flush it.
+pub(crate) fn flush(mut out: impl io::Write) -> io::Result<()> {
+    encode::flush_to_write(&mut out)?;
+    out.flush()
+}
@@ -0,0 +1,126 @@
+use bstr::ByteSlice;
+use gix_features::progress;
+use gix_protocol::push::{self, response::Status, Update};
+use gix_transport::Protocol;
+
+use crate::fetch::{helper_unused, oid, transport};
+
+fn update(old: &str, new: &str, name: &str) -> Update {
+    Update {
+        old: oid(old),
+        new: oid(new),
+        name: name.into(),
+    }
+}
+
+const MAIN: &str = "7a9fd4ad7f5e34dc33e5d57bc7be6e6b8a8f7bcf";
+const NEW: &str = "ae0bd9e4de5e8e3f9ef1ef5ea6aa5f55c4bb0f00";
+const NULL: &str = "0000000000000000000000000000000000000000";
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn update_and_delete_with_sideband_and_push_options() -> crate::Result {
+    let mut out = Vec::new();
+    let mut transport = transport(
+        &mut out,
+        "v1/push.response",
+        Protocol::V1,
+        gix_transport::client::git::ConnectMode::Process,
+    );
+    let outcome = push::handshake(&mut transport, helper_unused, Vec::new(), &mut progress::Discard).await?;
+    assert_eq!(outcome.server_protocol_version, Protocol::V1);
+    assert_eq!(outcome.refs.expect("present in V1").len(), 2);
+
+    let pack = b"PACK-bytes-are-passed-through";
+    let report = gix_protocol::push(
+        &mut transport,
+        &outcome.capabilities,
+        &[
+            update(MAIN, NEW, "refs/heads/main"),
+            update(MAIN, NULL, "refs/heads/old"),
+        ],
+        Some(&mut pack.as_slice()),
+        &push::Options {
+            atomic: true,
+            push_options: vec!["ci.skip".into()],
+            quiet: false,
+            agent: Some("agent".into()),
+        },
+        &mut progress::Discard,
+        false,
+    )
+    .await?
+    .expect("report-status is supported");
+    assert!(report.is_success());
+    assert_eq!(
+        report.status_by_name("refs/heads/old".into()),
+        Some(&Status::Ok { rewrites: Vec::new() })
+    );
+
+    drop(transport);
+    assert_eq!(
+        out.as_bstr(),
+        format!(
+            "00bb{MAIN} {NEW} refs/heads/main\0report-status-v2 side-band-64k object-format=sha1 atomic push-options agent=git/agent\
+             0064{MAIN} {NULL} refs/heads/old\
+             0000\
+             000bci.skip\
+             0000\
+             PACK-bytes-are-passed-through"
+        )
+        .as_bytes()
+        .as_bstr()
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn rejection_without_sideband() -> crate::Result {
+    let mut out = Vec::new();
+    let mut transport = transport(
+        &mut out,
+        "v1/push-without-sideband.response",
+        Protocol::V1,
+        gix_transport::client::git::ConnectMode::Process,
+    );
+    let outcome = push::handshake(&mut transport, helper_unused, Vec::new(), &mut progress::Discard).await?;
+
+    let pack = b"PACK";
+    let report = gix_protocol::push(
+        &mut transport,
+        &outcome.capabilities,
+        &[update(MAIN, NEW, "refs/heads/main")],
+        Some(&mut pack.as_slice()),
+        &push::Options::default(),
+        &mut progress::Discard,
+        false,
+    )
+    .await?
+    .expect("report-status is supported");
+    assert!(!report.is_success());
+    assert_eq!(
+        report.status_by_name("refs/heads/main".into()),
+        Some(&Status::Rejected {
+            reason: "non-fast-forward".into()
+        })
+    );
+
+    let err = gix_protocol::push(
+        &mut transport,
+        &outcome.capabilities,
+        &[update(MAIN, NEW, "refs/heads/main")],
+        None,
+        &push::Options {
+            atomic: true,
+            ..Default::default()
+        },
+        &mut progress::Discard,
+        false,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(err, push::Error::MissingServerCapability { feature: "atomic", .. }),
+        "capabilities are checked before anything is sent"
+    );
+    Ok(())
+}
@@ -14,6 +14,8 @@
 //!     * one reference maps to a file on disk
 //!   * **packed**
 //!     * references are stored in a single human-readable file, along with their targets if they are symbolic.
+//! * **[reftable][reftable::Stack]**
+//!   * references and their logs are stored in a stack of binary tables, with each transaction adding a new table.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -29,7 +31,7 @@ use gix_object::bstr::{BStr, BString};
 
 #[path = "store/mod.rs"]
 mod store_impl;
-pub use store_impl::{file, packed};
+pub use store_impl::{file, packed, reftable};
 
 mod fullname;
 ///
@@ -84,33 +86,41 @@ pub mod store {
         Disable,
     }
 
+    /// The way references are stored in a repository, as configured by `extensions.refStorage`.
+    #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
+    pub enum StorageFormat {
+        /// Loose references in files, along with a `packed-refs` file, as handled by the [file store](crate::file::Store).
+        #[default]
+        Files,
+        /// A stack of reftables in the `reftable` directory, as handled by the [reftable stack](crate::reftable::Stack).
+        Reftable,
+    }
+
     /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
     #[derive(Clone)]
-    #[allow(dead_code)]
-    pub(crate) struct Handle {
+    pub struct Handle {
         /// A way to access shared state with the requirement that interior mutability doesn't leak or is incorporated into error types
         /// if it could. The latter can't happen if references to said internal aren't ever returned.
         state: handle::State,
     }
 
-    #[allow(dead_code)]
     pub(crate) enum State {
         Loose { store: file::Store },
+        Reftable { stack: reftable::Stack },
     }
 
-    pub(crate) mod general;
+    ///
+    pub mod general;
 
     ///
     #[path = "general/handle/mod.rs"]
     mod handle;
-    use crate::file;
+    use crate::{file, reftable};
     pub use handle::find;
 }
 
-/// The git reference store.
-/// TODO: Figure out if handles are needed at all, which depends on the ref-table implementation.
-#[allow(dead_code)]
-pub(crate) struct Store {
+/// The git reference store, which uses the backend matching the [storage format](store::StorageFormat) of a repository.
+pub struct Store {
     inner: store::State,
 }
 
@@ -90,6 +90,9 @@ impl file::Store {
             }
             r
         }
+        if let Some(stack) = self.reftable_with_settings() {
+            return Ok(stack.try_find(partial_name)?);
+        }
         let mut buf = BString::default();
         let mut precomposed_partial_name_storage = packed.filter(|_| self.precompose_unicode).and_then(|_| {
             use gix_object::bstr::ByteSlice;
@@ -389,6 +392,8 @@ mod error {
         PackedRef(#[from] packed::find::Error),
         #[error("Could not open the packed refs buffer when trying to find references.")]
         PackedOpen(#[from] packed::buffer::open::Error),
+        #[error("A reference could not be read from the reftable stack")]
+        Reftable(#[from] crate::reftable::find::Error),
     }
 
     impl From<Infallible> for Error {
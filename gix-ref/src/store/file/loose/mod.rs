@@ -55,6 +55,7 @@ mod init {
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                reftable: None,
             }
         }
 
@@ -83,6 +84,7 @@ mod init {
                 packed: gix_fs::SharedFileSnapshotMut::new().into(),
                 object_hash,
                 precompose_unicode,
+                reftable: None,
             }
         }
     }
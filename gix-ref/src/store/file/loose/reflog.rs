@@ -16,13 +16,20 @@ impl file::Store {
         Name: TryInto<&'a FullNameRef, Error = E>,
         crate::name::Error: From<E>,
     {
-        Ok(self.reflog_path(name.try_into()?).is_file())
+        let name = name.try_into()?;
+        if let Some(stack) = self.reftable_with_settings() {
+            return Ok(stack.reflog_exists(name).unwrap_or_default());
+        }
+        Ok(self.reflog_path(name).is_file())
     }
 
     /// Return a reflog reverse iterator for the given fully qualified `name`, reading chunks from the back into the fixed buffer `buf`.
     ///
     /// The iterator will traverse log entries from most recent to oldest, reading the underlying file in chunks from the back.
     /// Return `Ok(None)` if no reflog exists.
+    ///
+    /// Note that this isn't supported yet if references are [kept in reftables](file::Store::set_reftable()),
+    /// use [`reflog_iter()`](file::Store::reflog_iter()) instead.
     pub fn reflog_iter_rev<'a, 'b, Name, E>(
         &self,
         name: Name,
@@ -33,6 +40,12 @@ impl file::Store {
         crate::name::Error: From<E>,
     {
         let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        if self.reftable.is_some() {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "reflogs in reftables can't be iterated in reverse yet",
+            )));
+        }
         let path = self.reflog_path(name);
         if path.is_dir() {
             return Ok(None);
@@ -58,6 +71,21 @@ impl file::Store {
         crate::name::Error: From<E>,
     {
         let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+        if let Some(stack) = self.reftable_with_settings() {
+            let lines = stack
+                .reflog(name)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+            return Ok(match lines {
+                Some(lines) => {
+                    buf.clear();
+                    for line in lines {
+                        line.write_to(buf)?;
+                    }
+                    Some(log::iter::forward(buf))
+                }
+                None => None,
+            });
+        }
         let path = self.reflog_path(name);
         match std::fs::File::open(&path) {
             Ok(mut file) => {
@@ -230,6 +258,86 @@ pub mod create_or_update {
     use crate::FullNameRef;
 }
 
+///
+pub mod expire {
+    use std::io::Write;
+
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{
+        store_impl::{file, file::log::LineRef},
+        FullNameRef,
+    };
+
+    impl file::Store {
+        /// Remove all lines from the reflog of `name` for which `should_expire` returns `true`, and return the amount of
+        /// removed lines, or `None` if there is no reflog.
+        ///
+        /// The reference is locked with `lock_mode` while its reflog is rewritten so it can't change concurrently.
+        /// Lines that can't be parsed are kept, and the reflog is kept even if all of its lines are removed, just like git does.
+        pub fn reflog_expire(
+            &self,
+            name: &FullNameRef,
+            lock_mode: gix_lock::acquire::Fail,
+            should_expire: &mut dyn FnMut(&LineRef<'_>) -> bool,
+        ) -> Result<Option<usize>, Error> {
+            let log_path = self.reflog_path(name);
+            let (base, relative_path) = self.reference_path_with_base(name);
+            let _ref_lock = gix_lock::Marker::acquire_to_hold_resource(
+                base.join(relative_path.as_ref()),
+                lock_mode,
+                Some(base.clone().into_owned()),
+            )?;
+            let data = match std::fs::read(&log_path) {
+                Ok(data) => data,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(_) if log_path.is_dir() => return Ok(None),
+                Err(source) => return Err(Error::Io { source, path: log_path }),
+            };
+
+            let mut out = Vec::with_capacity(data.len());
+            let mut removed = 0;
+            for line in data.lines_with_terminator() {
+                let content = line.strip_suffix(b"\n").unwrap_or(line);
+                if LineRef::from_bytes(content).map_or(false, |line| should_expire(&line)) {
+                    removed += 1;
+                    continue;
+                }
+                out.extend_from_slice(line);
+            }
+            if removed == 0 {
+                return Ok(Some(0));
+            }
+
+            let mut lock = gix_lock::File::acquire_to_update_resource(&log_path, lock_mode, None)?;
+            lock.write_all(&out).map_err(|source| Error::Io {
+                source,
+                path: log_path.clone(),
+            })?;
+            lock.commit().map_err(|err| Error::Io {
+                source: err.error,
+                path: log_path,
+            })?;
+            Ok(Some(removed))
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        /// The error returned by [`crate::file::Store::reflog_expire()`].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not lock the reference or its reflog")]
+            Lock(#[from] gix_lock::acquire::Error),
+            #[error("Could not read or write the reflog at {path:?}")]
+            Io { source: std::io::Error, path: PathBuf },
+        }
+    }
+    pub use error::Error;
+}
+
 mod error {
     /// The error returned by [`crate::file::Store::reflog_iter()`].
     #[derive(Debug, thiserror::Error)]
@@ -41,10 +41,14 @@ pub struct Store {
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
     packed: packed::modifiable::MutableSharedBuffer,
+    /// If set, all references and their logs are kept in this stack of reftables instead of loose files and `packed-refs`,
+    /// as in repositories with `extensions.refStorage = reftable`.
+    /// It's boxed to keep the size of the store small.
+    reftable: Option<Box<crate::reftable::Stack>>,
 }
 
 mod access {
-    use std::path::Path;
+    use std::{borrow::Cow, path::Path};
 
     /// Mutation
     impl file::Store {
@@ -54,6 +58,16 @@ mod access {
             std::mem::swap(&mut self.packed_buffer_mmap_threshold, &mut bytes);
             bytes
         }
+
+        /// Keep all references and their logs in the reftable `stack` if `Some`, instead of using loose files and `packed-refs`,
+        /// as needed for repositories with `extensions.refStorage = reftable`.
+        /// Returns the previous value.
+        ///
+        /// The [`namespace`](file::Store::namespace) and [`write_reflog`](file::Store::write_reflog) settings of this
+        /// instance take precedence over the ones of `stack`.
+        pub fn set_reftable(&mut self, stack: Option<crate::reftable::Stack>) -> Option<crate::reftable::Stack> {
+            std::mem::replace(&mut self.reftable, stack.map(Box::new)).map(|stack| *stack)
+        }
     }
 
     use crate::file;
@@ -81,6 +95,35 @@ mod access {
         pub fn common_dir_resolved(&self) -> &Path {
             self.common_dir.as_deref().unwrap_or(&self.git_dir)
         }
+
+        /// Return the way references are stored, which is in [reftables](file::Store::set_reftable()) or in files.
+        pub fn storage_format(&self) -> crate::store::StorageFormat {
+            if self.reftable.is_some() {
+                crate::store::StorageFormat::Reftable
+            } else {
+                crate::store::StorageFormat::Files
+            }
+        }
+
+        /// Return the stack of reftables in which all references are kept, if [set](file::Store::set_reftable()).
+        pub fn reftable(&self) -> Option<&crate::reftable::Stack> {
+            self.reftable.as_deref()
+        }
+
+        /// Return our reftable stack with our own namespace and reflog settings, if one is set.
+        pub(crate) fn reftable_with_settings(&self) -> Option<Cow<'_, crate::reftable::Stack>> {
+            let stack = self.reftable.as_deref()?;
+            Some(
+                if stack.namespace == self.namespace && stack.write_reflog == self.write_reflog {
+                    Cow::Borrowed(stack)
+                } else {
+                    let mut stack = stack.clone();
+                    stack.namespace.clone_from(&self.namespace);
+                    stack.write_reflog = self.write_reflog;
+                    Cow::Owned(stack)
+                },
+            )
+        }
     }
 }
 
@@ -90,6 +133,8 @@ pub struct Transaction<'s, 'p> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs<'p>,
+    /// The transaction to perform instead if the store keeps its references in reftables.
+    reftable: Option<crate::reftable::Transaction<'s>>,
 }
 
 pub(in crate::store_impl::file) fn path_to_name<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
@@ -8,7 +8,7 @@ use std::{
 
 use crate::{
     file::{loose, loose::iter::SortedLoosePaths, path_to_name},
-    store_impl::{file, packed},
+    store_impl::{file, packed, reftable},
     BString, FullName, Namespace, Reference,
 };
 
@@ -16,6 +16,8 @@ use crate::{
 /// equivalent packed references.
 ///
 /// All errors will be returned verbatim, while packed errors are depleted first if loose refs also error.
+///
+/// If the store [keeps references in reftables](file::Store::set_reftable()), these are returned instead.
 pub struct LooseThenPacked<'p, 's> {
     git_dir: &'s Path,
     common_dir: Option<&'s Path>,
@@ -24,6 +26,7 @@ pub struct LooseThenPacked<'p, 's> {
     iter_git_dir: Peekable<SortedLoosePaths>,
     #[allow(dead_code)]
     iter_common_dir: Option<Peekable<SortedLoosePaths>>,
+    iter_reftable: Option<reftable::iter::Iter<'p>>,
     buf: Vec<u8>,
 }
 
@@ -38,6 +41,7 @@ enum IterKind {
 pub struct Platform<'s> {
     store: &'s file::Store,
     packed: Option<file::packed::SharedBufferSnapshot>,
+    reftable: Option<reftable::iter::Platform<'s>>,
 }
 
 impl<'p> LooseThenPacked<'p, '_> {
@@ -149,6 +153,9 @@ impl Iterator for LooseThenPacked<'_, '_> {
                 None => git_dir.peek().map(|r| (r, IterKind::Git)),
             }
         }
+        if let Some(reftable_iter) = self.iter_reftable.as_mut() {
+            return reftable_iter.next().map(|res| res.map_err(Error::Reftable));
+        }
         match self.iter_packed.as_mut() {
             Some(packed_iter) => match (
                 peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()),
@@ -192,15 +199,24 @@ impl Platform<'_> {
     ///
     /// Errors are returned similarly to what would happen when loose and packed refs where iterated by themselves.
     pub fn all(&self) -> std::io::Result<LooseThenPacked<'_, '_>> {
-        self.store.iter_packed(self.packed.as_ref().map(|b| &***b))
+        match &self.reftable {
+            Some(reftable) => Ok(self.store.iter_reftable(reftable.all())),
+            None => self.store.iter_packed(self.packed.as_ref().map(|b| &***b)),
+        }
     }
 
     /// As [`iter(…)`][file::Store::iter()], but filters by `prefix`, i.e. "refs/heads".
     ///
     /// Please note that "refs/heads" or "refs\\heads" is equivalent to "refs/heads/"
     pub fn prefixed(&self, prefix: &Path) -> std::io::Result<LooseThenPacked<'_, '_>> {
-        self.store
-            .iter_prefixed_packed(prefix, self.packed.as_ref().map(|b| &***b))
+        match &self.reftable {
+            Some(reftable) => Ok(self
+                .store
+                .iter_reftable(reftable.prefixed(path_to_name(prefix).as_ref()))),
+            None => self
+                .store
+                .iter_prefixed_packed(prefix, self.packed.as_ref().map(|b| &***b)),
+        }
     }
 }
 
@@ -212,9 +228,20 @@ impl file::Store {
     /// Note that since packed-refs are storing refs as precomposed unicode if [`Self::precompose_unicode`] is true, for consistency
     /// we also return loose references as precomposed unicode.
     pub fn iter(&self) -> Result<Platform<'_>, packed::buffer::open::Error> {
+        if let Some(stack) = self.reftable_with_settings() {
+            return Ok(Platform {
+                store: self,
+                packed: None,
+                reftable: Some(
+                    reftable::iter::Platform::new(stack)
+                        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?,
+                ),
+            });
+        }
         Ok(Platform {
             store: self,
             packed: self.assure_packed_refs_uptodate()?,
+            reftable: None,
         })
     }
 }
@@ -426,10 +453,25 @@ impl file::Store {
             },
             iter_git_dir: git_dir_info.into_iter(),
             iter_common_dir: common_dir_info.map(IterInfo::into_iter),
+            iter_reftable: None,
             buf: Vec::new(),
             namespace: self.namespace.as_ref(),
         })
     }
+
+    fn iter_reftable<'s, 'p>(&'s self, iter: reftable::iter::Iter<'p>) -> LooseThenPacked<'p, 's> {
+        LooseThenPacked {
+            git_dir: self.git_dir(),
+            common_dir: self.common_dir(),
+            iter_packed: None,
+            iter_git_dir: SortedLoosePaths::at(&self.git_dir().join("refs"), self.git_dir().into(), None, false)
+                .peekable(),
+            iter_common_dir: None,
+            iter_reftable: Some(iter),
+            buf: Vec::new(),
+            namespace: self.namespace.as_ref(),
+        }
+    }
 }
 
 mod error {
@@ -454,6 +496,8 @@ mod error {
         },
         #[error("Invalid reference in line {line_number}: {invalid_line:?}")]
         PackedReference { invalid_line: BString, line_number: usize },
+        #[error("A reference could not be read from the reftable stack")]
+        Reftable(#[source] crate::reftable::find::Error),
     }
 }
 pub use error::Error;
@@ -30,6 +30,9 @@ impl Transaction<'_, '_> {
     }
 
     fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
+        if let Some(reftable) = self.reftable {
+            return reftable.commit(committer).map_err(Error::Reftable);
+        }
         let mut updates = self.updates.expect("BUG: must call prepare before commit");
         let delete_loose_refs = matches!(
             self.packed_refs,
@@ -197,6 +200,8 @@ mod error {
         DeleteReflog { full_name: BString, source: std::io::Error },
         #[error("The reflog could not be created or updated")]
         CreateOrUpdateRefLog(#[from] file::log::create_or_update::Error),
+        #[error("The reftable transaction could not be committed")]
+        Reftable(#[source] crate::reftable::transaction::commit::Error),
     }
 }
 pub use error::Error;
@@ -67,12 +67,16 @@ impl file::Store {
     /// will never have been altered.
     ///
     /// The transaction inherits the parent namespace.
+    ///
+    /// If the store [keeps references in reftables](file::Store::set_reftable()), all edits are applied to the stack
+    /// of reftables instead, and the way [packed refs](Transaction::packed_refs()) are handled doesn't matter.
     pub fn transaction(&self) -> Transaction<'_, '_> {
         Transaction {
             store: self,
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            reftable: self.reftable_with_settings().map(crate::reftable::Transaction::new),
         }
     }
 }
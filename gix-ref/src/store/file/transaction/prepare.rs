@@ -221,6 +221,10 @@ impl Transaction<'_, '_> {
         ref_files_lock_fail_mode: gix_lock::acquire::Fail,
         packed_refs_lock_fail_mode: gix_lock::acquire::Fail,
     ) -> Result<Self, Error> {
+        if let Some(reftable) = self.reftable.take() {
+            self.reftable = Some(reftable.prepare(edits, ref_files_lock_fail_mode)?);
+            return Ok(self);
+        }
         assert!(self.updates.is_none(), "BUG: Must not call prepare(…) multiple times");
         let store = self.store;
         let mut updates: Vec<_> = edits
@@ -414,6 +418,9 @@ impl Transaction<'_, '_> {
     ///
     /// A rollback happens automatically as this instance is dropped as well.
     pub fn rollback(self) -> Vec<RefEdit> {
+        if let Some(reftable) = self.reftable {
+            return reftable.rollback();
+        }
         self.updates
             .map(|updates| updates.into_iter().map(|u| u.update).collect())
             .unwrap_or_default()
@@ -468,6 +475,8 @@ mod error {
         },
         #[error("An IO error occurred while applying an edit")]
         Io(#[from] std::io::Error),
+        #[error("The reftable transaction could not be prepared")]
+        Reftable(#[from] crate::reftable::transaction::prepare::Error),
         #[error("The reference {full_name:?} for deletion did not exist or could not be parsed")]
         DeleteReferenceMustExist { full_name: BString },
         #[error("Reference {full_name:?} was not supposed to exist when writing it with value {new:?}, but actual content was {actual:?}")]
@@ -1,9 +1,9 @@
-#![allow(dead_code)]
 use crate::{store, Namespace};
 
 #[derive(Clone)]
 pub(crate) enum State {
     Loose { store: crate::file::Store },
+    Reftable { stack: crate::reftable::Stack },
 }
 
 impl crate::Store {
@@ -28,6 +28,13 @@ impl crate::Store {
                         store
                     },
                 },
+                store::State::Reftable { stack } => store::handle::State::Reftable {
+                    stack: {
+                        let mut stack = stack.clone();
+                        stack.namespace = namespace;
+                        stack
+                    },
+                },
             },
         }
     }
@@ -35,9 +42,3 @@ impl crate::Store {
 
 ///
 pub mod find;
-
-mod iter {
-    // impl store::Handle {
-    //     pub fn iter<'p, 's>(&'s self, packed: Option<&'p packed::Buffer>) -> std::io::Result<LooseThenPacked<'p, 's>> {
-    // }
-}
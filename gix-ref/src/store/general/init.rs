@@ -12,22 +12,34 @@ mod error {
 
 pub use error::Error;
 
-use crate::file;
+use crate::{file, reftable, store::StorageFormat};
 
-#[allow(dead_code)]
 impl crate::Store {
-    /// Create a new store at the given location, typically the `.git/` directory.
+    /// Create a new store at the given location, typically the `.git/` directory, which keeps references in the given `format`,
+    /// typically the value of `extensions.refStorage`.
     /// Use [`opts`](crate::store::init::Options) to adjust settings.
     ///
     /// Note that if [`precompose_unicode`](crate::store::init::Options::precompose_unicode) is set in the options,
     /// the `git_dir` is also expected to use precomposed unicode, or else some operations that strip prefixes will fail.
-    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Result<Self, Error> {
-        // for now, just try to read the directory - later we will do that naturally as we have to figure out if it's a ref-table or not.
+    pub fn at(git_dir: PathBuf, format: StorageFormat, opts: crate::store::init::Options) -> Result<Self, Error> {
         std::fs::read_dir(&git_dir)?;
         Ok(crate::Store {
-            inner: crate::store::State::Loose {
-                store: file::Store::at(git_dir, opts),
+            inner: match format {
+                StorageFormat::Files => crate::store::State::Loose {
+                    store: file::Store::at(git_dir, opts),
+                },
+                StorageFormat::Reftable => crate::store::State::Reftable {
+                    stack: reftable::Stack::at(git_dir, opts),
+                },
             },
         })
     }
+
+    /// Return the format in which references are stored.
+    pub fn storage_format(&self) -> StorageFormat {
+        match self.inner {
+            crate::store::State::Loose { .. } => StorageFormat::Files,
+            crate::store::State::Reftable { .. } => StorageFormat::Reftable,
+        }
+    }
 }
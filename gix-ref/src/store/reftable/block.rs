@@ -0,0 +1,384 @@
+//! Reading and writing of the blocks reftables are made of, each holding prefix-compressed records
+//! followed by restart points to allow binary searches.
+use std::{borrow::Cow, ops::Range};
+
+use crate::store_impl::reftable::{decode::Error, varint};
+
+/// The kind of block holding reference records.
+pub(crate) const REF: u8 = b'r';
+/// The kind of block holding log records, which is compressed.
+pub(crate) const LOG: u8 = b'g';
+/// The kind of block holding object records.
+pub(crate) const OBJ: u8 = b'o';
+/// The kind of block holding index records, pointing to other blocks.
+pub(crate) const INDEX: u8 = b'i';
+
+/// The length of the header of each block, its kind and its length.
+const HEADER_LEN: usize = 4;
+
+pub(crate) fn be24(data: &[u8]) -> usize {
+    (usize::from(data[0]) << 16) | (usize::from(data[1]) << 8) | usize::from(data[2])
+}
+
+fn put_be24(value: usize, out: &mut [u8]) {
+    out[0] = (value >> 16) as u8;
+    out[1] = (value >> 8) as u8;
+    out[2] = value as u8;
+}
+
+/// A block read from a table, along with the state needed to iterate its records.
+pub(crate) struct Block<'a> {
+    /// The kind of block, like [`REF`].
+    pub(crate) kind: u8,
+    /// The amount of bytes the block occupies in the file, which is where the next block starts.
+    pub(crate) size_in_file: usize,
+    /// The offset of the block in the file, for error messages.
+    offset: usize,
+    /// The block itself, decompressed if needed, along with the file header if it's the first block.
+    data: Cow<'a, [u8]>,
+    records_start: usize,
+    restarts_start: usize,
+    restart_count: usize,
+    /// The position of the next record to decode.
+    pos: usize,
+    /// The key of the most recently decoded record.
+    key: Vec<u8>,
+    /// A record that was decoded already, but wasn't yet returned.
+    pending: Option<(u8, Range<usize>)>,
+}
+
+impl<'a> Block<'a> {
+    /// Read the block at `offset` in `blocks`, which holds all blocks of a table, or return `None` if there is no block at `offset`.
+    ///
+    /// `header_len` is the length of the file header which is part of the first block, and `block_size` is the size
+    /// padded blocks are expected to have.
+    pub(crate) fn at(
+        blocks: &'a [u8],
+        offset: usize,
+        header_len: usize,
+        block_size: usize,
+    ) -> Result<Option<Self>, Error> {
+        let Some(block) = blocks.get(offset..).filter(|block| !block.is_empty()) else {
+            return Ok(None);
+        };
+        let corrupt = |message| Error::CorruptBlock { offset, message };
+        let header_off = if offset == 0 { header_len } else { 0 };
+        let records_start = header_off + HEADER_LEN;
+        if block.len() < records_start {
+            return Err(corrupt("the block header is truncated"));
+        }
+        let kind = block[header_off];
+        let len = be24(&block[header_off + 1..]);
+        if len < records_start + 2 {
+            return Err(corrupt("the block is too short to hold any record"));
+        }
+
+        let (data, size_in_file) = if kind == LOG {
+            let mut data = Vec::with_capacity(len + 1);
+            data.extend_from_slice(&block[..records_start]);
+            // One more byte than needed to allow the decompressor to see the end of the stream.
+            data.resize(len + 1, 0);
+            let mut inflate = gix_features::zlib::Inflate::default();
+            let (mut consumed, mut produced) = (0, 0);
+            loop {
+                let (status, bytes_in, bytes_out) = inflate.once(
+                    &block[records_start + consumed..],
+                    &mut data[records_start + produced..],
+                )?;
+                consumed += bytes_in;
+                produced += bytes_out;
+                if status == gix_features::zlib::Status::StreamEnd {
+                    break;
+                }
+                if bytes_in == 0 && bytes_out == 0 {
+                    return Err(corrupt("the compressed log block is truncated"));
+                }
+            }
+            if records_start + produced != len {
+                return Err(corrupt("the log block didn't decompress to its advertised size"));
+            }
+            data.truncate(len);
+            (Cow::Owned(data), records_start + consumed)
+        } else {
+            if block.len() < len {
+                return Err(corrupt("the block is truncated"));
+            }
+            // Blocks are padded with zeroes up to the block size, unless the next block follows right away.
+            let size_in_file = if len < block_size && len < block.len() && block[len] != 0 {
+                len
+            } else {
+                block_size.max(len)
+            };
+            (Cow::Borrowed(&block[..len]), size_in_file)
+        };
+
+        let restart_count = usize::from(u16::from_be_bytes([data[len - 2], data[len - 1]]));
+        let restarts_start = (len - 2)
+            .checked_sub(restart_count * 3)
+            .filter(|start| *start >= records_start && restart_count > 0)
+            .ok_or_else(|| corrupt("invalid amount of restart points"))?;
+        Ok(Some(Block {
+            kind,
+            size_in_file,
+            offset,
+            data,
+            records_start,
+            restarts_start,
+            restart_count,
+            pos: records_start,
+            key: Vec::new(),
+            pending: None,
+        }))
+    }
+
+    /// The offset of the block within its table.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The key of the record most recently returned by [`next_record()`](Self::next_record()).
+    pub(crate) fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Return the bytes of a value previously returned as `range` by [`next_record()`](Self::next_record()).
+    pub(crate) fn value(&self, range: Range<usize>) -> &[u8] {
+        &self.data[range]
+    }
+
+    /// Decode the next record and return its value type and the range of its value, or `None` if there is no more record.
+    /// Its key is available via [`key()`](Self::key()).
+    pub(crate) fn next_record(&mut self, hash_len: usize) -> Result<Option<(u8, Range<usize>)>, Error> {
+        if let Some(pending) = self.pending.take() {
+            return Ok(Some(pending));
+        }
+        if self.pos >= self.restarts_start {
+            return Ok(None);
+        }
+        let offset = self.offset;
+        let corrupt = |message| Error::CorruptBlock { offset, message };
+        let data = &self.data[..self.restarts_start];
+        let mut pos = self.pos;
+        let mut read_varint = |what| {
+            let (value, consumed) = varint::decode(&data[pos..]).ok_or_else(|| corrupt(what))?;
+            pos += consumed;
+            usize::try_from(value).map_err(|_| corrupt(what))
+        };
+        let prefix_len = read_varint("invalid key prefix length")?;
+        let suffix_and_type = read_varint("invalid key suffix length")?;
+        let (suffix_len, value_type) = (suffix_and_type >> 3, (suffix_and_type & 0x7) as u8);
+        if prefix_len > self.key.len() {
+            return Err(corrupt("the key prefix is longer than the previous key"));
+        }
+        let suffix = data
+            .get(pos..pos + suffix_len)
+            .ok_or_else(|| corrupt("the key suffix is truncated"))?;
+        self.key.truncate(prefix_len);
+        self.key.extend_from_slice(suffix);
+        pos += suffix_len;
+
+        let value_len = value_len(self.kind, value_type, &data[pos..], hash_len)
+            .filter(|len| pos + len <= data.len())
+            .ok_or_else(|| corrupt("the record value is invalid or truncated"))?;
+        self.pos = pos + value_len;
+        Ok(Some((value_type, pos..self.pos)))
+    }
+
+    /// Position ourselves so that the next call to [`next_record()`](Self::next_record()) returns the first record
+    /// with a key equal to or greater than `want`, if there is one.
+    pub(crate) fn seek(&mut self, want: &[u8], hash_len: usize) -> Result<(), Error> {
+        let (mut low, mut high) = (0, self.restart_count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.restart_key(mid)? <= want {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        self.pos = if low == 0 {
+            self.records_start
+        } else {
+            self.restart_offset(low - 1)?
+        };
+        self.key.clear();
+        self.pending = None;
+        while let Some(record) = self.next_record(hash_len)? {
+            if self.key.as_slice() >= want {
+                self.pending = Some(record);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn restart_offset(&self, idx: usize) -> Result<usize, Error> {
+        let offset = be24(&self.data[self.restarts_start + idx * 3..]);
+        if offset < self.records_start || offset >= self.restarts_start {
+            return Err(Error::CorruptBlock {
+                offset: self.offset,
+                message: "a restart point is out of bounds",
+            });
+        }
+        Ok(offset)
+    }
+
+    /// Return the full key of the record at restart point `idx`, as these are never prefix-compressed.
+    fn restart_key(&self, idx: usize) -> Result<&[u8], Error> {
+        let corrupt = || Error::CorruptBlock {
+            offset: self.offset,
+            message: "invalid record at restart point",
+        };
+        let mut pos = self.restart_offset(idx)?;
+        let data = &self.data[..self.restarts_start];
+        let (prefix_len, consumed) = varint::decode(&data[pos..]).ok_or_else(corrupt)?;
+        if prefix_len != 0 {
+            return Err(corrupt());
+        }
+        pos += consumed;
+        let (suffix_and_type, consumed) = varint::decode(&data[pos..]).ok_or_else(corrupt)?;
+        pos += consumed;
+        let suffix_len = usize::try_from(suffix_and_type >> 3).map_err(|_| corrupt())?;
+        data.get(pos..pos + suffix_len).ok_or_else(corrupt)
+    }
+}
+
+/// Return the length of the value of type `value_type` at the beginning of `data` in a block of `kind`.
+fn value_len(kind: u8, value_type: u8, data: &[u8], hash_len: usize) -> Option<usize> {
+    let varint_len = |pos: usize| varint::decode(data.get(pos..)?).map(|(_, consumed)| consumed);
+    let string_len = |pos: usize| {
+        let (len, consumed) = varint::decode(data.get(pos..)?)?;
+        consumed.checked_add(usize::try_from(len).ok()?)
+    };
+    Some(match kind {
+        REF => {
+            let pos = varint_len(0)?;
+            pos + match value_type {
+                0 => 0,
+                1 => hash_len,
+                2 => 2 * hash_len,
+                3 => string_len(pos)?,
+                _ => return None,
+            }
+        }
+        LOG => match value_type {
+            0 => 0,
+            1 => {
+                let mut pos = 2 * hash_len;
+                pos += string_len(pos)?;
+                pos += string_len(pos)?;
+                pos += varint_len(pos)?;
+                pos += 2;
+                pos + string_len(pos)?
+            }
+            _ => return None,
+        },
+        INDEX => varint_len(0)?,
+        OBJ => {
+            let (count, mut pos) = if value_type == 0 {
+                let (count, consumed) = varint::decode(data)?;
+                (count, consumed)
+            } else {
+                (u64::from(value_type), 0)
+            };
+            for _ in 0..count {
+                pos += varint_len(pos)?;
+            }
+            pos
+        }
+        _ => return None,
+    })
+}
+
+/// Build a block by adding records in order.
+pub(crate) struct Writer {
+    pub(crate) kind: u8,
+    buf: Vec<u8>,
+    header_len: usize,
+    block_size: usize,
+    restart_interval: usize,
+    restarts: Vec<usize>,
+    entries: usize,
+    last_key: Vec<u8>,
+}
+
+impl Writer {
+    /// Create a new block of `kind` that reserves `header_len` bytes for the file header, and which may not grow
+    /// larger than `block_size` bytes before compression.
+    pub(crate) fn new(kind: u8, header_len: usize, block_size: usize, restart_interval: usize) -> Self {
+        let mut buf = Vec::with_capacity(block_size);
+        buf.resize(header_len, 0);
+        buf.extend_from_slice(&[kind, 0, 0, 0]);
+        Writer {
+            kind,
+            buf,
+            header_len,
+            block_size,
+            restart_interval: restart_interval.max(1),
+            restarts: Vec::new(),
+            entries: 0,
+            last_key: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    pub(crate) fn last_key(&self) -> &[u8] {
+        &self.last_key
+    }
+
+    /// Add a record with `key`, `value_type` and `value`, or return `false` if it doesn't fit into the block anymore.
+    pub(crate) fn add(&mut self, key: &[u8], value_type: u8, value: &[u8]) -> bool {
+        let is_restart = self.entries % self.restart_interval == 0 && self.restarts.len() < usize::from(u16::MAX);
+        let prefix_len = if is_restart {
+            0
+        } else {
+            key.iter().zip(&self.last_key).take_while(|(a, b)| a == b).count()
+        };
+        let start = self.buf.len();
+        varint::encode(prefix_len as u64, &mut self.buf);
+        varint::encode(
+            (((key.len() - prefix_len) as u64) << 3) | u64::from(value_type),
+            &mut self.buf,
+        );
+        self.buf.extend_from_slice(&key[prefix_len..]);
+        self.buf.extend_from_slice(value);
+
+        let restarts = self.restarts.len() + usize::from(is_restart);
+        if self.buf.len() + restarts * 3 + 2 > self.block_size {
+            self.buf.truncate(start);
+            return false;
+        }
+        if is_restart {
+            self.restarts.push(start);
+        }
+        self.entries += 1;
+        self.last_key.clear();
+        self.last_key.extend_from_slice(key);
+        true
+    }
+
+    /// Finish the block and return its bytes, with the space for the file header left zeroed.
+    pub(crate) fn finish(mut self) -> std::io::Result<Vec<u8>> {
+        for offset in &self.restarts {
+            let mut bytes = [0; 3];
+            put_be24(*offset, &mut bytes);
+            self.buf.extend_from_slice(&bytes);
+        }
+        self.buf.extend_from_slice(&(self.restarts.len() as u16).to_be_bytes());
+        let len = self.buf.len();
+        put_be24(len, &mut self.buf[self.header_len + 1..]);
+
+        if self.kind != LOG {
+            return Ok(self.buf);
+        }
+        use std::io::Write;
+        let records_start = self.header_len + HEADER_LEN;
+        let mut out = gix_features::zlib::stream::deflate::Write::new(self.buf[..records_start].to_vec());
+        out.write_all(&self.buf[records_start..])?;
+        out.flush()?;
+        Ok(out.into_inner())
+    }
+}
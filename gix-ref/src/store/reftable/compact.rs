@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use crate::store_impl::reftable::{
+    init::commit_table_names, iter::Merged, table::footer_len, write, LogRecord, LogValue, RefRecord, RefValue, Stack,
+    Table, Tables,
+};
+
+/// The error returned by [`Stack::compact()`] and [`Stack::auto_compact()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The lock for the reftable stack could not be obtained")]
+    LockAcquire(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    Open(#[from] crate::store_impl::reftable::open::Error),
+    #[error(transparent)]
+    Decode(#[from] crate::store_impl::reftable::decode::Error),
+    #[error("The compacted table could not be created")]
+    Write(#[from] write::Error),
+    #[error("The compacted table could not be written or added to the stack")]
+    Io(#[from] std::io::Error),
+}
+
+/// Compaction
+impl Stack {
+    /// Merge all tables of the stack into a single one, which also removes all deleted references and log entries,
+    /// and return `true` if there was more than one table to merge.
+    ///
+    /// Use `lock_fail_mode` to control how long to wait for the stack to become available if it's locked by a concurrent transaction.
+    pub fn compact(&self, lock_fail_mode: gix_lock::acquire::Fail) -> Result<bool, Error> {
+        self.compact_with(lock_fail_mode, |tables| (tables.len() > 1).then_some(0..tables.len()))
+    }
+
+    /// Merge only as many of the newest tables as needed to keep table sizes in a geometric sequence, and return `true`
+    /// if tables were merged.
+    ///
+    /// This is what happens after each transaction if [auto-compaction](Stack::auto_compaction) is enabled, and fails
+    /// immediately if the stack is locked.
+    pub fn auto_compact(&self) -> Result<bool, Error> {
+        self.compact_with(gix_lock::acquire::Fail::Immediately, |tables| {
+            let sizes: Vec<_> = tables.iter().map(Table::size_of_records).collect();
+            suggest_compaction_segment(&sizes, 2)
+        })
+    }
+
+    fn compact_with(
+        &self,
+        lock_fail_mode: gix_lock::acquire::Fail,
+        segment: impl FnOnce(&[Table]) -> Option<Range<usize>>,
+    ) -> Result<bool, Error> {
+        let lock = self.lock(lock_fail_mode)?;
+        let Some(tables) = self.load_tables()? else {
+            return Ok(false);
+        };
+        let Some(range) = segment(tables.tables()) else {
+            return Ok(false);
+        };
+        let table = self.merge(&tables, range.clone())?;
+        let segment_tables = &tables.tables()[range.clone()];
+        let update_indices =
+            segment_tables[0].min_update_index()..=segment_tables[segment_tables.len() - 1].max_update_index();
+        let name = self.write_table(&table, update_indices)?;
+
+        let names = tables.names();
+        commit_table_names(
+            lock,
+            names[..range.start]
+                .iter()
+                .map(String::as_str)
+                .chain(Some(name.as_str()))
+                .chain(names[range.end..].iter().map(String::as_str)),
+        )?;
+        for name in &names[range] {
+            // Readers may still use the table, in which case it's collected during the next compaction.
+            std::fs::remove_file(self.dir.join(name)).ok();
+        }
+        self.force_refresh_tables().ok();
+        Ok(true)
+    }
+
+    /// Merge the tables in `range` into a single table and return it.
+    fn merge(&self, tables: &Tables, range: Range<usize>) -> Result<Vec<u8>, Error> {
+        // Deletions only need to shadow records in older tables, so they can go once there are none.
+        let keep_deletions = range.start != 0;
+        let segment = &tables.tables()[range];
+
+        let refs = Merged::new(segment.iter().map(Table::refs), |a: &RefRecord, b: &RefRecord| {
+            a.name.cmp(&b.name)
+        })
+        .filter(|r| {
+            keep_deletions
+                || !matches!(
+                    r,
+                    Ok(RefRecord {
+                        value: RefValue::Deletion,
+                        ..
+                    })
+                )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        let logs = Merged::new(segment.iter().map(Table::logs), |a: &LogRecord, b: &LogRecord| {
+            a.name.cmp(&b.name).then(b.update_index.cmp(&a.update_index))
+        })
+        .filter(|r| {
+            keep_deletions
+                || !matches!(
+                    r,
+                    Ok(LogRecord {
+                        value: LogValue::Deletion,
+                        ..
+                    })
+                )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let update_indices = segment[0].min_update_index()..=segment[segment.len() - 1].max_update_index();
+        Ok(write::table(
+            self.object_hash(),
+            update_indices,
+            refs,
+            logs,
+            &self.write_options,
+        )?)
+    }
+}
+
+impl Table {
+    /// The size of the table without header and footer.
+    fn size_of_records(&self) -> u64 {
+        (self.data.len() - self.header_len - footer_len(self.header_len)) as u64
+    }
+}
+
+/// Return the range of tables to merge to restore a geometric sequence of table sizes with the given `factor`, like git does,
+/// or `None` if no compaction is needed.
+fn suggest_compaction_segment(sizes: &[u64], factor: u64) -> Option<Range<usize>> {
+    // Find the newest table which is too large compared to its predecessor.
+    let end = (1..sizes.len()).rev().find(|&i| sizes[i - 1] < sizes[i] * factor)? + 1;
+    let mut bytes = sizes[end - 1];
+    let mut start = end - 1;
+    for i in (1..end).rev() {
+        let current = bytes;
+        bytes += sizes[i - 1];
+        if sizes[i - 1] < current * factor {
+            start = i - 1;
+        }
+    }
+    (end - start > 1).then_some(start..end)
+}
@@ -0,0 +1,156 @@
+pub use error::Error;
+
+use crate::{
+    bstr::BString,
+    store_impl::reftable::{RefRecord, RefValue, Stack, Tables},
+    FullName, FullNameRef, PartialNameRef, Reference, Target,
+};
+
+/// ### Finding References
+impl Stack {
+    /// Find a single reference by the given `partial` name, which is required to be a valid reference name.
+    ///
+    /// Returns `Ok(None)` if no such ref exists.
+    ///
+    /// The lookup algorithm follows the one in [the git documentation][git-lookup-docs], just like
+    /// [`file::Store::try_find()`](crate::file::Store::try_find()).
+    ///
+    /// [git-lookup-docs]: https://github.com/git/git/blob/5d5b1473453400224ebb126bf3947e0a3276bdf5/Documentation/revisions.txt#L34-L46
+    pub fn try_find<'a, Name, E>(&self, partial: Name) -> Result<Option<Reference>, Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        Error: From<E>,
+    {
+        let partial = partial.try_into()?;
+        let Some(tables) = self.tables()? else {
+            return Ok(None);
+        };
+        let mut buf = BString::default();
+        for inbetween in &["", "tags", "heads", "remotes"] {
+            let full_name = partial.construct_full_name_ref(inbetween, &mut buf);
+            if let Some(r) = self.find_in(&tables, full_name)? {
+                return Ok(Some(r));
+            }
+        }
+        if partial.as_bstr() == "HEAD" {
+            return Ok(None);
+        }
+        let partial = partial.to_owned().join("HEAD".into()).expect("HEAD is valid name");
+        let full_name = partial.as_ref().construct_full_name_ref("remotes", &mut buf);
+        self.find_in(&tables, full_name)
+    }
+
+    /// Find the reference with `name` in `tables`, after adjusting it to our namespace.
+    pub(crate) fn find_in(&self, tables: &Tables, name: &FullNameRef) -> Result<Option<Reference>, Error> {
+        let namespaced;
+        let name = match &self.namespace {
+            Some(namespace) => {
+                namespaced = namespace.to_owned().into_namespaced_name(name);
+                namespaced.as_ref()
+            }
+            None => name,
+        };
+        for table in tables.tables().iter().rev() {
+            if let Some(record) = table.find_ref(name.as_bstr())? {
+                return self.to_reference(record);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Turn `record` into a reference outside of our namespace, or return `None` if it's a deletion.
+    pub(crate) fn to_reference(&self, record: RefRecord) -> Result<Option<Reference>, Error> {
+        let name =
+            |name: BString| FullName::try_from(name.clone()).map_err(|source| Error::InvalidRecord { name, source });
+        let (target, peeled) = match record.value {
+            RefValue::Deletion => return Ok(None),
+            RefValue::Object(id) => (Target::Object(id), None),
+            RefValue::Peeled { target, peeled } => (Target::Object(target), Some(peeled)),
+            RefValue::Symbolic(target) => (Target::Symbolic(name(target)?), None),
+        };
+        let mut r = Reference {
+            name: name(record.name)?,
+            target,
+            peeled,
+        };
+        if let Some(namespace) = &self.namespace {
+            r.strip_namespace(namespace);
+        }
+        Ok(Some(r))
+    }
+}
+
+mod error {
+    use std::convert::Infallible;
+
+    use crate::{bstr::BString, store_impl::reftable};
+
+    /// The error returned by [`reftable::Stack::try_find()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The ref name or path is not a valid ref name")]
+        RefnameValidation(#[from] crate::name::Error),
+        #[error(transparent)]
+        Open(#[from] reftable::open::Error),
+        #[error(transparent)]
+        Decode(#[from] reftable::decode::Error),
+        #[error("The reference name {name:?} in a reftable record is invalid")]
+        InvalidRecord {
+            name: BString,
+            source: gix_validate::reference::name::Error,
+        },
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_: Infallible) -> Self {
+            unreachable!("this impl is needed to allow passing a known valid partial path as parameter")
+        }
+    }
+}
+
+///
+pub mod existing {
+    pub use error::Error;
+
+    use crate::{
+        store_impl::reftable::{find, Stack},
+        PartialNameRef, Reference,
+    };
+
+    impl Stack {
+        /// Similar to [`Stack::try_find()`] but a non-existing ref is treated as error.
+        pub fn find<'a, Name, E>(&self, partial: Name) -> Result<Reference, Error>
+        where
+            Name: TryInto<&'a PartialNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let path = partial
+                .try_into()
+                .map_err(|err| Error::Find(find::Error::RefnameValidation(err.into())))?;
+            match self.try_find(path) {
+                Ok(Some(r)) => Ok(r),
+                Ok(None) => Err(Error::NotFound {
+                    name: path.to_partial_path().to_owned(),
+                }),
+                Err(err) => Err(err.into()),
+            }
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        use crate::store_impl::reftable::find;
+
+        /// The error returned by [`Stack::find()`](crate::reftable::Stack::find()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("An error occurred while trying to find a reference")]
+            Find(#[from] find::Error),
+            #[error("The ref partially named {name:?} could not be found")]
+            NotFound { name: PathBuf },
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    io,
+    io::Write,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use gix_features::threading::OwnShared;
+
+use crate::store_impl::reftable::{open, write, Stack, Table, Tables};
+
+/// An up-to-date snapshot of the tables of a [`Stack`].
+pub type SharedTables = gix_fs::SharedFileSnapshot<Tables>;
+
+impl Stack {
+    /// Create a new instance for the reftable stack of the repository at `git_dir`, typically the `.git/` directory.
+    ///
+    /// Note that the stack doesn't have to exist yet, it will be created with the first transaction.
+    /// Only [`write_reflog`](crate::store::init::Options::write_reflog) and [`object_hash`](crate::store::init::Options::object_hash)
+    /// of `opts` are relevant.
+    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Self {
+        Stack {
+            dir: git_dir.join("reftable"),
+            object_hash: opts.object_hash,
+            write_reflog: opts.write_reflog,
+            namespace: None,
+            auto_compaction: true,
+            write_options: write::Options::default(),
+            tables: OwnShared::new(gix_fs::SharedFileSnapshotMut::new()),
+        }
+    }
+}
+
+/// Access
+impl Stack {
+    /// Return the directory holding the tables and the `tables.list` file.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The kind of hash used by all tables of this stack.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return the path of the file that lists all tables of the stack.
+    pub fn tables_list_path(&self) -> PathBuf {
+        self.dir.join("tables.list")
+    }
+
+    /// Return a possibly cached snapshot of all tables, or `None` if the stack doesn't exist yet.
+    ///
+    /// The snapshot is reloaded once `tables.list` changes, but won't change itself so it can be used
+    /// for consistent lookups.
+    pub fn tables(&self) -> Result<Option<SharedTables>, open::Error> {
+        self.tables.recent_snapshot(
+            || self.tables_list_path().metadata().and_then(|m| m.modified()).ok(),
+            || self.load_tables(),
+        )
+    }
+
+    /// Reload the tables from disk, and update our cached snapshot with them.
+    pub(crate) fn force_refresh_tables(&self) -> Result<(), open::Error> {
+        self.tables.force_refresh(|| {
+            let modified = self.tables_list_path().metadata()?.modified()?;
+            Ok(self.load_tables()?.map(|tables| (modified, tables)))
+        })
+    }
+
+    /// Load all tables listed in `tables.list`, or return `None` if it doesn't exist.
+    pub(crate) fn load_tables(&self) -> Result<Option<Tables>, open::Error> {
+        // Tables may be removed by a concurrent compaction after we read the list, so try again with a newer list.
+        let mut attempts = 0;
+        loop {
+            let names = match std::fs::read(self.tables_list_path()) {
+                Ok(list) => parse_table_names(&list),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            let tables: Result<Vec<_>, _> = names.iter().map(|name| Table::at(&self.dir.join(name))).collect();
+            match tables {
+                Ok(tables) => return Ok(Some(Tables { names, tables })),
+                Err(open::Error::Io(err)) if err.kind() == io::ErrorKind::NotFound && attempts < 3 => {
+                    attempts += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Persisting tables
+impl Stack {
+    /// Lock `tables.list` to prevent concurrent modifications of the stack, creating our directory if needed.
+    pub(crate) fn lock(&self, fail_mode: gix_lock::acquire::Fail) -> Result<gix_lock::File, gix_lock::acquire::Error> {
+        gix_lock::File::acquire_to_update_resource(
+            self.tables_list_path(),
+            fail_mode,
+            self.dir.parent().map(ToOwned::to_owned),
+        )
+    }
+
+    /// Write `table` with updates in `update_indices` into a new file in our directory and return its name.
+    pub(crate) fn write_table(&self, table: &[u8], update_indices: RangeInclusive<u64>) -> io::Result<String> {
+        use std::hash::{BuildHasher, Hasher};
+        // Like git, add a random suffix so that tables with the same update indices never clash.
+        let suffix = std::collections::hash_map::RandomState::new().build_hasher().finish() as u32;
+        let name = format!(
+            "0x{:012x}-0x{:012x}-{suffix:08x}.ref",
+            update_indices.start(),
+            update_indices.end()
+        );
+        let mut lock = gix_lock::File::acquire_to_update_resource(
+            self.dir.join(&name),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )
+        .map_err(|err| io::Error::new(io::ErrorKind::AlreadyExists, err))?;
+        lock.with_mut(|file| file.write_all(table))?;
+        lock.commit().map_err(|err| err.error)?;
+        Ok(name)
+    }
+}
+
+/// Write all `names` into the locked `tables.list` file and commit it.
+pub(crate) fn commit_table_names<'a>(
+    mut lock: gix_lock::File,
+    names: impl IntoIterator<Item = &'a str>,
+) -> io::Result<()> {
+    lock.with_mut(|file| {
+        for name in names {
+            writeln!(file, "{name}")?;
+        }
+        Ok(())
+    })?;
+    lock.commit().map_err(|err| err.error)?;
+    Ok(())
+}
+
+pub(crate) fn parse_table_names(list: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(list)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Access
+impl Tables {
+    /// Return the names of all tables, from the oldest to the newest.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Return all tables, from the oldest to the newest.
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Return the update index to use for the next table added on top of these tables.
+    pub fn next_update_index(&self) -> u64 {
+        self.tables.last().map_or(1, |table| table.max_update_index() + 1)
+    }
+}
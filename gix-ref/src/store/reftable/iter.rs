@@ -0,0 +1,146 @@
+use std::{borrow::Cow, cmp::Ordering, iter::Fuse};
+
+use crate::{
+    bstr::{BStr, BString},
+    store_impl::reftable::{decode, find, open, table, RefRecord, RefValue, SharedTables, Stack},
+    Reference,
+};
+
+/// An intermediate structure to hold shared state alive long enough for iteration to happen.
+#[must_use = "Iterators should be obtained from this platform"]
+pub struct Platform<'s> {
+    stack: Cow<'s, Stack>,
+    tables: Option<SharedTables>,
+}
+
+impl Stack {
+    /// Return a platform to obtain iterators over all references, or proper errors in case the tables can't be loaded.
+    pub fn iter(&self) -> Result<Platform<'_>, open::Error> {
+        Platform::new(Cow::Borrowed(self))
+    }
+}
+
+impl<'s> Platform<'s> {
+    pub(crate) fn new(stack: Cow<'s, Stack>) -> Result<Self, open::Error> {
+        Ok(Platform {
+            tables: stack.tables()?,
+            stack,
+        })
+    }
+}
+
+impl Platform<'_> {
+    /// Return an iterator over all references, ordered by name.
+    ///
+    /// Like with all other access, only references in our namespace are returned if one is set.
+    pub fn all(&self) -> Iter<'_> {
+        self.prefixed("".into())
+    }
+
+    /// Return an iterator over all references whose full name starts with `prefix`, like `refs/heads/`, ordered by name.
+    pub fn prefixed(&self, prefix: &BStr) -> Iter<'_> {
+        let prefix: BString = match &self.stack.namespace {
+            Some(namespace) => {
+                let mut namespaced = namespace.as_bstr().to_owned();
+                namespaced.extend_from_slice(prefix);
+                namespaced
+            }
+            None => prefix.to_owned(),
+        };
+        Iter {
+            stack: &self.stack,
+            inner: Merged::new(
+                self.tables
+                    .iter()
+                    .flat_map(|tables| tables.tables())
+                    .map(|table| table.refs_prefixed(prefix.as_ref())),
+                |a: &RefRecord, b: &RefRecord| a.name.cmp(&b.name),
+            ),
+        }
+    }
+}
+
+/// An iterator over the references of all tables of a [`Stack`], with records of newer tables
+/// shadowing those of older ones.
+pub struct Iter<'a> {
+    stack: &'a Stack,
+    inner: Merged<table::RefIter<'a>, RefRecord>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Reference, find::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.inner.next()? {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err.into())),
+            };
+            if record.value == RefValue::Deletion {
+                continue;
+            }
+            return self.stack.to_reference(record).transpose();
+        }
+    }
+}
+
+/// Merges the sorted records of multiple tables, passed from the oldest to the newest, into one sorted stream.
+///
+/// Of all records which compare equal, only the one of the newest table is returned.
+pub(crate) struct Merged<I, T> {
+    iters: Vec<Fuse<I>>,
+    heads: Vec<Option<T>>,
+    cmp: fn(&T, &T) -> Ordering,
+}
+
+impl<I, T> Merged<I, T>
+where
+    I: Iterator<Item = Result<T, decode::Error>>,
+{
+    pub(crate) fn new(iters: impl IntoIterator<Item = I>, cmp: fn(&T, &T) -> Ordering) -> Self {
+        let iters: Vec<_> = iters.into_iter().map(Iterator::fuse).collect();
+        Merged {
+            heads: iters.iter().map(|_| None).collect(),
+            iters,
+            cmp,
+        }
+    }
+}
+
+impl<I, T> Iterator for Merged<I, T>
+where
+    I: Iterator<Item = Result<T, decode::Error>>,
+{
+    type Item = Result<T, decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (head, iter) in self.heads.iter_mut().zip(self.iters.iter_mut()) {
+            if head.is_none() {
+                match iter.next() {
+                    Some(Ok(record)) => *head = Some(record),
+                    Some(Err(err)) => return Some(Err(err)),
+                    None => {}
+                }
+            }
+        }
+        let cmp = self.cmp;
+        let mut newest_smallest: Option<usize> = None;
+        for (idx, head) in self.heads.iter().enumerate() {
+            let Some(record) = head else { continue };
+            match newest_smallest {
+                Some(best) if cmp(record, self.heads[best].as_ref().expect("set")) == Ordering::Greater => {}
+                _ => newest_smallest = Some(idx),
+            }
+        }
+        let record = self.heads[newest_smallest?].take().expect("set");
+        for head in &mut self.heads {
+            if head
+                .as_ref()
+                .map_or(false, |other| cmp(other, &record) == Ordering::Equal)
+            {
+                *head = None;
+            }
+        }
+        Some(Ok(record))
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    store_impl::reftable::{decode, iter::Merged, open, LogRecord, LogValue, Stack, Tables},
+    FullNameRef,
+};
+
+/// The error returned when accessing the reflog of a [`Stack`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Open(#[from] open::Error),
+    #[error(transparent)]
+    Decode(#[from] decode::Error),
+}
+
+/// ### Reference Logs
+impl Stack {
+    /// Returns `true` if the reference named `name` has at least one reflog entry.
+    pub fn reflog_exists(&self, name: &FullNameRef) -> Result<bool, Error> {
+        Ok(self.reflog(name)?.is_some())
+    }
+
+    /// Return all reflog entries of the reference named `name`, from the oldest to the newest,
+    /// or `None` if there is none.
+    pub fn reflog(&self, name: &FullNameRef) -> Result<Option<Vec<crate::log::Line>>, Error> {
+        let Some(tables) = self.tables()? else {
+            return Ok(None);
+        };
+        let lines: Vec<_> = self
+            .log_records(&tables, name)?
+            .into_iter()
+            .rev()
+            .filter_map(|record| match record.value {
+                LogValue::Update(line) => Some(line),
+                LogValue::Deletion => None,
+            })
+            .collect();
+        Ok((!lines.is_empty()).then_some(lines))
+    }
+
+    /// Return all existing log records of the reference named `name` in `tables`, from the newest to the oldest,
+    /// with `name` being adjusted to our namespace.
+    pub(crate) fn log_records(&self, tables: &Tables, name: &FullNameRef) -> Result<Vec<LogRecord>, decode::Error> {
+        let namespaced;
+        let name = match &self.namespace {
+            Some(namespace) => {
+                namespaced = namespace.to_owned().into_namespaced_name(name);
+                namespaced.as_ref()
+            }
+            None => name,
+        };
+        Merged::new(
+            tables.tables().iter().map(|table| table.logs_of(name.as_bstr())),
+            |a: &LogRecord, b: &LogRecord| b.update_index.cmp(&a.update_index),
+        )
+        .filter(|record| {
+            !matches!(
+                record,
+                Ok(LogRecord {
+                    value: LogValue::Deletion,
+                    ..
+                })
+            )
+        })
+        .collect()
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use gix_features::threading::OwnShared;
+use gix_hash::ObjectId;
+
+use crate::{bstr::BString, store::WriteReflog, Namespace};
+
+/// A store for references which keeps them along with their logs in a stack of [reftables](https://git-scm.com/docs/reftable),
+/// as used in repositories with `extensions.refStorage = reftable`.
+///
+/// The stack is described by the `tables.list` file in the `reftable` directory, which lists all tables from the oldest to the newest.
+/// Each transaction adds a new table with all of its changes, and newer tables shadow the records of older ones.
+/// To keep lookups fast, tables are merged with each other from time to time, a process called _compaction_.
+///
+/// Note that per-worktree references of linked worktrees, which are kept in their own stack, aren't supported yet.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    /// The directory holding `tables.list` and all tables, typically `.git/reftable`.
+    dir: PathBuf,
+    /// The kind of hash used in all tables.
+    object_hash: gix_hash::Kind,
+    /// The way to handle reflog edits
+    pub write_reflog: WriteReflog,
+    /// The namespace to use for edits and reads
+    pub namespace: Option<Namespace>,
+    /// If `true`, tables are merged after each transaction to keep their sizes in a geometric sequence,
+    /// similar to `reftable.autoCompaction`.
+    pub auto_compaction: bool,
+    /// The options to use when writing tables.
+    pub write_options: write::Options,
+    /// The most recently loaded tables, which are reloaded once `tables.list` changes.
+    tables: OwnShared<gix_fs::SharedFileSnapshotMut<Tables>>,
+}
+
+/// The bytes of a [`Table`], which are either loaded entirely in memory or mapped from the file on disk.
+enum Backing {
+    InMemory(Vec<u8>),
+    Mapped(memmap2::Mmap),
+}
+
+/// A single, immutable reftable, holding references and their logs.
+pub struct Table {
+    data: Backing,
+    object_hash: gix_hash::Kind,
+    header_len: usize,
+    block_size: usize,
+    min_update_index: u64,
+    max_update_index: u64,
+    ref_index_position: u64,
+    log_position: u64,
+    log_index_position: u64,
+    has_refs: bool,
+    has_logs: bool,
+}
+
+/// All tables of a [`Stack`] at the time they were loaded, from the oldest to the newest.
+#[derive(Debug, Default)]
+pub struct Tables {
+    names: Vec<String>,
+    tables: Vec<Table>,
+}
+
+/// A reference as stored in a [`Table`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct RefRecord {
+    /// The full name of the reference.
+    pub name: BString,
+    /// The index of the update which wrote this record, which is within the range of update indices of its table.
+    pub update_index: u64,
+    /// The value of the reference.
+    pub value: RefValue,
+}
+
+/// The value of a [`RefRecord`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum RefValue {
+    /// The reference was deleted, shadowing records of older tables.
+    Deletion,
+    /// The reference points to an object.
+    Object(ObjectId),
+    /// The reference points to an annotated tag `target`, which peels to the object `peeled`.
+    Peeled {
+        /// The object the reference points to.
+        target: ObjectId,
+        /// The object `target` ultimately points to.
+        peeled: ObjectId,
+    },
+    /// The reference points to the reference with the given full name.
+    Symbolic(BString),
+}
+
+/// An entry of a reference log as stored in a [`Table`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct LogRecord {
+    /// The full name of the reference the log entry belongs to.
+    pub name: BString,
+    /// The index of the update which added this entry, which is unique among all entries of the reference.
+    pub update_index: u64,
+    /// The log entry itself.
+    pub value: LogValue,
+}
+
+/// The value of a [`LogRecord`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum LogValue {
+    /// The log entry with the same update index was deleted, shadowing records of older tables.
+    Deletion,
+    /// The reference was changed as described by the log line.
+    Update(crate::log::Line),
+}
+
+///
+pub mod decode {
+    /// The error returned when decoding a [`Table`](super::Table).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The table is too short to be a reftable")]
+        Truncated,
+        #[error("The table doesn't start with the 'REFT' signature")]
+        Signature,
+        #[error("Reftable version {version} is not supported")]
+        UnsupportedVersion { version: u8 },
+        #[error("The hash identified by {id:#010x} is not supported")]
+        UnsupportedHash { id: u32 },
+        #[error("The footer checksum was {actual:#010x}, but should have been {expected:#010x}")]
+        Checksum { expected: u32, actual: u32 },
+        #[error("The footer doesn't repeat the header of the table")]
+        FooterMismatch,
+        #[error("The block at offset {offset} is corrupt: {message}")]
+        CorruptBlock { offset: usize, message: &'static str },
+        #[error("A record is corrupt: {message}")]
+        CorruptRecord { message: &'static str },
+        #[error(transparent)]
+        Inflate(#[from] gix_features::zlib::inflate::Error),
+    }
+}
+
+///
+pub mod open {
+    /// The error returned when loading the tables of a [`Stack`](super::Stack).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the table list or one of its tables")]
+        Io(#[from] std::io::Error),
+        #[error("The table {name:?} could not be decoded")]
+        Decode { name: String, source: super::decode::Error },
+    }
+}
+
+mod block;
+mod varint;
+
+mod init;
+pub use init::SharedTables;
+
+///
+pub mod table;
+
+///
+pub mod write;
+
+///
+pub mod find;
+
+///
+pub mod iter;
+
+///
+pub mod log;
+
+///
+pub mod transaction;
+pub use transaction::Transaction;
+
+///
+pub mod compact;
+
+#[cfg(test)]
+mod tests;
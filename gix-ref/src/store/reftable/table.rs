@@ -0,0 +1,478 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, ByteSlice},
+    log::Line,
+    store_impl::reftable::{
+        block::{self, be24, Block},
+        decode::Error,
+        varint, Backing, LogRecord, LogValue, RefRecord, RefValue, Table,
+    },
+};
+
+impl std::ops::Deref for Backing {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Backing::InMemory(data) => data,
+            Backing::Mapped(map) => map,
+        }
+    }
+}
+
+pub(crate) const SIGNATURE: &[u8; 4] = b"REFT";
+pub(crate) const SHA1_ID: u32 = u32::from_be_bytes(*b"sha1");
+pub(crate) const SHA256_ID: u32 = u32::from_be_bytes(*b"s256");
+
+/// Return the length of the file header for tables of `object_hash`, which determines the table version.
+pub(crate) fn header_len(object_hash: gix_hash::Kind) -> usize {
+    match object_hash {
+        gix_hash::Kind::Sha1 => 24,
+        gix_hash::Kind::Sha256 => 28,
+    }
+}
+
+/// Return the length of the footer for tables with a header of `header_len` bytes.
+pub(crate) fn footer_len(header_len: usize) -> usize {
+    header_len + 5 * 8 + 4
+}
+
+fn be64(data: &[u8]) -> u64 {
+    u64::from_be_bytes(data[..8].try_into().expect("8 bytes"))
+}
+
+/// Lifecycle
+impl Table {
+    /// Map the table at `path` into memory.
+    pub fn at(path: &Path) -> Result<Self, crate::store_impl::reftable::open::Error> {
+        let data = Backing::Mapped(
+            // SAFETY: we have to take the risk of somebody changing the file underneath. Tables are never changed once written.
+            #[allow(unsafe_code)]
+            unsafe {
+                memmap2::MmapOptions::new().map(&std::fs::File::open(path)?)?
+            },
+        );
+        Table::from_backing(data).map_err(|source| crate::store_impl::reftable::open::Error::Decode {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            source,
+        })
+    }
+
+    /// Decode the table in `data` after validating its header and footer.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        Table::from_backing(Backing::InMemory(data))
+    }
+
+    fn from_backing(data: Backing) -> Result<Self, Error> {
+        if data.len() < header_len(gix_hash::Kind::Sha1) {
+            return Err(Error::Truncated);
+        }
+        if &data[..4] != SIGNATURE {
+            return Err(Error::Signature);
+        }
+        let (header_len, object_hash) = match data[4] {
+            1 => (header_len(gix_hash::Kind::Sha1), gix_hash::Kind::Sha1),
+            2 => {
+                let header_len = header_len(gix_hash::Kind::Sha256);
+                let id = u32::from_be_bytes(
+                    data.get(24..header_len)
+                        .ok_or(Error::Truncated)?
+                        .try_into()
+                        .expect("4 bytes"),
+                );
+                let object_hash = match id {
+                    SHA1_ID => gix_hash::Kind::Sha1,
+                    SHA256_ID => gix_hash::Kind::Sha256,
+                    _ => return Err(Error::UnsupportedHash { id }),
+                };
+                (header_len, object_hash)
+            }
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let footer_len = footer_len(header_len);
+        if data.len() < header_len + footer_len {
+            return Err(Error::Truncated);
+        }
+        let blocks_end = data.len() - footer_len;
+        let footer = &data[blocks_end..];
+        if footer[..header_len] != data[..header_len] {
+            return Err(Error::FooterMismatch);
+        }
+        let (footer, checksum) = footer.split_at(footer_len - 4);
+        let expected = u32::from_be_bytes(checksum.try_into().expect("4 bytes"));
+        let actual = gix_features::hash::crc32(footer);
+        if expected != actual {
+            return Err(Error::Checksum { expected, actual });
+        }
+        let fields = &footer[header_len..];
+        let first_block_kind = (blocks_end > header_len).then(|| data[header_len]);
+        let log_position = be64(&fields[24..]);
+        Ok(Table {
+            object_hash,
+            header_len,
+            block_size: be24(&data[5..]),
+            min_update_index: be64(&data[8..]),
+            max_update_index: be64(&data[16..]),
+            ref_index_position: be64(fields),
+            log_position,
+            log_index_position: be64(&fields[32..]),
+            has_refs: first_block_kind == Some(block::REF),
+            has_logs: first_block_kind == Some(block::LOG) || log_position > 0,
+            data,
+        })
+    }
+}
+
+/// Access
+impl Table {
+    /// The kind of hash used for all object ids in the table.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// The smallest update index of all records in the table.
+    pub fn min_update_index(&self) -> u64 {
+        self.min_update_index
+    }
+
+    /// The largest update index of all records in the table.
+    pub fn max_update_index(&self) -> u64 {
+        self.max_update_index
+    }
+
+    /// The size of the table in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return `true` if the table contains neither reference nor log records.
+    pub fn is_empty(&self) -> bool {
+        !self.has_refs && !self.has_logs
+    }
+
+    /// Return the record of the reference with the given full `name`, which may also be a deletion.
+    pub fn find_ref(&self, name: &BStr) -> Result<Option<RefRecord>, Error> {
+        match self.refs_from(name).next().transpose()? {
+            Some(record) if record.name == name => Ok(Some(record)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Return an iterator over all reference records, ordered by name.
+    pub fn refs(&self) -> RefIter<'_> {
+        RefIter {
+            inner: self.section(block::REF, Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Return an iterator over all reference records whose name starts with `prefix`, ordered by name.
+    pub fn refs_prefixed(&self, prefix: &BStr) -> RefIter<'_> {
+        RefIter {
+            inner: self.section(block::REF, prefix.to_vec(), prefix.to_vec()),
+        }
+    }
+
+    /// Return an iterator over all reference records whose name is equal to or greater than `name`.
+    fn refs_from(&self, name: &BStr) -> RefIter<'_> {
+        RefIter {
+            inner: self.section(block::REF, name.to_vec(), Vec::new()),
+        }
+    }
+
+    /// Return an iterator over all log records, ordered by reference name and from the newest to the oldest.
+    pub fn logs(&self) -> LogIter<'_> {
+        LogIter {
+            inner: self.section(block::LOG, Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Return an iterator over all log records of the reference with the given full `name`, from the newest to the oldest.
+    pub fn logs_of(&self, name: &BStr) -> LogIter<'_> {
+        let mut prefix = name.to_vec();
+        prefix.push(0);
+        LogIter {
+            inner: self.section(block::LOG, prefix.clone(), prefix),
+        }
+    }
+
+    fn section(&self, kind: u8, seek: Vec<u8>, prefix: Vec<u8>) -> Section<'_> {
+        let is_present = match kind {
+            block::REF => self.has_refs,
+            _ => self.has_logs,
+        };
+        Section {
+            table: self,
+            kind,
+            state: if is_present { State::Start } else { State::Done },
+            seek,
+            prefix,
+        }
+    }
+
+    fn blocks(&self) -> &[u8] {
+        &self.data[..self.data.len() - footer_len(self.header_len)]
+    }
+
+    fn block_at(&self, offset: usize) -> Result<Option<Block<'_>>, Error> {
+        Block::at(self.blocks(), offset, self.header_len, self.block_size)
+    }
+
+    /// Find the offset of the first block of `kind` which may contain keys equal to or greater than `want`,
+    /// using the index if there is one, or `None` if there is no such block.
+    fn seek_block(&self, kind: u8, want: &[u8]) -> Result<Option<usize>, Error> {
+        let (start, index_position) = match kind {
+            block::REF => (0, self.ref_index_position),
+            _ => (self.log_position, self.log_index_position),
+        };
+        let (Ok(start), Ok(index_position)) = (usize::try_from(start), usize::try_from(index_position)) else {
+            return Err(Error::Truncated);
+        };
+        if index_position == 0 || want.is_empty() {
+            return Ok(Some(start));
+        }
+        let hash_len = self.object_hash.len_in_bytes();
+        let mut offset = index_position;
+        loop {
+            let mut block = self.block_at(offset)?.ok_or(Error::Truncated)?;
+            if block.kind == kind {
+                return Ok(Some(offset));
+            }
+            if block.kind != block::INDEX {
+                return Err(Error::CorruptBlock {
+                    offset,
+                    message: "the index points to a block of unexpected kind",
+                });
+            }
+            // The top-level index may span multiple blocks, but lower levels always contain the key in their first block.
+            loop {
+                block.seek(want, hash_len)?;
+                if let Some((_, range)) = block.next_record(hash_len)? {
+                    offset = varint::decode(block.value(range))
+                        .and_then(|(position, _)| usize::try_from(position).ok())
+                        .ok_or(Error::CorruptRecord {
+                            message: "invalid block position in index record",
+                        })?;
+                    break;
+                }
+                offset += block.size_in_file;
+                match self.block_at(offset)? {
+                    Some(next) if next.kind == block::INDEX => block = next,
+                    _ => return Ok(None),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table")
+            .field("object_hash", &self.object_hash)
+            .field("len", &self.data.len())
+            .field("block_size", &self.block_size)
+            .field("min_update_index", &self.min_update_index)
+            .field("max_update_index", &self.max_update_index)
+            .finish_non_exhaustive()
+    }
+}
+
+enum State<'a> {
+    Start,
+    Block(Block<'a>),
+    NextBlock(usize),
+    Done,
+}
+
+/// An iterator over the raw records of one section of a table.
+struct Section<'a> {
+    table: &'a Table,
+    kind: u8,
+    state: State<'a>,
+    /// The key to seek to in each block until a record was found.
+    seek: Vec<u8>,
+    /// The prefix all returned keys must have.
+    prefix: Vec<u8>,
+}
+
+impl Section<'_> {
+    /// Call `f` with the key, value type and value of the next record.
+    fn next_with<T>(&mut self, f: impl FnOnce(&[u8], u8, &[u8]) -> Result<T, Error>) -> Option<Result<T, Error>> {
+        let hash_len = self.table.object_hash.len_in_bytes();
+        loop {
+            let offset = match std::mem::replace(&mut self.state, State::Done) {
+                State::Done => return None,
+                State::Start => match self.table.seek_block(self.kind, &self.seek) {
+                    Ok(Some(offset)) => offset,
+                    Ok(None) => return None,
+                    Err(err) => return Some(Err(err)),
+                },
+                State::NextBlock(offset) => offset,
+                State::Block(mut block) => {
+                    let record = match block.next_record(hash_len) {
+                        Ok(record) => record,
+                        Err(err) => return Some(Err(err)),
+                    };
+                    match record {
+                        Some((value_type, range)) => {
+                            self.seek.clear();
+                            if !block.key().starts_with(&self.prefix) {
+                                return None;
+                            }
+                            let res = f(block.key(), value_type, block.value(range));
+                            self.state = State::Block(block);
+                            return Some(res);
+                        }
+                        None => {
+                            self.state = State::NextBlock(block.offset() + block.size_in_file);
+                            continue;
+                        }
+                    }
+                }
+            };
+            let mut block = match self.table.block_at(offset) {
+                Ok(Some(block)) if block.kind == self.kind => block,
+                Ok(_) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+            if !self.seek.is_empty() {
+                if let Err(err) = block.seek(&self.seek, hash_len) {
+                    return Some(Err(err));
+                }
+            }
+            self.state = State::Block(block);
+        }
+    }
+}
+
+/// An iterator over the [reference records](RefRecord) of a [`Table`].
+pub struct RefIter<'a> {
+    inner: Section<'a>,
+}
+
+impl Iterator for RefIter<'_> {
+    type Item = Result<RefRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let table = self.inner.table;
+        self.inner
+            .next_with(|key, value_type, value| decode_ref(table, key, value_type, value))
+    }
+}
+
+/// An iterator over the [log records](LogRecord) of a [`Table`].
+pub struct LogIter<'a> {
+    inner: Section<'a>,
+}
+
+impl Iterator for LogIter<'_> {
+    type Item = Result<LogRecord, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let object_hash = self.inner.table.object_hash;
+        self.inner
+            .next_with(|key, value_type, value| decode_log(object_hash, key, value_type, value))
+    }
+}
+
+fn corrupt(message: &'static str) -> Error {
+    Error::CorruptRecord { message }
+}
+
+/// A cursor to read the fields of a record value.
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn varint(&mut self) -> Result<u64, Error> {
+        let (value, consumed) = varint::decode(self.0).ok_or_else(|| corrupt("invalid varint"))?;
+        self.0 = &self.0[consumed..];
+        Ok(value)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(corrupt("the value is truncated"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.varint()?;
+        self.bytes(usize::try_from(len).map_err(|_| corrupt("invalid string length"))?)
+    }
+
+    fn id(&mut self, object_hash: gix_hash::Kind) -> Result<ObjectId, Error> {
+        Ok(ObjectId::from_bytes_or_panic(self.bytes(object_hash.len_in_bytes())?))
+    }
+}
+
+fn decode_ref(table: &Table, key: &[u8], value_type: u8, value: &[u8]) -> Result<RefRecord, Error> {
+    let mut fields = Fields(value);
+    let update_index = table
+        .min_update_index
+        .checked_add(fields.varint()?)
+        .ok_or_else(|| corrupt("the update index overflows"))?;
+    let value = match value_type {
+        0 => RefValue::Deletion,
+        1 => RefValue::Object(fields.id(table.object_hash)?),
+        2 => RefValue::Peeled {
+            target: fields.id(table.object_hash)?,
+            peeled: fields.id(table.object_hash)?,
+        },
+        3 => RefValue::Symbolic(fields.string()?.into()),
+        _ => return Err(corrupt("unknown reference value type")),
+    };
+    Ok(RefRecord {
+        name: key.into(),
+        update_index,
+        value,
+    })
+}
+
+fn decode_log(object_hash: gix_hash::Kind, key: &[u8], value_type: u8, value: &[u8]) -> Result<LogRecord, Error> {
+    let (name, update_index) = key
+        .len()
+        .checked_sub(9)
+        .filter(|name_len| key[*name_len] == 0)
+        .map(|name_len| (&key[..name_len], u64::MAX - be64(&key[name_len + 1..])))
+        .ok_or_else(|| corrupt("invalid log record key"))?;
+    let value = match value_type {
+        0 => LogValue::Deletion,
+        1 => {
+            let mut fields = Fields(value);
+            let previous_oid = fields.id(object_hash)?;
+            let new_oid = fields.id(object_hash)?;
+            let name = fields.string()?;
+            let email = fields.string()?;
+            let seconds = fields.varint()?;
+            let offset_in_minutes = i16::from_be_bytes(fields.bytes(2)?.try_into().expect("2 bytes"));
+            let message = fields.string()?;
+            LogValue::Update(Line {
+                previous_oid,
+                new_oid,
+                signature: gix_actor::Signature {
+                    name: name.into(),
+                    email: email.into(),
+                    time: gix_actor::date::Time::new(
+                        seconds.try_into().map_err(|_| corrupt("the time is out of range"))?,
+                        i32::from(offset_in_minutes) * 60,
+                    ),
+                },
+                message: message.strip_suffix(b"\n").unwrap_or(message).as_bstr().into(),
+            })
+        }
+        _ => return Err(corrupt("unknown log value type")),
+    };
+    Ok(LogRecord {
+        name: name.into(),
+        update_index,
+        value,
+    })
+}
@@ -0,0 +1,119 @@
+mod varint {
+    use crate::store_impl::reftable::varint::{decode, encode};
+
+    #[test]
+    fn round_trip() {
+        for value in [0, 1, 127, 128, 255, 16383, 16384, 1 << 32, u64::MAX - 1, u64::MAX] {
+            let mut buf = Vec::new();
+            encode(value, &mut buf);
+            assert_eq!(decode(&buf), Some((value, buf.len())), "{value}");
+        }
+    }
+
+    #[test]
+    fn encoding_is_offset_based() {
+        let mut buf = Vec::new();
+        encode(128, &mut buf);
+        assert_eq!(buf, [0x80, 0x00], "unlike LEB128, each continuation adds one");
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        assert_eq!(decode(&[0x80]), None);
+        assert_eq!(decode(&[]), None);
+    }
+}
+
+mod block {
+    use crate::store_impl::reftable::{
+        block::{Block, Writer, INDEX, LOG, REF},
+        varint,
+    };
+
+    /// Records as they would be found in index blocks, whose values are block offsets.
+    fn records(count: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+        (0..count)
+            .map(|idx| {
+                let mut offset = Vec::new();
+                varint::encode(idx as u64 * 4096, &mut offset);
+                (format!("refs/heads/b{idx:04}").into_bytes(), offset)
+            })
+            .collect()
+    }
+
+    fn write(kind: u8, records: &[(Vec<u8>, Vec<u8>)], header_len: usize) -> Vec<u8> {
+        let mut block = Writer::new(kind, header_len, 4096, 4);
+        for (key, value) in records {
+            assert!(block.add(key, 0, value), "everything fits");
+        }
+        block.finish().expect("in-memory")
+    }
+
+    fn read_all(block: &mut Block<'_>) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut out = Vec::new();
+        while let Some((_value_type, range)) = block.next_record(0).expect("valid") {
+            out.push((block.key().to_vec(), block.value(range).to_vec()));
+        }
+        out
+    }
+
+    #[test]
+    fn records_round_trip_with_prefix_compression_and_restarts() {
+        let records = records(50);
+        let data = write(INDEX, &records, 0);
+        assert!(
+            data.len() < records.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>(),
+            "shared prefixes are not repeated"
+        );
+        let mut block = Block::at(&data, 0, 0, 4096).expect("valid").expect("present");
+        assert_eq!(
+            block.size_in_file, 4096,
+            "the last block isn't padded, but would be followed by padding"
+        );
+        assert_eq!(read_all(&mut block), records);
+    }
+
+    #[test]
+    fn seek_finds_the_first_record_at_or_after_the_key() {
+        let records = records(50);
+        let data = write(INDEX, &records, 0);
+        let mut block = Block::at(&data, 0, 0, 4096).expect("valid").expect("present");
+        for (idx, (key, _)) in records.iter().enumerate() {
+            block.seek(key, 0).expect("valid");
+            let (_, range) = block.next_record(0).expect("valid").expect("present");
+            assert_eq!(block.key(), key.as_slice());
+            assert_eq!(block.value(range), records[idx].1.as_slice());
+        }
+        block.seek(b"refs/heads/b0010x", 0).expect("valid");
+        block.next_record(0).expect("valid").expect("present");
+        assert_eq!(block.key(), b"refs/heads/b0011");
+        block.seek(b"refs/heads/c", 0).expect("valid");
+        assert!(block.next_record(0).expect("valid").is_none());
+        block.seek(b"", 0).expect("valid");
+        block.next_record(0).expect("valid").expect("present");
+        assert_eq!(block.key(), b"refs/heads/b0000");
+    }
+
+    #[test]
+    fn records_that_do_not_fit_are_rejected() {
+        let mut block = Writer::new(REF, 24, 256, 16);
+        assert!(!block.add(&[b'a'; 300], 0, &[]));
+        assert!(block.is_empty());
+    }
+
+    #[test]
+    fn log_blocks_are_compressed() {
+        let records: Vec<_> = (0..20)
+            .map(|idx| (format!("refs/heads/main\0{idx:08}").into_bytes(), Vec::new()))
+            .collect();
+        let data = write(LOG, &records, 24);
+        assert!(data.len() < 24 + 20 * 24, "compression worked");
+        let mut block = Block::at(&data, 0, 24, 4096).expect("valid").expect("present");
+        assert_eq!(
+            block.size_in_file,
+            data.len(),
+            "log blocks take exactly as much space as their compressed data"
+        );
+        assert_eq!(read_all(&mut block), records);
+    }
+}
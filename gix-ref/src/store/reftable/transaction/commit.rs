@@ -0,0 +1,175 @@
+use crate::{
+    bstr::BString,
+    store::WriteReflog,
+    store_impl::reftable::{
+        init::commit_table_names, transaction::Transaction, write, LogRecord, LogValue, RefRecord, RefValue,
+    },
+    transaction::{Change, PreviousValue, RefEdit, RefLog},
+    FullNameRef, Target,
+};
+
+impl Transaction<'_> {
+    /// Make all [prepared][Transaction::prepare()] permanent by adding a new table to the stack, and return the performed edits
+    /// which represent the current state of the affected refs in the ref store in that instant. Please note that the obtained edits
+    /// may have been adjusted to contain more dependent edits or additional information.
+    /// `committer` is used in the reflog and only if the reflog is actually written, which is why it is optional.
+    /// If `None` is passed and the reflog needs to be written, the operation will fail without changing anything.
+    ///
+    /// If [auto-compaction](crate::reftable::Stack::auto_compaction) is enabled, tables will be compacted afterwards
+    /// as needed, which won't affect the outcome of the transaction if it fails.
+    pub fn commit<'a>(self, committer: impl Into<Option<gix_actor::SignatureRef<'a>>>) -> Result<Vec<RefEdit>, Error> {
+        self.commit_inner(committer.into())
+    }
+
+    fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
+        let updates = self.updates.expect("BUG: must call prepare before commit");
+        let lock = self.lock.expect("BUG: prepare() always obtains the lock");
+        let stack = self.stack;
+        let tables = self.tables;
+        let update_index = tables.next_update_index();
+        let namespaced = |name: &FullNameRef| -> BString {
+            match &stack.namespace {
+                Some(namespace) => namespace.to_owned().into_namespaced_name(name).into_inner(),
+                None => name.as_bstr().to_owned(),
+            }
+        };
+
+        let mut refs = Vec::new();
+        let mut logs = Vec::new();
+        for change in &updates {
+            assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
+            let name = change.update.name.as_ref();
+            match &change.update.change {
+                Change::Update { log, new, expected } => {
+                    if log.mode == RefLog::AndReference {
+                        refs.push(RefRecord {
+                            name: namespaced(name),
+                            update_index,
+                            value: match new {
+                                Target::Object(id) => RefValue::Object(*id),
+                                Target::Symbolic(target) => RefValue::Symbolic(target.as_bstr().to_owned()),
+                            },
+                        });
+                    }
+
+                    let log_update = match new {
+                        Target::Symbolic(_) => {
+                            // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
+                            // Unless, the ref is new and we can obtain a peeled id
+                            // identified by the expectation of what could be there, as is the case when cloning.
+                            match expected {
+                                PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
+                                    Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
+                                }
+                                _ => None,
+                            }
+                        }
+                        Target::Object(new_oid) => {
+                            let previous = match expected {
+                                PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(oid.to_owned()),
+                                _ => None,
+                            }
+                            .or(change.leaf_referent_previous_oid);
+                            Some((previous, new_oid))
+                        }
+                    };
+                    let Some((previous, new_oid)) = log_update else {
+                        continue;
+                    };
+                    if previous.as_ref() == Some(new_oid) {
+                        continue;
+                    }
+                    let write_log = match stack.write_reflog {
+                        WriteReflog::Disable => false,
+                        WriteReflog::Always => true,
+                        WriteReflog::Normal => {
+                            log.force_create_reflog
+                                || should_autocreate_reflog(name)
+                                || !stack.log_records(&tables, name)?.is_empty()
+                        }
+                    };
+                    if !write_log {
+                        continue;
+                    }
+                    let committer = committer.ok_or(Error::MissingCommitter)?;
+                    logs.push(LogRecord {
+                        name: namespaced(name),
+                        update_index,
+                        value: LogValue::Update(crate::log::Line {
+                            previous_oid: previous.unwrap_or_else(|| new_oid.kind().null()),
+                            new_oid: *new_oid,
+                            signature: committer.to_owned(),
+                            message: log.message.clone(),
+                        }),
+                    });
+                }
+                Change::Delete { log: mode, .. } => {
+                    if *mode == RefLog::AndReference {
+                        refs.push(RefRecord {
+                            name: namespaced(name),
+                            update_index,
+                            value: RefValue::Deletion,
+                        });
+                    }
+                    // Like with loose references, the reflog goes away along with the reference.
+                    logs.extend(stack.log_records(&tables, name)?.into_iter().map(|record| LogRecord {
+                        value: LogValue::Deletion,
+                        ..record
+                    }));
+                }
+            }
+        }
+
+        if !refs.is_empty() || !logs.is_empty() {
+            let table = write::table(
+                stack.object_hash(),
+                update_index..=update_index,
+                refs,
+                logs,
+                &stack.write_options,
+            )?;
+            let name = stack.write_table(&table, update_index..=update_index)?;
+            commit_table_names(
+                lock,
+                tables.names().iter().map(String::as_str).chain(Some(name.as_str())),
+            )?;
+
+            // Always refresh ourselves right away to avoid races. We ignore errors as there may be many reasons this fails, and it's not
+            // critical to be done here.
+            stack.force_refresh_tables().ok();
+            if stack.auto_compaction {
+                // Compaction is an optimization which doesn't affect the outcome of the transaction.
+                stack.auto_compact().ok();
+            }
+        }
+        Ok(updates.into_iter().map(|edit| edit.update).collect())
+    }
+}
+
+fn should_autocreate_reflog(name: &FullNameRef) -> bool {
+    let name = name.as_bstr();
+    name.starts_with(b"refs/heads/")
+        || name.starts_with(b"refs/remotes/")
+        || name.starts_with(b"refs/notes/")
+        || name.starts_with(b"refs/worktree/")
+        || name == "HEAD"
+}
+
+mod error {
+    use crate::store_impl::reftable;
+
+    /// The error returned by various [`Transaction`][super::Transaction] methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The existing reflog could not be read")]
+        Decode(#[from] reftable::decode::Error),
+        #[error("reflog messages need a committer which isn't set")]
+        MissingCommitter,
+        #[error("The table with all edits could not be created")]
+        Write(#[from] reftable::write::Error),
+        #[error("The new table could not be written or added to the stack")]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
@@ -0,0 +1,86 @@
+use std::{borrow::Cow, fmt::Formatter};
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::BString,
+    store_impl::reftable::{Stack, Tables},
+    transaction::RefEdit,
+};
+
+/// A transaction on a [reftable stack](Stack), which adds all of its edits as a single new table.
+///
+/// Unlike with loose references, all edits are applied atomically as the stack is locked during the whole transaction.
+pub struct Transaction<'s> {
+    stack: Cow<'s, Stack>,
+    /// The lock on `tables.list`, held from preparation to commit.
+    lock: Option<gix_lock::File>,
+    /// The tables as they were when we obtained the lock.
+    tables: Tables,
+    updates: Option<Vec<Edit>>,
+}
+
+#[derive(Debug)]
+struct Edit {
+    update: RefEdit,
+    /// Set if this update is coming from a symbolic reference and used to make it appear like it is the one that is handled,
+    /// instead of the referent reference.
+    parent_index: Option<usize>,
+    /// For symbolic refs, this is the previous OID to put into the reflog instead of our own previous value. It's the
+    /// peeled value of the leaf referent.
+    leaf_referent_previous_oid: Option<ObjectId>,
+}
+
+impl Edit {
+    fn name(&self) -> BString {
+        self.update.name.0.clone()
+    }
+}
+
+impl std::borrow::Borrow<RefEdit> for Edit {
+    fn borrow(&self) -> &RefEdit {
+        &self.update
+    }
+}
+
+impl std::borrow::BorrowMut<RefEdit> for Edit {
+    fn borrow_mut(&mut self) -> &mut RefEdit {
+        &mut self.update
+    }
+}
+
+/// Edits
+impl Stack {
+    /// Open a transaction which will add a new table with all edits to the stack.
+    ///
+    /// The transaction inherits the parent namespace.
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction::new(Cow::Borrowed(self))
+    }
+}
+
+impl<'s> Transaction<'s> {
+    pub(crate) fn new(stack: Cow<'s, Stack>) -> Self {
+        Transaction {
+            stack,
+            lock: None,
+            tables: Tables::default(),
+            updates: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("stack", &self.stack)
+            .field("edits", &self.updates.as_ref().map(Vec::len))
+            .finish_non_exhaustive()
+    }
+}
+
+///
+pub mod prepare;
+
+///
+pub mod commit;
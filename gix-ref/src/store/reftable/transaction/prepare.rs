@@ -0,0 +1,219 @@
+use crate::{
+    store_impl::reftable::{
+        transaction::{Edit, Transaction},
+        Stack, Tables,
+    },
+    transaction::{Change, PreviousValue, RefEdit, RefEditsExt},
+    FullNameRef, Target,
+};
+
+impl Transaction<'_> {
+    /// Prepare for calling [`commit(…)`][Transaction::commit()] by locking the stack and validating all `edits` against
+    /// the current state of all references, or fail if the lock can't be obtained according to `lock_fail_mode`.
+    ///
+    /// If the operation succeeds, the transaction can be committed or dropped to cause a rollback automatically.
+    pub fn prepare(
+        self,
+        edits: impl IntoIterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, Error> {
+        self.prepare_inner(&mut edits.into_iter(), lock_fail_mode)
+    }
+
+    fn prepare_inner(
+        mut self,
+        edits: &mut dyn Iterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, Error> {
+        assert!(self.updates.is_none(), "BUG: Must not call prepare(…) multiple times");
+        let stack: &Stack = &self.stack;
+        self.lock = Some(stack.lock(lock_fail_mode).map_err(Error::LockAcquire)?);
+        // Read the tables only while holding the lock to be sure nobody changes them before we commit.
+        self.tables = stack.load_tables()?.unwrap_or_default();
+
+        let tables = &self.tables;
+        let mut updates: Vec<_> = edits
+            .map(|update| Edit {
+                update,
+                parent_index: None,
+                leaf_referent_previous_oid: None,
+            })
+            .collect();
+        updates
+            .pre_process(
+                &mut |name| {
+                    stack
+                        .find_in(tables, FullNameRef::new_unchecked(name.as_bstr()))
+                        .ok()
+                        .flatten()
+                        .map(|r| r.target)
+                },
+                &mut |idx, update| Edit {
+                    update,
+                    parent_index: Some(idx),
+                    leaf_referent_previous_oid: None,
+                },
+            )
+            .map_err(Error::PreprocessingFailed)?;
+
+        for cid in 0..updates.len() {
+            let change = &mut updates[cid];
+            apply_change(stack, tables, change)?;
+
+            // traverse parent chain from leaf/peeled ref and set the leaf previous oid accordingly
+            // to help with their reflog entries
+            if let (Some(crate::TargetRef::Object(oid)), Some(parent_idx)) =
+                (change.update.change.previous_value(), change.parent_index)
+            {
+                let oid = oid.to_owned();
+                let mut parent_idx_cursor = Some(parent_idx);
+                while let Some(parent) = parent_idx_cursor.take().map(|idx| &mut updates[idx]) {
+                    parent_idx_cursor = parent.parent_index;
+                    parent.leaf_referent_previous_oid = Some(oid);
+                }
+            }
+        }
+        self.updates = Some(updates);
+        Ok(self)
+    }
+
+    /// Rollback all intermediate state and return the `RefEdits` as we know them thus far.
+    ///
+    /// Note that they have been altered compared to what was initially provided as they have
+    /// been split and know about their current state on disk.
+    ///
+    /// # Note
+    ///
+    /// A rollback happens automatically as this instance is dropped as well.
+    pub fn rollback(self) -> Vec<RefEdit> {
+        self.updates
+            .map(|updates| updates.into_iter().map(|u| u.update).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Check the expectations of `change` against the current value of its reference in `tables`,
+/// and remember that value as its expected previous value.
+fn apply_change(stack: &Stack, tables: &Tables, change: &mut Edit) -> Result<(), Error> {
+    let existing_ref = stack.find_in(tables, change.update.name.as_ref())?;
+    match &mut change.update.change {
+        Change::Delete { expected, .. } => {
+            match (&expected, &existing_ref) {
+                (PreviousValue::MustNotExist, _) => {
+                    panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
+                }
+                (PreviousValue::ExistingMustMatch(_) | PreviousValue::Any, None)
+                | (PreviousValue::MustExist | PreviousValue::Any, Some(_)) => {}
+                (PreviousValue::MustExist | PreviousValue::MustExistAndMatch(_), None) => {
+                    return Err(Error::DeleteReferenceMustExist {
+                        full_name: change.name(),
+                    })
+                }
+                (
+                    PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                    Some(existing),
+                ) => {
+                    let actual = existing.target.clone();
+                    if *previous != actual {
+                        let expected = previous.clone();
+                        return Err(Error::ReferenceOutOfDate {
+                            full_name: change.name(),
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+            }
+
+            // Keep the previous value for the caller and ourselves. Maybe they want to keep a log of sorts.
+            if let Some(existing) = existing_ref {
+                *expected = PreviousValue::MustExistAndMatch(existing.target);
+            }
+        }
+        Change::Update { expected, new, .. } => {
+            match (&expected, &existing_ref) {
+                (PreviousValue::Any, _)
+                | (PreviousValue::MustExist, Some(_))
+                | (PreviousValue::MustNotExist | PreviousValue::ExistingMustMatch(_), None) => {}
+                (PreviousValue::MustExist, None) => {
+                    let expected = Target::Object(stack.object_hash().null());
+                    let full_name = change.name();
+                    return Err(Error::MustExist { full_name, expected });
+                }
+                (PreviousValue::MustNotExist, Some(existing)) => {
+                    if existing.target != *new {
+                        let new = new.clone();
+                        return Err(Error::MustNotExist {
+                            full_name: change.name(),
+                            actual: existing.target.clone(),
+                            new,
+                        });
+                    }
+                }
+                (
+                    PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                    Some(existing),
+                ) => {
+                    if *previous != existing.target {
+                        let actual = existing.target.clone();
+                        let expected = previous.to_owned();
+                        let full_name = change.name();
+                        return Err(Error::ReferenceOutOfDate {
+                            full_name,
+                            actual,
+                            expected,
+                        });
+                    }
+                }
+                (PreviousValue::MustExistAndMatch(previous), None) => {
+                    let expected = previous.to_owned();
+                    let full_name = change.name();
+                    return Err(Error::MustExist { full_name, expected });
+                }
+            }
+
+            if let Some(existing) = existing_ref {
+                *expected = PreviousValue::MustExistAndMatch(existing.target);
+            }
+        }
+    }
+    Ok(())
+}
+
+mod error {
+    use gix_object::bstr::BString;
+
+    use crate::{store_impl::reftable, Target};
+
+    /// The error returned by various [`Transaction`][super::Transaction] methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The lock for the reftable stack could not be obtained")]
+        LockAcquire(#[source] gix_lock::acquire::Error),
+        #[error("The tables of the stack could not be loaded")]
+        Open(#[from] reftable::open::Error),
+        #[error("A reference could not be read from the stack")]
+        Find(#[from] reftable::find::Error),
+        #[error("Edit preprocessing failed with an error")]
+        PreprocessingFailed(#[source] std::io::Error),
+        #[error("The reference {full_name:?} for deletion did not exist or could not be parsed")]
+        DeleteReferenceMustExist { full_name: BString },
+        #[error("Reference {full_name:?} was not supposed to exist when writing it with value {new:?}, but actual content was {actual:?}")]
+        MustNotExist {
+            full_name: BString,
+            actual: Target,
+            new: Target,
+        },
+        #[error("Reference {full_name:?} was supposed to exist with value {expected}, but didn't.")]
+        MustExist { full_name: BString, expected: Target },
+        #[error("The reference {full_name:?} should have content {expected}, actual content was {actual}")]
+        ReferenceOutOfDate {
+            full_name: BString,
+            expected: Target,
+            actual: Target,
+        },
+    }
+}
+
+pub use error::Error;
@@ -0,0 +1,35 @@
+//! The variable-length integer encoding used by reftables, which is the same as the one used for offsets in packs.
+
+/// Append `value` to `out` in its variable-length encoding.
+pub(crate) fn encode(mut value: u64, out: &mut Vec<u8>) {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = (value & 0x7f) as u8;
+    loop {
+        value >>= 7;
+        if value == 0 {
+            break;
+        }
+        value -= 1;
+        pos -= 1;
+        buf[pos] = 0x80 | (value & 0x7f) as u8;
+    }
+    out.extend_from_slice(&buf[pos..]);
+}
+
+/// Decode a variable-length integer from the beginning of `data` and return it along with the amount of consumed bytes,
+/// or `None` if `data` is truncated or the value overflows.
+pub(crate) fn decode(data: &[u8]) -> Option<(u64, usize)> {
+    let mut byte = *data.first()?;
+    let mut value = u64::from(byte & 0x7f);
+    let mut consumed = 1;
+    while byte & 0x80 != 0 {
+        byte = *data.get(consumed)?;
+        consumed += 1;
+        if value >= 1 << 57 {
+            return None;
+        }
+        value = ((value + 1) << 7) | u64::from(byte & 0x7f);
+    }
+    Some((value, consumed))
+}
@@ -0,0 +1,328 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    store_impl::reftable::{
+        block,
+        table::{footer_len, header_len, SHA256_ID, SIGNATURE},
+        varint, LogRecord, LogValue, RefRecord, RefValue,
+    },
+};
+
+/// Options for use in [`table()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The size of each block in bytes, with records never crossing block boundaries.
+    ///
+    /// Larger blocks make tables smaller, while smaller blocks make lookups faster.
+    pub block_size: u32,
+    /// The amount of records after which a record is stored with its full key, instead of sharing
+    /// a prefix with the previous key, to allow binary searches within blocks.
+    pub restart_interval: u16,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            block_size: 4096,
+            restart_interval: 16,
+        }
+    }
+}
+
+/// The error returned by [`table()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The block size must be larger than 256 bytes and smaller than 16MB, got {block_size}")]
+    InvalidBlockSize { block_size: u32 },
+    #[error("The record of {name:?} doesn't fit into a block of {block_size} bytes")]
+    RecordTooLarge { name: BString, block_size: u32 },
+    #[error("The update index {update_index} of {name:?} is out of the table's range of {min}..={max}")]
+    UpdateIndexOutOfRange {
+        name: BString,
+        update_index: u64,
+        min: u64,
+        max: u64,
+    },
+    #[error("There is more than one record for {name:?}")]
+    DuplicateRecord { name: BString },
+    #[error("The log message of {name:?} must be a single line")]
+    MultiLineLogMessage { name: BString },
+    #[error("Failed to compress a log block")]
+    Compress(#[from] std::io::Error),
+}
+
+/// Write a reftable with all `refs` and `logs` using `object_hash`, and return its bytes.
+///
+/// The update index of all reference records must be within `update_indices`, which is also stored in the table.
+/// Log records may use any update index, which allows deleting log entries added by older tables.
+/// Records don't need to be sorted, but each reference may only have one record, just like each log entry,
+/// which is identified by its reference name and update index.
+pub fn table(
+    object_hash: gix_hash::Kind,
+    update_indices: RangeInclusive<u64>,
+    mut refs: Vec<RefRecord>,
+    mut logs: Vec<LogRecord>,
+    options: &Options,
+) -> Result<Vec<u8>, Error> {
+    if !(256..1 << 24).contains(&options.block_size) {
+        return Err(Error::InvalidBlockSize {
+            block_size: options.block_size,
+        });
+    }
+    let (min, max) = (*update_indices.start(), *update_indices.end());
+    let check_update_index = |name: &BString, update_index: u64| {
+        if update_indices.contains(&update_index) {
+            Ok(())
+        } else {
+            Err(Error::UpdateIndexOutOfRange {
+                name: name.clone(),
+                update_index,
+                min,
+                max,
+            })
+        }
+    };
+
+    refs.sort_by(|a, b| a.name.cmp(&b.name));
+    if let Some(dup) = refs.windows(2).find(|w| w[0].name == w[1].name) {
+        return Err(Error::DuplicateRecord {
+            name: dup[0].name.clone(),
+        });
+    }
+    logs.sort_by(|a, b| a.name.cmp(&b.name).then(b.update_index.cmp(&a.update_index)));
+    if let Some(dup) = logs
+        .windows(2)
+        .find(|w| w[0].name == w[1].name && w[0].update_index == w[1].update_index)
+    {
+        return Err(Error::DuplicateRecord {
+            name: dup[0].name.clone(),
+        });
+    }
+
+    let mut out = Writer {
+        out: Vec::new(),
+        pending_padding: 0,
+        header_len: header_len(object_hash),
+        options,
+    };
+    let ref_index_position = out.section(
+        block::REF,
+        refs.iter().map(|r| {
+            check_update_index(&r.name, r.update_index)?;
+            let mut value = Vec::new();
+            let value_type = encode_ref(r, min, &mut value);
+            Ok((r.name.to_vec(), value_type, value))
+        }),
+    )?;
+
+    let log_position = out.next_block_offset();
+    let log_index_position = out.section(
+        block::LOG,
+        logs.iter().map(|r| {
+            let mut key = r.name.to_vec();
+            key.push(0);
+            key.extend_from_slice(&(u64::MAX - r.update_index).to_be_bytes());
+            let mut value = Vec::new();
+            let value_type = encode_log(r, &mut value)?;
+            Ok((key, value_type, value))
+        }),
+    )?;
+
+    let header = header(object_hash, options.block_size, min, max);
+    let mut out = out.out;
+    if out.is_empty() {
+        out.extend_from_slice(&header);
+    } else {
+        out[..header.len()].copy_from_slice(&header);
+    }
+    let footer_start = out.len();
+    out.extend_from_slice(&header);
+    for field in [
+        ref_index_position as u64,
+        0,
+        0,
+        if logs.is_empty() { 0 } else { log_position as u64 },
+        log_index_position as u64,
+    ] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+    let checksum = gix_features::hash::crc32(&out[footer_start..]);
+    out.extend_from_slice(&checksum.to_be_bytes());
+    debug_assert_eq!(out.len() - footer_start, footer_len(header.len()));
+    Ok(out)
+}
+
+fn header(object_hash: gix_hash::Kind, block_size: u32, min: u64, max: u64) -> Vec<u8> {
+    let mut header = Vec::with_capacity(header_len(object_hash));
+    header.extend_from_slice(SIGNATURE);
+    header.push(match object_hash {
+        gix_hash::Kind::Sha1 => 1,
+        gix_hash::Kind::Sha256 => 2,
+    });
+    header.extend_from_slice(&block_size.to_be_bytes()[1..]);
+    header.extend_from_slice(&min.to_be_bytes());
+    header.extend_from_slice(&max.to_be_bytes());
+    if let gix_hash::Kind::Sha256 = object_hash {
+        header.extend_from_slice(&SHA256_ID.to_be_bytes());
+    }
+    header
+}
+
+fn encode_ref(record: &RefRecord, min_update_index: u64, out: &mut Vec<u8>) -> u8 {
+    varint::encode(record.update_index - min_update_index, out);
+    match &record.value {
+        RefValue::Deletion => 0,
+        RefValue::Object(id) => {
+            out.extend_from_slice(id.as_bytes());
+            1
+        }
+        RefValue::Peeled { target, peeled } => {
+            out.extend_from_slice(target.as_bytes());
+            out.extend_from_slice(peeled.as_bytes());
+            2
+        }
+        RefValue::Symbolic(target) => {
+            varint::encode(target.len() as u64, out);
+            out.extend_from_slice(target);
+            3
+        }
+    }
+}
+
+fn encode_log(record: &LogRecord, out: &mut Vec<u8>) -> Result<u8, Error> {
+    let line = match &record.value {
+        LogValue::Deletion => return Ok(0),
+        LogValue::Update(line) => line,
+    };
+    let message = line.message.trim_end_with(|c| c == '\n');
+    if message.contains(&b'\n') {
+        return Err(Error::MultiLineLogMessage {
+            name: record.name.clone(),
+        });
+    }
+    out.extend_from_slice(line.previous_oid.as_bytes());
+    out.extend_from_slice(line.new_oid.as_bytes());
+    for field in [&line.signature.name, &line.signature.email] {
+        varint::encode(field.len() as u64, out);
+        out.extend_from_slice(field);
+    }
+    varint::encode(u64::try_from(line.signature.time.seconds).unwrap_or_default(), out);
+    out.extend_from_slice(&((line.signature.time.offset / 60) as i16).to_be_bytes());
+    // Messages are stored with a trailing newline, just like in reflog files.
+    varint::encode(message.len() as u64 + 1, out);
+    out.extend_from_slice(message);
+    out.push(b'\n');
+    Ok(1)
+}
+
+/// Writes blocks of a table, padding all but log blocks to the block size unless they are the last block in the file.
+struct Writer<'a> {
+    out: Vec<u8>,
+    pending_padding: usize,
+    header_len: usize,
+    options: &'a Options,
+}
+
+impl Writer<'_> {
+    fn next_block_offset(&self) -> usize {
+        self.out.len() + self.pending_padding
+    }
+
+    fn new_block(&self, kind: u8) -> block::Writer {
+        block::Writer::new(
+            kind,
+            if self.out.is_empty() { self.header_len } else { 0 },
+            self.options.block_size as usize,
+            usize::from(self.options.restart_interval),
+        )
+    }
+
+    fn flush(&mut self, block: block::Writer) -> Result<usize, Error> {
+        let pad = block.kind != block::LOG;
+        let bytes = block.finish()?;
+        self.out.resize(self.out.len() + self.pending_padding, 0);
+        let offset = self.out.len();
+        self.out.extend_from_slice(&bytes);
+        self.pending_padding = if pad {
+            (self.options.block_size as usize).saturating_sub(bytes.len())
+        } else {
+            0
+        };
+        Ok(offset)
+    }
+
+    /// Write all `records` as blocks of `kind`, followed by an index if there are enough blocks, and return
+    /// the position of the index or 0 if there is none.
+    fn section(
+        &mut self,
+        kind: u8,
+        records: impl Iterator<Item = Result<(Vec<u8>, u8, Vec<u8>), Error>>,
+    ) -> Result<usize, Error> {
+        let mut index = Vec::new();
+        let mut block = self.new_block(kind);
+        for record in records {
+            let (key, value_type, value) = record?;
+            if block.add(&key, value_type, &value) {
+                continue;
+            }
+            if !block.is_empty() {
+                let last_key = block.last_key().to_owned();
+                index.push((last_key, self.flush(block)?));
+                block = self.new_block(kind);
+                if block.add(&key, value_type, &value) {
+                    continue;
+                }
+            }
+            let name = if kind == block::LOG {
+                &key[..key.len() - 9]
+            } else {
+                &key
+            };
+            return Err(Error::RecordTooLarge {
+                name: name.into(),
+                block_size: self.options.block_size,
+            });
+        }
+        if block.is_empty() {
+            return Ok(0);
+        }
+        let last_key = block.last_key().to_owned();
+        index.push((last_key, self.flush(block)?));
+
+        // Like git, only write an index if it saves reading more than a few blocks, and add levels until
+        // the top-level is small enough.
+        let mut index_position = 0;
+        let mut value = Vec::new();
+        while index.len() > 3 {
+            let mut next_level = Vec::new();
+            let mut block = self.new_block(block::INDEX);
+            let mut level_start = None;
+            for (key, offset) in index {
+                value.clear();
+                varint::encode(offset as u64, &mut value);
+                if block.add(&key, 0, &value) {
+                    continue;
+                }
+                let last_key = block.last_key().to_owned();
+                let offset = self.flush(block)?;
+                level_start.get_or_insert(offset);
+                next_level.push((last_key, offset));
+                block = self.new_block(block::INDEX);
+                if !block.add(&key, 0, &value) {
+                    return Err(Error::RecordTooLarge {
+                        name: key.into(),
+                        block_size: self.options.block_size,
+                    });
+                }
+            }
+            let last_key = block.last_key().to_owned();
+            let offset = self.flush(block)?;
+            index_position = *level_start.get_or_insert(offset);
+            next_level.push((last_key, offset));
+            index = next_level;
+        }
+        Ok(index_position)
+    }
+}
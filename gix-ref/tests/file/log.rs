@@ -192,3 +192,60 @@ mod iter {
         }
     }
 }
+
+mod expire {
+    use gix_lock::acquire::Fail;
+    use gix_ref::FullNameRef;
+
+    use crate::file::store_writable;
+
+    #[test]
+    fn removes_matching_lines_and_keeps_the_others() -> crate::Result {
+        let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+        let head: &FullNameRef = "HEAD".try_into()?;
+        let messages = |store: &gix_ref::file::Store| -> crate::Result<Vec<String>> {
+            let mut buf = Vec::new();
+            Ok(store
+                .reflog_iter(head, &mut buf)?
+                .expect("log exists")
+                .map(|line| line.map(|line| line.message.to_string()))
+                .collect::<Result<_, _>>()?)
+        };
+        let before = messages(&store)?;
+        assert_eq!(before.len(), 5);
+
+        let removed = store.reflog_expire(head, Fail::Immediately, &mut |line| line.message.starts_with(b"revert"))?;
+        assert_eq!(removed, Some(2), "both reverts are removed");
+        assert_eq!(
+            messages(&store)?,
+            before
+                .into_iter()
+                .filter(|message| !message.starts_with("revert"))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            store.reflog_expire(head, Fail::Immediately, &mut |_| false)?,
+            Some(0),
+            "nothing to do"
+        );
+        assert_eq!(store.reflog_expire(head, Fail::Immediately, &mut |_| true)?, Some(3));
+        assert!(
+            messages(&store)?.is_empty(),
+            "the reflog is kept even if it is empty, just like git does"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_reflogs_are_no_error() -> crate::Result {
+        let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+        assert_eq!(
+            store.reflog_expire("refs/heads/does-not-exist".try_into()?, Fail::Immediately, &mut |_| {
+                true
+            })?,
+            None
+        );
+        Ok(())
+    }
+}
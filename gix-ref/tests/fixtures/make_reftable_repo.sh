@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --ref-format=reftable
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git commit -q --allow-empty -m c2
+git commit -q --allow-empty -m c3
+
+git branch other HEAD~1
+git tag lightweight HEAD~2
+git tag -a -m "annotated" annotated HEAD~1
+git symbolic-ref refs/heads/symbolic refs/heads/main
+git update-ref refs/remotes/origin/main HEAD~1
+git branch to-delete && git branch -q -D to-delete
+
+git for-each-ref --format='%(refname) %(objectname) %(*objectname) %(symref)' > for-each-ref.baseline
+git reflog show --format=%H refs/heads/main > main-reflog.baseline
@@ -0,0 +1,76 @@
+use gix_hash::ObjectId;
+use gix_ref::{reftable::Stack, Target};
+
+fn stack() -> crate::Result<Option<Stack>> {
+    if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 45, 0) {
+        return Ok(None);
+    }
+    let dir = gix_testtools::scripted_fixture_read_only("make_reftable_repo.sh")?;
+    Ok(Some(Stack::at(dir.join(".git"), Default::default())))
+}
+
+fn baseline(name: &str) -> crate::Result<String> {
+    let dir = gix_testtools::scripted_fixture_read_only("make_reftable_repo.sh")?;
+    Ok(std::fs::read_to_string(dir.join(name))?)
+}
+
+#[test]
+fn references_match_git() -> crate::Result {
+    let Some(stack) = stack()? else {
+        return Ok(());
+    };
+    let mut expected_names = Vec::new();
+    for line in baseline("for-each-ref.baseline")?.lines() {
+        let mut tokens = line.split(' ');
+        let mut next = || tokens.next().filter(|token| !token.is_empty());
+        let (name, id, peeled, symref) = (next().expect("name"), next().expect("id"), next(), next());
+        let reference = stack.find(name)?;
+        match symref {
+            Some(target) => assert_eq!(reference.target, Target::Symbolic(target.try_into()?), "{name}"),
+            None => assert_eq!(
+                reference.target,
+                Target::Object(ObjectId::from_hex(id.as_bytes())?),
+                "{name}"
+            ),
+        }
+        assert_eq!(
+            reference.peeled,
+            peeled.map(|id| ObjectId::from_hex(id.as_bytes())).transpose()?,
+            "{name}: annotated tags are stored with their peeled value"
+        );
+        expected_names.push(name.to_owned());
+    }
+
+    let actual_names: Vec<_> = stack
+        .iter()?
+        .prefixed("refs/".into())
+        .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(actual_names, expected_names, "deleted references are gone");
+    assert_eq!(
+        stack.find("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?),
+        "HEAD is stored in the table"
+    );
+    Ok(())
+}
+
+#[test]
+fn reflog_matches_git() -> crate::Result {
+    let Some(stack) = stack()? else {
+        return Ok(());
+    };
+    let expected: Vec<_> = baseline("main-reflog.baseline")?
+        .lines()
+        .rev()
+        .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<_, _>>()?;
+    let actual: Vec<_> = stack
+        .reflog("refs/heads/main".try_into()?)?
+        .expect("present")
+        .into_iter()
+        .map(|line| line.new_oid)
+        .collect();
+    assert_eq!(actual, expected);
+    Ok(())
+}
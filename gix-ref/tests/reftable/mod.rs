@@ -0,0 +1,75 @@
+use gix_date::{time::Sign, Time};
+use gix_hash::ObjectId;
+use gix_ref::{
+    reftable::{LogRecord, LogValue, RefRecord, RefValue},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+mod baseline;
+mod stack;
+mod table;
+
+fn id(byte: u8) -> ObjectId {
+    ObjectId::from_bytes_or_panic(&[byte; 20])
+}
+
+fn committer() -> gix_actor::Signature {
+    gix_actor::Signature {
+        name: "committer".into(),
+        email: "committer@example.com".into(),
+        time: Time {
+            seconds: 1234,
+            offset: -1800,
+            sign: Sign::Minus,
+        },
+    }
+}
+
+fn ref_record(name: &str, update_index: u64, value: RefValue) -> RefRecord {
+    RefRecord {
+        name: name.into(),
+        update_index,
+        value,
+    }
+}
+
+fn log_record(name: &str, update_index: u64, previous: ObjectId, new: ObjectId, message: &str) -> LogRecord {
+    LogRecord {
+        name: name.into(),
+        update_index,
+        value: LogValue::Update(gix_ref::log::Line {
+            previous_oid: previous,
+            new_oid: new,
+            signature: committer(),
+            message: message.into(),
+        }),
+    }
+}
+
+fn update(name: &str, expected: PreviousValue, new: Target) -> RefEdit {
+    RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("update {name}").into(),
+            },
+            expected,
+            new,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+fn delete(name: &str, expected: PreviousValue) -> RefEdit {
+    RefEdit {
+        change: Change::Delete {
+            expected,
+            log: RefLog::AndReference,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
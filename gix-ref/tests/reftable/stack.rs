@@ -0,0 +1,511 @@
+use gix_lock::acquire::Fail;
+use gix_ref::{
+    reftable::{self, Stack},
+    store::{StorageFormat, WriteReflog},
+    transaction::{Change, PreviousValue, RefLog},
+    FullName, Target,
+};
+
+use crate::reftable::{committer, delete, id, update};
+
+fn empty_stack() -> (gix_testtools::tempfile::TempDir, Stack) {
+    let dir = gix_testtools::tempfile::TempDir::new().unwrap();
+    let mut stack = Stack::at(dir.path().into(), Default::default());
+    stack.auto_compaction = false;
+    (dir, stack)
+}
+
+fn commit(
+    stack: &Stack,
+    edits: Vec<gix_ref::transaction::RefEdit>,
+) -> crate::Result<Vec<gix_ref::transaction::RefEdit>> {
+    Ok(stack
+        .transaction()
+        .prepare(edits, Fail::Immediately)?
+        .commit(committer().to_ref())?)
+}
+
+fn table_count(stack: &Stack) -> crate::Result<usize> {
+    Ok(stack.tables()?.map_or(0, |tables| tables.tables().len()))
+}
+
+fn names(stack: &Stack, prefix: &str) -> crate::Result<Vec<String>> {
+    Ok(stack
+        .iter()?
+        .prefixed(prefix.into())
+        .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn transactions_add_one_table_each() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    assert!(stack.tables()?.is_none(), "the stack is created lazily");
+    assert_eq!(stack.try_find("main")?, None);
+
+    let edits = commit(
+        &stack,
+        vec![update(
+            "refs/heads/main",
+            PreviousValue::MustNotExist,
+            Target::Object(id(1)),
+        )],
+    )?;
+    assert_eq!(edits.len(), 1);
+    assert_eq!(table_count(&stack)?, 1);
+    let list = std::fs::read_to_string(stack.tables_list_path())?;
+    assert!(
+        list.starts_with("0x000000000001-0x000000000001-") && list.ends_with(".ref\n"),
+        "{list:?} is named like git does"
+    );
+    assert_eq!(stack.find("main")?.target, Target::Object(id(1)));
+
+    let edits = commit(
+        &stack,
+        vec![update(
+            "refs/heads/main",
+            PreviousValue::MustExistAndMatch(Target::Object(id(1))),
+            Target::Object(id(2)),
+        )],
+    )?;
+    assert!(
+        matches!(&edits[0].change, Change::Update { expected: PreviousValue::MustExistAndMatch(Target::Object(previous)), .. } if *previous == id(1)),
+        "the previous value is provided"
+    );
+    let tables = stack.tables()?.expect("present");
+    assert_eq!(tables.tables().len(), 2);
+    assert_eq!(tables.next_update_index(), 3);
+    assert_eq!(stack.find("refs/heads/main")?.target, Target::Object(id(2)));
+
+    let log = stack
+        .reflog("refs/heads/main".try_into()?)?
+        .expect("branches have logs");
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].previous_oid, gix_hash::Kind::Sha1.null());
+    assert_eq!(log[0].new_oid, id(1));
+    assert_eq!(log[1].previous_oid, id(1));
+    assert_eq!(log[1].new_oid, id(2));
+    assert_eq!(log[1].message, "update refs/heads/main");
+    assert_eq!(log[1].signature, committer());
+    Ok(())
+}
+
+#[test]
+fn failed_preconditions_change_nothing_and_release_the_lock() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    commit(
+        &stack,
+        vec![update("refs/tags/v1", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+
+    let err = stack
+        .transaction()
+        .prepare(
+            vec![
+                update("refs/heads/new", PreviousValue::MustNotExist, Target::Object(id(3))),
+                update("refs/tags/v1", PreviousValue::MustNotExist, Target::Object(id(2))),
+            ],
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        reftable::transaction::prepare::Error::MustNotExist { .. }
+    ));
+
+    let err = stack
+        .transaction()
+        .prepare(
+            vec![update(
+                "refs/tags/v1",
+                PreviousValue::MustExistAndMatch(Target::Object(id(2))),
+                Target::Object(id(3)),
+            )],
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        reftable::transaction::prepare::Error::ReferenceOutOfDate { .. }
+    ));
+
+    let err = stack
+        .transaction()
+        .prepare(
+            vec![delete("refs/heads/missing", PreviousValue::MustExist)],
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        reftable::transaction::prepare::Error::DeleteReferenceMustExist { .. }
+    ));
+
+    assert_eq!(table_count(&stack)?, 1);
+    assert_eq!(stack.try_find("new")?, None, "transactions are all or nothing");
+    commit(
+        &stack,
+        vec![update("refs/tags/v1", PreviousValue::MustExist, Target::Object(id(2)))],
+    )?;
+    assert_eq!(stack.find("v1")?.target, Target::Object(id(2)));
+    Ok(())
+}
+
+#[test]
+fn prepared_transactions_lock_the_stack() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    let transaction = stack.transaction().prepare(
+        vec![update("refs/heads/main", PreviousValue::Any, Target::Object(id(1)))],
+        Fail::Immediately,
+    )?;
+    let err = stack
+        .transaction()
+        .prepare(
+            vec![update("refs/heads/other", PreviousValue::Any, Target::Object(id(1)))],
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(err, reftable::transaction::prepare::Error::LockAcquire(_)));
+
+    let edits = transaction.rollback();
+    assert_eq!(edits.len(), 1);
+    assert!(stack.tables()?.is_none(), "nothing was written");
+    commit(
+        &stack,
+        vec![update("refs/heads/other", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn deletions_shadow_older_tables_and_remove_logs() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    commit(
+        &stack,
+        vec![
+            update("refs/heads/a", PreviousValue::Any, Target::Object(id(1))),
+            update("refs/heads/b", PreviousValue::Any, Target::Object(id(1))),
+        ],
+    )?;
+    commit(
+        &stack,
+        vec![update("refs/heads/a", PreviousValue::Any, Target::Object(id(2)))],
+    )?;
+    assert_eq!(stack.reflog("refs/heads/a".try_into()?)?.map(|log| log.len()), Some(2));
+
+    let edits = commit(&stack, vec![delete("refs/heads/a", PreviousValue::MustExist)])?;
+    assert!(matches!(
+        &edits[0].change,
+        Change::Delete { expected: PreviousValue::MustExistAndMatch(Target::Object(previous)), log: RefLog::AndReference } if *previous == id(2)
+    ));
+    assert_eq!(stack.try_find("refs/heads/a")?, None);
+    assert!(!stack.reflog_exists("refs/heads/a".try_into()?)?);
+    assert_eq!(names(&stack, "")?, ["refs/heads/b"]);
+
+    commit(
+        &stack,
+        vec![update(
+            "refs/heads/a",
+            PreviousValue::MustNotExist,
+            Target::Object(id(3)),
+        )],
+    )?;
+    let log = stack.reflog("refs/heads/a".try_into()?)?.expect("recreated");
+    assert_eq!(log.len(), 1, "the old log doesn't come back");
+    Ok(())
+}
+
+#[test]
+fn symbolic_refs_are_followed_with_logs_for_both() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    commit(
+        &stack,
+        vec![
+            update(
+                "HEAD",
+                PreviousValue::MustNotExist,
+                Target::Symbolic("refs/heads/main".try_into()?),
+            ),
+            update("refs/heads/main", PreviousValue::MustNotExist, Target::Object(id(1))),
+        ],
+    )?;
+    let head = stack.find("HEAD")?;
+    assert_eq!(head.target, Target::Symbolic("refs/heads/main".try_into()?));
+
+    let mut edit = update("HEAD", PreviousValue::Any, Target::Object(id(2)));
+    edit.deref = true;
+    let edits = commit(&stack, vec![edit])?;
+    assert_eq!(edits.len(), 2, "the referent is updated as well");
+    assert_eq!(
+        stack.find("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?),
+        "HEAD is unchanged"
+    );
+    assert_eq!(stack.find("main")?.target, Target::Object(id(2)));
+
+    let head_log = stack.reflog("HEAD".try_into()?)?.expect("HEAD has a log");
+    assert_eq!(head_log.len(), 1);
+    assert_eq!(
+        (head_log[0].previous_oid, head_log[0].new_oid),
+        (id(1), id(2)),
+        "the previous value is taken from the referent"
+    );
+    assert_eq!(
+        stack.reflog("refs/heads/main".try_into()?)?.map(|log| log.len()),
+        Some(2)
+    );
+    Ok(())
+}
+
+#[test]
+fn reflogs_follow_the_configuration() -> crate::Result {
+    let (_dir, mut stack) = empty_stack();
+    commit(
+        &stack,
+        vec![update("refs/tags/v1", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+    assert!(
+        !stack.reflog_exists("refs/tags/v1".try_into()?)?,
+        "tags have no log by default"
+    );
+
+    stack.write_reflog = WriteReflog::Always;
+    commit(
+        &stack,
+        vec![update("refs/tags/v1", PreviousValue::Any, Target::Object(id(2)))],
+    )?;
+    assert!(stack.reflog_exists("refs/tags/v1".try_into()?)?);
+
+    stack.write_reflog = WriteReflog::Disable;
+    commit(
+        &stack,
+        vec![update("refs/heads/main", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+    assert!(!stack.reflog_exists("refs/heads/main".try_into()?)?);
+
+    stack.write_reflog = WriteReflog::Normal;
+    let err = stack
+        .transaction()
+        .prepare(
+            vec![update("refs/heads/main", PreviousValue::Any, Target::Object(id(2)))],
+            Fail::Immediately,
+        )?
+        .commit(None)
+        .unwrap_err();
+    assert!(matches!(err, reftable::transaction::commit::Error::MissingCommitter));
+    assert_eq!(stack.find("main")?.target, Target::Object(id(1)), "nothing was written");
+    Ok(())
+}
+
+#[test]
+fn iteration_merges_all_tables() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    commit(
+        &stack,
+        vec![
+            update("refs/heads/b", PreviousValue::Any, Target::Object(id(1))),
+            update("refs/tags/t", PreviousValue::Any, Target::Object(id(1))),
+            update("refs/heads/d", PreviousValue::Any, Target::Object(id(1))),
+        ],
+    )?;
+    commit(
+        &stack,
+        vec![
+            update("refs/heads/a", PreviousValue::Any, Target::Object(id(2))),
+            update("refs/heads/d", PreviousValue::Any, Target::Object(id(2))),
+        ],
+    )?;
+    commit(
+        &stack,
+        vec![
+            update("HEAD", PreviousValue::Any, Target::Symbolic("refs/heads/a".try_into()?)),
+            delete("refs/heads/b", PreviousValue::Any),
+            update("refs/heads/c", PreviousValue::Any, Target::Object(id(3))),
+        ],
+    )?;
+
+    assert_eq!(
+        names(&stack, "")?,
+        ["HEAD", "refs/heads/a", "refs/heads/c", "refs/heads/d", "refs/tags/t"]
+    );
+    assert_eq!(
+        names(&stack, "refs/heads/")?,
+        ["refs/heads/a", "refs/heads/c", "refs/heads/d"]
+    );
+    let d = stack
+        .iter()?
+        .all()
+        .find(|r| r.as_ref().map_or(false, |r| r.name.as_bstr() == "refs/heads/d"))
+        .expect("present")?;
+    assert_eq!(d.target, Target::Object(id(2)), "newer tables win");
+    Ok(())
+}
+
+#[test]
+fn namespaces_isolate_references() -> crate::Result {
+    let (_dir, mut stack) = empty_stack();
+    commit(
+        &stack,
+        vec![update("refs/heads/main", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+    stack.namespace = Some(gix_ref::namespace::expand("foo")?);
+    assert_eq!(stack.try_find("main")?, None);
+    commit(
+        &stack,
+        vec![update(
+            "refs/heads/main",
+            PreviousValue::MustNotExist,
+            Target::Object(id(2)),
+        )],
+    )?;
+    let main = stack.find("main")?;
+    assert_eq!(main.name.as_bstr(), "refs/heads/main", "the namespace is stripped");
+    assert_eq!(main.target, Target::Object(id(2)));
+    assert_eq!(names(&stack, "")?, ["refs/heads/main"]);
+    assert!(stack.reflog_exists("refs/heads/main".try_into()?)?);
+
+    stack.namespace = None;
+    assert_eq!(
+        names(&stack, "")?,
+        ["refs/heads/main", "refs/namespaces/foo/refs/heads/main"]
+    );
+    assert_eq!(stack.find("main")?.target, Target::Object(id(1)));
+    Ok(())
+}
+
+#[test]
+fn compaction_merges_tables_and_drops_deletions() -> crate::Result {
+    let (_dir, stack) = empty_stack();
+    for idx in 0..5_u8 {
+        commit(
+            &stack,
+            vec![update(
+                &format!("refs/heads/b{idx}"),
+                PreviousValue::Any,
+                Target::Object(id(idx)),
+            )],
+        )?;
+    }
+    commit(&stack, vec![delete("refs/heads/b0", PreviousValue::MustExist)])?;
+    let old_tables = stack.tables()?.expect("present").names().to_vec();
+    assert_eq!(old_tables.len(), 6);
+
+    assert!(stack.compact(Fail::Immediately)?);
+    let tables = stack.tables()?.expect("present");
+    assert_eq!(tables.tables().len(), 1);
+    let table = &tables.tables()[0];
+    assert_eq!((table.min_update_index(), table.max_update_index()), (1, 6));
+    assert!(
+        table
+            .refs()
+            .all(|r| r.expect("valid").value != reftable::RefValue::Deletion),
+        "deletions are dropped as there is nothing left to shadow"
+    );
+    for name in old_tables {
+        assert!(!stack.dir().join(name).exists(), "merged tables are removed");
+    }
+    assert_eq!(names(&stack, "")?.len(), 4);
+    assert_eq!(stack.reflog("refs/heads/b1".try_into()?)?.map(|log| log.len()), Some(1));
+    assert!(!stack.reflog_exists("refs/heads/b0".try_into()?)?);
+
+    assert!(!stack.compact(Fail::Immediately)?, "nothing to do");
+    commit(
+        &stack,
+        vec![update("refs/heads/b1", PreviousValue::Any, Target::Object(id(9)))],
+    )?;
+    assert_eq!(stack.tables()?.expect("present").next_update_index(), 8);
+    Ok(())
+}
+
+#[test]
+fn auto_compaction_keeps_the_stack_small() -> crate::Result {
+    let (_dir, mut stack) = empty_stack();
+    stack.auto_compaction = true;
+    for idx in 0..64_u8 {
+        commit(
+            &stack,
+            vec![update(
+                &format!("refs/heads/b{idx:02}"),
+                PreviousValue::Any,
+                Target::Object(id(idx)),
+            )],
+        )?;
+        assert!(table_count(&stack)? <= 8, "table sizes form a geometric sequence");
+    }
+    assert_eq!(names(&stack, "")?.len(), 64);
+    assert_eq!(std::fs::read_dir(stack.dir())?.count(), table_count(&stack)? + 1);
+    Ok(())
+}
+
+#[test]
+fn general_store_selects_the_backend() -> crate::Result {
+    let (dir, stack) = empty_stack();
+    commit(
+        &stack,
+        vec![update("refs/heads/main", PreviousValue::Any, Target::Object(id(1)))],
+    )?;
+    let store = gix_ref::Store::at(dir.path().into(), StorageFormat::Reftable, Default::default())?;
+    assert_eq!(store.storage_format(), StorageFormat::Reftable);
+    let main = store.to_handle().find("main")?;
+    assert_eq!(main.name, FullName::try_from("refs/heads/main")?);
+
+    let store = gix_ref::Store::at(dir.path().into(), StorageFormat::Files, Default::default())?;
+    assert_eq!(
+        store.to_handle().try_find("main")?,
+        None,
+        "loose refs don't know the stack"
+    );
+    Ok(())
+}
+
+#[test]
+fn file_store_can_keep_references_in_the_stack() -> crate::Result {
+    let (dir, stack) = empty_stack();
+    let mut store = gix_ref::file::Store::at(dir.path().into(), Default::default());
+    assert_eq!(store.storage_format(), StorageFormat::Files);
+    assert!(store.set_reftable(Some(stack.clone())).is_none());
+    assert_eq!(store.storage_format(), StorageFormat::Reftable);
+
+    store
+        .transaction()
+        .prepare(
+            vec![
+                update("refs/heads/main", PreviousValue::MustNotExist, Target::Object(id(1))),
+                update("HEAD", PreviousValue::Any, Target::Symbolic("refs/heads/main".try_into()?)),
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(table_count(&stack)?, 1, "edits are written into a new table");
+    assert!(
+        !dir.path().join("refs").exists() && !dir.path().join("HEAD").exists(),
+        "no loose reference was written"
+    );
+
+    let head = store.find("HEAD")?;
+    assert_eq!(head.target, Target::Symbolic("refs/heads/main".try_into()?));
+    assert_eq!(store.find("main")?.target, Target::Object(id(1)));
+    let names: Vec<_> = store
+        .iter()?
+        .all()?
+        .map(|r| r.map(|r| r.name.as_bstr().to_string()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(names, ["HEAD", "refs/heads/main"]);
+    assert!(store.reflog_exists("refs/heads/main")?);
+    let mut buf = Vec::new();
+    let log: Vec<_> = store
+        .reflog_iter("refs/heads/main", &mut buf)?
+        .expect("present")
+        .map(|line| line.map(|line| line.new_oid()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(log, [id(1)], "reflogs are read from the stack as well");
+
+    store.namespace = Some(gix_ref::namespace::expand("foo")?);
+    assert_eq!(
+        store.try_find("main")?,
+        None,
+        "the namespace of the store takes precedence over the one of the stack"
+    );
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use gix_ref::reftable::{decode, write, LogValue, RefValue, Table};
+
+use crate::reftable::{id, log_record, ref_record};
+
+fn options(block_size: u32) -> write::Options {
+    write::Options {
+        block_size,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn empty_table_is_just_header_and_footer() -> crate::Result {
+    let data = write::table(gix_hash::Kind::Sha1, 1..=1, Vec::new(), Vec::new(), &Default::default())?;
+    assert_eq!(data.len(), 24 + 68);
+    assert_eq!(
+        &data[..8],
+        b"REFT\x01\x00\x10\x00",
+        "version 1 with a block size of 4096"
+    );
+    assert_eq!(&data[24..48], &data[..24], "the footer repeats the header");
+
+    let table = Table::from_bytes(data)?;
+    assert!(table.is_empty());
+    assert_eq!(table.min_update_index(), 1);
+    assert_eq!(table.max_update_index(), 1);
+    assert_eq!(table.refs().count(), 0);
+    assert_eq!(table.logs().count(), 0);
+    Ok(())
+}
+
+#[test]
+fn tiny_table_layout() -> crate::Result {
+    let data = write::table(
+        gix_hash::Kind::Sha1,
+        3..=4,
+        vec![ref_record("HEAD", 4, RefValue::Symbolic("refs/heads/main".into()))],
+        Vec::new(),
+        &Default::default(),
+    )?;
+    let mut expected = b"REFT\x01\x00\x10\x00".to_vec();
+    expected.extend_from_slice(&3_u64.to_be_bytes());
+    expected.extend_from_slice(&4_u64.to_be_bytes());
+    expected.extend_from_slice(b"r\x00\x00\x38"); // The block length includes the file header.
+    expected.extend_from_slice(b"\x00\x23HEAD"); // No prefix, suffix of 4 bytes with value type 3.
+    expected.extend_from_slice(b"\x01\x0frefs/heads/main"); // Update index delta and the target.
+    expected.extend_from_slice(b"\x00\x00\x1c\x00\x01"); // One restart point.
+    assert_eq!(&data[..expected.len()], expected.as_slice());
+    assert_eq!(data.len(), expected.len() + 68, "the only block isn't padded");
+
+    let table = Table::from_bytes(data)?;
+    assert_eq!(
+        table.find_ref("HEAD".into())?,
+        Some(ref_record("HEAD", 4, RefValue::Symbolic("refs/heads/main".into())))
+    );
+    assert_eq!(table.find_ref("HEA".into())?, None);
+    assert_eq!(table.find_ref("refs/heads/main".into())?, None);
+    Ok(())
+}
+
+#[test]
+fn many_refs_and_logs_round_trip_through_multi_level_indices() -> crate::Result {
+    let count = 3000_usize;
+    let name = |idx: usize| format!("refs/heads/branch-{idx:05}");
+    let refs: Vec<_> = (0..count)
+        .map(|idx| {
+            let value = match idx % 4 {
+                0 => RefValue::Object(id(idx as u8)),
+                1 => RefValue::Peeled {
+                    target: id(idx as u8),
+                    peeled: id(!(idx as u8)),
+                },
+                2 => RefValue::Symbolic(name(idx + 1).into()),
+                _ => RefValue::Deletion,
+            };
+            ref_record(&name(idx), 10 + (idx % 5) as u64, value)
+        })
+        .collect();
+    let logs: Vec<_> = (0..count / 10)
+        .flat_map(|idx| {
+            (10..13).map(move |update_index| {
+                log_record(
+                    &name(idx),
+                    update_index,
+                    id(update_index as u8),
+                    id(update_index as u8 + 1),
+                    &format!("message {update_index}"),
+                )
+            })
+        })
+        .collect();
+
+    let mut reversed = refs.clone();
+    reversed.reverse();
+    let data = write::table(gix_hash::Kind::Sha1, 10..=14, reversed, logs.clone(), &options(256))?;
+    let table = Table::from_bytes(data)?;
+
+    assert_eq!(
+        table.refs().collect::<Result<Vec<_>, _>>()?,
+        refs,
+        "records are sorted by name"
+    );
+    for record in refs.iter().step_by(7) {
+        assert_eq!(table.find_ref(record.name.as_ref())?.as_ref(), Some(record));
+    }
+    assert_eq!(table.find_ref("refs/heads/branch-99999".into())?, None);
+    assert_eq!(table.find_ref("refs/heads/a".into())?, None);
+
+    let prefixed = table
+        .refs_prefixed("refs/heads/branch-012".into())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(prefixed.len(), 100);
+    assert_eq!(prefixed[0], refs[1200]);
+
+    let all_logs = table.logs().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(all_logs.len(), logs.len());
+    let of_one = table.logs_of(name(42).as_str().into()).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        of_one.iter().map(|r| r.update_index).collect::<Vec<_>>(),
+        [12, 11, 10],
+        "logs are ordered from newest to oldest"
+    );
+    assert_eq!(of_one[0], logs[42 * 3 + 2], "logs round-trip");
+    assert_eq!(
+        table.logs_of("refs/heads/branch-0004".into()).count(),
+        0,
+        "only exact names match"
+    );
+    Ok(())
+}
+
+#[test]
+fn sha256_tables_use_version_2() -> crate::Result {
+    let id = gix_hash::ObjectId::from_bytes_or_panic(&[7; 32]);
+    let data = write::table(
+        gix_hash::Kind::Sha256,
+        1..=1,
+        vec![ref_record("refs/heads/main", 1, RefValue::Object(id))],
+        vec![log_record(
+            "refs/heads/main",
+            1,
+            gix_hash::Kind::Sha256.null(),
+            id,
+            "initial",
+        )],
+        &Default::default(),
+    )?;
+    assert_eq!(&data[..5], b"REFT\x02");
+    assert_eq!(&data[24..28], b"s256");
+
+    let table = Table::from_bytes(data)?;
+    assert_eq!(table.object_hash(), gix_hash::Kind::Sha256);
+    assert_eq!(
+        table.find_ref("refs/heads/main".into())?.map(|r| r.value),
+        Some(RefValue::Object(id))
+    );
+    let logs = table.logs().collect::<Result<Vec<_>, _>>()?;
+    assert!(matches!(&logs[0].value, LogValue::Update(line) if line.new_oid == id && line.message == "initial"));
+    Ok(())
+}
+
+#[test]
+fn corruption_is_detected() -> crate::Result {
+    let mut data = write::table(
+        gix_hash::Kind::Sha1,
+        1..=1,
+        vec![ref_record("refs/heads/main", 1, RefValue::Object(id(1)))],
+        Vec::new(),
+        &Default::default(),
+    )?;
+    let last = data.len() - 1;
+    data[last] ^= 1;
+    assert!(matches!(Table::from_bytes(data), Err(decode::Error::Checksum { .. })));
+    assert!(matches!(
+        Table::from_bytes(b"REFT".to_vec()),
+        Err(decode::Error::Truncated)
+    ));
+    Ok(())
+}
+
+#[test]
+fn invalid_input_is_rejected() {
+    let main = || ref_record("refs/heads/main", 1, RefValue::Object(id(1)));
+    assert!(matches!(
+        write::table(
+            gix_hash::Kind::Sha1,
+            1..=1,
+            vec![main(), main()],
+            Vec::new(),
+            &Default::default()
+        ),
+        Err(write::Error::DuplicateRecord { .. })
+    ));
+    assert!(matches!(
+        write::table(
+            gix_hash::Kind::Sha1,
+            2..=3,
+            vec![main()],
+            Vec::new(),
+            &Default::default()
+        ),
+        Err(write::Error::UpdateIndexOutOfRange { update_index: 1, .. })
+    ));
+    assert!(matches!(
+        write::table(gix_hash::Kind::Sha1, 1..=1, vec![main()], Vec::new(), &options(100)),
+        Err(write::Error::InvalidBlockSize { block_size: 100 })
+    ));
+    assert!(matches!(
+        write::table(
+            gix_hash::Kind::Sha1,
+            1..=1,
+            Vec::new(),
+            vec![log_record("refs/heads/main", 1, id(0), id(1), "two\nlines")],
+            &Default::default()
+        ),
+        Err(write::Error::MultiLineLogMessage { .. })
+    ));
+    assert!(matches!(
+        write::table(
+            gix_hash::Kind::Sha1,
+            1..=1,
+            vec![ref_record(&"x".repeat(300), 1, RefValue::Deletion)],
+            Vec::new(),
+            &options(256)
+        ),
+        Err(write::Error::RecordTooLarge { .. })
+    ));
+}
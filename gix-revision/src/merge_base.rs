@@ -53,7 +53,9 @@ pub(crate) mod function {
         graph.clear_commit_data(|f| *f = Flags::empty());
         let bases = paint_down_to_common(first, others, graph)?;
 
-        let bases = remove_redundant(&bases, graph)?;
+        let mut bases = remove_redundant(&bases, graph)?;
+        // Like `git`, order by commit time as the order of discovery depends on the kind of generation numbers.
+        bases.sort_by_key(|id| std::cmp::Reverse(graph.get(id).expect("previously added").commit_time));
         Ok((!bases.is_empty()).then_some(bases))
     }
 
@@ -214,8 +216,8 @@ pub(crate) mod function {
     // TODO(ST): Should this type be used for `describe` as well?
     #[derive(Debug, Clone, Copy)]
     struct GenThenTime {
-        /// Note that the special [`GENERATION_NUMBER_INFINITY`](gix_commitgraph::GENERATION_NUMBER_INFINITY) is used to indicate
-        /// that no commitgraph is available.
+        /// Note that the special [`GENERATION_INFINITY`](gix_commitgraph::GENERATION_INFINITY) is used to indicate
+        /// that the commit isn't in the commitgraph.
         generation: gix_revwalk::graph::Generation,
         time: gix_date::SecondsSinceUnixEpoch,
     }
@@ -223,7 +225,7 @@ pub(crate) mod function {
     impl From<&graph::Commit<Flags>> for GenThenTime {
         fn from(commit: &graph::Commit<Flags>) -> Self {
             GenThenTime {
-                generation: commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+                generation: commit.generation.unwrap_or(gix_commitgraph::GENERATION_INFINITY),
                 time: commit.commit_time,
             }
         }
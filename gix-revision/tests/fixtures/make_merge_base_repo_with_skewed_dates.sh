@@ -0,0 +1,76 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# A history whose commit dates don't follow the topology, so ordering commits by their
+# topological level differs from ordering them by their corrected commit date.
+# The commit-graph in `objects/info` has corrected commit dates, the one in
+# `objects/info-v1` has topological levels only.
+
+git init -q
+git config merge.ff false
+
+day=86400
+tick=1112911993
+function commit() {
+  local message=${1:?first argument is the commit message}
+  local skew=${2:-0}
+  tick=$(($tick + 60))
+  GIT_COMMITTER_DATE="$(($tick + $skew)) -0700" GIT_AUTHOR_DATE="$(($tick + $skew)) -0700" \
+    git commit -q --allow-empty -m "$message"
+  git tag "$message"
+}
+
+function merge() {
+  local message=${1:?first argument is the commit message}
+  shift
+  tick=$(($tick + 60))
+  GIT_COMMITTER_DATE="$tick -0700" GIT_AUTHOR_DATE="$tick -0700" \
+    git merge -q -m "$message" "$@"
+  git tag "$message"
+}
+
+function baseline() {
+  echo "$@"
+  echo $(git rev-parse "$@")
+  git merge-base --all "$@" || :
+  echo
+}
+
+# A long chain of commits with dates in order, and a short one with dates far in the future
+# and in the past, which are merged back and forth.
+git checkout -q -b main
+commit R
+for i in $(seq 1 8); do
+  commit A$i
+done
+
+git checkout -q -b side R
+commit S1 $(($day * 365))
+commit S2 $((-$day * 730))
+commit S3
+
+git checkout -q main
+merge M1 S2
+commit A9 $((-$day * 30))
+
+git checkout -q side
+merge M2 A4
+commit S4 $((-$day * 365))
+
+git checkout -q main
+merge M3 S4
+git checkout -q side
+commit S5
+
+git -c commitGraph.generationVersion=1 commit-graph write --no-progress --reachable
+mkdir .git/objects/info-v1
+mv .git/objects/info/commit-graph .git/objects/info-v1/
+git -c commitGraph.generationVersion=2 commit-graph write --no-progress --reachable
+
+commits=$(git tag)
+for first in $commits; do
+  for second in $commits; do
+    baseline $first $second
+  done
+done > permutations.baseline
+baseline A9 S5 M2 > octopus.baseline
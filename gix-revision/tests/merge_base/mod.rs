@@ -43,6 +43,52 @@ mod baseline {
         Ok(())
     }
 
+    #[test]
+    fn validate_with_topological_levels_and_corrected_commit_dates() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("make_merge_base_repo_with_skewed_dates.sh")?;
+        let odb = gix_odb::at(root.join(".git/objects"))?;
+        let v1 = gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info-v1"))?;
+        let v2 = gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info"))?;
+        assert!(!v1.has_generation_data());
+        assert!(v2.has_generation_data());
+
+        let generations = |graph: &gix_commitgraph::Graph, name: &str| {
+            let id = gix_hash::ObjectId::from_hex(
+                std::fs::read(root.join(".git/refs/tags").join(name))
+                    .expect("tag exists")
+                    .trim(),
+            )
+            .expect("valid hex");
+            graph.generation(&graph.commit_by_id(id).expect("in graph"))
+        };
+        assert!(
+            generations(&v1, "A8") > generations(&v1, "S3"),
+            "the topological level of the long chain is higher…"
+        );
+        assert!(
+            generations(&v2, "A8") < generations(&v2, "S3"),
+            "…but its corrected commit date is lower, as the short chain has dates in the future"
+        );
+
+        for baseline_path in expectation_paths(&root)? {
+            for cache in [None, Some(&v1), Some(&v2)] {
+                for expected in parse_expectations(&baseline_path)? {
+                    let mut graph = gix_revision::Graph::new(&odb, cache);
+                    let actual = merge_base(expected.first, &expected.others, &mut graph)?;
+                    assert_eq!(
+                        actual,
+                        expected.bases,
+                        "sample {file:?}:{input} with generation data: {generation_data:?}",
+                        file = baseline_path.with_extension("").file_name(),
+                        input = expected.plain_input,
+                        generation_data = cache.map(gix_commitgraph::Graph::has_generation_data)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// The expectation as produced by Git itself
     #[derive(Debug)]
     struct Expectation {
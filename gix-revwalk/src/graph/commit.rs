@@ -29,7 +29,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
     pub fn generation(&self) -> Option<Generation> {
         match &self.backing {
             Either::Left(_) => None,
-            Either::Right((cache, pos)) => cache.commit_at(*pos).generation().into(),
+            Either::Right((cache, pos)) => cache.generation(&cache.commit_at(*pos)).into(),
         }
     }
 
@@ -45,9 +45,9 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
             Either::Right((cache, pos)) => {
                 let commit = cache.commit_at(*pos);
                 (
-                    commit.generation().into(),
+                    cache.generation(&commit).into(),
                     // a cast as we cannot represent the error and trying seems overkill
-                    cache.commit_at(*pos).committer_timestamp() as SecondsSinceUnixEpoch,
+                    commit.committer_timestamp() as SecondsSinceUnixEpoch,
                 )
             }
         })
@@ -100,7 +100,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
                             actual: commit.committer_timestamp(),
                         }
                     })?,
-                    generation: Some(commit.generation()),
+                    generation: Some(cache.generation(&commit)),
                     data,
                 }
             }
@@ -47,11 +47,13 @@ mod errors {
 pub use errors::{get_or_insert_default, insert_parents};
 use gix_date::SecondsSinceUnixEpoch;
 
-/// The generation away from the HEAD of graph, useful to limit algorithms by topological depth as well.
+/// The generation of a commit as obtained from the commit-graph, useful to limit algorithms by topological depth as well.
 ///
-/// 0 would mean the starting point of the hierarchy, and 1 their parents.
-/// This number is only available natively if there is a commit-graph.
-pub type Generation = u32;
+/// It's the corrected commit date if all commit-graph files have one, or the topological level otherwise, which is 1 for
+/// root commits and one more than the highest level of their parents for all others.
+/// In both cases, it's larger than the generation of all parents of a commit.
+/// This number is only available natively if there is a commit-graph, see [`gix_commitgraph::Graph::generation()`].
+pub type Generation = u64;
 
 impl<T: std::fmt::Debug> std::fmt::Debug for Graph<'_, '_, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -405,7 +407,7 @@ pub struct Commit<T> {
     /// The time at which the commit was created.
     pub commit_time: SecondsSinceUnixEpoch,
     /// The generation of the commit, if available.
-    pub generation: Option<u32>,
+    pub generation: Option<Generation>,
     /// Any kind of data to associate with this commit.
     pub data: T,
 }
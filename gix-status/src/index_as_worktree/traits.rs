@@ -143,14 +143,14 @@ impl CompareBlobs for HashEq {
         let mut stream = data.stream_worktree_file()?;
         match stream.as_bytes() {
             Some(buffer) => {
-                let file_hash = gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buffer);
+                let file_hash = gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buffer)?;
                 Ok((entry.id != file_hash).then_some(file_hash))
             }
             None => {
                 let file_hash = match stream.size() {
                     None => {
                         stream.read_to_end(buf)?;
-                        gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buf)
+                        gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buf)?
                     }
                     Some(len) => gix_object::compute_stream_hash(
                         entry.id.kind(),
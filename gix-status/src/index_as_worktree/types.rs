@@ -18,6 +18,8 @@ pub enum Error {
         rela_path: BString,
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
 }
 
 /// Options that control how the index status with a worktree is computed.
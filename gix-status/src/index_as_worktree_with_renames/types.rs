@@ -18,6 +18,8 @@ pub enum Error {
     OpenWorktreeFile(std::io::Error),
     #[error(transparent)]
     HashFile(std::io::Error),
+    #[error(transparent)]
+    Hasher(#[from] gix_features::hash::Error),
     #[error("Could not read worktree link content")]
     ReadLink(std::io::Error),
     #[error(transparent)]
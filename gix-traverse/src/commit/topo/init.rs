@@ -101,7 +101,7 @@ where
             indegree_queue: PriorityQueue::new(),
             topo_queue: super::iter::Queue::new(self.sorting),
             parents: self.parents,
-            min_gen: gix_commitgraph::GENERATION_NUMBER_INFINITY,
+            min_gen: gix_commitgraph::GENERATION_INFINITY,
             buf: vec![],
         };
 
@@ -119,7 +119,7 @@ where
         {
             *w.indegrees.entry(*id).or_default() = 1;
             let commit = find(w.commit_graph.as_ref(), &w.find, id, &mut w.buf)?;
-            let (gen, time) = gen_and_commit_time(commit)?;
+            let (gen, time) = gen_and_commit_time(w.commit_graph.as_ref(), commit)?;
 
             if gen < w.min_gen {
                 w.min_gen = gen;
@@ -155,7 +155,7 @@ where
             }
 
             let commit = find(w.commit_graph.as_ref(), &w.find, id, &mut w.buf)?;
-            let (_, time) = gen_and_commit_time(commit)?;
+            let (_, time) = gen_and_commit_time(w.commit_graph.as_ref(), commit)?;
             let parent_ids = w.collect_all_parents(id)?.into_iter().map(|e| e.0).collect();
 
             w.topo_queue.push(
@@ -4,7 +4,7 @@ use gix_hash::{oid, ObjectId};
 use gix_revwalk::PriorityQueue;
 use smallvec::SmallVec;
 
-pub(in crate::commit) type GenAndCommitTime = (u32, i64);
+pub(in crate::commit) type GenAndCommitTime = (gix_revwalk::graph::Generation, i64);
 
 // Git's priority queue works as a LIFO stack if no compare function is set,
 // which is the case for `--topo-order.` However, even in that case the initial
@@ -49,7 +49,10 @@ impl<Find, Predicate> Topo<Find, Predicate>
 where
     Find: gix_object::Find,
 {
-    pub(super) fn compute_indegrees_to_depth(&mut self, gen_cutoff: u32) -> Result<(), Error> {
+    pub(super) fn compute_indegrees_to_depth(
+        &mut self,
+        gen_cutoff: gix_revwalk::graph::Generation,
+    ) -> Result<(), Error> {
         while let Some(((gen, _), _)) = self.indegree_queue.peek() {
             if *gen >= gen_cutoff {
                 self.indegree_walk_step()?;
@@ -79,7 +82,7 @@ where
         Ok(())
     }
 
-    fn explore_to_depth(&mut self, gen_cutoff: u32) -> Result<(), Error> {
+    fn explore_to_depth(&mut self, gen_cutoff: gix_revwalk::graph::Generation) -> Result<(), Error> {
         while let Some(((gen, _), _)) = self.explore_queue.peek() {
             if *gen >= gen_cutoff {
                 self.explore_walk_step()?;
@@ -266,7 +269,7 @@ where
             // doesn't mean a parent is not.
             for (id, gen_time) in parents.iter_mut() {
                 let commit = find(cache.as_ref(), &f, id, buf)?;
-                *gen_time = gen_and_commit_time(commit)?;
+                *gen_time = gen_and_commit_time(cache.as_ref(), commit)?;
             }
         }
         Either::CachedCommit(c) => {
@@ -276,13 +279,14 @@ where
                     *cache = None;
                     return collect_parents(cache, f, id, first_only, buf);
                 };
-                let parent_commit = cache
-                    .as_ref()
-                    .expect("cache exists if CachedCommit was returned")
-                    .commit_at(pos);
+                let cache = cache.as_ref().expect("cache exists if CachedCommit was returned");
+                let parent_commit = cache.commit_at(pos);
                 parents.push((
                     parent_commit.id().into(),
-                    (parent_commit.generation(), parent_commit.committer_timestamp() as i64),
+                    (
+                        cache.generation(&parent_commit),
+                        parent_commit.committer_timestamp() as i64,
+                    ),
                 ));
                 if first_only {
                     break;
@@ -293,7 +297,13 @@ where
     Ok(parents)
 }
 
-pub(super) fn gen_and_commit_time(c: Either<'_, '_>) -> Result<GenAndCommitTime, Error> {
+/// Return the generation of `c` as assigned by `cache`, along with its commit time.
+///
+/// Commits that aren't in `cache` are assigned [`GENERATION_INFINITY`](gix_commitgraph::GENERATION_INFINITY).
+pub(super) fn gen_and_commit_time(
+    cache: Option<&gix_commitgraph::Graph>,
+    c: Either<'_, '_>,
+) -> Result<GenAndCommitTime, Error> {
     match c {
         Either::CommitRefIter(c) => {
             let mut commit_time = 0;
@@ -311,8 +321,11 @@ pub(super) fn gen_and_commit_time(c: Either<'_, '_>) -> Result<GenAndCommitTime,
                     Err(err) => return Err(err.into()),
                 }
             }
-            Ok((gix_commitgraph::GENERATION_NUMBER_INFINITY, commit_time))
+            Ok((gix_commitgraph::GENERATION_INFINITY, commit_time))
         }
-        Either::CachedCommit(c) => Ok((c.generation(), c.committer_timestamp() as i64)),
+        Either::CachedCommit(c) => Ok((
+            cache.expect("cache exists if CachedCommit was returned").generation(&c),
+            c.committer_timestamp() as i64,
+        )),
     }
 }
@@ -0,0 +1,60 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use gix_hash::ObjectId;
+use gix_traverse::commit::topo;
+use std::path::Path;
+
+fn git_rev_list(repo_dir: &Path) -> Vec<ObjectId> {
+    let out = std::process::Command::new("git")
+        .args(["rev-list", "--topo-order", "HEAD"])
+        .current_dir(repo_dir)
+        .output()
+        .expect("git can be executed");
+    assert!(out.status.success(), "git rev-list failed");
+    out.stdout
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|hex| ObjectId::from_hex(hex).expect("valid hex"))
+        .collect()
+}
+
+fn gix_topo(store: &gix_odb::Handle, objects_dir: &Path, tip: ObjectId, info_dir: Option<&str>) -> Vec<ObjectId> {
+    let graph = info_dir.map(|info_dir| gix_commitgraph::at(objects_dir.join(info_dir)).expect("graph is valid"));
+    topo::Builder::from_iters(store, Some(tip), None::<Vec<ObjectId>>)
+        .sorting(topo::Sorting::TopoOrder)
+        .with_commit_graph(graph)
+        .build()
+        .expect("tip exists")
+        .map(|res| res.map(|info| info.id))
+        .collect::<Result<_, _>>()
+        .expect("traversal succeeds")
+}
+
+fn topo_order_with_skewed_dates(c: &mut Criterion) {
+    let repo_dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_topo_with_skewed_dates.sh")
+        .expect("fixture can be created")
+        .join("large");
+    let objects_dir = repo_dir.join(".git").join("objects");
+    let store = gix_odb::at(&objects_dir).expect("valid object database");
+
+    let expected = git_rev_list(&repo_dir);
+    let tip = expected[0];
+    let mut group = c.benchmark_group("topo-order with skewed commit dates");
+    group.throughput(Throughput::Elements(expected.len() as u64));
+    group.bench_function("git rev-list --topo-order", |b| b.iter(|| git_rev_list(&repo_dir)));
+    for (name, info_dir) in [
+        ("gix without commit-graph", None),
+        ("gix with topological levels", Some("info-v1")),
+        ("gix with corrected commit dates", Some("info")),
+    ] {
+        assert_eq!(
+            gix_topo(&store, &objects_dir, tip, info_dir),
+            expected,
+            "{name} must produce the same order as git"
+        );
+        group.bench_function(name, |b| b.iter(|| gix_topo(&store, &objects_dir, tip, info_dir)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, topo_order_with_skewed_dates);
+criterion_main!(benches);
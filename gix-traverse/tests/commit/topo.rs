@@ -371,3 +371,71 @@ mod date_order {
         Ok(())
     }
 }
+
+mod skewed_dates {
+    use gix_hash::ObjectId;
+    use gix_object::bstr::ByteSlice;
+    use gix_traverse::commit::{topo, Parents};
+    use std::path::Path;
+
+    use crate::hex_to_id;
+
+    fn read_ids(dir: &Path, name: &str) -> crate::Result<Vec<ObjectId>> {
+        Ok(std::fs::read(dir.join(name))?
+            .lines()
+            .map(|hex| hex_to_id(hex.to_str().expect("ascii")))
+            .collect())
+    }
+
+    /// Walk `repo_name` without commit-graph, with topological levels and with corrected commit dates, and assert
+    /// that each walk matches what `git rev-list` produced even though some commits are not in the graph.
+    fn assert_matches_baselines(repo_name: &str) -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_topo_with_skewed_dates.sh")?
+            .join(repo_name);
+        let objects_dir = dir.join(".git").join("objects");
+        let store = gix_odb::at(&objects_dir)?;
+        let end = read_ids(&dir, "end")?;
+
+        for (info_dir, has_generation_data) in [("info-v1", false), ("info", true)] {
+            let graph = gix_commitgraph::at(objects_dir.join(info_dir))?;
+            assert_eq!(graph.has_generation_data(), has_generation_data, "{info_dir}");
+        }
+
+        for (baseline, ends, sorting, parents) in [
+            ("all-commits", &[][..], topo::Sorting::TopoOrder, Parents::All),
+            ("first-parent", &[], topo::Sorting::TopoOrder, Parents::First),
+            ("date-order", &[], topo::Sorting::DateOrder, Parents::All),
+            ("with-end", &end, topo::Sorting::TopoOrder, Parents::All),
+        ] {
+            let expected = read_ids(&dir, &format!("{baseline}.baseline"))?;
+            let tip = expected.first().copied().expect("baselines start with the tip");
+            for info_dir in [None, Some("info-v1"), Some("info")] {
+                let graph = info_dir
+                    .map(|info_dir| gix_commitgraph::at(objects_dir.join(info_dir)))
+                    .transpose()?;
+                let actual = topo::Builder::from_iters(&store, Some(tip), Some(ends.iter().copied()))
+                    .sorting(sorting)
+                    .parents(parents)
+                    .with_commit_graph(graph)
+                    .build()?
+                    .map(|res| res.map(|info| info.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(
+                    actual, expected,
+                    "{repo_name}: {baseline} with commit-graph in {info_dir:?}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn small() -> crate::Result {
+        assert_matches_baselines("small")
+    }
+
+    #[test]
+    fn large() -> crate::Result {
+        assert_matches_baselines("large")
+    }
+}
@@ -0,0 +1,128 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Histories whose commit dates don't follow the topology, so walks that rely on
+# them for cut-offs would end early. Each repository has a commit-graph with
+# corrected commit dates in `objects/info` and one with topological levels only
+# in `objects/info-v1`. Both leave out the newest commits so the walk has to fall back
+# to the object database for them.
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  local skew=${2:-0}
+  tick
+  GIT_COMMITTER_DATE="$(($tick + $skew)) -0700" GIT_AUTHOR_DATE="$(($tick + $skew)) -0700" \
+    git commit -q --allow-empty -m "$message"
+}
+
+function write_commit_graphs() {
+  git -c commitGraph.generationVersion=1 commit-graph write --no-progress --reachable
+  mkdir .git/objects/info-v1
+  mv .git/objects/info/commit-graph .git/objects/info-v1/
+  git commit-graph write --no-progress --reachable
+}
+
+function collect_baselines() {
+  local end=${1:?first argument is the commit to stop at}
+  git rev-list --topo-order HEAD > all-commits.baseline
+  git rev-list --topo-order --first-parent HEAD > first-parent.baseline
+  git rev-list --date-order HEAD > date-order.baseline
+  git rev-list --topo-order "^$end" HEAD > with-end.baseline
+  git rev-parse "$end" > end
+}
+
+day=86400
+
+(git init -q small && cd small
+  git config merge.ff false
+  git checkout -q -b main
+  tick
+  commit c0
+  commit c1
+  git branch branch1
+  git branch branch2
+  commit c2 $((day * 30))
+  commit c3 $((-day * 60))
+
+  git checkout -q branch1
+  commit b1c1 $((-day * 365))
+  commit b1c2
+  commit b1c3 $((day * 365))
+
+  git checkout -q branch2
+  commit b2c1 $((-day * 10))
+  git merge -q branch1 -m "merge branch1 into branch2"
+  commit b2c2 $((-day * 400))
+
+  git checkout -q main
+  git merge -q branch1 -m "merge branch1"
+  commit c4 $((-day * 500))
+  git tag end
+  git merge -q branch2 -m "merge branch2"
+
+  write_commit_graphs
+
+  commit c5 $((-day * 1000))
+  git checkout -q branch2
+  commit b2c3 $((day * 1000))
+  git checkout -q main
+  git merge -q branch2 -m "merge branch2 again"
+  commit c6
+
+  collect_baselines end
+)
+
+(git init -q large && cd large
+  {
+    main=
+    side=
+    for i in $(seq 1 3000); do
+      date=$((1112911993 + i * 60))
+      if (( i % 7 == 0 )); then date=$((date - day * 30)); fi
+      if (( i % 11 == 0 )); then date=$((date + day * 30)); fi
+      if (( i % 3 == 0 )); then branch=side; parent=${side:-$main}; else branch=main; parent=$main; fi
+
+      echo "commit refs/heads/$branch"
+      echo "mark :$i"
+      echo "committer C O Mitter <committer@example.com> $date +0000"
+      echo "data 0"
+      if [ -n "$parent" ]; then echo "from :$parent"; fi
+      if [ "$branch" = main ] && [ -n "$side" ] && (( i % 10 == 1 )); then
+        echo "merge :$side"
+        side=
+      fi
+      echo
+
+      if [ "$branch" = main ]; then main=$i; else side=$i; fi
+    done
+  } | git fast-import --quiet
+  git checkout -q main
+  git branch -q end HEAD~500
+
+  write_commit_graphs
+
+  {
+    for i in $(seq 1 100); do
+      echo "commit refs/heads/main"
+      echo "committer C O Mitter <committer@example.com> $((1112911993 - i * 60)) +0000"
+      echo "data 0"
+      if (( i == 1 )); then echo "from refs/heads/main^0"; fi
+      echo
+    done
+  } | git fast-import --quiet
+  git reset -q --hard main
+
+  collect_baselines end
+)
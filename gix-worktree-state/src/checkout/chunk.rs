@@ -107,7 +107,7 @@ pub fn process<'entry, Find>(
     ctx: &mut Context<Find>,
 ) -> Result<Outcome<'entry>, checkout::Error>
 where
-    Find: gix_object::Find + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Clone,
 {
     let mut delayed_symlinks = Vec::new();
     let mut collisions = Vec::new();
@@ -162,7 +162,7 @@ pub fn process_delayed_filter_results<Find>(
     ctx: &mut Context<Find>,
 ) -> Result<(), checkout::Error>
 where
-    Find: gix_object::Find + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Clone,
 {
     let Options {
         destination_is_initially_empty,
@@ -295,7 +295,7 @@ pub fn checkout_entry_handle_result<'entry, Find>(
     }: &mut Context<Find>,
 ) -> Result<entry::Outcome<'entry>, checkout::Error>
 where
-    Find: gix_object::Find + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Clone,
 {
     let res = entry::checkout(
         entry,
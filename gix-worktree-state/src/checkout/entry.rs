@@ -76,7 +76,7 @@ pub fn checkout<'entry, Find>(
     }: crate::checkout::chunk::Options,
 ) -> Result<Outcome<'entry>, crate::checkout::Error>
 where
-    Find: gix_object::Find,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream,
 {
     let dest_relative = gix_path::try_from_bstr(entry_path).map_err(|_| crate::checkout::Error::IllformedUtf8 {
         path: entry_path.to_owned(),
@@ -86,6 +86,22 @@ where
 
     let object_size = match entry.mode {
         gix_index::entry::Mode::FILE | gix_index::entry::Mode::FILE_EXECUTABLE => {
+            let large_blob = find_large_blob_stream(&*objects, filters, entry, entry_path, dest, &mut |_, attrs| {
+                path_cache.matching_attributes(attrs);
+            })?;
+            if let Some(mut stream) = large_blob {
+                let (mut file, set_executable_after_creation) = open_file(
+                    dest,
+                    destination_is_initially_empty,
+                    overwrite_existing,
+                    executable_bit,
+                    entry.mode,
+                )?;
+                let num_bytes = std::io::copy(&mut stream, &mut file)? as usize;
+                finalize_entry(entry, file, set_executable_after_creation.then_some(dest))?;
+                return Ok(Outcome::Written { bytes: num_bytes });
+            }
+
             let obj = (*objects)
                 .find_blob(&entry.id, buf)
                 .map_err(|err| crate::checkout::Error::Find {
@@ -190,6 +206,47 @@ where
     Ok(Outcome::Written { bytes: object_size })
 }
 
+/// Return a stream over the blob of `entry` if it's larger than the threshold configured in `filters`, and if no filter
+/// may change it according to the `attributes` at `entry_path`, so it can be written to `dest` without holding it in memory.
+fn find_large_blob_stream<Find>(
+    objects: &Find,
+    filters: &mut gix_filter::Pipeline,
+    entry: &Entry,
+    entry_path: &BStr,
+    dest: &Path,
+    attributes: &mut dyn FnMut(&BStr, &mut gix_filter::attributes::search::Outcome),
+) -> Result<Option<gix_object::find::Stream>, crate::checkout::Error>
+where
+    Find: gix_object::FindHeader + gix_object::FindStream,
+{
+    let large_file_threshold_bytes = filters.options().large_file_threshold_bytes;
+    if large_file_threshold_bytes == 0
+        || filters
+            .may_convert_to_worktree(entry_path, attributes)
+            .map_err(gix_filter::pipeline::convert::to_worktree::Error::from)?
+    {
+        return Ok(None);
+    }
+    let find_error = |err| crate::checkout::Error::Find {
+        err,
+        path: dest.to_path_buf(),
+    };
+    let is_large_blob = objects
+        .try_header(&entry.id)
+        .map_err(|err| find_error(gix_object::find::existing_object::Error::Find(err)))?
+        .map_or(false, |header| {
+            header.kind == gix_object::Kind::Blob && header.size > large_file_threshold_bytes
+        });
+    if !is_large_blob {
+        return Ok(None);
+    }
+    objects
+        .try_find_stream(&entry.id)
+        .map_err(|err| find_error(gix_object::find::existing_object::Error::Find(err)))?
+        .ok_or_else(|| find_error(gix_object::find::existing_object::Error::NotFound { oid: entry.id }))
+        .map(Some)
+}
+
 /// Note that this works only because we assume to not race ourselves when symlinks are involved, and we do this by
 /// delaying symlink creation to the end and will always do that sequentially.
 /// It's still possible to fall for a race if other actors create symlinks in our path, but that's nothing to defend against.
@@ -26,7 +26,7 @@ pub fn checkout<Find>(
     options: crate::checkout::Options,
 ) -> Result<crate::checkout::Outcome, crate::checkout::Error>
 where
-    Find: gix_object::Find + Send + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Send + Clone,
 {
     let paths = index.take_path_backing();
     let res = checkout_inner(index, &paths, dir, objects, files, bytes, should_interrupt, options);
@@ -46,7 +46,7 @@ fn checkout_inner<Find>(
     mut options: crate::checkout::Options,
 ) -> Result<crate::checkout::Outcome, crate::checkout::Error>
 where
-    Find: gix_object::Find + Send + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Send + Clone,
 {
     let num_files = files.counter();
     let num_bytes = bytes.counter();
@@ -594,6 +594,45 @@ pub fn dir_structure<P: AsRef<std::path::Path>>(path: P) -> Vec<std::path::PathB
     files
 }
 
+#[test]
+fn large_blobs_are_streamed_if_no_filter_applies() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.keep_going = true;
+    opts.filters.options_mut().large_file_threshold_bytes = 1;
+    let attributes_id = gix_object::compute_hash(
+        gix_hash::Kind::Sha1,
+        gix_object::Kind::Blob,
+        &std::fs::read(fixture_path("make_mixed_without_submodules").join(".gitattributes"))?,
+    )?;
+    let (source_tree, destination, _index, outcome) = checkout_index_in_tmp_dir_opts(
+        opts,
+        "make_mixed_without_submodules",
+        None,
+        move |id| id == attributes_id,
+        |_| Ok(()),
+    )?;
+
+    let mut failed: Vec<_> = outcome
+        .errors
+        .iter()
+        .map(|r| r.path.to_path_lossy().into_owned())
+        .collect();
+    failed.sort();
+    assert_eq!(
+        failed,
+        paths(["dir/sub-dir/symlink", "empty"]),
+        "symlinks and small blobs have to be found in full, which isn't possible here"
+    );
+    for path in [".gitattributes", "dir/content", "executable"] {
+        assert_eq!(
+            std::fs::read(destination.path().join(path))?,
+            std::fs::read(source_tree.join(path))?,
+            "{path}: large blobs without filters were streamed"
+        );
+    }
+    Ok(())
+}
+
 fn checkout_index_in_tmp_dir(
     opts: gix_worktree_state::checkout::Options,
     name: &str,
@@ -647,6 +686,30 @@ fn checkout_index_in_tmp_dir_opts(
         }
     }
 
+    impl<Allow, Find> gix_object::FindHeader for MaybeFind<Allow, Find>
+    where
+        Allow: Clone,
+        Find: gix_object::FindHeader + Clone,
+    {
+        fn try_header(&self, id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+            self.objects.try_header(id)
+        }
+    }
+
+    /// Streams aren't affected by `allow` so they can be told apart from objects that were found in full.
+    impl<Allow, Find> gix_object::FindStream for MaybeFind<Allow, Find>
+    where
+        Allow: Clone,
+        Find: gix_object::FindStream + Clone,
+    {
+        fn try_find_stream(
+            &self,
+            id: &gix_hash::oid,
+        ) -> Result<Option<gix_object::find::Stream>, gix_object::find::Error> {
+            self.objects.try_find_stream(id)
+        }
+    }
+
     let db = MaybeFind {
         allow: allow_return_object.into(),
         objects: odb,
@@ -9,6 +9,8 @@ use crate::{entry, entry::Error, protocol, AdditionalEntry, SharedErrorSlot, Str
 ///
 /// `pipeline` is used to convert blobs to their worktree representation, and `attributes` is used to read
 /// the `export-ignore` attribute. If set on a directory or blob, it won't be added to the archive.
+/// Blobs larger than the [threshold](gix_filter::pipeline::Options::large_file_threshold_bytes) of `pipeline`
+/// which no filter may change are streamed from `objects` instead of being loaded into memory.
 ///
 /// ### Types of entries in stream
 ///
@@ -41,7 +43,7 @@ pub fn from_tree<Find, E>(
         + 'static,
 ) -> Stream
 where
-    Find: gix_object::Find + Clone + Send + 'static,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Clone + Send + 'static,
     E: std::error::Error + Send + Sync + 'static,
 {
     let (stream, mut write, additional_entries) = Stream::new();
@@ -87,7 +89,7 @@ fn run<Find, E>(
     additional_entries: std::sync::mpsc::Receiver<AdditionalEntry>,
 ) -> Result<(), Error>
 where
-    Find: gix_object::Find + Clone,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream + Clone,
     E: std::error::Error + Send + Sync + 'static,
 {
     let mut buf = Vec::new();
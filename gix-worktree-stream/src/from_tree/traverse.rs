@@ -3,7 +3,7 @@ use std::{collections::VecDeque, io::Write};
 use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
 use gix_object::{
     bstr::{BStr, BString, ByteSlice, ByteVec},
-    tree, FindExt,
+    tree, FindExt, FindStreamExt,
 };
 use gix_traverse::tree::{visit::Action, Visit};
 
@@ -11,7 +11,7 @@ use crate::{entry::Error, protocol, SharedErrorSlot};
 
 pub struct Delegate<'a, AttributesFn, Find>
 where
-    Find: gix_object::Find,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream,
 {
     pub(crate) out: &'a mut gix_features::io::pipe::Writer,
     pub(crate) err: SharedErrorSlot,
@@ -26,7 +26,7 @@ where
 
 impl<AttributesFn, Find> Delegate<'_, AttributesFn, Find>
 where
-    Find: gix_object::Find,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream,
     AttributesFn:
         FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), Error> + 'static,
 {
@@ -54,6 +54,36 @@ where
             .state
     }
 
+    /// Return a stream over the blob of `entry` if it's larger than the threshold configured in our pipeline,
+    /// and if no filter may change it, so it can be passed on without holding it in memory.
+    fn find_large_blob_stream(
+        &mut self,
+        entry: &tree::EntryRef<'_>,
+    ) -> Result<Option<gix_object::find::Stream>, Error> {
+        let large_file_threshold_bytes = self.pipeline.options().large_file_threshold_bytes;
+        if large_file_threshold_bytes == 0
+            || self
+                .pipeline
+                .may_convert_to_worktree(self.path.as_ref(), &mut |a, b| {
+                    (self.fetch_attributes)(a, entry.mode, b).ok();
+                })
+                .map_err(gix_filter::pipeline::convert::to_worktree::Error::from)?
+        {
+            return Ok(None);
+        }
+        let is_large_blob = self
+            .objects
+            .try_header(entry.oid)
+            .map_err(gix_object::find::existing::Error::Find)?
+            .map_or(false, |header| {
+                header.kind == gix_object::Kind::Blob && header.size > large_file_threshold_bytes
+            });
+        if !is_large_blob {
+            return Ok(None);
+        }
+        Ok(Some(self.objects.find_stream(entry.oid)?))
+    }
+
     fn handle_entry(&mut self, entry: &tree::EntryRef<'_>) -> Result<Action, Error> {
         if !entry.mode.is_blob_or_symlink() {
             return Ok(Action::Continue);
@@ -62,6 +92,19 @@ where
         if self.ignore_state().is_set() {
             return Ok(Action::Continue);
         }
+        if entry.mode.is_blob() {
+            if let Some(mut stream) = self.find_large_blob_stream(entry)? {
+                protocol::write_entry_header_and_path(
+                    self.path.as_ref(),
+                    entry.oid,
+                    entry.mode,
+                    Some(stream.size as usize),
+                    self.out,
+                )?;
+                std::io::copy(&mut stream, self.out)?;
+                return Ok(Action::Continue);
+            }
+        }
         self.objects.find(entry.oid, &mut self.buf)?;
 
         self.pipeline.driver_context_mut().blob = Some(entry.oid.into());
@@ -101,7 +144,7 @@ where
 
 impl<AttributesFn, Find> Visit for Delegate<'_, AttributesFn, Find>
 where
-    Find: gix_object::Find,
+    Find: gix_object::Find + gix_object::FindHeader + gix_object::FindStream,
     AttributesFn:
         FnMut(&BStr, gix_object::tree::EntryMode, &mut gix_attributes::search::Outcome) -> Result<(), Error> + 'static,
 {
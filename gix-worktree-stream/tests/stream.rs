@@ -32,6 +32,94 @@ mod from_tree {
         }
     }
 
+    impl gix_object::FindHeader for FailObjectRetrieval {
+        fn try_header(&self, _id: &oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+            Err(Box::new(Error::new(ErrorKind::Other, "header retrieval failed")))
+        }
+    }
+
+    impl gix_object::FindStream for FailObjectRetrieval {
+        fn try_find_stream(&self, _id: &oid) -> Result<Option<gix_object::find::Stream>, gix_object::find::Error> {
+            Err(Box::new(Error::new(ErrorKind::Other, "stream retrieval failed")))
+        }
+    }
+
+    /// Fail to find `blob` in full, while allowing it to be streamed.
+    #[derive(Clone)]
+    struct OnlyStream {
+        objects: gix_odb::HandleArc,
+        blob: gix_hash::ObjectId,
+    }
+
+    impl gix_object::Find for OnlyStream {
+        fn try_find<'a>(&self, id: &oid, buffer: &'a mut Vec<u8>) -> Result<Option<Data<'a>>, gix_object::find::Error> {
+            if id == self.blob {
+                return Err(Box::new(Error::new(ErrorKind::Other, "blob must be streamed")));
+            }
+            self.objects.try_find(id, buffer)
+        }
+    }
+
+    impl gix_object::FindHeader for OnlyStream {
+        fn try_header(&self, id: &oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+            self.objects.try_header(id)
+        }
+    }
+
+    impl gix_object::FindStream for OnlyStream {
+        fn try_find_stream(&self, id: &oid) -> Result<Option<gix_object::find::Stream>, gix_object::find::Error> {
+            self.objects.try_find_stream(id)
+        }
+    }
+
+    #[test]
+    fn large_blobs_are_streamed_if_no_filter_applies() -> gix_testtools::Result {
+        let (_dir, head_tree, odb, mut cache) = basic()?;
+        let bigfile = hex_to_id("4995fde49ed64e043977e22539f66a0d372dd129");
+        let mut stream = gix_worktree_stream::from_tree(
+            head_tree,
+            OnlyStream {
+                objects: odb.clone(),
+                blob: bigfile,
+            },
+            gix_filter::Pipeline::new(
+                Default::default(),
+                gix_filter::pipeline::Options {
+                    large_file_threshold_bytes: 1024,
+                    ..Default::default()
+                },
+            ),
+            move |rela_path, mode, attrs| {
+                cache
+                    .at_entry(rela_path, Some(mode.into()), &odb)
+                    .map(|entry| entry.matching_attributes(attrs))
+                    .map(|_| ())
+            },
+        );
+
+        let mut num_entries = 0;
+        let mut buf = Vec::new();
+        while let Some(mut entry) = stream.next_entry().expect("entry retrieval does not fail") {
+            num_entries += 1;
+            let is_bigfile = entry.id == bigfile;
+            if is_bigfile {
+                assert_eq!(
+                    entry.bytes_remaining(),
+                    Some(156 * 1024),
+                    "the size of streamed blobs is known upfront"
+                );
+            }
+            buf.clear();
+            entry.read_to_end(&mut buf)?;
+            if is_bigfile {
+                assert_eq!(buf.len(), 156 * 1024);
+                assert!(buf.iter().all(|b| *b == 0), "it's the unchanged blob");
+            }
+        }
+        assert_eq!(num_entries, 8, "all entries are present");
+        Ok(())
+    }
+
     #[test]
     fn can_receive_err_if_root_is_not_found() {
         let mut stream = gix_worktree_stream::from_tree(
@@ -35,6 +35,13 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this clone a partial one which omits objects matching `filter`, turning the remote into a *promisor remote*
+    /// from which these objects are obtained once they are needed.
+    pub fn with_filter(mut self, filter: crate::remote::fetch::ObjectFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
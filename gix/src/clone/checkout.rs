@@ -24,6 +24,8 @@ pub mod main_worktree {
         #[error(transparent)]
         WriteIndex(#[from] gix_index::file::write::Error),
         #[error(transparent)]
+        IndexWriteOptions(#[from] crate::config::index_write_options::Error),
+        #[error(transparent)]
         CheckoutOptions(#[from] crate::config::checkout_options::Error),
         #[error(transparent)]
         IndexCheckout(#[from] gix_worktree_state::checkout::Error),
@@ -35,6 +37,8 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error("Could not obtain the blobs to check out from the promisor remote")]
+        Prefetch(#[from] gix_odb::store::find::Error),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -118,6 +122,19 @@ pub mod main_worktree {
                 })?;
             let mut index = gix_index::File::from_state(index, repo.index_path());
 
+            // Obtain all blobs missing in a partial clone at once, instead of fetching them one by one while checking out.
+            repo.objects.prefetch(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| {
+                        !(entry.mode.is_submodule()
+                            || entry.mode.is_sparse()
+                            || entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE))
+                    })
+                    .map(|entry| entry.id),
+            )?;
+
             let mut opts = repo
                 .config
                 .checkout_options(repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
@@ -142,7 +159,7 @@ pub mod main_worktree {
             files.show_throughput(start);
             bytes.show_throughput(start);
 
-            index.write(Default::default())?;
+            index.write(repo.config.index_write_options()?)?;
             Ok((self.repo.take().expect("still present").clone(), outcome))
         }
     }
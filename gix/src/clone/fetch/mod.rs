@@ -121,7 +121,7 @@ impl PrepareFetch {
             clone_fetch_tags = remote::fetch::Tags::All.into();
         }
 
-        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone(), self.filter.as_ref())?;
 
         // Now we are free to apply remote configuration we don't want to be written to disk.
         if let Some(fetch_tags) = clone_fetch_tags {
@@ -137,7 +137,7 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
+        let mut pending_pack: remote::fetch::Prepare<'_, '_, _> = {
             let mut connection = remote.connect(remote::Direction::Fetch).await?;
             if let Some(f) = self.configure_connection.as_mut() {
                 f(&mut connection).map_err(Error::RemoteConnection)?;
@@ -198,6 +198,9 @@ impl PrepareFetch {
             b.insert_str(0, "clone: from ");
             b
         };
+        if let Some(filter) = self.filter.clone() {
+            pending_pack = pending_pack.with_filter(filter);
+        }
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
             .with_reflog_message(RefLogMessage::Override {
@@ -208,6 +211,11 @@ impl PrepareFetch {
             .await?;
 
         util::append_config_to_repo_config(repo, config);
+        #[cfg(feature = "blocking-network-client")]
+        if self.filter.is_some() {
+            // Objects omitted from the pack must be available to the checkout that typically follows.
+            crate::remote::promisor::install(repo);
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
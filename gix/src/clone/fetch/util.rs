@@ -8,6 +8,7 @@ use gix_ref::{
 use super::Error;
 use crate::{
     bstr::{BStr, BString, ByteSlice},
+    config::tree::{Core, Extensions, Key, Remote},
     Repository,
 };
 
@@ -20,9 +21,27 @@ enum WriteMode {
 pub fn write_remote_to_local_config_file(
     remote: &mut crate::Remote<'_>,
     remote_name: BString,
+    filter: Option<&crate::remote::fetch::ObjectFilter>,
 ) -> Result<gix_config::File<'static>, Error> {
     let mut config = gix_config::File::new(local_config_meta(remote.repo));
-    remote.save_as_to(remote_name, &mut config)?;
+    remote.save_as_to(remote_name.clone(), &mut config)?;
+    if let Some(filter) = filter {
+        let remote_name = remote_name.as_bstr();
+        for (key, value) in [
+            (&Remote::PROMISOR as &dyn Key, "true".into()),
+            (&Remote::PARTIAL_CLONE_FILTER, filter.to_bstring()),
+        ] {
+            config
+                .set_raw_value_by("remote", Some(remote_name), key.name(), value.as_bstr())
+                .expect("statically known key and validated remote name");
+        }
+        config
+            .set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")
+            .expect("statically known key");
+        config
+            .set_raw_value(&Extensions::PARTIAL_CLONE, remote_name)
+            .expect("statically known key");
+    }
 
     write_to_local_config(&config, WriteMode::Append)?;
     Ok(config)
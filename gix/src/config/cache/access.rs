@@ -141,7 +141,7 @@ impl Cache {
         &self,
     ) -> Result<gix_merge::blob::pipeline::Options, config::merge::pipeline_options::Error> {
         Ok(gix_merge::blob::pipeline::Options {
-            large_file_threshold_bytes: self.big_file_threshold()?,
+            large_file_threshold_bytes: self.big_file_threshold,
         })
     }
 
@@ -150,7 +150,7 @@ impl Cache {
         &self,
     ) -> Result<gix_diff::blob::pipeline::Options, config::diff::pipeline_options::Error> {
         Ok(gix_diff::blob::pipeline::Options {
-            large_file_threshold_bytes: self.big_file_threshold()?,
+            large_file_threshold_bytes: self.big_file_threshold,
             fs: self.fs_capabilities()?,
         })
     }
@@ -162,17 +162,6 @@ impl Cache {
             .copied()
     }
 
-    #[cfg(feature = "blob-diff")]
-    pub(crate) fn big_file_threshold(&self) -> Result<u64, config::unsigned_integer::Error> {
-        Ok(self
-            .resolved
-            .integer("core.bigFileThreshold")
-            .map(|number| Core::BIG_FILE_THRESHOLD.try_into_u64(number))
-            .transpose()
-            .with_leniency(self.lenient_config)?
-            .unwrap_or(512 * 1024 * 1024))
-    }
-
     /// Returns a user agent for use with servers.
     #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
     pub(crate) fn user_agent_tuple(&self) -> (&'static str, Option<Cow<'static, str>>) {
@@ -296,6 +285,38 @@ impl Cache {
         })
     }
 
+    #[cfg(feature = "index")]
+    pub(crate) fn index_write_options(&self) -> Result<gix_index::write::Options, config::index_write_options::Error> {
+        use crate::config::tree::{Index, SplitIndex};
+        let defaults = gix_index::write::SplitIndex::default();
+        Ok(gix_index::write::Options {
+            skip_hash: boolean(self, "index.skipHash", &Index::SKIP_HASH, false)?,
+            split_index: gix_index::write::SplitIndex {
+                enabled: self.apply_leniency(
+                    self.resolved
+                        .boolean("core.splitIndex")
+                        .map(|v| Core::SPLIT_INDEX.enrich_error(v)),
+                )?,
+                max_percent_change: self
+                    .resolved
+                    .integer("splitIndex.maxPercentChange")
+                    .map(|number| SplitIndex::MAX_PERCENT_CHANGE.try_into_u64(number))
+                    .transpose()
+                    .with_leniency(self.lenient_config)?
+                    .and_then(|percent| u8::try_from(percent).ok())
+                    .filter(|percent| *percent <= 100)
+                    .unwrap_or(defaults.max_percent_change),
+            },
+            thread_limit: self
+                .resolved
+                .string(Index::THREADS)
+                .map(|value| Index::THREADS.try_into_index_threads(value))
+                .transpose()
+                .with_leniency(self.lenient_config)?,
+            ..Default::default()
+        })
+    }
+
     #[cfg(any(feature = "index", feature = "tree-editor"))]
     pub(crate) fn protect_options(&self) -> Result<gix_validate::path::component::Options, config::boolean::Error> {
         const IS_WINDOWS: bool = cfg!(windows);
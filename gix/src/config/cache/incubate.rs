@@ -13,6 +13,7 @@ pub(crate) struct StageOne {
     pub is_bare: bool,
     pub lossy: Option<bool>,
     pub object_hash: gix_hash::Kind,
+    pub ref_storage: gix_ref::store::StorageFormat,
     pub reflog: Option<gix_ref::store::WriteReflog>,
     pub precompose_unicode: bool,
     pub protect_windows: bool,
@@ -54,6 +55,15 @@ impl StageOne {
             })
             .transpose()?
             .unwrap_or(gix_hash::Kind::Sha1);
+        let ref_storage = (repo_format_version == 1)
+            .then(|| {
+                config
+                    .string(Extensions::REF_STORAGE)
+                    .map(|format| Extensions::REF_STORAGE.try_into_ref_storage(format))
+            })
+            .flatten()
+            .transpose()?
+            .unwrap_or_default();
 
         let extension_worktree = util::config_bool(
             &config,
@@ -97,6 +107,7 @@ impl StageOne {
             is_bare,
             lossy,
             object_hash,
+            ref_storage,
             reflog,
             precompose_unicode,
             protect_windows,
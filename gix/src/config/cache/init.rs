@@ -26,6 +26,7 @@ impl Cache {
             lossy,
             is_bare,
             object_hash,
+            ref_storage: _,
             reflog: _,
             precompose_unicode: _,
             protect_windows: _,
@@ -158,6 +159,7 @@ impl Cache {
         let object_kind_hint = util::disambiguate_hint(&config, lenient_config)?;
         let (static_pack_cache_limit_bytes, pack_cache_bytes, object_cache_bytes) =
             util::parse_object_caches(&config, lenient_config, filter_config_section)?;
+        let big_file_threshold = util::parse_big_file_threshold(&config, lenient_config)?;
         // NOTE: When adding a new initial cache, consider adjusting `reread_values_and_clear_caches()` as well.
         Ok(Cache {
             resolved: config.into(),
@@ -168,6 +170,7 @@ impl Cache {
             static_pack_cache_limit_bytes,
             pack_cache_bytes,
             object_cache_bytes,
+            big_file_threshold,
             reflog,
             refs_namespace,
             is_bare,
@@ -246,6 +249,7 @@ impl Cache {
             self.pack_cache_bytes,
             self.object_cache_bytes,
         ) = util::parse_object_caches(config, self.lenient_config, self.filter_config_section)?;
+        self.big_file_threshold = util::parse_big_file_threshold(config, self.lenient_config)?;
         #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
         {
             self.url_scheme = Default::default();
@@ -286,16 +290,18 @@ impl crate::Repository {
         &mut self,
         config: crate::Config,
     ) -> Result<(), Error> {
-        let (a, b, c) = (
+        let (a, b, c, d) = (
             self.config.static_pack_cache_limit_bytes,
             self.config.pack_cache_bytes,
             self.config.object_cache_bytes,
+            self.config.big_file_threshold,
         );
         self.config.reread_values_and_clear_caches_replacing_config(config)?;
         self.apply_changed_values();
         if a != self.config.static_pack_cache_limit_bytes
             || b != self.config.pack_cache_bytes
             || c != self.config.object_cache_bytes
+            || d != self.config.big_file_threshold
         {
             setup_objects(&mut self.objects, &self.config);
         }
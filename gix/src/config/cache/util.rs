@@ -103,6 +103,19 @@ pub(crate) fn parse_object_caches(
     Ok((static_pack_cache_limit, pack_cache_bytes, object_cache_bytes))
 }
 
+/// Return the amount of bytes after which blobs are considered big, as parsed from `core.bigFileThreshold`.
+pub(crate) fn parse_big_file_threshold(
+    config: &gix_config::File<'static>,
+    lenient: bool,
+) -> Result<u64, config::unsigned_integer::Error> {
+    Ok(config
+        .integer("core.bigFileThreshold")
+        .map(|number| Core::BIG_FILE_THRESHOLD.try_into_u64(number))
+        .transpose()
+        .with_leniency(lenient)?
+        .unwrap_or(512 * 1024 * 1024))
+}
+
 pub(crate) fn parse_core_abbrev(
     config: &gix_config::File<'static>,
     object_hash: gix_hash::Kind,
@@ -195,6 +195,22 @@ pub mod stat_options {
     }
 }
 
+///
+#[cfg(feature = "index")]
+pub mod index_write_options {
+    /// The error produced when collecting the options for writing the index.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] super::boolean::Error),
+        #[error(transparent)]
+        ConfigUnsignedInteger(#[from] super::unsigned_integer::Error),
+        #[error(transparent)]
+        ConfigIndexThreads(#[from] super::key::GenericErrorWithValue),
+    }
+}
+
 ///
 #[cfg(feature = "attributes")]
 pub mod checkout_options {
@@ -616,6 +632,8 @@ pub(crate) struct Cache {
     pub(crate) object_cache_bytes: usize,
     /// The amount of bytes we can hold in our static LRU cache. Otherwise, go with the defaults.
     pub(crate) static_pack_cache_limit_bytes: Option<usize>,
+    /// The amount of bytes after which blobs are considered big, as configured by `core.bigFileThreshold`.
+    pub(crate) big_file_threshold: u64,
     /// The config section filter from the options used to initialize this instance. Keep these in sync!
     filter_config_section: fn(&gix_config::file::Metadata) -> bool,
     /// The object kind to pick if a prefix is ambiguous.
@@ -24,6 +24,8 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commitGraph` section.
+        pub const COMMIT_GRAPH: sections::CommitGraph = sections::CommitGraph;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -37,6 +39,8 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
         /// The `http` section.
@@ -57,8 +61,12 @@ pub(crate) mod root {
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `repack` section.
+        pub const REPACK: sections::Repack = sections::Repack;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
+        /// The `splitIndex` section.
+        pub const SPLIT_INDEX: sections::SplitIndex = sections::SplitIndex;
         /// The `ssh` section.
         pub const SSH: sections::Ssh = sections::Ssh;
         /// The `status` section.
@@ -76,6 +84,7 @@ pub(crate) mod root {
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COMMIT_GRAPH,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -83,6 +92,7 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
                 &Self::HTTP,
                 &Self::INDEX,
@@ -93,7 +103,9 @@ pub(crate) mod root {
                 &Self::PROTOCOL,
                 &Self::PUSH,
                 &Self::REMOTE,
+                &Self::REPACK,
                 &Self::SAFE,
+                &Self::SPLIT_INDEX,
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
@@ -106,9 +118,9 @@ pub(crate) mod root {
 
 mod sections;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, remote, ssh,
+    Author, Branch, Checkout, Clone, CommitGraph, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http,
+    Index, Init, Mailmap, Merge, Pack, Protocol, Push, Remote, Repack, Safe, SplitIndex, Ssh, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
@@ -0,0 +1,22 @@
+use crate::{
+    config,
+    config::tree::{keys, CommitGraph, Key, Section},
+};
+
+impl CommitGraph {
+    /// The `commitGraph.generationVersion` key.
+    pub const GENERATION_VERSION: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("generationVersion", &config::Tree::COMMIT_GRAPH).with_deviation(
+            "Only used when writing commit-graphs, as corrected commit dates are always read if all commit-graph files have them",
+        );
+}
+
+impl Section for CommitGraph {
+    fn name(&self) -> &str {
+        "commitGraph"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::GENERATION_VERSION]
+    }
+}
@@ -8,14 +8,21 @@ impl Extensions {
     pub const WORKTREE_CONFIG: keys::Boolean = keys::Boolean::new_boolean("worktreeConfig", &config::Tree::EXTENSIONS);
     /// The `extensions.objectFormat` key.
     pub const OBJECT_FORMAT: ObjectFormat =
-        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
-            "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
-        );
+        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat);
+    /// The `extensions.partialClone` key, naming the promisor remote.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
+    /// The `extensions.refStorage` key.
+    pub const REF_STORAGE: RefStorage =
+        RefStorage::new_with_validate("refStorage", &config::Tree::EXTENSIONS, validate::RefStorage);
 }
 
 /// The `core.checkStat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
+/// The `extensions.refStorage` key.
+pub type RefStorage = keys::Any<validate::RefStorage>;
+
 mod object_format {
     use std::borrow::Cow;
 
@@ -28,6 +35,29 @@ mod object_format {
         ) -> Result<gix_hash::Kind, config::key::GenericErrorWithValue> {
             if value.as_ref().eq_ignore_ascii_case(b"sha1") {
                 Ok(gix_hash::Kind::Sha1)
+            } else if value.as_ref().eq_ignore_ascii_case(b"sha256") {
+                Ok(gix_hash::Kind::Sha256)
+            } else {
+                Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+            }
+        }
+    }
+}
+
+mod ref_storage {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::extensions::RefStorage};
+
+    impl RefStorage {
+        pub fn try_into_ref_storage(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_ref::store::StorageFormat, config::key::GenericErrorWithValue> {
+            if value.as_ref().eq_ignore_ascii_case(b"files") {
+                Ok(gix_ref::store::StorageFormat::Files)
+            } else if value.as_ref().eq_ignore_ascii_case(b"reftable") {
+                Ok(gix_ref::store::StorageFormat::Reftable)
             } else {
                 Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
             }
@@ -41,7 +71,12 @@ impl Section for Extensions {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[
+            &Self::OBJECT_FORMAT,
+            &Self::PARTIAL_CLONE,
+            &Self::REF_STORAGE,
+            &Self::WORKTREE_CONFIG,
+        ]
     }
 }
 
@@ -50,6 +85,15 @@ mod validate {
 
     pub struct ObjectFormat;
 
+    pub struct RefStorage;
+
+    impl keys::Validate for RefStorage {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::REF_STORAGE.try_into_ref_storage(value.into())?;
+            Ok(())
+        }
+    }
+
     impl keys::Validate for ObjectFormat {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
             super::Extensions::OBJECT_FORMAT.try_into_object_format(value.into())?;
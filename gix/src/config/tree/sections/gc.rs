@@ -0,0 +1,126 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.packRefs` key.
+    pub const PACK_REFS: PackRefs = PackRefs::new_with_validate("packRefs", &config::Tree::GC, validate::PackRefs);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expire = Expire::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expire);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expire = Expire::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expire)
+        .with_deviation("per-reference values in `gc.<pattern>.reflogExpire` are not supported");
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expire =
+        Expire::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expire)
+            .with_deviation("per-reference values in `gc.<pattern>.reflogExpireUnreachable` are not supported");
+    /// The `gc.writeCommitGraph` key.
+    pub const WRITE_COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("writeCommitGraph", &config::Tree::GC);
+}
+
+/// The `gc.packRefs` key.
+pub type PackRefs = keys::Any<validate::PackRefs>;
+
+/// A key which holds an expiry date, like `gc.pruneExpire`.
+pub type Expire = keys::Any<validate::Expire>;
+
+mod pack_refs {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::gc::PackRefs,
+    };
+
+    impl PackRefs {
+        /// Return `true` if references should be packed in a repository that `is_bare`, as `value` can also be `notbare`.
+        pub fn try_into_pack_refs(
+            &'static self,
+            value: Cow<'_, BStr>,
+            is_bare: bool,
+        ) -> Result<bool, config::key::GenericErrorWithValue> {
+            if value.as_ref().as_bytes() == b"notbare" {
+                return Ok(!is_bare);
+            }
+            gix_config::Boolean::try_from(value.as_ref())
+                .map(|b| b.0)
+                .map_err(|err| {
+                    config::key::GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err)
+                })
+        }
+    }
+}
+
+mod expire {
+    use std::{borrow::Cow, time::SystemTime};
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::gc::Expire,
+    };
+
+    impl Expire {
+        /// Return the time in seconds since the unix epoch at or before which something is considered expired,
+        /// or `None` if it never expires.
+        ///
+        /// Relative dates like `2.weeks.ago` are relative to `now`, and `now` or `all` let everything expire,
+        /// while `never` or `false` prevent expiry.
+        pub fn try_into_expire(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: SystemTime,
+        ) -> Result<Option<gix_date::SecondsSinceUnixEpoch>, config::key::GenericErrorWithValue<gix_date::parse::Error>>
+        {
+            let err = || config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned());
+            let date = value.as_ref().to_str().map_err(|_| err())?;
+            Ok(match date {
+                "never" | "false" => None,
+                "now" | "all" => Some(gix_date::SecondsSinceUnixEpoch::MAX),
+                date => Some(
+                    gix_date::parse(&date.replace('.', " "), Some(now))
+                        .map_err(|source| err().with_source(source))?
+                        .seconds,
+                ),
+            })
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::PACK_REFS,
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+            &Self::WRITE_COMMIT_GRAPH,
+        ]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct PackRefs;
+    impl keys::Validate for PackRefs {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PACK_REFS.try_into_pack_refs(value.into(), false)?;
+            Ok(())
+        }
+    }
+
+    pub struct Expire;
+    impl keys::Validate for Expire {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_expire(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
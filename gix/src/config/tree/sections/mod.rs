@@ -20,6 +20,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commitGraph` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct CommitGraph;
+mod commit_graph;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -52,6 +57,11 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
@@ -100,11 +110,21 @@ pub mod push;
 pub struct Remote;
 pub mod remote;
 
+/// The `repack` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Repack;
+mod repack;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
 mod safe;
 
+/// The `splitIndex` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct SplitIndex;
+mod split_index;
+
 /// The `ssh` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Ssh;
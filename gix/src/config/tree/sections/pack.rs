@@ -12,6 +12,16 @@ impl Pack {
     /// The `pack.indexVersion` key.
     pub const INDEX_VERSION: IndexVersion =
         IndexVersion::new_with_validate("indexVersion", &config::Tree::PACK, validate::IndexVersion);
+
+    /// The `pack.window` key.
+    pub const WINDOW: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("window", &config::Tree::PACK);
+
+    /// The `pack.depth` key.
+    pub const DEPTH: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer("depth", &config::Tree::PACK);
+
+    /// The `pack.island` key.
+    pub const ISLAND: keys::String = keys::String::new_string("island", &config::Tree::PACK);
 }
 
 /// The `pack.indexVersion` key.
@@ -42,7 +52,13 @@ impl Section for Pack {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::INDEX_VERSION]
+        &[
+            &Self::THREADS,
+            &Self::INDEX_VERSION,
+            &Self::WINDOW,
+            &Self::DEPTH,
+            &Self::ISLAND,
+        ]
     }
 }
 
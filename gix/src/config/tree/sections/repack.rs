@@ -0,0 +1,19 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Repack, Section},
+};
+
+impl Repack {
+    /// The `repack.useDeltaIslands` key.
+    pub const USE_DELTA_ISLANDS: keys::Boolean = keys::Boolean::new_boolean("useDeltaIslands", &config::Tree::REPACK);
+}
+
+impl Section for Repack {
+    fn name(&self) -> &str {
+        "repack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::USE_DELTA_ISLANDS]
+    }
+}
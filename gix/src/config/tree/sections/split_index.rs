@@ -0,0 +1,21 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, SplitIndex},
+};
+
+impl SplitIndex {
+    /// The `splitIndex.maxPercentChange` key.
+    pub const MAX_PERCENT_CHANGE: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("maxPercentChange", &config::Tree::SPLIT_INDEX)
+            .with_note("values larger than 100 are ignored in favor of the default of 20, just like git does");
+}
+
+impl Section for SplitIndex {
+    fn name(&self) -> &str {
+        "splitIndex"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::MAX_PERCENT_CHANGE]
+    }
+}
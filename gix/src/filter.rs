@@ -107,6 +107,7 @@ impl<'repo> Pipeline<'repo> {
             encodings_with_roundtrip_check: encodings,
             crlf_roundtrip_check: safe_crlf,
             object_hash: repo.object_hash(),
+            large_file_threshold_bytes: repo.config.big_file_threshold,
         })
     }
 
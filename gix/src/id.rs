@@ -40,6 +40,20 @@ impl<'repo> Id<'repo> {
         self.repo.try_find_header(self.inner)
     }
 
+    /// Obtain a [`stream`][gix_odb::find::Stream] over the data of the object associated with this object id, or an error if it doesn't exist.
+    ///
+    /// Use this method to read large objects without holding them in memory.
+    pub fn stream(&self) -> Result<gix_odb::find::Stream, find::existing::Error> {
+        self.repo.find_object_stream(self.inner)
+    }
+
+    /// Obtain a [`stream`][gix_odb::find::Stream] over the data of the object associated with this object id, or return `None` if it doesn't exist.
+    ///
+    /// Use this method to read large objects without holding them in memory.
+    pub fn try_stream(&self) -> Result<Option<gix_odb::find::Stream>, find::Error> {
+        self.repo.try_find_object_stream(self.inner)
+    }
+
     /// Turn this object id into a shortened id with a length in hex as configured by `core.abbrev`.
     pub fn shorten(&self) -> Result<gix_hash::Prefix, shorten::Error> {
         let hex_len = self.repo.config.hex_len.map_or_else(
@@ -198,7 +212,7 @@ mod tests {
     #[test]
     fn size_of_oid() {
         let actual = std::mem::size_of::<Id<'_>>();
-        let ceiling = 32;
+        let ceiling = 48;
         assert!(
             actual <= ceiling,
             "size of oid shouldn't change without notice: {actual} <= {ceiling}"
@@ -95,6 +95,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "blame")]
+pub use gix_blame as blame;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -158,6 +160,8 @@ mod attribute_stack;
 pub mod path;
 
 /// The standard type for a store to handle git references.
+///
+/// In repositories with `extensions.refStorage = reftable`, it [keeps all references in reftables](gix_ref::file::Store::set_reftable()).
 pub type RefStore = gix_ref::file::Store;
 /// A handle for finding objects in an object database, abstracting away caches for thread-local use.
 pub type OdbHandle = gix_odb::memory::Proxy<gix_odb::Handle>;
@@ -306,6 +310,9 @@ pub mod config;
 #[cfg(feature = "mailmap")]
 pub mod mailmap;
 
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
+
 ///
 pub mod worktree;
 
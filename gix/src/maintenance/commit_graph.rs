@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_commitgraph::write::{ChangedPaths, Commit};
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_object::{bstr::BString, FindExt};
+use gix_ref::Target;
+
+use super::{CommitGraph, Error, ProgressId};
+use crate::Repository;
+
+/// Write the commit-graph with all commits reachable from `HEAD` and all references.
+///
+/// Changed paths are only computed for commits that aren't yet in the existing commit-graph with Bloom filters of the same kind,
+/// as their filters are reused.
+pub(super) fn write(
+    repo: &Repository,
+    options: &CommitGraph,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_commitgraph::write::Outcome, Error> {
+    if repo.is_shallow() {
+        return Err(Error::CommitGraphInShallowRepository);
+    }
+    let existing = repo
+        .commit_graph()
+        .ok()
+        .filter(|graph| options.changed_paths.is_some() && graph.bloom_filter_settings() == options.changed_paths);
+
+    let mut tips = Vec::new();
+    if let Ok(head) = repo.refs.find_loose("HEAD") {
+        tips.extend(head.target.try_id().map(ToOwned::to_owned));
+    }
+    for reference in repo.refs.iter()?.all().map_err(|err| Error::IterRefs(err.into()))? {
+        let reference = reference.map_err(|err| Error::IterRefs(err.into()))?;
+        if let Target::Object(id) = reference.target {
+            tips.push(id);
+        }
+    }
+    let mut stack: Vec<ObjectId> = tips
+        .into_iter()
+        .filter_map(|id| {
+            repo.find_object(id)
+                .ok()?
+                .peel_to_kind(gix_object::Kind::Commit)
+                .ok()
+                .map(|commit| commit.id)
+        })
+        .collect();
+
+    let mut commit_progress =
+        progress.add_child_with_id("writing commit-graph".into(), ProgressId::CommitGraphCommits.into());
+    commit_progress.init(None, gix_features::progress::count("commits"));
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut commits = Vec::new();
+    let mut diff_state = gix_diff::tree::State::default();
+    let (mut buf, mut lhs_buf, mut rhs_buf) = (Vec::new(), Vec::new(), Vec::new());
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        let (tree, parents, committer_timestamp) = {
+            let commit = repo.objects.find_commit(&id, &mut buf)?;
+            let parents: Vec<_> = commit.parents().collect();
+            (commit.tree(), parents, commit.committer().time.seconds.max(0) as u64)
+        };
+
+        let changed_paths = match options.changed_paths {
+            Some(settings) if existing.as_ref().map_or(true, |graph| graph.lookup(id).is_none()) => {
+                let parent_tree = parents
+                    .first()
+                    .map(|parent| repo.objects.find_commit(parent, &mut buf).map(|commit| commit.tree()))
+                    .transpose()?;
+                Some(ChangedPaths::Paths(changed_paths(
+                    repo,
+                    parent_tree,
+                    tree,
+                    settings.max_changed_paths as usize,
+                    &mut diff_state,
+                    (&mut lhs_buf, &mut rhs_buf),
+                )?))
+            }
+            _ => None,
+        };
+        stack.extend(parents.iter().filter(|parent| !seen.contains(*parent)));
+        commits.push(Commit {
+            id,
+            tree,
+            parents,
+            committer_timestamp,
+            changed_paths,
+        });
+        commit_progress.inc();
+    }
+    drop(existing);
+
+    Ok(gix_commitgraph::write::to_info_dir(
+        &repo.objects.store_ref().path().join("info"),
+        commits,
+        &gix_commitgraph::write::Options {
+            object_hash: repo.object_hash(),
+            generation_data: options.generation_data,
+            changed_paths: options.changed_paths,
+            split: options.split,
+        },
+    )?)
+}
+
+/// Return the paths of all files that differ between `parent_tree` and `tree`, or stop once there are more than `max` of them.
+fn changed_paths(
+    repo: &Repository,
+    parent_tree: Option<ObjectId>,
+    tree: ObjectId,
+    max: usize,
+    state: &mut gix_diff::tree::State,
+    (lhs_buf, rhs_buf): (&mut Vec<u8>, &mut Vec<u8>),
+) -> Result<Vec<BString>, Error> {
+    let lhs = match parent_tree {
+        Some(parent_tree) => repo.objects.find_tree_iter(&parent_tree, lhs_buf)?,
+        None => gix_object::TreeRefIter::from_bytes(&[], repo.object_hash()),
+    };
+    let rhs = repo.objects.find_tree_iter(&tree, rhs_buf)?;
+    let mut recorder = gix_diff::tree::Recorder::default();
+    gix_diff::tree(lhs, rhs, state, &repo.objects, &mut recorder)?;
+
+    use gix_diff::tree::recorder::Change;
+    let mut out = Vec::new();
+    for change in recorder.records {
+        let (is_file, path) = match change {
+            Change::Addition { entry_mode, path, .. } | Change::Deletion { entry_mode, path, .. } => {
+                (!entry_mode.is_tree(), path)
+            }
+            Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                path,
+                ..
+            } => (!previous_entry_mode.is_tree() || !entry_mode.is_tree(), path),
+        };
+        if is_file {
+            out.push(path);
+            if out.len() > max {
+                break;
+            }
+        }
+    }
+    Ok(out)
+}
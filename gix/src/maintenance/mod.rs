@@ -0,0 +1,431 @@
+//! Optimize repositories by repacking objects, pruning unreachable ones, packing references, expiring reflogs and
+//! writing the commit-graph, similar to `git gc` and `git maintenance`.
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_date::SecondsSinceUnixEpoch;
+
+use crate::{config, config::cache::util::ApplyLeniency, Repository};
+
+mod commit_graph;
+mod pack;
+mod prune;
+mod refs;
+
+/// How objects should be repacked by [Repository::maintenance()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Repack {
+    /// Put all loose objects into a new pack and delete them, leaving existing packs alone.
+    ///
+    /// This is like `git maintenance run --task=loose-objects`.
+    Incremental,
+    /// Put all reachable objects into a single new pack, reusing deltas of existing packs, and delete all other packs
+    /// unless they have a `.keep` file.
+    ///
+    /// Unreachable objects of deleted packs are unpacked as loose objects with the modification time of their pack,
+    /// unless their pack is older than [`Options::prune_expire`], so they will be pruned once they expired.
+    /// This is like `git repack -a -d --unpack-unreachable=<prune-expire>`.
+    Full,
+}
+
+/// How objects are delta-compressed when repacking with [Repository::maintenance()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeltaCompression {
+    /// The amount of objects to consider as delta base for each object, like `pack.window`.
+    pub window: usize,
+    /// The maximum length of delta chains, like `pack.depth`.
+    pub depth: usize,
+    /// If `true`, objects are only delta-compressed against objects that are reachable from at least the same islands
+    /// of references as configured with `pack.island`, like `repack.useDeltaIslands`.
+    pub delta_islands: bool,
+}
+
+impl Default for DeltaCompression {
+    fn default() -> Self {
+        DeltaCompression {
+            window: 10,
+            depth: 50,
+            delta_islands: false,
+        }
+    }
+}
+
+/// How the commit-graph is written by [Repository::maintenance()] and [Repository::write_commit_graph()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommitGraph {
+    /// If set, add a layer to a split commit-graph instead of rewriting a single `commit-graph` file, like
+    /// `git commit-graph write --split`.
+    pub split: Option<gix_commitgraph::write::Split>,
+    /// If `true`, write corrected commit dates, also known as generation numbers v2, which speed up traversals when
+    /// commit dates are skewed.
+    pub generation_data: bool,
+    /// If set, write changed-path Bloom filters with these settings to speed up traversals limited to paths, like
+    /// `git commit-graph write --changed-paths`.
+    pub changed_paths: Option<gix_commitgraph::bloom::Settings>,
+}
+
+/// Options for use in [Repository::maintenance()], typically obtained with [Repository::maintenance_options()].
+///
+/// All times are in seconds since the unix epoch, and `None` means that nothing ever expires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, loose references pointing to objects are moved into the `packed-refs` file, like `git pack-refs --all`.
+    pub pack_refs: bool,
+    /// Reflog entries created at or before this time are removed.
+    pub reflog_expire: Option<SecondsSinceUnixEpoch>,
+    /// Reflog entries created at or before this time are removed if the commits they refer to aren't reachable from
+    /// the current value of the reference anymore, or from any reference in case of `HEAD`.
+    pub reflog_expire_unreachable: Option<SecondsSinceUnixEpoch>,
+    /// How to repack objects, if at all.
+    pub repack: Option<Repack>,
+    /// If set, search for similar objects to delta-compress them against each other when repacking, like `git repack -f`
+    /// does. Deltas of existing packs are still reused.
+    /// Otherwise, objects that aren't already deltas in existing packs are written as they are.
+    pub delta_compression: Option<DeltaCompression>,
+    /// Loose objects that are unreachable and were last modified at or before this time are deleted.
+    ///
+    /// Note that unreachable objects which are still referenced by recent unreachable objects aren't protected,
+    /// so this should be at least as long as the longest operation writing objects that aren't yet referenced.
+    pub prune_expire: Option<SecondsSinceUnixEpoch>,
+    /// If `true`, write a multi-pack-index for all packs.
+    pub write_multi_pack_index: bool,
+    /// If set, write the commit-graph for all commits reachable from references.
+    pub commit_graph: Option<CommitGraph>,
+}
+
+/// The outcome of [Repository::maintenance()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of loose references moved into the `packed-refs` file.
+    pub packed_refs: usize,
+    /// The amount of reflog entries that were removed.
+    pub expired_reflog_entries: usize,
+    /// The path to the pack that was written, if there were objects to pack.
+    pub pack_path: Option<PathBuf>,
+    /// The amount of objects written to the new pack.
+    pub packed_objects: usize,
+    /// The amount of packs that were deleted after all of their reachable objects were written to the new pack.
+    pub deleted_packs: usize,
+    /// The amount of unreachable objects in deleted packs that were written as loose objects.
+    pub unpacked_objects: usize,
+    /// The amount of loose objects that were deleted as they were packed.
+    pub deleted_packed_loose_objects: usize,
+    /// The amount of unreachable loose objects that were deleted as they expired.
+    pub pruned_objects: usize,
+    /// The path to the multi-pack-index, if it was written.
+    pub multi_pack_index_path: Option<PathBuf>,
+    /// The path to the commit-graph file that was written, if any.
+    pub commit_graph_path: Option<PathBuf>,
+}
+
+/// The progress ids used in [Repository::maintenance()].
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of reachable objects that were counted.
+    CountObjects,
+    /// The amount of bytes of the new pack that were written.
+    WritePack,
+    /// The amount of loose objects that were checked for deletion.
+    PruneObjects,
+    /// The amount of commits that were collected for writing the commit-graph.
+    CommitGraphCommits,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::CountObjects => *b"MNCO",
+            ProgressId::WritePack => *b"MNWP",
+            ProgressId::PruneObjects => *b"MNPO",
+            ProgressId::CommitGraphCommits => *b"MNCG",
+        }
+    }
+}
+
+///
+pub mod options {
+    /// The error returned by [Repository::maintenance_options()](crate::Repository::maintenance_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Expire(#[from] crate::config::key::GenericErrorWithValue<gix_date::parse::Error>),
+        #[error(transparent)]
+        PackRefs(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        UnsignedInteger(#[from] crate::config::unsigned_integer::Error),
+    }
+}
+
+/// The error returned by [Repository::maintenance()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not pack references")]
+    PackRefs(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    InitRefIter(#[from] gix_ref::packed::buffer::open::Error),
+    #[error("Could not iterate references")]
+    IterRefs(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    ExpireReflog(#[from] gix_ref::file::log::expire::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error("Could not open a thread-safe handle to the object database")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    OpenBitmap(#[from] gix_pack::bitmap::bundle::Error),
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    IndexPack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    WriteMultiPackIndex(#[from] gix_pack::multi_index::write::Error),
+    #[error(transparent)]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DiffTree(#[from] gix_diff::tree::Error),
+    #[error(transparent)]
+    WriteCommitGraph(#[from] gix_commitgraph::write::Error),
+    #[error("Delta islands configured with `pack.island` need the `revparse-regex` feature")]
+    DeltaIslandsUnsupported,
+    #[cfg(feature = "revparse-regex")]
+    #[error("The `pack.island` pattern {pattern:?} is invalid")]
+    DeltaIslandPattern {
+        pattern: crate::bstr::BString,
+        source: regex::Error,
+    },
+    #[error("Only up to 64 delta islands are supported, but {0} were configured")]
+    TooManyDeltaIslands(usize),
+    #[error("Commit-graphs can't be written in shallow repositories")]
+    CommitGraphInShallowRepository,
+    #[error("An IO error occurred while {action} {path:?}")]
+    Io {
+        source: std::io::Error,
+        action: &'static str,
+        path: PathBuf,
+    },
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// Maintenance
+impl Repository {
+    /// Return the options for [maintenance()](Self::maintenance()) that are equivalent to `git gc` as configured
+    /// with `gc.packRefs`, `gc.reflogExpire`, `gc.reflogExpireUnreachable`, `gc.pruneExpire`, `gc.writeCommitGraph`,
+    /// `core.commitGraph`, `commitGraph.generationVersion`, `pack.window`, `pack.depth` and `repack.useDeltaIslands`.
+    ///
+    /// This means references are packed, reflogs expire after 90 days, or 30 days if they are unreachable,
+    /// all objects are fully repacked with delta compression and unreachable loose objects are pruned after two weeks.
+    /// Finally, a single commit-graph file is written with generation data unless `commitGraph.generationVersion` is 1,
+    /// and with changed-path Bloom filters if the existing commit-graph has them.
+    pub fn maintenance_options(&self) -> Result<Options, options::Error> {
+        use config::tree::{Gc, Pack};
+        const DAY: SecondsSinceUnixEpoch = 24 * 60 * 60;
+
+        let now = SystemTime::now();
+        let now_seconds = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as SecondsSinceUnixEpoch);
+        let config = &self.config.resolved;
+        let write_commit_graph = config
+            .boolean(Gc::WRITE_COMMIT_GRAPH)
+            .map(|res| Gc::WRITE_COMMIT_GRAPH.enrich_error(res))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or(true);
+        let unsigned = |key: &'static config::tree::keys::UnsignedInteger, default: usize| {
+            config
+                .integer(key)
+                .map(|value| key.try_into_usize(value))
+                .transpose()
+                .with_leniency(self.config.lenient_config)
+                .map(|value| value.unwrap_or(default))
+        };
+        let defaults = DeltaCompression::default();
+        let delta_compression = DeltaCompression {
+            window: unsigned(&Pack::WINDOW, defaults.window)?,
+            depth: unsigned(&Pack::DEPTH, defaults.depth)?,
+            delta_islands: config
+                .boolean(config::tree::Repack::USE_DELTA_ISLANDS)
+                .map(|res| config::tree::Repack::USE_DELTA_ISLANDS.enrich_error(res))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or(defaults.delta_islands),
+        };
+        let generation_data = unsigned(&config::tree::CommitGraph::GENERATION_VERSION, 2)? == 2;
+        let expire = |key: &'static config::tree::gc::Expire, default_days: SecondsSinceUnixEpoch| {
+            config
+                .string(key)
+                .map(|value| key.try_into_expire(value, now))
+                .transpose()
+                .with_leniency(self.config.lenient_config)
+                .map(|expire| expire.unwrap_or(Some(now_seconds - default_days * DAY)))
+        };
+        Ok(Options {
+            pack_refs: config
+                .string(Gc::PACK_REFS)
+                .map(|value| Gc::PACK_REFS.try_into_pack_refs(value, self.is_bare()))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or(true),
+            reflog_expire: expire(&Gc::REFLOG_EXPIRE, 90)?,
+            reflog_expire_unreachable: expire(&Gc::REFLOG_EXPIRE_UNREACHABLE, 30)?,
+            repack: Some(Repack::Full),
+            delta_compression: Some(delta_compression),
+            prune_expire: expire(&Gc::PRUNE_EXPIRE, 14)?,
+            write_multi_pack_index: false,
+            commit_graph: (write_commit_graph && self.config.may_use_commit_graph()? && !self.is_shallow()).then(
+                || CommitGraph {
+                    split: None,
+                    generation_data,
+                    changed_paths: self.commit_graph().ok().and_then(|graph| graph.bloom_filter_settings()),
+                },
+            ),
+        })
+    }
+
+    /// Write the commit-graph for all commits reachable from `HEAD` and all references as described by `options`,
+    /// like `git commit-graph write --reachable`.
+    ///
+    /// Changed paths are computed only for commits that don't have a Bloom filter in the existing commit-graph yet,
+    /// and the writing fails in shallow repositories.
+    pub fn write_commit_graph(
+        &self,
+        options: &CommitGraph,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_commitgraph::write::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::write_commit_graph()");
+        commit_graph::write(self, options, progress, should_interrupt)
+    }
+
+    /// Optimize this repository as described by `options`, which are typically obtained
+    /// with [maintenance_options()](Self::maintenance_options()).
+    ///
+    /// References are packed and reflogs are expired first, so that the objects they don't refer to anymore can
+    /// be dropped when repacking. Objects are reachable from references, reflogs, the `HEAD` and the index of each worktree.
+    /// Finally, loose objects that were packed are deleted along with unreachable loose objects that expired,
+    /// and a multi-pack-index and the commit-graph are written if configured.
+    ///
+    /// Note that objects in alternate object databases are considered as well, and that running this concurrently with
+    /// itself isn't safe.
+    pub fn maintenance(
+        &self,
+        options: &Options,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::maintenance()");
+        let interrupted = || {
+            if should_interrupt.load(Ordering::Relaxed) {
+                Err(Error::Interrupted)
+            } else {
+                Ok(())
+            }
+        };
+        let mut out = Outcome::default();
+
+        if options.pack_refs {
+            out.packed_refs = refs::pack(self)?;
+        }
+        if options.reflog_expire.is_some() || options.reflog_expire_unreachable.is_some() {
+            out.expired_reflog_entries = refs::expire_reflogs(self, options, should_interrupt)?;
+        }
+        interrupted()?;
+
+        let reachable = if options.repack == Some(Repack::Full) || options.prune_expire.is_some() {
+            Some(pack::count_reachable(
+                self,
+                refs::roots(self)?,
+                progress,
+                should_interrupt,
+            )?)
+        } else {
+            None
+        };
+        let reachable_ids: Option<gix_hashtable::HashSet> = reachable
+            .as_ref()
+            .map(|counts| counts.iter().map(|count| count.id).collect());
+        interrupted()?;
+
+        let prune_expire = options.prune_expire.filter(|_| reachable_ids.is_some());
+        let is_expired = |object: &prune::LooseObject| {
+            prune_expire.map_or(false, |expire| object.mtime <= expire)
+                && reachable_ids
+                    .as_ref()
+                    .map_or(false, |reachable| !reachable.contains(&object.id))
+        };
+        let mut packed = gix_hashtable::HashSet::default();
+        let mut loose_objects = None;
+        match options.repack {
+            None => {}
+            Some(Repack::Incremental) => {
+                let objects = prune::loose_objects(self)?;
+                let counts: Vec<_> = objects
+                    .iter()
+                    .filter(|object| !is_expired(object))
+                    .map(|object| gix_pack::data::output::Count::from_data(object.id, None))
+                    .collect();
+                packed.extend(counts.iter().map(|count| count.id));
+                out.packed_objects = counts.len();
+                out.pack_path = pack::write(self, counts, options.delta_compression, progress, should_interrupt)?;
+                loose_objects = Some(objects);
+            }
+            Some(Repack::Full) => {
+                let counts = reachable.expect("always counted for full repacks");
+                packed.extend(counts.iter().map(|count| count.id));
+                out.packed_objects = counts.len();
+                out.pack_path = pack::write(self, counts, options.delta_compression, progress, should_interrupt)?;
+                interrupted()?;
+                (out.deleted_packs, out.unpacked_objects) =
+                    pack::delete_all_but(self, out.pack_path.as_deref(), &packed, options.prune_expire)?;
+            }
+        }
+        interrupted()?;
+
+        if !packed.is_empty() || prune_expire.is_some() {
+            let objects = match loose_objects {
+                Some(objects) => objects,
+                None => prune::loose_objects(self)?,
+            };
+            (out.deleted_packed_loose_objects, out.pruned_objects) =
+                prune::delete(objects, &packed, is_expired, progress, should_interrupt)?;
+        }
+
+        if options.write_multi_pack_index {
+            interrupted()?;
+            out.multi_pack_index_path = pack::write_multi_index(self, progress, should_interrupt)?;
+        }
+        if let Some(commit_graph) = options.commit_graph.as_ref().filter(|_| !self.is_shallow()) {
+            interrupted()?;
+            out.commit_graph_path = commit_graph::write(self, commit_graph, progress, should_interrupt)?.path;
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_date::SecondsSinceUnixEpoch;
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+
+use super::{prune::mtime, DeltaCompression, Error, ProgressId};
+use crate::{config::tree::Pack, Repository};
+
+/// The extensions of the files that belong to a pack, with the index first so readers won't see the pack anymore.
+const PACK_EXTENSIONS: &[&str] = &["idx", "pack", "rev", "bitmap"];
+
+/// Count all objects reachable from `roots`, with their location in existing packs, using the reachability bitmap if there is one.
+pub(super) fn count_reachable(
+    repo: &Repository,
+    roots: Vec<ObjectId>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<output::Count>, Error> {
+    let mut db = repo
+        .objects
+        .clone()
+        .into_inner()
+        .into_arc()
+        .map_err(Error::OpenObjectDatabase)?;
+    db.prevent_pack_unload();
+    let mut count_progress = progress.add_child_with_id("counting objects".into(), ProgressId::CountObjects.into());
+    count_progress.init(None, gix_features::progress::count("objects"));
+    let bitmaps = gix_pack::bitmap::Bundle::at(repo.objects.store_ref().path().join("pack"), repo.object_hash())?;
+    let (counts, _) = output::count::objects(
+        db,
+        Box::new(roots.into_iter().map(Ok)),
+        &count_progress,
+        should_interrupt,
+        output::count::objects::Options {
+            thread_limit: Some(1),
+            input_object_expansion: output::count::objects::ObjectExpansion::AllReachable,
+            bitmaps: bitmaps.as_ref(),
+            ..Default::default()
+        },
+    )?;
+    count_progress.show_throughput(std::time::Instant::now());
+    Ok(counts)
+}
+
+/// Write all objects in `counts` into a new pack in the object database, with deltas copied from existing packs
+/// where possible and new deltas if `delta_compression` is set, and return the path to its data file if it was written.
+pub(super) fn write(
+    repo: &Repository,
+    counts: Vec<output::Count>,
+    delta_compression: Option<DeltaCompression>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<PathBuf>, Error> {
+    if counts.is_empty() {
+        return Ok(None);
+    }
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    std::fs::create_dir_all(&pack_dir).map_err(|source| Error::Io {
+        source,
+        action: "creating",
+        path: pack_dir.clone(),
+    })?;
+
+    let mut db = repo
+        .objects
+        .clone()
+        .into_inner()
+        .into_arc()
+        .map_err(Error::OpenObjectDatabase)?;
+    db.prevent_pack_unload();
+    let mut options = output::entry::iter_from_counts::Options {
+        chunk_size: 1000,
+        ..Default::default()
+    };
+    if let Some(delta_compression) = delta_compression {
+        options.mode = output::entry::iter_from_counts::Mode::DeltaCompression;
+        options.window = delta_compression.window;
+        options.depth = delta_compression.depth;
+        options.big_file_threshold = repo.config.big_file_threshold;
+        if delta_compression.delta_islands {
+            options.islands = delta_islands(repo, progress, should_interrupt)?.map(std::sync::Arc::new);
+        }
+    }
+    let num_objects = counts.len();
+    let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        db,
+        Box::new(progress.add_child("creating entries".into())),
+        options,
+    ));
+
+    let mut pack = gix_tempfile::new(
+        &pack_dir,
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )
+    .map_err(|source| Error::Io {
+        source,
+        action: "creating a temporary pack in",
+        path: pack_dir.clone(),
+    })?;
+    let mut write_progress = progress.add_child_with_id("writing pack".into(), ProgressId::WritePack.into());
+    write_progress.init(None, gix_features::progress::bytes());
+    let mut writer = output::bytes::FromEntriesIter::new(
+        entries,
+        BufWriter::new(&mut pack),
+        num_objects as u32,
+        gix_pack::data::Version::V2,
+        repo.object_hash(),
+    );
+    for written in &mut writer {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        write_progress.inc_by(written? as usize);
+    }
+    drop(writer);
+    write_progress.show_throughput(std::time::Instant::now());
+
+    let temporary_pack_path = pack
+        .with_mut(|file| file.path().to_owned())
+        .map_err(|source| Error::Io {
+            source,
+            action: "accessing the temporary pack in",
+            path: pack_dir.clone(),
+        })?;
+    let mut pack_file = BufReader::new(std::fs::File::open(&temporary_pack_path).map_err(|source| Error::Io {
+        source,
+        action: "opening",
+        path: temporary_pack_path.clone(),
+    })?);
+    let outcome = gix_pack::Bundle::write_to_directory(
+        &mut pack_file,
+        Some(&pack_dir),
+        progress,
+        should_interrupt,
+        None::<crate::OdbHandle>,
+        gix_pack::bundle::write::Options {
+            thread_limit: None,
+            index_version: Default::default(),
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: repo.object_hash(),
+        },
+    )?;
+    drop(pack_file);
+    drop(pack);
+    if let Some(keep_path) = outcome.keep_path {
+        remove_file(&keep_path)?;
+    }
+    Ok(outcome.data_path.or_else(|| {
+        // The pack already existed, which is when its path isn't returned.
+        Some(pack_dir.join(format!("pack-{}.pack", outcome.index.data_hash)))
+    }))
+}
+
+/// Assign all objects reachable from references to the delta islands configured with `pack.island`, or return `None`
+/// if there are none.
+///
+/// References whose name matches one of the patterns belong to the island named by the capture groups of the last matching
+/// pattern, and objects belong to the islands of all references they are reachable from, like `git pack-objects --delta-islands`.
+fn delta_islands(
+    repo: &Repository,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<output::entry::iter_from_counts::Islands>, Error> {
+    let patterns = repo.config.resolved.strings(&Pack::ISLAND).unwrap_or_default();
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    #[cfg(not(feature = "revparse-regex"))]
+    {
+        _ = (progress, should_interrupt);
+        Err(Error::DeltaIslandsUnsupported)
+    }
+    #[cfg(feature = "revparse-regex")]
+    {
+        use crate::bstr::{BString, ByteSlice};
+
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                regex::bytes::Regex::new(&pattern.to_str_lossy()).map_err(|source| Error::DeltaIslandPattern {
+                    pattern: pattern.into_owned(),
+                    source,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut tips_by_island = std::collections::BTreeMap::<BString, Vec<ObjectId>>::new();
+        for reference in repo.refs.iter()?.all().map_err(|err| Error::IterRefs(err.into()))? {
+            let reference = reference.map_err(|err| Error::IterRefs(err.into()))?;
+            let gix_ref::Target::Object(id) = reference.target else {
+                continue;
+            };
+            let Some(captures) = patterns
+                .iter()
+                .rev()
+                .find_map(|pattern| pattern.captures(reference.name.as_bstr()))
+            else {
+                continue;
+            };
+            let island: Vec<_> = captures
+                .iter()
+                .skip(1)
+                .flatten()
+                .map(|group| group.as_bytes())
+                .collect();
+            tips_by_island.entry(island.join(&b'-').into()).or_default().push(id);
+        }
+        if tips_by_island.len() > 64 {
+            return Err(Error::TooManyDeltaIslands(tips_by_island.len()));
+        }
+
+        let mut islands = output::entry::iter_from_counts::Islands::default();
+        for (bit, tips) in tips_by_island.into_values().enumerate() {
+            for count in count_reachable(repo, tips, progress, should_interrupt)? {
+                *islands.marks.entry(count.id).or_default() |= 1 << bit;
+            }
+        }
+        Ok(Some(islands))
+    }
+}
+
+/// Delete all packs but the one at `keep` and those with a `.keep` file, along with a multi-pack-index referring to them.
+///
+/// Objects in deleted packs that aren't in `packed` are written as loose objects if their pack didn't expire.
+/// Return the amount of deleted packs and the amount of unpacked objects.
+pub(super) fn delete_all_but(
+    repo: &Repository,
+    keep: Option<&Path>,
+    packed: &gix_hashtable::HashSet,
+    prune_expire: Option<SecondsSinceUnixEpoch>,
+) -> Result<(usize, usize), Error> {
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let packs: Vec<_> = pack_data_paths(&pack_dir)?
+        .into_iter()
+        .filter(|path| Some(path.as_path()) != keep && !path.with_extension("keep").is_file())
+        .collect();
+    if packs.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let loose = gix_odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash());
+    let mut unpacked = 0;
+    for pack in &packs {
+        let pack_mtime = mtime(pack)?;
+        if prune_expire.map_or(false, |expire| pack_mtime <= expire) {
+            continue;
+        }
+        let index = gix_pack::index::File::at(pack.with_extension("idx"), repo.object_hash())?;
+        for entry in index.iter() {
+            if packed.contains(&entry.oid) || loose.contains(&entry.oid) {
+                continue;
+            }
+            let object = repo.find_object(entry.oid)?;
+            let id =
+                gix_odb::Write::write_buf(&loose, object.kind, &object.data).map_err(crate::object::write::Error)?;
+            let path = loose.object_path(&id);
+            filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(pack_mtime, 0)).map_err(|source| {
+                Error::Io {
+                    source,
+                    action: "setting the modification time of",
+                    path: path.clone(),
+                }
+            })?;
+            unpacked += 1;
+        }
+    }
+
+    remove_file(&pack_dir.join("multi-pack-index"))?;
+    for pack in &packs {
+        for extension in PACK_EXTENSIONS {
+            remove_file(&pack.with_extension(extension))?;
+        }
+    }
+    Ok((packs.len(), unpacked))
+}
+
+/// Write a multi-pack-index for all packs and return its path, or `None` if there are no packs.
+pub(super) fn write_multi_index(
+    repo: &Repository,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<PathBuf>, Error> {
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let index_paths: Vec<_> = pack_data_paths(&pack_dir)?
+        .into_iter()
+        .map(|path| path.with_extension("idx"))
+        .filter(|path| path.is_file())
+        .collect();
+    let path = pack_dir.join("multi-pack-index");
+    if index_paths.is_empty() {
+        remove_file(&path)?;
+        return Ok(None);
+    }
+    let mut out = BufWriter::new(gix_lock::File::acquire_to_update_resource(
+        &path,
+        gix_lock::acquire::Fail::Immediately,
+        None,
+    )?);
+    gix_pack::multi_index::File::write_from_index_paths(
+        index_paths,
+        &mut out,
+        progress,
+        should_interrupt,
+        gix_pack::multi_index::write::Options {
+            object_hash: repo.object_hash(),
+            reverse_index: false,
+        },
+    )?;
+    out.into_inner()
+        .map_err(|err| Error::Io {
+            source: err.into_error(),
+            action: "writing",
+            path: path.clone(),
+        })?
+        .commit()
+        .map_err(|err| Error::Io {
+            source: err.error,
+            action: "committing",
+            path: path.clone(),
+        })?;
+    Ok(Some(path))
+}
+
+/// Return the paths to all pack data files in `pack_dir`, sorted for stability.
+fn pack_data_paths(pack_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(Error::Io {
+                source,
+                action: "listing",
+                path: pack_dir.to_owned(),
+            })
+        }
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|source| Error::Io {
+                source,
+                action: "listing",
+                path: pack_dir.to_owned(),
+            })?
+            .path();
+        let is_pack = path.extension().map_or(false, |ext| ext == "pack")
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with("pack-"));
+        if is_pack {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Remove the file at `path` if it exists.
+pub(super) fn remove_file(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(Error::Io {
+            source,
+            action: "deleting",
+            path: path.to_owned(),
+        }),
+    }
+}
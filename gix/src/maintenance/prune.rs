@@ -0,0 +1,90 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_date::SecondsSinceUnixEpoch;
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+
+use super::{pack::remove_file, Error, ProgressId};
+use crate::Repository;
+
+/// A loose object along with the time it was last modified.
+pub(super) struct LooseObject {
+    pub id: ObjectId,
+    pub path: PathBuf,
+    pub mtime: SecondsSinceUnixEpoch,
+}
+
+/// Return all loose objects of `repo`, without those of alternate object databases.
+pub(super) fn loose_objects(repo: &Repository) -> Result<Vec<LooseObject>, Error> {
+    let store = gix_odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash());
+    let mut out = Vec::new();
+    for id in store.iter() {
+        let id = id?;
+        let path = store.object_path(&id);
+        let mtime = match mtime(&path) {
+            Ok(mtime) => mtime,
+            // It may have been deleted concurrently, which is fine as we would delete it anyway.
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        out.push(LooseObject { id, path, mtime });
+    }
+    Ok(out)
+}
+
+/// Delete all `objects` that are `packed` or `is_expired`, and return the amount of each.
+///
+/// Fan-out directories that end up empty are deleted as well.
+pub(super) fn delete(
+    objects: Vec<LooseObject>,
+    packed: &gix_hashtable::HashSet,
+    is_expired: impl Fn(&LooseObject) -> bool,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(usize, usize), Error> {
+    let mut progress = progress.add_child_with_id("pruning objects".into(), ProgressId::PruneObjects.into());
+    progress.init(Some(objects.len()), gix_features::progress::count("objects"));
+    let (mut deleted_packed, mut pruned) = (0, 0);
+    let mut directories = Vec::new();
+    for object in objects {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        progress.inc();
+        if packed.contains(&object.id) {
+            deleted_packed += 1;
+        } else if is_expired(&object) {
+            pruned += 1;
+        } else {
+            continue;
+        }
+        remove_file(&object.path)?;
+        if let Some(dir) = object.path.parent() {
+            if directories.last().map_or(true, |last: &PathBuf| last != dir) {
+                directories.push(dir.to_owned());
+            }
+        }
+    }
+    for dir in directories {
+        // Fails if the directory isn't empty, which is expected.
+        std::fs::remove_dir(dir).ok();
+    }
+    Ok((deleted_packed, pruned))
+}
+
+/// Return the modification time of the file at `path` in seconds since the unix epoch.
+pub(super) fn mtime(path: &Path) -> Result<SecondsSinceUnixEpoch, Error> {
+    let modified = std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map_err(|source| Error::Io {
+            source,
+            action: "reading the modification time of",
+            path: path.to_owned(),
+        })?;
+    Ok(modified
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as SecondsSinceUnixEpoch))
+}
@@ -0,0 +1,199 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+    FullName, Target,
+};
+
+use super::{Error, Options};
+use crate::Repository;
+
+/// Move all loose references that point to an object into the `packed-refs` file and return their amount.
+///
+/// Per-worktree references are left alone, just like symbolic references.
+pub(super) fn pack(repo: &Repository) -> Result<usize, Error> {
+    let mut edits = Vec::new();
+    for reference in repo.refs.loose_iter().map_err(|err| Error::IterRefs(err.into()))? {
+        let reference = reference.map_err(|err| Error::IterRefs(err.into()))?;
+        let Target::Object(id) = reference.target else {
+            continue;
+        };
+        if reference
+            .name
+            .category()
+            .map_or(true, |category| category.is_worktree_private())
+        {
+            continue;
+        }
+        edits.push(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::MustExistAndMatch(Target::Object(id)),
+                new: Target::Object(id),
+            },
+            name: reference.name,
+            deref: false,
+        });
+    }
+    if edits.is_empty() {
+        return Ok(0);
+    }
+
+    let (file_lock_fail, packed_refs_lock_fail) = repo
+        .config
+        .lock_timeout()
+        .map_err(crate::reference::edit::Error::from)?;
+    let edits = repo
+        .refs
+        .transaction()
+        .packed_refs(
+            gix_ref::file::transaction::PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(Box::new(
+                &repo.objects,
+            )),
+        )
+        .prepare(edits, file_lock_fail, packed_refs_lock_fail)
+        .map_err(crate::reference::edit::Error::from)?
+        .commit(
+            repo.committer()
+                .transpose()
+                .map_err(crate::reference::edit::Error::from)?,
+        )
+        .map_err(crate::reference::edit::Error::from)?;
+    Ok(edits.len())
+}
+
+/// Remove the reflog entries of all references, including `HEAD`, that expired according to `options`,
+/// and return their amount.
+pub(super) fn expire_reflogs(
+    repo: &Repository,
+    options: &Options,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error> {
+    let head: FullName = "HEAD".try_into().expect("valid");
+    let mut refs = Vec::new();
+    for reference in repo.refs.iter()?.all().map_err(|err| Error::IterRefs(err.into()))? {
+        let reference = reference.map_err(|err| Error::IterRefs(err.into()))?;
+        refs.push((reference.name, reference.target.try_id().map(ToOwned::to_owned)));
+    }
+    let all_tips: Vec<_> = refs.iter().filter_map(|(_, id)| *id).collect();
+
+    let (file_lock_fail, _) = repo
+        .config
+        .lock_timeout()
+        .map_err(crate::reference::edit::Error::from)?;
+    let mut expired = 0;
+    for (name, tips) in std::iter::once((head, all_tips)).chain(
+        refs.into_iter()
+            .map(|(name, id)| (name, id.into_iter().collect::<Vec<_>>())),
+    ) {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        // Only computed once an entry needs it, as it's expensive and most entries are either recent or expired anyway.
+        let mut reachable: Option<Option<gix_hashtable::HashSet>> = None;
+        let mut is_unreachable = |id: ObjectId| {
+            if id.is_null() {
+                return false;
+            }
+            let reachable = reachable.get_or_insert_with(|| reachable_commits(repo, &tips));
+            match reachable {
+                Some(reachable) => {
+                    !reachable.contains(&id)
+                        && repo
+                            .find_header(id)
+                            .map_or(false, |header| header.kind() == gix_object::Kind::Commit)
+                }
+                None => false,
+            }
+        };
+        expired += repo
+            .refs
+            .reflog_expire(name.as_ref(), file_lock_fail, &mut |line| {
+                let time = line.signature.time.seconds;
+                if options.reflog_expire.map_or(false, |expire| time <= expire) {
+                    return true;
+                }
+                options.reflog_expire_unreachable.map_or(false, |expire| time <= expire)
+                    && (is_unreachable(line.new_oid()) || is_unreachable(line.previous_oid()))
+            })?
+            .unwrap_or_default();
+    }
+    Ok(expired)
+}
+
+/// Return all commits reachable from `tips`, or `None` if they couldn't be traversed, in which case everything
+/// should be considered reachable.
+fn reachable_commits(repo: &Repository, tips: &[ObjectId]) -> Option<gix_hashtable::HashSet> {
+    let commits: Vec<_> = tips
+        .iter()
+        .filter_map(|id| {
+            repo.find_object(*id)
+                .ok()?
+                .peel_to_kind(gix_object::Kind::Commit)
+                .ok()
+                .map(|commit| commit.id)
+        })
+        .collect();
+    let mut out = gix_hashtable::HashSet::default();
+    for info in repo.rev_walk(commits).all().ok()? {
+        out.insert(info.ok()?.id);
+    }
+    Some(out)
+}
+
+/// Return the ids of all objects that are used by the repository and thus are the roots of reachability.
+///
+/// These are the targets of all references and their reflogs, along with `HEAD` and the index of each worktree.
+/// Objects that don't exist are skipped.
+pub(super) fn roots(repo: &Repository) -> Result<Vec<ObjectId>, Error> {
+    let mut out = Vec::new();
+    let mut buf = Vec::new();
+    let mut add_ref_and_reflog = |repo: &Repository, out: &mut Vec<ObjectId>, name: &gix_ref::FullNameRef| {
+        if let Some(log) = repo.refs.reflog_iter(name, &mut buf)? {
+            for line in log {
+                let line = line.map_err(|err| Error::IterRefs(err.into()))?;
+                out.extend([line.previous_oid(), line.new_oid()]);
+            }
+        }
+        Ok::<_, Error>(())
+    };
+
+    let mut worktree_repos = Vec::new();
+    for proxy in repo.worktrees().map_err(|source| Error::Io {
+        source,
+        action: "listing worktrees in",
+        path: repo.common_dir().to_owned(),
+    })? {
+        worktree_repos.push(proxy.into_repo_with_possibly_inaccessible_worktree()?);
+    }
+    for repo in std::iter::once(repo).chain(worktree_repos.iter()) {
+        let head = repo.refs.find_loose("HEAD").ok();
+        if let Some(Target::Object(id)) = head.map(|head| head.target) {
+            out.push(id);
+        }
+        add_ref_and_reflog(repo, &mut out, "HEAD".try_into().expect("valid"))?;
+        if let Some(index) = repo.try_index()? {
+            out.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+        }
+    }
+
+    for reference in repo.refs.iter()?.all().map_err(|err| Error::IterRefs(err.into()))? {
+        let reference = reference.map_err(|err| Error::IterRefs(err.into()))?;
+        if let Target::Object(id) = reference.target {
+            out.push(id);
+        }
+        add_ref_and_reflog(repo, &mut out, reference.name.as_ref())?;
+    }
+
+    out.sort();
+    out.dedup();
+    out.retain(|id| !id.is_null() && repo.has_object(id));
+    Ok(out)
+}
@@ -172,4 +172,11 @@ impl Blob<'_> {
     pub fn take_data(&mut self) -> Vec<u8> {
         std::mem::take(&mut self.data)
     }
+
+    /// Turn this instance into a [`stream`][gix_odb::find::Stream] over its data.
+    ///
+    /// See [`Object::into_stream()`][crate::Object::into_stream()] for details.
+    pub fn into_stream(mut self) -> gix_odb::find::Stream {
+        gix_odb::find::Stream::from_data(gix_object::Kind::Blob, self.take_data())
+    }
 }
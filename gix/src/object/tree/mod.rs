@@ -33,12 +33,12 @@ impl<'repo> Tree<'repo> {
 
     /// Parse our tree data and return the parse tree for direct access to its entries.
     pub fn decode(&self) -> Result<gix_object::TreeRef<'_>, gix_object::decode::Error> {
-        gix_object::TreeRef::from_bytes(&self.data)
+        gix_object::TreeRef::from_bytes(&self.data, self.id.kind())
     }
 
     /// Find the entry named `name` by iteration, or return `None` if it wasn't found.
     pub fn find_entry(&self, name: impl PartialEq<BStr>) -> Option<EntryRef<'repo, '_>> {
-        TreeRefIter::from_bytes(&self.data)
+        TreeRefIter::from_bytes(&self.data, self.id.kind())
             .filter_map(Result::ok)
             .find(|entry| name.eq(entry.filename))
             .map(|entry| EntryRef {
@@ -67,7 +67,7 @@ impl<'repo> Tree<'repo> {
         let mut path = path.into_iter().peekable();
         buf.extend_from_slice(&self.data);
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(&buf)
+            match TreeRefIter::from_bytes(&buf, self.id.kind())
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -109,7 +109,7 @@ impl<'repo> Tree<'repo> {
     {
         let mut path = path.into_iter().peekable();
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(&self.data)
+            match TreeRefIter::from_bytes(&self.data, self.id.kind())
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -48,7 +48,7 @@ impl Platform<'_, '_> {
     where
         V: gix_traverse::tree::Visit,
     {
-        let root = gix_object::TreeRefIter::from_bytes(&self.root.data);
+        let root = gix_object::TreeRefIter::from_bytes(&self.root.data, self.root.id.kind());
         let state = gix_traverse::tree::breadthfirst::State::default();
         gix_traverse::tree::breadthfirst(root, state, &self.root.repo.objects, delegate)
     }
@@ -219,12 +219,19 @@ impl ThreadSafeRepository {
                 precompose_unicode: repo_config.precompose_unicode,
                 prohibit_windows_device_names: repo_config.protect_windows,
             };
-            match &common_dir {
+            let mut refs = match &common_dir {
                 Some(common_dir) => {
                     crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
                 }
                 None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
+            };
+            if repo_config.ref_storage == gix_ref::store::StorageFormat::Reftable {
+                refs.set_reftable(Some(gix_ref::reftable::Stack::at(
+                    common_dir_ref.to_owned(),
+                    ref_store_init_opts,
+                )));
             }
+            refs
         };
         let head = refs.find("HEAD").ok();
         let git_install_dir = crate::path::install_dir().ok();
@@ -45,6 +45,15 @@ pub enum Error {
     RejectShallowRemote,
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("Could not obtain configuration to learn if the remote is a promisor remote")]
+    PromisorConfig(#[source] config::boolean::Error),
+    #[error(transparent)]
+    PartialCloneFilterConfig(config::key::GenericErrorWithValue),
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error("Failed to read remaining bytes in stream")]
     ReadRemainingBytes(#[source] std::io::Error),
     #[error("None of the refspec(s) {} matched any of the {num_remote_refs} refs on the remote", refspecs.iter().map(|r| r.to_ref().instruction().to_bstring().to_string()).collect::<Vec<_>>().join(", "))]
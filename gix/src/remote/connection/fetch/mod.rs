@@ -193,6 +193,7 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter: None,
         })
     }
 }
@@ -207,7 +208,7 @@ where
     }
 }
 
-mod config;
+pub(crate) mod config;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -224,6 +225,7 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<remote::fetch::ObjectFilter>,
 }
 
 /// Builder
@@ -265,6 +267,15 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Ask the remote to omit objects matching `filter` from the pack, and mark the received pack as obtained from a
+    /// promisor remote.
+    ///
+    /// If unset, the `remote.<name>.partialCloneFilter` of promisor remotes is used.
+    pub fn with_filter(mut self, filter: remote::fetch::ObjectFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
 }
 
 impl<T> Drop for Prepare<'_, '_, T>
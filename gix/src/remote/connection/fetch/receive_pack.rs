@@ -10,9 +10,10 @@ use gix_protocol::{
 };
 
 use crate::{
+    bstr::ByteSlice,
     config::{
         cache::util::ApplyLeniency,
-        tree::{Clone, Fetch, Key},
+        tree::{Clone, Extensions, Fetch, Key, Remote},
     },
     remote,
     remote::{
@@ -127,6 +128,22 @@ where
             arguments.use_include_tag();
         }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        let is_promisor_remote = self.filter.is_some() || is_promisor_remote(repo, con.remote)?;
+        let filter = match self.filter.take() {
+            Some(filter) => Some(filter),
+            None if is_promisor_remote => partial_clone_filter(repo, con.remote)?,
+            None => None,
+        };
+        if let Some(filter) = &filter {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description:
+                        "partial clones need server support to omit objects, otherwise all objects would be sent",
+                });
+            }
+            arguments.filter(filter.to_bstring().to_str_lossy().as_ref());
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -150,8 +167,9 @@ where
             .into_negotiator();
         let graph_repo = {
             let mut r = repo.clone();
-            // assure that checking for unknown server refs doesn't trigger ODB refreshes.
+            // assure that checking for unknown server refs doesn't trigger ODB refreshes, or fetches from a promisor remote.
             r.objects.refresh = RefreshMode::Never;
+            r.objects.promisor = None;
             // we cache everything of importance in the graph and thus don't need an object cache.
             r.objects.unset_object_cache();
             r
@@ -346,6 +364,11 @@ where
         )?;
 
         if let Some(bundle) = write_pack_bundle.as_mut() {
+            if is_promisor_remote {
+                if let Some(path) = bundle.data_path.as_ref().map(|path| path.with_extension("promisor")) {
+                    std::fs::write(&path, b"").map_err(|err| Error::WritePromisorFile { path, source: err })?;
+                }
+            }
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
                     std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
@@ -372,6 +395,51 @@ where
     }
 }
 
+/// Return `true` if `remote` is a promisor remote, from which objects that are missing locally can be obtained.
+fn is_promisor_remote(repo: &Repository, remote: &crate::Remote<'_>) -> Result<bool, Error> {
+    let Some(name) = remote.name().map(remote::Name::as_bstr) else {
+        return Ok(false);
+    };
+    let config = &repo.config.resolved;
+    if config
+        .string_filter(Extensions::PARTIAL_CLONE, &mut repo.filter_config_section())
+        .map_or(false, |partial_clone| partial_clone.as_ref() == name)
+    {
+        return Ok(true);
+    }
+    Ok(config
+        .boolean_filter_by(
+            "remote",
+            Some(name),
+            Remote::PROMISOR.name,
+            &mut repo.filter_config_section(),
+        )
+        .map(|value| Remote::PROMISOR.enrich_error(value))
+        .transpose()
+        .with_leniency(repo.config.lenient_config)
+        .map_err(Error::PromisorConfig)?
+        .unwrap_or(false))
+}
+
+/// Return the filter that `remote` was configured with when it became a promisor remote.
+fn partial_clone_filter(repo: &Repository, remote: &crate::Remote<'_>) -> Result<Option<fetch::ObjectFilter>, Error> {
+    let Some(name) = remote.name().map(remote::Name::as_bstr) else {
+        return Ok(None);
+    };
+    repo.config
+        .resolved
+        .string_filter_by(
+            "remote",
+            Some(name),
+            Remote::PARTIAL_CLONE_FILTER.name,
+            &mut repo.filter_config_section(),
+        )
+        .map(|value| Remote::PARTIAL_CLONE_FILTER.try_into_object_filter(value))
+        .transpose()
+        .with_leniency(repo.config.lenient_config)
+        .map_err(Error::PartialCloneFilterConfig)
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
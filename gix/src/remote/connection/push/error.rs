@@ -0,0 +1,68 @@
+use crate::bstr::BString;
+
+/// The error returned by [`transmit()`](super::Prepare::transmit()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain the value of `push.default` to derive what to push")]
+    PushDefaultConfig(#[from] crate::config::key::GenericErrorWithValue),
+    #[error("Cannot derive what to push without any ref-specs as `push.default` is set to 'nothing'")]
+    NothingToPush,
+    #[error("Cannot derive what to push without any ref-specs as HEAD is detached")]
+    DetachedHead,
+    #[error("The current branch {branch:?} has no upstream branch to push to")]
+    NoUpstream { branch: BString },
+    #[error("The upstream branch {upstream:?} of the current branch {branch:?} doesn't match its name, which is required with `push.default=simple`")]
+    UpstreamNameMismatch { branch: BString, upstream: BString },
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    BranchRemoteRefName(#[from] crate::repository::branch_remote_ref_name::Error),
+    #[error(transparent)]
+    ParseRefSpec(#[from] gix_refspec::parse::Error),
+    #[error(transparent)]
+    InitRefIter(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterRefs(#[from] crate::reference::iter::Error),
+    #[error("Could not read a local reference")]
+    IterRef(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    MappingValidation(#[from] gix_refspec::match_group::validate::Error),
+    #[error("Could not determine the remote reference to push {source_name:?} to, as HEAD is detached")]
+    UnknownDestination { source_name: BString },
+    #[error(transparent)]
+    CommitGraphIfEnabled(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error("Could not open a thread-safe handle to the object database")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    CreatePackEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Push(#[from] gix_protocol::push::Error),
+    #[error("Failed to update remote-tracking references after pushing")]
+    UpdateTrackingRefs(#[from] crate::reference::edit::Error),
+}
+
+impl gix_protocol::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Push(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use gix_protocol::transport::client::Transport;
+
+use crate::{
+    bstr::BString,
+    remote::{connection::ref_map, Connection, Direction},
+    Progress,
+};
+
+mod error;
+pub use error::Error;
+
+mod transmit;
+
+/// The status of a single reference update on the remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The remote reference already points to the object we would have pushed, so no update was sent.
+    UpToDate,
+    /// The update was rejected locally and thus wasn't sent to the remote.
+    Rejected(Rejection),
+    /// The update would have been sent, but [dry-run](Prepare::with_dry_run()) mode was enabled.
+    DryRun,
+    /// The remote accepted the update, or it didn't report the status of reference updates at all.
+    Ok {
+        /// Additional information about how the update was carried out if server-side hooks altered it.
+        rewrites: Vec<gix_protocol::push::response::Rewrite>,
+    },
+    /// The remote rejected the update for the given `reason`.
+    RemoteRejected {
+        /// The reason for the rejection as sent by the remote, like `non-fast-forward`.
+        reason: BString,
+    },
+}
+
+/// The reason for locally rejecting a reference update, before anything was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The remote reference points to a commit that isn't an ancestor of the commit we would push,
+    /// and the update wasn't forced.
+    NonFastForward,
+    /// The remote reference points to an object we don't have locally, so we can't tell if the update is a fast-forward.
+    ///
+    /// Fetching from the remote first will resolve this.
+    FetchFirst,
+    /// The remote reference is a tag which already exists, and tags are only updated if forced.
+    AlreadyExists,
+    /// A [lease](Lease) was set for the reference, but the remote reference didn't point to the expected value.
+    StaleInfo {
+        /// The value we expected the remote reference to have, or `None` if we expected it to not exist.
+        expected: Option<gix_hash::ObjectId>,
+    },
+    /// The remote reference to delete doesn't exist.
+    RemoteRefMissing,
+    /// Another update was rejected, and as the push is [atomic](Prepare::with_atomic()), this one wasn't sent either.
+    AtomicPushFailed,
+}
+
+/// A single reference update on the remote as derived from the push ref-specs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    /// The local reference name, or the spelled out object id, that was pushed, or `None` if the remote reference is deleted.
+    pub local: Option<BString>,
+    /// The full name of the reference on the remote, like `refs/heads/main`.
+    pub remote: BString,
+    /// The value of the reference on the remote before the push, or `None` if it didn't exist.
+    pub old: Option<gix_hash::ObjectId>,
+    /// The value of the reference on the remote after the push, or `None` if it is deleted.
+    pub new: Option<gix_hash::ObjectId>,
+    /// If `true`, the update is allowed to be a non-fast-forward, either because it was forced or because a lease was given.
+    pub forced: bool,
+    /// What happened to the update.
+    pub status: Status,
+}
+
+/// The expectation about the value of a reference on the remote, which needs to be met for it to be updated
+/// even if the update isn't a fast-forward.
+///
+/// This is known as `--force-with-lease` in `git push`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lease {
+    /// Expect the remote reference to point to the same object as the remote-tracking reference it is mapped to
+    /// by the fetch ref-specs of the remote, or to not exist if there is no such remote-tracking reference.
+    RemoteTrackingReference,
+    /// Expect the remote reference to point to the given object, or to not exist if `None`.
+    Exact(Option<gix_hash::ObjectId>),
+}
+
+/// The outcome of [`Prepare::transmit()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// All reference updates derived from the push ref-specs, along with their status.
+    pub updates: Vec<Update>,
+    /// The amount of objects sent to the remote in a pack, or `None` if no pack was sent.
+    pub num_objects_sent: Option<usize>,
+    /// If the remote failed to unpack the objects we sent, this is the reason it gave.
+    pub unpack_error: Option<BString>,
+    /// The edits to remote-tracking references which were made after the remote accepted updates.
+    pub tracking_ref_edits: Vec<gix_ref::transaction::RefEdit>,
+}
+
+impl Outcome {
+    /// Return `true` if all updates were accepted by the remote or were up-to-date already.
+    pub fn is_success(&self) -> bool {
+        self.unpack_error.is_none()
+            && self
+                .updates
+                .iter()
+                .all(|u| matches!(u.status, Status::UpToDate | Status::Ok { .. } | Status::DryRun))
+    }
+}
+
+/// The progress ids used in during various steps of the push operation.
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of commits traversed to find the objects to send.
+    WalkCommits,
+    /// The amount of objects counted for sending them.
+    CountObjects,
+    /// The amount of bytes of the pack that is sent.
+    WritePack,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::WalkCommits => *b"PUWC",
+            ProgressId::CountObjects => *b"PUCO",
+            ProgressId::WritePack => *b"PUWP",
+        }
+    }
+}
+
+///
+pub mod prepare {
+    /// The error returned by [`prepare_push()`][super::Connection::prepare_push()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        RefMap(#[from] crate::remote::ref_map::Error),
+        #[error("Cannot push to a remote that uses {remote} while local repository uses {local} for object hashes")]
+        IncompatibleObjectHash {
+            local: gix_hash::Kind,
+            remote: gix_hash::Kind,
+        },
+    }
+
+    impl gix_protocol::transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::RefMap(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+
+impl<'remote, 'repo, T> Connection<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Perform a handshake with the `receive-pack` service of the remote to learn about its references and capabilities,
+    /// using `handshake_parameters` in the form of `(name, optional value)` for custom servers.
+    /// Note that at this point, the `transport` should already be configured using the [`transport_mut()`][Self::transport_mut()]
+    /// method, as it will be consumed here.
+    ///
+    /// From there the push can be configured and [transmitted](Prepare::transmit()).
+    ///
+    /// # Async Experimental
+    ///
+    /// Note that this implementation is currently limited correctly in blocking mode only as it relies on Drop semantics to close the connection
+    /// should the push not be performed. Furthermore, the code producing the pack is inherently blocking and it's not offloaded to a thread,
+    /// making this call block the executor.
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    pub async fn prepare_push(
+        mut self,
+        mut progress: impl Progress,
+        handshake_parameters: Vec<(String, Option<String>)>,
+    ) -> Result<Prepare<'remote, 'repo, T>, prepare::Error> {
+        let _span = gix_trace::coarse!("remote::Connection::prepare_push()");
+        let mut handshake = self
+            .handshake_inner(Direction::Push, handshake_parameters, &mut progress)
+            .await?;
+        let remote_refs = handshake.refs.take().unwrap_or_default();
+        let remote_object_hash = ref_map::extract_object_format(self.remote.repo, &handshake)?;
+        let local_object_hash = self.remote.repo.object_hash();
+        if remote_object_hash != local_object_hash && !remote_refs.is_empty() {
+            return Err(prepare::Error::IncompatibleObjectHash {
+                local: local_object_hash,
+                remote: remote_object_hash,
+            });
+        }
+        Ok(Prepare {
+            con: Some(self),
+            handshake,
+            remote_refs,
+            refspecs: None,
+            leases: Vec::new(),
+            force: false,
+            atomic: false,
+            dry_run: false,
+            push_options: Vec::new(),
+        })
+    }
+}
+
+/// A structure to hold the result of the handshake with the remote and configure the upcoming push operation.
+pub struct Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    con: Option<Connection<'remote, 'repo, T>>,
+    handshake: gix_protocol::handshake::Outcome,
+    remote_refs: Vec<gix_protocol::handshake::Ref>,
+    refspecs: Option<Vec<gix_refspec::RefSpec>>,
+    leases: Vec<(BString, Lease)>,
+    force: bool,
+    atomic: bool,
+    dry_run: bool,
+    push_options: Vec<BString>,
+}
+
+/// Access
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    /// Return the references advertised by the remote.
+    pub fn remote_refs(&self) -> &[gix_protocol::handshake::Ref] {
+        &self.remote_refs
+    }
+
+    /// Return the outcome of the handshake with the remote, which includes its capabilities.
+    ///
+    /// Note that the advertised references are available via [`remote_refs()`](Self::remote_refs()) instead.
+    pub fn handshake(&self) -> &gix_protocol::handshake::Outcome {
+        &self.handshake
+    }
+}
+
+/// Builder
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    /// Use `specs` to determine what to push instead of the push ref-specs configured for the remote, or the ones derived from `push.default`.
+    pub fn with_refspecs(mut self, specs: impl IntoIterator<Item = gix_refspec::RefSpec>) -> Self {
+        self.refspecs = Some(specs.into_iter().collect());
+        self
+    }
+
+    /// If `enabled`, allow all updates to be non-fast-forwards, as if each ref-spec was prefixed with `+`.
+    pub fn with_force(mut self, enabled: bool) -> Self {
+        self.force = enabled;
+        self
+    }
+
+    /// Only update the remote reference with the full name `remote_ref`, like `refs/heads/main`, if its current value on
+    /// the remote matches `lease`, in which case the update may also be a non-fast-forward.
+    ///
+    /// This is the equivalent of `git push --force-with-lease=<remote_ref>`.
+    pub fn with_lease(mut self, remote_ref: impl Into<BString>, lease: Lease) -> Self {
+        self.leases.push((remote_ref.into(), lease));
+        self
+    }
+
+    /// If `enabled`, the remote will either apply all updates or none of them.
+    /// This also means that nothing is sent if one update is already rejected locally.
+    ///
+    /// Note that this requires the `atomic` capability on the remote.
+    pub fn with_atomic(mut self, enabled: bool) -> Self {
+        self.atomic = enabled;
+        self
+    }
+
+    /// Pass `options` to the hooks on the remote, which requires the `push-options` capability on the remote.
+    pub fn with_push_options(mut self, options: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.push_options = options.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// If dry run is enabled, all updates are computed but nothing is sent to the remote, and no local reference is changed.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+}
+
+impl<T> Drop for Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    fn drop(&mut self) {
+        if let Some(mut con) = self.con.take() {
+            #[cfg(feature = "async-network-client")]
+            {
+                // TODO: this should be an async drop once the feature is available.
+                gix_protocol::futures_lite::future::block_on(gix_protocol::indicate_end_of_interaction(
+                    &mut con.transport,
+                    con.trace,
+                ))
+                .ok();
+            }
+            #[cfg(not(feature = "async-network-client"))]
+            {
+                gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace).ok();
+            }
+        }
+    }
+}
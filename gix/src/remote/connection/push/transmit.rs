@@ -0,0 +1,658 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::progress::{Count, NestedProgress};
+use gix_protocol::transport::client::Transport;
+use gix_refspec::{instruction::Push, match_group, Instruction, RefSpec};
+
+use super::{Error, Lease, Outcome, Prepare, ProgressId, Rejection, Status, Update};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{cache::util::ApplyLeniencyDefault, tree::Push as PushConfig},
+    remote::Direction,
+    Progress, Repository,
+};
+
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    /// Compute the reference updates from the configured ref-specs, check them locally, and send them along with a pack
+    /// of all objects the remote doesn't have yet.
+    ///
+    /// If no ref-specs were [set explicitly](Self::with_refspecs()), the push ref-specs of the remote are used, or if
+    /// there are none, the ones derived from the `push.default` configuration.
+    ///
+    /// Updates are rejected locally if they aren't fast-forwards and aren't forced, if they would overwrite existing tags, or if
+    /// a [lease](Lease) doesn't hold. Rejected updates aren't sent, and if the push is [atomic](Self::with_atomic()),
+    /// nothing is sent at all in this case.
+    ///
+    /// After the remote accepted updates, the remote-tracking references mapped to the updated remote references
+    /// by the fetch ref-specs of the remote are updated accordingly.
+    ///
+    /// Note that the returned [`Outcome`] has to be checked for the status of each update.
+    #[gix_protocol::maybe_async::maybe_async]
+    pub async fn transmit<P>(mut self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    where
+        P: NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("remote::Prepare::transmit()");
+        let repo = self.con.as_ref().expect("present until consumed").remote.repo;
+        let specs = self.refspecs()?;
+        let mut updates = self.updates(&specs)?;
+        for update in &mut updates {
+            self.check(update)?;
+        }
+
+        // Updates that are still in the `DryRun` state are the ones that would be sent.
+        let is_pending = |update: &Update| update.status == Status::DryRun;
+        if self.atomic
+            && updates
+                .iter()
+                .any(|update| matches!(update.status, Status::Rejected(_)))
+        {
+            for update in updates.iter_mut().filter(|update| is_pending(update)) {
+                update.status = Status::Rejected(Rejection::AtomicPushFailed);
+            }
+        }
+
+        let mut outcome = Outcome {
+            updates,
+            num_objects_sent: None,
+            unpack_error: None,
+            tracking_ref_edits: Vec::new(),
+        };
+        if self.dry_run || !outcome.updates.iter().any(is_pending) {
+            return Ok(outcome);
+        }
+
+        let pending: Vec<_> = outcome.updates.iter().filter(|update| is_pending(update)).collect();
+        let mut pack = if pending.iter().any(|update| update.new.is_some()) {
+            let (pack, num_objects) = create_pack(repo, &pending, &self.remote_refs, &mut progress, should_interrupt)?;
+            outcome.num_objects_sent = Some(num_objects);
+            Some(pack)
+        } else {
+            None
+        };
+        let null = repo.object_hash().null();
+        let protocol_updates: Vec<_> = pending
+            .iter()
+            .map(|update| gix_protocol::push::Update {
+                old: update.old.unwrap_or(null),
+                new: update.new.unwrap_or(null),
+                name: update.remote.clone(),
+            })
+            .collect();
+
+        let mut con = self.con.take().expect("present until consumed");
+        let report = gix_protocol::push(
+            &mut con.transport,
+            &self.handshake.capabilities,
+            &protocol_updates,
+            pack.as_mut().map(|pack| pack as &mut dyn std::io::Read),
+            &gix_protocol::push::Options {
+                atomic: self.atomic,
+                push_options: std::mem::take(&mut self.push_options),
+                quiet: false,
+                agent: repo.config.user_agent_tuple().1.map(Into::into),
+            },
+            &mut progress,
+            con.trace,
+        )
+        .await?;
+
+        for update in outcome.updates.iter_mut().filter(|update| is_pending(update)) {
+            update.status = match &report {
+                None => Status::Ok { rewrites: Vec::new() },
+                Some(report) => match report.status_by_name(update.remote.as_ref()) {
+                    Some(gix_protocol::push::response::Status::Ok { rewrites }) => Status::Ok {
+                        rewrites: rewrites.clone(),
+                    },
+                    Some(gix_protocol::push::response::Status::Rejected { reason }) => {
+                        Status::RemoteRejected { reason: reason.clone() }
+                    }
+                    None => Status::RemoteRejected {
+                        reason: "no status reported by remote".into(),
+                    },
+                },
+            };
+        }
+        if let Some(gix_protocol::push::response::Unpack::Failed { message }) = report.map(|report| report.unpack) {
+            outcome.unpack_error = Some(message);
+        }
+
+        outcome.tracking_ref_edits = update_tracking_refs(con.remote, &outcome.updates)?;
+        Ok(outcome)
+    }
+}
+
+impl<T> Prepare<'_, '_, T>
+where
+    T: Transport,
+{
+    fn repo(&self) -> &Repository {
+        self.con.as_ref().expect("present until consumed").remote.repo
+    }
+
+    /// Obtain the ref-specs to push, either as configured by the caller, the remote, or as derived from `push.default`.
+    fn refspecs(&self) -> Result<Vec<RefSpec>, Error> {
+        if let Some(specs) = &self.refspecs {
+            return Ok(specs.clone());
+        }
+        let remote = self.con.as_ref().expect("present until consumed").remote;
+        let remote_specs = remote.refspecs(Direction::Push);
+        if !remote_specs.is_empty() {
+            return Ok(remote_specs.to_vec());
+        }
+
+        let repo = self.repo();
+        let push_default = repo
+            .config
+            .resolved
+            .string(PushConfig::DEFAULT)
+            .map_or(Ok(Default::default()), |v| {
+                PushConfig::DEFAULT
+                    .try_into_default(v)
+                    .with_lenient_default(repo.config.lenient_config)
+            })?;
+        let spec = match push_default {
+            crate::push::Default::Nothing => return Err(Error::NothingToPush),
+            crate::push::Default::Matching => BString::from(":"),
+            crate::push::Default::Current | crate::push::Default::Upstream | crate::push::Default::Simple => {
+                let head = repo.head_name()?.ok_or(Error::DetachedHead)?;
+                let upstream = repo
+                    .branch_remote_ref_name(head.as_ref(), Direction::Fetch)
+                    .transpose()?
+                    .map(|name| name.as_bstr().to_owned());
+                let destination = match (push_default, upstream) {
+                    (crate::push::Default::Upstream, None) => {
+                        return Err(Error::NoUpstream {
+                            branch: head.as_bstr().into(),
+                        })
+                    }
+                    (crate::push::Default::Upstream, Some(upstream)) => upstream,
+                    (crate::push::Default::Simple, Some(upstream)) if upstream != head.as_bstr() => {
+                        return Err(Error::UpstreamNameMismatch {
+                            branch: head.as_bstr().into(),
+                            upstream,
+                        })
+                    }
+                    _ => head.as_bstr().into(),
+                };
+                format!("{}:{}", head.as_bstr(), destination).into()
+            }
+        };
+        Ok(vec![gix_refspec::parse(
+            spec.as_ref(),
+            gix_refspec::parse::Operation::Push,
+        )?
+        .to_owned()])
+    }
+
+    /// Map `specs` to updates of remote references, with all of them in the `DryRun` state unless they are obviously invalid.
+    fn updates(&self, specs: &[RefSpec]) -> Result<Vec<Update>, Error> {
+        let repo = self.repo();
+        let mut local_refs = Vec::new();
+        for reference in repo.references()?.all()? {
+            let reference = reference.map_err(Error::IterRef)?;
+            if let Some(id) = reference.target().try_id() {
+                local_refs.push((reference.name().as_bstr().to_owned(), id.to_owned()));
+            }
+        }
+        if let Ok(id) = repo.head_id() {
+            local_refs.push(("HEAD".into(), id.detach()));
+        }
+
+        let mut updates = Vec::new();
+        let group = gix_refspec::MatchGroup::from_push_specs(specs.iter().map(RefSpec::to_ref));
+        let (outcome, _fixes) = group
+            .match_remotes(local_refs.iter().map(|(name, id)| match_group::Item {
+                full_ref_name: name.as_ref(),
+                target: id,
+                object: None,
+            }))
+            .validated()?;
+        for mapping in &outcome.mappings {
+            let (local, new) = match mapping.lhs {
+                match_group::SourceRef::FullName(name) => {
+                    let id = mapping
+                        .item_index
+                        .map(|idx| local_refs[idx].1)
+                        .expect("names are always matched from items");
+                    (name.to_owned(), id)
+                }
+                match_group::SourceRef::ObjectId(id) => (id.to_string().into(), id),
+            };
+            let remote: BString = match &mapping.rhs {
+                Some(destination) => destination.as_ref().to_owned(),
+                None if local == "HEAD" => repo
+                    .head_name()?
+                    .ok_or_else(|| Error::UnknownDestination {
+                        source_name: local.clone(),
+                    })?
+                    .as_bstr()
+                    .to_owned(),
+                None => local.clone(),
+            };
+            let forced = match outcome.group.specs[mapping.spec_index].instruction() {
+                Instruction::Push(Push::Matching {
+                    allow_non_fast_forward, ..
+                }) => allow_non_fast_forward,
+                _ => false,
+            };
+            updates.push(Update {
+                old: self.remote_ref_target(remote.as_ref()),
+                local: Some(local),
+                remote,
+                new: Some(new),
+                forced: forced || self.force,
+                status: Status::DryRun,
+            });
+        }
+
+        for spec in specs {
+            match spec.to_ref().instruction() {
+                Instruction::Push(Push::Delete { ref_or_pattern }) => {
+                    let mut num_matches = 0;
+                    for (name, old) in self.remote_refs_with_target() {
+                        if matches_delete_pattern(ref_or_pattern, name) {
+                            num_matches += 1;
+                            updates.push(Update {
+                                local: None,
+                                remote: name.to_owned(),
+                                old: Some(old),
+                                new: None,
+                                forced: self.force,
+                                status: Status::DryRun,
+                            });
+                        }
+                    }
+                    if num_matches == 0 && !ref_or_pattern.contains(&b'*') {
+                        updates.push(Update {
+                            local: None,
+                            remote: expand_partial_name(ref_or_pattern),
+                            old: None,
+                            new: None,
+                            forced: self.force,
+                            status: Status::Rejected(Rejection::RemoteRefMissing),
+                        });
+                    }
+                }
+                Instruction::Push(Push::AllMatchingBranches { allow_non_fast_forward }) => {
+                    for (name, new) in local_refs.iter().filter(|(name, _)| name.starts_with(b"refs/heads/")) {
+                        let Some(old) = self.remote_ref_target(name.as_ref()) else {
+                            continue;
+                        };
+                        if updates.iter().any(|update| &update.remote == name) {
+                            continue;
+                        }
+                        updates.push(Update {
+                            local: Some(name.clone()),
+                            remote: name.clone(),
+                            old: Some(old),
+                            new: Some(*new),
+                            forced: allow_non_fast_forward || self.force,
+                            status: Status::DryRun,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(updates)
+    }
+
+    /// Reject `update` if it's pending and shouldn't be sent, or mark it as up-to-date.
+    fn check(&self, update: &mut Update) -> Result<(), Error> {
+        if update.status != Status::DryRun {
+            return Ok(());
+        }
+        if update.old == update.new {
+            update.status = Status::UpToDate;
+            return Ok(());
+        }
+        if let Some((_, lease)) = self.leases.iter().find(|(name, _)| *name == update.remote) {
+            let expected = match lease {
+                Lease::Exact(expected) => *expected,
+                Lease::RemoteTrackingReference => {
+                    let remote = self.con.as_ref().expect("present until consumed").remote;
+                    match tracking_ref_name(remote, update.remote.as_ref()) {
+                        Some(name) => self
+                            .repo()
+                            .try_find_reference(name.as_bstr())?
+                            .and_then(|reference| reference.target().try_id().map(ToOwned::to_owned)),
+                        None => None,
+                    }
+                }
+            };
+            if expected != update.old {
+                update.status = Status::Rejected(Rejection::StaleInfo { expected });
+            } else {
+                update.forced = true;
+            }
+            return Ok(());
+        }
+
+        let (Some(old), Some(new)) = (update.old, update.new) else {
+            return Ok(());
+        };
+        if update.forced {
+            return Ok(());
+        }
+        let repo = self.repo();
+        if update.remote.starts_with(b"refs/tags/") {
+            update.status = Status::Rejected(Rejection::AlreadyExists);
+        } else if !repo.has_object(old) {
+            update.status = Status::Rejected(Rejection::FetchFirst);
+        } else if !is_ancestor(repo, old, new)? {
+            update.status = Status::Rejected(Rejection::NonFastForward);
+        }
+        Ok(())
+    }
+
+    fn remote_refs_with_target(&self) -> impl Iterator<Item = (&BStr, gix_hash::ObjectId)> + '_ {
+        self.remote_refs.iter().filter_map(|remote_ref| {
+            let (name, target, _peeled) = remote_ref.unpack();
+            target.map(|target| (name, target.to_owned()))
+        })
+    }
+
+    fn remote_ref_target(&self, name: &BStr) -> Option<gix_hash::ObjectId> {
+        self.remote_refs_with_target()
+            .find_map(|(remote_name, target)| (remote_name == name).then_some(target))
+    }
+}
+
+/// Return `true` if `name` is matched by the source of a deletion ref-spec, which is a full or partial name, or a pattern with one `*`.
+fn matches_delete_pattern(pattern: &BStr, name: &BStr) -> bool {
+    match pattern.find_byte(b'*') {
+        Some(pos) => {
+            let (prefix, suffix) = (&pattern[..pos], &pattern[pos + 1..]);
+            name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix)
+        }
+        None => name == pattern || expand_partial_name(pattern) == name,
+    }
+}
+
+fn expand_partial_name(name: &BStr) -> BString {
+    if name.starts_with(b"refs/") {
+        name.to_owned()
+    } else {
+        let mut out = BString::from("refs/heads/");
+        out.extend_from_slice(name);
+        out
+    }
+}
+
+/// Return the name of the remote-tracking reference that `remote_name` is mapped to by the fetch ref-specs of `remote`.
+fn tracking_ref_name(remote: &crate::Remote<'_>, remote_name: &BStr) -> Option<BString> {
+    let null = remote.repo.object_hash().null();
+    gix_refspec::MatchGroup::from_fetch_specs(remote.refspecs(Direction::Fetch).iter().map(RefSpec::to_ref))
+        .match_remotes(std::iter::once(match_group::Item {
+            full_ref_name: remote_name,
+            target: &null,
+            object: None,
+        }))
+        .mappings
+        .into_iter()
+        .find_map(|mapping| mapping.rhs.map(std::borrow::Cow::into_owned))
+}
+
+/// Return `true` if `ancestor` can be reached from `descendant`, both of which are expected to be commits.
+///
+/// This is the case if `ancestor` is their only merge-base, which, unlike a walk that stops at commits older than
+/// `ancestor`, can't be fooled by skewed commit dates.
+fn is_ancestor(repo: &Repository, ancestor: gix_hash::ObjectId, descendant: gix_hash::ObjectId) -> Result<bool, Error> {
+    let (Ok(_), Ok(_)) = (repo.find_commit(ancestor), repo.find_commit(descendant)) else {
+        return Ok(false);
+    };
+    let cache = repo.commit_graph_if_enabled()?;
+    let mut graph = repo.revision_graph(cache.as_ref());
+    Ok(gix_revision::merge_base(ancestor, &[descendant], &mut graph)?.map_or(false, |bases| bases == [ancestor]))
+}
+
+/// Prepare a pack with all objects reachable from the new values of `updates`, but not from any of the `remote_refs`,
+/// and return it along with the amount of objects in it.
+///
+/// The pack is produced while it's read, so it never has to be held in memory as a whole.
+fn create_pack<'a, P>(
+    repo: &Repository,
+    updates: &[&Update],
+    remote_refs: &[gix_protocol::handshake::Ref],
+    progress: &mut P,
+    should_interrupt: &'a AtomicBool,
+) -> Result<(impl std::io::Read + 'a, usize), Error>
+where
+    P: NestedProgress,
+    P::SubProgress: 'static,
+{
+    use gix_pack::data::output;
+
+    let haves: gix_hashtable::HashSet = remote_refs
+        .iter()
+        .flat_map(|remote_ref| {
+            let (_name, target, peeled) = remote_ref.unpack();
+            target.into_iter().chain(peeled)
+        })
+        .map(ToOwned::to_owned)
+        .filter(|id| repo.has_object(id))
+        .collect();
+
+    let mut commits = Vec::new();
+    let mut other_objects = Vec::new();
+    let mut trees = Vec::new();
+    for id in updates.iter().filter_map(|update| update.new) {
+        let mut object = repo.find_object(id)?;
+        loop {
+            match object.kind {
+                gix_object::Kind::Commit => commits.push(object.id),
+                gix_object::Kind::Tree => trees.push(object.id),
+                gix_object::Kind::Blob => other_objects.push(object.id),
+                gix_object::Kind::Tag => {
+                    other_objects.push(object.id);
+                    let target = object.to_tag_ref().target();
+                    object = repo.find_object(target)?;
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+
+    let mut walk_progress = progress.add_child_with_id("walking commits", ProgressId::WalkCommits.into());
+    walk_progress.init(None, gix_features::progress::count("commits"));
+    for info in repo.rev_walk(commits).selected({
+        let haves = haves.clone();
+        move |id| !haves.contains(id)
+    })? {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        other_objects.push(info?.id);
+        walk_progress.inc();
+    }
+    walk_progress.show_throughput(std::time::Instant::now());
+
+    let mut db = repo
+        .objects
+        .clone()
+        .into_inner()
+        .into_arc()
+        .map_err(Error::OpenObjectDatabase)?;
+    db.prevent_pack_unload();
+    let mut count_progress = progress.add_child_with_id("counting objects", ProgressId::CountObjects.into());
+    count_progress.init(None, gix_features::progress::count("objects"));
+    let (mut counts, _) = output::count::objects_unthreaded(
+        &db,
+        &mut other_objects.into_iter().map(Ok),
+        &count_progress,
+        should_interrupt,
+        output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    )?;
+    if !trees.is_empty() {
+        let (tree_counts, _) = output::count::objects_unthreaded(
+            &db,
+            &mut trees.into_iter().map(Ok),
+            &count_progress,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeContents,
+        )?;
+        let mut seen: gix_hashtable::HashSet = counts.iter().map(|count| count.id).collect();
+        counts.extend(tree_counts.into_iter().filter(|count| seen.insert(count.id)));
+    }
+
+    // Expansion also counts the parents of the boundary commits and their trees, which the remote has already.
+    let mut known = haves.clone();
+    for id in &haves {
+        let object = repo.find_object(*id)?;
+        if let Some(tree_id) = object.try_to_commit_ref_iter().and_then(|mut iter| iter.tree_id().ok()) {
+            known.insert(tree_id);
+        }
+    }
+    counts.retain(|count| !known.contains(&count.id));
+
+    let num_objects = counts.len();
+    let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        db,
+        Box::new(progress.add_child("creating entries")),
+        output::entry::iter_from_counts::Options {
+            thread_limit: None,
+            mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+            allow_thin_pack: false,
+            chunk_size: 1000,
+            version: Default::default(),
+            ..Default::default()
+        },
+    ));
+
+    let mut write_progress = progress.add_child_with_id("writing pack", ProgressId::WritePack.into());
+    write_progress.init(None, gix_features::progress::bytes());
+    let buf = PackBuffer::default();
+    let pack = PackReader {
+        writer: output::bytes::FromEntriesIter::new(
+            entries,
+            buf.clone(),
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            repo.object_hash(),
+        ),
+        buf,
+        pos: 0,
+        progress: write_progress,
+        should_interrupt,
+    };
+    Ok((pack, num_objects))
+}
+
+/// The bytes written by a pack writer which are yet to be read by a [`PackReader`].
+#[derive(Default, Clone)]
+struct PackBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for PackBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().expect("not poisoned").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write the next pack entries into `buf` whenever all previously written bytes were read.
+struct PackReader<'a, I, P> {
+    writer: gix_pack::data::output::bytes::FromEntriesIter<I, PackBuffer>,
+    buf: PackBuffer,
+    /// The amount of bytes in `buf` that were read already.
+    pos: usize,
+    progress: P,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl<I, P> std::io::Read for PackReader<'_, I, P>
+where
+    I: Iterator<
+        Item = Result<Vec<gix_pack::data::output::Entry>, gix_pack::data::output::entry::iter_from_counts::Error>,
+    >,
+    P: Progress,
+{
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            {
+                let mut buf = self.buf.0.lock().expect("not poisoned");
+                if self.pos < buf.len() {
+                    let len = out.len().min(buf.len() - self.pos);
+                    out[..len].copy_from_slice(&buf[self.pos..][..len]);
+                    self.pos += len;
+                    if self.pos == buf.len() {
+                        buf.clear();
+                        self.pos = 0;
+                    }
+                    return Ok(len);
+                }
+            }
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, Error::Interrupted));
+            }
+            match self.writer.next() {
+                Some(written) => {
+                    let written = written.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                    self.progress.inc_by(written as usize);
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Update the remote-tracking references of all remote references that were successfully updated.
+fn update_tracking_refs(
+    remote: &crate::Remote<'_>,
+    updates: &[Update],
+) -> Result<Vec<gix_ref::transaction::RefEdit>, Error> {
+    use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+    let repo = remote.repo;
+    let mut edits = Vec::new();
+    for update in updates
+        .iter()
+        .filter(|update| matches!(update.status, Status::Ok { .. }))
+    {
+        let Some(name) = tracking_ref_name(remote, update.remote.as_ref()) else {
+            continue;
+        };
+        let Ok(name) = gix_ref::FullName::try_from(name) else {
+            continue;
+        };
+        let change = match update.new {
+            Some(new) => Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "update by push".into(),
+                },
+                expected: PreviousValue::Any,
+                new: gix_ref::Target::Object(new),
+            },
+            None => {
+                if repo.try_find_reference(name.as_ref())?.is_none() {
+                    continue;
+                }
+                Change::Delete {
+                    expected: PreviousValue::Any,
+                    log: RefLog::AndReference,
+                }
+            }
+        };
+        edits.push(RefEdit {
+            change,
+            name,
+            deref: false,
+        });
+    }
+    if edits.is_empty() {
+        return Ok(edits);
+    }
+    Ok(repo.edit_references(edits)?)
+}
@@ -179,22 +179,21 @@ where
         })
     }
 
+    /// Configure the transport and credentials for `direction`, and perform the handshake for the service matching it.
     #[allow(clippy::result_large_err)]
     #[gix_protocol::maybe_async::maybe_async]
-    async fn fetch_refs(
+    pub(crate) async fn handshake_inner(
         &mut self,
-        filter_by_prefix: bool,
+        direction: Direction,
         extra_parameters: Vec<(String, Option<String>)>,
-        refspecs: &[gix_refspec::RefSpec],
-        mut progress: impl Progress,
-    ) -> Result<HandshakeWithRefs, Error> {
-        let _span = gix_trace::coarse!("remote::Connection::fetch_refs()");
+        progress: &mut impl Progress,
+    ) -> Result<gix_protocol::handshake::Outcome, Error> {
         let mut credentials_storage;
         let url = self.transport.to_url();
         let authenticate = match self.authenticate.as_mut() {
             Some(f) => f,
             None => {
-                let url = self.remote.url(Direction::Fetch).map_or_else(
+                let url = self.remote.url(direction).map_or_else(
                     || gix_url::parse(url.as_ref()).expect("valid URL to be provided by transport"),
                     ToOwned::to_owned,
                 );
@@ -216,8 +215,26 @@ where
         if let Some(config) = self.transport_options.as_ref() {
             self.transport.configure(&**config)?;
         }
-        let mut outcome =
-            gix_protocol::fetch::handshake(&mut self.transport, authenticate, extra_parameters, &mut progress).await?;
+        let service = match direction {
+            Direction::Fetch => gix_protocol::transport::Service::UploadPack,
+            Direction::Push => gix_protocol::transport::Service::ReceivePack,
+        };
+        Ok(gix_protocol::handshake(&mut self.transport, service, authenticate, extra_parameters, progress).await?)
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    async fn fetch_refs(
+        &mut self,
+        filter_by_prefix: bool,
+        extra_parameters: Vec<(String, Option<String>)>,
+        refspecs: &[gix_refspec::RefSpec],
+        mut progress: impl Progress,
+    ) -> Result<HandshakeWithRefs, Error> {
+        let _span = gix_trace::coarse!("remote::Connection::fetch_refs()");
+        let mut outcome = self
+            .handshake_inner(Direction::Fetch, extra_parameters, &mut progress)
+            .await?;
         let refs = match outcome.refs.take() {
             Some(refs) => refs,
             None => {
@@ -255,7 +272,7 @@ where
 
 /// Assume sha1 if server says nothing, otherwise configure anything beyond sha1 in the local repo configuration
 #[allow(clippy::result_large_err)]
-fn extract_object_format(
+pub(crate) fn extract_object_format(
     _repo: &crate::Repository,
     outcome: &gix_protocol::handshake::Outcome,
 ) -> Result<gix_hash::Kind, Error> {
@@ -267,6 +284,7 @@ fn extract_object_format(
             })?;
             match object_format {
                 "sha1" => gix_hash::Kind::Sha1,
+                "sha256" => gix_hash::Kind::Sha256,
                 unknown => return Err(Error::UnknownObjectFormat { format: unknown.into() }),
             }
         } else {
@@ -1,3 +1,5 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
 ///
 pub mod negotiate {
     #[cfg(feature = "credentials")]
@@ -65,6 +67,80 @@ impl Tags {
     }
 }
 
+/// A filter to limit the objects sent by the remote, turning the receiving repository into a *partial clone*.
+///
+/// Objects that were filtered are obtained on demand from the remote that sent the pack, the *promisor remote*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectFilter {
+    /// Don't send any blob, as in `blob:none`.
+    BlobNone,
+    /// Don't send blobs that are `size` bytes or larger, as in `blob:limit=<size>`.
+    BlobLimit {
+        /// The size in bytes at which blobs are omitted.
+        size: u64,
+    },
+    /// Don't send trees and blobs that are `depth` or more levels below the root tree, as in `tree:<depth>`.
+    ///
+    /// A `depth` of `0` omits all trees and blobs.
+    TreeDepth {
+        /// The depth at which trees and blobs are omitted.
+        depth: u64,
+    },
+    /// Only send blobs that match the sparse-checkout patterns contained in the blob that `spec` resolves to on the remote,
+    /// as in `sparse:oid=<spec>`.
+    SparseOid {
+        /// A blob-ish revision like `HEAD:.gitsparse` or a blob id.
+        spec: BString,
+    },
+}
+
+impl ObjectFilter {
+    /// Parse `spec` as a filter specification as it is passed to `git clone --filter`, or return `None`
+    /// if it isn't supported.
+    ///
+    /// `blob:limit` understands the `k`, `m` and `g` suffixes.
+    pub fn from_bytes(spec: &BStr) -> Option<Self> {
+        Some(if spec == "blob:none" {
+            ObjectFilter::BlobNone
+        } else if let Some(size) = spec.strip_prefix(b"blob:limit=") {
+            let (size, factor) = match size.last()?.to_ascii_lowercase() {
+                b'k' => (&size[..size.len() - 1], 1 << 10),
+                b'm' => (&size[..size.len() - 1], 1 << 20),
+                b'g' => (&size[..size.len() - 1], 1 << 30),
+                _ => (size, 1),
+            };
+            ObjectFilter::BlobLimit {
+                size: size.to_str().ok()?.parse::<u64>().ok()?.checked_mul(factor)?,
+            }
+        } else if let Some(depth) = spec.strip_prefix(b"tree:") {
+            ObjectFilter::TreeDepth {
+                depth: depth.to_str().ok()?.parse().ok()?,
+            }
+        } else if let Some(blob) = spec.strip_prefix(b"sparse:oid=") {
+            if blob.is_empty() {
+                return None;
+            }
+            ObjectFilter::SparseOid { spec: blob.into() }
+        } else {
+            return None;
+        })
+    }
+
+    /// Return the specification of this filter as understood by the remote.
+    pub fn to_bstring(&self) -> BString {
+        match self {
+            ObjectFilter::BlobNone => "blob:none".into(),
+            ObjectFilter::BlobLimit { size } => format!("blob:limit={size}").into(),
+            ObjectFilter::TreeDepth { depth } => format!("tree:{depth}").into(),
+            ObjectFilter::SparseOid { spec } => {
+                let mut out = BString::from("sparse:oid=");
+                out.extend_from_slice(spec);
+                out
+            }
+        }
+    }
+}
+
 /// Describe how shallow clones are handled when fetching, with variants defining how the *shallow boundary* is handled.
 ///
 /// The *shallow boundary* is a set of commits whose parents are not present in the repository.
@@ -54,9 +54,11 @@ pub mod fetch;
 pub mod connect;
 
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
-mod connection;
+pub(crate) mod connection;
+#[cfg(feature = "blocking-network-client")]
+pub(crate) mod promisor;
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
-pub use connection::{ref_map, AuthenticateFn, Connection};
+pub use connection::{push, ref_map, AuthenticateFn, Connection};
 
 ///
 pub mod save;
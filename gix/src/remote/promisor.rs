@@ -0,0 +1,96 @@
+use std::{
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Extensions, Fetch},
+    remote, Repository,
+};
+
+/// Configure the object database of `repo` to fetch objects that are missing locally from its promisor remote
+/// if it is a partial clone.
+pub(crate) fn install(repo: &mut Repository) {
+    repo.objects.promisor = repo
+        .config
+        .resolved
+        .string_filter(Extensions::PARTIAL_CLONE, &mut repo.filter_config_section())
+        .map(|remote_name| {
+            Arc::new(LazyFetch {
+                git_dir: repo.git_dir().to_owned(),
+                options: repo.options.clone(),
+                remote_name: remote_name.into_owned(),
+                #[cfg(feature = "parallel")]
+                repo: Default::default(),
+            }) as Arc<dyn gix_odb::store::Promisor>
+        });
+}
+
+/// Fetch missing objects from a promisor remote through a repository of its own, one which doesn't fetch lazily.
+struct LazyFetch {
+    git_dir: PathBuf,
+    options: crate::open::Options,
+    remote_name: BString,
+    /// The repository to fetch with, opened on first use and reused for all subsequent fetches.
+    ///
+    /// It can only be shared across threads if these are supported.
+    #[cfg(feature = "parallel")]
+    repo: std::sync::Mutex<Option<Repository>>,
+}
+
+impl LazyFetch {
+    fn open(&self) -> Result<Repository, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut repo = crate::open_opts(self.git_dir.clone(), self.options.clone())?;
+        {
+            // Sending haves lets the remote omit all objects reachable from them, including the ones we are missing.
+            let mut config = repo.config_snapshot_mut();
+            config.set_value(&Fetch::NEGOTIATION_ALGORITHM, "noop")?;
+            config.commit()?;
+        }
+        repo.objects.promisor = None;
+        Ok(repo)
+    }
+
+    fn fetch_with(
+        &self,
+        repo: &Repository,
+        ids: &[gix_hash::ObjectId],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut remote = repo
+            .find_remote(self.remote_name.as_bstr())?
+            .with_fetch_tags(remote::fetch::Tags::None);
+        remote.replace_refspecs(
+            ids.iter().map(|id| BString::from(id.to_string())),
+            remote::Direction::Fetch,
+        )?;
+        let outcome = remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(gix_features::progress::Discard, Default::default())?
+            .with_filter(remote::fetch::ObjectFilter::BlobNone)
+            .receive(gix_features::progress::Discard, &AtomicBool::default())?;
+        // There are no refs to protect the new pack, but being a promisor pack it's retained anyway.
+        if let remote::fetch::Status::Change { write_pack_bundle, .. } = outcome.status {
+            if let Some(path) = write_pack_bundle.keep_path {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl gix_odb::store::Promisor for LazyFetch {
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        #[cfg(feature = "parallel")]
+        {
+            let mut repo = self.repo.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            let repo = match repo.as_mut() {
+                Some(repo) => repo,
+                None => repo.insert(self.open()?),
+            };
+            self.fetch_with(repo, ids)
+        }
+        #[cfg(not(feature = "parallel"))]
+        self.fetch_with(&self.open()?, ids)
+    }
+}
@@ -0,0 +1,42 @@
+use gix_hash::ObjectId;
+
+use crate::bstr::BStr;
+use crate::repository::blame_file;
+use crate::Repository;
+
+/// Blame-utilities
+impl Repository {
+    /// Attribute each line of the file at `file_path` in the `suspect` commit to the commit that introduced it,
+    /// returning hunks of lines along with the commit and the path the lines originally had.
+    ///
+    /// If `options` are unset, they will be filled in according to the git configuration of this repository,
+    /// which means renames are followed according to `diff.renames`.
+    /// The commit-graph is used to accelerate the traversal if it is enabled.
+    ///
+    /// # Performance
+    /// Be sure to [set an object cache](crate::Repository::object_cache_size_if_unset) as trees and blobs
+    /// are looked up repeatedly.
+    pub fn blame_file(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        options: impl Into<Option<gix_blame::Options>>,
+    ) -> Result<gix_blame::Outcome, blame_file::Error> {
+        let options = match options.into() {
+            Some(options) => options,
+            None => gix_blame::Options {
+                rewrites: self.config.diff_renames()?.unwrap_or_default().into(),
+            },
+        };
+        let cache = self.commit_graph_if_enabled()?;
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        Ok(gix_blame::file(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_path,
+            options,
+        )?)
+    }
+}
@@ -14,7 +14,8 @@ impl crate::Repository {
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
-        crate::Repository {
+        #[cfg_attr(not(feature = "blocking-network-client"), allow(unused_mut))]
+        let mut repo = crate::Repository {
             bufs: Some(RefCell::new(Vec::with_capacity(4))),
             work_tree,
             common_dir,
@@ -27,7 +28,10 @@ impl crate::Repository {
             shallow_commits,
             #[cfg(feature = "attributes")]
             modules,
-        }
+        };
+        #[cfg(feature = "blocking-network-client")]
+        crate::remote::promisor::install(&mut repo);
+        repo
     }
 
     /// Convert this instance into a [`ThreadSafeRepository`][crate::ThreadSafeRepository] by dropping all thread-local data.
@@ -38,6 +42,7 @@ impl crate::Repository {
 
 #[cfg_attr(not(feature = "max-performance-safe"), allow(unused_variables, unused_mut))]
 pub(crate) fn setup_objects(objects: &mut crate::OdbHandle, config: &crate::config::Cache) {
+    objects.big_file_threshold = Some(config.big_file_threshold);
     #[cfg(feature = "max-performance-safe")]
     {
         match config.pack_cache_bytes {
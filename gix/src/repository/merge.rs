@@ -1,9 +1,13 @@
 use crate::config::cache::util::ApplyLeniencyDefault;
 use crate::config::tree;
 use crate::repository::{blob_merge_options, merge_resource_cache};
+#[cfg(feature = "merge")]
+use crate::repository::{merge_commits, merge_trees, tree_merge_options};
 use crate::Repository;
 use gix_merge::blob::builtin_driver::text;
 use std::borrow::Cow;
+#[cfg(feature = "merge")]
+use std::ops::DerefMut;
 
 /// Merge-utilities
 impl Repository {
@@ -79,4 +83,92 @@ impl Repository {
             },
         })
     }
+
+    /// Return options for use with [`gix_merge::tree()`] and [`Repository::merge_trees()`], configured according to
+    /// the git configuration of this repository.
+    ///
+    /// Renames are tracked according to `diff.renames`, and tracked by default.
+    #[cfg(feature = "merge")]
+    pub fn tree_merge_options(&self) -> Result<gix_merge::tree::Options, tree_merge_options::Error> {
+        Ok(gix_merge::tree::Options {
+            rewrites: Some(self.config.diff_renames()?.unwrap_or_default()),
+            blob_merge: self.blob_merge_options()?,
+            blob_merge_command_ctx: self.command_context()?,
+        })
+    }
+
+    /// Merge `our_tree` and `their_tree` using `ancestor_tree` as their common ancestor, and return the outcome, which
+    /// holds the merged tree ready to be [written](gix_merge::tree::Outcome::tree), along with all conflicts.
+    /// `labels` are used to annotate conflicts, and `options` can be obtained from
+    /// [`tree_merge_options()`](Self::tree_merge_options()).
+    ///
+    /// Merged blobs are written to the object database right away, and attributes are read from the current index.
+    ///
+    /// Note that the merged tree must be written to obtain its id, like `outcome.tree.write(|tree| repo.write_object(tree))`.
+    #[cfg(feature = "merge")]
+    pub fn merge_trees(
+        &self,
+        ancestor_tree: impl AsRef<gix_hash::oid>,
+        our_tree: impl AsRef<gix_hash::oid>,
+        their_tree: impl AsRef<gix_hash::oid>,
+        labels: text::Labels<'_>,
+        options: gix_merge::tree::Options,
+    ) -> Result<gix_merge::tree::Outcome<'_>, merge_trees::Error> {
+        let mut diff_resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        Ok(gix_merge::tree(
+            ancestor_tree.as_ref(),
+            our_tree.as_ref(),
+            their_tree.as_ref(),
+            labels,
+            &self.objects,
+            |buf| self.write_blob(buf).map(crate::Id::detach),
+            &mut Default::default(),
+            &mut diff_resource_cache,
+            &mut blob_merge,
+            options,
+        )?)
+    }
+
+    /// Merge `our_commit` and `their_commit` by merging their trees with the tree of their merge-base as common ancestor,
+    /// and return the outcome, which holds the merged tree ready to be [written](gix_merge::tree::Outcome::tree) along with
+    /// all conflicts and information about the merge-bases.
+    /// `labels` are used to annotate conflicts, and `options` are typically created with the tree-merge options obtained
+    /// from [`tree_merge_options()`](Self::tree_merge_options()).
+    ///
+    /// If there are multiple merge-bases, they are merged into a virtual merge-base just like `git` does, whose tree
+    /// and all trees leading up to it are written to the object database, along with all merged blobs.
+    /// Attributes are read from the current index, and the commit-graph is used if it's enabled.
+    ///
+    /// # Performance
+    /// Be sure to [set an object cache](crate::Repository::object_cache_size_if_unset) as trees and blobs
+    /// are looked up repeatedly.
+    #[cfg(feature = "merge")]
+    pub fn merge_commits(
+        &self,
+        our_commit: impl Into<gix_hash::ObjectId>,
+        their_commit: impl Into<gix_hash::ObjectId>,
+        labels: text::Labels<'_>,
+        options: gix_merge::commit::Options,
+    ) -> Result<gix_merge::commit::Outcome<'_>, merge_commits::Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(cache.as_ref());
+        let mut diff_resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        Ok(gix_merge::commit(
+            our_commit.into(),
+            their_commit.into(),
+            labels,
+            &mut graph,
+            &mut diff_resource_cache,
+            &mut blob_merge,
+            &self.objects,
+            |object| -> Result<_, crate::object::write::Error> {
+                let mut buf = self.empty_reusable_buffer();
+                object.write_to(buf.deref_mut()).expect("write to memory works");
+                self.write_object_inner(&buf, object.kind()).map(crate::Id::detach)
+            },
+            options,
+        )?)
+    }
 }
@@ -19,6 +19,9 @@ pub enum Kind {
 
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+///
+#[cfg(feature = "blame")]
+mod blame;
 mod cache;
 mod config;
 ///
@@ -46,11 +49,15 @@ mod mailmap;
 #[cfg(feature = "blob-merge")]
 mod merge;
 mod object;
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+mod pack;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
 mod revision;
+#[cfg(feature = "blocking-network-server")]
+mod server;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
@@ -58,6 +65,98 @@ mod submodule;
 mod thread_safe;
 mod worktree;
 
+///
+#[cfg(feature = "blocking-network-server")]
+pub mod receive_pack {
+    use gix_protocol::push::Update;
+
+    use crate::bstr::BString;
+
+    /// The invocation of a hook by [Repository::receive_pack()](crate::Repository::receive_pack()), to let it decide
+    /// which reference updates may be applied, similar to the hooks of `git receive-pack`.
+    ///
+    /// It's only called for updates which passed all checks, and the hook can reject them by returning the reason for it.
+    #[derive(Debug, Clone, Copy)]
+    pub enum Hook<'a> {
+        /// Called once with all updates before any of them is applied, like the `pre-receive` hook.
+        /// Rejecting it rejects all of them.
+        PreReceive {
+            /// The updates requested by the client.
+            updates: &'a [Update],
+            /// The push-options sent by the client, typically in the form of `key=value`.
+            push_options: &'a [BString],
+        },
+        /// Called for each update that passed [`PreReceive`](Hook::PreReceive), like the `update` hook.
+        /// Rejecting it rejects only this update.
+        Update {
+            /// The update requested by the client.
+            update: &'a Update,
+        },
+    }
+}
+
+///
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub mod write_pack {
+    /// The error returned by [Repository::write_pack()](crate::Repository::write_pack()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        InitRefIter(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        IterRefs(#[from] crate::reference::iter::Error),
+        #[error("Could not read a tag reference")]
+        IterRef(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Could not open a thread-safe handle to the object database")]
+        OpenObjectDatabase(#[source] std::io::Error),
+        #[error(transparent)]
+        CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+        #[error(transparent)]
+        WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+        #[error("The operation was interrupted")]
+        Interrupted,
+    }
+
+    /// Options for use in [Repository::write_pack()](crate::Repository::write_pack()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// If `true`, annotated tags which point to objects in the pack are added to it as well.
+        ///
+        /// This is the equivalent of the `include-tag` capability when fetching.
+        pub include_tags: bool,
+    }
+
+    /// The progress ids used in [Repository::write_pack()](crate::Repository::write_pack()).
+    ///
+    /// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+    #[derive(Debug, Copy, Clone)]
+    pub enum ProgressId {
+        /// The amount of commits traversed to find the objects to write.
+        WalkCommits,
+        /// The amount of objects counted for writing them.
+        CountObjects,
+        /// The amount of bytes of the pack that were written.
+        WritePack,
+    }
+
+    impl From<ProgressId> for gix_features::progress::Id {
+        fn from(v: ProgressId) -> Self {
+            match v {
+                ProgressId::WalkCommits => *b"WPWC",
+                ProgressId::CountObjects => *b"WPCO",
+                ProgressId::WritePack => *b"WPWP",
+            }
+        }
+    }
+}
+
 ///
 #[cfg(feature = "blob-diff")]
 pub mod diff_tree_to_tree {
@@ -74,6 +173,24 @@ pub mod diff_tree_to_tree {
     }
 }
 
+///
+#[cfg(feature = "blame")]
+pub mod blame_file {
+    /// The error returned by [Repository::blame_file()](crate::Repository::blame_file()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        CommitGraphIfEnabled(#[from] super::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        RewritesConfiguration(#[from] crate::diff::new_rewrites::Error),
+        #[error(transparent)]
+        Blame(#[from] gix_blame::Error),
+    }
+}
+
 ///
 #[cfg(feature = "blob-merge")]
 pub mod blob_merge_options {
@@ -112,6 +229,56 @@ pub mod merge_resource_cache {
     }
 }
 
+///
+#[cfg(feature = "merge")]
+pub mod tree_merge_options {
+    /// The error returned by [Repository::tree_merge_options()](crate::Repository::tree_merge_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        BlobMergeOptions(#[from] super::blob_merge_options::Error),
+        #[error(transparent)]
+        RewritesConfiguration(#[from] crate::diff::new_rewrites::Error),
+        #[error(transparent)]
+        CommandContext(#[from] crate::config::command_context::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod merge_trees {
+    /// The error returned by [Repository::merge_trees()](crate::Repository::merge_trees()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        MergeResourceCache(#[from] super::merge_resource_cache::Error),
+        #[error(transparent)]
+        MergeTree(#[from] gix_merge::tree::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod merge_commits {
+    /// The error returned by [Repository::merge_commits()](crate::Repository::merge_commits()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        CommitGraphIfEnabled(#[from] super::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        MergeResourceCache(#[from] super::merge_resource_cache::Error),
+        #[error(transparent)]
+        MergeCommits(#[from] gix_merge::commit::Error),
+    }
+}
+
 ///
 #[cfg(feature = "blob-diff")]
 pub mod diff_resource_cache {
@@ -1,9 +1,9 @@
 #![allow(clippy::result_large_err)]
-use std::ops::DerefMut;
+use std::{ops::DerefMut, sync::atomic::AtomicBool};
 
 use gix_hash::ObjectId;
 use gix_object::{Exists, Find, FindExt};
-use gix_odb::{Header, HeaderExt, Write};
+use gix_odb::{FindStream, FindStreamExt, Header, HeaderExt, Write};
 use gix_ref::{
     transaction::{LogChange, PreviousValue, RefLog},
     FullName,
@@ -133,6 +133,42 @@ impl crate::Repository {
         self.objects.try_header(&id).map_err(Into::into)
     }
 
+    /// Obtain a stream over the decompressed data of the object with `id`, or fail if the object doesn't exist.
+    ///
+    /// Use this method to read large objects, like big blobs, without holding them in memory.
+    ///
+    /// # Performance Note
+    ///
+    /// Only loose objects and objects that are stored in packs without deltas can be streamed directly, all others
+    /// will be decoded into memory first.
+    pub fn find_object_stream(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<gix_odb::find::Stream, object::find::existing::Error> {
+        let id = id.into();
+        if id == ObjectId::empty_tree(self.object_hash()) {
+            return Ok(gix_odb::find::Stream::from_data(gix_object::Kind::Tree, Vec::new()));
+        }
+        self.objects.find_stream(&id)
+    }
+
+    /// Obtain a stream over the decompressed data of the object with `id`, or `None` if the object doesn't exist.
+    ///
+    /// See [`Self::find_object_stream()`] for details.
+    pub fn try_find_object_stream(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<Option<gix_odb::find::Stream>, object::find::Error> {
+        let id = id.into();
+        if id == ObjectId::empty_tree(self.object_hash()) {
+            return Ok(Some(gix_odb::find::Stream::from_data(
+                gix_object::Kind::Tree,
+                Vec::new(),
+            )));
+        }
+        self.objects.try_find_stream(&id).map_err(Into::into)
+    }
+
     /// Try to find the object with `id` or return `None` if it wasn't found.
     pub fn try_find_object(&self, id: impl Into<ObjectId>) -> Result<Option<Object<'_>>, object::find::Error> {
         let id = id.into();
@@ -169,8 +205,13 @@ impl crate::Repository {
         self.write_object_inner(&buf, object.kind())
     }
 
-    fn write_object_inner(&self, buf: &[u8], kind: gix_object::Kind) -> Result<Id<'_>, object::write::Error> {
-        let oid = gix_object::compute_hash(self.object_hash(), kind, buf);
+    pub(crate) fn write_object_inner(
+        &self,
+        buf: &[u8],
+        kind: gix_object::Kind,
+    ) -> Result<Id<'_>, object::write::Error> {
+        let oid =
+            gix_object::compute_hash(self.object_hash(), kind, buf).map_err(|err| object::write::Error(err.into()))?;
         if self.objects.exists(&oid) {
             return Ok(oid.attach(self));
         }
@@ -187,7 +228,8 @@ impl crate::Repository {
     /// pre-hashing the data, and checking if the object is already present.
     pub fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<Id<'_>, object::write::Error> {
         let bytes = bytes.as_ref();
-        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, bytes);
+        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, bytes)
+            .map_err(|err| object::write::Error(err.into()))?;
         if self.objects.exists(&oid) {
             return Ok(oid.attach(self));
         }
@@ -202,19 +244,63 @@ impl crate::Repository {
     /// Note that we hash the object in memory to avoid storing objects that are already present. That way,
     /// we avoid writing duplicate objects using slow disks that will eventually have to be garbage collected.
     ///
-    /// If that is prohibitive, use the object database directly.
+    /// Blobs larger than `core.bigFileThreshold` are hashed while reading `bytes` instead, and streamed into a pack
+    /// of their own if they aren't present yet, so memory usage stays bounded.
     pub fn write_blob_stream(
         &self,
         mut bytes: impl std::io::Read + std::io::Seek,
     ) -> Result<Id<'_>, object::write::Error> {
+        let start = bytes
+            .stream_position()
+            .map_err(|err| object::write::Error(err.into()))?;
+        let size = bytes
+            .seek(std::io::SeekFrom::End(0))
+            .map_err(|err| object::write::Error(err.into()))?
+            .saturating_sub(start);
+        bytes
+            .seek(std::io::SeekFrom::Start(start))
+            .map_err(|err| object::write::Error(err.into()))?;
+        if size > self.config.big_file_threshold {
+            return self.write_big_blob_stream(bytes, start, size);
+        }
+
         let mut buf = self.empty_reusable_buffer();
         std::io::copy(&mut bytes, buf.deref_mut()).expect("write to memory works");
 
         self.write_blob_stream_inner(&buf)
     }
 
+    fn write_big_blob_stream(
+        &self,
+        mut bytes: impl std::io::Read + std::io::Seek,
+        start: u64,
+        size: u64,
+    ) -> Result<Id<'_>, object::write::Error> {
+        let oid = gix_object::compute_stream_hash(
+            self.object_hash(),
+            gix_object::Kind::Blob,
+            &mut bytes,
+            size,
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+        )
+        .map_err(|err| object::write::Error(err.into()))?;
+        if self.objects.exists(&oid) {
+            return Ok(oid.attach(self));
+        }
+
+        bytes
+            .seek(std::io::SeekFrom::Start(start))
+            .map_err(|err| object::write::Error(err.into()))?;
+        self.objects
+            .write_stream(gix_object::Kind::Blob, size, &mut bytes)
+            .map_err(Into::into)
+            .map(|oid| oid.attach(self))
+    }
+
     fn write_blob_stream_inner(&self, buf: &[u8]) -> Result<Id<'_>, object::write::Error> {
-        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, buf);
+        let oid = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, buf)
+            .map_err(|err| object::write::Error(err.into()))?;
         if self.objects.exists(&oid) {
             return Ok(oid.attach(self));
         }
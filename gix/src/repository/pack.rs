@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::progress::{Count, NestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+
+use crate::{repository::write_pack, Repository};
+
+impl Repository {
+    /// Write a pack to `out` which contains all objects reachable from `tips`, but none of the objects reachable from `haves`,
+    /// and return the amount of objects written.
+    ///
+    /// `tips` may be commits, which are traversed, annotated tags, which are peeled and sent along with their targets,
+    /// as well as trees or blobs, which are sent with all of their contents.
+    /// `haves` are typically commits the receiving side is known to have, and those that don't exist in this repository are ignored.
+    ///
+    /// The resulting pack is self-contained, and thus can be indexed without access to any other object database.
+    /// Use `options` to further specify what should be contained in the pack.
+    pub fn write_pack<P>(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        haves: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: write_pack::Options,
+        out: &mut dyn std::io::Write,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<usize, write_pack::Error>
+    where
+        P: NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::write_pack()");
+        let haves: gix_hashtable::HashSet = haves
+            .into_iter()
+            .map(Into::into)
+            .filter(|id| self.has_object(id))
+            .collect();
+
+        let mut commits = Vec::new();
+        let mut objects = Vec::new();
+        let mut trees = Vec::new();
+        for id in tips {
+            let mut object = self.find_object(id)?;
+            loop {
+                match object.kind {
+                    gix_object::Kind::Commit => commits.push(object.id),
+                    gix_object::Kind::Tree => trees.push(object.id),
+                    gix_object::Kind::Blob => objects.push(object.id),
+                    gix_object::Kind::Tag => {
+                        objects.push(object.id);
+                        let target = object.to_tag_ref().target();
+                        object = self.find_object(target)?;
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+
+        let mut walk_progress =
+            progress.add_child_with_id("walking commits", write_pack::ProgressId::WalkCommits.into());
+        walk_progress.init(None, gix_features::progress::count("commits"));
+        for info in self.rev_walk(commits).selected({
+            let haves = haves.clone();
+            move |id| !haves.contains(id)
+        })? {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(write_pack::Error::Interrupted);
+            }
+            objects.push(info?.id);
+            walk_progress.inc();
+        }
+        walk_progress.show_throughput(std::time::Instant::now());
+
+        let mut db = self
+            .objects
+            .clone()
+            .into_inner()
+            .into_arc()
+            .map_err(write_pack::Error::OpenObjectDatabase)?;
+        db.prevent_pack_unload();
+        let mut count_progress =
+            progress.add_child_with_id("counting objects", write_pack::ProgressId::CountObjects.into());
+        count_progress.init(None, gix_features::progress::count("objects"));
+        let (mut counts, _) = output::count::objects_unthreaded(
+            &db,
+            &mut objects.into_iter().map(Ok),
+            &count_progress,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?;
+        let mut seen: gix_hashtable::HashSet = counts.iter().map(|count| count.id).collect();
+        if !trees.is_empty() {
+            let (tree_counts, _) = output::count::objects_unthreaded(
+                &db,
+                &mut trees.into_iter().map(Ok),
+                &count_progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::TreeContents,
+            )?;
+            counts.extend(tree_counts.into_iter().filter(|count| seen.insert(count.id)));
+        }
+        // Expansion also counts the parents of the boundary commits and their trees, which are known to the receiving side.
+        let mut known = haves.clone();
+        for id in &haves {
+            let object = self.find_object(*id)?;
+            if let Some(tree_id) = object.try_to_commit_ref_iter().and_then(|mut iter| iter.tree_id().ok()) {
+                known.insert(tree_id);
+            }
+        }
+        counts.retain(|count| !known.contains(&count.id));
+        if options.include_tags {
+            seen.retain(|id| !known.contains(id));
+            let tags = self.annotated_tags_pointing_into(&seen)?;
+            let (tag_counts, _) = output::count::objects_unthreaded(
+                &db,
+                &mut tags.into_iter().map(Ok),
+                &count_progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?;
+            counts.extend(tag_counts.into_iter().filter(|count| seen.insert(count.id)));
+        }
+
+        let num_objects = counts.len();
+        let entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress.add_child("creating entries")),
+            output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                ..Default::default()
+            },
+        ));
+
+        let mut write_progress = progress.add_child_with_id("writing pack", write_pack::ProgressId::WritePack.into());
+        write_progress.init(None, gix_features::progress::bytes());
+        let mut writer = output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            self.object_hash(),
+        );
+        for written in &mut writer {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(write_pack::Error::Interrupted);
+            }
+            write_progress.inc_by(written? as usize);
+        }
+        write_progress.show_throughput(std::time::Instant::now());
+        Ok(num_objects)
+    }
+
+    /// Return the ids of all annotated tags that aren't in `objects` themselves, but whose target is.
+    fn annotated_tags_pointing_into(
+        &self,
+        objects: &gix_hashtable::HashSet,
+    ) -> Result<Vec<ObjectId>, write_pack::Error> {
+        let mut out = Vec::new();
+        for tag in self.references()?.tags()? {
+            let tag = tag.map_err(write_pack::Error::IterRef)?;
+            let Some(id) = tag.target().try_id().map(ToOwned::to_owned) else {
+                continue;
+            };
+            if objects.contains(&id) {
+                continue;
+            }
+            let object = self.find_object(id)?;
+            if object.kind == gix_object::Kind::Tag && objects.contains(&object.to_tag_ref().target()) {
+                out.push(id);
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,420 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_hash::ObjectId;
+use gix_protocol::{
+    handshake::Ref,
+    push::{response::Status, Report, Update},
+    server::{
+        receive_pack,
+        upload_pack::{self, DelegateError, PackOptions},
+    },
+    transport::Protocol,
+};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::BString,
+    repository::{self, write_pack},
+    Repository,
+};
+
+impl Repository {
+    /// Serve a client connected through `input` and `out` as `git upload-pack` would, using the protocol `version`
+    /// the client asked for, to let it obtain references and objects from this repository.
+    ///
+    /// All references are advertised, along with `HEAD`, and packs are created on the fly.
+    /// `should_interrupt` is checked while creating packs.
+    ///
+    /// `input` should be buffered, and in case of `git://` connections, the client's request must already have been
+    /// consumed from it, see [`ConnectRequest`](gix_protocol::server::ConnectRequest). `version` is then derived from it,
+    /// or from the `GIT_PROTOCOL` environment variable
+    /// via [`desired_version_from_git_protocol()`](gix_protocol::server::desired_version_from_git_protocol()).
+    pub fn upload_pack(
+        &self,
+        input: impl std::io::Read,
+        out: impl std::io::Write,
+        version: Protocol,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), upload_pack::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::upload_pack()");
+        gix_protocol::server::upload_pack(
+            input,
+            out,
+            version,
+            &mut UploadPack {
+                repo: self,
+                should_interrupt,
+            },
+            &upload_pack::Options {
+                object_hash: self.object_hash(),
+                agent: self.config.user_agent_tuple().1.map(Into::into),
+                trace: self.config.trace_packet(),
+            },
+        )
+    }
+
+    /// Serve a client connected through `input` and `out` as `git receive-pack` would, using the protocol `version`
+    /// the client asked for, to let it update references in this repository and send the objects they need.
+    ///
+    /// The received pack is stored in the object database, and each update is checked before `hook` is invoked to decide
+    /// if it may be applied, see [`Hook`](repository::receive_pack::Hook) for details.
+    /// Updates are rejected if the previous value of the reference doesn't match, if not all objects reachable from the new value
+    /// are present, or if the reference is the currently checked-out branch, and all updates that remain are applied
+    /// in a single transaction if the client asked for an atomic push.
+    /// `should_interrupt` is checked while receiving the pack.
+    ///
+    /// Return the report that was sent to the client, or `None` if the client didn't request any update.
+    /// See [`upload_pack()`](Self::upload_pack()) for details on `input` and `version`.
+    pub fn receive_pack(
+        &self,
+        input: impl std::io::Read,
+        out: impl std::io::Write,
+        version: Protocol,
+        hook: impl FnMut(repository::receive_pack::Hook<'_>) -> Result<(), BString>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<Report>, receive_pack::Error> {
+        let _span = gix_trace::coarse!("gix::Repository::receive_pack()");
+        gix_protocol::server::receive_pack(
+            input,
+            out,
+            version,
+            &mut ReceivePack {
+                repo: self,
+                hook,
+                should_interrupt,
+            },
+            &receive_pack::Options {
+                object_hash: self.object_hash(),
+                agent: self.config.user_agent_tuple().1.map(Into::into),
+                trace: self.config.trace_packet(),
+            },
+        )
+    }
+}
+
+struct UploadPack<'a> {
+    repo: &'a Repository,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl UploadPack<'_> {
+    /// Create a reference named `full_ref_name` pointing to `id`, which is peeled if it's an annotated tag.
+    fn to_ref(&self, full_ref_name: BString, id: ObjectId) -> Result<Ref, DelegateError> {
+        Ok(if self.repo.find_header(id)?.kind() == gix_object::Kind::Tag {
+            Ref::Peeled {
+                full_ref_name,
+                tag: id,
+                object: self.repo.find_object(id)?.peel_tags_to_end()?.id,
+            }
+        } else {
+            Ref::Direct {
+                full_ref_name,
+                object: id,
+            }
+        })
+    }
+}
+
+impl upload_pack::Delegate for UploadPack<'_> {
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+        let mut refs = Vec::new();
+        let head = self.repo.head()?;
+        let head_target = head.referent_name().map(|name| name.as_bstr().to_owned());
+        refs.push(match (head.id(), head_target) {
+            (Some(id), None) => self.to_ref("HEAD".into(), id.detach())?,
+            (Some(id), Some(target)) => match self.to_ref("HEAD".into(), id.detach())? {
+                Ref::Direct { full_ref_name, object } => Ref::Symbolic {
+                    full_ref_name,
+                    target,
+                    tag: None,
+                    object,
+                },
+                Ref::Peeled {
+                    full_ref_name,
+                    tag,
+                    object,
+                } => Ref::Symbolic {
+                    full_ref_name,
+                    target,
+                    tag: Some(tag),
+                    object,
+                },
+                _ => unreachable!("to_ref() only produces direct or peeled references"),
+            },
+            (None, target) => Ref::Unborn {
+                full_ref_name: "HEAD".into(),
+                target: target.expect("an unborn HEAD always points to a branch"),
+            },
+        });
+
+        for r in self.repo.references()?.all()? {
+            let mut r = r?;
+            // Like `git`, ignore symbolic references which point to nothing.
+            let Ok(id) = r.follow_to_object() else {
+                continue;
+            };
+            refs.push(self.to_ref(r.name().as_bstr().to_owned(), id.detach())?);
+        }
+        Ok(refs)
+    }
+
+    fn contains(&mut self, id: &gix_hash::oid) -> bool {
+        self.repo.has_object(id)
+    }
+
+    fn write_pack(
+        &mut self,
+        wants: &[ObjectId],
+        common: &[ObjectId],
+        options: PackOptions,
+        out: &mut dyn std::io::Write,
+    ) -> Result<(), DelegateError> {
+        self.repo.write_pack(
+            wants.iter().copied(),
+            common.iter().copied(),
+            write_pack::Options {
+                include_tags: options.include_tags,
+            },
+            out,
+            gix_features::progress::Discard,
+            self.should_interrupt,
+        )?;
+        Ok(())
+    }
+}
+
+struct ReceivePack<'a, H> {
+    repo: &'a Repository,
+    hook: H,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl<H> ReceivePack<'_, H> {
+    /// Return the reason for rejecting `update` if it can't be applied, without considering hooks.
+    /// `tips` are the objects all references point to, whose reachable objects are known to be present.
+    fn check(&self, update: &Update, tips: &gix_hashtable::HashSet) -> Result<Option<&'static str>, DelegateError> {
+        let Ok(name) = gix_ref::FullName::try_from(update.name.clone()) else {
+            return Ok(Some("funny refname"));
+        };
+        if !name.as_bstr().starts_with(b"refs/") {
+            return Ok(Some("funny refname"));
+        }
+        let current = match self.repo.try_find_reference(name.as_ref())? {
+            Some(r) => match r.target().try_id() {
+                Some(id) => id.to_owned(),
+                None => return Ok(Some("cannot update symbolic reference")),
+            },
+            None => self.repo.object_hash().null(),
+        };
+        if current != update.old {
+            return Ok(Some("stale info"));
+        }
+        if self.repo.head_name()?.as_ref() == Some(&name) {
+            if update.is_delete() {
+                return Ok(Some("deletion of the current branch prohibited"));
+            }
+            if !self.repo.is_bare() {
+                return Ok(Some("branch is currently checked out"));
+            }
+        }
+        if !update.is_delete() && !self.is_connected(update.new, tips)? {
+            return Ok(Some("missing necessary objects"));
+        }
+        Ok(None)
+    }
+
+    /// Return `true` if `id` and all objects reachable from it are present, assuming that everything reachable from `tips` is.
+    fn is_connected(&self, mut id: ObjectId, tips: &gix_hashtable::HashSet) -> Result<bool, DelegateError> {
+        let repo = self.repo;
+        let mut trees = Vec::new();
+        loop {
+            if tips.contains(&id) {
+                return Ok(true);
+            }
+            let Some(object) = repo.try_find_object(id)? else {
+                return Ok(false);
+            };
+            match object.kind {
+                gix_object::Kind::Tag => {
+                    id = object.to_tag_ref().target();
+                    continue;
+                }
+                gix_object::Kind::Commit => {
+                    let walk = repo.rev_walk([id]).selected({
+                        let tips = tips.clone();
+                        move |id| !tips.contains(id)
+                    })?;
+                    for info in walk {
+                        let Ok(info) = info else {
+                            return Ok(false);
+                        };
+                        trees.push(info.object()?.tree_id()?.detach());
+                    }
+                }
+                gix_object::Kind::Tree => trees.push(id),
+                gix_object::Kind::Blob => {}
+            }
+            break;
+        }
+
+        let mut seen = gix_hashtable::HashSet::default();
+        while let Some(id) = trees.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(tree) = repo.try_find_object(id)? else {
+                return Ok(false);
+            };
+            for entry in tree.try_into_tree()?.iter() {
+                let entry = entry?;
+                if entry.mode().is_tree() {
+                    trees.push(entry.oid().to_owned());
+                } else if !entry.mode().is_commit() && !repo.has_object(entry.oid()) {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn edit(update: &Update) -> Result<RefEdit, DelegateError> {
+        let log = RefLog::AndReference;
+        Ok(RefEdit {
+            change: if update.is_delete() {
+                Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(update.old)),
+                    log,
+                }
+            } else {
+                Change::Update {
+                    log: LogChange {
+                        mode: log,
+                        force_create_reflog: false,
+                        message: "push".into(),
+                    },
+                    expected: if update.is_create() {
+                        PreviousValue::MustNotExist
+                    } else {
+                        PreviousValue::MustExistAndMatch(Target::Object(update.old))
+                    },
+                    new: Target::Object(update.new),
+                }
+            },
+            name: update.name.clone().try_into()?,
+            deref: false,
+        })
+    }
+}
+
+impl<H> receive_pack::Delegate for ReceivePack<'_, H>
+where
+    H: FnMut(repository::receive_pack::Hook<'_>) -> Result<(), BString>,
+{
+    fn refs(&mut self) -> Result<Vec<Ref>, DelegateError> {
+        let mut refs = Vec::new();
+        for r in self.repo.references()?.all()? {
+            let r = r?;
+            // Symbolic references aren't advertised.
+            let Some(id) = r.target().try_id().map(ToOwned::to_owned) else {
+                continue;
+            };
+            refs.push(Ref::Direct {
+                full_ref_name: r.name().as_bstr().to_owned(),
+                object: id,
+            });
+        }
+        Ok(refs)
+    }
+
+    fn receive_pack(&mut self, pack: &mut dyn std::io::BufRead) -> Result<(), DelegateError> {
+        use crate::remote::connection::fetch::config;
+        let repo = self.repo;
+        gix_pack::Bundle::write_to_directory(
+            pack,
+            Some(&repo.objects.store_ref().path().join("pack")),
+            &mut gix_features::progress::Discard,
+            self.should_interrupt,
+            Some(repo.objects.clone()),
+            gix_pack::bundle::write::Options {
+                thread_limit: config::index_threads(repo)?,
+                index_version: config::pack_index_version(repo)?,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                object_hash: repo.object_hash(),
+            },
+        )?;
+        Ok(())
+    }
+
+    fn update_refs(
+        &mut self,
+        updates: &[Update],
+        options: &receive_pack::UpdateOptions,
+    ) -> Result<Vec<Status>, DelegateError> {
+        use repository::receive_pack::Hook;
+
+        let mut tips = gix_hashtable::HashSet::default();
+        for r in self.repo.references()?.all()? {
+            if let Some(id) = r?.target().try_id() {
+                tips.insert(id.to_owned());
+            }
+        }
+        let mut rejections = Vec::<Option<BString>>::with_capacity(updates.len());
+        for update in updates {
+            rejections.push(self.check(update, &tips)?.map(Into::into));
+        }
+
+        let accepted: Vec<_> = updates
+            .iter()
+            .zip(&rejections)
+            .filter(|(_, rejection)| rejection.is_none())
+            .map(|(update, _)| update.clone())
+            .collect();
+        if !accepted.is_empty() {
+            if let Err(reason) = (self.hook)(Hook::PreReceive {
+                updates: &accepted,
+                push_options: &options.push_options,
+            }) {
+                for rejection in rejections.iter_mut().filter(|rejection| rejection.is_none()) {
+                    *rejection = Some(reason.clone());
+                }
+            }
+        }
+        for (update, rejection) in updates.iter().zip(rejections.iter_mut()) {
+            if rejection.is_none() {
+                *rejection = (self.hook)(Hook::Update { update }).err();
+            }
+        }
+
+        let is_accepted = |rejection: &Option<BString>| rejection.is_none();
+        if options.atomic {
+            if !rejections.iter().all(is_accepted) {
+                for rejection in rejections.iter_mut().filter(|rejection| rejection.is_none()) {
+                    *rejection = Some("atomic push failure".into());
+                }
+            } else if self
+                .repo
+                .edit_references(updates.iter().map(Self::edit).collect::<Result<Vec<_>, _>>()?)
+                .is_err()
+            {
+                rejections.fill(Some("failed to update ref".into()));
+            }
+        } else {
+            for (update, rejection) in updates.iter().zip(rejections.iter_mut()) {
+                if rejection.is_none() && self.repo.edit_reference(Self::edit(update)?).is_err() {
+                    *rejection = Some("failed to update ref".into());
+                }
+            }
+        }
+
+        Ok(rejections
+            .into_iter()
+            .map(|rejection| match rejection {
+                None => Status::Ok { rewrites: Vec::new() },
+                Some(reason) => Status::Rejected { reason },
+            })
+            .collect())
+    }
+}
@@ -159,6 +159,8 @@ pub struct Platform<'repo> {
     pub(crate) parents: gix_traverse::commit::Parents,
     pub(crate) use_commit_graph: Option<bool>,
     pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    #[cfg(feature = "attributes")]
+    pub(crate) pathspec: Option<gix_pathspec::Search>,
 }
 
 impl<'repo> Platform<'repo> {
@@ -171,6 +173,8 @@ impl<'repo> Platform<'repo> {
             use_commit_graph: None,
             commit_graph: None,
             prune: Vec::new(),
+            #[cfg(feature = "attributes")]
+            pathspec: None,
         }
     }
 }
@@ -209,6 +213,19 @@ impl Platform<'_> {
         self
     }
 
+    /// Only return commits that changed a path matching `pathspec` compared to their first parent, or for root commits,
+    /// compared to the empty tree, similar to `git log -- <pathspec>` without history simplification.
+    ///
+    /// Note that the ancestry of commits that don't match is still traversed, and that attributes in `pathspec` never match.
+    /// If the commit-graph has changed-path Bloom filters and all patterns are non-excluded, case-sensitive and have a
+    /// literal directory prefix, commits are ruled out without diffing their trees.
+    #[cfg(feature = "attributes")]
+    pub fn with_pathspec(mut self, pathspec: gix_pathspec::Search) -> Self {
+        let is_empty = pathspec.patterns().len() == 0;
+        self.pathspec = (!is_empty).then_some(pathspec);
+        self
+    }
+
     /// Prune the commit with the given `ids` such that they won't be returned, and such that none of their ancestors is returned either.
     ///
     /// Note that this forces the [sorting](Self::sorting) to
@@ -260,54 +277,66 @@ impl<'repo> Platform<'repo> {
             use_commit_graph,
             commit_graph,
             mut prune,
+            #[cfg(feature = "attributes")]
+            pathspec,
         } = self;
         prune.sort();
-        Ok(revision::Walk {
-            repo,
-            inner: Box::new(
-                gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
-                    // Note that specific shallow handling for commit-graphs isn't needed as these contain
-                    // all information there is, and exclude shallow parents to be structurally consistent.
-                    let shallow_commits = repo.shallow_commits()?;
-                    let mut grafted_parents_to_skip = Vec::new();
-                    let mut buf = Vec::new();
-                    move |id| {
-                        if !filter(id) {
-                            return false;
-                        }
-                        let id = id.to_owned();
-                        if prune.binary_search(&id).is_ok() {
-                            return false;
-                        }
-                        match shallow_commits.as_ref() {
-                            Some(commits) => {
-                                if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
-                                    grafted_parents_to_skip.remove(idx);
-                                    return false;
-                                };
-                                if commits.binary_search(&id).is_ok() {
-                                    if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
-                                        grafted_parents_to_skip.extend(commit.parent_ids());
-                                        grafted_parents_to_skip.sort();
-                                    }
-                                };
-                                true
+        let use_commit_graph = use_commit_graph.map_or_else(|| repo.config.may_use_commit_graph(), Ok)?;
+        let inner = Box::new(
+            gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
+                // Note that specific shallow handling for commit-graphs isn't needed as these contain
+                // all information there is, and exclude shallow parents to be structurally consistent.
+                let shallow_commits = repo.shallow_commits()?;
+                let mut grafted_parents_to_skip = Vec::new();
+                let mut buf = Vec::new();
+                move |id| {
+                    if !filter(id) {
+                        return false;
+                    }
+                    let id = id.to_owned();
+                    if prune.binary_search(&id).is_ok() {
+                        return false;
+                    }
+                    match shallow_commits.as_ref() {
+                        Some(commits) => {
+                            if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
+                                grafted_parents_to_skip.remove(idx);
+                                return false;
+                            }
+                            if commits.binary_search(&id).is_ok() {
+                                if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
+                                    grafted_parents_to_skip.extend(commit.parent_ids());
+                                    grafted_parents_to_skip.sort();
+                                }
                             }
-                            None => true,
+                            true
                         }
+                        None => true,
                     }
-                })
-                .sorting(sorting.into_simple().expect("for now there is nothing else"))?
-                .parents(parents)
-                .commit_graph(
-                    commit_graph.or(use_commit_graph
-                        .map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
-                        .then(|| self.repo.commit_graph().ok())
-                        .flatten()),
-                )
-                .map(|res| res.map_err(iter::Error::from)),
-            ),
-        })
+                }
+            })
+            .sorting(sorting.into_simple().expect("for now there is nothing else"))?
+            .parents(parents)
+            .commit_graph(commit_graph.or(use_commit_graph.then(|| repo.commit_graph().ok()).flatten()))
+            .map(|res| res.map_err(iter::Error::from)),
+        );
+        #[cfg(feature = "attributes")]
+        if let Some(pathspec) = pathspec {
+            let mut changed_paths =
+                changed_paths::Filter::new(pathspec, use_commit_graph.then(|| repo.commit_graph().ok()).flatten());
+            return Ok(revision::Walk {
+                repo,
+                inner: Box::new(inner.filter_map(move |res| match res {
+                    Ok(info) => match changed_paths.matches(repo, &info) {
+                        Ok(true) => Some(Ok(info)),
+                        Ok(false) => None,
+                        Err(err) => Some(Err(err)),
+                    },
+                    Err(err) => Some(Err(err)),
+                })),
+            });
+        }
+        Ok(revision::Walk { repo, inner })
     }
     /// Return an iterator to traverse all commits reachable as configured by the [Platform].
     ///
@@ -328,6 +357,209 @@ pub mod iter {
     pub enum Error {
         #[error(transparent)]
         SimpleTraversal(#[from] gix_traverse::commit::simple::Error),
+        #[error(transparent)]
+        FindObject(#[from] gix_object::find::existing_iter::Error),
+        #[error(transparent)]
+        DiffTree(#[from] gix_diff::tree::Error),
+    }
+}
+
+#[cfg(feature = "attributes")]
+mod changed_paths {
+    use std::collections::VecDeque;
+
+    use gix_commitgraph::bloom;
+    use gix_hash::ObjectId;
+    use gix_object::{
+        bstr::{BStr, BString, ByteSlice, ByteVec},
+        FindExt,
+    };
+    use gix_pathspec::{MagicSignature, SearchMode};
+
+    use super::iter::Error;
+    use crate::Repository;
+
+    /// Decide if commits changed paths matching a pathspec, using Bloom filters of the commit-graph if possible.
+    pub(super) struct Filter {
+        pathspec: gix_pathspec::Search,
+        graph: Option<gix_commitgraph::Graph>,
+        /// The literal paths to look up in Bloom filters, or `None` if the pathspec can't be represented by them.
+        bloom_paths: Option<Vec<BString>>,
+        /// The keys of `bloom_paths` for each kind of Bloom filter we encountered.
+        keys: Vec<(bloom::Settings, Vec<bloom::Key>)>,
+        state: gix_diff::tree::State,
+        buf: Vec<u8>,
+        lhs_buf: Vec<u8>,
+        rhs_buf: Vec<u8>,
+    }
+
+    impl Filter {
+        pub(super) fn new(pathspec: gix_pathspec::Search, graph: Option<gix_commitgraph::Graph>) -> Self {
+            let bloom_paths = graph
+                .as_ref()
+                .and_then(gix_commitgraph::Graph::bloom_filter_settings)
+                .and_then(|_| pathspec.patterns().map(bloom_path).collect());
+            Filter {
+                pathspec,
+                graph,
+                bloom_paths,
+                keys: Vec::new(),
+                state: Default::default(),
+                buf: Vec::new(),
+                lhs_buf: Vec::new(),
+                rhs_buf: Vec::new(),
+            }
+        }
+
+        /// Return `true` if the commit of `info` changed a path matching our pathspec compared to its first parent.
+        pub(super) fn matches(&mut self, repo: &Repository, info: &gix_traverse::commit::Info) -> Result<bool, Error> {
+            if self.is_ruled_out_by_bloom_filter(&info.id) {
+                return Ok(false);
+            }
+            let tree = self.tree_of(repo, &info.id)?;
+            let parent_tree = info
+                .parent_ids
+                .first()
+                .map(|parent| self.tree_of(repo, parent))
+                .transpose()?;
+
+            let lhs = match parent_tree {
+                Some(parent_tree) => repo.objects.find_tree_iter(&parent_tree, &mut self.lhs_buf)?,
+                None => gix_object::TreeRefIter::from_bytes(&[], repo.object_hash()),
+            };
+            let rhs = repo.objects.find_tree_iter(&tree, &mut self.rhs_buf)?;
+            let mut delegate = Delegate {
+                pathspec: &mut self.pathspec,
+                path_deque: Default::default(),
+                path: Default::default(),
+                matched: false,
+            };
+            match gix_diff::tree(lhs, rhs, &mut self.state, &repo.objects, &mut delegate) {
+                Ok(()) | Err(gix_diff::tree::Error::Cancelled) => Ok(delegate.matched),
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        fn is_ruled_out_by_bloom_filter(&mut self, id: &gix_hash::oid) -> bool {
+            let (Some(graph), Some(paths)) = (self.graph.as_ref(), self.bloom_paths.as_ref()) else {
+                return false;
+            };
+            let Some(filter) = graph.commit_by_id(id).and_then(|commit| commit.changed_paths_filter()) else {
+                return false;
+            };
+            let keys = match self.keys.iter().position(|(settings, _)| settings == filter.settings()) {
+                Some(pos) => &self.keys[pos].1,
+                None => {
+                    let settings = *filter.settings();
+                    let keys = paths
+                        .iter()
+                        .map(|path| bloom::Key::new(path.as_ref(), &settings))
+                        .collect();
+                    self.keys.push((settings, keys));
+                    &self.keys.last().expect("just pushed").1
+                }
+            };
+            !keys.iter().any(|key| filter.contains(key))
+        }
+
+        fn tree_of(&mut self, repo: &Repository, commit: &gix_hash::oid) -> Result<ObjectId, Error> {
+            if let Some(commit) = self.graph.as_ref().and_then(|graph| graph.commit_by_id(commit)) {
+                return Ok(commit.root_tree_id().to_owned());
+            }
+            Ok(repo
+                .objects
+                .find_commit_iter(commit, &mut self.buf)?
+                .tree_id()
+                .map_err(|err| gix_object::find::existing_iter::Error::Find(Box::new(err)))?)
+        }
+    }
+
+    /// Return the longest literal leading path of `pattern`, without trailing slash, which must be contained in the Bloom
+    /// filter of every commit that changed a path matching it, or `None` if there is no such path.
+    fn bloom_path(pattern: &gix_pathspec::Pattern) -> Option<BString> {
+        if pattern.is_excluded()
+            || pattern.is_nil()
+            || !pattern.attributes.is_empty()
+            || pattern.signature.contains(MagicSignature::ICASE)
+        {
+            return None;
+        }
+        let path = pattern.path();
+        let path = match pattern.search_mode {
+            SearchMode::Literal => path,
+            SearchMode::ShellGlob | SearchMode::PathAwareGlob => match path.find_byteset(b"*?[\\") {
+                Some(wildcard_pos) => path[..path[..wildcard_pos].rfind_byte(b'/')?].as_bstr(),
+                None => path,
+            },
+        };
+        let path = path.strip_suffix(b"/").unwrap_or(path);
+        (!path.is_empty()).then(|| path.into())
+    }
+
+    /// A tree-diff delegate which stops once a non-tree entry matching the pathspec changed.
+    struct Delegate<'a> {
+        pathspec: &'a mut gix_pathspec::Search,
+        path_deque: VecDeque<BString>,
+        path: BString,
+        matched: bool,
+    }
+
+    impl Delegate<'_> {
+        fn pop_element(&mut self) {
+            if let Some(pos) = self.path.rfind_byte(b'/') {
+                self.path.resize(pos, 0);
+            } else {
+                self.path.clear();
+            }
+        }
+
+        fn push_element(&mut self, name: &BStr) {
+            if !self.path.is_empty() {
+                self.path.push(b'/');
+            }
+            self.path.push_str(name);
+        }
+    }
+
+    impl gix_diff::tree::Visit for Delegate<'_> {
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            self.path = self.path_deque.pop_front().expect("every parent is set only once");
+        }
+
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            self.push_element(component);
+            self.path_deque.push_back(self.path.clone());
+        }
+
+        fn push_path_component(&mut self, component: &BStr) {
+            self.push_element(component);
+        }
+
+        fn pop_path_component(&mut self) {
+            self.pop_element();
+        }
+
+        fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
+            use gix_diff::tree::visit::{Action, Change};
+            let is_file = match change {
+                Change::Addition { entry_mode, .. } | Change::Deletion { entry_mode, .. } => !entry_mode.is_tree(),
+                Change::Modification {
+                    previous_entry_mode,
+                    entry_mode,
+                    ..
+                } => !previous_entry_mode.is_tree() || !entry_mode.is_tree(),
+            };
+            if is_file
+                && self
+                    .pathspec
+                    .pattern_matching_relative_path(self.path.as_ref(), Some(false), &mut |_, _, _, _| false)
+                    .map_or(false, |m| !m.is_excluded())
+            {
+                self.matched = true;
+                return Action::Cancel;
+            }
+            Action::Continue
+        }
     }
 }
 
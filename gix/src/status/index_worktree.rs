@@ -307,7 +307,6 @@ pub struct Iter {
 ///
 pub mod iter {
     use crate::bstr::{BStr, BString};
-    use crate::config::cache::util::ApplyLeniencyDefault;
     use crate::status::index_worktree::{iter, BuiltinSubmoduleStatus};
     use crate::status::{index_worktree, Platform};
     use crate::worktree::IndexPersistedOrInMemory;
@@ -326,7 +325,7 @@ pub mod iter {
         pub index_worktree: gix_status::index_as_worktree_with_renames::Outcome,
         /// The index that was used for the operation.
         pub index: crate::worktree::IndexPersistedOrInMemory,
-        skip_hash: bool,
+        write_options: gix_index::write::Options,
         changes: Option<Vec<(usize, iter::ApplyChange)>>,
     }
 
@@ -362,10 +361,7 @@ pub mod iter {
                 }
             }
 
-            Some(index.write(crate::index::write::Options {
-                extensions: Default::default(),
-                skip_hash: self.skip_hash,
-            }))
+            Some(index.write(self.write_options))
         }
     }
 
@@ -604,7 +600,7 @@ pub mod iter {
         #[cfg(not(feature = "parallel"))]
         IndexWorktreeStatus(#[from] crate::status::index_worktree::Error),
         #[error(transparent)]
-        ConfigSkipHash(#[from] crate::config::boolean::Error),
+        IndexWriteOptions(#[from] crate::config::index_write_options::Error),
         #[error(transparent)]
         PrepareSubmodules(#[from] crate::submodule::modules::Error),
     }
@@ -628,15 +624,7 @@ pub mod iter {
                 Some(index) => index,
             };
 
-            let skip_hash = self
-                .repo
-                .config
-                .resolved
-                .boolean(crate::config::tree::Index::SKIP_HASH)
-                .map(|res| crate::config::tree::Index::SKIP_HASH.enrich_error(res))
-                .transpose()
-                .with_lenient_default(self.repo.config.lenient_config)?
-                .unwrap_or_default();
+            let write_options = self.repo.config.index_write_options()?;
             let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
             let submodule = BuiltinSubmoduleStatus::new(self.repo.clone().into_sync(), self.submodules)?;
             #[cfg(feature = "parallel")]
@@ -667,7 +655,7 @@ pub mod iter {
                                 index_worktree: out,
                                 index,
                                 changes: None,
-                                skip_hash,
+                                write_options,
                             })
                         }
                     })
@@ -702,7 +690,7 @@ pub mod iter {
                     index_worktree: out,
                     index,
                     changes: None,
-                    skip_hash,
+                    write_options,
                 };
                 let mut iter = super::Iter {
                     items: Vec::new().into_iter(),
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+mkdir -p dir/sub
+echo a > a
+git add a && git commit -q -m "add a"
+echo b > dir/b
+git add dir && git commit -q -m "add dir/b"
+echo c > dir/sub/c.txt
+git add dir && git commit -q -m "add dir/sub/c.txt"
+
+git checkout -q -b side
+echo b2 > dir/b
+git commit -q -am "change dir/b"
+
+git checkout -q main
+echo a2 > a
+git commit -q -am "change a"
+git merge -q -m "merge side" side
+
+echo c2 > dir/sub/c.txt
+echo d > dir/sub/d.md
+git add dir && git commit -q -m "change dir/sub"
+
+git commit-graph write --no-progress --reachable --changed-paths
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 2000 > file
+git add file && git commit -q -m main
+
+git checkout -q --orphan other
+seq 2001 > file
+git add file && git commit -q -m other
+git checkout -q main
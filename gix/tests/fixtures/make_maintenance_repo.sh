@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+git add a && git commit -q -m c1
+
+git checkout -q -b side
+echo side > side
+git add side && git commit -q -m side
+git checkout -q main
+
+git repack -q -a -d
+git pack-refs --all
+
+git branch -q -D side
+echo b > b
+git add b && git commit -q -m c2
+
+echo unreachable | git hash-object -w --stdin >/dev/null
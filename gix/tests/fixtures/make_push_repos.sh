@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --bare remote.git
+
+git init -q local
+(cd local
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  git branch old
+  echo 2 > file && git commit -q -am c2
+  git tag v1
+  git branch to-delete
+  git remote add origin ../remote.git
+  git push -q origin main to-delete v1
+  git fetch -q origin
+  git branch --set-upstream-to origin/main main
+
+  git checkout -q -b feature
+  echo feature > new && git add new && git commit -q -m "feature"
+  git checkout -q -b skewed main
+  echo skewed-1 > file && GIT_COMMITTER_DATE="2000-01-01 00:00:00 +0000" git commit -q -am "skewed, dated before its parent"
+  echo skewed-2 > file && git commit -q -am "skewed"
+  git checkout -q main
+  mkdir dir && echo 3 > dir/file && git add dir && git commit -q -m c3
+)
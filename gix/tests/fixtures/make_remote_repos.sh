@@ -381,3 +381,16 @@ git init empty-core-askpass
 (cd empty-core-askpass
   echo "    askpass =" >> .git/config
 )
+
+git init partial-clone-source
+(cd partial-clone-source
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+  mkdir dir
+  echo small > small
+  seq 1000 > big
+  echo nested > dir/nested
+  git add . && git commit -m "init"
+  echo changed > small
+  git commit -am "change small"
+)
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+echo a > a
+mkdir dir && echo b > dir/b
+git add . && git commit -q -m c1
+echo a >> a
+git commit -q -am c2
+
+git gc -q
+git multi-pack-index write
+
+echo c > dir/c
+git add . && git commit -q -m "loose"
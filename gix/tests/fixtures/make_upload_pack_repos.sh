@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  git tag -m "the first release" v1
+  echo 2 > file && git commit -q -am c2
+)
+
+git clone -q --bare server client.git
+
+(cd server
+  echo 3 > file && git commit -q -am c3
+  git checkout -q -b feature
+  echo feature > new && git add new && git commit -q -m feature
+  git checkout -q main
+)
@@ -9,7 +9,7 @@ mod blocking_io {
         bstr::BString,
         config::tree::{Clone, Core, Init, Key},
         remote::{
-            fetch::{Shallow, SpecIndex},
+            fetch::{ObjectFilter, Shallow, SpecIndex},
             Direction,
         },
     };
@@ -694,6 +694,148 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_only_with_filter_then_obtain_missing_blobs_lazily() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(remote::repo("partial-clone-source").path(), tmp.path())?
+            .with_filter(ObjectFilter::BlobNone)
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+        assert_eq!(
+            config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+            "blob:none"
+        );
+        assert_eq!(
+            config.string("extensions.partialClone").expect("set").as_ref(),
+            "origin"
+        );
+        assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+        drop(config);
+        assert_eq!(
+            promisor_files(&repo)?,
+            1,
+            "the received pack is marked as promisor pack"
+        );
+
+        let tree = repo.head_commit()?.tree()?;
+        let small = tree.find_entry("small").expect("present").object_id();
+        assert!(tree.find_entry("dir").is_some(), "trees are present");
+        assert!(
+            !repo.has_object(small),
+            "blobs were omitted, and checking for them doesn't fetch"
+        );
+
+        let blob = repo.find_object(small)?;
+        assert_eq!(
+            blob.data.as_bstr(),
+            "changed\n",
+            "the blob was fetched from the promisor remote"
+        );
+        assert!(repo.has_object(small));
+        assert_eq!(
+            promisor_files(&repo)?,
+            2,
+            "the lazily fetched pack is a promisor pack as well"
+        );
+
+        let repo = gix::open_opts(repo.git_dir(), restricted())?;
+        let nested = repo.rev_parse_single("HEAD:dir/nested")?;
+        assert_eq!(
+            nested.object()?.data.as_bstr(),
+            "nested\n",
+            "partial clones obtain missing objects after re-opening them as well"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_with_filter() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("partial-clone-source").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(ObjectFilter::BlobLimit { size: 100 });
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let index = repo.index()?;
+        assert_eq!(index.entries().len(), 3);
+        let work_dir = repo.work_dir().expect("non-bare");
+        assure_index_entries_on_disk(&index, work_dir);
+        assert_eq!(
+            std::fs::read(work_dir.join("big"))?.len(),
+            3893,
+            "the blob omitted due to its size was obtained for checkout"
+        );
+        assert_eq!(
+            promisor_files(&repo)?,
+            2,
+            "only the big blob was fetched lazily, small ones were part of the clone"
+        );
+        assert_eq!(
+            repo.config_snapshot()
+                .string("remote.origin.partialCloneFilter")
+                .expect("set")
+                .as_ref(),
+            "blob:limit=100"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_with_filter_obtains_all_missing_blobs_at_once() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("partial-clone-source").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(ObjectFilter::BlobNone);
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let index = repo.index()?;
+        assert_eq!(index.entries().len(), 3);
+        assure_index_entries_on_disk(&index, repo.work_dir().expect("non-bare"));
+        assert_eq!(
+            promisor_files(&repo)?,
+            2,
+            "all blobs were fetched with a single request before checking them out"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_with_filter_fails_if_the_remote_does_not_support_it() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_filter(ObjectFilter::TreeDepth { depth: 0 })
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::MissingServerFeature { feature: "filter", .. })
+        ));
+        Ok(())
+    }
+
+    fn promisor_files(repo: &gix::Repository) -> std::io::Result<usize> {
+        Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "promisor"))
+            .count())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -269,6 +269,60 @@ mod fetch {
     }
 }
 
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn pack_refs() -> crate::Result {
+        for (value, is_bare, expected) in [
+            ("true", true, true),
+            ("false", false, false),
+            ("notbare", false, true),
+            ("notbare", true, false),
+        ] {
+            assert_eq!(Gc::PACK_REFS.try_into_pack_refs(bcow(value), is_bare)?, expected);
+            assert!(Gc::PACK_REFS.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::PACK_REFS
+                .try_into_pack_refs(bcow("foo"), false)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.packRefs=foo\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expire() -> crate::Result {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        for (value, expected) in [
+            ("never", None),
+            ("false", None),
+            ("now", Some(i64::MAX)),
+            ("all", Some(i64::MAX)),
+            ("2.weeks.ago", Some(86 * 24 * 60 * 60)),
+            ("90 days ago", Some(10 * 24 * 60 * 60)),
+            ("1970-01-02", Some(24 * 60 * 60)),
+        ] {
+            assert_eq!(Gc::PRUNE_EXPIRE.try_into_expire(bcow(value), now)?, expected, "{value}");
+            assert!(Gc::REFLOG_EXPIRE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expire(bcow("foo"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=foo\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 #[cfg(feature = "blob-diff")]
 mod diff {
     use gix::{
@@ -694,6 +748,10 @@ mod extensions {
             gix_hash::Kind::Sha1,
             "case-insensitive"
         );
+        assert_eq!(
+            Extensions::OBJECT_FORMAT.try_into_object_format(bcow("sha256"))?,
+            gix_hash::Kind::Sha256
+        );
         assert_eq!(
             Extensions::OBJECT_FORMAT
                 .try_into_object_format(bcow("invalid"))
@@ -702,9 +760,32 @@ mod extensions {
             "The key \"extensions.objectFormat=invalid\" was invalid"
         );
         assert!(Extensions::OBJECT_FORMAT.validate("sha1".into()).is_ok());
+        assert!(Extensions::OBJECT_FORMAT.validate("sha256".into()).is_ok());
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("files"))?,
+            gix::refs::store::StorageFormat::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("reftable"))?,
+            gix::refs::store::StorageFormat::Reftable
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_storage(bcow("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=invalid\" was invalid"
+        );
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_ok());
+        assert!(Extensions::REF_STORAGE.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
@@ -1096,6 +1177,61 @@ mod remote {
         Ok(())
     }
 
+    #[test]
+    fn partial_clone_filter() -> crate::Result {
+        use gix::remote::fetch::ObjectFilter;
+        for (spec, expected, normalized) in [
+            ("blob:none", ObjectFilter::BlobNone, "blob:none"),
+            ("blob:limit=42", ObjectFilter::BlobLimit { size: 42 }, "blob:limit=42"),
+            (
+                "blob:limit=1k",
+                ObjectFilter::BlobLimit { size: 1024 },
+                "blob:limit=1024",
+            ),
+            (
+                "blob:limit=2M",
+                ObjectFilter::BlobLimit { size: 2 << 20 },
+                "blob:limit=2097152",
+            ),
+            ("tree:0", ObjectFilter::TreeDepth { depth: 0 }, "tree:0"),
+            (
+                "sparse:oid=HEAD:.sparse",
+                ObjectFilter::SparseOid {
+                    spec: "HEAD:.sparse".into(),
+                },
+                "sparse:oid=HEAD:.sparse",
+            ),
+        ] {
+            let actual = Remote::PARTIAL_CLONE_FILTER.try_into_object_filter(bcow(spec))?;
+            assert_eq!(actual, expected);
+            assert_eq!(actual.to_bstring(), normalized);
+            assert!(Remote::PARTIAL_CLONE_FILTER.validate(spec.into()).is_ok());
+        }
+
+        for invalid in [
+            "blob:limit=",
+            "blob:limit=k",
+            "tree:",
+            "tree:-1",
+            "sparse:oid=",
+            "sparse:path=x",
+            "combine:",
+        ] {
+            assert!(
+                Remote::PARTIAL_CLONE_FILTER.validate(invalid.into()).is_err(),
+                "{invalid} is not supported"
+            );
+        }
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER
+                .try_into_object_filter(bcow("blob:unknown"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"remote.<name>.partialCloneFilter=blob:unknown\" was invalid"
+        );
+        Ok(())
+    }
+
     #[test]
     fn url_and_push_url() {
         assert!(Remote::URL.try_into_url(bcow("http://example.org")).is_ok());
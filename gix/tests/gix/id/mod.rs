@@ -188,4 +188,56 @@ mod ancestors {
         }
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "attributes")]
+    fn with_pathspec() -> crate::Result {
+        let repo = crate::named_repo("make_changed_paths_repo.sh")?;
+        let graph = repo.commit_graph()?;
+        assert!(graph.bloom_filter_settings().is_some(), "the fixture has Bloom filters");
+        let head = repo.head_id()?;
+
+        for (specs, expected) in [
+            (&["a"][..], &["change a", "add a"][..]),
+            (
+                &["dir"],
+                &[
+                    "change dir/sub",
+                    "merge side",
+                    "change dir/b",
+                    "add dir/sub/c.txt",
+                    "add dir/b",
+                ],
+            ),
+            (&["dir/sub/"], &["change dir/sub", "add dir/sub/c.txt"]),
+            (&["dir/sub/*.md"], &["change dir/sub"]),
+            (&["*.txt"], &["change dir/sub", "add dir/sub/c.txt"]),
+            (
+                &["dir/b", "a"],
+                &["merge side", "change a", "change dir/b", "add dir/b", "add a"],
+            ),
+            (&[":(exclude)dir"], &["change a", "add a"]),
+            (&["does-not-exist"], &[]),
+        ] {
+            for use_commit_graph in [false, true] {
+                let pathspec = gix::pathspec::Search::from_specs(
+                    specs
+                        .iter()
+                        .map(|spec| gix::pathspec::parse(spec.as_bytes(), Default::default()))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None,
+                    std::path::Path::new(""),
+                )?;
+                let actual = head
+                    .ancestors()
+                    .use_commit_graph(use_commit_graph)
+                    .with_pathspec(pathspec)
+                    .all()?
+                    .map(|info| -> crate::Result<_> { Ok(info?.object()?.message()?.summary().to_string()) })
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(actual, expected, "{specs:?}, commit-graph: {use_commit_graph}");
+            }
+        }
+        Ok(())
+    }
 }
@@ -66,8 +66,11 @@ pub(crate) fn into_daemon_remote_if_async<'repo, 'a>(
 
 mod connect;
 pub(crate) mod fetch;
+mod push;
 mod ref_map;
 mod save;
+#[cfg(feature = "blocking-network-server")]
+mod serve;
 mod name {
 
     #[test]
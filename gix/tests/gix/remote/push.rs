@@ -0,0 +1,189 @@
+#[cfg(feature = "blocking-network-client")]
+mod blocking_io {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::protocol::transport::client::Transport;
+    use gix::remote::{
+        push::{Lease, Prepare, Rejection, Status},
+        Direction::Push,
+    };
+
+    fn repos() -> crate::Result<(gix::Repository, gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let dir = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+        let local = gix::open_opts(dir.path().join("local"), crate::restricted())?;
+        let remote = gix::open_opts(dir.path().join("remote.git"), crate::restricted())?;
+        Ok((local, remote, dir))
+    }
+
+    /// The configured url is relative to the local repository, which isn't our current working directory.
+    fn origin<'repo>(repo: &'repo gix::Repository, remote_repo: &gix::Repository) -> crate::Result<gix::Remote<'repo>> {
+        Ok(repo.find_remote("origin")?.push_url(remote_repo.git_dir())?)
+    }
+
+    fn spec(spec: &str) -> gix_refspec::RefSpec {
+        gix_refspec::parse(spec.into(), gix_refspec::parse::Operation::Push)
+            .expect("valid")
+            .to_owned()
+    }
+
+    fn prepare<'a, 'repo>(
+        remote: &'a gix::Remote<'repo>,
+        spec_str: &str,
+    ) -> crate::Result<Prepare<'a, 'repo, Box<dyn Transport + Send>>> {
+        Ok(remote
+            .connect(Push)?
+            .prepare_push(gix::progress::Discard, Default::default())?
+            .with_refspecs([spec(spec_str)]))
+    }
+
+    fn id(repo: &gix::Repository, name: &str) -> Option<gix::ObjectId> {
+        repo.try_find_reference(name)
+            .expect("valid name")
+            .map(|r| r.id().detach())
+    }
+
+    #[test]
+    fn push_default_updates_current_branch_and_remote_tracking_ref() -> crate::Result {
+        let (repo, remote_repo, _tmp) = repos()?;
+        let remote = origin(&repo, &remote_repo)?;
+        let outcome = remote
+            .connect(Push)?
+            .prepare_push(gix::progress::Discard, Default::default())?
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert!(outcome.is_success());
+        assert_eq!(
+            outcome.updates.len(),
+            1,
+            "`push.default=simple` pushes only the current branch"
+        );
+        let update = &outcome.updates[0];
+        assert_eq!(update.remote, "refs/heads/main");
+        assert_eq!(update.status, Status::Ok { rewrites: Vec::new() });
+        assert_eq!(
+            outcome.num_objects_sent,
+            Some(4),
+            "the commit, its changed trees and the new blob"
+        );
+
+        let main = id(&repo, "refs/heads/main");
+        assert_eq!(id(&remote_repo, "refs/heads/main"), main);
+        assert_eq!(id(&repo, "refs/remotes/origin/main"), main);
+        assert_eq!(outcome.tracking_ref_edits.len(), 1);
+        assert!(
+            remote_repo
+                .find_commit(main.expect("set"))?
+                .tree()?
+                .find_entry("dir")
+                .is_some(),
+            "the remote has all objects"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn new_branch_and_deletion() -> crate::Result {
+        let (repo, remote_repo, _tmp) = repos()?;
+        let remote = origin(&repo, &remote_repo)?;
+        let outcome = remote
+            .connect(Push)?
+            .prepare_push(gix::progress::Discard, Default::default())?
+            .with_refspecs([spec("feature"), spec(":refs/heads/to-delete"), spec(":missing")])
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+
+        let statuses: Vec<_> = outcome
+            .updates
+            .iter()
+            .map(|u| (u.remote.to_string(), u.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("refs/heads/feature".into(), Status::Ok { rewrites: Vec::new() }),
+                ("refs/heads/to-delete".into(), Status::Ok { rewrites: Vec::new() }),
+                (
+                    "refs/heads/missing".into(),
+                    Status::Rejected(Rejection::RemoteRefMissing)
+                ),
+            ]
+        );
+        assert_eq!(id(&remote_repo, "refs/heads/feature"), id(&repo, "refs/heads/feature"));
+        assert_eq!(id(&remote_repo, "refs/heads/to-delete"), None);
+        assert_eq!(
+            id(&repo, "refs/remotes/origin/to-delete"),
+            None,
+            "tracking refs are deleted"
+        );
+        assert_eq!(
+            id(&repo, "refs/remotes/origin/feature"),
+            id(&repo, "refs/heads/feature")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_fast_forward_needs_force_or_lease() -> crate::Result {
+        const OLD_TO_MAIN: &str = "refs/heads/old:refs/heads/main";
+        let (repo, remote_repo, _tmp) = repos()?;
+        let remote = origin(&repo, &remote_repo)?;
+        let remote_main = id(&remote_repo, "refs/heads/main");
+        let outcome = prepare(&remote, OLD_TO_MAIN)?.transmit(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(outcome.updates[0].status, Status::Rejected(Rejection::NonFastForward));
+        assert!(!outcome.is_success());
+
+        let outcome = prepare(&remote, OLD_TO_MAIN)?
+            .with_force(true)
+            .with_dry_run(true)
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(outcome.updates[0].status, Status::DryRun);
+        assert_eq!(outcome.num_objects_sent, None);
+        assert_eq!(id(&remote_repo, "refs/heads/main"), remote_main, "nothing changed");
+
+        let outcome = prepare(&remote, OLD_TO_MAIN)?
+            .with_lease("refs/heads/main", Lease::Exact(None))
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            outcome.updates[0].status,
+            Status::Rejected(Rejection::StaleInfo { expected: None })
+        );
+
+        let outcome = prepare(&remote, OLD_TO_MAIN)?
+            .with_lease("refs/heads/main", Lease::RemoteTrackingReference)
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(outcome.is_success());
+        assert!(outcome.updates[0].forced);
+        assert_eq!(id(&remote_repo, "refs/heads/main"), id(&repo, "refs/heads/old"));
+        Ok(())
+    }
+
+    #[test]
+    fn fast_forward_is_detected_despite_clock_skew() -> crate::Result {
+        let (repo, remote_repo, _tmp) = repos()?;
+        let remote = origin(&repo, &remote_repo)?;
+        let outcome = prepare(&remote, "refs/heads/skewed:refs/heads/main")?
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            outcome.is_success(),
+            "a commit dated before the remote commit is still traversed to find it"
+        );
+        assert_eq!(id(&remote_repo, "refs/heads/main"), id(&repo, "refs/heads/skewed"));
+        Ok(())
+    }
+
+    #[test]
+    fn atomic_push_sends_nothing_if_one_update_is_rejected() -> crate::Result {
+        let (repo, remote_repo, _tmp) = repos()?;
+        let remote = origin(&repo, &remote_repo)?;
+        let outcome = remote
+            .connect(Push)?
+            .prepare_push(gix::progress::Discard, Default::default())?
+            .with_refspecs([spec("feature"), spec("refs/heads/old:refs/heads/main")])
+            .with_atomic(true)
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(outcome.updates[0].status, Status::Rejected(Rejection::AtomicPushFailed));
+        assert_eq!(outcome.updates[1].status, Status::Rejected(Rejection::NonFastForward));
+        assert_eq!(id(&remote_repo, "refs/heads/feature"), None);
+        Ok(())
+    }
+}
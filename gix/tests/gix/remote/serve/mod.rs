@@ -0,0 +1,74 @@
+use std::{io::BufReader, net::TcpListener, sync::atomic::AtomicBool, thread::JoinHandle};
+
+use gix::{
+    bstr::BString,
+    protocol::{
+        push::Report,
+        server::ConnectRequest,
+        transport::{
+            packetline::{PacketLineRef, StreamingPeekableIter},
+            Service,
+        },
+    },
+    repository::receive_pack::Hook,
+};
+
+type ServerResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Serve `connections` clients from `repo` via the `git://` protocol, and return the url to reach it.
+fn spawn_server(
+    repo: gix::ThreadSafeRepository,
+    connections: usize,
+) -> crate::Result<(String, JoinHandle<ServerResult<Vec<Report>>>)> {
+    spawn_server_with_hook(repo, connections, |_| Ok(()))
+}
+
+/// Like [`spawn_server()`], but return a handle to obtain the reports of all pushes, which are checked by `hook`.
+fn spawn_server_with_hook(
+    repo: gix::ThreadSafeRepository,
+    connections: usize,
+    mut hook: impl FnMut(Hook<'_>) -> Result<(), BString> + Send + 'static,
+) -> crate::Result<(String, JoinHandle<ServerResult<Vec<Report>>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("git://{}/server", listener.local_addr()?);
+    let handle = std::thread::spawn(move || -> ServerResult<Vec<Report>> {
+        let mut reports = Vec::new();
+        for _ in 0..connections {
+            let (stream, _) = listener.accept()?;
+            let mut lines =
+                StreamingPeekableIter::new(BufReader::new(stream.try_clone()?), &[PacketLineRef::Flush], false);
+            let request = {
+                let line = lines.read_line().expect("the client sends its request")??;
+                ConnectRequest::from_bytes(line.as_slice().expect("a data line"))?
+            };
+            assert_eq!(request.repository_path, "/server");
+            let repo = repo.to_thread_local();
+            match request.service {
+                Service::UploadPack => repo.upload_pack(
+                    lines.into_inner(),
+                    stream,
+                    request.desired_version,
+                    &AtomicBool::default(),
+                )?,
+                Service::ReceivePack => reports.extend(repo.receive_pack(
+                    lines.into_inner(),
+                    stream,
+                    request.desired_version,
+                    &mut hook,
+                    &AtomicBool::default(),
+                )?),
+            }
+        }
+        Ok(reports)
+    });
+    Ok((url, handle))
+}
+
+fn id(repo: &gix::Repository, name: &str) -> Option<gix::ObjectId> {
+    repo.try_find_reference(name)
+        .expect("valid name")
+        .map(|r| r.id().detach())
+}
+
+mod receive_pack;
+mod upload_pack;
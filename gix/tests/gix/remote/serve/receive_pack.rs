@@ -0,0 +1,130 @@
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+use gix::{
+    protocol::transport::{client::Transport, Protocol},
+    remote::{push::Status, Direction::Push},
+    repository::receive_pack::Hook,
+};
+
+use super::{id, spawn_server_with_hook};
+
+fn repos(version: Protocol) -> crate::Result<(gix::Repository, gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_push_repos.sh")?;
+    let mut local = gix::open_opts(dir.path().join("local"), crate::restricted())?;
+    local.config_snapshot_mut().set_raw_value(
+        &gix::config::tree::Protocol::VERSION,
+        (version as u8).to_string().as_str(),
+    )?;
+    let remote = gix::open_opts(dir.path().join("remote.git"), crate::restricted())?;
+    Ok((local, remote, dir))
+}
+
+fn spec(spec: &str) -> gix_refspec::RefSpec {
+    gix_refspec::parse(spec.into(), gix_refspec::parse::Operation::Push)
+        .expect("valid")
+        .to_owned()
+}
+
+fn prepare<'a, 'repo>(
+    remote: &'a gix::Remote<'repo>,
+    specs: &[&str],
+) -> crate::Result<gix::remote::push::Prepare<'a, 'repo, Box<dyn Transport + Send>>> {
+    Ok(remote
+        .connect(Push)?
+        .prepare_push(gix::progress::Discard, Default::default())?
+        .with_refspecs(specs.iter().map(|s| spec(s))))
+}
+
+#[test]
+fn updates_creations_and_deletions() -> crate::Result {
+    for version in [Protocol::V1, Protocol::V2] {
+        let (repo, remote_repo, _tmp) = repos(version)?;
+        let pre_receive = Arc::new(Mutex::new(Vec::new()));
+        let (url, handle) = spawn_server_with_hook(remote_repo.clone().into_sync(), 1, {
+            let pre_receive = pre_receive.clone();
+            move |hook| {
+                if let Hook::PreReceive { updates, push_options } = hook {
+                    let mut pre_receive = pre_receive.lock().expect("not poisoned");
+                    pre_receive.extend(updates.iter().map(|update| update.name.clone()));
+                    pre_receive.extend(push_options.iter().cloned());
+                }
+                Ok(())
+            }
+        })?;
+        let remote = repo.find_remote("origin")?.push_url(url.as_str())?;
+        let outcome = prepare(&remote, &["main", "feature", ":refs/heads/to-delete"])?
+            .with_push_options(["ci.skip"])
+            .transmit(gix::progress::Discard, &AtomicBool::default())?;
+        let reports = handle.join().expect("no panic").expect("no server error");
+
+        assert!(outcome.is_success(), "{version:?}");
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].is_success(), "the server reports what it sent");
+        assert_eq!(
+            *pre_receive.lock().expect("not poisoned"),
+            [
+                "refs/heads/main",
+                "refs/heads/feature",
+                "refs/heads/to-delete",
+                "ci.skip"
+            ],
+            "{version:?}: the hook sees all updates and push-options"
+        );
+        for name in ["refs/heads/main", "refs/heads/feature"] {
+            assert_eq!(id(&remote_repo, name), id(&repo, name), "{version:?}: {name}");
+        }
+        assert_eq!(id(&remote_repo, "refs/heads/to-delete"), None, "{version:?}");
+
+        let commits = remote_repo
+            .rev_walk([id(&remote_repo, "refs/heads/feature").expect("present")])
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(commits.len(), 3, "{version:?}: all received commits are present");
+    }
+    Ok(())
+}
+
+#[test]
+fn hooks_reject_updates_individually_or_atomically() -> crate::Result {
+    let (repo, remote_repo, _tmp) = repos(Protocol::V1)?;
+    let (url, handle) = spawn_server_with_hook(remote_repo.clone().into_sync(), 2, |hook| match hook {
+        Hook::Update { update } if update.name == "refs/heads/feature" => {
+            Err("feature branches are not allowed".into())
+        }
+        _ => Ok(()),
+    })?;
+    let remote = repo.find_remote("origin")?.push_url(url.as_str())?;
+
+    let outcome = prepare(&remote, &["old:refs/heads/old", "feature"])?
+        .with_atomic(true)
+        .transmit(gix::progress::Discard, &AtomicBool::default())?;
+    let statuses: Vec<_> = outcome.updates.iter().map(|u| u.status.clone()).collect();
+    assert_eq!(
+        statuses,
+        [
+            Status::RemoteRejected {
+                reason: "atomic push failure".into()
+            },
+            Status::RemoteRejected {
+                reason: "feature branches are not allowed".into()
+            },
+        ]
+    );
+    assert_eq!(id(&remote_repo, "refs/heads/old"), None, "nothing was updated");
+
+    let outcome = prepare(&remote, &["old:refs/heads/old", "feature"])?
+        .transmit(gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.updates[0].status, Status::Ok { rewrites: Vec::new() });
+    assert_eq!(
+        outcome.updates[1].status,
+        Status::RemoteRejected {
+            reason: "feature branches are not allowed".into()
+        }
+    );
+    assert_eq!(id(&remote_repo, "refs/heads/old"), id(&repo, "refs/heads/old"));
+    assert_eq!(id(&remote_repo, "refs/heads/feature"), None);
+
+    let reports = handle.join().expect("no panic").expect("no server error");
+    assert_eq!(reports.len(), 2);
+    Ok(())
+}
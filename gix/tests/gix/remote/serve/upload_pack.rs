@@ -0,0 +1,91 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    protocol::transport::Protocol,
+    remote::{fetch, Direction::Fetch},
+};
+
+use super::{id, spawn_server};
+
+#[test]
+fn clone_bare() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only("make_upload_pack_repos.sh")?;
+    let server = gix::open_opts(dir.join("server"), crate::restricted())?;
+    for version in [Protocol::V1, Protocol::V2] {
+        let (url, handle) = spawn_server(server.clone().into_sync(), 1)?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _outcome) = gix::prepare_clone_bare(url.as_str(), tmp.path())?
+            .with_in_memory_config_overrides([format!("protocol.version={}", version as u8)])
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        handle.join().expect("no panic").expect("no server error");
+
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "{version:?}: HEAD is advertised as symbolic reference"
+        );
+        for (local, remote) in [
+            ("refs/heads/main", "refs/heads/main"),
+            ("refs/remotes/origin/main", "refs/heads/main"),
+            ("refs/remotes/origin/feature", "refs/heads/feature"),
+            ("refs/tags/v1", "refs/tags/v1"),
+        ] {
+            assert_eq!(id(&repo, local), id(&server, remote), "{version:?}: {local}");
+        }
+        let commits = repo
+            .rev_walk([id(&repo, "refs/remotes/origin/feature").expect("present")])
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(commits.len(), 4, "{version:?}: all commits were received");
+        assert!(
+            repo.find_object(id(&repo, "refs/tags/v1").expect("present"))?.kind == gix::object::Kind::Tag,
+            "annotated tags are received as well"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn fetch_with_negotiation() -> crate::Result {
+    for version in [Protocol::V1, Protocol::V2] {
+        let dir = gix_testtools::scripted_fixture_writable("make_upload_pack_repos.sh")?;
+        let server = gix::open_opts(dir.path().join("server"), crate::restricted())?;
+        let mut repo = gix::open_opts(dir.path().join("client.git"), crate::restricted())?;
+        repo.config_snapshot_mut().set_raw_value(
+            &gix::config::tree::Protocol::VERSION,
+            (version as u8).to_string().as_str(),
+        )?;
+
+        let (url, handle) = spawn_server(server.clone().into_sync(), 1)?;
+        let remote = repo
+            .remote_at(url.as_str())?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?;
+        let outcome = remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        handle.join().expect("no panic").expect("no server error");
+
+        match outcome.status {
+            fetch::Status::Change {
+                write_pack_bundle,
+                negotiate,
+                ..
+            } => {
+                assert_eq!(negotiate.rounds.len(), 1, "{version:?}");
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 6,
+                    "{version:?}: only the commit, tree and blob of c3 and of the feature branch were sent"
+                );
+            }
+            other => unreachable!("{version:?}: expected a pack, got {other:?}"),
+        }
+        for (local, remote) in [
+            ("refs/remotes/origin/main", "refs/heads/main"),
+            ("refs/remotes/origin/feature", "refs/heads/feature"),
+        ] {
+            assert_eq!(id(&repo, local), id(&server, remote), "{version:?}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,271 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::maintenance::{CommitGraph, DeltaCompression, Options, Repack};
+
+use crate::util::repo_rw;
+
+fn files_with_extension(dir: &Path, extension: &str) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().map_or(false, |ext| ext == extension))
+                .count()
+        })
+        .unwrap_or_default()
+}
+
+fn loose_objects(repo: &gix::Repository) -> usize {
+    gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .count()
+}
+
+fn no_op() -> Options {
+    Options {
+        pack_refs: false,
+        reflog_expire: None,
+        reflog_expire_unreachable: None,
+        repack: None,
+        delta_compression: None,
+        prune_expire: None,
+        write_multi_pack_index: false,
+        commit_graph: None,
+    }
+}
+
+#[test]
+fn full_repack_packs_reachable_objects_and_unpacks_unreachable_ones() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    assert_eq!(
+        files_with_extension(&pack_dir, "pack"),
+        1,
+        "one pack with all objects of c1 and `side`"
+    );
+    assert_eq!(loose_objects(&repo), 4, "c2 and an unreachable blob");
+
+    let out = repo.maintenance(
+        &Options {
+            pack_refs: true,
+            reflog_expire_unreachable: Some(i64::MAX),
+            repack: Some(Repack::Full),
+            write_multi_pack_index: true,
+            ..no_op()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.packed_refs, 1, "only `main` was loose");
+    assert_eq!(
+        out.expired_reflog_entries, 2,
+        "the `HEAD` entries that refer to the deleted `side` commit"
+    );
+    assert_eq!(out.packed_objects, 6, "two commits, two trees and two blobs");
+    assert_eq!(out.deleted_packs, 1);
+    assert_eq!(out.unpacked_objects, 3, "the `side` commit, its tree and its blob");
+    assert_eq!(out.deleted_packed_loose_objects, 3, "all objects of c2");
+    assert_eq!(out.pruned_objects, 0, "nothing expires");
+    assert!(out.pack_path.as_deref().map_or(false, Path::is_file));
+    assert_eq!(out.multi_pack_index_path, Some(pack_dir.join("multi-pack-index")));
+
+    assert_eq!(files_with_extension(&pack_dir, "pack"), 1);
+    assert_eq!(loose_objects(&repo), 4, "the unpacked objects and the unreachable blob");
+    assert_eq!(repo.refs.loose_iter()?.count(), 0, "all references are packed");
+
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let head = repo.head_commit()?;
+    for commit in head.ancestors().all()? {
+        let commit = repo.find_commit(commit?.id)?;
+        for entry in commit.tree()?.traverse().breadthfirst.files()? {
+            assert!(repo.has_object(entry.oid), "all reachable objects are still present");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn incremental_repack_packs_loose_objects_and_prunes_unreachable_ones() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+
+    let out = repo.maintenance(
+        &Options {
+            repack: Some(Repack::Incremental),
+            prune_expire: Some(i64::MAX),
+            ..no_op()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.packed_refs, 0);
+    assert_eq!(out.expired_reflog_entries, 0);
+    assert_eq!(out.packed_objects, 3, "all objects of c2");
+    assert_eq!(out.deleted_packs, 0, "existing packs are left alone");
+    assert_eq!(out.deleted_packed_loose_objects, 3);
+    assert_eq!(out.pruned_objects, 1, "the unreachable blob");
+    assert_eq!(out.multi_pack_index_path, None);
+
+    assert_eq!(files_with_extension(&pack_dir, "pack"), 2);
+    assert_eq!(loose_objects(&repo), 0);
+
+    let out = repo.maintenance(
+        &Options {
+            repack: Some(Repack::Incremental),
+            ..no_op()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.pack_path, None, "without loose objects, there is nothing to pack");
+    assert_eq!(files_with_extension(&pack_dir, "pack"), 2);
+    Ok(())
+}
+
+fn num_deltas_in_pack(pack: &Path) -> crate::Result<usize> {
+    let pack = gix::odb::pack::data::File::at(pack, gix::hash::Kind::Sha1)?;
+    let mut deltas = 0;
+    for entry in pack.streaming_iter()? {
+        deltas += usize::from(entry?.header.is_delta());
+    }
+    Ok(deltas)
+}
+
+#[test]
+fn full_repack_with_delta_compression() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_delta_repo.sh")?;
+    let options = Options {
+        repack: Some(Repack::Full),
+        delta_compression: Some(DeltaCompression {
+            delta_islands: true,
+            ..Default::default()
+        }),
+        ..no_op()
+    };
+
+    let out = repo.maintenance(&options, &mut gix::progress::Discard, &AtomicBool::default())?;
+    let pack = out.pack_path.expect("pack was written");
+    assert_eq!(out.packed_objects, 6, "two commits, two trees and two blobs");
+    assert_eq!(
+        num_deltas_in_pack(&pack)?,
+        2,
+        "one blob and one commit are deltas against their similar counterparts, while trees are too small"
+    );
+
+    let (mut repo, _tmp) = repo_rw("make_maintenance_delta_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_raw_value(&gix::config::tree::Pack::ISLAND, "refs/heads/(.*)")?;
+    let out = repo.maintenance(&options, &mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        num_deltas_in_pack(&out.pack_path.expect("pack was written"))?,
+        0,
+        "each blob is only reachable from one branch, so deltas between them would cross islands"
+    );
+
+    for id in ["main", "other"] {
+        let commit = repo.rev_parse_single(id)?.object()?.into_commit();
+        assert!(commit.tree()?.find_entry("file").is_some(), "objects are readable");
+    }
+    Ok(())
+}
+
+#[test]
+fn commit_graph_contains_all_reachable_commits() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let info_dir = repo.objects.store_ref().path().join("info");
+
+    let out = repo.maintenance(
+        &Options {
+            commit_graph: Some(CommitGraph {
+                generation_data: true,
+                changed_paths: Some(Default::default()),
+                ..Default::default()
+            }),
+            ..no_op()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.commit_graph_path, Some(info_dir.join("commit-graph")));
+
+    let graph = gix::commitgraph::Graph::from_info_dir(&info_dir)?;
+    assert_eq!(graph.num_commits(), 2, "c1 and c2, as `side` isn't reachable anymore");
+    assert_eq!(graph.bloom_filter_settings(), Some(Default::default()));
+    for id in repo.head_commit()?.ancestors().all()? {
+        let commit = graph.commit_by_id(id?.id).expect("all reachable commits are present");
+        assert!(commit.corrected_commit_date().is_some());
+    }
+
+    let out = repo.write_commit_graph(
+        &CommitGraph {
+            split: Some(Default::default()),
+            ..Default::default()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        (out.path, out.num_files),
+        (None, 1),
+        "the existing file becomes the base of the chain, and there is nothing new to write"
+    );
+    Ok(())
+}
+
+#[test]
+fn options_from_configuration() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let options = repo.maintenance_options()?;
+    assert!(options.pack_refs);
+    assert_eq!(options.repack, Some(Repack::Full));
+    assert_eq!(options.delta_compression, Some(DeltaCompression::default()));
+    assert!(options.prune_expire.is_some());
+    assert!(
+        options.reflog_expire < options.reflog_expire_unreachable,
+        "unreachable entries expire sooner"
+    );
+    assert_eq!(
+        options.commit_graph,
+        Some(CommitGraph {
+            generation_data: true,
+            ..Default::default()
+        }),
+        "without an existing commit-graph, there are no Bloom filters"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value(&gix::config::tree::CommitGraph::GENERATION_VERSION, "1")?;
+    assert_eq!(
+        repo.maintenance_options()?.commit_graph,
+        Some(CommitGraph::default()),
+        "topological levels only"
+    );
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(&gix::config::tree::Gc::PACK_REFS, "notbare")?;
+        config.set_raw_value(&gix::config::tree::Gc::PRUNE_EXPIRE, "never")?;
+        config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE_UNREACHABLE, "now")?;
+        config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE, "1.week.ago")?;
+        config.set_raw_value(&gix::config::tree::Gc::WRITE_COMMIT_GRAPH, "false")?;
+        config.set_raw_value(&gix::config::tree::Pack::WINDOW, "250")?;
+        config.set_raw_value(&gix::config::tree::Pack::DEPTH, "4")?;
+        config.set_raw_value(&gix::config::tree::Repack::USE_DELTA_ISLANDS, "true")?;
+    }
+    let options = repo.maintenance_options()?;
+    assert!(options.pack_refs, "the repository isn't bare");
+    assert_eq!(options.prune_expire, None);
+    assert_eq!(options.commit_graph, None);
+    assert_eq!(
+        options.delta_compression,
+        Some(DeltaCompression {
+            window: 250,
+            depth: 4,
+            delta_islands: true
+        })
+    );
+    assert_eq!(options.reflog_expire_unreachable, Some(i64::MAX));
+    let week_ago = gix::date::Time::now_local_or_utc().seconds - 7 * 24 * 60 * 60;
+    assert!(options.reflog_expire.map_or(false, |time| (time - week_ago).abs() < 60));
+    Ok(())
+}
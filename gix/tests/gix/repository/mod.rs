@@ -5,6 +5,8 @@ mod config;
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
@@ -276,6 +276,35 @@ mod write_blob {
         assert!(repo.set_freelist(None).is_none(), "previous list was already dropped");
         Ok(())
     }
+
+    #[test]
+    fn from_stream_bigger_than_big_file_threshold() -> crate::Result {
+        let (_tmp, mut repo) = empty_bare_repo()?;
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::Core::BIG_FILE_THRESHOLD, "5")?;
+        let mut cursor = std::io::Cursor::new(b"hello world");
+        cursor.seek(SeekFrom::Start(1))?;
+        let oid = repo.write_blob_stream(&mut cursor)?;
+        assert_eq!(oid.object()?.data, &b"ello world"[..]);
+
+        let loose = gix::odb::loose::Store::at(repo.git_dir().join("objects"), repo.object_hash());
+        assert_eq!(loose.iter().count(), 0, "big blobs aren't written as loose objects…");
+        let num_packs = || -> std::io::Result<usize> {
+            Ok(std::fs::read_dir(repo.git_dir().join("objects/pack"))?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "pack"))
+                .count())
+        };
+        assert_eq!(num_packs()?, 1, "…but streamed into a pack of their own");
+
+        cursor.seek(SeekFrom::Start(1))?;
+        assert_eq!(repo.write_blob_stream(&mut cursor)?, oid);
+        assert_eq!(num_packs()?, 1, "existing blobs aren't written again");
+
+        let small_oid = repo.write_blob_stream(std::io::Cursor::new(b"hi"))?;
+        assert!(loose.contains(&small_oid), "small blobs are written as loose objects");
+        Ok(())
+    }
 }
 
 #[test]
@@ -297,6 +326,11 @@ fn writes_avoid_io_using_duplicate_check() -> crate::Result {
         let header = repo.find_header(id)?;
         assert_eq!(obj.kind, header.kind(), "header and object agree");
         assert_eq!(repo.try_find_header(id)?, Some(header));
+        let mut stream = repo.find_object_stream(id)?;
+        assert_eq!(stream.kind, obj.kind, "stream and object agree");
+        let mut streamed = Vec::new();
+        std::io::Read::read_to_end(&mut stream, &mut streamed)?;
+        assert_eq!(streamed, obj.data, "streamed data is the same as the decoded object");
         use gix_object::Kind::*;
         match obj.kind {
             Commit => {
@@ -391,6 +425,16 @@ mod find {
                     commit.id().try_header()?.expect("exists").kind(),
                     gix_object::Kind::Commit,
                 );
+                assert_eq!(commit.id().stream()?.kind, gix_object::Kind::Commit);
+                assert_eq!(
+                    commit.id().try_stream()?.expect("exists").kind,
+                    gix_object::Kind::Commit,
+                );
+                let object = commit.id().object()?;
+                let expected = object.data.clone();
+                let mut actual = Vec::new();
+                std::io::Read::read_to_end(&mut object.into_stream(), &mut actual)?;
+                assert_eq!(actual, expected, "objects can be turned into streams over their data");
             }
         }
         Ok(())
@@ -427,6 +471,11 @@ mod find {
             "empty tree is considered a loose object"
         );
 
+        let stream = repo.find_object_stream(empty_tree)?;
+        assert_eq!(stream.kind, gix_object::Kind::Tree);
+        assert_eq!(stream.size, 0, "empty tree can be streamed as well");
+        assert!(repo.try_find_object_stream(empty_tree)?.is_some());
+
         let mut buf = Vec::new();
         assert!(
             repo.objects.try_find(&empty_tree, &mut buf)?.is_none(),
@@ -66,6 +66,81 @@ fn bare_repo_with_index() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn sha256_repository() -> crate::Result {
+    let repo = gix::open_opts(
+        gix_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?,
+        gix::open::Options::isolated(),
+    )?;
+    assert_eq!(
+        repo.object_hash(),
+        gix_hash::Kind::Sha256,
+        "`extensions.objectFormat` is honored"
+    );
+
+    let head = repo.head_commit()?;
+    assert_eq!(head.id.kind(), gix_hash::Kind::Sha256);
+    assert_eq!(
+        head.message()?.summary().as_ref(),
+        "loose",
+        "the tip of the branch is a loose object"
+    );
+    assert!(head.tree()?.lookup_entry_by_path("dir/c")?.is_some());
+    assert_eq!(
+        head.ancestors().all()?.count(),
+        3,
+        "all but the tip are read from the pack, via the multi-pack index"
+    );
+
+    let index = repo.index()?;
+    index.verify_integrity()?;
+    assert_eq!(index.entries().len(), 3);
+    for entry in index.entries() {
+        assert_eq!(repo.find_object(entry.id)?.kind, gix::object::Kind::Blob);
+    }
+
+    let outcome = repo.objects.store_ref().verify_integrity(
+        &mut gix::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+        gix::odb::pack::index::verify::integrity::Options::default(),
+    )?;
+    assert_eq!(outcome.index_statistics.len(), 1, "the multi-pack index was verified");
+    assert_eq!(outcome.loose_object_stores.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn reftable_repository() -> crate::Result {
+    if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 45, 0) {
+        return Ok(());
+    }
+    let repo = gix::open_opts(
+        gix_testtools::scripted_fixture_read_only("make_reftable_repo.sh")?,
+        gix::open::Options::isolated(),
+    )?;
+    assert_eq!(
+        repo.refs.storage_format(),
+        gix::refs::store::StorageFormat::Reftable,
+        "the backend is selected by `extensions.refStorage`"
+    );
+
+    let head = repo.head()?;
+    assert_eq!(
+        head.referent_name().expect("HEAD points to a branch").as_bstr(),
+        "refs/heads/main",
+        "HEAD is read from the table, not from the placeholder file"
+    );
+    let main = repo.find_reference("refs/heads/main")?;
+    assert_eq!(repo.head_id()?, main.id());
+    assert_eq!(repo.head_commit()?.message_raw()?, "c2\n");
+    assert_eq!(
+        repo.references()?.local_branches()?.count(),
+        1,
+        "iteration reads the table as well"
+    );
+    Ok(())
+}
+
 #[test]
 fn non_bare_non_git_repo_without_worktree() -> crate::Result {
     let repo = named_subrepo_opts(
@@ -26,7 +26,7 @@ mod index_worktree {
         fn item_size() {
             assert_eq!(
                 std::mem::size_of::<gix::status::index_worktree::iter::Item>(),
-                264,
+                320,
                 "The size is pretty huge and goes down ideally"
             );
         }
@@ -17,8 +17,8 @@ use gix::bstr::{io::BufReadExt, BString};
 use crate::plumbing::options::merge;
 use crate::plumbing::{
     options::{
-        attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, odb, revision, tree,
-        Args, Subcommands,
+        attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, maintenance, odb,
+        revision, tree, Args, Subcommands,
     },
     show_progress,
 };
@@ -189,6 +189,24 @@ pub fn main() -> Result<()> {
                 core::repository::merge_base(repository(Mode::Lenient)?, first, others, out, format)
             },
         ),
+        Subcommands::Blame { statistics, rev, file } => prepare_and_run(
+            "blame",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                let err = statistics.then_some(err);
+                core::repository::blame::blame_file(
+                    repository(Mode::Lenient)?,
+                    &file,
+                    rev.as_ref().map(AsRef::as_ref),
+                    out,
+                    err,
+                )
+            },
+        ),
         Subcommands::Diff(crate::plumbing::options::diff::Platform { cmd }) => match cmd {
             crate::plumbing::options::diff::SubCommands::Tree {
                 old_treeish,
@@ -448,6 +466,47 @@ pub fn main() -> Result<()> {
                 },
             )
             .map(|_| ()),
+            commitgraph::Subcommands::Write {
+                split,
+                no_merge,
+                replace,
+                size_multiple,
+                max_commits,
+                changed_paths,
+            } => prepare_and_run(
+                "commitgraph-write",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::commitgraph::write::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    use gix::commitgraph::write::{Split, SplitStrategy};
+                    let split = (split || no_merge || replace || size_multiple.is_some() || max_commits.is_some())
+                        .then(|| Split {
+                            strategy: if no_merge {
+                                SplitStrategy::NoMerge
+                            } else if replace {
+                                SplitStrategy::Replace
+                            } else {
+                                SplitStrategy::Merge
+                            },
+                            size_multiple: size_multiple.unwrap_or(Split::default().size_multiple),
+                            max_commits,
+                        });
+                    core::repository::commitgraph::write(
+                        repository(Mode::Strict)?,
+                        out,
+                        progress,
+                        &should_interrupt,
+                        core::repository::commitgraph::write::Options {
+                            format,
+                            split,
+                            changed_paths,
+                        },
+                    )
+                },
+            ),
             commitgraph::Subcommands::Verify { statistics } => prepare_and_run(
                 "commitgraph-verify",
                 trace,
@@ -477,6 +536,7 @@ pub fn main() -> Result<()> {
             ref_name,
             remote,
             shallow,
+            filter,
             directory,
         }) => {
             let opts = core::repository::clone::Options {
@@ -486,6 +546,7 @@ pub fn main() -> Result<()> {
                 no_tags,
                 ref_name,
                 shallow: shallow.into(),
+                filter,
             };
             prepare_and_run(
                 "clone",
@@ -505,6 +566,7 @@ pub fn main() -> Result<()> {
             open_negotiation_graph,
             remote,
             shallow,
+            filter,
             ref_spec,
         }) => {
             let opts = core::repository::fetch::Options {
@@ -515,6 +577,7 @@ pub fn main() -> Result<()> {
                 negotiation_info,
                 open_negotiation_graph,
                 shallow: shallow.into(),
+                filter,
                 ref_specs: ref_spec,
             };
             prepare_and_run(
@@ -758,6 +821,8 @@ pub fn main() -> Result<()> {
                     pack_cache_size_mb,
                     counting_threads,
                     object_cache_size_mb,
+                    window,
+                    depth,
                     output_directory,
                 } => {
                     let has_tips = !tips.is_empty();
@@ -778,6 +843,8 @@ pub fn main() -> Result<()> {
                                 pack_cache_size_in_bytes: pack_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 object_cache_size_in_bytes: object_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 statistics: if statistics { Some(format) } else { None },
+                                delta_window: window,
+                                delta_depth: depth,
                                 out,
                                 expansion: expansion.unwrap_or(if has_tips {
                                     core::pack::create::ObjectExpansion::TreeTraversal
@@ -1041,7 +1108,12 @@ pub fn main() -> Result<()> {
             },
         ),
         Subcommands::Revision(cmd) => match cmd {
-            revision::Subcommands::List { spec, svg, limit } => prepare_and_run(
+            revision::Subcommands::List {
+                spec,
+                svg,
+                limit,
+                pathspec,
+            } => prepare_and_run(
                 "revision-list",
                 trace,
                 auto_verbose,
@@ -1056,6 +1128,7 @@ pub fn main() -> Result<()> {
                         core::repository::revision::list::Context {
                             limit,
                             spec,
+                            pathspec,
                             format,
                             text: svg.map_or(core::repository::revision::list::Format::Text, |path| {
                                 core::repository::revision::list::Format::Svg { path }
@@ -1283,6 +1356,32 @@ pub fn main() -> Result<()> {
             None,
             move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
         ),
+        Subcommands::Maintenance(maintenance::Platform {
+            incremental,
+            no_prune,
+            multi_pack_index,
+        }) => prepare_and_run(
+            "maintenance",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            core::repository::maintenance::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::maintenance(
+                    repository(Mode::Strict)?,
+                    out,
+                    progress,
+                    &should_interrupt,
+                    core::repository::maintenance::Options {
+                        format,
+                        incremental,
+                        prune: !no_prune,
+                        multi_pack_index,
+                    },
+                )
+            },
+        ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
                 "mailmap-entries",
@@ -120,7 +120,8 @@ pub mod pack {
             #[clap(long, short = 'e', value_parser = AsObjectExpansion)]
             /// the way objects are expanded. They differ in costs.
             ///
-            /// Possible values are "none" and "tree-traversal". Default is "none".
+            /// Possible values are "none", "tree-traversal", "tree-diff" and "all-reachable". Default is "none".
+            /// The latter uses the reachability bitmap of the repository if there is one.
             expansion: Option<core::pack::create::ObjectExpansion>,
 
             #[clap(long, default_value_t = 3, requires = "nondeterministic_count")]
@@ -167,6 +168,17 @@ pub mod pack {
             /// Packs produced with this option enabled are only valid in transit, but not at rest.
             thin: bool,
 
+            #[clap(long)]
+            /// If set, search this amount of objects for a delta base for each object, similar to `git pack-objects --window`.
+            ///
+            /// This produces packs that are about as small as the ones created by git, at the cost of time. Otherwise, only
+            /// deltas of existing packs are copied.
+            window: Option<usize>,
+
+            #[clap(long, default_value_t = 50, requires = "window")]
+            /// The maximum length of delta chains when searching for deltas with `--window`.
+            depth: usize,
+
             /// The directory into which to write the pack file.
             #[clap(long, short = 'o')]
             output_directory: Option<PathBuf>,
@@ -91,6 +91,9 @@ pub enum Subcommands {
     Odb(odb::Subcommands),
     /// Check for missing objects.
     Fsck(fsck::Platform),
+    /// Optimize the repository by packing references, expiring reflogs, repacking objects and pruning unreachable ones,
+    /// similar to `git gc`.
+    Maintenance(maintenance::Platform),
     /// Interact with tree objects.
     #[clap(subcommand)]
     Tree(tree::Subcommands),
@@ -145,6 +148,17 @@ pub enum Subcommands {
     Corpus(corpus::Platform),
     MergeBase(merge_base::Command),
     Merge(merge::Platform),
+    /// Annotate each line of a file with the commit that introduced it, similar to `git blame`.
+    Blame {
+        /// Print additional statistics to help understanding performance.
+        #[clap(long, short = 's')]
+        statistics: bool,
+        /// The revision to start blaming at, or `HEAD` if unset.
+        #[clap(long, short = 'r', value_parser = crate::shared::AsBString)]
+        rev: Option<BString>,
+        /// The file to create the blame information for, relative to the root of the repository.
+        file: std::ffi::OsString,
+    },
     Diff(diff::Platform),
     Worktree(worktree::Platform),
     /// Subcommands that need no git repository to run.
@@ -452,6 +466,13 @@ pub mod fetch {
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
+        /// Omit objects matching the filter specification, like `blob:none`, `blob:limit=<n>[kmg]`, `tree:<depth>` or
+        /// `sparse:oid=<blob-ish>`, to be obtained lazily from the remote when needed.
+        ///
+        /// If unset, the filter of a promisor remote is used.
+        #[clap(long, value_parser = crate::shared::AsObjectFilter, value_name = "FILTER_SPEC")]
+        pub filter: Option<gix::remote::fetch::ObjectFilter>,
+
         /// The name of the remote to connect to, or the url of the remote to connect to directly.
         ///
         /// If unset, the current branch will determine the remote.
@@ -531,6 +552,11 @@ pub mod clone {
         #[clap(flatten)]
         pub shallow: ShallowOptions,
 
+        /// Create a partial clone which omits objects matching the filter specification, like `blob:none`,
+        /// `blob:limit=<n>[kmg]`, `tree:<depth>` or `sparse:oid=<blob-ish>`, to obtain them lazily when needed.
+        #[clap(long, value_parser = crate::shared::AsObjectFilter, value_name = "FILTER_SPEC")]
+        pub filter: Option<gix::remote::fetch::ObjectFilter>,
+
         /// The url of the remote to connect to, like `https://github.com/byron/gitoxide`.
         pub remote: OsString,
 
@@ -716,6 +742,21 @@ pub mod fsck {
     }
 }
 
+pub mod maintenance {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Only pack loose objects into a new pack, instead of repacking all reachable objects into a single pack.
+        #[clap(long, short = 'i')]
+        pub incremental: bool,
+        /// Don't delete unreachable loose objects, even if they are older than `gc.pruneExpire`.
+        #[clap(long)]
+        pub no_prune: bool,
+        /// Write a multi-pack-index for all packs after repacking.
+        #[clap(long, short = 'm')]
+        pub multi_pack_index: bool,
+    }
+}
+
 pub mod tree {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -822,6 +863,27 @@ pub mod commitgraph {
             #[clap(default_value = "@")]
             spec: std::ffi::OsString,
         },
+        /// Write the commit-graph for all commits reachable from `HEAD` and all references.
+        Write {
+            /// Add a layer to a split commit-graph instead of rewriting a single file.
+            #[clap(long, short = 's')]
+            split: bool,
+            /// Don't merge the new layer with existing ones, implies `--split`.
+            #[clap(long, conflicts_with = "replace")]
+            no_merge: bool,
+            /// Merge all layers into a single one, implies `--split`.
+            #[clap(long)]
+            replace: bool,
+            /// Merge the top layer into the new one unless it has at least this many times as many commits, implies `--split`.
+            #[clap(long, value_name = "FACTOR")]
+            size_multiple: Option<u32>,
+            /// Merge the new layer with the top layer if it would have more than this many commits, implies `--split`.
+            #[clap(long, value_name = "COUNT")]
+            max_commits: Option<u32>,
+            /// Write changed-path Bloom filters to speed up traversals limited to paths.
+            #[clap(long, short = 'c')]
+            changed_paths: bool,
+        },
     }
 }
 
@@ -865,6 +927,9 @@ pub mod revision {
             /// The rev-spec to list reachable commits from.
             #[clap(default_value = "@")]
             spec: std::ffi::OsString,
+            /// Only list commits that changed paths matching these path-specs compared to their first parent.
+            #[clap(last = true, value_parser = crate::shared::CheckPathSpec)]
+            pathspec: Vec<gix::bstr::BString>,
         },
         /// Provide the revision specification like `@~1` to explain.
         #[clap(visible_alias = "e")]
@@ -130,10 +130,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.preloadIndex",
         usage: Planned("It's enabled by default and allows parallel stat checks - it's using a lot of CPU for just minor performance boosts though")
     },
-    Record {
-        config: "commitGraph.generationVersion",
-        usage: NotPlanned("Couldn't find a test that would require corrected generation numbers, even 'git' has no test for this.")
-    },
     Record {
         config: "commitGraph.maxNewFilters",
         usage: NotPlanned("Can be considered when the underlying feature is actually used or needed")
@@ -274,17 +270,9 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.sparseCheckoutCone",
         usage: Planned("Non-cone mode is deprecated but should still fail gracefully if encountered")
     },
-    Record {
-        config: "core.splitIndex",
-        usage: NotPlanned("We don't want to be able to create split indices, but we will read them. It's (somewhat) superseded by sparse indices")
-    },
-    Record {
-        config: "splitIndex.maxPercentageChange",
-        usage: NotPlanned("Seems like it's superseded by sparse indices")
-    },
     Record {
         config: "splitIndex.sharedIndexExpire",
-        usage: NotPlanned("Seems like it's superseded by sparse indices")
+        usage: Planned("Shared indices are written and kept fresh, but old ones are never removed")
     },
     Record {
         config: "index.sparse",
@@ -396,6 +396,22 @@ mod clap {
         }
     }
 
+    #[derive(Clone)]
+    pub struct AsObjectFilter;
+
+    impl TypedValueParser for AsObjectFilter {
+        type Value = gix::remote::fetch::ObjectFilter;
+
+        fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
+            AsBString
+                .try_map(|spec| {
+                    gix::remote::fetch::ObjectFilter::from_bytes(spec.as_ref())
+                        .ok_or_else(|| format!("unsupported filter specification: {spec}"))
+                })
+                .parse_ref(cmd, arg, value)
+        }
+    }
+
     #[derive(Clone)]
     pub struct AsPartialRefName;
 
@@ -410,7 +426,8 @@ mod clap {
     }
 }
 pub use self::clap::{
-    AsBString, AsHashKind, AsOutputFormat, AsPartialRefName, AsPathSpec, AsTime, CheckPathSpec, ParseRenameFraction,
+    AsBString, AsHashKind, AsObjectFilter, AsOutputFormat, AsPartialRefName, AsPathSpec, AsTime, CheckPathSpec,
+    ParseRenameFraction,
 };
 
 #[cfg(test)]
@@ -115,7 +115,11 @@ pub(super) mod function {
                             })?;
                             let mapped = crate::commands::copy_royal::remapped(data);
                             (
-                                gix::objs::compute_hash(repo.object_hash(), gix::object::Kind::Blob, mapped.as_bytes()),
+                                gix::objs::compute_hash(
+                                    repo.object_hash(),
+                                    gix::object::Kind::Blob,
+                                    mapped.as_bytes(),
+                                )?,
                                 Cow::Owned(mapped.into()),
                             )
                         }